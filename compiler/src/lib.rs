@@ -0,0 +1,22 @@
+/* THE COMPILER AS A LIBRARY. `compile` TAKES IN-MEMORY SOURCES AND RETURNS THE TOKENS, SYNTAX
+TREES, DIAGNOSTICS AND ARTIFACTS OF EVERY STAGE, THE `solc` BINARY IS A THIN WRAPPER OVER IT */
+mod mods;
+
+pub use mods::{
+    functions::controllers::{compile::compile, sub_main::compile_source_code},
+    types::{
+        ast,
+        bindings::{self, Bindings, Declaration},
+        cli,
+        compilation::{Artifact, CompilationOutput},
+        compiler_errors,
+        cron::{self, CronJob, CronSchedule},
+        diagnostics::{self, Diagnostic, Diagnostics, ErrorFormat, Severity},
+        line_descriptors::{self, LineDescriptions, Span},
+        semantic_types::{self, ConstValue, Type, TypeTable},
+        settings::{self, EvmVersion, Remapping, Settings},
+        source_graph::{self, ResolvedUnit, SourceGraph, Symbol},
+        source_map::{self, SourceFile, SourceMap},
+        token::{self, Token},
+    },
+};
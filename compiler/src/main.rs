@@ -1,22 +1,21 @@
-use std::{
-    env,
-    time::{self, SystemTime},
-};
-mod mods;
+use std::time::{self, SystemTime};
 
-use mods::functions::controllers::sub_main::compile_source_code;
+use clap::Parser;
+use solc::{cli::Cli, compile_source_code};
 
 #[tokio::main]
 async fn main() {
     let start_time = time::SystemTime::now().duration_since(SystemTime::UNIX_EPOCH);
-    /* GET ENVIRONMENT ARGUMENTS */
-    let args: Vec<String> = env::args().collect();
+    /* PARSE THE COMMAND LINE, CLAP EXITS WITH USAGE ON INVALID ARGUMENTS */
+    let cli = Cli::parse();
 
-    let _ = compile_source_code(args).await;
+    compile_source_code(&cli).await;
 
     let end_time = time::SystemTime::now().duration_since(SystemTime::UNIX_EPOCH);
-    println!(
-        "Program completed in \x1b[93m{:?}\x1b[0m",
-        (end_time.unwrap() - start_time.unwrap())
-    );
+    if !cli.quiet {
+        println!(
+            "Program completed in \x1b[93m{:?}\x1b[0m",
+            (end_time.unwrap() - start_time.unwrap())
+        );
+    }
 }
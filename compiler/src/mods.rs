@@ -1,10 +1,26 @@
 pub mod types {
+    pub mod abi;
+    pub mod assembly;
+    pub mod ast;
+    pub mod bindings;
+    pub mod cli;
+    pub mod compilation;
     pub mod compiler_errors;
+    pub mod cron;
+    pub mod diagnostics;
+    pub mod ir;
     pub mod line_descriptors;
+    pub mod opcodes;
+    pub mod semantic_types;
+    pub mod settings;
+    pub mod source_graph;
+    pub mod source_map;
+    pub mod storage_layout;
     pub mod token;
 }
 
 pub mod constants {
+    #[allow(clippy::module_inception)]
     pub mod constants;
 }
 
@@ -12,12 +28,29 @@ pub mod functions {
     pub mod helpers {
         // pub mod error_helper;
         // pub mod global;
+        pub mod hash_helper;
         // pub mod token_helper;
     }
 
     pub mod controllers {
 
         // pub mod process_enum;
+        pub mod abi;
+        pub mod abi_codegen;
+        pub mod assembly_codegen;
+        pub mod codegen;
+        pub mod compile;
+        pub mod const_evaluator;
+        pub mod cron_checker;
+        pub mod evm_backend;
+        pub mod expression_codegen;
+        pub mod formatter;
+        pub mod gasless_checker;
+        pub mod import_resolver;
+        pub mod inheritance_checker;
+        pub mod ir_verifier;
+        pub mod name_resolver;
+        pub mod optimizer;
         pub mod process_file_contents;
         // pub mod process_function;
         // pub mod process_state_variables;
@@ -25,7 +58,10 @@ pub mod functions {
         pub mod parser;
         // pub mod strip_comments;
         // pub mod structure_to_line_descriptors;
+        pub mod storage_layout;
         pub mod sub_main;
+        pub mod type_checker;
+        pub mod upgrade_checker;
     }
 }
 
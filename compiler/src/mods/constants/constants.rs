@@ -1,79 +1,20 @@
-pub const DATA_TYPES: [&str; 6] = ["bytes", "uint", "int", "address", "string", "bool"];
-
-pub const KEYWORDS: [&str; 65] = [
-    "contract",
-    "mapping",
-    "solidity",
-    "payable",
-    "using",
-    "library",
-    "abstract",
-    "indexed",
-    "call",
-    "delegatecall",
-    "wei",
-    "gwei",
-    "days",
-    "weeks",
-    "years",
-    "emit",
-    "event",
-    "ether",
-    "error",
-    "push",
-    "pop",
-    "is",
-    "import",
-    "from",
-    "assert",
-    "revert",
-    "while",
-    "immutable",
-    "mutable",
-    "constant",
-    "fallback",
-    "calldata",
-    "new",
-    "cron",
-    "delete",
-    "receive",
-    "gasless",
-    "tx",
-    "msg",
-    "block",
-    "pragma",
-    "constructor",
-    "enum",
-    "address",
-    "private",
-    "struct",
-    "function",
-    "public",
-    "views",
-    "returns",
-    "pure",
-    "return",
-    "external",
-    "internal",
-    "interface",
-    "modifier",
-    "memory",
-    "if",
-    "else",
-    "for",
-    "upgrdable",
-    "constant",
-    "immutable",
-    "true",
-    "false",
+/* MULTI CHARACTER OPERATORS, LONGEST FIRST SO THAT LEXING IS MAXIMAL MUNCH */
+pub const OPERATORS: [&str; 22] = [
+    "<<=", ">>=", "==", "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=", "*=", "/=", "%=",
+    "|=", "&=", "^=", "<<", ">>", "**", "=>",
 ];
 
 pub const SYMBOLS: [char; 24] = [
-    '+', '-', '/', '*', '(', ')', '[', ']', '{', '}', '>', '<', '.', '=', '!', '%', ';', '\'', '"',
-    ',', '|', '&', '~', '^',
+    '+', '-', '/', '*', '(', ')', '[', ']', '{', '}', '>', '<', '.', '=', '!', '%', ';', ',', '|',
+    '&', '~', '^', ':', '?',
 ];
 
 pub const INTEGER_SIZES: [u16; 32] = [
     8, 16, 24, 32, 40, 48, 56, 64, 72, 80, 88, 96, 104, 112, 120, 128, 136, 144, 152, 160, 168,
     176, 184, 192, 200, 208, 216, 224, 232, 240, 248, 256,
 ];
+
+/* ERC-1967 SLOT A PROXY KEEPS THE ADDRESS OF ITS IMPLEMENTATION IN,
+`keccak256("eip1967.proxy.implementation") - 1`. IT IS A HASH SO NO STATE VARIABLE CAN LAND ON IT */
+pub const IMPLEMENTATION_SLOT: &str =
+    "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
@@ -0,0 +1,499 @@
+use crate::mods::types::{
+    abi::{AbiEntry, AbiEntryKind, AbiParameter},
+    ast::{
+        ContractKind, ContractPart, FunctionKind, Parameter, StateMutability, StorageLocation,
+        Visibility,
+    },
+    bindings::{Bindings, ContractId, Declaration},
+    cron::CronJob,
+    line_descriptors::Span,
+    semantic_types::{self, Type, TypeTable},
+    source_graph::SourceGraph,
+};
+
+/* THE JSON ABI OF A CONTRACT: ITS CONSTRUCTOR, THE PUBLIC AND EXTERNAL FUNCTIONS AND GETTERS IT
+HAS, OWN OR INHERITED, THE EVENTS AND ERRORS IT DECLARES, INHERITS OR USES, ITS `fallback` AND
+`receive`, AND THE ENTRY POINT OF EACH OF ITS `cron` BLOCKS. SORTED BY KIND, THEN NAME, THEN
+SIGNATURE */
+pub fn contract_abi(
+    contract: ContractId,
+    graph: &SourceGraph,
+    bindings: &Bindings,
+    types: &TypeTable,
+    cron_jobs: &[CronJob],
+) -> Vec<AbiEntry> {
+    let definition = &graph.units[contract.0].ast.contracts[contract.1];
+    let interface = definition.kind == ContractKind::Interface;
+    let library = definition.kind == ContractKind::Library;
+    let builder = AbiBuilder {
+        graph,
+        types,
+        library,
+    };
+    let mut entries: Vec<AbiEntry> = Vec::new();
+    let linearization = bindings
+        .linearization
+        .get(&contract)
+        .cloned()
+        .unwrap_or(vec![contract]);
+
+    /* MOST DERIVED FIRST, SO AN OVERRIDE HIDES WHAT IT OVERRIDES */
+    for &id in &linearization {
+        let own = id == contract;
+        for part in &graph.units[id.0].ast.contracts[id.1].parts {
+            let entry = match part {
+                ContractPart::Function(function) => {
+                    let external = interface
+                        || !matches!(
+                            function.visibility,
+                            Some(Visibility::Internal | Visibility::Private)
+                        );
+                    let (kind, name) = match function.kind {
+                        FunctionKind::Constructor if own => (AbiEntryKind::Constructor, ""),
+                        FunctionKind::Function if external => match &function.name {
+                            Some(_name) => (AbiEntryKind::Function, _name.name.as_str()),
+                            None => continue,
+                        },
+                        FunctionKind::Fallback => (AbiEntryKind::Fallback, ""),
+                        FunctionKind::Receive => (AbiEntryKind::Receive, ""),
+                        _ => continue,
+                    };
+                    AbiEntry {
+                        kind,
+                        name: name.to_string(),
+                        inputs: builder.parameters(&function.parameters),
+                        outputs: builder.parameters(&function.returns),
+                        state_mutability: Some(mutability_name(function.mutability)),
+                        anonymous: false,
+                        gasless: function.gasless,
+                        signature: match library {
+                            true => library_signature(name, &function.parameters, graph, types),
+                            false => signature(name, &function.parameters, graph, types),
+                        },
+                    }
+                }
+                ContractPart::StateVariable(variable)
+                    if variable.visibility == Some(Visibility::Public) =>
+                {
+                    builder.getter(&variable.name.name, type_name(&variable.ty.span, types))
+                }
+                ContractPart::Event(_) | ContractPart::Error(_) => {
+                    match builder.declaration(part) {
+                        Some(_entry) => _entry,
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+            entries.push(entry);
+        }
+    }
+
+    /* EVENTS AND ERRORS DECLARED ELSEWHERE BUT EMITTED OR REVERTED WITH HERE */
+    let mut used: Vec<(usize, Declaration)> = bindings
+        .references
+        .iter()
+        .filter(|(span, _)| {
+            linearization.iter().any(|&(file_id, index)| {
+                let outer = graph.units[file_id].ast.contracts[index].span;
+                span.file_id == file_id && outer.start <= span.start && span.end <= outer.end
+            })
+        })
+        .flat_map(|(span, declarations)| {
+            declarations
+                .iter()
+                .map(|declaration| (span.start, *declaration))
+        })
+        .collect();
+    used.sort_by_key(|(start, _)| *start);
+    for (_, declaration) in used {
+        let entry = match declaration {
+            Declaration::Error { file_id, index } => {
+                let error = &graph.units[file_id].ast.custom_errors[index];
+                builder.error(&error.name.name, &error.parameters)
+            }
+            Declaration::Member {
+                file_id,
+                contract,
+                part,
+            } => match builder
+                .declaration(&graph.units[file_id].ast.contracts[contract].parts[part])
+            {
+                Some(_entry) => _entry,
+                None => continue,
+            },
+            _ => continue,
+        };
+        entries.push(entry);
+    }
+
+    entries.extend(cron_jobs.iter().map(CronJob::abi_entry));
+
+    /* ONE ENTRY PER SIGNATURE, AND ONE `fallback` AND `receive` */
+    let mut abi: Vec<AbiEntry> = Vec::new();
+    for entry in entries {
+        if !abi
+            .iter()
+            .any(|seen| seen.kind == entry.kind && seen.signature == entry.signature)
+        {
+            abi.push(entry);
+        }
+    }
+    abi.sort_by(|a, b| (a.kind, &a.name, &a.signature).cmp(&(b.kind, &b.name, &b.signature)));
+    abi
+}
+
+/* THE PARAMETER TYPES AND THE RETURNED VALUE OF THE GETTER OF A PUBLIC STATE VARIABLE: AN
+ARGUMENT FOR EVERY MAPPING KEY AND ARRAY INDEX ON THE WAY TO THE VALUE */
+pub fn getter(ty: &Type) -> (Vec<Type>, Type) {
+    let mut parameters = Vec::new();
+    let mut ty = ty.clone();
+    loop {
+        ty = match ty {
+            Type::Mapping { key, value } => {
+                parameters.push(*key);
+                *value
+            }
+            Type::Array { base, .. } => {
+                parameters.push(Type::UINT256);
+                *base
+            }
+            _ => return (parameters, ty),
+        }
+    }
+}
+
+struct AbiBuilder<'a> {
+    graph: &'a SourceGraph,
+    types: &'a TypeTable,
+    /* STORAGE REFERENCES OF LIBRARY FUNCTIONS ARE PASSED AS SLOTS, NOT ENCODED */
+    library: bool,
+}
+
+impl AbiBuilder<'_> {
+    fn parameters(&self, parameters: &[Parameter]) -> Vec<AbiParameter> {
+        parameters
+            .iter()
+            .map(|parameter| {
+                let name = parameter
+                    .name
+                    .as_ref()
+                    .map_or("", |name| name.name.as_str());
+                let ty = type_name(&parameter.ty.span, self.types);
+                match self.library && parameter.storage == Some(StorageLocation::Storage) {
+                    true => AbiParameter {
+                        name: name.to_string(),
+                        ty: library_type(ty, true, self.graph, self.types),
+                        internal_type: self.internal_type(ty),
+                        components: Vec::new(),
+                        indexed: None,
+                    },
+                    false => self.parameter(name, ty),
+                }
+            })
+            .collect()
+    }
+
+    fn parameter(&self, name: &str, ty: &Type) -> AbiParameter {
+        let (element, suffix) = array_suffix(ty);
+        let (ty, components) = match element {
+            Type::Struct {
+                file_id,
+                contract,
+                part,
+                ..
+            } => (
+                format!("tuple{suffix}"),
+                self.fields(*file_id, *contract, *part)
+                    .into_iter()
+                    .map(|(name, ty)| self.parameter(name, ty))
+                    .collect(),
+            ),
+            _ => (canonical_type(ty, self.graph, self.types), Vec::new()),
+        };
+        AbiParameter {
+            name: name.to_string(),
+            ty,
+            internal_type: self.internal_type(element) + &suffix,
+            components,
+            indexed: None,
+        }
+    }
+
+    fn fields(&self, file_id: usize, contract: usize, part: usize) -> Vec<(&str, &Type)> {
+        match &self.graph.units[file_id].ast.contracts[contract].parts[part] {
+            ContractPart::Struct(definition) => definition
+                .fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.name.name.as_str(),
+                        type_name(&field.ty.span, self.types),
+                    )
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /* THE TYPE AS WRITTEN, WITH STRUCTS AND ENUMS QUALIFIED BY THEIR CONTRACT AND FUNCTIONS
+    WITH THEIR WHOLE SIGNATURE */
+    fn internal_type(&self, ty: &Type) -> String {
+        match ty {
+            Type::Struct {
+                name,
+                file_id,
+                contract,
+                ..
+            } => format!(
+                "struct {}",
+                qualified_name(*file_id, *contract, name, self.graph)
+            ),
+            Type::Enum {
+                name,
+                file_id,
+                contract,
+                ..
+            } => format!(
+                "enum {}",
+                qualified_name(*file_id, *contract, name, self.graph)
+            ),
+            Type::Contract { name, .. } => format!("contract {name}"),
+            Type::Address { payable: true } => "address payable".to_string(),
+            Type::Array { base, length } => format!(
+                "{}[{}]",
+                self.internal_type(base),
+                length.as_ref().map_or(String::new(), ToString::to_string)
+            ),
+            Type::Function(function) => {
+                let list = |types: &[Type]| {
+                    types
+                        .iter()
+                        .map(|ty| self.internal_type(ty))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                };
+                let mut name = format!("function ({})", list(&function.parameters));
+                if let Some(_mutability) = function.mutability {
+                    name = format!("{name} {}", mutability_name(Some(_mutability)));
+                }
+                if function.kind == semantic_types::FunctionKind::External {
+                    name += " external";
+                }
+                if !function.returns.is_empty() {
+                    name = format!("{name} returns ({})", list(&function.returns));
+                }
+                name
+            }
+            ty => canonical_type(ty, self.graph, self.types),
+        }
+    }
+
+    /* THE GETTER RETURNS A STRUCT AS ITS MEMBERS, LEAVING OUT MAPPINGS AND ARRAYS */
+    fn getter(&self, name: &str, ty: &Type) -> AbiEntry {
+        let (parameters, value) = getter(ty);
+        let outputs = match &value {
+            Type::Struct {
+                file_id,
+                contract,
+                part,
+                ..
+            } => self
+                .fields(*file_id, *contract, *part)
+                .into_iter()
+                .filter(|(_, ty)| !matches!(ty, Type::Mapping { .. } | Type::Array { .. }))
+                .map(|(name, ty)| self.parameter(name, ty))
+                .collect(),
+            value => vec![self.parameter("", value)],
+        };
+        let canonical: Vec<String> = parameters
+            .iter()
+            .map(|ty| canonical_type(ty, self.graph, self.types))
+            .collect();
+        AbiEntry {
+            kind: AbiEntryKind::Function,
+            name: name.to_string(),
+            inputs: parameters.iter().map(|ty| self.parameter("", ty)).collect(),
+            outputs,
+            state_mutability: Some("view"),
+            anonymous: false,
+            gasless: false,
+            signature: format!("{name}({})", canonical.join(",")),
+        }
+    }
+
+    fn declaration(&self, part: &ContractPart) -> Option<AbiEntry> {
+        match part {
+            ContractPart::Event(event) => {
+                let mut inputs = self.parameters(&event.parameters);
+                for (input, parameter) in inputs.iter_mut().zip(&event.parameters) {
+                    input.indexed = Some(parameter.indexed);
+                }
+                Some(AbiEntry {
+                    kind: AbiEntryKind::Event,
+                    name: event.name.name.clone(),
+                    inputs,
+                    outputs: Vec::new(),
+                    state_mutability: None,
+                    anonymous: event.anonymous,
+                    gasless: false,
+                    signature: signature(
+                        &event.name.name,
+                        &event.parameters,
+                        self.graph,
+                        self.types,
+                    ),
+                })
+            }
+            ContractPart::Error(error) => Some(self.error(&error.name.name, &error.parameters)),
+            _ => None,
+        }
+    }
+
+    fn error(&self, name: &str, parameters: &[Parameter]) -> AbiEntry {
+        AbiEntry {
+            kind: AbiEntryKind::Error,
+            name: name.to_string(),
+            inputs: self.parameters(parameters),
+            outputs: Vec::new(),
+            state_mutability: None,
+            anonymous: false,
+            gasless: false,
+            signature: signature(name, parameters, self.graph, self.types),
+        }
+    }
+}
+
+/* THE ELEMENT TYPE OF A (NESTED) ARRAY AND THE `[..]` THAT FOLLOW IT */
+fn array_suffix(ty: &Type) -> (&Type, String) {
+    match ty {
+        Type::Array { base, length } => {
+            let (element, suffix) = array_suffix(base);
+            (
+                element,
+                format!(
+                    "{suffix}[{}]",
+                    length.as_ref().map_or(String::new(), ToString::to_string)
+                ),
+            )
+        }
+        ty => (ty, String::new()),
+    }
+}
+
+fn mutability_name(mutability: Option<StateMutability>) -> &'static str {
+    match mutability {
+        None => "nonpayable",
+        Some(StateMutability::Pure) => "pure",
+        Some(StateMutability::View) => "view",
+        Some(StateMutability::Payable) => "payable",
+    }
+}
+
+/* NAME OF A TYPE IN A CANONICAL SIGNATURE. STRUCTS BECOME TUPLES OF THEIR FIELDS, ENUMS
+`uint8`, AND CONTRACTS AND `address payable` PLAIN `address` */
+pub fn canonical_type(ty: &Type, graph: &SourceGraph, types: &TypeTable) -> String {
+    match ty {
+        Type::Address { .. } | Type::Contract { .. } => "address".to_string(),
+        Type::Enum { .. } => "uint8".to_string(),
+        Type::Array { base, length } => format!(
+            "{}[{}]",
+            canonical_type(base, graph, types),
+            length.as_ref().map_or(String::new(), ToString::to_string)
+        ),
+        Type::Struct {
+            file_id,
+            contract,
+            part,
+            ..
+        } => match &graph.units[*file_id].ast.contracts[*contract].parts[*part] {
+            ContractPart::Struct(definition) => {
+                let fields: Vec<String> = definition
+                    .fields
+                    .iter()
+                    .map(|field| canonical_type(type_name(&field.ty.span, types), graph, types))
+                    .collect();
+                format!("({})", fields.join(","))
+            }
+            _ => "tuple".to_string(),
+        },
+        Type::Function(_) => "function".to_string(),
+        ty => ty.to_string(),
+    }
+}
+
+/* NAME OF A TYPE IN THE SIGNATURE OF A LIBRARY FUNCTION. STRUCTS GO BY THEIR QUALIFIED NAME
+INSTEAD OF AS TUPLES AND STORAGE REFERENCES, PASSED AS A SLOT, END IN ` storage` */
+pub fn library_type(ty: &Type, storage: bool, graph: &SourceGraph, types: &TypeTable) -> String {
+    let name = match ty {
+        Type::Struct {
+            name,
+            file_id,
+            contract,
+            ..
+        } => qualified_name(*file_id, *contract, name, graph),
+        Type::Array { base, length } => format!(
+            "{}[{}]",
+            library_type(base, false, graph, types),
+            length.as_ref().map_or(String::new(), ToString::to_string)
+        ),
+        Type::Mapping { key, value } => format!(
+            "mapping({} => {})",
+            library_type(key, false, graph, types),
+            library_type(value, false, graph, types)
+        ),
+        ty => canonical_type(ty, graph, types),
+    };
+    match storage {
+        true => format!("{name} storage"),
+        false => name,
+    }
+}
+
+/* `name(type1,type2)` OF A LIBRARY FUNCTION, SEE `library_type` */
+pub fn library_signature(
+    name: &str,
+    parameters: &[Parameter],
+    graph: &SourceGraph,
+    types: &TypeTable,
+) -> String {
+    let parameters: Vec<String> = parameters
+        .iter()
+        .map(|parameter| {
+            library_type(
+                type_name(&parameter.ty.span, types),
+                parameter.storage == Some(StorageLocation::Storage),
+                graph,
+                types,
+            )
+        })
+        .collect();
+    format!("{name}({})", parameters.join(","))
+}
+
+/* `Contract.Name`, OR `Name` FOR WHAT IS DECLARED AT FILE LEVEL */
+fn qualified_name(file_id: usize, contract: usize, name: &str, graph: &SourceGraph) -> String {
+    let definition = &graph.units[file_id].ast.contracts[contract];
+    match definition.kind {
+        ContractKind::File => name.to_string(),
+        _ => format!("{}.{name}", definition.name.name),
+    }
+}
+
+/* `name(type1,type2)`, THE TEXT HASHED INTO A SELECTOR */
+pub fn signature(
+    name: &str,
+    parameters: &[Parameter],
+    graph: &SourceGraph,
+    types: &TypeTable,
+) -> String {
+    let parameters: Vec<String> = parameters
+        .iter()
+        .map(|parameter| canonical_type(type_name(&parameter.ty.span, types), graph, types))
+        .collect();
+    format!("{name}({})", parameters.join(","))
+}
+
+fn type_name<'a>(span: &Span, types: &'a TypeTable) -> &'a Type {
+    types.type_names.get(span).unwrap_or(&Type::Unknown)
+}
@@ -0,0 +1,497 @@
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+
+use crate::mods::{
+    functions::controllers::{
+        codegen::{
+            add, and, apply, ceil32, constant, eq, gt, iszero, lt, mload, mul, shl, slot, sub,
+            Codegen, Routine, ERROR_SELECTOR, FREE_MEMORY_POINTER,
+        },
+        expression_codegen::clean,
+    },
+    types::{
+        ast::Expression,
+        ir::{mask, Word},
+        opcodes::Opcode,
+        semantic_types::Type,
+    },
+};
+
+/* OFFSETS AND LENGTHS IN THE ENCODING ARE CHECKED TO FIT IN 64 BITS, SO NOTHING ADDED TO THEM
+CAN OVERFLOW */
+const MAX_OFFSET: u64 = u64::MAX;
+
+/* WHETHER VALUES OF `ty` ARE ENCODED IN THE TAIL, WITH AN OFFSET IN THE HEAD */
+pub fn is_dynamic(ty: &Type, fields: &dyn Fn(&Type) -> Vec<Type>) -> bool {
+    match ty {
+        Type::String | Type::Bytes | Type::Array { length: None, .. } => true,
+        Type::Array { base, .. } => is_dynamic(base, fields),
+        Type::Struct { .. } => fields(ty).iter().any(|field| is_dynamic(field, fields)),
+        _ => false,
+    }
+}
+
+impl Codegen<'_, '_> {
+    pub fn is_dynamic(&self, ty: &Type) -> bool {
+        is_dynamic(ty, &|ty| self.struct_fields(ty))
+    }
+
+    /* BYTES A VALUE OF `ty` TAKES IN THE HEAD OF A TUPLE */
+    pub fn head_size(&self, ty: &Type) -> usize {
+        if self.is_dynamic(ty) {
+            return 32;
+        }
+        match ty {
+            Type::Array {
+                base,
+                length: Some(length),
+            } => length.to_usize().unwrap_or_default() * self.head_size(base),
+            Type::Struct { .. } => self
+                .struct_fields(ty)
+                .iter()
+                .map(|field| self.head_size(field))
+                .sum(),
+            _ => 32,
+        }
+    }
+
+    /* ------------------------------ ENCODING ------------------------------ */
+
+    /* ENCODES THE VALUES AT `positions` FROM `start` ON, RETURNING THE POSITION OF THE END */
+    pub fn encode_tuple(&mut self, types: &[Type], positions: &[usize], start: Word) -> usize {
+        let start = self.let_(start);
+        let heads: usize = types.iter().map(|ty| self.head_size(ty)).sum();
+        let tail = self.let_(add(slot(start), constant(heads)));
+        let mut offset = 0;
+        for (ty, position) in types.iter().zip(positions) {
+            self.encode_element(
+                ty,
+                slot(*position),
+                add(slot(start), constant(offset)),
+                start,
+                tail,
+            );
+            offset += self.head_size(ty);
+        }
+        tail
+    }
+
+    /* ENCODES ONE VALUE OF A TUPLE WHOSE ENCODING STARTS AT `start`, AT `head` OR IN THE TAIL,
+    MOVING THE TAIL AT `tail` PAST IT */
+    fn encode_element(&mut self, ty: &Type, value: Word, head: Word, start: usize, tail: usize) {
+        if self.is_dynamic(ty) {
+            self.exec(Opcode::MStore, [head, sub(slot(tail), slot(start))]);
+            self.call_routine(Routine::AbiEncode(ty.clone()), vec![value, slot(tail)], 1);
+            self.store_top(tail);
+        } else if matches!(ty, Type::Array { .. } | Type::Struct { .. }) {
+            self.call_routine(Routine::AbiEncode(ty.clone()), vec![value, head], 1);
+            self.pop(1);
+        } else {
+            self.exec(Opcode::MStore, [head, clean(ty, value)]);
+        }
+    }
+
+    /* (value, position) -> end */
+    pub fn abi_encode_routine(&mut self, ty: &Type) {
+        let (value, position) = (1, 2);
+        match ty {
+            Type::String | Type::Bytes => {
+                let length = self.let_(mload(slot(value)));
+                self.exec(Opcode::MStore, [slot(position), slot(length)]);
+                let data = self.let_(add(slot(position), constant(32u8)));
+                self.copy_memory(add(slot(value), constant(32u8)), slot(data), slot(length));
+                self.exec(
+                    Opcode::MStore,
+                    [add(slot(data), slot(length)), constant(0u8)],
+                );
+                let end = self.let_(add(slot(data), ceil32(slot(length))));
+                self.leave(&[end]);
+            }
+            Type::Array { base, length } => {
+                let (count, items, start) = match length {
+                    Some(_length) => (
+                        constant(_length.to_biguint().unwrap_or_default()),
+                        slot(value),
+                        self.let_(slot(position)),
+                    ),
+                    None => {
+                        self.exec(Opcode::MStore, [slot(position), mload(slot(value))]);
+                        let start = self.let_(add(slot(position), constant(32u8)));
+                        (mload(slot(value)), add(slot(value), constant(32u8)), start)
+                    }
+                };
+                let size = self.head_size(base);
+                let heads = mul(count.clone(), constant(size));
+                let tail = self.let_(add(slot(start), heads));
+                self.for_range(count, |codegen, index| {
+                    codegen.encode_element(
+                        base,
+                        mload(add(items.clone(), mul(slot(index), constant(32u8)))),
+                        add(slot(start), mul(slot(index), constant(size))),
+                        start,
+                        tail,
+                    );
+                });
+                self.leave(&[tail]);
+            }
+            Type::Struct { .. } => {
+                let fields = self.struct_fields(ty);
+                let heads: usize = fields.iter().map(|field| self.head_size(field)).sum();
+                let tail = self.let_(add(slot(position), constant(heads)));
+                let mut offset = 0;
+                for (index, field) in fields.iter().enumerate() {
+                    self.encode_element(
+                        field,
+                        mload(add(slot(value), constant(32 * index))),
+                        add(slot(position), constant(offset)),
+                        position,
+                        tail,
+                    );
+                    offset += self.head_size(field);
+                }
+                self.leave(&[tail]);
+            }
+            _ => {
+                self.exec(Opcode::MStore, [slot(position), clean(ty, slot(value))]);
+                let end = self.let_(add(slot(position), constant(32u8)));
+                self.leave(&[end]);
+            }
+        }
+    }
+
+    /* `abi.encodePacked`: VALUE TYPES IN AS FEW BYTES AS THEY TAKE, `string` AND `bytes`
+    UNPADDED AND ARRAY ELEMENTS PADDED TO 32 BYTES */
+    pub fn encode_packed(
+        &mut self,
+        expression: &Expression,
+        types: &[Type],
+        positions: &[usize],
+        start: Word,
+    ) -> usize {
+        let cursor = self.let_(start);
+        for (ty, position) in types.iter().zip(positions) {
+            let value = slot(*position);
+            let bytes = match ty {
+                Type::FixedBytes(size) => Some((*size as usize, clean(ty, value.clone()))),
+                Type::Integer { bits, .. } => Some((
+                    *bits as usize / 8,
+                    shl(constant(256 - *bits as usize), value.clone()),
+                )),
+                Type::Address { .. } | Type::Contract { .. } => {
+                    Some((20, shl(constant(96u8), value.clone())))
+                }
+                Type::Bool => Some((1, shl(constant(248u8), clean(ty, value.clone())))),
+                Type::Enum { .. } => Some((1, shl(constant(248u8), value.clone()))),
+                _ => None,
+            };
+            if let Some((size, word)) = bytes {
+                self.exec(Opcode::MStore, [slot(cursor), word]);
+                self.assign(cursor, add(slot(cursor), constant(size)));
+                continue;
+            }
+            match ty {
+                Type::String | Type::Bytes => {
+                    self.copy_memory(
+                        add(value.clone(), constant(32u8)),
+                        slot(cursor),
+                        mload(value.clone()),
+                    );
+                    self.assign(cursor, add(slot(cursor), mload(value)));
+                }
+                Type::Array { base, length } if base.is_value_type() => {
+                    let (count, items) = match length {
+                        Some(_length) => (
+                            constant(_length.to_biguint().unwrap_or_default()),
+                            value.clone(),
+                        ),
+                        None => (mload(value.clone()), add(value.clone(), constant(32u8))),
+                    };
+                    self.for_range(count, |codegen, index| {
+                        let offset = mul(slot(index), constant(32u8));
+                        codegen.exec(
+                            Opcode::MStore,
+                            [
+                                add(slot(cursor), offset.clone()),
+                                clean(base, mload(add(items.clone(), offset))),
+                            ],
+                        );
+                    });
+                    let count = match length {
+                        Some(_length) => constant(_length.to_biguint().unwrap_or_default()),
+                        None => mload(value),
+                    };
+                    self.assign(cursor, add(slot(cursor), mul(count, constant(32u8))));
+                }
+                _ => self.unsupported(expression.span, "packed encoding of this type"),
+            }
+        }
+        cursor
+    }
+
+    /* ------------------------------ DECODING ------------------------------ */
+
+    /* PUSHES THE VALUE OF `ty` WHOSE HEAD IS AT `head`, IN CALLDATA OR MEMORY, IN A TUPLE
+    STARTING AT `base` AND ENDING AT `end`. REVERTS ON ANYTHING A VALID ENCODING CANNOT HOLD */
+    pub fn decode(
+        &mut self,
+        ty: &Type,
+        calldata: bool,
+        head: Word,
+        base: Word,
+        end: Word,
+    ) -> usize {
+        let load = |address: Word| match calldata {
+            true => apply(Opcode::CallDataLoad, [address]),
+            false => mload(address),
+        };
+        if self.is_dynamic(ty) {
+            let offset = self.let_(load(head));
+            self.revert_if(gt(slot(offset), constant(MAX_OFFSET)));
+            self.call_routine(
+                Routine::AbiDecode {
+                    ty: ty.clone(),
+                    calldata,
+                },
+                vec![add(base, slot(offset)), end],
+                1,
+            );
+            self.squash(1);
+            return self.ir.height;
+        }
+        if matches!(ty, Type::Array { .. } | Type::Struct { .. }) {
+            self.call_routine(
+                Routine::AbiDecode {
+                    ty: ty.clone(),
+                    calldata,
+                },
+                vec![head, end],
+                1,
+            );
+            return self.ir.height;
+        }
+        let value = self.let_(load(head));
+        let invalid = match ty {
+            Type::Bool => gt(slot(value), constant(1u8)),
+            Type::Enum { .. } => iszero(lt(slot(value), constant(self.enum_size(ty)))),
+            _ => iszero(eq(slot(value), clean(ty, slot(value)))),
+        };
+        self.revert_if(invalid);
+        value
+    }
+
+    /* (data, end) -> value, WHERE `data` IS WHERE THE ENCODING OF THE VALUE STARTS */
+    pub fn abi_decode_routine(&mut self, ty: &Type, calldata: bool) {
+        let (data, end) = (1, 2);
+        let load = |address: Word| match calldata {
+            true => apply(Opcode::CallDataLoad, [address]),
+            false => mload(address),
+        };
+        match ty {
+            Type::String | Type::Bytes => {
+                let length = self.let_(load(slot(data)));
+                self.revert_if(gt(slot(length), constant(MAX_OFFSET)));
+                let source = self.let_(add(slot(data), constant(32u8)));
+                self.revert_if(gt(add(slot(source), slot(length)), slot(end)));
+                let pointer = self.allocate(add(ceil32(slot(length)), constant(32u8)));
+                self.exec(Opcode::MStore, [slot(pointer), slot(length)]);
+                let target = add(slot(pointer), constant(32u8));
+                if calldata {
+                    self.exec(Opcode::CallDataCopy, [target, slot(source), slot(length)]);
+                } else {
+                    self.copy_memory(slot(source), target, slot(length));
+                }
+                self.leave(&[pointer]);
+            }
+            Type::Array { base, length } => {
+                let size = self.head_size(base);
+                let (count, items, first) = match length {
+                    Some(_length) => {
+                        let count = self.let_(constant(_length.to_biguint().unwrap_or_default()));
+                        (count, data, 0usize)
+                    }
+                    None => {
+                        let count = self.let_(load(slot(data)));
+                        self.revert_if(gt(slot(count), constant(MAX_OFFSET)));
+                        let items = self.let_(add(slot(data), constant(32u8)));
+                        (count, items, 32)
+                    }
+                };
+                self.revert_if(gt(
+                    add(slot(items), mul(slot(count), constant(size))),
+                    slot(end),
+                ));
+                let pointer = self.allocate(add(mul(slot(count), constant(32u8)), constant(first)));
+                if length.is_none() {
+                    self.exec(Opcode::MStore, [slot(pointer), slot(count)]);
+                }
+                self.for_range(slot(count), |codegen, index| {
+                    let value = codegen.decode(
+                        base,
+                        calldata,
+                        add(slot(items), mul(slot(index), constant(size))),
+                        slot(items),
+                        slot(end),
+                    );
+                    codegen.exec(
+                        Opcode::MStore,
+                        [
+                            add(
+                                slot(pointer),
+                                add(constant(first), mul(slot(index), constant(32u8))),
+                            ),
+                            slot(value),
+                        ],
+                    );
+                    codegen.pop(1);
+                });
+                self.leave(&[pointer]);
+            }
+            Type::Struct { .. } => {
+                let fields = self.struct_fields(ty);
+                let heads: usize = fields.iter().map(|field| self.head_size(field)).sum();
+                self.revert_if(gt(add(slot(data), constant(heads)), slot(end)));
+                let pointer = self.allocate(constant(32 * fields.len()));
+                let mut offset = 0;
+                for (index, field) in fields.iter().enumerate() {
+                    let value = self.decode(
+                        field,
+                        calldata,
+                        add(slot(data), constant(offset)),
+                        slot(data),
+                        slot(end),
+                    );
+                    self.exec(
+                        Opcode::MStore,
+                        [add(slot(pointer), constant(32 * index)), slot(value)],
+                    );
+                    self.pop(1);
+                    offset += self.head_size(field);
+                }
+                self.leave(&[pointer]);
+            }
+            _ => {
+                let value = self.let_(load(slot(data)));
+                self.leave(&[value]);
+            }
+        }
+    }
+
+    /* ------------------------------ RETURNING AND REVERTING ------------------------------ */
+
+    /* RETURNS THE VALUES AT `positions`, ABI ENCODED */
+    pub fn return_values(&mut self, types: &[Type], positions: &[usize]) {
+        let height = self.ir.height;
+        let start = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+        let end = self.encode_tuple(types, positions, slot(start));
+        self.exec(Opcode::Return, [slot(start), sub(slot(end), slot(start))]);
+        self.ir.height = height;
+    }
+
+    /* REVERTS WITH `selector` FOLLOWED BY THE VALUES AT `positions`, ABI ENCODED */
+    pub fn revert_with_error(&mut self, selector: [u8; 4], types: &[Type], positions: &[usize]) {
+        let height = self.ir.height;
+        let start = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+        self.exec(
+            Opcode::MStore,
+            [
+                slot(start),
+                constant(BigUint::from_bytes_be(&selector) << 224),
+            ],
+        );
+        let end = self.encode_tuple(types, positions, add(slot(start), constant(4u8)));
+        self.exec(Opcode::Revert, [slot(start), sub(slot(end), slot(start))]);
+        self.ir.height = height;
+    }
+
+    /* `Error(string)` WITH THE STRING AT `position` */
+    pub fn revert_with_reason(&mut self, position: usize) {
+        self.revert_with_error(ERROR_SELECTOR.to_be_bytes(), &[Type::String], &[position]);
+    }
+
+    pub fn revert_with_message(&mut self, message: &str) {
+        let height = self.ir.height;
+        let pointer = self.memory_bytes(message.as_bytes());
+        self.revert_with_reason(pointer);
+        self.ir.height = height;
+    }
+
+    /* ------------------------------ MEMORY ROUTINES ------------------------------ */
+
+    /* (size) -> pointer */
+    pub fn allocate_routine(&mut self) {
+        let size = 1;
+        let pointer = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+        let next = self.let_(add(slot(pointer), ceil32(slot(size))));
+        self.panic_if(
+            apply(
+                Opcode::Or,
+                [
+                    gt(slot(next), constant(MAX_OFFSET)),
+                    lt(slot(next), slot(pointer)),
+                ],
+            ),
+            0x41,
+        );
+        self.exec(Opcode::MStore, [constant(FREE_MEMORY_POINTER), slot(next)]);
+        self.leave(&[pointer]);
+    }
+
+    /* (source, target, length), A WORD AT A TIME */
+    pub fn copy_memory_routine(&mut self) {
+        let (source, target, length) = (1, 2, 3);
+        self.for_range(
+            apply(
+                Opcode::Div,
+                [add(slot(length), constant(31u8)), constant(32u8)],
+            ),
+            |codegen, index| {
+                let offset = mul(slot(index), constant(32u8));
+                codegen.exec(
+                    Opcode::MStore,
+                    [
+                        add(slot(target), offset.clone()),
+                        mload(add(slot(source), offset)),
+                    ],
+                );
+            },
+        );
+        self.leave(&[]);
+    }
+
+    /* (base, exponent) -> power, PANICKING WHEN IT DOES NOT FIT IN `bits` */
+    pub fn exp_routine(&mut self, bits: u16) {
+        let (base, exponent) = (1, 2);
+        let max = constant(mask(bits as usize));
+        let overflows = |a: Word, b: Word| {
+            let product = mul(a.clone(), b.clone());
+            let wrapped = and(
+                iszero(iszero(a.clone())),
+                iszero(eq(apply(Opcode::Div, [product.clone(), a]), b)),
+            );
+            match bits {
+                256 => wrapped,
+                _ => apply(Opcode::Or, [wrapped, gt(product, max.clone())]),
+            }
+        };
+        let result = self.let_(constant(1u8));
+        let start = self.ir.new_block();
+        let end = self.ir.new_block();
+        self.ir.start(start);
+        self.ir.branch(iszero(slot(exponent)), end);
+        self.if_(and(slot(exponent), constant(1u8)), |codegen| {
+            codegen.panic_if(overflows(slot(result), slot(base)), 0x11);
+            codegen.assign(result, mul(slot(result), slot(base)));
+        });
+        self.assign(
+            exponent,
+            apply(Opcode::Shr, [constant(1u8), slot(exponent)]),
+        );
+        self.if_(slot(exponent), |codegen| {
+            codegen.panic_if(overflows(slot(base), slot(base)), 0x11);
+            codegen.assign(base, mul(slot(base), slot(base)));
+        });
+        self.ir.jump(start);
+        self.ir.start(end);
+        self.leave(&[result]);
+    }
+}
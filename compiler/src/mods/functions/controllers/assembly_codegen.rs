@@ -0,0 +1,356 @@
+use crate::mods::{
+    functions::controllers::{
+        codegen::{apply, constant, eq, iszero, slot, Codegen, Exit, Frame, Loop},
+        const_evaluator::yul_literal,
+        expression_codegen::Location,
+    },
+    types::{
+        ast::{
+            ContractPart, Identifier, YulBlock, YulExpression, YulExpressionKind, YulFunction,
+            YulStatement, YulStatementKind,
+        },
+        bindings::Declaration,
+        ir::Word,
+        opcodes::Opcode,
+        settings::EvmVersion,
+    },
+};
+
+/* INLINE ASSEMBLY IS LOWERED STRAIGHT INTO THE IR: ITS VARIABLES ARE VARIABLES OF THE FRAME,
+ITS BUILTINS ARE THE INSTRUCTIONS OF THE SAME NAME AND ITS FUNCTIONS ARE INTERNAL FUNCTIONS,
+GENERATED WHEN THEY ARE FIRST CALLED. ARGUMENTS ARE EVALUATED RIGHT TO LEFT, AS THE IR DOES */
+
+/* WHETHER EVALUATING `expression` CALLS A FUNCTION OF THE ASSEMBLY, WHICH CAN ONLY BE DONE
+ONTO THE STACK */
+fn calls_function(expression: &YulExpression) -> bool {
+    match &expression.kind {
+        YulExpressionKind::Call {
+            function,
+            arguments,
+        } => Opcode::builtin(&function.name).is_none() || arguments.iter().any(calls_function),
+        _ => false,
+    }
+}
+
+impl<'a> Codegen<'a, '_> {
+    /* ------------------------------ FUNCTIONS ------------------------------ */
+
+    /* LIKE AN INTERNAL FUNCTION OF SOLIDITY: THE RETURN VARIABLES START AT ZERO AND `leave`
+    JUMPS TO THE END */
+    pub fn assembly_function(&mut self, function: &'a YulFunction) {
+        self.enter(Frame {
+            span: function.span,
+            contract: self.contract,
+            ..Frame::default()
+        });
+        self.begin_scope();
+        for (index, parameter) in function.parameters.iter().enumerate() {
+            self.declare_at(parameter.span, index + 1, Location::Memory);
+        }
+        let mut outputs = Vec::new();
+        for name in &function.returns {
+            outputs.push(self.let_(constant(0u8)));
+            self.declare(name.span, Location::Memory);
+        }
+        let end = self.ir.new_block();
+        self.frame.exits.push(Exit {
+            label: end,
+            height: self.ir.height,
+        });
+        self.assembly_block(&function.body);
+        self.ir.start(end);
+        self.frame.exits.pop();
+        self.leave(&outputs);
+    }
+
+    /* ------------------------------ STATEMENTS ------------------------------ */
+
+    pub fn assembly_block(&mut self, block: &'a YulBlock) {
+        self.begin_scope();
+        self.assembly_statements(&block.statements);
+        self.end_scope();
+    }
+
+    /* THE FUNCTIONS OF A BLOCK CAN BE CALLED BEFORE THEIR DEFINITION */
+    fn assembly_statements(&mut self, statements: &'a [YulStatement]) {
+        for statement in statements {
+            if let YulStatementKind::Function(_function) = &statement.kind {
+                self.assembly_definitions
+                    .insert(_function.name.span, _function);
+            }
+        }
+        for statement in statements {
+            self.assembly_statement(statement);
+        }
+    }
+
+    fn assembly_statement(&mut self, statement: &'a YulStatement) {
+        let height = self.ir.height;
+        match &statement.kind {
+            YulStatementKind::Block(block) => self.assembly_block(block),
+            YulStatementKind::VariableDeclaration { names, value } => {
+                let words = match value {
+                    Some(_value) => self.assembly_values(_value),
+                    None => names.iter().map(|_| constant(0u8)).collect(),
+                };
+                let positions: Vec<usize> = words
+                    .into_iter()
+                    .map(|word| match word {
+                        Word::Slot(_position) if _position > height => _position,
+                        word => self.let_(word),
+                    })
+                    .collect();
+                self.keep(height, &positions);
+                for (index, name) in names.iter().enumerate() {
+                    self.declare_at(name.span, height + index + 1, Location::Memory);
+                }
+            }
+            YulStatementKind::Assignment { targets, value } => {
+                let words = self.assembly_values(value);
+                for (target, word) in targets.iter().zip(words) {
+                    if let Some(Declaration::Local(_span)) =
+                        self.declarations(&target[0].span).first()
+                    {
+                        if let Some(_position) = self.frame.locals.get(_span).copied() {
+                            self.assign(_position, word);
+                        }
+                    }
+                }
+                self.pop_to(height);
+            }
+            YulStatementKind::Expression(expression) => {
+                self.assembly_values(expression);
+                self.pop_to(height);
+            }
+            YulStatementKind::If { condition, body } => {
+                let skip = self.ir.new_block();
+                self.assembly_value(condition);
+                self.op(Opcode::IsZero);
+                self.ir.jump_if(skip);
+                self.assembly_block(body);
+                self.ir.start(skip);
+            }
+            /* THE VALUE STAYS ON THE STACK WHILE THE CASES ARE COMPARED WITH IT */
+            YulStatementKind::Switch {
+                expression,
+                cases,
+                default,
+            } => {
+                let value = self.assembly_value(expression);
+                let end = self.ir.new_block();
+                for (case, body) in cases {
+                    let YulExpressionKind::Literal(_literal) = &case.kind else {
+                        continue;
+                    };
+                    let case = yul_literal(case.span, _literal).unwrap_or_default();
+                    let next = self.ir.new_block();
+                    self.ir
+                        .branch(iszero(eq(slot(value), constant(case))), next);
+                    self.assembly_block(body);
+                    self.ir.jump(end);
+                    self.ir.start(next);
+                }
+                if let Some(_default) = default {
+                    self.assembly_block(_default);
+                }
+                self.ir.start(end);
+                self.pop(1);
+            }
+            /* WHAT `init` DECLARES LIVES UNTIL THE END OF THE LOOP */
+            YulStatementKind::For {
+                init,
+                condition,
+                post,
+                body,
+            } => {
+                self.begin_scope();
+                self.assembly_statements(&init.statements);
+                let start = self.ir.new_block();
+                let next = self.ir.new_block();
+                let end = self.ir.new_block();
+                self.ir.start(start);
+                let height = self.ir.height;
+                self.assembly_value(condition);
+                self.op(Opcode::IsZero);
+                self.ir.jump_if(end);
+                self.frame.loops.push(Loop {
+                    break_to: Exit { label: end, height },
+                    continue_to: Exit {
+                        label: next,
+                        height,
+                    },
+                });
+                self.assembly_block(body);
+                self.frame.loops.pop();
+                self.ir.start(next);
+                self.assembly_block(post);
+                self.ir.jump(start);
+                self.ir.start(end);
+                self.end_scope();
+            }
+            YulStatementKind::Function(_) => {}
+            YulStatementKind::Break => {
+                if let Some(_loop) = self.frame.loops.last().copied() {
+                    self.jump_out(_loop.break_to);
+                }
+            }
+            YulStatementKind::Continue => {
+                if let Some(_loop) = self.frame.loops.last().copied() {
+                    self.jump_out(_loop.continue_to);
+                }
+            }
+            YulStatementKind::Leave => {
+                if let Some(_exit) = self.frame.exits.last().copied() {
+                    self.jump_out(_exit);
+                }
+            }
+        }
+    }
+
+    /* ------------------------------ EXPRESSIONS ------------------------------ */
+
+    /* PUSHES THE VALUE OF `expression` RIGHT ABOVE WHAT WAS ON THE STACK, RETURNING ITS
+    POSITION */
+    fn assembly_value(&mut self, expression: &'a YulExpression) -> usize {
+        let height = self.ir.height;
+        let word = self.assembly_expression(expression);
+        let position = self.let_(word);
+        self.keep(height, &[position]);
+        height + 1
+    }
+
+    /* THE VALUES OF AN EXPRESSION, ANY NUMBER OF THEM FOR A CALL. WHAT IS CALLED TO GET THEM
+    STAYS ON THE STACK UNTIL THE END OF THE STATEMENT */
+    fn assembly_values(&mut self, expression: &'a YulExpression) -> Vec<Word> {
+        match &expression.kind {
+            YulExpressionKind::Call {
+                function,
+                arguments,
+            } => self.assembly_call(function, arguments),
+            _ => vec![self.assembly_expression(expression)],
+        }
+    }
+
+    fn assembly_expression(&mut self, expression: &'a YulExpression) -> Word {
+        match &expression.kind {
+            YulExpressionKind::Literal(literal) => {
+                constant(yul_literal(expression.span, literal).unwrap_or_default())
+            }
+            YulExpressionKind::Path(path) => self.assembly_path(path),
+            YulExpressionKind::Call { .. } => self
+                .assembly_values(expression)
+                .pop()
+                .unwrap_or(constant(0u8)),
+        }
+    }
+
+    /* A VARIABLE OF THE ASSEMBLY OR A LOCAL ONE OF SOLIDITY, WHERE ONE IN STORAGE HOLDS ITS SLOT
+    AND STARTS A WORD, OR A STATE VARIABLE */
+    fn assembly_path(&mut self, path: &'a [Identifier]) -> Word {
+        let name = &path[0];
+        let suffix = path.get(1).map(|suffix| suffix.name.as_str());
+        let Some(declaration) = self.declarations(&name.span).first().copied() else {
+            return constant(0u8);
+        };
+        if let Declaration::Local(_span) = declaration {
+            return match (self.frame.locals.get(&_span), suffix) {
+                (_, Some("offset")) | (None, _) => constant(0u8),
+                (Some(_position), _) => slot(*_position),
+            };
+        }
+        let Some(ContractPart::StateVariable(variable)) = self.part(declaration) else {
+            return constant(0u8);
+        };
+        match (suffix, self.storage_position(variable)) {
+            (Some("slot"), Some((_slot, _))) => constant(_slot),
+            (Some(_), Some((_, _offset))) => constant(_offset),
+            (Some(_), None) => {
+                self.unsupported(name.span, "state variables outside the storage layout");
+                constant(0u8)
+            }
+            (None, _) => {
+                let ty = self.type_name(&variable.ty.span);
+                self.state_variable(variable, &ty);
+                slot(self.ir.height)
+            }
+        }
+    }
+
+    /* THE RESULTS OF A BUILTIN ARE A WORD TO EVALUATE WHERE IT IS USED, THOSE OF A FUNCTION ARE
+    ON THE STACK */
+    fn assembly_call(
+        &mut self,
+        function: &'a Identifier,
+        arguments: &'a [YulExpression],
+    ) -> Vec<Word> {
+        match self.declarations(&function.span).first().copied() {
+            Some(Declaration::Builtin(name)) => {
+                let Some((_, opcode)) = Opcode::builtin(name) else {
+                    return Vec::new();
+                };
+                if matches!(
+                    opcode,
+                    Opcode::TLoad
+                        | Opcode::TStore
+                        | Opcode::MCopy
+                        | Opcode::BlobHash
+                        | Opcode::BlobBaseFee
+                ) && self.context.settings.evm_version < EvmVersion::Cancun
+                {
+                    self.unsupported(function.span, format!("`{name}` on this EVM version"));
+                }
+                let mut words = self.assembly_arguments(arguments);
+                match opcode.stack_io() {
+                    _ if opcode == Opcode::Pop => {
+                        self.let_(words.remove(0));
+                        self.pop(1);
+                        Vec::new()
+                    }
+                    (_, 0) => {
+                        self.exec(opcode, words);
+                        Vec::new()
+                    }
+                    _ => vec![apply(opcode, words)],
+                }
+            }
+            Some(Declaration::Local(_span)) => {
+                let Some(definition) = self.assembly_definitions.get(&_span).copied() else {
+                    return Vec::new();
+                };
+                let id = self.assembly_function_id(definition);
+                let mut words = self.assembly_arguments(arguments);
+                /* THE ARGUMENTS ARE PUSHED LEFT TO RIGHT, SO THOSE THAT DEPEND ON THE ORDER ARE
+                EVALUATED FIRST, RIGHT TO LEFT */
+                if words.iter().filter(|word| !word.is_pure()).count() > 1 {
+                    for word in words.iter_mut().rev().filter(|word| !word.is_pure()) {
+                        *word = slot(self.let_(word.clone()));
+                    }
+                }
+                self.call_function(id, &words);
+                let top = self.ir.height;
+                (top + 1 - definition.returns.len()..=top)
+                    .map(slot)
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /* A CALL OF A FUNCTION OF THE ASSEMBLY IN AN ARGUMENT RUNS BEFORE THE WORDS OF THE
+    ARGUMENTS TO ITS RIGHT ARE EVALUATED, SO THOSE THAT DEPEND ON THE ORDER ARE EVALUATED
+    BEFORE IT */
+    fn assembly_arguments(&mut self, arguments: &'a [YulExpression]) -> Vec<Word> {
+        let mut words: Vec<Word> = Vec::new();
+        for argument in arguments.iter().rev() {
+            if calls_function(argument) {
+                for word in words.iter_mut().filter(|word| !word.is_pure()) {
+                    *word = slot(self.let_(word.clone()));
+                }
+            }
+            let word = self.assembly_expression(argument);
+            words.push(word);
+        }
+        words.reverse();
+        words
+    }
+}
@@ -0,0 +1,2088 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use num_bigint::BigUint;
+
+use crate::mods::{
+    functions::{
+        controllers::{
+            abi::{getter, signature},
+            evm_backend::lower,
+            expression_codegen::Location,
+            ir_verifier::verify,
+            optimizer::optimize,
+            storage_layout::{storage_types, type_id},
+        },
+        helpers::hash_helper::{keccak256, selector},
+    },
+    types::{
+        assembly::Bytecode,
+        ast::{
+            Block, CallArguments, ContractDefinition, ContractKind, ContractPart, CronDefinition,
+            Expression, ExpressionKind, FunctionDefinition, FunctionKind, ModifierDefinition,
+            Parameter, StateMutability, Statement, StatementKind, StorageLocation,
+            VariableDefinition, VariableMutability, Visibility, YulFunction,
+        },
+        bindings::{BaseArguments, Bindings, ContractId, Declaration},
+        compilation::ContractBytecode,
+        compiler_errors::{CodegenError, CompilerError},
+        cron::{CronField, CronJob},
+        diagnostics::Diagnostics,
+        ir::{BlockId, FunctionId, Link, Program, Terminator, Word},
+        line_descriptors::Span,
+        opcodes::Opcode,
+        semantic_types::{Type, TypeTable},
+        settings::{Emit, EvmVersion, Settings},
+        source_graph::SourceGraph,
+        source_map::SourceMap,
+        storage_layout::{StorageLayout, StorageType},
+    },
+};
+
+/* EVERYTHING THE BACKEND READS, THE OUTPUT OF THE EARLIER PASSES */
+pub struct CodegenContext<'a> {
+    pub graph: &'a SourceGraph,
+    pub bindings: &'a Bindings,
+    pub types: &'a TypeTable,
+    pub sources: &'a SourceMap,
+    pub storage_layouts: &'a HashMap<ContractId, StorageLayout>,
+    pub cron_jobs: &'a HashMap<ContractId, Vec<CronJob>>,
+    pub settings: &'a Settings,
+}
+
+/* BYTECODE OF THE CONTRACTS GENERATED SO FAR, NONE FOR THOSE THAT FAILED. A CONTRACT THAT
+CREATES ANOTHER WITH `new` EMBEDS ITS CREATION CODE, SO ONE CONTRACT MAY BE ASKED FOR WHILE
+ANOTHER IS BEING GENERATED */
+#[derive(Default)]
+pub struct BytecodeCache {
+    bytecode: HashMap<ContractId, Option<ContractBytecode>>,
+    active: Vec<ContractId>,
+    /* A FUNCTION IS COMPILED INTO THE CREATION AND THE RUNTIME CODE, ITS ERRORS ARE REPORTED ONCE */
+    reported: HashSet<Span>,
+}
+
+/* THE CREATION AND RUNTIME CODE OF `contract`, NONE FOR WHAT CANNOT BE DEPLOYED OR WHEN IT
+USES SOMETHING THE BACKEND DOES NOT SUPPORT, WHICH IS REPORTED */
+pub fn generate_bytecode(
+    contract: ContractId,
+    context: &CodegenContext,
+    cache: &mut BytecodeCache,
+    diagnostics: &mut Diagnostics,
+) -> Option<ContractBytecode> {
+    if let Some(_bytecode) = cache.bytecode.get(&contract) {
+        return _bytecode.clone();
+    }
+    let definition = &context.graph.units[contract.0].ast.contracts[contract.1];
+    if definition.kind != ContractKind::Contract || cache.active.contains(&contract) {
+        return None;
+    }
+    if context.settings.evm_version < EvmVersion::Constantinople {
+        diagnostics.push(
+            CompilerError::CodegenError(CodegenError::Unsupported(format!(
+                "the `{}` EVM, which has no shift instructions",
+                context.settings.evm_version
+            )))
+            .diagnostic()
+            .with_label(
+                definition.name.span,
+                "cannot be compiled for this EVM version",
+            ),
+        );
+        cache.bytecode.insert(contract, None);
+        return None;
+    }
+    cache.active.push(contract);
+    let runtime = Codegen::new(contract, false, context, cache, diagnostics).runtime();
+    let bytecode = match runtime {
+        Some((_runtime, _runtime_ir, _runtime_passes)) => {
+            Codegen::new(contract, true, context, cache, diagnostics)
+                .creation(&_runtime)
+                .map(
+                    |(creation, creation_ir, creation_passes)| ContractBytecode {
+                        creation: creation.code,
+                        runtime: _runtime.code,
+                        ir: context.settings.emit.contains(&Emit::Ir).then(|| {
+                            format!("/* creation */\n{creation_ir}\n/* runtime */\n{_runtime_ir}")
+                        }),
+                        passes: (context.settings.optimize
+                            && context.settings.emit.contains(&Emit::Passes))
+                        .then(|| {
+                            format!(
+                            "/* creation */\n{creation_passes}\n/* runtime */\n{_runtime_passes}"
+                        )
+                        }),
+                    },
+                )
+        }
+        None => None,
+    };
+    cache.active.retain(|id| *id != contract);
+    cache.bytecode.insert(contract, bytecode.clone());
+    bytecode
+}
+
+pub fn constant(value: impl Into<BigUint>) -> Word {
+    Word::Constant(value.into())
+}
+
+pub fn slot(position: usize) -> Word {
+    Word::Slot(position)
+}
+
+pub fn apply(opcode: Opcode, arguments: impl Into<Vec<Word>>) -> Word {
+    Word::Apply(opcode, arguments.into())
+}
+
+pub fn add(a: Word, b: Word) -> Word {
+    apply(Opcode::Add, [a, b])
+}
+
+pub fn sub(a: Word, b: Word) -> Word {
+    apply(Opcode::Sub, [a, b])
+}
+
+pub fn mul(a: Word, b: Word) -> Word {
+    apply(Opcode::Mul, [a, b])
+}
+
+pub fn div(a: Word, b: Word) -> Word {
+    apply(Opcode::Div, [a, b])
+}
+
+pub fn and(a: Word, b: Word) -> Word {
+    apply(Opcode::And, [a, b])
+}
+
+pub fn or(a: Word, b: Word) -> Word {
+    apply(Opcode::Or, [a, b])
+}
+
+pub fn not(a: Word) -> Word {
+    apply(Opcode::Not, [a])
+}
+
+pub fn shl(shift: Word, value: Word) -> Word {
+    apply(Opcode::Shl, [shift, value])
+}
+
+pub fn shr(shift: Word, value: Word) -> Word {
+    apply(Opcode::Shr, [shift, value])
+}
+
+pub fn lt(a: Word, b: Word) -> Word {
+    apply(Opcode::Lt, [a, b])
+}
+
+pub fn gt(a: Word, b: Word) -> Word {
+    apply(Opcode::Gt, [a, b])
+}
+
+pub fn eq(a: Word, b: Word) -> Word {
+    apply(Opcode::Eq, [a, b])
+}
+
+pub fn iszero(a: Word) -> Word {
+    apply(Opcode::IsZero, [a])
+}
+
+pub fn mload(address: Word) -> Word {
+    apply(Opcode::MLoad, [address])
+}
+
+pub fn sload(key: Word) -> Word {
+    apply(Opcode::SLoad, [key])
+}
+
+pub fn rem(a: Word, modulus: impl Into<BigUint>) -> Word {
+    apply(Opcode::Mod, [a, constant(modulus)])
+}
+
+/* 1 WHEN `value` IS ONE OF THE VALUES OF `field`, 0 OTHERWISE. NOTHING FOR `*` */
+fn selected(field: &CronField, value: Word) -> Option<Word> {
+    if field.is_wildcard() {
+        return None;
+    }
+    let mask = field.values.iter().fold(BigUint::from(0u8), |mask, value| {
+        mask | (BigUint::from(1u8) << *value)
+    });
+    Some(and(shr(value, constant(mask)), constant(1u8)))
+}
+
+pub fn env(opcode: Opcode) -> Word {
+    apply(opcode, [])
+}
+
+/* `x` ROUNDED UP TO A MULTIPLE OF 32 */
+pub fn ceil32(x: Word) -> Word {
+    and(add(x, constant(31u8)), not(constant(31u8)))
+}
+
+/* WHERE `return`, `break` AND `continue` JUMP AND HOW MANY VALUES ARE ON THE STACK THERE */
+#[derive(Debug, Clone, Copy)]
+pub struct Exit {
+    pub label: BlockId,
+    pub height: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Loop {
+    pub break_to: Exit,
+    pub continue_to: Exit,
+}
+
+#[derive(Debug, Default)]
+pub struct Scope {
+    pub height: usize,
+    pub names: Vec<(Span, Option<usize>)>,
+}
+
+/* THE FUNCTION, MODIFIER OR ROUTINE BEING GENERATED. POSITIONS COUNT FROM THE BOTTOM OF THE
+FRAME, WHERE THE RETURN ADDRESS IS, AT 1 */
+#[derive(Debug, Default)]
+pub struct Frame<'a> {
+    /* PARAMETERS AND LOCAL VARIABLES IN SCOPE, BY THE SPAN OF THEIR NAME */
+    pub locals: HashMap<Span, usize>,
+    /* THOSE DECLARED `storage`, WHICH HOLD A SLOT */
+    pub storage_locals: HashSet<Span>,
+    pub scopes: Vec<Scope>,
+    pub loops: Vec<Loop>,
+    /* WHERE A `return` GOES: THE END OF THE INNERMOST MODIFIER OR FUNCTION BODY */
+    pub exits: Vec<Exit>,
+    /* POSITION, TYPE AND LOCATION OF EACH RETURN VARIABLE */
+    pub returns: Vec<(usize, Type, Location)>,
+    /* WHAT A `_` RUNS: THE FUNCTION AND THE INDEX OF ITS NEXT MODIFIER */
+    pub placeholders: Vec<(&'a FunctionDefinition, usize)>,
+    pub unchecked: bool,
+    /* THE CONTRACT THAT DECLARES THE CODE, WHERE `super` STARTS LOOKING */
+    pub contract: ContractId,
+    pub span: Span,
+}
+
+/* CODE SHARED BY EVERY USE, AN INTERNAL FUNCTION OF THE IR WHOSE RESULTS REPLACE ITS
+ARGUMENTS */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Routine {
+    /* (size) -> pointer */
+    Allocate,
+    /* (source, target, length), WITHOUT MCOPY */
+    CopyMemory,
+    /* (base, exponent) -> power, OF AN UNSIGNED TYPE */
+    Exp(u16),
+    /* (value, position) -> end */
+    AbiEncode(Type),
+    /* (head, base, end) -> value */
+    AbiDecode { ty: Type, calldata: bool },
+    /* (slot) -> pointer */
+    StorageToMemory(Type),
+    /* (pointer, slot) */
+    MemoryToStorage(Type),
+    /* (slot) */
+    ClearStorage(Type),
+    /* (slot, index) -> (slot, offset) OF AN ARRAY ELEMENT */
+    StorageIndex(Type),
+    /* (pointer, slot) -> slot OF THE VALUE UNDER A `string` OR `bytes` KEY */
+    MappingSlot,
+}
+
+impl Routine {
+    /* HOW THE ROUTINE IS NAMED IN THE IR */
+    pub fn name(&self) -> String {
+        match self {
+            Routine::Allocate => "allocate".to_string(),
+            Routine::CopyMemory => "copy_memory".to_string(),
+            Routine::Exp(bits) => format!("exp_uint{bits}"),
+            Routine::AbiEncode(ty) => format!("abi_encode_{ty}"),
+            Routine::AbiDecode { ty, calldata: true } => format!("abi_decode_{ty}_calldata"),
+            Routine::AbiDecode { ty, .. } => format!("abi_decode_{ty}"),
+            Routine::StorageToMemory(ty) => format!("storage_to_memory_{ty}"),
+            Routine::MemoryToStorage(ty) => format!("memory_to_storage_{ty}"),
+            Routine::ClearStorage(ty) => format!("clear_storage_{ty}"),
+            Routine::StorageIndex(ty) => format!("storage_index_{ty}"),
+            Routine::MappingSlot => "mapping_slot".to_string(),
+        }
+    }
+}
+
+pub enum Pending<'a> {
+    Function(Declaration),
+    Routine(Routine),
+    Assembly(&'a YulFunction),
+}
+
+/* GENERATES THE CREATION OR THE RUNTIME CODE OF ONE CONTRACT */
+pub struct Codegen<'a, 'c> {
+    pub context: &'c CodegenContext<'a>,
+    pub cache: &'c mut BytecodeCache,
+    pub diagnostics: &'c mut Diagnostics,
+    pub contract: ContractId,
+    pub creation: bool,
+    pub ir: Program,
+    pub frame: Frame<'a>,
+    pub failed: bool,
+    storage_types: BTreeMap<String, StorageType>,
+    functions: HashMap<Declaration, FunctionId>,
+    routines: HashMap<String, FunctionId>,
+    pending: Vec<(FunctionId, Pending<'a>)>,
+    /* FUNCTIONS DEFINED IN INLINE ASSEMBLY SO FAR, BY THE SPAN OF THEIR NAME */
+    pub assembly_definitions: HashMap<Span, &'a YulFunction>,
+    /* DATA SECTIONS HOLDING THE CREATION OR RUNTIME CODE OF OTHER CONTRACTS */
+    pub embedded: HashMap<(ContractId, bool), usize>,
+}
+
+/* MEMORY BELOW 0x80: SCRATCH SPACE FOR HASHING, THE FREE MEMORY POINTER AND A ZERO WORD THAT
+EMPTY DYNAMIC ARRAYS POINT AT */
+pub const FREE_MEMORY_POINTER: u8 = 0x40;
+pub const ZERO_SLOT: u8 = 0x60;
+pub const MEMORY_START: u8 = 0x80;
+
+/* SELECTOR OF `Error(string)` */
+pub const ERROR_SELECTOR: u32 = 0x08c379a0;
+
+impl<'a, 'c> Codegen<'a, 'c> {
+    fn new(
+        contract: ContractId,
+        creation: bool,
+        context: &'c CodegenContext<'a>,
+        cache: &'c mut BytecodeCache,
+        diagnostics: &'c mut Diagnostics,
+    ) -> Self {
+        let storage_types = context
+            .storage_layouts
+            .get(&contract)
+            .map(|layout| layout.types.clone())
+            .unwrap_or_default();
+        Codegen {
+            context,
+            cache,
+            diagnostics,
+            contract,
+            creation,
+            ir: Program::default(),
+            frame: Frame::default(),
+            failed: false,
+            storage_types,
+            functions: HashMap::new(),
+            routines: HashMap::new(),
+            pending: Vec::new(),
+            assembly_definitions: HashMap::new(),
+            embedded: HashMap::new(),
+        }
+    }
+
+    /* ------------------------------ PROGRAM LOOKUPS ------------------------------ */
+
+    pub fn graph(&self) -> &'a SourceGraph {
+        self.context.graph
+    }
+
+    pub fn types(&self) -> &'a TypeTable {
+        self.context.types
+    }
+
+    pub fn definition(&self, (file_id, contract): ContractId) -> &'a ContractDefinition {
+        &self.context.graph.units[file_id].ast.contracts[contract]
+    }
+
+    pub fn part(&self, declaration: Declaration) -> Option<&'a ContractPart> {
+        match declaration {
+            Declaration::Member {
+                file_id,
+                contract,
+                part,
+            } => self.definition((file_id, contract)).parts.get(part),
+            _ => None,
+        }
+    }
+
+    pub fn declarations(&self, span: &Span) -> &'a [Declaration] {
+        self.context.bindings.get(span).unwrap_or_default()
+    }
+
+    pub fn type_name(&self, span: &Span) -> Type {
+        self.context
+            .types
+            .type_names
+            .get(span)
+            .cloned()
+            .unwrap_or(Type::Unknown)
+    }
+
+    pub fn expression_type(&self, expression: &Expression) -> Type {
+        self.context
+            .types
+            .expressions
+            .get(&expression.span)
+            .cloned()
+            .unwrap_or(Type::Unknown)
+    }
+
+    pub fn parameter_types(&self, parameters: &[Parameter]) -> Vec<Type> {
+        parameters
+            .iter()
+            .map(|parameter| self.type_name(&parameter.ty.span))
+            .collect()
+    }
+
+    pub fn parameter_location(parameter: &Parameter) -> Location {
+        match parameter.storage {
+            Some(StorageLocation::Storage) => Location::Storage,
+            _ => Location::Memory,
+        }
+    }
+
+    pub fn linearization(&self, contract: ContractId) -> Vec<ContractId> {
+        self.context
+            .bindings
+            .linearization
+            .get(&contract)
+            .cloned()
+            .unwrap_or(vec![contract])
+    }
+
+    /* THE IMPLEMENTATION A CALL OF `declaration` RUNS IN THIS CONTRACT. PRIVATE AND LIBRARY
+    FUNCTIONS CANNOT BE OVERRIDDEN */
+    pub fn virtual_function(&self, declaration: Declaration) -> Declaration {
+        let Declaration::Member {
+            file_id, contract, ..
+        } = declaration
+        else {
+            return declaration;
+        };
+        let Some(ContractPart::Function(function)) = self.part(declaration) else {
+            return declaration;
+        };
+        if function.visibility == Some(Visibility::Private)
+            || self.definition((file_id, contract)).kind == ContractKind::Library
+        {
+            return declaration;
+        }
+        let name = function.name.as_ref().map(|name| name.name.as_str());
+        let parameters = self.parameter_types(&function.parameters);
+        self.linearization(self.contract)
+            .into_iter()
+            .find_map(|id| {
+                self.find_function(id, |candidate| {
+                    candidate.name.as_ref().map(|name| name.name.as_str()) == name
+                        && self.parameter_types(&candidate.parameters) == parameters
+                })
+            })
+            .unwrap_or(declaration)
+    }
+
+    /* `super.name(..)`: THE FIRST IMPLEMENTATION OF THE SIGNATURE OF `declaration` AFTER THE
+    CONTRACT THE CALL IS WRITTEN IN, IN THE LINEARIZATION OF THE CONTRACT BEING COMPILED */
+    pub fn super_function(&self, declaration: Declaration) -> Option<Declaration> {
+        let Some(ContractPart::Function(function)) = self.part(declaration) else {
+            return None;
+        };
+        let name = function.name.as_ref().map(|name| name.name.as_str());
+        let parameters = self.parameter_types(&function.parameters);
+        self.linearization(self.contract)
+            .into_iter()
+            .skip_while(|id| *id != self.frame.contract)
+            .skip(1)
+            .find_map(|id| {
+                self.find_function(id, |candidate| {
+                    candidate.name.as_ref().map(|name| name.name.as_str()) == name
+                        && self.parameter_types(&candidate.parameters) == parameters
+                })
+            })
+    }
+
+    fn find_function(
+        &self,
+        (file_id, contract): ContractId,
+        matches: impl Fn(&FunctionDefinition) -> bool,
+    ) -> Option<Declaration> {
+        self.definition((file_id, contract))
+            .parts
+            .iter()
+            .enumerate()
+            .find_map(|(part, candidate)| match candidate {
+                ContractPart::Function(_function)
+                    if _function.kind == FunctionKind::Function
+                        && _function.body.is_some()
+                        && matches(_function) =>
+                {
+                    Some(Declaration::Member {
+                        file_id,
+                        contract,
+                        part,
+                    })
+                }
+                _ => None,
+            })
+    }
+
+    /* MODIFIERS ARE VIRTUAL, THE MOST DERIVED ONE WITH THE NAME AND A BODY RUNS */
+    fn virtual_modifier(&self, modifier: &'a ModifierDefinition) -> &'a ModifierDefinition {
+        self.linearization(self.contract)
+            .into_iter()
+            .flat_map(|id| &self.definition(id).parts)
+            .find_map(|part| match part {
+                ContractPart::Modifier(_modifier)
+                    if _modifier.name.name == modifier.name.name && _modifier.body.is_some() =>
+                {
+                    Some(_modifier)
+                }
+                _ => None,
+            })
+            .unwrap_or(modifier)
+    }
+
+    /* THE ARGUMENTS OF A CALL IN THE ORDER OF THE PARAMETERS */
+    pub fn ordered_arguments(
+        &self,
+        arguments: &'a CallArguments,
+        names: &[Option<String>],
+    ) -> Vec<&'a Expression> {
+        match arguments {
+            CallArguments::Positional(_arguments) => _arguments.iter().collect(),
+            CallArguments::Named(_arguments) => names
+                .iter()
+                .filter_map(|name| {
+                    _arguments
+                        .iter()
+                        .find(|argument| Some(&argument.name.name) == name.as_ref())
+                        .map(|argument| &argument.value)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn parameter_names(parameters: &[Parameter]) -> Vec<Option<String>> {
+        parameters
+            .iter()
+            .map(|parameter| parameter.name.as_ref().map(|name| name.name.clone()))
+            .collect()
+    }
+
+    /* WHERE A STATE VARIABLE OF THIS CONTRACT IS STORED */
+    pub fn storage_position(&self, variable: &VariableDefinition) -> Option<(BigUint, u8)> {
+        self.context
+            .storage_layouts
+            .get(&self.contract)?
+            .variables
+            .iter()
+            .find(|candidate| candidate.span == Some(variable.name.span))
+            .map(|candidate| (candidate.slot.clone(), candidate.offset))
+    }
+
+    /* HOW VALUES OF `ty` ARE STORED, AS IN THE STORAGE LAYOUT */
+    pub fn storage_type(&mut self, ty: &Type) -> StorageType {
+        let id = type_id(ty);
+        if !self.storage_types.contains_key(&id) {
+            let context = self.context;
+            self.storage_types.extend(storage_types(
+                ty,
+                context.graph,
+                context.types,
+                context.sources,
+            ));
+        }
+        self.storage_types[&id].clone()
+    }
+
+    /* IMMUTABLES ARE NUMBERED IN STORAGE ORDER. THE CONSTRUCTOR KEEPS THEM IN MEMORY FROM
+    `MEMORY_START` ON AND COPIES THEM INTO THE RUNTIME CODE */
+    pub fn immutables(&self) -> Vec<&'a VariableDefinition> {
+        self.context
+            .bindings
+            .inheritance_order(self.contract)
+            .into_iter()
+            .flat_map(|id| &self.definition(id).parts)
+            .filter_map(|part| match part {
+                ContractPart::StateVariable(variable)
+                    if variable.mutability == Some(VariableMutability::Immutable) =>
+                {
+                    Some(variable)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn immutable_id(&self, variable: &VariableDefinition) -> usize {
+        self.immutables()
+            .iter()
+            .position(|candidate| candidate.name.span == variable.name.span)
+            .unwrap_or_default()
+    }
+
+    /* ------------------------------ DIAGNOSTICS ------------------------------ */
+
+    pub fn unsupported(&mut self, span: Span, what: impl Into<String>) {
+        self.failed = true;
+        if self.cache.reported.insert(span) {
+            self.diagnostics.push(
+                CompilerError::CodegenError(CodegenError::Unsupported(what.into()))
+                    .diagnostic()
+                    .with_label(span, "not supported by the code generator"),
+            );
+        }
+    }
+
+    fn too_deep(&mut self, span: Span) {
+        self.failed = true;
+        if self.cache.reported.insert(span) {
+            self.diagnostics.push(
+                CompilerError::CodegenError(CodegenError::StackTooDeep(
+                    "stack too deep, a value is more than 16 slots down the stack".to_string(),
+                ))
+                .diagnostic()
+                .with_label(span, "too many variables are live here")
+                .with_help("move some variables into a struct in memory or split the function"),
+            );
+        }
+    }
+
+    /* ------------------------------ STACK ------------------------------ */
+
+    /* THE CODE IS BUILT AS IR, WHOSE VARIABLES ARE THE VALUES ON THE STACK OF THE FUNCTION
+    BEING GENERATED, COUNTED FROM ITS FIRST PARAMETER AT 1 */
+
+    /* APPLIES AN INSTRUCTION TO THE VALUES ON TOP OF THE STACK, THE TOP ONE AS ITS FIRST
+    ARGUMENT */
+    pub fn op(&mut self, opcode: Opcode) {
+        let top = self.ir.height;
+        let (pops, _) = opcode.stack_io();
+        let arguments: Vec<Word> = (0..pops).map(|index| slot(top - index)).collect();
+        self.ir.replace(pops, apply(opcode, arguments));
+    }
+
+    pub fn push(&mut self, value: impl Into<BigUint>) {
+        self.ir.let_(constant(value));
+    }
+
+    pub fn eval(&mut self, word: &Word) {
+        self.ir.let_(word.clone());
+    }
+
+    /* RUNS AN INSTRUCTION THAT PUSHES NOTHING, SUCH AS MSTORE */
+    pub fn exec(&mut self, opcode: Opcode, arguments: impl Into<Vec<Word>>) {
+        self.ir.exec(apply(opcode, arguments));
+    }
+
+    /* PUSHES `word`, RETURNING ITS POSITION */
+    pub fn let_(&mut self, word: Word) -> usize {
+        self.ir.let_(word)
+    }
+
+    pub fn assign(&mut self, position: usize, word: Word) {
+        self.ir.assign(position, word);
+    }
+
+    pub fn dup(&mut self, position: usize) {
+        self.ir.let_(slot(position));
+    }
+
+    /* MOVES THE TOP OF THE STACK INTO `position` */
+    pub fn store_top(&mut self, position: usize) {
+        self.ir.store_top(position);
+    }
+
+    pub fn pop(&mut self, count: usize) {
+        self.ir.pop(count);
+    }
+
+    pub fn pop_to(&mut self, height: usize) {
+        self.pop(self.ir.height.saturating_sub(height));
+    }
+
+    /* REPLACES THE `count` VALUES UNDER THE TOP WITH IT */
+    pub fn squash(&mut self, count: usize) {
+        let top = self.ir.height;
+        self.ir.keep(top - count - 1, &[top]);
+    }
+
+    /* PUSHES `word`, WHICH READS THE TOP `count` VALUES, IN THEIR PLACE */
+    pub fn replace(&mut self, count: usize, word: Word) {
+        self.ir.replace(count, word);
+    }
+
+    /* MOVES THE TOP VALUE UNDER THE `count` BELOW IT */
+    pub fn rotate_down(&mut self, count: usize) {
+        let top = self.ir.height;
+        let base = top - count - 1;
+        let mut positions = vec![top];
+        positions.extend(base + 1..top);
+        self.ir.keep(base, &positions);
+    }
+
+    /* LEAVES ONLY THE VALUES AT `positions`, IN THAT ORDER, ABOVE `base` */
+    pub fn keep(&mut self, base: usize, positions: &[usize]) {
+        self.ir.keep(base, positions);
+    }
+
+    /* ------------------------------ CONTROL FLOW ------------------------------ */
+
+    pub fn if_(&mut self, condition: Word, body: impl FnOnce(&mut Self)) {
+        let skip = self.ir.new_block();
+        self.ir.branch(iszero(condition), skip);
+        let height = self.ir.height;
+        body(self);
+        self.ir.height = height;
+        self.ir.start(skip);
+    }
+
+    /* RUNS `body` WITH THE POSITION OF A COUNTER GOING FROM 0 TO `count` */
+    pub fn for_range(&mut self, count: Word, mut body: impl FnMut(&mut Self, usize)) {
+        let count = self.let_(count);
+        let index = self.let_(constant(0u8));
+        let start = self.ir.new_block();
+        let end = self.ir.new_block();
+        self.ir.start(start);
+        self.ir.branch(iszero(lt(slot(index), slot(count))), end);
+        body(self, index);
+        self.assign(index, add(slot(index), constant(1u8)));
+        self.ir.jump(start);
+        self.ir.start(end);
+        self.pop(2);
+    }
+
+    pub fn revert_if(&mut self, condition: Word) {
+        let revert = self.ir.out_of_line(Terminator::Halt(apply(
+            Opcode::Revert,
+            [constant(0u8), constant(0u8)],
+        )));
+        self.ir.branch(condition, revert);
+    }
+
+    pub fn panic_if(&mut self, condition: Word, code: u8) {
+        let panic = self.ir.out_of_line(Terminator::Panic(code));
+        self.ir.branch(condition, panic);
+    }
+
+    /* THE IR FUNCTION OF A ROUTINE, TAKING `parameters` VALUES AND RETURNING `returns` */
+    pub fn routine_function(
+        &mut self,
+        routine: Routine,
+        parameters: usize,
+        returns: usize,
+    ) -> FunctionId {
+        let key = format!("{routine:?}");
+        if let Some(_function) = self.routines.get(&key) {
+            return *_function;
+        }
+        let span = self.definition(self.contract).name.span;
+        let function = self
+            .ir
+            .add_function(routine.name(), span, parameters, returns);
+        self.routines.insert(key, function);
+        self.pending.push((function, Pending::Routine(routine)));
+        function
+    }
+
+    pub fn function_id(&mut self, declaration: Declaration) -> FunctionId {
+        if let Some(_function) = self.functions.get(&declaration) {
+            return *_function;
+        }
+        let (name, span, parameters, returns) = match self.part(declaration) {
+            Some(ContractPart::Function(_function)) => (
+                _function.name.as_ref().map_or_else(
+                    || format!("{:?}", _function.kind).to_lowercase(),
+                    |name| name.name.clone(),
+                ),
+                _function.span,
+                _function.parameters.len(),
+                _function.returns.len(),
+            ),
+            _ => (String::new(), Span::default(), 0, 0),
+        };
+        let function = self.ir.add_function(name, span, parameters, returns);
+        self.functions.insert(declaration, function);
+        self.pending
+            .push((function, Pending::Function(declaration)));
+        function
+    }
+
+    /* FUNCTIONS OF INLINE ASSEMBLY ARE KEYED BY THE SPAN OF THEIR NAME, AS NAMES RESOLVE TO */
+    pub fn assembly_function_id(&mut self, function: &'a YulFunction) -> FunctionId {
+        let declaration = Declaration::Local(function.name.span);
+        if let Some(_function) = self.functions.get(&declaration) {
+            return *_function;
+        }
+        let id = self.ir.add_function(
+            function.name.name.clone(),
+            function.span,
+            function.parameters.len(),
+            function.returns.len(),
+        );
+        self.functions.insert(declaration, id);
+        self.pending.push((id, Pending::Assembly(function)));
+        id
+    }
+
+    pub fn call_routine(&mut self, routine: Routine, arguments: Vec<Word>, outputs: usize) {
+        let function = self.routine_function(routine, arguments.len(), outputs);
+        self.call_function(function, &arguments);
+    }
+
+    /* PUSHES THE ARGUMENTS AND CALLS `function`, WHOSE RESULTS REPLACE THEM */
+    pub fn call_function(&mut self, function: FunctionId, arguments: &[Word]) {
+        for argument in arguments {
+            self.eval(argument);
+        }
+        self.ir.call(function);
+    }
+
+    /* LEAVES A FUNCTION OR ROUTINE WITH THE VALUES AT `outputs` AS ITS RESULTS */
+    pub fn leave(&mut self, outputs: &[usize]) {
+        self.ir.leave(outputs);
+    }
+
+    /* STARTS GENERATING WHAT `frame` DESCRIBES */
+    pub fn enter(&mut self, frame: Frame<'a>) {
+        self.ir.span = frame.span;
+        self.frame = frame;
+    }
+
+    /* GENERATES THE FUNCTIONS AND ROUTINES CALLED SO FAR, AND THE ONES THEY CALL */
+    fn flush(&mut self) {
+        while let Some((function, pending)) = self.pending.pop() {
+            self.enter(Frame::default());
+            self.ir.begin(function);
+            match pending {
+                Pending::Function(declaration) => self.function(declaration),
+                Pending::Routine(routine) => self.routine(routine),
+                Pending::Assembly(_function) => self.assembly_function(_function),
+            }
+            self.ir.end();
+        }
+    }
+
+    fn routine(&mut self, routine: Routine) {
+        self.enter(Frame {
+            span: self.definition(self.contract).name.span,
+            ..Frame::default()
+        });
+        match routine {
+            Routine::Allocate => self.allocate_routine(),
+            Routine::CopyMemory => self.copy_memory_routine(),
+            Routine::Exp(bits) => self.exp_routine(bits),
+            Routine::AbiEncode(ty) => self.abi_encode_routine(&ty),
+            Routine::AbiDecode { ty, calldata } => self.abi_decode_routine(&ty, calldata),
+            Routine::StorageToMemory(ty) => self.storage_to_memory_routine(&ty),
+            Routine::MemoryToStorage(ty) => self.memory_to_storage_routine(&ty),
+            Routine::ClearStorage(ty) => self.clear_storage_routine(&ty),
+            Routine::StorageIndex(ty) => self.storage_index_routine(&ty),
+            Routine::MappingSlot => self.mapping_slot_routine(),
+        }
+    }
+
+    /* ------------------------------ SCOPES ------------------------------ */
+
+    pub fn begin_scope(&mut self) {
+        self.frame.scopes.push(Scope {
+            height: self.ir.height,
+            names: Vec::new(),
+        });
+    }
+
+    pub fn end_scope(&mut self) {
+        let Some(scope) = self.frame.scopes.pop() else {
+            return;
+        };
+        for (span, previous) in scope.names.into_iter().rev() {
+            match previous {
+                Some(_position) => self.frame.locals.insert(span, _position),
+                None => self.frame.locals.remove(&span),
+            };
+        }
+        self.pop_to(scope.height);
+    }
+
+    pub fn declare_at(&mut self, span: Span, position: usize, location: Location) {
+        let previous = self.frame.locals.insert(span, position);
+        if location == Location::Storage {
+            self.frame.storage_locals.insert(span);
+        }
+        if let Some(_scope) = self.frame.scopes.last_mut() {
+            _scope.names.push((span, previous));
+        }
+    }
+
+    pub fn declare(&mut self, span: Span, location: Location) {
+        self.declare_at(span, self.ir.height, location);
+    }
+
+    /* ------------------------------ CONTRACT ------------------------------ */
+
+    /* THE DISPATCHER: THE FIRST FOUR BYTES OF CALLDATA PICK A PUBLIC FUNCTION, A GETTER OR A
+    `cron` ENTRY POINT. ANYTHING ELSE GOES TO `receive` OR `fallback` */
+    fn runtime(mut self) -> Option<(Bytecode, Program, String)> {
+        self.begin_entry();
+        self.exec(
+            Opcode::MStore,
+            [constant(FREE_MEMORY_POINTER), constant(MEMORY_START)],
+        );
+        let entries = self.entries();
+        let short = self.ir.new_block();
+        let mut targets = Vec::new();
+        if !entries.is_empty() {
+            self.ir
+                .branch(lt(env(Opcode::CallDataSize), constant(4u8)), short);
+            self.let_(shr(
+                constant(224u8),
+                apply(Opcode::CallDataLoad, [constant(0u8)]),
+            ));
+            for (selector, _) in &entries {
+                let target = self.ir.new_block();
+                self.ir.branch(eq(slot(1), constant(*selector)), target);
+                targets.push(target);
+            }
+            self.pop(1);
+        }
+        self.ir.start(short);
+        let special = |kind: FunctionKind| {
+            self.linearization(self.contract)
+                .into_iter()
+                .find_map(|id| self.find_special(id, kind))
+        };
+        let receive = special(FunctionKind::Receive);
+        let fallback = special(FunctionKind::Fallback);
+        let receive_block = self.ir.new_block();
+        if receive.is_some() {
+            self.ir
+                .branch(iszero(env(Opcode::CallDataSize)), receive_block);
+        }
+        match fallback {
+            Some(_fallback) => self.special_entry(_fallback),
+            None => self.exec(Opcode::Revert, [constant(0u8), constant(0u8)]),
+        }
+        if let Some(_receive) = receive {
+            self.ir.height = 0;
+            self.ir.start(receive_block);
+            self.special_entry(_receive);
+        }
+        for ((_, entry), target) in entries.into_iter().zip(targets) {
+            self.ir.height = 1;
+            self.ir.start(target);
+            self.pop(1);
+            match entry {
+                Entry::Function(declaration) => self.function_entry(declaration),
+                Entry::Getter(variable) => self.getter_entry(variable),
+                Entry::Cron(cron, job) => self.cron_entry(cron, job),
+            }
+        }
+        self.ir.end();
+        self.flush();
+        self.assemble()
+    }
+
+    /* THE CODE THAT RUNS FIRST, THE FUNCTION OF THE IR EVERYTHING ELSE IS CALLED FROM */
+    fn begin_entry(&mut self) {
+        let name = &self.definition(self.contract).name;
+        let entry = self.ir.add_function(name.name.clone(), name.span, 0, 0);
+        self.ir.span = name.span;
+        self.ir.begin(entry);
+    }
+
+    fn find_special(
+        &self,
+        (file_id, contract): ContractId,
+        kind: FunctionKind,
+    ) -> Option<Declaration> {
+        self.definition((file_id, contract))
+            .parts
+            .iter()
+            .position(|part| {
+                matches!(part, ContractPart::Function(_function)
+                    if _function.kind == kind && _function.body.is_some())
+            })
+            .map(|part| Declaration::Member {
+                file_id,
+                contract,
+                part,
+            })
+    }
+
+    /* WHAT THE DISPATCHER CAN JUMP TO, BY SELECTOR. AN OVERRIDE HIDES WHAT IT OVERRIDES */
+    fn entries(&self) -> Vec<(u32, Entry<'a>)> {
+        let context = self.context;
+        let mut entries: Vec<(u32, Entry)> = Vec::new();
+        let mut add = |signature: String, entry: Entry<'a>| {
+            let selector = u32::from_be_bytes(selector(&signature));
+            if !entries.iter().any(|(seen, _)| *seen == selector) {
+                entries.push((selector, entry));
+            }
+        };
+        for (file_id, contract) in self.linearization(self.contract) {
+            for (part, definition) in self
+                .definition((file_id, contract))
+                .parts
+                .iter()
+                .enumerate()
+            {
+                match definition {
+                    ContractPart::Function(function)
+                        if function.kind == FunctionKind::Function
+                            && matches!(
+                                function.visibility,
+                                Some(Visibility::Public | Visibility::External)
+                            )
+                            && function.body.is_some() =>
+                    {
+                        let Some(_name) = &function.name else {
+                            continue;
+                        };
+                        add(
+                            signature(
+                                &_name.name,
+                                &function.parameters,
+                                context.graph,
+                                context.types,
+                            ),
+                            Entry::Function(Declaration::Member {
+                                file_id,
+                                contract,
+                                part,
+                            }),
+                        );
+                    }
+                    ContractPart::StateVariable(variable)
+                        if variable.visibility == Some(Visibility::Public) =>
+                    {
+                        let (parameters, _) = getter(&self.type_name(&variable.ty.span));
+                        let parameters: Vec<String> = parameters
+                            .iter()
+                            .map(|ty| {
+                                crate::mods::functions::controllers::abi::canonical_type(
+                                    ty,
+                                    context.graph,
+                                    context.types,
+                                )
+                            })
+                            .collect();
+                        add(
+                            format!("{}({})", variable.name.name, parameters.join(",")),
+                            Entry::Getter(variable),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for job in context.cron_jobs.get(&self.contract).into_iter().flatten() {
+            let cron = self
+                .definition(job.contract)
+                .parts
+                .iter()
+                .find_map(|part| match part {
+                    ContractPart::Cron(_cron) if _cron.span == job.span => Some(_cron),
+                    _ => None,
+                });
+            if let Some(_cron) = cron {
+                add(job.entry_point(), Entry::Cron(_cron, job));
+            }
+        }
+        entries
+    }
+
+    /* DECODES THE CALLDATA, CALLS THE FUNCTION AND RETURNS WHAT IT RETURNS, ABI ENCODED */
+    fn function_entry(&mut self, declaration: Declaration) {
+        let Some(ContractPart::Function(function)) = self.part(declaration) else {
+            return;
+        };
+        self.enter(Frame {
+            span: function.span,
+            ..Frame::default()
+        });
+        if function.mutability != Some(StateMutability::Payable) {
+            self.revert_if(env(Opcode::CallValue));
+        }
+        let parameters = self.parameter_types(&function.parameters);
+        let returns = self.parameter_types(&function.returns);
+        let arguments = self.decode_calldata(&parameters);
+        let function = self.function_id(declaration);
+        self.call_function(function, &arguments);
+        let height = self.ir.height;
+        let positions: Vec<usize> = (height + 1 - returns.len()..=height).collect();
+        self.return_values(&returns, &positions);
+    }
+
+    /* `receive` AND `fallback` TAKE NOTHING AND RETURN NOTHING, EXCEPT A `fallback` THAT TAKES
+    THE CALLDATA AS `bytes` AND RETURNS `bytes` AS THE RAW RETURN DATA, AS THE TYPE CHECKER
+    ENFORCES */
+    fn special_entry(&mut self, declaration: Declaration) {
+        let Some(ContractPart::Function(function)) = self.part(declaration) else {
+            return;
+        };
+        self.enter(Frame {
+            span: function.span,
+            ..Frame::default()
+        });
+        let parameters = self.parameter_types(&function.parameters);
+        let returns = self.parameter_types(&function.returns);
+        let raw = !parameters.is_empty();
+        if function.mutability != Some(StateMutability::Payable) {
+            self.revert_if(env(Opcode::CallValue));
+        }
+        let mut arguments = Vec::new();
+        if raw {
+            let size = self.let_(env(Opcode::CallDataSize));
+            let input = self.allocate(add(slot(size), constant(32u8)));
+            self.exec(Opcode::MStore, [slot(input), slot(size)]);
+            self.exec(
+                Opcode::CallDataCopy,
+                [add(slot(input), constant(32u8)), constant(0u8), slot(size)],
+            );
+            arguments.push(slot(input));
+        }
+        let function = self.function_id(declaration);
+        self.call_function(function, &arguments);
+        if returns.is_empty() {
+            return self.exec(Opcode::Stop, []);
+        }
+        let output = self.ir.height;
+        self.exec(
+            Opcode::Return,
+            [add(slot(output), constant(32u8)), mload(slot(output))],
+        );
+    }
+
+    /* THE CALLDATA AFTER THE SELECTOR AS `types`, CHECKED, EACH AS A WORD ON THE STACK */
+    fn decode_calldata(&mut self, types: &[Type]) -> Vec<Word> {
+        let size: usize = types.iter().map(|ty| self.head_size(ty)).sum();
+        self.revert_if(apply(
+            Opcode::SLt,
+            [
+                sub(env(Opcode::CallDataSize), constant(4u8)),
+                constant(size),
+            ],
+        ));
+        let mut arguments = Vec::new();
+        let mut offset = 4usize;
+        for ty in types {
+            let position = self.decode(
+                ty,
+                true,
+                constant(offset),
+                constant(4u8),
+                env(Opcode::CallDataSize),
+            );
+            arguments.push(slot(position));
+            offset += self.head_size(ty);
+        }
+        arguments
+    }
+
+    /* THE GETTER OF A PUBLIC STATE VARIABLE: ONE ARGUMENT PER MAPPING KEY OR ARRAY INDEX */
+    fn getter_entry(&mut self, variable: &'a VariableDefinition) {
+        self.enter(Frame {
+            span: variable.span,
+            ..Frame::default()
+        });
+        self.revert_if(env(Opcode::CallValue));
+        let ty = self.type_name(&variable.ty.span);
+        if variable
+            .mutability
+            .is_some_and(|mutability| mutability != VariableMutability::Mutable)
+        {
+            self.state_variable(variable, &ty);
+            return self.return_values(&[ty], &[1]);
+        }
+        let (parameters, value) = getter(&ty);
+        let keys = self.decode_calldata(&parameters);
+        let Some((start, offset)) = self.storage_position(variable) else {
+            return;
+        };
+        let base = self.ir.height;
+        let current = self.let_(constant(start));
+        let offset = self.let_(constant(offset));
+        let mut ty = ty;
+        for key in keys {
+            ty = match &ty {
+                Type::Mapping {
+                    key: key_type,
+                    value,
+                } => {
+                    self.mapping_slot(key_type, key, slot(current));
+                    self.store_top(current);
+                    self.assign(offset, constant(0u8));
+                    (**value).clone()
+                }
+                Type::Array { base, .. } => {
+                    let base = (**base).clone();
+                    self.call_routine(
+                        Routine::StorageIndex(ty.clone()),
+                        vec![slot(current), key],
+                        2,
+                    );
+                    self.store_top(offset);
+                    self.store_top(current);
+                    base
+                }
+                _ => ty.clone(),
+            };
+        }
+        let (types, positions) = match &value {
+            Type::Struct { .. } => {
+                let mut types = Vec::new();
+                let mut positions = Vec::new();
+                let members = self.storage_type(&value).members.unwrap_or_default();
+                for (member, member_type) in members.iter().zip(self.struct_fields(&value)) {
+                    if matches!(member_type, Type::Mapping { .. } | Type::Array { .. }) {
+                        continue;
+                    }
+                    let member_slot = add(slot(current), constant(member.slot.clone()));
+                    positions.push(self.storage_load(
+                        &member_type,
+                        member_slot,
+                        constant(member.offset),
+                    ));
+                    types.push(member_type);
+                }
+                (types, positions)
+            }
+            _ => (
+                vec![value.clone()],
+                vec![self.storage_load(&value, slot(current), slot(offset))],
+            ),
+        };
+        self.return_values(&types, &positions);
+        self.ir.height = base;
+    }
+
+    /* A `cron` BLOCK, RUN BY THE SCHEDULER THROUGH `__cron_<i>()`, AT MOST `max_runs` TIMES.
+    ANYONE MAY CALL IT, BUT ONLY IN A MINUTE THE SCHEDULE SELECTS AND ONCE IN THAT MINUTE. ITS RUN
+    COUNT AND LAST RUN ARE KEYED BY THE ENTRY POINT, WHICH NO OTHER BLOCK, OWN OR INHERITED, SHARES */
+    fn cron_entry(&mut self, cron: &'a CronDefinition, job: &CronJob) {
+        self.enter(Frame {
+            span: cron.span,
+            contract: job.contract,
+            ..Frame::default()
+        });
+        self.revert_if(env(Opcode::CallValue));
+        self.cron_schedule(job);
+        if job.max_runs > 0 {
+            let counter = BigUint::from_bytes_be(&keccak256(job.entry_point().as_bytes()));
+            let runs = self.let_(sload(constant(counter.clone())));
+            let done = self.ir.new_block();
+            self.ir.branch(lt(slot(runs), constant(job.max_runs)), done);
+            self.revert_with_message("cron job has already run the maximum number of times");
+            self.ir.start(done);
+            self.exec(
+                Opcode::SStore,
+                [constant(counter), add(slot(runs), constant(1u8))],
+            );
+            self.pop(1);
+        }
+        let end = self.ir.new_block();
+        self.frame.exits.push(Exit {
+            label: end,
+            height: self.ir.height,
+        });
+        self.block(&cron.body);
+        self.ir.start(end);
+        self.exec(Opcode::Stop, []);
+    }
+
+    /* REVERTS UNLESS THE BLOCK TIMESTAMP IS IN A MINUTE `job` IS SCHEDULED FOR AND IT HAS NOT
+    RUN IN THAT MINUTE YET. THE DATE IS THE PROLEPTIC GREGORIAN ONE OF THE DAYS SINCE THE EPOCH */
+    fn cron_schedule(&mut self, job: &CronJob) {
+        let base = self.ir.height;
+        let now = self.let_(div(env(Opcode::Timestamp), constant(60u8)));
+        let days = self.let_(div(slot(now), constant(1440u16)));
+        let era_day = self.let_(rem(add(slot(days), constant(719_468u32)), 146_097u32));
+        let year = self.let_(div(
+            sub(
+                add(
+                    sub(slot(era_day), div(slot(era_day), constant(1460u16))),
+                    div(slot(era_day), constant(36_524u16)),
+                ),
+                div(slot(era_day), constant(146_096u32)),
+            ),
+            constant(365u16),
+        ));
+        let year_day = self.let_(sub(
+            slot(era_day),
+            sub(
+                add(
+                    mul(constant(365u16), slot(year)),
+                    div(slot(year), constant(4u8)),
+                ),
+                div(slot(year), constant(100u8)),
+            ),
+        ));
+        /* MONTHS COUNTED FROM MARCH, SO THAT FEBRUARY IS LAST */
+        let march_month = self.let_(div(
+            add(mul(constant(5u8), slot(year_day)), constant(2u8)),
+            constant(153u8),
+        ));
+        let day_of_month = add(
+            sub(
+                slot(year_day),
+                div(
+                    add(mul(constant(153u8), slot(march_month)), constant(2u8)),
+                    constant(5u8),
+                ),
+            ),
+            constant(1u8),
+        );
+        let schedule = &job.schedule;
+        /* AS IN VIXIE CRON, EITHER DAY FIELD SELECTS THE DAY IF BOTH ARE RESTRICTED */
+        let day = match (
+            selected(&schedule.day_of_month, day_of_month),
+            selected(
+                &schedule.day_of_week,
+                rem(add(slot(days), constant(4u8)), 7u8),
+            ),
+        ) {
+            (Some(_month_day), Some(_week_day)) => Some(or(_month_day, _week_day)),
+            (_month_day, _week_day) => _month_day.or(_week_day),
+        };
+        let fields = [
+            selected(&schedule.minute, rem(slot(now), 60u8)),
+            selected(&schedule.hour, rem(div(slot(now), constant(60u8)), 24u8)),
+            day,
+            selected(
+                &schedule.month,
+                add(
+                    rem(add(slot(march_month), constant(2u8)), 12u8),
+                    constant(1u8),
+                ),
+            ),
+        ];
+        if let Some(_matches) = fields.into_iter().flatten().reduce(and) {
+            let scheduled = self.ir.new_block();
+            self.ir.branch(_matches, scheduled);
+            self.revert_with_message("cron job is not scheduled to run now");
+            self.ir.start(scheduled);
+        }
+
+        /* THE MINUTE OF THE LAST RUN, PLUS ONE SO THAT NO RUN IS 0 */
+        let last =
+            BigUint::from_bytes_be(&keccak256(format!("{}.last", job.entry_point()).as_bytes()));
+        let fresh = self.ir.new_block();
+        self.ir.branch(
+            lt(sload(constant(last.clone())), add(slot(now), constant(1u8))),
+            fresh,
+        );
+        self.revert_with_message("cron job has already run this minute");
+        self.ir.start(fresh);
+        self.exec(
+            Opcode::SStore,
+            [constant(last), add(slot(now), constant(1u8))],
+        );
+        self.pop_to(base);
+    }
+
+    /* THE CONSTRUCTOR: DECODES THE ARGUMENTS APPENDED TO THE CODE, RUNS THE INITIALIZERS AND
+    CONSTRUCTORS OF EVERY CONTRACT MOST BASE FIRST, AND RETURNS THE RUNTIME CODE WITH THE
+    IMMUTABLES FILLED IN */
+    fn creation(mut self, runtime: &Bytecode) -> Option<(Bytecode, Program, String)> {
+        self.begin_entry();
+        let immutables = self.immutables().len();
+        self.exec(
+            Opcode::MStore,
+            [
+                constant(FREE_MEMORY_POINTER),
+                constant(MEMORY_START as usize + 32 * immutables),
+            ],
+        );
+        let constructors: HashMap<ContractId, &'a FunctionDefinition> = self
+            .linearization(self.contract)
+            .into_iter()
+            .filter_map(|id| {
+                self.definition(id)
+                    .parts
+                    .iter()
+                    .find_map(|part| match part {
+                        ContractPart::Function(_function)
+                            if _function.kind == FunctionKind::Constructor =>
+                        {
+                            Some(_function)
+                        }
+                        _ => None,
+                    })
+                    .map(|constructor| (id, constructor))
+            })
+            .collect();
+        let own = constructors.get(&self.contract).copied();
+        self.enter(Frame {
+            span: own.map_or(self.definition(self.contract).span, |constructor| {
+                constructor.span
+            }),
+            contract: self.contract,
+            ..Frame::default()
+        });
+        if own.and_then(|constructor| constructor.mutability) != Some(StateMutability::Payable) {
+            self.revert_if(env(Opcode::CallValue));
+        }
+
+        /* THE ARGUMENTS OF EACH CONSTRUCTOR, THE MOST DERIVED ONE'S FROM THE END OF THE CODE AND
+        THE OTHERS FROM WHEREVER THEY ARE WRITTEN, WHICH MAY USE THE FORMER */
+        self.begin_scope();
+        let mut arguments: HashMap<ContractId, Vec<usize>> = HashMap::new();
+        if let Some(_own) = own.filter(|constructor| !constructor.parameters.is_empty()) {
+            let size = self.let_(sub(env(Opcode::CodeSize), Word::Link(Link::ProgramSize)));
+            let data = self.allocate(slot(size));
+            self.exec(
+                Opcode::CodeCopy,
+                [slot(data), Word::Link(Link::ProgramSize), slot(size)],
+            );
+            let mut positions = Vec::new();
+            let mut offset = 0usize;
+            for parameter in &_own.parameters {
+                let ty = self.type_name(&parameter.ty.span);
+                positions.push(self.decode(
+                    &ty,
+                    false,
+                    add(slot(data), constant(offset)),
+                    slot(data),
+                    add(slot(data), slot(size)),
+                ));
+                offset += self.head_size(&ty);
+            }
+            self.revert_if(gt(constant(offset), slot(size)));
+            arguments.insert(self.contract, positions);
+        }
+        let written = self
+            .context
+            .bindings
+            .constructor_arguments
+            .get(&self.contract)
+            .cloned()
+            .unwrap_or_default();
+        for id in self.linearization(self.contract) {
+            if let (Some(_constructor), Some(_positions)) =
+                (constructors.get(&id), arguments.get(&id))
+            {
+                for (parameter, position) in _constructor.parameters.iter().zip(_positions) {
+                    if let Some(_name) = &parameter.name {
+                        self.declare_at(_name.span, *position, Location::Memory);
+                    }
+                }
+            }
+            self.frame.contract = id;
+            for (base, place) in &written {
+                let expressions = match *place {
+                    BaseArguments::Specifier { contract, index } if contract == id => self
+                        .definition(contract)
+                        .bases
+                        .get(index)
+                        .and_then(|specifier| specifier.arguments.as_deref()),
+                    BaseArguments::Constructor {
+                        contract,
+                        part,
+                        index,
+                    } if contract == id => match self.definition(contract).parts.get(part) {
+                        Some(ContractPart::Function(_constructor)) => _constructor
+                            .modifiers
+                            .get(index)
+                            .and_then(|invocation| invocation.arguments.as_deref()),
+                        _ => None,
+                    },
+                    _ => continue,
+                };
+                let Some(_constructor) = constructors.get(base) else {
+                    continue;
+                };
+                let mut positions = Vec::new();
+                for (parameter, argument) in _constructor
+                    .parameters
+                    .iter()
+                    .zip(expressions.unwrap_or_default())
+                {
+                    let ty = self.type_name(&parameter.ty.span);
+                    self.expression_to(argument, &ty, Location::Memory);
+                    positions.push(self.ir.height);
+                }
+                arguments.insert(*base, positions);
+            }
+        }
+
+        for id in self.context.bindings.inheritance_order(self.contract) {
+            self.frame.contract = id;
+            for part in &self.definition(id).parts {
+                let ContractPart::StateVariable(variable) = part else {
+                    continue;
+                };
+                let Some(_initializer) = &variable.initializer else {
+                    continue;
+                };
+                if variable.mutability == Some(VariableMutability::Constant) {
+                    continue;
+                }
+                let ty = self.type_name(&variable.ty.span);
+                let location = self.location(_initializer);
+                self.expression_to(_initializer, &ty, location);
+                if let Some(_lvalue) = self.state_variable_lvalue(variable, &ty) {
+                    self.store(&_lvalue, location);
+                }
+            }
+            if let Some(_constructor) = constructors.get(&id) {
+                self.frame.span = _constructor.span;
+                self.ir.span = _constructor.span;
+                self.function_body(_constructor, 0);
+            }
+        }
+        self.end_scope();
+
+        let section = self.ir.add_data(runtime.code.clone());
+        let code = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+        self.exec(
+            Opcode::CodeCopy,
+            [
+                slot(code),
+                Word::Link(Link::DataOffset(section)),
+                Word::Link(Link::DataSize(section)),
+            ],
+        );
+        for (id, offsets) in &runtime.immutables {
+            for offset in offsets {
+                self.exec(
+                    Opcode::MStore,
+                    [
+                        add(slot(code), constant(offset + 1)),
+                        mload(constant(MEMORY_START as usize + 32 * id)),
+                    ],
+                );
+            }
+        }
+        self.exec(
+            Opcode::Return,
+            [slot(code), Word::Link(Link::DataSize(section))],
+        );
+        self.ir.end();
+        self.flush();
+        self.assemble()
+    }
+
+    /* CHECKS THE IR, OPTIMIZES IT WHEN ASKED TO AND LOWERS IT TO BYTECODE. THE STRING HAS THE
+    IR BEFORE AND AFTER EVERY OPTIMIZER PASS, WHEN `--emit=passes` ASKS FOR IT */
+    fn assemble(mut self) -> Option<(Bytecode, Program, String)> {
+        if self.failed {
+            return None;
+        }
+        let settings = self.context.settings;
+        let push0 = settings.evm_version >= EvmVersion::Shanghai;
+        let mut passes = String::new();
+        let checked = verify(&self.ir).and_then(|()| match settings.optimize {
+            true => optimize(
+                &mut self.ir,
+                &settings.disabled_passes,
+                push0,
+                settings.emit.contains(&Emit::Passes).then_some(&mut passes),
+            ),
+            false => Ok(()),
+        });
+        if let Err(_message) = checked {
+            let span = self.definition(self.contract).name.span;
+            self.diagnostics.push(
+                CompilerError::InternalError(format!("invalid IR, {_message}"))
+                    .diagnostic()
+                    .with_label(span, "while generating the code of this contract"),
+            );
+            return None;
+        }
+        let lowered = lower(&self.ir, push0);
+        for span in &lowered.too_deep {
+            self.too_deep(*span);
+        }
+        if self.failed {
+            return None;
+        }
+        match lowered.assembly.assemble() {
+            Ok(_bytecode) => Some((_bytecode, self.ir, passes)),
+            Err(message) => {
+                let span = self.definition(self.contract).name.span;
+                self.diagnostics.push(
+                    CompilerError::CodegenError(CodegenError::CodeTooLarge(message))
+                        .diagnostic()
+                        .with_label(span, "this contract is too large"),
+                );
+                None
+            }
+        }
+    }
+
+    /* ------------------------------ FUNCTIONS ------------------------------ */
+
+    /* AN INTERNAL FUNCTION: CALLED WITH THE RETURN ADDRESS AND THE ARGUMENTS ON THE STACK, IT
+    LEAVES ITS RETURN VALUES IN THEIR PLACE */
+    fn function(&mut self, declaration: Declaration) {
+        let Declaration::Member {
+            file_id, contract, ..
+        } = declaration
+        else {
+            return;
+        };
+        let Some(ContractPart::Function(function)) = self.part(declaration) else {
+            return;
+        };
+        self.enter(Frame {
+            span: function.span,
+            contract: (file_id, contract),
+            ..Frame::default()
+        });
+        self.begin_scope();
+        for (index, parameter) in function.parameters.iter().enumerate() {
+            if let Some(_name) = &parameter.name {
+                self.declare_at(_name.span, index + 1, Self::parameter_location(parameter));
+            }
+        }
+        for parameter in &function.returns {
+            let ty = self.type_name(&parameter.ty.span);
+            let location = Self::parameter_location(parameter);
+            self.default_value(&ty, location);
+            if let Some(_name) = &parameter.name {
+                self.declare(_name.span, location);
+            }
+            self.frame.returns.push((self.ir.height, ty, location));
+        }
+        self.function_body(function, 0);
+        let outputs: Vec<usize> = self
+            .frame
+            .returns
+            .iter()
+            .map(|(position, ..)| *position)
+            .collect();
+        self.leave(&outputs);
+    }
+
+    /* THE MODIFIERS OF `function` FROM `index` ON, EACH INLINED AROUND THE NEXT, AND THEN ITS
+    BODY. A BASE CONSTRUCTOR CALL IN THE LIST OF A CONSTRUCTOR IS SKIPPED */
+    pub fn function_body(&mut self, function: &'a FunctionDefinition, index: usize) {
+        let Some(invocation) = function.modifiers.get(index) else {
+            let end = self.ir.new_block();
+            self.frame.exits.push(Exit {
+                label: end,
+                height: self.ir.height,
+            });
+            if let Some(_body) = &function.body {
+                self.block(_body);
+            }
+            self.ir.start(end);
+            self.frame.exits.pop();
+            return;
+        };
+        let name = invocation.name.last().unwrap();
+        let modifier = self
+            .declarations(&name.span)
+            .first()
+            .and_then(|declaration| self.part(*declaration));
+        let Some(ContractPart::Modifier(modifier)) = modifier else {
+            return self.function_body(function, index + 1);
+        };
+        let modifier = self.virtual_modifier(modifier);
+        self.begin_scope();
+        let arguments = invocation.arguments.as_deref().unwrap_or_default();
+        for (parameter, argument) in modifier.parameters.iter().zip(arguments) {
+            let ty = self.type_name(&parameter.ty.span);
+            self.expression_to(argument, &ty, Location::Memory);
+            if let Some(_name) = &parameter.name {
+                self.declare(_name.span, Location::Memory);
+            }
+        }
+        let end = self.ir.new_block();
+        self.frame.exits.push(Exit {
+            label: end,
+            height: self.ir.height,
+        });
+        self.frame.placeholders.push((function, index + 1));
+        let loops = std::mem::take(&mut self.frame.loops);
+        if let Some(_body) = &modifier.body {
+            self.block(_body);
+        }
+        self.frame.loops = loops;
+        self.frame.placeholders.pop();
+        self.ir.start(end);
+        self.frame.exits.pop();
+        self.end_scope();
+    }
+
+    /* ------------------------------ STATEMENTS ------------------------------ */
+
+    pub fn block(&mut self, block: &'a Block) {
+        let unchecked = self.frame.unchecked;
+        self.frame.unchecked |= block.unchecked;
+        self.begin_scope();
+        for statement in &block.statements {
+            self.statement(statement);
+        }
+        self.end_scope();
+        self.frame.unchecked = unchecked;
+    }
+
+    /* A STATEMENT THAT IS NOT A BLOCK STILL GETS A SCOPE, SO A DECLARATION IN A LOOP BODY DOES
+    NOT PILE UP ON THE STACK */
+    fn scoped(&mut self, statement: &'a Statement) {
+        self.begin_scope();
+        self.statement(statement);
+        self.end_scope();
+    }
+
+    fn statement(&mut self, statement: &'a Statement) {
+        match &statement.kind {
+            StatementKind::Block(block) => self.block(block),
+            StatementKind::VariableDeclaration {
+                declarations,
+                initializer,
+            } => self.variable_declaration(declarations, initializer.as_ref()),
+            StatementKind::Expression(expression) => self.expression_statement(expression),
+            StatementKind::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                self.expression_to(condition, &Type::Bool, Location::Memory);
+                let other = self.ir.new_block();
+                self.op(Opcode::IsZero);
+                self.ir.jump_if(other);
+                self.scoped(then);
+                match otherwise {
+                    Some(_otherwise) => {
+                        let end = self.ir.new_block();
+                        self.ir.jump(end);
+                        self.ir.start(other);
+                        self.scoped(_otherwise);
+                        self.ir.start(end);
+                    }
+                    None => self.ir.start(other),
+                }
+            }
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.begin_scope();
+                if let Some(_init) = init {
+                    self.statement(_init);
+                }
+                let start = self.ir.new_block();
+                let next = self.ir.new_block();
+                let end = self.ir.new_block();
+                self.ir.start(start);
+                if let Some(_condition) = condition {
+                    self.expression_to(_condition, &Type::Bool, Location::Memory);
+                    self.op(Opcode::IsZero);
+                    self.ir.jump_if(end);
+                }
+                self.loop_body(body, next, end);
+                self.ir.start(next);
+                if let Some(_update) = update {
+                    self.expression_statement(_update);
+                }
+                self.ir.jump(start);
+                self.ir.start(end);
+                self.end_scope();
+            }
+            StatementKind::While { condition, body } => {
+                let start = self.ir.new_block();
+                let end = self.ir.new_block();
+                self.ir.start(start);
+                self.expression_to(condition, &Type::Bool, Location::Memory);
+                self.op(Opcode::IsZero);
+                self.ir.jump_if(end);
+                self.loop_body(body, start, end);
+                self.ir.jump(start);
+                self.ir.start(end);
+            }
+            StatementKind::DoWhile { body, condition } => {
+                let start = self.ir.new_block();
+                let next = self.ir.new_block();
+                let end = self.ir.new_block();
+                self.ir.start(start);
+                self.loop_body(body, next, end);
+                self.ir.start(next);
+                self.expression_to(condition, &Type::Bool, Location::Memory);
+                self.ir.jump_if(start);
+                self.ir.start(end);
+            }
+            StatementKind::Return(expression) => {
+                if let Some(_expression) = expression {
+                    self.return_statement(_expression);
+                }
+                if let Some(_exit) = self.frame.exits.last().copied() {
+                    self.jump_out(_exit);
+                }
+            }
+            StatementKind::Emit(expression) => self.emit(expression),
+            StatementKind::Revert { error, arguments } => {
+                self.revert_statement(statement.span, error.as_deref(), arguments)
+            }
+            StatementKind::Assembly { body, .. } => self.assembly_block(body),
+            StatementKind::Break => {
+                if let Some(_loop) = self.frame.loops.last().copied() {
+                    self.jump_out(_loop.break_to);
+                }
+            }
+            StatementKind::Continue => {
+                if let Some(_loop) = self.frame.loops.last().copied() {
+                    self.jump_out(_loop.continue_to);
+                }
+            }
+            StatementKind::Placeholder => {
+                let Some((function, next)) = self.frame.placeholders.pop() else {
+                    return;
+                };
+                let loops = std::mem::take(&mut self.frame.loops);
+                let unchecked = std::mem::replace(&mut self.frame.unchecked, false);
+                self.function_body(function, next);
+                self.frame.unchecked = unchecked;
+                self.frame.loops = loops;
+                self.frame.placeholders.push((function, next));
+            }
+        }
+    }
+
+    fn loop_body(&mut self, body: &'a Statement, next: BlockId, end: BlockId) {
+        let height = self.ir.height;
+        self.frame.loops.push(Loop {
+            break_to: Exit { label: end, height },
+            continue_to: Exit {
+                label: next,
+                height,
+            },
+        });
+        self.scoped(body);
+        self.frame.loops.pop();
+    }
+
+    /* DROPS WHAT WAS PUSHED SINCE `exit` AND JUMPS THERE. THE CODE THAT FOLLOWS IS ONLY REACHED
+    BY OTHER PATHS, WITH THE STACK AS BEFORE */
+    pub fn jump_out(&mut self, exit: Exit) {
+        let height = self.ir.height;
+        self.pop_to(exit.height);
+        self.ir.jump(exit.label);
+        self.ir.height = height;
+    }
+
+    fn variable_declaration(
+        &mut self,
+        declarations: &'a [Option<crate::mods::types::ast::VariableDeclaration>],
+        initializer: Option<&'a Expression>,
+    ) {
+        let location = |declaration: &crate::mods::types::ast::VariableDeclaration| {
+            match declaration.storage {
+                Some(StorageLocation::Storage) => Location::Storage,
+                _ => Location::Memory,
+            }
+        };
+        let declared_type =
+            |codegen: &Self, declaration: &crate::mods::types::ast::VariableDeclaration| {
+                codegen
+                    .types()
+                    .declarations
+                    .get(&declaration.name.span)
+                    .cloned()
+                    .unwrap_or(Type::Unknown)
+            };
+        match (declarations, initializer) {
+            ([Some(declaration)], initializer) => {
+                let ty = declared_type(self, declaration);
+                match initializer {
+                    Some(_initializer) => {
+                        self.expression_to(_initializer, &ty, location(declaration))
+                    }
+                    None => self.default_value(&ty, location(declaration)),
+                }
+                self.declare(declaration.name.span, location(declaration));
+            }
+            (_, Some(_initializer)) => {
+                let start = self.ir.height;
+                match &_initializer.kind {
+                    ExpressionKind::Tuple(components) if components.len() == declarations.len() => {
+                        for (component, declaration) in components.iter().zip(declarations) {
+                            let Some(_component) = component else {
+                                continue;
+                            };
+                            match declaration {
+                                Some(_declaration) => {
+                                    let ty = declared_type(self, _declaration);
+                                    self.expression_to(_component, &ty, location(_declaration));
+                                }
+                                None => {
+                                    let ty = self.expression_type(_component).mobile();
+                                    self.expression_to(_component, &ty, Location::Memory);
+                                }
+                            }
+                        }
+                    }
+                    _ => self.expression(_initializer),
+                }
+                for (index, declaration) in declarations.iter().enumerate() {
+                    if let Some(_declaration) = declaration {
+                        self.declare_at(
+                            _declaration.name.span,
+                            start + index + 1,
+                            location(_declaration),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn expression_statement(&mut self, expression: &'a Expression) {
+        let height = self.ir.height;
+        match &expression.kind {
+            ExpressionKind::Assignment {
+                operator,
+                left,
+                right,
+            } => self.assignment(*operator, left, right, false),
+            ExpressionKind::Unary { operator, operand } => self.unary(*operator, operand, false),
+            _ => self.expression(expression),
+        }
+        self.pop_to(height);
+    }
+
+    fn return_statement(&mut self, expression: &'a Expression) {
+        let returns = self.frame.returns.clone();
+        match (returns.as_slice(), &expression.kind) {
+            ([(position, ty, location)], _) => {
+                self.expression_to(expression, ty, *location);
+                self.store_top(*position);
+            }
+            (_, ExpressionKind::Tuple(components)) if components.len() == returns.len() => {
+                for ((_, ty, location), component) in returns.iter().zip(components) {
+                    if let Some(_component) = component {
+                        self.expression_to(_component, ty, *location);
+                    }
+                }
+                for (position, ..) in returns.iter().rev() {
+                    self.store_top(*position);
+                }
+            }
+            _ => {
+                self.expression(expression);
+                for (position, ..) in returns.iter().rev() {
+                    self.store_top(*position);
+                }
+            }
+        }
+    }
+
+    /* THE NAMES AN EVENT, ERROR OR FUNCTION IS CALLED THROUGH MAY MEAN */
+    pub fn callee_declarations(&self, callee: &Expression) -> &'a [Declaration] {
+        match &callee.kind {
+            ExpressionKind::Variable(identifier) => self.declarations(&identifier.span),
+            ExpressionKind::MemberAccess { member, .. } => self.declarations(&member.span),
+            _ => &[],
+        }
+    }
+
+    /* TOPIC 0 IS THE HASH OF THE SIGNATURE UNLESS THE EVENT IS ANONYMOUS, THEN ONE TOPIC PER
+    INDEXED ARGUMENT, `string` AND `bytes` HASHED. THE OTHER ARGUMENTS ARE THE DATA */
+    fn emit(&mut self, expression: &'a Expression) {
+        let ExpressionKind::FunctionCall { callee, arguments } = &expression.kind else {
+            return;
+        };
+        let index = self
+            .types()
+            .overloads
+            .get(&expression.span)
+            .copied()
+            .unwrap_or_default();
+        let event = self
+            .callee_declarations(callee)
+            .get(index)
+            .and_then(|declaration| self.part(*declaration));
+        let Some(ContractPart::Event(event)) = event else {
+            return self.unsupported(expression.span, "emitting this expression");
+        };
+        let height = self.ir.height;
+        let types = self.parameter_types(&event.parameters);
+        let names = Self::parameter_names(&event.parameters);
+        let mut topics = Vec::new();
+        if !event.anonymous {
+            let signature = signature(
+                &event.name.name,
+                &event.parameters,
+                self.graph(),
+                self.types(),
+            );
+            topics.push(constant(BigUint::from_bytes_be(&keccak256(
+                signature.as_bytes(),
+            ))));
+        }
+        let mut data_types = Vec::new();
+        let mut data = Vec::new();
+        for ((argument, parameter), ty) in self
+            .ordered_arguments(arguments, &names)
+            .into_iter()
+            .zip(&event.parameters)
+            .zip(types)
+        {
+            self.expression_to(argument, &ty, Location::Memory);
+            let position = self.ir.height;
+            if !parameter.indexed {
+                data_types.push(ty);
+                data.push(position);
+            } else if ty.is_value_type() {
+                topics.push(slot(position));
+            } else if matches!(ty, Type::String | Type::Bytes) {
+                let hash = self.let_(apply(
+                    Opcode::Keccak256,
+                    [add(slot(position), constant(32u8)), mload(slot(position))],
+                ));
+                topics.push(slot(hash));
+            } else {
+                self.unsupported(argument.span, "indexed event arguments of this type");
+            }
+        }
+        let start = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+        let end = self.encode_tuple(&data_types, &data, slot(start));
+        let count = topics.len() as u8;
+        let mut arguments = vec![slot(start), sub(slot(end), slot(start))];
+        arguments.extend(topics);
+        self.exec(Opcode::Log(count), arguments);
+        self.pop_to(height);
+    }
+
+    fn revert_statement(
+        &mut self,
+        span: Span,
+        error: Option<&'a [crate::mods::types::ast::Identifier]>,
+        arguments: &'a CallArguments,
+    ) {
+        let height = self.ir.height;
+        let Some(_error) = error else {
+            match self.ordered_arguments(arguments, &[]).as_slice() {
+                [message] => {
+                    self.expression_to(message, &Type::String, Location::Memory);
+                    self.revert_with_reason(self.ir.height);
+                }
+                _ => self.exec(Opcode::Revert, [constant(0u8), constant(0u8)]),
+            }
+            self.ir.height = height;
+            return;
+        };
+        let (name, parameters) = match self.declarations(&_error.last().unwrap().span).first() {
+            Some(Declaration::Error { file_id, index }) => {
+                let error = &self.graph().units[*file_id].ast.custom_errors[*index];
+                (&error.name.name, &error.parameters)
+            }
+            Some(declaration) => match self.part(*declaration) {
+                Some(ContractPart::Error(_error)) => (&_error.name.name, &_error.parameters),
+                _ => return self.unsupported(span, "reverting with this error"),
+            },
+            None => return self.unsupported(span, "reverting with this error"),
+        };
+        let types = self.parameter_types(parameters);
+        let mut positions = Vec::new();
+        for (argument, ty) in self
+            .ordered_arguments(arguments, &Self::parameter_names(parameters))
+            .into_iter()
+            .zip(&types)
+        {
+            self.expression_to(argument, ty, Location::Memory);
+            positions.push(self.ir.height);
+        }
+        let signature = signature(name, parameters, self.graph(), self.types());
+        self.revert_with_error(selector(&signature), &types, &positions);
+        self.ir.height = height;
+    }
+}
+
+/* WHAT THE DISPATCHER CAN CALL */
+enum Entry<'a> {
+    Function(Declaration),
+    Getter(&'a VariableDefinition),
+    Cron(&'a CronDefinition, &'a CronJob),
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use crate::mods::{
+    functions::controllers::{
+        abi::contract_abi,
+        codegen::{generate_bytecode, BytecodeCache, CodegenContext},
+        cron_checker::check_cron_jobs,
+        gasless_checker::{check_gasless, sponsored_functions},
+        import_resolver::resolve_imports,
+        inheritance_checker::{check_inheritance, linearize_contracts},
+        name_resolver::resolve_names,
+        storage_layout::storage_layout,
+        type_checker::check_types,
+        upgrade_checker::check_upgradable,
+    },
+    types::{
+        ast::ContractKind,
+        bindings::Bindings,
+        compilation::{Artifact, CompilationOutput},
+        diagnostics::Diagnostics,
+        semantic_types::TypeTable,
+        settings::Settings,
+        source_map::SourceMap,
+    },
+};
+
+/* COMPILES IN-MEMORY SOURCES. EVERY FILE IN `sources` IS AN ENTRY, AND AN IMPORT THAT NAMES
+NONE OF THEM IS READ FROM DISK UNDER THE BASE AND INCLUDE PATHS OF `settings` */
+pub fn compile(mut sources: SourceMap, settings: &Settings) -> CompilationOutput {
+    let mut diagnostics = Diagnostics::default();
+    let entries: Vec<usize> = (0..sources.len()).collect();
+    let graph = resolve_imports(&mut sources, &entries, settings, &mut diagnostics);
+
+    /* SEMANTIC PASSES ONLY RUN ON UNITS THAT PARSED AND RESOLVED CLEANLY */
+    let mut bindings = if diagnostics.has_errors() {
+        Bindings::default()
+    } else {
+        resolve_names(&graph, &mut diagnostics)
+    };
+    if !diagnostics.has_errors() {
+        linearize_contracts(&graph, &mut bindings, &mut diagnostics);
+    }
+    let types = if diagnostics.has_errors() {
+        TypeTable::default()
+    } else {
+        check_types(&graph, &bindings, &mut diagnostics)
+    };
+    if !diagnostics.has_errors() {
+        check_inheritance(&graph, &mut bindings, &types, &mut diagnostics);
+    }
+    let mut cron_jobs = if diagnostics.has_errors() {
+        HashMap::new()
+    } else {
+        check_cron_jobs(&graph, &bindings, &mut diagnostics)
+    };
+    if !diagnostics.has_errors() {
+        check_gasless(&graph, &mut diagnostics);
+    }
+    let mut storage_layouts = HashMap::new();
+    if !diagnostics.has_errors() {
+        for unit in &graph.units {
+            for (index, contract) in unit.ast.contracts.iter().enumerate() {
+                if matches!(
+                    contract.kind,
+                    ContractKind::Contract | ContractKind::AbstractContract
+                ) {
+                    let id = (unit.file_id, index);
+                    let layout =
+                        storage_layout(id, &graph, &bindings, &types, &sources, &mut diagnostics);
+                    storage_layouts.insert(id, layout);
+                }
+            }
+        }
+        check_upgradable(
+            &graph,
+            &bindings,
+            &sources,
+            &storage_layouts,
+            settings,
+            &mut diagnostics,
+        );
+    }
+
+    /* BYTECODE OF THE DEPLOYABLE CONTRACTS OF EACH ENTRY, AND OF THOSE THEY CREATE */
+    let mut bytecode = HashMap::new();
+    if !diagnostics.has_errors() {
+        let context = CodegenContext {
+            graph: &graph,
+            bindings: &bindings,
+            types: &types,
+            sources: &sources,
+            storage_layouts: &storage_layouts,
+            cron_jobs: &cron_jobs,
+            settings,
+        };
+        let mut cache = BytecodeCache::default();
+        for &file_id in &entries {
+            for index in 0..graph.units[file_id].ast.contracts.len() {
+                let id = (file_id, index);
+                if let Some(_bytecode) =
+                    generate_bytecode(id, &context, &mut cache, &mut diagnostics)
+                {
+                    bytecode.insert(id, _bytecode);
+                }
+            }
+        }
+    }
+
+    /* ONE ARTIFACT PER CONTRACT OF EACH ENTRY, ONLY WHEN THE WHOLE PROGRAM IS VALID */
+    let mut artifacts = Vec::new();
+    if !diagnostics.has_errors() {
+        for &file_id in &entries {
+            for (index, contract) in graph.units[file_id].ast.contracts.iter().enumerate() {
+                if contract.kind == ContractKind::File {
+                    continue;
+                }
+                let cron = cron_jobs.remove(&(file_id, index)).unwrap_or_default();
+                artifacts.push(Artifact {
+                    source_unit: sources.get(file_id).unwrap().name.clone(),
+                    contract: contract.name.name.clone(),
+                    abi: contract_abi((file_id, index), &graph, &bindings, &types, &cron),
+                    cron,
+                    gasless: match contract.kind {
+                        ContractKind::Contract | ContractKind::AbstractContract => {
+                            sponsored_functions((file_id, index), &graph, &bindings, &types)
+                        }
+                        _ => Vec::new(),
+                    },
+                    upgradable: contract.upgradable,
+                    storage_layout: storage_layouts.remove(&(file_id, index)),
+                    bytecode: bytecode.remove(&(file_id, index)),
+                });
+            }
+        }
+    }
+
+    CompilationOutput {
+        sources,
+        graph,
+        bindings,
+        types,
+        diagnostics,
+        artifacts,
+    }
+}
@@ -0,0 +1,757 @@
+use num_bigint::{BigInt, BigUint};
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::mods::types::{
+    ast::{
+        BinaryOperator, CallArguments, ContractPart, ElementaryType, Expression, ExpressionKind,
+        Literal, TypeNameKind, UnaryOperator, VariableDefinition, VariableMutability,
+    },
+    bindings::{Bindings, Declaration},
+    compiler_errors::{CompilerError, SemanticError},
+    diagnostics::Diagnostic,
+    line_descriptors::Span,
+    semantic_types::{ConstValue, Type},
+    source_graph::SourceGraph,
+};
+
+/* RATIONAL CONSTANTS LARGER THAN THIS MANY BITS ARE REJECTED RATHER THAN COMPUTED, AS IN SOLC */
+pub const MAX_CONSTANT_BITS: u64 = 4096;
+
+/* WHY AN EXPRESSION HAS NO COMPILE TIME VALUE. `NotConstant` IS EXPECTED WHEREVER RUNTIME
+VALUES ARE ALSO ALLOWED, `Invalid` (OVERFLOW, DIVISION BY ZERO, A CYCLE) IS ALWAYS AN ERROR */
+#[derive(Debug, Clone)]
+pub enum ConstError {
+    NotConstant(Box<Diagnostic>),
+    Invalid(Box<Diagnostic>),
+}
+
+impl ConstError {
+    pub fn diagnostic(self) -> Diagnostic {
+        match self {
+            ConstError::NotConstant(diagnostic) | ConstError::Invalid(diagnostic) => *diagnostic,
+        }
+    }
+}
+
+/* FOLDS EXPRESSIONS OF LITERALS, `constant` STATE VARIABLES AND `type(T).min/max`. LITERALS
+ARE EXACT RATIONALS, AND ONCE A TYPED VALUE IS INVOLVED THE ARITHMETIC IS CHECKED AGAINST ITS
+INTEGER TYPE AS AT RUNTIME */
+pub struct ConstEvaluator<'a> {
+    graph: &'a SourceGraph,
+    bindings: &'a Bindings,
+    /* NAME SPANS OF THE CONSTANTS BEING EVALUATED, TO REPORT `A = B; B = A` INSTEAD OF LOOPING */
+    evaluating: Vec<Span>,
+}
+
+impl<'a> ConstEvaluator<'a> {
+    pub fn new(graph: &'a SourceGraph, bindings: &'a Bindings) -> Self {
+        ConstEvaluator {
+            graph,
+            bindings,
+            evaluating: Vec::new(),
+        }
+    }
+
+    /* LENGTH OF `T[n]`, WHICH MUST BE A POSITIVE INTEGER THAT FITS IN `uint256` */
+    pub fn array_length(&mut self, length: &Expression) -> Result<BigInt, ConstError> {
+        let value = self.evaluate(length)?;
+        let Some(integer) = value.to_integer() else {
+            return Err(invalid(
+                length.span,
+                format!("array length must be an integer, found `{value}`"),
+                "invalid array length",
+            ));
+        };
+        let (_, max) = Type::integer_range(false, 256);
+        if integer.is_zero() {
+            Err(invalid(
+                length.span,
+                "array with zero length specified",
+                "zero length",
+            ))
+        } else if integer.is_negative() {
+            Err(invalid(
+                length.span,
+                "array with negative length specified",
+                "negative length",
+            ))
+        } else if integer > max {
+            Err(invalid(
+                length.span,
+                "array length too large",
+                "does not fit in `uint256`",
+            ))
+        } else {
+            Ok(integer)
+        }
+    }
+
+    /* VALUE OF A `constant` STATE VARIABLE, CONVERTED TO ITS DECLARED TYPE */
+    pub fn constant(&mut self, variable: &VariableDefinition) -> Result<ConstValue, ConstError> {
+        let Some(initializer) = &variable.initializer else {
+            return Err(not_constant(variable.name.span, &variable.name.name, None));
+        };
+        if self.evaluating.contains(&variable.name.span) {
+            return Err(invalid(
+                variable.name.span,
+                format!("cyclic definition of constant `{}`", variable.name.name),
+                "the value of this constant depends on itself",
+            ));
+        }
+        self.evaluating.push(variable.name.span);
+        let value = self.evaluate(initializer);
+        self.evaluating.pop();
+        match (value?, &variable.ty.kind) {
+            (value, TypeNameKind::Elementary(ElementaryType::Uint(bits))) => {
+                typed(initializer.span, value, false, *bits)
+            }
+            (value, TypeNameKind::Elementary(ElementaryType::Int(bits))) => {
+                typed(initializer.span, value, true, *bits)
+            }
+            (value, _) => Ok(value),
+        }
+    }
+
+    pub fn evaluate(&mut self, expression: &Expression) -> Result<ConstValue, ConstError> {
+        match &expression.kind {
+            ExpressionKind::Literal(literal) => Ok(match literal {
+                Literal::Bool(value) => ConstValue::Bool(*value),
+                Literal::Number(value, _) => ConstValue::Rational(BigRational::from(value.clone())),
+                Literal::Rational(value) => ConstValue::Rational(value.clone()),
+                Literal::String(value) => ConstValue::Bytes(value.as_bytes().to_vec()),
+                Literal::HexString(value) => ConstValue::Bytes(value.clone()),
+                Literal::Address(value) => {
+                    ConstValue::Bytes(hex::decode(&value[2..]).unwrap_or_default())
+                }
+            }),
+            ExpressionKind::Variable(identifier) => {
+                self.reference(expression.span, &identifier.span, &identifier.name)
+            }
+            ExpressionKind::MemberAccess {
+                expression: base,
+                member,
+            } => {
+                if self.bindings.get(&member.span).is_some() {
+                    return self.reference(expression.span, &member.span, &member.name);
+                }
+                match (self.type_argument(base), member.name.as_str()) {
+                    (Some((signed, bits)), "min" | "max") => {
+                        let (min, max) = Type::integer_range(signed, bits);
+                        Ok(ConstValue::Integer {
+                            value: if member.name == "min" { min } else { max },
+                            signed,
+                            bits,
+                        })
+                    }
+                    _ => Err(not_constant(expression.span, "this expression", None)),
+                }
+            }
+            ExpressionKind::FunctionCall {
+                callee,
+                arguments: CallArguments::Positional(arguments),
+            } if arguments.len() == 1 => {
+                let (signed, bits) = match callee.kind {
+                    ExpressionKind::ElementaryType(ElementaryType::Uint(bits)) => (false, bits),
+                    ExpressionKind::ElementaryType(ElementaryType::Int(bits)) => (true, bits),
+                    _ => return Err(not_constant(expression.span, "this expression", None)),
+                };
+                /* AN EXPLICIT CONVERSION TRUNCATES TYPED VALUES BUT A LITERAL MUST FIT */
+                match self.evaluate(&arguments[0])? {
+                    ConstValue::Integer { value, .. } => Ok(ConstValue::Integer {
+                        value: wrap(value, signed, bits),
+                        signed,
+                        bits,
+                    }),
+                    value => typed(arguments[0].span, value, signed, bits),
+                }
+            }
+            ExpressionKind::Unary { operator, operand } => {
+                let value = self.evaluate(operand)?;
+                unary(expression.span, *operator, value)
+            }
+            ExpressionKind::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
+                binary(expression.span, *operator, left, right)
+            }
+            ExpressionKind::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => match self.evaluate(condition)? {
+                ConstValue::Bool(true) => self.evaluate(then),
+                ConstValue::Bool(false) => self.evaluate(otherwise),
+                value => Err(invalid(
+                    condition.span,
+                    format!("condition must be a boolean, found `{value}`"),
+                    "not a boolean",
+                )),
+            },
+            _ => Err(not_constant(expression.span, "this expression", None)),
+        }
+    }
+
+    /* A NAME, WHICH IS CONSTANT ONLY IF IT IS A `constant` STATE VARIABLE */
+    fn reference(
+        &mut self,
+        span: Span,
+        name_span: &Span,
+        name: &str,
+    ) -> Result<ConstValue, ConstError> {
+        let graph = self.graph;
+        let variable = match self.bindings.get(name_span) {
+            Some(
+                [Declaration::Member {
+                    file_id,
+                    contract,
+                    part,
+                }],
+            ) => match &graph.units[*file_id].ast.contracts[*contract].parts[*part] {
+                ContractPart::StateVariable(variable) => variable,
+                _ => return Err(not_constant(span, name, None)),
+            },
+            Some([Declaration::Local(declaration)]) => {
+                return Err(not_constant(
+                    span,
+                    name,
+                    Some((*declaration, "declared here")),
+                ))
+            }
+            _ => return Err(not_constant(span, name, None)),
+        };
+        if variable.mutability != Some(VariableMutability::Constant) {
+            return Err(not_constant(
+                span,
+                name,
+                Some((variable.name.span, "declared here without `constant`")),
+            ));
+        }
+        self.constant(variable)
+    }
+
+    /* `T` IN `type(T)` WHEN IT IS AN INTEGER TYPE */
+    fn type_argument(&self, expression: &Expression) -> Option<(bool, u16)> {
+        let ExpressionKind::FunctionCall {
+            callee,
+            arguments: CallArguments::Positional(arguments),
+        } = &expression.kind
+        else {
+            return None;
+        };
+        let ExpressionKind::Variable(identifier) = &callee.kind else {
+            return None;
+        };
+        if self.bindings.get(&identifier.span) != Some(&[Declaration::Builtin("type")]) {
+            return None;
+        }
+        match arguments.as_slice() {
+            [Expression {
+                kind: ExpressionKind::ElementaryType(ElementaryType::Uint(bits)),
+                ..
+            }] => Some((false, *bits)),
+            [Expression {
+                kind: ExpressionKind::ElementaryType(ElementaryType::Int(bits)),
+                ..
+            }] => Some((true, *bits)),
+            _ => None,
+        }
+    }
+}
+
+fn not_constant(span: Span, name: &str, declaration: Option<(Span, &str)>) -> ConstError {
+    let message = match name {
+        "this expression" => "expression is not a compile-time constant".to_string(),
+        name => format!("`{name}` is not a compile-time constant"),
+    };
+    let mut diagnostic = CompilerError::SemanticError(SemanticError::NotConstant(message))
+        .diagnostic()
+        .with_label(span, "not a constant")
+        .with_help("only literals, `constant` variables and `type(T).min/max` can be used here");
+    if let Some((_declaration, label)) = declaration {
+        diagnostic = diagnostic.with_secondary_label(_declaration, label);
+    }
+    ConstError::NotConstant(Box::new(diagnostic))
+}
+
+/* THE WORD A LITERAL OF INLINE ASSEMBLY STANDS FOR, STRINGS ARE LEFT ALIGNED LIKE A `bytes32` */
+pub fn yul_literal(span: Span, literal: &Literal) -> Result<BigUint, ConstError> {
+    let bytes = match literal {
+        Literal::Bool(value) => return Ok(BigUint::from(*value as u8)),
+        Literal::Number(value, _) => {
+            return match fits(value, false, 256) {
+                true => Ok(value.to_biguint().unwrap()),
+                false => Err(invalid(
+                    span,
+                    format!("number `{value}` does not fit in 256 bits"),
+                    "out of range",
+                )),
+            }
+        }
+        Literal::String(value) => value.as_bytes(),
+        Literal::HexString(bytes) => bytes.as_slice(),
+        Literal::Rational(_) | Literal::Address(_) => {
+            return Err(invalid(
+                span,
+                "invalid literal in inline assembly",
+                "not a word",
+            ))
+        }
+    };
+    match bytes.len() <= 32 {
+        true => Ok(BigUint::from_bytes_be(bytes) << (8 * (32 - bytes.len()))),
+        false => Err(invalid(
+            span,
+            format!(
+                "string literal of {} bytes does not fit in a word",
+                bytes.len()
+            ),
+            "longer than 32 bytes",
+        )),
+    }
+}
+
+fn invalid(span: Span, message: impl Into<String>, label: impl Into<String>) -> ConstError {
+    ConstError::Invalid(Box::new(
+        CompilerError::SemanticError(SemanticError::InvalidOperation(message.into()))
+            .diagnostic()
+            .with_label(span, label),
+    ))
+}
+
+/* `value` AS AN INTEGER OF THE GIVEN TYPE, WHICH IT MUST FIT */
+fn typed(span: Span, value: ConstValue, signed: bool, bits: u16) -> Result<ConstValue, ConstError> {
+    let type_name = Type::Integer { signed, bits };
+    match value.to_integer() {
+        Some(integer) if fits(&integer, signed, bits) => Ok(ConstValue::Integer {
+            value: integer,
+            signed,
+            bits,
+        }),
+        Some(_) => Err(invalid(
+            span,
+            format!("constant `{value}` does not fit in `{type_name}`"),
+            "out of range",
+        )),
+        None => Err(invalid(
+            span,
+            format!("`{value}` cannot be converted to `{type_name}`"),
+            "not an integer",
+        )),
+    }
+}
+
+fn fits(value: &BigInt, signed: bool, bits: u16) -> bool {
+    let (min, max) = Type::integer_range(signed, bits);
+    min <= *value && *value <= max
+}
+
+/* TWO'S COMPLEMENT TRUNCATION TO `bits`, AS EXPLICIT CONVERSIONS AND SHIFTS DO */
+fn wrap(value: BigInt, signed: bool, bits: u16) -> BigInt {
+    let modulus = BigInt::one() << bits;
+    let mut value = ((value % &modulus) + &modulus) % &modulus;
+    if signed && value >= (&modulus >> 1) {
+        value -= modulus;
+    }
+    value
+}
+
+fn unary(span: Span, operator: UnaryOperator, value: ConstValue) -> Result<ConstValue, ConstError> {
+    match (operator, value) {
+        (UnaryOperator::Not, ConstValue::Bool(value)) => Ok(ConstValue::Bool(!value)),
+        (UnaryOperator::Negate, ConstValue::Rational(value)) => Ok(ConstValue::Rational(-value)),
+        (
+            UnaryOperator::Negate,
+            ConstValue::Integer {
+                value,
+                signed: true,
+                bits,
+            },
+        ) => checked(span, -value, true, bits),
+        (UnaryOperator::BitNot, ConstValue::Rational(value)) if value.is_integer() => {
+            Ok(ConstValue::Rational(BigRational::from(!value.to_integer())))
+        }
+        (
+            UnaryOperator::BitNot,
+            ConstValue::Integer {
+                value,
+                signed,
+                bits,
+            },
+        ) => Ok(ConstValue::Integer {
+            value: wrap(!value, signed, bits),
+            signed,
+            bits,
+        }),
+        (UnaryOperator::Not | UnaryOperator::Negate | UnaryOperator::BitNot, value) => {
+            Err(invalid(
+                span,
+                format!(
+                    "operator `{}` cannot be applied to `{value}`",
+                    unary_symbol(operator)
+                ),
+                "in this constant expression",
+            ))
+        }
+        _ => Err(not_constant(span, "this expression", None)),
+    }
+}
+
+fn checked(span: Span, value: BigInt, signed: bool, bits: u16) -> Result<ConstValue, ConstError> {
+    if fits(&value, signed, bits) {
+        Ok(ConstValue::Integer {
+            value,
+            signed,
+            bits,
+        })
+    } else {
+        Err(invalid(
+            span,
+            format!(
+                "arithmetic overflow in constant expression: `{value}` does not fit in `{}`",
+                Type::Integer { signed, bits }
+            ),
+            "overflows",
+        ))
+    }
+}
+
+fn binary(
+    span: Span,
+    operator: BinaryOperator,
+    left: ConstValue,
+    right: ConstValue,
+) -> Result<ConstValue, ConstError> {
+    let arithmetic_error = |message: String| invalid(span, message, "in this constant expression");
+    match (left, right) {
+        (ConstValue::Rational(a), ConstValue::Rational(b)) => {
+            fold(operator, &a, &b).map_err(arithmetic_error)
+        }
+        (ConstValue::Bool(a), ConstValue::Bool(b)) => match operator {
+            BinaryOperator::And => Ok(ConstValue::Bool(a && b)),
+            BinaryOperator::Or => Ok(ConstValue::Bool(a || b)),
+            BinaryOperator::Eq => Ok(ConstValue::Bool(a == b)),
+            BinaryOperator::Ne => Ok(ConstValue::Bool(a != b)),
+            _ => Err(arithmetic_error(format!(
+                "operator `{}` cannot be applied to booleans",
+                binary_symbol(operator)
+            ))),
+        },
+        (ConstValue::Bytes(a), ConstValue::Bytes(b))
+            if matches!(operator, BinaryOperator::Eq | BinaryOperator::Ne) =>
+        {
+            Ok(ConstValue::Bool(
+                (a == b) == (operator == BinaryOperator::Eq),
+            ))
+        }
+        (left, right) => {
+            /* SHIFTS AND EXPONENTIATION KEEP THE TYPE OF THE LEFT OPERAND */
+            if matches!(
+                operator,
+                BinaryOperator::Shl | BinaryOperator::Shr | BinaryOperator::Exp
+            ) {
+                let amount = match right.to_integer() {
+                    Some(amount) if !amount.is_negative() => amount,
+                    _ => {
+                        return Err(arithmetic_error(format!(
+                            "right operand of `{}` must be a non-negative integer",
+                            binary_symbol(operator)
+                        )))
+                    }
+                };
+                return match left {
+                    ConstValue::Rational(a) => {
+                        fold(operator, &a, &BigRational::from(amount)).map_err(arithmetic_error)
+                    }
+                    ConstValue::Integer {
+                        value,
+                        signed,
+                        bits,
+                    } => typed_shift(span, operator, value, amount, signed, bits),
+                    left => Err(arithmetic_error(format!(
+                        "operator `{}` cannot be applied to `{left}`",
+                        binary_symbol(operator)
+                    ))),
+                };
+            }
+            let (a, b, signed, bits) = common_integer(span, &left, &right)?;
+            typed_binary(span, operator, a, b, signed, bits)
+        }
+    }
+}
+
+/* BRINGS TWO OPERANDS, AT LEAST ONE OF THEM TYPED, TO A COMMON INTEGER TYPE */
+fn common_integer(
+    span: Span,
+    left: &ConstValue,
+    right: &ConstValue,
+) -> Result<(BigInt, BigInt, bool, u16), ConstError> {
+    let (signed, bits) = match (left, right) {
+        (
+            ConstValue::Integer { signed, bits, .. },
+            ConstValue::Integer {
+                signed: other_signed,
+                bits: other_bits,
+                ..
+            },
+        ) => {
+            if signed != other_signed {
+                return Err(invalid(
+                    span,
+                    format!(
+                        "no common type for `{}` and `{}`",
+                        Type::Integer {
+                            signed: *signed,
+                            bits: *bits
+                        },
+                        Type::Integer {
+                            signed: *other_signed,
+                            bits: *other_bits
+                        }
+                    ),
+                    "in this constant expression",
+                ));
+            }
+            (*signed, *bits.max(other_bits))
+        }
+        (ConstValue::Integer { signed, bits, .. }, _)
+        | (_, ConstValue::Integer { signed, bits, .. }) => (*signed, *bits),
+        (left, right) => {
+            return Err(invalid(
+                span,
+                format!("operands `{left}` and `{right}` have no common type"),
+                "in this constant expression",
+            ))
+        }
+    };
+    let a = typed(span, left.clone(), signed, bits)?
+        .to_integer()
+        .unwrap();
+    let b = typed(span, right.clone(), signed, bits)?
+        .to_integer()
+        .unwrap();
+    Ok((a, b, signed, bits))
+}
+
+fn typed_binary(
+    span: Span,
+    operator: BinaryOperator,
+    a: BigInt,
+    b: BigInt,
+    signed: bool,
+    bits: u16,
+) -> Result<ConstValue, ConstError> {
+    let integer = |value| ConstValue::Integer {
+        value,
+        signed,
+        bits,
+    };
+    match operator {
+        BinaryOperator::Add => checked(span, a + b, signed, bits),
+        BinaryOperator::Sub => checked(span, a - b, signed, bits),
+        BinaryOperator::Mul => checked(span, a * b, signed, bits),
+        BinaryOperator::Div | BinaryOperator::Mod if b.is_zero() => Err(invalid(
+            span,
+            "division by zero",
+            "in this constant expression",
+        )),
+        /* BOTH TRUNCATE TOWARDS ZERO LIKE `SDIV` AND `SMOD` */
+        BinaryOperator::Div => checked(span, a / b, signed, bits),
+        BinaryOperator::Mod => Ok(integer(a % b)),
+        BinaryOperator::BitAnd => Ok(integer(a & b)),
+        BinaryOperator::BitOr => Ok(integer(a | b)),
+        BinaryOperator::BitXor => Ok(integer(a ^ b)),
+        BinaryOperator::Eq => Ok(ConstValue::Bool(a == b)),
+        BinaryOperator::Ne => Ok(ConstValue::Bool(a != b)),
+        BinaryOperator::Lt => Ok(ConstValue::Bool(a < b)),
+        BinaryOperator::Le => Ok(ConstValue::Bool(a <= b)),
+        BinaryOperator::Gt => Ok(ConstValue::Bool(a > b)),
+        BinaryOperator::Ge => Ok(ConstValue::Bool(a >= b)),
+        BinaryOperator::And
+        | BinaryOperator::Or
+        | BinaryOperator::Shl
+        | BinaryOperator::Shr
+        | BinaryOperator::Exp => Err(invalid(
+            span,
+            format!(
+                "operator `{}` cannot be applied to `{}`",
+                binary_symbol(operator),
+                Type::Integer { signed, bits }
+            ),
+            "in this constant expression",
+        )),
+    }
+}
+
+fn typed_shift(
+    span: Span,
+    operator: BinaryOperator,
+    value: BigInt,
+    amount: BigInt,
+    signed: bool,
+    bits: u16,
+) -> Result<ConstValue, ConstError> {
+    /* SHIFTING BY THE WIDTH OR MORE CLEARS THE VALUE (OR LEAVES THE SIGN FOR `>>`) */
+    let amount = amount.to_usize().unwrap_or(usize::MAX).min(bits as usize);
+    match operator {
+        BinaryOperator::Shl => Ok(ConstValue::Integer {
+            value: wrap(value << amount, signed, bits),
+            signed,
+            bits,
+        }),
+        BinaryOperator::Shr => Ok(ConstValue::Integer {
+            value: value >> amount,
+            signed,
+            bits,
+        }),
+        _ => {
+            let exponent = amount as u32;
+            /* ANY BASE BUT 0, 1 AND -1 OVERFLOWS LONG BEFORE THE EXPONENT REACHES THE WIDTH */
+            if value.magnitude() > &One::one() && exponent >= bits as u32 {
+                return Err(invalid(
+                    span,
+                    format!(
+                        "arithmetic overflow in constant expression: the result does not fit in `{}`",
+                        Type::Integer { signed, bits }
+                    ),
+                    "overflows",
+                ));
+            }
+            checked(
+                span,
+                num_traits::pow::Pow::pow(&value, exponent),
+                signed,
+                bits,
+            )
+        }
+    }
+}
+
+/* EVALUATES AN OPERATOR ON TWO CONSTANTS EXACTLY */
+pub fn fold(
+    operator: BinaryOperator,
+    a: &BigRational,
+    b: &BigRational,
+) -> Result<ConstValue, String> {
+    let integers = || -> Result<(BigInt, BigInt), String> {
+        if a.is_integer() && b.is_integer() {
+            Ok((a.to_integer(), b.to_integer()))
+        } else {
+            Err(format!(
+                "operator `{}` needs integer operands",
+                binary_symbol(operator)
+            ))
+        }
+    };
+    let shift = |amount: &BigInt| -> Result<usize, String> {
+        amount
+            .to_u64()
+            .filter(|amount| *amount <= MAX_CONSTANT_BITS)
+            .map(|amount| amount as usize)
+            .ok_or_else(|| "shift amount out of range".to_string())
+    };
+    let value = match operator {
+        BinaryOperator::Add => a + b,
+        BinaryOperator::Sub => a - b,
+        BinaryOperator::Mul => a * b,
+        BinaryOperator::Div | BinaryOperator::Mod if b.is_zero() => {
+            return Err("division by zero".to_string())
+        }
+        BinaryOperator::Div => a / b,
+        BinaryOperator::Mod => {
+            let (a, b) = integers()?;
+            BigRational::from(a % b)
+        }
+        BinaryOperator::Exp => {
+            let exponent = match b.is_integer().then(|| b.to_integer().to_i32()).flatten() {
+                Some(exponent) if a.is_zero() && exponent < 0 => {
+                    return Err("division by zero".to_string())
+                }
+                Some(exponent)
+                    if (a.numer().bits().max(a.denom().bits()) as u128)
+                        * exponent.unsigned_abs() as u128
+                        <= MAX_CONSTANT_BITS as u128 =>
+                {
+                    exponent
+                }
+                _ => return Err("exponent too large for a constant".to_string()),
+            };
+            num_traits::pow::Pow::pow(a, exponent)
+        }
+        BinaryOperator::Shl => {
+            let (a, b) = integers()?;
+            BigRational::from(a << shift(&b)?)
+        }
+        BinaryOperator::Shr => {
+            let (a, b) = integers()?;
+            BigRational::from(a >> shift(&b)?)
+        }
+        BinaryOperator::BitAnd => {
+            let (a, b) = integers()?;
+            BigRational::from(a & b)
+        }
+        BinaryOperator::BitOr => {
+            let (a, b) = integers()?;
+            BigRational::from(a | b)
+        }
+        BinaryOperator::BitXor => {
+            let (a, b) = integers()?;
+            BigRational::from(a ^ b)
+        }
+        BinaryOperator::Eq => return Ok(ConstValue::Bool(a == b)),
+        BinaryOperator::Ne => return Ok(ConstValue::Bool(a != b)),
+        BinaryOperator::Lt => return Ok(ConstValue::Bool(a < b)),
+        BinaryOperator::Le => return Ok(ConstValue::Bool(a <= b)),
+        BinaryOperator::Gt => return Ok(ConstValue::Bool(a > b)),
+        BinaryOperator::Ge => return Ok(ConstValue::Bool(a >= b)),
+        BinaryOperator::And | BinaryOperator::Or => {
+            return Err(format!(
+                "operator `{}` cannot be applied to numbers",
+                binary_symbol(operator)
+            ))
+        }
+    };
+    if value.numer().bits() > MAX_CONSTANT_BITS || value.denom().bits() > MAX_CONSTANT_BITS {
+        return Err("constant too large".to_string());
+    }
+    Ok(ConstValue::Rational(value))
+}
+
+pub fn binary_symbol(operator: BinaryOperator) -> &'static str {
+    match operator {
+        BinaryOperator::Add => "+",
+        BinaryOperator::Sub => "-",
+        BinaryOperator::Mul => "*",
+        BinaryOperator::Div => "/",
+        BinaryOperator::Mod => "%",
+        BinaryOperator::Exp => "**",
+        BinaryOperator::Shl => "<<",
+        BinaryOperator::Shr => ">>",
+        BinaryOperator::BitAnd => "&",
+        BinaryOperator::BitOr => "|",
+        BinaryOperator::BitXor => "^",
+        BinaryOperator::And => "&&",
+        BinaryOperator::Or => "||",
+        BinaryOperator::Eq => "==",
+        BinaryOperator::Ne => "!=",
+        BinaryOperator::Lt => "<",
+        BinaryOperator::Le => "<=",
+        BinaryOperator::Gt => ">",
+        BinaryOperator::Ge => ">=",
+    }
+}
+
+pub fn unary_symbol(operator: UnaryOperator) -> &'static str {
+    match operator {
+        UnaryOperator::Not => "!",
+        UnaryOperator::BitNot => "~",
+        UnaryOperator::Negate => "-",
+        UnaryOperator::PreIncrement | UnaryOperator::PostIncrement => "++",
+        UnaryOperator::PreDecrement | UnaryOperator::PostDecrement => "--",
+        UnaryOperator::Delete => "delete",
+    }
+}
@@ -0,0 +1,154 @@
+use std::{collections::HashMap, ops::Range};
+
+use crate::mods::{
+    functions::controllers::const_evaluator::ConstEvaluator,
+    types::{
+        ast::{ContractKind, ContractPart, CronDefinition, ExpressionKind, Literal},
+        bindings::{Bindings, ContractId},
+        compiler_errors::{CompilerError, SemanticError},
+        cron::{CronJob, CronSchedule},
+        diagnostics::{Diagnostic, Diagnostics},
+        line_descriptors::Span,
+        source_graph::SourceGraph,
+    },
+};
+
+/* VALIDATES THE `cron` BLOCKS OF EVERY CONTRACT AND RETURNS THEM AS JOBS FOR THE SCHEDULER. A
+CONTRACT RUNS ITS OWN BLOCKS AND THOSE IT INHERITS, NUMBERED ALONG ITS LINEARIZATION, MOST BASE
+FIRST, SO THAT NO TWO OF THEM SHARE AN ENTRY POINT */
+pub fn check_cron_jobs(
+    graph: &SourceGraph,
+    bindings: &Bindings,
+    diagnostics: &mut Diagnostics,
+) -> HashMap<ContractId, Vec<CronJob>> {
+    let mut declared = HashMap::new();
+    for unit in &graph.units {
+        for (index, contract) in unit.ast.contracts.iter().enumerate() {
+            let id = (unit.file_id, index);
+            let mut contract_jobs = Vec::new();
+            for part in &contract.parts {
+                let ContractPart::Cron(cron) = part else {
+                    continue;
+                };
+                if matches!(
+                    contract.kind,
+                    ContractKind::Interface | ContractKind::Library
+                ) {
+                    let kind = match contract.kind {
+                        ContractKind::Interface => "interfaces",
+                        _ => "libraries",
+                    };
+                    diagnostics.push(invalid(
+                        cron.span,
+                        format!("cron blocks are not allowed in {kind}"),
+                        "only contracts can be scheduled",
+                    ));
+                    continue;
+                }
+                if let Some(job) = cron_job(graph, bindings, id, cron, diagnostics) {
+                    contract_jobs.push(job);
+                }
+            }
+            declared.insert(id, contract_jobs);
+        }
+    }
+
+    let mut jobs = HashMap::new();
+    for &id in declared.keys() {
+        let contract_jobs: Vec<CronJob> = bindings
+            .inheritance_order(id)
+            .into_iter()
+            .flat_map(|base| declared.get(&base).into_iter().flatten())
+            .enumerate()
+            .map(|(index, job)| CronJob {
+                index,
+                ..job.clone()
+            })
+            .collect();
+        if !contract_jobs.is_empty() {
+            jobs.insert(id, contract_jobs);
+        }
+    }
+    jobs
+}
+
+fn cron_job(
+    graph: &SourceGraph,
+    bindings: &Bindings,
+    contract: ContractId,
+    cron: &CronDefinition,
+    diagnostics: &mut Diagnostics,
+) -> Option<CronJob> {
+    let ExpressionKind::Literal(Literal::String(text)) = &cron.schedule.kind else {
+        diagnostics.push(invalid(
+            cron.schedule.span,
+            "cron schedule must be a string literal".to_string(),
+            "expected a string such as \"0 8 * * 1\"",
+        ));
+        return None;
+    };
+    let schedule = match text.parse::<CronSchedule>() {
+        Ok(schedule) => schedule,
+        Err(error) => {
+            let span = field_span(cron.schedule.span, text, error.range);
+            diagnostics.push(invalid(
+                span,
+                error.message,
+                "invalid cron schedule",
+            ).with_note("a schedule is `minute hour day-of-month month day-of-week`, e.g. \"0 8 * * 1\""));
+            return None;
+        }
+    };
+
+    let max_runs = match &cron.max_runs {
+        Some(_max_runs) => match ConstEvaluator::new(graph, bindings).evaluate(_max_runs) {
+            Ok(value) => match value
+                .to_integer()
+                .and_then(|value| u64::try_from(value).ok())
+            {
+                Some(max_runs) => max_runs,
+                None => {
+                    diagnostics.push(invalid(
+                        _max_runs.span,
+                        format!("maximum number of runs must fit in `uint64`, found `{value}`"),
+                        "invalid run count",
+                    ));
+                    return None;
+                }
+            },
+            Err(error) => {
+                diagnostics.push(error.diagnostic());
+                return None;
+            }
+        },
+        None => 0,
+    };
+
+    Some(CronJob {
+        index: 0,
+        contract,
+        span: cron.span,
+        schedule,
+        max_runs,
+    })
+}
+
+/* SPAN OF THE BYTES `range` OF THE SCHEDULE, WHEN THE LITERAL IS WRITTEN WITHOUT ESCAPES SO
+THAT ITS TEXT MAPS ONE TO ONE ONTO THE SOURCE */
+fn field_span(literal: Span, text: &str, range: Range<usize>) -> Span {
+    if literal.end - literal.start != text.len() + 2 || range.is_empty() {
+        return literal;
+    }
+    Span {
+        start: literal.start + 1 + range.start,
+        end: literal.start + 1 + range.end,
+        column: literal.column + 1 + range.start as i32,
+        ..literal
+    }
+}
+
+fn invalid(span: Span, message: String, label: &str) -> Diagnostic {
+    CompilerError::SemanticError(SemanticError::InvalidCron(message))
+        .diagnostic()
+        .with_label(span, label)
+}
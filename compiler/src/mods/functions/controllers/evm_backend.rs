@@ -0,0 +1,449 @@
+use std::collections::HashSet;
+
+use num_bigint::BigInt;
+use num_traits::One;
+
+use crate::mods::{
+    functions::controllers::{
+        codegen::{add, and, constant, div, eq, gt, iszero, lt, mul, not, or, shl, sub},
+        expression_codegen::{sgt, signextend, slt, twos_complement},
+    },
+    types::{
+        assembly::{Assembly, AssemblyItem, Label},
+        ir::{
+            fold, mask, Checked, Function, Instruction, Integer, Link, Program, Terminator, Word,
+        },
+        line_descriptors::Span,
+        opcodes::Opcode,
+    },
+};
+
+/* THE ASSEMBLY OF A PROGRAM AND WHERE IT NEEDS A VALUE MORE THAN 16 SLOTS DOWN THE STACK */
+pub struct Lowered {
+    pub assembly: Assembly,
+    pub too_deep: Vec<Span>,
+}
+
+/* LOWERS THE IR TO EVM ASSEMBLY. THE BLOCKS ARE LAID OUT IN ORDER, A JUMP TO THE NEXT ONE FALLS
+THROUGH, AND BLOCKS THAT ONLY PANIC OR REVERT ARE SHARED BY THE WHOLE PROGRAM. AN INTERNAL
+FUNCTION IS ENTERED WITH ITS ARGUMENTS AND THE RETURN ADDRESS ABOVE THEM, WHICH STAYS UNDER ITS
+LOCAL VARIABLES UNTIL IT LEAVES */
+pub fn lower(program: &Program, push0: bool) -> Lowered {
+    let mut asm = Assembly::new(push0);
+    let labels = program
+        .functions
+        .iter()
+        .map(|function| function.blocks.iter().map(|_| asm.new_label()).collect())
+        .collect();
+    for data in &program.data {
+        asm.add_data(data.clone());
+    }
+    let mut backend = Backend {
+        asm,
+        labels,
+        stubs: Vec::new(),
+        too_deep: Vec::new(),
+        parameters: 0,
+        internal: false,
+        span: Span::default(),
+    };
+    for (index, function) in program.functions.iter().enumerate() {
+        backend.function(index, function);
+    }
+    for (stub, label) in backend.stubs.clone() {
+        backend.asm.label(label);
+        backend.asm.height = 0;
+        match stub {
+            Terminator::Panic(code) => {
+                backend.eval(&Word::Apply(
+                    Opcode::MStore,
+                    vec![constant(0u8), shl(constant(224u8), constant(0x4e487b71u32))],
+                ));
+                backend.eval(&Word::Apply(
+                    Opcode::MStore,
+                    vec![constant(4u8), constant(code)],
+                ));
+                backend.eval(&Word::Apply(
+                    Opcode::Revert,
+                    vec![constant(0u8), constant(0x24u8)],
+                ));
+            }
+            Terminator::Halt(word) => backend.eval(&word),
+            _ => {}
+        }
+    }
+
+    /* ONLY JUMP TARGETS NEED A JUMPDEST */
+    let targets: HashSet<Label> = backend
+        .asm
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            AssemblyItem::PushLabel(label) => Some(*label),
+            _ => None,
+        })
+        .collect();
+    backend
+        .asm
+        .items
+        .retain(|item| !matches!(item, AssemblyItem::Label(label) if !targets.contains(label)));
+    Lowered {
+        assembly: backend.asm,
+        too_deep: backend.too_deep,
+    }
+}
+
+struct Backend {
+    asm: Assembly,
+    /* OF EVERY BLOCK OF EVERY FUNCTION */
+    labels: Vec<Vec<Label>>,
+    stubs: Vec<(Terminator, Label)>,
+    too_deep: Vec<Span>,
+    /* THE FUNCTION AND BLOCK BEING LOWERED */
+    parameters: usize,
+    internal: bool,
+    span: Span,
+}
+
+/* A BLOCK THAT ONLY PANICS OR HALTS WITH CONSTANT ARGUMENTS, WHICH EVERY FUNCTION CAN SHARE */
+fn is_stub(function: &Function, block: usize) -> bool {
+    let block_data = &function.blocks[block];
+    block > 0
+        && block_data.instructions.is_empty()
+        && match &block_data.terminator {
+            Terminator::Panic(_) => true,
+            Terminator::Halt(word) => {
+                let mut constant = true;
+                word.visit(&mut |part| {
+                    constant &= !matches!(part, Word::Slot(_) | Word::Checked(..))
+                });
+                constant
+            }
+            _ => false,
+        }
+}
+
+impl Backend {
+    fn function(&mut self, index: usize, function: &Function) {
+        self.parameters = function.parameters;
+        self.internal = index > 0;
+        let emitted: Vec<usize> = (0..function.blocks.len())
+            .filter(|block| !is_stub(function, *block))
+            .collect();
+        for (order, block_index) in emitted.iter().enumerate() {
+            let block = &function.blocks[*block_index];
+            let next = emitted.get(order + 1).copied();
+            self.span = block.span;
+            self.asm.label(self.labels[index][*block_index]);
+            self.asm.height = self.height(block.height);
+            let mut instructions = block.instructions.iter().peekable();
+            while let Some(instruction) = instructions.next() {
+                match instruction {
+                    Instruction::Let(word) | Instruction::Exec(word) => {
+                        let word = self.physical(word);
+                        self.eval(&word);
+                    }
+                    Instruction::Assign(position, word) => {
+                        let position = self.position(*position);
+                        let word = self.physical(word);
+                        let top = self.asm.height;
+                        /* AN ASSIGNMENT OF THE TOP VARIABLE THAT IS THEN DROPPED IS A SWAP */
+                        let moved = word == Word::Slot(top)
+                            && matches!(instructions.peek(), Some(Instruction::Keep { base, positions })
+                                if positions.is_empty() && self.height(*base) + 1 == top);
+                        if moved {
+                            instructions.next();
+                        } else {
+                            self.eval(&word);
+                        }
+                        let depth = self.asm.height - position;
+                        if depth > 0 {
+                            self.swap(depth);
+                        }
+                        self.op(Opcode::Pop);
+                    }
+                    Instruction::Keep { base, positions } => {
+                        let positions: Vec<usize> = positions
+                            .iter()
+                            .map(|position| self.position(*position))
+                            .collect();
+                        self.keep(self.height(*base), &positions);
+                    }
+                }
+            }
+            match &block.terminator {
+                Terminator::Jump(target) => self.jump(function, index, *target, next),
+                Terminator::Branch {
+                    condition,
+                    pops,
+                    then,
+                    otherwise,
+                } => {
+                    let condition = self.physical(condition);
+                    if *pops != 1 || condition != Word::Slot(self.asm.height) {
+                        self.eval(&condition);
+                        for _ in 0..*pops {
+                            self.swap(1);
+                            self.op(Opcode::Pop);
+                        }
+                    }
+                    let label = self.target(function, index, *then);
+                    self.asm.jump_if(label);
+                    self.jump(function, index, *otherwise, next);
+                }
+                Terminator::Call {
+                    function: callee,
+                    next: back,
+                } => {
+                    let back = self.target(function, index, *back);
+                    self.asm.push_label(back);
+                    self.asm.jump(self.labels[*callee][0]);
+                }
+                Terminator::Leave(outputs) => {
+                    let mut kept: Vec<usize> = outputs
+                        .iter()
+                        .map(|position| self.position(*position))
+                        .collect();
+                    kept.push(self.parameters + 1);
+                    self.keep(0, &kept);
+                    self.op(Opcode::Jump);
+                }
+                Terminator::Halt(word) => {
+                    let word = self.physical(word);
+                    self.eval(&word);
+                }
+                Terminator::Panic(_) => {
+                    let label = self.stub(&block.terminator);
+                    self.asm.jump(label);
+                }
+            }
+        }
+    }
+
+    /* ------------------------------ FRAMES ------------------------------ */
+
+    /* WHERE A VARIABLE IS ON THE STACK, PAST THE RETURN ADDRESS OF AN INTERNAL FUNCTION */
+    fn position(&self, position: usize) -> usize {
+        match self.internal && position > self.parameters {
+            true => position + 1,
+            false => position,
+        }
+    }
+
+    fn height(&self, height: usize) -> usize {
+        height + self.internal as usize
+    }
+
+    fn physical(&self, word: &Word) -> Word {
+        word.substitute(&|position| Some(Word::Slot(self.position(position))))
+    }
+
+    fn target(&mut self, function: &Function, index: usize, block: usize) -> Label {
+        match is_stub(function, block) {
+            true => self.stub(&function.blocks[block].terminator),
+            false => self.labels[index][block],
+        }
+    }
+
+    fn stub(&mut self, terminator: &Terminator) -> Label {
+        if let Some((_, _label)) = self.stubs.iter().find(|(stub, _)| stub == terminator) {
+            return *_label;
+        }
+        let label = self.asm.new_label();
+        self.stubs.push((terminator.clone(), label));
+        label
+    }
+
+    fn jump(&mut self, function: &Function, index: usize, target: usize, next: Option<usize>) {
+        if next == Some(target) {
+            return;
+        }
+        let label = self.target(function, index, target);
+        self.asm.jump(label);
+    }
+
+    /* ------------------------------ STACK ------------------------------ */
+
+    fn op(&mut self, opcode: Opcode) {
+        self.asm.op(opcode);
+    }
+
+    fn report_too_deep(&mut self) {
+        if !self.too_deep.contains(&self.span) {
+            self.too_deep.push(self.span);
+        }
+    }
+
+    fn dup(&mut self, position: usize) {
+        let depth = self.asm.height + 1 - position;
+        if depth > 16 {
+            self.report_too_deep();
+        }
+        self.op(Opcode::Dup(depth.min(16) as u8));
+    }
+
+    fn swap(&mut self, depth: usize) {
+        if depth > 16 {
+            self.report_too_deep();
+        }
+        self.op(Opcode::Swap(depth.min(16) as u8));
+    }
+
+    fn pop(&mut self, count: usize) {
+        for _ in 0..count {
+            self.op(Opcode::Pop);
+        }
+    }
+
+    /* LEAVES ONLY THE VALUES AT `positions`, IN THAT ORDER, ABOVE `base`. THE COMMON SHAPES
+    ARE A FEW SWAPS, ANYTHING ELSE IS COPIED UP ROTATED BY THE NUMBER OF VALUES DROPPED, SO
+    THAT SWAPPING EACH DROPPED VALUE TO THE TOP AND POPPING IT LEAVES THEM IN ORDER */
+    fn keep(&mut self, base: usize, positions: &[usize]) {
+        let top = self.asm.height;
+        let dropped = top - base;
+        let kept = positions.len();
+        let from = |first: usize, values: &[usize]| {
+            values
+                .iter()
+                .enumerate()
+                .all(|(index, position)| *position == first + index)
+        };
+        if from(base + 1, positions) {
+            return self.pop(dropped - kept);
+        }
+        if positions[0] == top {
+            /* THE TOP VALUE OVER THE ONES UNDER IT */
+            if kept == 1 {
+                self.swap(dropped - 1);
+                return self.pop(dropped - 1);
+            }
+            /* THE TOP VALUE IN PLACE OF THE LOWEST */
+            if kept + 1 == dropped && from(base + 2, &positions[1..]) {
+                self.swap(dropped - 1);
+                return self.pop(1);
+            }
+            /* THE TOP VALUE UNDER THE OTHERS */
+            if kept == dropped && from(base + 1, &positions[1..]) {
+                for depth in (1..kept).rev() {
+                    self.swap(depth);
+                }
+                return;
+            }
+        }
+        for index in 0..kept {
+            self.dup(positions[(index + dropped) % kept]);
+        }
+        for _ in 0..dropped {
+            self.swap(kept);
+            self.op(Opcode::Pop);
+        }
+    }
+
+    /* PUSHES A WORD WHOSE VARIABLES ARE STACK POSITIONS */
+    fn eval(&mut self, word: &Word) {
+        if let (Word::Apply(..), Some(_value)) = (word, fold(word)) {
+            return self.asm.push(_value);
+        }
+        match word {
+            Word::Constant(value) => self.asm.push(value.clone()),
+            Word::Slot(position) => self.dup(*position),
+            Word::Apply(opcode, arguments) => {
+                for argument in arguments.iter().rev() {
+                    self.eval(argument);
+                }
+                self.op(*opcode);
+            }
+            Word::Checked(operation, ty, arguments) => self.checked(*operation, *ty, arguments),
+            Word::Link(link) => self.asm.append(match link {
+                Link::DataOffset(index) => AssemblyItem::PushDataOffset(*index),
+                Link::DataSize(index) => AssemblyItem::PushDataSize(*index),
+                Link::ProgramSize => AssemblyItem::PushProgramSize,
+                Link::Immutable(id) => AssemblyItem::PushImmutable(*id),
+            }),
+        }
+    }
+
+    fn panic_if(&mut self, condition: Word, code: u8) {
+        self.eval(&condition);
+        let label = self.stub(&Terminator::Panic(code));
+        self.asm.jump_if(label);
+    }
+
+    /* THE OPERATION ON THE TWO OPERANDS, FOLLOWED BY A CHECK THAT PANICS WHEN THE RESULT DOES
+    NOT FIT THE TYPE */
+    fn checked(&mut self, operation: Checked, ty: Integer, arguments: &[Word]) {
+        for argument in arguments.iter().rev() {
+            self.eval(argument);
+        }
+        let top = self.asm.height;
+        let (a, b) = (Word::Slot(top), Word::Slot(top - 1));
+        let (signed, bits) = (ty.signed, ty.bits as usize);
+        let max = constant(mask(bits - signed as usize));
+        let ones = not(constant(0u8));
+        let min = constant(twos_complement(&-(BigInt::one() << (bits - 1))));
+        let apply =
+            |opcode: Opcode, a: &Word, b: &Word| Word::Apply(opcode, vec![a.clone(), b.clone()]);
+        if matches!(operation, Checked::Div | Checked::Mod) {
+            self.panic_if(iszero(b.clone()), 0x12);
+            if operation == Checked::Div && signed {
+                self.panic_if(
+                    and(eq(a.clone(), min.clone()), eq(b.clone(), ones.clone())),
+                    0x11,
+                );
+            }
+            let opcode = match (operation, signed) {
+                (Checked::Div, true) => Opcode::SDiv,
+                (Checked::Div, false) => Opcode::Div,
+                (_, true) => Opcode::SMod,
+                _ => Opcode::Mod,
+            };
+            self.eval(&apply(opcode, &a, &b));
+        } else {
+            let result = match operation {
+                Checked::Add => add(a.clone(), b.clone()),
+                Checked::Sub => sub(a.clone(), b.clone()),
+                _ => mul(a.clone(), b.clone()),
+            };
+            self.eval(&result);
+            let r = Word::Slot(top + 1);
+            let narrow = |r: Word| iszero(eq(r.clone(), signextend(bits / 8 - 1, r)));
+            let overflow = match (operation, signed) {
+                (Checked::Add, false) if bits == 256 => lt(r, a),
+                (Checked::Add, false) => gt(r, max),
+                (Checked::Add, true) if bits == 256 => {
+                    apply(Opcode::Xor, &slt(b, constant(0u8)), &slt(r, a))
+                }
+                (Checked::Sub, false) => lt(a, b),
+                (Checked::Sub, true) if bits == 256 => {
+                    apply(Opcode::Xor, &slt(b, constant(0u8)), &sgt(r, a))
+                }
+                (Checked::Add | Checked::Sub, true) => narrow(r),
+                (_, false) if bits <= 128 => gt(r, max),
+                (_, false) => {
+                    let wrapped = and(iszero(iszero(a.clone())), iszero(eq(div(r.clone(), a), b)));
+                    match bits {
+                        256 => wrapped,
+                        _ => or(wrapped, gt(r, max)),
+                    }
+                }
+                (_, true) if bits <= 128 => narrow(r),
+                (_, true) => {
+                    let wrapped = or(
+                        and(
+                            iszero(iszero(a.clone())),
+                            iszero(eq(apply(Opcode::SDiv, &r, &a), b.clone())),
+                        ),
+                        and(eq(a, ones), eq(b, min)),
+                    );
+                    match bits {
+                        256 => wrapped,
+                        _ => or(wrapped, narrow(r)),
+                    }
+                }
+            };
+            self.panic_if(overflow, 0x11);
+        }
+        self.swap(2);
+        self.pop(2);
+    }
+}
@@ -0,0 +1,2991 @@
+use num_bigint::{BigInt, BigUint, Sign};
+use num_rational::BigRational;
+use num_traits::{One, ToPrimitive};
+
+use crate::mods::{
+    functions::{
+        controllers::{
+            abi::{canonical_type, library_signature, signature},
+            codegen::{
+                add, and, apply, ceil32, constant, div, env, eq, generate_bytecode, gt, iszero, lt,
+                mload, mul, not, or, shl, shr, sload, slot, sub, Codegen, Routine,
+                FREE_MEMORY_POINTER, ZERO_SLOT,
+            },
+        },
+        helpers::hash_helper::selector,
+    },
+    types::{
+        ast::{
+            BinaryOperator, CallArguments, ContractKind, ContractPart, Expression, ExpressionKind,
+            Literal, NamedArgument, StateMutability, TypeName, UnaryOperator, VariableDefinition,
+            VariableMutability, Visibility,
+        },
+        bindings::{ContractId, Declaration},
+        ir::{mask, Checked, Integer, Link, Word},
+        opcodes::Opcode,
+        semantic_types::Type,
+        settings::EvmVersion,
+    },
+};
+
+/* WHERE A REFERENCE VALUE LIVES. ON THE STACK IT IS A MEMORY POINTER OR A STORAGE SLOT.
+CALLDATA PARAMETERS ARE DECODED INTO MEMORY */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Memory,
+    Storage,
+}
+
+/* SOMETHING THAT CAN BE ASSIGNED TO, WITH ITS WORDS ON TOP OF THE STACK: NONE FOR A LOCAL OR
+AN IMMUTABLE, THE SLOT AND THE BYTE OFFSET OF A VALUE IN STORAGE, THE SLOT OF ANYTHING ELSE IN
+STORAGE AND THE ADDRESS OF A WORD OR A BYTE IN MEMORY */
+#[derive(Debug, Clone)]
+pub enum Lvalue {
+    Stack(usize),
+    StorageValue(Type),
+    StorageReference(Type),
+    Memory,
+    MemoryByte,
+    Immutable(usize),
+}
+
+impl Lvalue {
+    pub fn words(&self) -> usize {
+        match self {
+            Lvalue::Stack(_) | Lvalue::Immutable(_) => 0,
+            Lvalue::StorageValue(_) => 2,
+            Lvalue::StorageReference(_) | Lvalue::Memory | Lvalue::MemoryByte => 1,
+        }
+    }
+}
+
+pub fn slt(a: Word, b: Word) -> Word {
+    apply(Opcode::SLt, [a, b])
+}
+
+pub fn sgt(a: Word, b: Word) -> Word {
+    apply(Opcode::SGt, [a, b])
+}
+
+pub fn signextend(bytes: usize, value: Word) -> Word {
+    apply(Opcode::SignExtend, [constant(bytes), value])
+}
+
+/* THE CANONICAL WORD OF A VALUE OF `ty`: UNSIGNED VALUES ZERO EXTENDED, SIGNED ONES SIGN
+EXTENDED AND `bytesN` LEFT ALIGNED */
+pub fn clean(ty: &Type, value: Word) -> Word {
+    match ty {
+        Type::Integer {
+            signed: false,
+            bits,
+        } if *bits < 256 => and(value, constant(mask(*bits as usize))),
+        Type::Integer { signed: true, bits } if *bits < 256 => {
+            signextend(*bits as usize / 8 - 1, value)
+        }
+        Type::Address { .. } | Type::Contract { .. } => and(value, constant(mask(160))),
+        Type::FixedBytes(size) if *size < 32 => and(value, constant(high_mask(*size as usize))),
+        Type::Bool => iszero(iszero(value)),
+        _ => value,
+    }
+}
+
+/* THE TOP `bytes` BYTES OF A WORD SET */
+pub fn high_mask(bytes: usize) -> BigUint {
+    mask(256) ^ mask(256 - 8 * bytes)
+}
+
+/* A CONSTANT AS A WORD, NEGATIVE NUMBERS IN TWO'S COMPLEMENT */
+pub fn twos_complement(value: &BigInt) -> BigUint {
+    match value.sign() {
+        Sign::Minus => ((BigInt::one() << 256usize) + value)
+            .to_biguint()
+            .unwrap_or_default(),
+        _ => value.to_biguint().unwrap_or_default(),
+    }
+}
+
+/* BYTES LEFT ALIGNED IN A WORD, AS A `bytesN` */
+pub fn left_aligned(bytes: &[u8]) -> BigUint {
+    let mut word = [0u8; 32];
+    let length = bytes.len().min(32);
+    word[..length].copy_from_slice(&bytes[..length]);
+    BigUint::from_bytes_be(&word)
+}
+
+/* A FUNCTION OR GETTER AS CALLED FROM OUTSIDE */
+pub struct ExternalFunction {
+    pub signature: String,
+    pub parameters: Vec<Type>,
+    pub returns: Vec<Type>,
+    pub mutability: Option<StateMutability>,
+}
+
+fn is_reference(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::String
+            | Type::Bytes
+            | Type::Array { .. }
+            | Type::Struct { .. }
+            | Type::Mapping { .. }
+    )
+}
+
+impl<'a> Codegen<'a, '_> {
+    /* ------------------------------ TYPES ------------------------------ */
+
+    pub fn struct_fields(&self, ty: &Type) -> Vec<Type> {
+        self.struct_members(ty)
+            .into_iter()
+            .map(|(_, ty)| ty)
+            .collect()
+    }
+
+    pub fn struct_members(&self, ty: &Type) -> Vec<(&'a str, Type)> {
+        let Type::Struct {
+            file_id,
+            contract,
+            part,
+            ..
+        } = ty
+        else {
+            return Vec::new();
+        };
+        match self.part(Declaration::Member {
+            file_id: *file_id,
+            contract: *contract,
+            part: *part,
+        }) {
+            Some(ContractPart::Struct(definition)) => definition
+                .fields
+                .iter()
+                .map(|field| (field.name.name.as_str(), self.type_name(&field.ty.span)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn enum_size(&self, ty: &Type) -> usize {
+        let Type::Enum {
+            file_id,
+            contract,
+            part,
+            ..
+        } = ty
+        else {
+            return 0;
+        };
+        match self.part(Declaration::Member {
+            file_id: *file_id,
+            contract: *contract,
+            part: *part,
+        }) {
+            Some(ContractPart::Enum(definition)) => definition.values.len(),
+            _ => 0,
+        }
+    }
+
+    /* BYTES A VALUE TAKES IN STORAGE, 32 PER SLOT FOR WHAT TAKES WHOLE SLOTS */
+    pub fn storage_size(&mut self, ty: &Type) -> usize {
+        self.storage_type(ty)
+            .number_of_bytes
+            .to_usize()
+            .unwrap_or(32)
+    }
+
+    pub fn storage_slots(&mut self, ty: &Type) -> usize {
+        self.storage_size(ty).div_ceil(32).max(1)
+    }
+
+    /* WHERE ELEMENT `index` OF AN ARRAY WHOSE DATA STARTS AT `data` IS. ELEMENTS OF 16 BYTES
+    OR LESS SHARE SLOTS */
+    pub fn element_position(&mut self, base: &Type, data: Word, index: Word) -> (Word, Word) {
+        let size = self.storage_size(base);
+        if base.is_value_type() && size <= 16 {
+            let per_slot = 32 / size;
+            (
+                add(data, div(index.clone(), constant(per_slot))),
+                mul(
+                    apply(Opcode::Mod, [index, constant(per_slot)]),
+                    constant(size),
+                ),
+            )
+        } else {
+            let slots = self.storage_slots(base);
+            (add(data, mul(index, constant(slots))), constant(0u8))
+        }
+    }
+
+    fn to_storage(ty: &Type, value: Word) -> Word {
+        match ty {
+            Type::FixedBytes(size) if *size < 32 => shr(constant(256 - 8 * *size as usize), value),
+            Type::Integer { signed: true, bits } if *bits < 256 => {
+                and(value, constant(mask(*bits as usize)))
+            }
+            _ => value,
+        }
+    }
+
+    fn from_storage(ty: &Type, value: Word) -> Word {
+        match ty {
+            Type::FixedBytes(size) if *size < 32 => shl(constant(256 - 8 * *size as usize), value),
+            Type::Integer { signed: true, bits } if *bits < 256 => {
+                signextend(*bits as usize / 8 - 1, value)
+            }
+            _ => value,
+        }
+    }
+
+    pub fn storage_read(&mut self, ty: &Type, key: Word, offset: Word) -> Word {
+        let size = self.storage_size(ty);
+        if size >= 32 {
+            return Self::from_storage(ty, sload(key));
+        }
+        Self::from_storage(
+            ty,
+            and(
+                shr(mul(offset, constant(8u8)), sload(key)),
+                constant(mask(8 * size)),
+            ),
+        )
+    }
+
+    pub fn storage_write(&mut self, ty: &Type, key: Word, offset: Word, value: Word) {
+        let size = self.storage_size(ty);
+        if size >= 32 {
+            return self.exec(Opcode::SStore, [key, Self::to_storage(ty, value)]);
+        }
+        let shift = mul(offset, constant(8u8));
+        let field = constant(mask(8 * size));
+        let kept = and(sload(key.clone()), not(shl(shift.clone(), field.clone())));
+        let written = shl(shift, and(Self::to_storage(ty, value), field));
+        self.exec(Opcode::SStore, [key, or(kept, written)]);
+    }
+
+    /* PUSHES A VALUE READ FROM STORAGE, A MEMORY COPY FOR REFERENCE TYPES */
+    pub fn storage_load(&mut self, ty: &Type, key: Word, offset: Word) -> usize {
+        if ty.is_value_type() {
+            let value = self.storage_read(ty, key, offset);
+            return self.let_(value);
+        }
+        self.call_routine(Routine::StorageToMemory(ty.clone()), vec![key], 1);
+        self.ir.height
+    }
+
+    /* keccak256(word), HASHED IN SCRATCH SPACE */
+    pub fn hash_word(&mut self, word: Word) -> usize {
+        self.exec(Opcode::MStore, [constant(0u8), word]);
+        self.let_(apply(Opcode::Keccak256, [constant(0u8), constant(32u8)]))
+    }
+
+    /* PUSHES THE SLOT OF THE VALUE UNDER `key` IN THE MAPPING AT `key_slot` */
+    pub fn mapping_slot(&mut self, key_type: &Type, key: Word, key_slot: Word) {
+        if matches!(key_type, Type::String | Type::Bytes) {
+            return self.call_routine(Routine::MappingSlot, vec![key, key_slot], 1);
+        }
+        self.exec(Opcode::MStore, [constant(0u8), key]);
+        self.exec(Opcode::MStore, [constant(32u8), key_slot]);
+        self.eval(&apply(Opcode::Keccak256, [constant(0u8), constant(64u8)]));
+    }
+
+    /* `length` OF `string` OR `bytes` STORED AT `key`: THE LOW BYTE HOLDS TWICE THE LENGTH OF
+    SHORT DATA, THE WHOLE SLOT TWICE THE LENGTH PLUS ONE OF LONG DATA */
+    pub fn storage_bytes_length(value: Word) -> Word {
+        and(
+            shr(constant(1u8), value.clone()),
+            or(
+                constant(0x7fu8),
+                mul(and(value, constant(1u8)), not(constant(0u8))),
+            ),
+        )
+    }
+
+    pub fn allocate(&mut self, size: Word) -> usize {
+        self.call_routine(Routine::Allocate, vec![size], 1);
+        self.ir.height
+    }
+
+    /* CALLDATA PAST ITS END READS AS ZEROS, THE CHEAPEST WAY TO CLEAR MEMORY */
+    pub fn zero_memory(&mut self, start: Word, length: Word) {
+        self.exec(
+            Opcode::CallDataCopy,
+            [start, env(Opcode::CallDataSize), length],
+        );
+    }
+
+    pub fn copy_memory(&mut self, source: Word, target: Word, length: Word) {
+        if self.context.settings.evm_version >= EvmVersion::Cancun {
+            return self.exec(Opcode::MCopy, [target, source, length]);
+        }
+        self.call_routine(Routine::CopyMemory, vec![source, target, length], 0);
+    }
+
+    /* A `string` OR `bytes` IN MEMORY HOLDING `bytes` */
+    pub fn memory_bytes(&mut self, bytes: &[u8]) -> usize {
+        if bytes.is_empty() {
+            return self.let_(constant(ZERO_SLOT));
+        }
+        let pointer = self.allocate(constant(32 + bytes.len().div_ceil(32) * 32));
+        self.exec(Opcode::MStore, [slot(pointer), constant(bytes.len())]);
+        for (index, chunk) in bytes.chunks(32).enumerate() {
+            self.exec(
+                Opcode::MStore,
+                [
+                    add(slot(pointer), constant(32 + 32 * index)),
+                    constant(left_aligned(chunk)),
+                ],
+            );
+        }
+        pointer
+    }
+
+    /* ------------------------------ LOCATIONS ------------------------------ */
+
+    pub fn location(&self, expression: &'a Expression) -> Location {
+        match &expression.kind {
+            ExpressionKind::Variable(identifier) => {
+                match self.declarations(&identifier.span).first() {
+                    Some(Declaration::Local(span)) if self.frame.storage_locals.contains(span) => {
+                        Location::Storage
+                    }
+                    Some(declaration) => self.declaration_location(*declaration),
+                    None => Location::Memory,
+                }
+            }
+            ExpressionKind::MemberAccess { expression, member } => {
+                match self.declarations(&member.span).first() {
+                    Some(declaration) => self.declaration_location(*declaration),
+                    None => match self.expression_type(expression) {
+                        Type::Struct { .. } => self.location(expression),
+                        _ => Location::Memory,
+                    },
+                }
+            }
+            ExpressionKind::Index { base, .. } => match self.expression_type(base) {
+                Type::Mapping { .. } => Location::Storage,
+                Type::Array { .. } | Type::Bytes => self.location(base),
+                _ => Location::Memory,
+            },
+            ExpressionKind::Conditional { then, .. } => self.location(then),
+            ExpressionKind::FunctionCall { callee, arguments } => {
+                match self.expression_type(callee) {
+                    Type::TypeType(ty) if !matches!(*ty, Type::Struct { .. }) => match arguments {
+                        CallArguments::Positional(_arguments) if _arguments.len() == 1 => {
+                            self.location(&_arguments[0])
+                        }
+                        _ => Location::Memory,
+                    },
+                    Type::Function(_) | Type::Overloaded(_) => {
+                        match self.internal_target(expression) {
+                            Some((declaration, _)) => match self.part(declaration) {
+                                Some(ContractPart::Function(function)) => function
+                                    .returns
+                                    .first()
+                                    .map_or(Location::Memory, Self::parameter_location),
+                                _ => Location::Memory,
+                            },
+                            None => Location::Memory,
+                        }
+                    }
+                    _ => Location::Memory,
+                }
+            }
+            _ => Location::Memory,
+        }
+    }
+
+    fn declaration_location(&self, declaration: Declaration) -> Location {
+        match self.part(declaration) {
+            Some(ContractPart::StateVariable(variable))
+                if !matches!(
+                    variable.mutability,
+                    Some(VariableMutability::Constant | VariableMutability::Immutable)
+                ) =>
+            {
+                Location::Storage
+            }
+            _ => Location::Memory,
+        }
+    }
+
+    /* ------------------------------ LVALUES ------------------------------ */
+
+    pub fn state_variable_lvalue(
+        &mut self,
+        variable: &'a VariableDefinition,
+        ty: &Type,
+    ) -> Option<Lvalue> {
+        if variable.mutability == Some(VariableMutability::Immutable) {
+            return Some(Lvalue::Immutable(self.immutable_id(variable)));
+        }
+        let Some((key, offset)) = self.storage_position(variable) else {
+            self.unsupported(
+                variable.name.span,
+                "state variables outside the storage layout",
+            );
+            return None;
+        };
+        self.push(key);
+        if ty.is_value_type() {
+            self.push(offset);
+            return Some(Lvalue::StorageValue(ty.clone()));
+        }
+        Some(Lvalue::StorageReference(ty.clone()))
+    }
+
+    /* PUSHES THE VALUE OF A STATE VARIABLE: CONSTANTS ARE COMPILED WHERE THEY ARE USED */
+    pub fn state_variable(&mut self, variable: &'a VariableDefinition, ty: &Type) {
+        if variable.mutability == Some(VariableMutability::Constant) {
+            return match &variable.initializer {
+                Some(_initializer) => self.expression_to(_initializer, ty, Location::Memory),
+                None => self.push(0u8),
+            };
+        }
+        if let Some(_lvalue) = self.state_variable_lvalue(variable, ty) {
+            self.load(&_lvalue);
+        }
+    }
+
+    /* PUSHES THE WORDS OF WHAT `expression` NAMES */
+    pub fn lvalue(&mut self, expression: &'a Expression) -> Option<Lvalue> {
+        match &expression.kind {
+            ExpressionKind::Variable(identifier) => {
+                let declaration = *self.declarations(&identifier.span).first()?;
+                self.declaration_lvalue(declaration, expression)
+            }
+            ExpressionKind::MemberAccess {
+                expression: base,
+                member,
+            } => {
+                if let Some(_declaration) = self.declarations(&member.span).first() {
+                    return self.declaration_lvalue(*_declaration, expression);
+                }
+                let base_type = self.expression_type(base);
+                let members = self.struct_members(&base_type);
+                let index = members.iter().position(|(name, _)| *name == member.name)?;
+                let field = members[index].1.clone();
+                let location = self.location(base);
+                self.expression(base);
+                let pointer = self.ir.height;
+                if location == Location::Memory {
+                    self.replace(1, add(slot(pointer), constant(32 * index)));
+                    return Some(Lvalue::Memory);
+                }
+                let layout = self.storage_type(&base_type).members.unwrap_or_default();
+                let position = layout
+                    .iter()
+                    .find(|candidate| candidate.label == member.name)?;
+                self.replace(1, add(slot(pointer), constant(position.slot.clone())));
+                if field.is_value_type() {
+                    self.push(position.offset);
+                    return Some(Lvalue::StorageValue(field));
+                }
+                Some(Lvalue::StorageReference(field))
+            }
+            ExpressionKind::Index {
+                base,
+                index: Some(index),
+            } => {
+                let base_type = self.expression_type(base);
+                let location = self.location(base);
+                match &base_type {
+                    Type::Mapping { key, value } => {
+                        self.expression(base);
+                        self.expression_to(index, key, Location::Memory);
+                        let height = self.ir.height;
+                        self.mapping_slot(key, slot(height), slot(height - 1));
+                        self.squash(2);
+                        if value.is_value_type() {
+                            self.push(0u8);
+                            return Some(Lvalue::StorageValue((**value).clone()));
+                        }
+                        Some(Lvalue::StorageReference((**value).clone()))
+                    }
+                    Type::Array {
+                        base: element,
+                        length,
+                    } => {
+                        self.expression(base);
+                        self.expression_to(index, &Type::UINT256, Location::Memory);
+                        let (array, position) = (self.ir.height - 1, self.ir.height);
+                        if location == Location::Storage {
+                            self.call_routine(
+                                Routine::StorageIndex(base_type.clone()),
+                                vec![slot(array), slot(position)],
+                                2,
+                            );
+                            self.keep(array - 1, &[array + 2, array + 3]);
+                            if element.is_value_type() {
+                                return Some(Lvalue::StorageValue((**element).clone()));
+                            }
+                            self.pop(1);
+                            return Some(Lvalue::StorageReference((**element).clone()));
+                        }
+                        let (size, first) = match length {
+                            Some(_length) => {
+                                (constant(_length.to_biguint().unwrap_or_default()), 0usize)
+                            }
+                            None => (mload(slot(array)), 32),
+                        };
+                        self.panic_if(iszero(lt(slot(position), size)), 0x32);
+                        self.replace(
+                            2,
+                            add(
+                                slot(array),
+                                add(constant(first), mul(slot(position), constant(32u8))),
+                            ),
+                        );
+                        Some(Lvalue::Memory)
+                    }
+                    Type::Bytes if location == Location::Memory => {
+                        self.expression(base);
+                        self.expression_to(index, &Type::UINT256, Location::Memory);
+                        let (array, position) = (self.ir.height - 1, self.ir.height);
+                        self.panic_if(iszero(lt(slot(position), mload(slot(array)))), 0x32);
+                        self.replace(2, add(slot(array), add(constant(32u8), slot(position))));
+                        Some(Lvalue::MemoryByte)
+                    }
+                    _ => {
+                        self.unsupported(expression.span, "indexing this value");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn declaration_lvalue(
+        &mut self,
+        declaration: Declaration,
+        expression: &'a Expression,
+    ) -> Option<Lvalue> {
+        match declaration {
+            Declaration::Local(span) => match self.frame.locals.get(&span) {
+                Some(_position) => Some(Lvalue::Stack(*_position)),
+                None => {
+                    self.unsupported(expression.span, "this variable here");
+                    None
+                }
+            },
+            _ => match self.part(declaration) {
+                Some(ContractPart::StateVariable(variable))
+                    if variable.mutability != Some(VariableMutability::Constant) =>
+                {
+                    let ty = self.type_name(&variable.ty.span);
+                    self.state_variable_lvalue(variable, &ty)
+                }
+                _ => None,
+            },
+        }
+    }
+
+    /* REPLACES THE WORDS OF `lvalue` WITH ITS VALUE */
+    pub fn load(&mut self, lvalue: &Lvalue) {
+        let height = self.ir.height;
+        match lvalue {
+            Lvalue::Stack(position) => self.dup(*position),
+            Lvalue::StorageValue(ty) => {
+                let value = self.storage_read(ty, slot(height - 1), slot(height));
+                self.replace(2, value);
+            }
+            Lvalue::StorageReference(_) => {}
+            Lvalue::Memory => self.replace(1, mload(slot(height))),
+            Lvalue::MemoryByte => self.replace(1, and(mload(slot(height)), constant(high_mask(1)))),
+            Lvalue::Immutable(id) if self.creation => {
+                self.eval(&mload(constant(0x80 + 32 * id)));
+            }
+            Lvalue::Immutable(id) => self.eval(&Word::Link(Link::Immutable(*id))),
+        }
+    }
+
+    /* STORES THE VALUE UNDER THE WORDS OF `lvalue` AND POPS BOTH. `location` IS WHERE A
+    REFERENCE VALUE IS */
+    pub fn store(&mut self, lvalue: &Lvalue, location: Location) {
+        let height = self.ir.height;
+        match lvalue {
+            Lvalue::Stack(position) => self.store_top(*position),
+            Lvalue::StorageValue(ty) => {
+                self.storage_write(ty, slot(height - 1), slot(height), slot(height - 2));
+                self.pop(3);
+            }
+            Lvalue::StorageReference(Type::Mapping { .. }) => self.pop(2),
+            Lvalue::StorageReference(ty) => {
+                let mut value = slot(height - 1);
+                if location == Location::Storage {
+                    self.call_routine(Routine::StorageToMemory(ty.clone()), vec![value], 1);
+                    value = slot(self.ir.height);
+                }
+                self.call_routine(
+                    Routine::MemoryToStorage(ty.clone()),
+                    vec![value, slot(height)],
+                    0,
+                );
+                self.pop_to(height - 2);
+            }
+            Lvalue::Memory => {
+                self.exec(Opcode::MStore, [slot(height), slot(height - 1)]);
+                self.pop(2);
+            }
+            Lvalue::MemoryByte => {
+                self.exec(
+                    Opcode::MStore8,
+                    [slot(height), shr(constant(248u8), slot(height - 1))],
+                );
+                self.pop(2);
+            }
+            Lvalue::Immutable(id) => {
+                self.exec(Opcode::MStore, [constant(0x80 + 32 * id), slot(height)]);
+                self.pop(1);
+            }
+        }
+    }
+
+    /* ------------------------------ EXPRESSIONS ------------------------------ */
+
+    /* PUSHES THE VALUE OF `expression`, ONE WORD PER COMPONENT OF ITS TYPE */
+    pub fn expression(&mut self, expression: &'a Expression) {
+        let ty = self.expression_type(expression);
+        if matches!(ty, Type::RationalConstant(..) | Type::StringLiteral(_)) {
+            return self.expression_to(expression, &ty.mobile(), Location::Memory);
+        }
+        match &expression.kind {
+            ExpressionKind::Literal(Literal::Bool(value)) => self.push(*value as u8),
+            ExpressionKind::Literal(Literal::Address(value)) => {
+                self.push(BigUint::from_bytes_be(
+                    &hex::decode(&value[2..]).unwrap_or_default(),
+                ));
+            }
+            ExpressionKind::Literal(_) => self.unsupported(expression.span, "this literal"),
+            ExpressionKind::Variable(_)
+            | ExpressionKind::MemberAccess { .. }
+            | ExpressionKind::Index { .. } => {
+                if self.rvalue(expression) {
+                    return;
+                }
+                match self.lvalue(expression) {
+                    Some(_lvalue) => self.load(&_lvalue),
+                    None => self.unsupported(expression.span, "this expression"),
+                }
+            }
+            ExpressionKind::FunctionCall { .. } => self.call(expression),
+            ExpressionKind::Unary { operator, operand } => self.unary(*operator, operand, true),
+            ExpressionKind::Binary {
+                operator,
+                left,
+                right,
+            } => self.binary(expression, *operator, left, right),
+            ExpressionKind::Assignment {
+                operator,
+                left,
+                right,
+            } => self.assignment(*operator, left, right, true),
+            ExpressionKind::Conditional { .. } | ExpressionKind::ArrayLiteral(_) => {
+                let location = self.location(expression);
+                self.expression_to(expression, &ty, location)
+            }
+            ExpressionKind::Tuple(components) => {
+                for component in components.iter().flatten() {
+                    self.expression(component);
+                }
+            }
+            ExpressionKind::ElementaryType(_)
+            | ExpressionKind::CallOptions { .. }
+            | ExpressionKind::New(_) => self.unsupported(expression.span, "this expression"),
+        }
+    }
+
+    /* PUSHES THE VALUE OF `expression` AS `target`, COPYING IT TO MEMORY WHEN IT IS IN STORAGE
+    AND `location` IS MEMORY */
+    pub fn expression_to(&mut self, expression: &'a Expression, target: &Type, location: Location) {
+        let ty = self.expression_type(expression);
+        match (&ty, target) {
+            (Type::RationalConstant(value, _), _) => {
+                return self.rational(expression, value, target);
+            }
+            (Type::StringLiteral(bytes), Type::FixedBytes(_)) => {
+                return self.push(left_aligned(bytes));
+            }
+            (Type::StringLiteral(bytes), _) => {
+                self.memory_bytes(bytes);
+                return;
+            }
+            _ => {}
+        }
+        match &expression.kind {
+            ExpressionKind::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let height = self.ir.height;
+                let other = self.ir.new_block();
+                let end = self.ir.new_block();
+                self.expression_to(condition, &Type::Bool, Location::Memory);
+                self.op(Opcode::IsZero);
+                self.ir.jump_if(other);
+                self.expression_to(then, target, location);
+                self.ir.jump(end);
+                self.ir.height = height;
+                self.ir.start(other);
+                self.expression_to(otherwise, target, location);
+                self.ir.start(end);
+                return;
+            }
+            ExpressionKind::ArrayLiteral(elements) => {
+                let element = match target {
+                    Type::Array { base, .. } => (**base).clone(),
+                    _ => Type::Unknown,
+                };
+                let height = self.ir.height;
+                let array = self.allocate(constant(32 * elements.len()));
+                for (index, value) in elements.iter().enumerate() {
+                    self.expression_to(value, &element, Location::Memory);
+                    let top = self.ir.height;
+                    self.exec(
+                        Opcode::MStore,
+                        [add(slot(array), constant(32 * index)), slot(top)],
+                    );
+                    self.pop(1);
+                }
+                self.keep(height, &[array]);
+                return;
+            }
+            ExpressionKind::Tuple(components) => {
+                if let Type::Tuple(_targets) = target {
+                    for (component, target) in components.iter().zip(_targets) {
+                        if let (Some(_component), Some(_target)) = (component, target) {
+                            self.expression_to(_component, _target, location);
+                        }
+                    }
+                    return;
+                }
+            }
+            _ => {}
+        }
+        let from = self.location(expression);
+        self.expression(expression);
+        if location == Location::Memory
+            && from == Location::Storage
+            && is_reference(&ty)
+            && !matches!(ty, Type::Mapping { .. })
+        {
+            let height = self.ir.height;
+            self.call_routine(Routine::StorageToMemory(ty), vec![slot(height)], 1);
+            self.squash(1);
+        }
+    }
+
+    fn rational(&mut self, expression: &Expression, value: &BigRational, target: &Type) {
+        if !value.is_integer() {
+            return self.unsupported(expression.span, "fractional numbers");
+        }
+        let value = value.to_integer();
+        match target {
+            Type::FixedBytes(size) => {
+                let unsigned = value.to_biguint().unwrap_or_default();
+                self.push((unsigned << (256 - 8 * *size as usize)) & mask(256));
+            }
+            _ => self.push(twos_complement(&value)),
+        }
+    }
+
+    /* MEMBERS AND INDICES THAT ARE VALUES RATHER THAN PLACES. FALSE WHEN `expression` IS NOT
+    ONE OF THEM */
+    fn rvalue(&mut self, expression: &'a Expression) -> bool {
+        match &expression.kind {
+            ExpressionKind::Variable(identifier) => {
+                match self.declarations(&identifier.span).first() {
+                    Some(_declaration) => self.declaration_value(*_declaration, expression),
+                    None => false,
+                }
+            }
+            ExpressionKind::MemberAccess {
+                expression: base,
+                member,
+            } => {
+                if let Some(_declaration) = self.declarations(&member.span).first() {
+                    return self.declaration_value(*_declaration, expression);
+                }
+                self.member_value(expression, base, &member.name)
+            }
+            ExpressionKind::Index {
+                base,
+                index: Some(index),
+            } => {
+                let Type::FixedBytes(size) = self.expression_type(base) else {
+                    return false;
+                };
+                self.expression(base);
+                let index_type = self.expression_type(index).mobile();
+                self.expression_to(index, &index_type, Location::Memory);
+                let (value, position) = (self.ir.height - 1, self.ir.height);
+                self.panic_if(iszero(lt(slot(position), constant(size))), 0x32);
+                self.replace(
+                    2,
+                    shl(
+                        constant(248u8),
+                        apply(Opcode::Byte, [slot(position), slot(value)]),
+                    ),
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn declaration_value(&mut self, declaration: Declaration, expression: &'a Expression) -> bool {
+        match declaration {
+            Declaration::Builtin("this") => {
+                self.op(Opcode::Address);
+                true
+            }
+            Declaration::EnumValue { value, .. } => {
+                self.push(value);
+                true
+            }
+            _ => match self.part(declaration) {
+                Some(ContractPart::StateVariable(variable))
+                    if variable.mutability == Some(VariableMutability::Constant) =>
+                {
+                    let ty = self.type_name(&variable.ty.span);
+                    self.state_variable(variable, &ty);
+                    true
+                }
+                Some(ContractPart::Function(_)) => {
+                    self.unsupported(expression.span, "functions as values");
+                    true
+                }
+                _ => false,
+            },
+        }
+    }
+
+    fn member_value(
+        &mut self,
+        expression: &'a Expression,
+        base: &'a Expression,
+        name: &str,
+    ) -> bool {
+        let base_type = self.expression_type(base);
+        let word = match (&base_type, name) {
+            (Type::Magic("msg"), "sender") => env(Opcode::Caller),
+            (Type::Magic("msg"), "value") => env(Opcode::CallValue),
+            (Type::Magic("msg"), "gas") => env(Opcode::Gas),
+            (Type::Magic("msg"), "sig") => and(
+                apply(Opcode::CallDataLoad, [constant(0u8)]),
+                constant(high_mask(4)),
+            ),
+            (Type::Magic("msg"), "data") => {
+                let height = self.ir.height;
+                let size = self.let_(env(Opcode::CallDataSize));
+                let pointer = self.allocate(add(slot(size), constant(32u8)));
+                self.exec(Opcode::MStore, [slot(pointer), slot(size)]);
+                self.exec(
+                    Opcode::CallDataCopy,
+                    [
+                        add(slot(pointer), constant(32u8)),
+                        constant(0u8),
+                        slot(size),
+                    ],
+                );
+                self.keep(height, &[pointer]);
+                return true;
+            }
+            (Type::Magic("block"), "coinbase") => env(Opcode::Coinbase),
+            (Type::Magic("block"), "timestamp") => env(Opcode::Timestamp),
+            (Type::Magic("block"), "number") => env(Opcode::Number),
+            (Type::Magic("block"), "chainid") => env(Opcode::ChainId),
+            (Type::Magic("block"), "basefee") => env(Opcode::BaseFee),
+            (Type::Magic("block"), "gaslimit") => env(Opcode::GasLimit),
+            (Type::Magic("block"), "difficulty" | "prevrandao") => env(Opcode::PrevRandao),
+            (Type::Magic("block"), "blobbasefee")
+                if self.context.settings.evm_version >= EvmVersion::Cancun =>
+            {
+                env(Opcode::BlobBaseFee)
+            }
+            (Type::Magic("tx"), "origin") => env(Opcode::Origin),
+            (Type::Magic("tx"), "gasprice") => env(Opcode::GasPrice),
+            (Type::Magic(_), _) => {
+                self.unsupported(expression.span, format!("`{name}` on this EVM version"));
+                return true;
+            }
+            (Type::FixedBytes(size), "length") => constant(*size),
+            (
+                Type::Array {
+                    length: Some(length),
+                    ..
+                },
+                "length",
+            ) => constant(length.to_biguint().unwrap_or_default()),
+            (Type::Array { .. } | Type::Bytes | Type::String, "length") => {
+                let location = self.location(base);
+                self.expression(base);
+                let top = slot(self.ir.height);
+                let length = match (location, &base_type) {
+                    (Location::Memory, _) => mload(top),
+                    (Location::Storage, Type::Array { .. }) => sload(top),
+                    (Location::Storage, _) => Self::storage_bytes_length(sload(top)),
+                };
+                self.replace(1, length);
+                return true;
+            }
+            (Type::Address { .. }, "balance" | "codehash" | "code") => {
+                self.expression(base);
+                let address = self.ir.height;
+                match name {
+                    "balance" => self.replace(1, apply(Opcode::Balance, [slot(address)])),
+                    "codehash" => self.replace(1, apply(Opcode::ExtCodeHash, [slot(address)])),
+                    _ => {
+                        let size = self.let_(apply(Opcode::ExtCodeSize, [slot(address)]));
+                        let pointer = self.allocate(add(slot(size), constant(32u8)));
+                        self.exec(Opcode::MStore, [slot(pointer), slot(size)]);
+                        self.exec(
+                            Opcode::ExtCodeCopy,
+                            [
+                                slot(address),
+                                add(slot(pointer), constant(32u8)),
+                                constant(0u8),
+                                slot(size),
+                            ],
+                        );
+                        self.keep(address - 1, &[pointer]);
+                    }
+                }
+                return true;
+            }
+            (Type::Function(_) | Type::Overloaded(_), "selector") => {
+                match self.function_signature(base) {
+                    Some(_signature) => {
+                        constant(BigUint::from_bytes_be(&selector(&_signature)) << 224)
+                    }
+                    None => {
+                        self.unsupported(expression.span, "this selector");
+                        return true;
+                    }
+                }
+            }
+            (
+                Type::BuiltinMember {
+                    name: "type",
+                    receiver,
+                },
+                _,
+            ) => return self.type_member(expression, receiver, name),
+            _ => return false,
+        };
+        self.eval(&word);
+        true
+    }
+
+    /* `type(T).min`, `type(C).name` AND THE LIKE */
+    fn type_member(&mut self, expression: &'a Expression, receiver: &Type, name: &str) -> bool {
+        match (receiver, name) {
+            (Type::Integer { signed, bits }, "min" | "max") => {
+                let (min, max) = Type::integer_range(*signed, *bits);
+                self.push(twos_complement(if name == "min" { &min } else { &max }));
+            }
+            (Type::Enum { .. }, "min") => self.push(0u8),
+            (Type::Enum { .. }, "max") => self.push(self.enum_size(receiver).saturating_sub(1)),
+            (
+                Type::Contract {
+                    name: contract_name,
+                    ..
+                },
+                "name",
+            ) => {
+                self.memory_bytes(contract_name.as_bytes());
+            }
+            (
+                Type::Contract {
+                    file_id, contract, ..
+                },
+                "interfaceId",
+            ) => {
+                let context = self.context;
+                let mut id = [0u8; 4];
+                for part in &self.definition((*file_id, *contract)).parts {
+                    if let ContractPart::Function(function) = part {
+                        if let Some(_name) = &function.name {
+                            let signature = signature(
+                                &_name.name,
+                                &function.parameters,
+                                context.graph,
+                                context.types,
+                            );
+                            for (byte, selector_byte) in id.iter_mut().zip(selector(&signature)) {
+                                *byte ^= selector_byte;
+                            }
+                        }
+                    }
+                }
+                self.push(BigUint::from_bytes_be(&id) << 224);
+            }
+            (
+                Type::Contract {
+                    file_id, contract, ..
+                },
+                "creationCode" | "runtimeCode",
+            ) => {
+                let creation = name == "creationCode";
+                let Some(section) = self.embedded_code((*file_id, *contract), creation, expression)
+                else {
+                    self.push(0u8);
+                    return true;
+                };
+                let size = Word::Link(Link::DataSize(section));
+                let pointer = self.allocate(add(size.clone(), constant(32u8)));
+                self.exec(Opcode::MStore, [slot(pointer), size.clone()]);
+                self.exec(
+                    Opcode::CodeCopy,
+                    [
+                        add(slot(pointer), constant(32u8)),
+                        Word::Link(Link::DataOffset(section)),
+                        size,
+                    ],
+                );
+            }
+            _ => self.unsupported(expression.span, format!("`type(..).{name}`")),
+        }
+        true
+    }
+
+    /* THE DATA SECTION HOLDING THE CODE OF ANOTHER CONTRACT */
+    pub fn embedded_code(
+        &mut self,
+        contract: ContractId,
+        creation: bool,
+        expression: &Expression,
+    ) -> Option<usize> {
+        if let Some(_section) = self.embedded.get(&(contract, creation)) {
+            return Some(*_section);
+        }
+        let bytecode = if contract == self.contract {
+            None
+        } else {
+            generate_bytecode(contract, self.context, self.cache, self.diagnostics)
+        };
+        let Some(_bytecode) = bytecode else {
+            self.unsupported(
+                expression.span,
+                "code of a contract that cannot be compiled here, such as one that creates this one",
+            );
+            return None;
+        };
+        let code = if creation {
+            _bytecode.creation
+        } else {
+            _bytecode.runtime
+        };
+        let section = self.ir.add_data(code);
+        self.embedded.insert((contract, creation), section);
+        Some(section)
+    }
+
+    /* `f(..)` SIGNATURE OF THE FUNCTION AN EXPRESSION NAMES, FOR `.selector` AND
+    `abi.encodeCall` */
+    fn function_signature(&self, expression: &'a Expression) -> Option<String> {
+        let declaration = match &expression.kind {
+            ExpressionKind::Variable(identifier) => *self.declarations(&identifier.span).first()?,
+            ExpressionKind::MemberAccess {
+                expression: base,
+                member,
+            } => match self.declarations(&member.span).first() {
+                Some(_declaration) => *_declaration,
+                None => match self.expression_type(base) {
+                    Type::Contract {
+                        file_id, contract, ..
+                    } => *self
+                        .external_candidates((file_id, contract), &member.name)
+                        .first()?,
+                    _ => return None,
+                },
+            },
+            _ => return None,
+        };
+        self.external_signature(declaration)
+            .map(|function| function.signature)
+    }
+
+    /* ------------------------------ OPERATORS ------------------------------ */
+
+    pub fn unary(&mut self, operator: UnaryOperator, operand: &'a Expression, keep: bool) {
+        let ty = self.expression_type(operand);
+        match operator {
+            UnaryOperator::Not => {
+                self.expression_to(operand, &Type::Bool, Location::Memory);
+                let top = self.ir.height;
+                self.replace(1, iszero(slot(top)));
+            }
+            UnaryOperator::BitNot => {
+                self.expression(operand);
+                let top = self.ir.height;
+                self.replace(1, clean(&ty, not(slot(top))));
+            }
+            UnaryOperator::Negate => {
+                self.expression(operand);
+                let top = self.ir.height;
+                if let Type::Integer { bits, .. } = ty {
+                    if !self.frame.unchecked {
+                        let min = twos_complement(&-(BigInt::one() << (bits as usize - 1)));
+                        self.panic_if(eq(slot(top), constant(min)), 0x11);
+                    }
+                }
+                self.replace(1, clean(&ty, sub(constant(0u8), slot(top))));
+            }
+            UnaryOperator::PreIncrement => {
+                self.step(operand, &ty, BinaryOperator::Add, false, keep)
+            }
+            UnaryOperator::PreDecrement => {
+                self.step(operand, &ty, BinaryOperator::Sub, false, keep)
+            }
+            UnaryOperator::PostIncrement => {
+                self.step(operand, &ty, BinaryOperator::Add, true, keep)
+            }
+            UnaryOperator::PostDecrement => {
+                self.step(operand, &ty, BinaryOperator::Sub, true, keep)
+            }
+            UnaryOperator::Delete => self.delete(operand, &ty),
+        }
+    }
+
+    /* `++` AND `--`, LEAVING THE OLD OR THE NEW VALUE WHEN `keep` */
+    fn step(
+        &mut self,
+        operand: &'a Expression,
+        ty: &Type,
+        operator: BinaryOperator,
+        post: bool,
+        keep: bool,
+    ) {
+        let Some(lvalue) = self.lvalue(operand) else {
+            return self.unsupported(operand.span, "assigning to this expression");
+        };
+        let count = lvalue.words();
+        let base = self.ir.height - count;
+        for index in 1..=count {
+            self.dup(base + index);
+        }
+        self.load(&lvalue);
+        if keep && post {
+            self.dup(self.ir.height);
+            self.rotate_down(count + 1);
+        }
+        self.push(1u8);
+        self.arithmetic(operator, ty);
+        if keep && !post {
+            self.dup(self.ir.height);
+            self.rotate_down(count + 1);
+        }
+        self.rotate_down(count);
+        self.store(&lvalue, Location::Memory);
+    }
+
+    fn delete(&mut self, operand: &'a Expression, ty: &Type) {
+        if !ty.is_value_type() && self.location(operand) == Location::Storage {
+            self.expression(operand);
+            let top = self.ir.height;
+            if !matches!(ty, Type::Mapping { .. }) {
+                self.call_routine(Routine::ClearStorage(ty.clone()), vec![slot(top)], 0);
+            }
+            return self.pop(1);
+        }
+        self.default_value(ty, Location::Memory);
+        match self.lvalue(operand) {
+            Some(_lvalue) => self.store(&_lvalue, Location::Memory),
+            None => self.unsupported(operand.span, "deleting this expression"),
+        }
+    }
+
+    fn binary(
+        &mut self,
+        expression: &'a Expression,
+        operator: BinaryOperator,
+        left: &'a Expression,
+        right: &'a Expression,
+    ) {
+        let ty = self.expression_type(expression);
+        match operator {
+            BinaryOperator::And | BinaryOperator::Or => {
+                let end = self.ir.new_block();
+                self.expression_to(left, &Type::Bool, Location::Memory);
+                self.dup(self.ir.height);
+                if operator == BinaryOperator::And {
+                    self.op(Opcode::IsZero);
+                }
+                self.ir.jump_if(end);
+                self.pop(1);
+                self.expression_to(right, &Type::Bool, Location::Memory);
+                self.ir.start(end);
+            }
+            BinaryOperator::Eq
+            | BinaryOperator::Ne
+            | BinaryOperator::Lt
+            | BinaryOperator::Le
+            | BinaryOperator::Gt
+            | BinaryOperator::Ge => {
+                let common = match (self.expression_type(left), self.expression_type(right)) {
+                    (Type::RationalConstant(..), right_type) => right_type.mobile(),
+                    (left_type, _) => left_type.mobile(),
+                };
+                self.expression_to(left, &common, Location::Memory);
+                self.expression_to(right, &common, Location::Memory);
+                let (a, b) = (slot(self.ir.height - 1), slot(self.ir.height));
+                let signed = matches!(common, Type::Integer { signed: true, .. });
+                let (less, greater) = if signed {
+                    (slt(a.clone(), b.clone()), sgt(a.clone(), b.clone()))
+                } else {
+                    (lt(a.clone(), b.clone()), gt(a.clone(), b.clone()))
+                };
+                let word = match operator {
+                    BinaryOperator::Eq => eq(a, b),
+                    BinaryOperator::Ne => iszero(eq(a, b)),
+                    BinaryOperator::Lt => less,
+                    BinaryOperator::Gt => greater,
+                    BinaryOperator::Le => iszero(greater),
+                    _ => iszero(less),
+                };
+                self.replace(2, word);
+            }
+            BinaryOperator::Shl | BinaryOperator::Shr | BinaryOperator::Exp => {
+                self.expression_to(left, &ty, Location::Memory);
+                let right_type = self.expression_type(right).mobile();
+                self.expression_to(right, &right_type, Location::Memory);
+                if operator == BinaryOperator::Exp {
+                    if let Type::Integer { signed: true, .. } = right_type {
+                        return self.unsupported(expression.span, "signed exponents");
+                    }
+                }
+                self.arithmetic(operator, &ty);
+            }
+            _ => {
+                self.expression_to(left, &ty, Location::Memory);
+                self.expression_to(right, &ty, Location::Memory);
+                self.arithmetic(operator, &ty);
+            }
+        }
+    }
+
+    /* REPLACES THE TWO OPERANDS ON TOP OF THE STACK WITH THE RESULT. OUTSIDE `unchecked`
+    BLOCKS INTEGER ARITHMETIC IS A CHECKED OPERATION OF THE IR, WHICH THE BACKEND EXPANDS */
+    pub fn arithmetic(&mut self, operator: BinaryOperator, ty: &Type) {
+        let base = self.ir.height - 2;
+        let (a, b) = (slot(base + 1), slot(base + 2));
+        let (signed, bits) = match ty {
+            Type::Integer { signed, bits } => (*signed, *bits as usize),
+            _ => (false, 256),
+        };
+        let checked = !self.frame.unchecked && ty.is_integer();
+        let operation = match operator {
+            BinaryOperator::Add => Some(Checked::Add),
+            BinaryOperator::Sub => Some(Checked::Sub),
+            BinaryOperator::Mul => Some(Checked::Mul),
+            BinaryOperator::Div => Some(Checked::Div),
+            BinaryOperator::Mod => Some(Checked::Mod),
+            _ => None,
+        };
+        if let (Some(_operation), true) = (operation, checked) {
+            let integer = Integer {
+                bits: bits as u16,
+                signed,
+            };
+            return self.replace(2, Word::Checked(_operation, integer, vec![a, b]));
+        }
+        let result = match operator {
+            BinaryOperator::Add => add(a.clone(), b.clone()),
+            BinaryOperator::Sub => sub(a.clone(), b.clone()),
+            BinaryOperator::Mul => mul(a.clone(), b.clone()),
+            BinaryOperator::Div | BinaryOperator::Mod => {
+                self.panic_if(iszero(b.clone()), 0x12);
+                let opcode = match (operator, signed) {
+                    (BinaryOperator::Div, true) => Opcode::SDiv,
+                    (BinaryOperator::Div, false) => Opcode::Div,
+                    (_, true) => Opcode::SMod,
+                    _ => Opcode::Mod,
+                };
+                apply(opcode, [a.clone(), b.clone()])
+            }
+            BinaryOperator::Exp if checked && signed => {
+                self.unsupported(self.frame.span, "checked exponentiation of signed integers");
+                apply(Opcode::Exp, [a.clone(), b.clone()])
+            }
+            BinaryOperator::Exp if checked => {
+                self.call_routine(Routine::Exp(bits as u16), vec![a.clone(), b.clone()], 1);
+                return self.squash(2);
+            }
+            BinaryOperator::Exp => apply(Opcode::Exp, [a.clone(), b.clone()]),
+            BinaryOperator::Shl => shl(b.clone(), a.clone()),
+            BinaryOperator::Shr if signed => apply(Opcode::Sar, [b.clone(), a.clone()]),
+            BinaryOperator::Shr => shr(b.clone(), a.clone()),
+            BinaryOperator::BitAnd => and(a.clone(), b.clone()),
+            BinaryOperator::BitOr => or(a.clone(), b.clone()),
+            BinaryOperator::BitXor => apply(Opcode::Xor, [a.clone(), b.clone()]),
+            _ => return self.unsupported(self.frame.span, "this operator"),
+        };
+        let result = match operator {
+            BinaryOperator::Add
+            | BinaryOperator::Sub
+            | BinaryOperator::Mul
+            | BinaryOperator::Exp
+            | BinaryOperator::Shl => clean(ty, result),
+            _ => result,
+        };
+        self.replace(2, result);
+    }
+
+    /* `left = right` AND `left op= right`, LEAVING THE ASSIGNED VALUE WHEN `keep` */
+    pub fn assignment(
+        &mut self,
+        operator: Option<BinaryOperator>,
+        left: &'a Expression,
+        right: &'a Expression,
+        keep: bool,
+    ) {
+        let ty = self.expression_type(left);
+        let Some(_operator) = operator else {
+            if let ExpressionKind::Tuple(components) = &left.kind {
+                if keep {
+                    self.unsupported(left.span, "using the value of a tuple assignment");
+                }
+                return self.tuple_assignment(components, right);
+            }
+            let target = self.location(left);
+            self.expression_to(right, &ty, target);
+            let location = if target == Location::Memory || ty.is_value_type() {
+                Location::Memory
+            } else {
+                self.location(right)
+            };
+            if keep {
+                self.dup(self.ir.height);
+            }
+            return match self.lvalue(left) {
+                Some(_lvalue) => self.store(&_lvalue, location),
+                None => self.unsupported(left.span, "assigning to this expression"),
+            };
+        };
+        let Some(lvalue) = self.lvalue(left) else {
+            return self.unsupported(left.span, "assigning to this expression");
+        };
+        let count = lvalue.words();
+        let base = self.ir.height - count;
+        for index in 1..=count {
+            self.dup(base + index);
+        }
+        self.load(&lvalue);
+        let right_type = match _operator {
+            BinaryOperator::Shl | BinaryOperator::Shr | BinaryOperator::Exp => {
+                self.expression_type(right).mobile()
+            }
+            _ => ty.clone(),
+        };
+        self.expression_to(right, &right_type, Location::Memory);
+        self.arithmetic(_operator, &ty);
+        if keep {
+            self.dup(self.ir.height);
+            self.rotate_down(count + 1);
+        }
+        self.rotate_down(count);
+        self.store(&lvalue, Location::Memory);
+    }
+
+    fn tuple_assignment(&mut self, components: &'a [Option<Expression>], right: &'a Expression) {
+        let mut locations = vec![Location::Memory; components.len()];
+        match &right.kind {
+            ExpressionKind::Tuple(values) if values.len() == components.len() => {
+                for ((component, value), location) in
+                    components.iter().zip(values).zip(locations.iter_mut())
+                {
+                    let Some(_value) = value else {
+                        continue;
+                    };
+                    match component {
+                        Some(_component) => {
+                            let ty = self.expression_type(_component);
+                            let target = self.location(_component);
+                            self.expression_to(_value, &ty, target);
+                            if target == Location::Storage && !ty.is_value_type() {
+                                *location = self.location(_value);
+                            }
+                        }
+                        None => {
+                            let ty = self.expression_type(_value).mobile();
+                            self.expression_to(_value, &ty, Location::Memory);
+                        }
+                    }
+                }
+            }
+            _ => self.expression(right),
+        }
+        for (component, location) in components.iter().zip(locations).rev() {
+            match component {
+                Some(_component) => match self.lvalue(_component) {
+                    Some(_lvalue) => {
+                        self.rotate_down(_lvalue.words());
+                        self.store(&_lvalue, location);
+                    }
+                    None => self.unsupported(_component.span, "assigning to this expression"),
+                },
+                None => self.pop(1),
+            }
+        }
+    }
+
+    /* PUSHES THE VALUE A VARIABLE OF `ty` STARTS WITH. DYNAMIC ARRAYS POINT AT THE ZERO WORD,
+    STATIC ARRAYS AND STRUCTS ARE ALLOCATED */
+    pub fn default_value(&mut self, ty: &Type, location: Location) {
+        if location == Location::Storage {
+            return self.push(0u8);
+        }
+        match ty {
+            Type::String | Type::Bytes | Type::Array { length: None, .. } => self.push(ZERO_SLOT),
+            Type::Array {
+                base,
+                length: Some(length),
+            } => {
+                let length = length.to_usize().unwrap_or_default();
+                let pointer = self.allocate(constant(32 * length));
+                self.for_range(constant(length), |codegen, index| {
+                    codegen.default_value(base, Location::Memory);
+                    let top = codegen.ir.height;
+                    codegen.exec(
+                        Opcode::MStore,
+                        [
+                            add(slot(pointer), mul(slot(index), constant(32u8))),
+                            slot(top),
+                        ],
+                    );
+                    codegen.pop(1);
+                });
+            }
+            Type::Struct { .. } => {
+                let fields = self.struct_fields(ty);
+                let pointer = self.allocate(constant(32 * fields.len()));
+                for (index, field) in fields.iter().enumerate() {
+                    self.default_value(field, Location::Memory);
+                    let top = self.ir.height;
+                    self.exec(
+                        Opcode::MStore,
+                        [add(slot(pointer), constant(32 * index)), slot(top)],
+                    );
+                    self.pop(1);
+                }
+            }
+            _ => self.push(0u8),
+        }
+    }
+
+    /* ------------------------------ CALLS ------------------------------ */
+
+    /* THE FUNCTION AN INTERNAL CALL RUNS AND, FOR A FUNCTION ATTACHED WITH `using`, THE
+    EXPRESSION PASSED AS ITS FIRST ARGUMENT */
+    pub fn internal_target(
+        &self,
+        call: &'a Expression,
+    ) -> Option<(Declaration, Option<&'a Expression>)> {
+        let ExpressionKind::FunctionCall { callee, .. } = &call.kind else {
+            return None;
+        };
+        let index = self
+            .types()
+            .overloads
+            .get(&call.span)
+            .copied()
+            .unwrap_or_default();
+        let callable = |declaration: &Declaration| {
+            matches!(
+                declaration,
+                Declaration::Member { .. } | Declaration::Error { .. }
+            )
+        };
+        match &callee.kind {
+            ExpressionKind::Variable(identifier) => {
+                let declarations = self.declarations(&identifier.span);
+                let declaration = *declarations
+                    .iter()
+                    .filter(|declaration| callable(declaration))
+                    .nth(index)?;
+                match self.part(declaration) {
+                    Some(ContractPart::Function(_)) => {
+                        Some((self.virtual_function(declaration), None))
+                    }
+                    _ => None,
+                }
+            }
+            ExpressionKind::MemberAccess { expression, member } => {
+                if let Some(_attached) = self.types().attached.get(&member.span) {
+                    return Some((*_attached.get(index)?, Some(&**expression)));
+                }
+                if let Some(_supers) = self.types().supers.get(&member.span) {
+                    return Some((self.super_function(*_supers.get(index)?)?, None));
+                }
+                let declaration = *self
+                    .declarations(&member.span)
+                    .iter()
+                    .filter(|declaration| callable(declaration))
+                    .nth(index)?;
+                let Some(ContractPart::Function(function)) = self.part(declaration) else {
+                    return None;
+                };
+                let Declaration::Member {
+                    file_id, contract, ..
+                } = declaration
+                else {
+                    return None;
+                };
+                /* LIBRARY AND FREE FUNCTIONS, `L.f()` OR `M.f()` OF `import "x" as M`, AND
+                THOSE OF A BASE */
+                let internal = matches!(
+                    self.definition((file_id, contract)).kind,
+                    ContractKind::Library | ContractKind::File
+                );
+                let base = self
+                    .context
+                    .bindings
+                    .derives_from(self.contract, (file_id, contract));
+                (function.body.is_some() && (internal || base)).then_some((declaration, None))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn call(&mut self, expression: &'a Expression) {
+        let ExpressionKind::FunctionCall { callee, arguments } = &expression.kind else {
+            return;
+        };
+        let (callee, options): (&'a Expression, &'a [NamedArgument]) = match &callee.kind {
+            ExpressionKind::CallOptions { callee, options } => (callee, options),
+            _ => (callee, &[]),
+        };
+        if let ExpressionKind::New(type_name) = &callee.kind {
+            return self.new_expression(expression, type_name, options, arguments);
+        }
+        match self.expression_type(callee) {
+            Type::TypeType(ty) => match *ty {
+                Type::Struct { .. } => self.struct_constructor(&ty, arguments),
+                target => self.conversion(expression, &target, arguments),
+            },
+            Type::Magic(name) => self.builtin_call(expression, name, arguments),
+            Type::BuiltinMember { name, receiver } => {
+                self.member_call(expression, callee, name, &receiver, options, arguments)
+            }
+            Type::Function(_) | Type::Overloaded(_) => match self.internal_target(expression) {
+                Some((declaration, receiver)) => {
+                    self.internal_call(expression, declaration, receiver, arguments)
+                }
+                None => self.external_call(expression, callee, options, arguments),
+            },
+            _ => self.unsupported(expression.span, "this call"),
+        }
+    }
+
+    fn internal_call(
+        &mut self,
+        expression: &'a Expression,
+        declaration: Declaration,
+        receiver: Option<&'a Expression>,
+        arguments: &'a CallArguments,
+    ) {
+        let Some(ContractPart::Function(function)) = self.part(declaration) else {
+            return self.unsupported(expression.span, "this call");
+        };
+        let target = self.function_id(declaration);
+        let mut parameters = function.parameters.iter();
+        if let Some(_receiver) = receiver {
+            if let Some(_parameter) = parameters.next() {
+                let ty = self.type_name(&_parameter.ty.span);
+                self.expression_to(_receiver, &ty, Self::parameter_location(_parameter));
+            }
+        }
+        let parameters: Vec<_> = parameters.collect();
+        let names: Vec<Option<String>> = parameters
+            .iter()
+            .map(|parameter| parameter.name.as_ref().map(|name| name.name.clone()))
+            .collect();
+        for (argument, parameter) in self
+            .ordered_arguments(arguments, &names)
+            .into_iter()
+            .zip(parameters)
+        {
+            let ty = self.type_name(&parameter.ty.span);
+            self.expression_to(argument, &ty, Self::parameter_location(parameter));
+        }
+        self.ir.call(target);
+    }
+
+    fn struct_constructor(&mut self, ty: &Type, arguments: &'a CallArguments) {
+        let members = self.struct_members(ty);
+        let names: Vec<Option<String>> = members
+            .iter()
+            .map(|(name, _)| Some(name.to_string()))
+            .collect();
+        let height = self.ir.height;
+        let pointer = self.allocate(constant(32 * members.len()));
+        for (index, (argument, (_, field))) in self
+            .ordered_arguments(arguments, &names)
+            .into_iter()
+            .zip(&members)
+            .enumerate()
+        {
+            self.expression_to(argument, field, Location::Memory);
+            let top = self.ir.height;
+            self.exec(
+                Opcode::MStore,
+                [add(slot(pointer), constant(32 * index)), slot(top)],
+            );
+            self.pop(1);
+        }
+        self.keep(height, &[pointer]);
+    }
+
+    fn conversion(
+        &mut self,
+        expression: &'a Expression,
+        target: &Type,
+        arguments: &'a CallArguments,
+    ) {
+        let [argument] = self.ordered_arguments(arguments, &[])[..] else {
+            return self.unsupported(expression.span, "this conversion");
+        };
+        let from = self.expression_type(argument);
+        if matches!(from, Type::RationalConstant(..) | Type::StringLiteral(_)) {
+            return self.expression_to(argument, target, Location::Memory);
+        }
+        self.expression(argument);
+        self.convert(&from, target);
+    }
+
+    /* EXPLICIT CONVERSION OF THE VALUE ON TOP OF THE STACK */
+    pub fn convert(&mut self, from: &Type, to: &Type) {
+        let top = slot(self.ir.height);
+        let word = match (from, to) {
+            (Type::Integer { bits, .. }, Type::FixedBytes(size)) if *bits == 8 * *size as u16 => {
+                shl(constant(256 - *bits as usize), top)
+            }
+            (Type::FixedBytes(size), Type::Integer { .. }) => {
+                shr(constant(256 - 8 * *size as usize), top)
+            }
+            (Type::Address { .. } | Type::Contract { .. }, Type::FixedBytes(20)) => {
+                shl(constant(96u8), top)
+            }
+            (Type::FixedBytes(20), Type::Address { .. } | Type::Contract { .. }) => {
+                shr(constant(96u8), top)
+            }
+            (Type::Integer { .. }, Type::Enum { .. }) => {
+                let size = self.enum_size(to);
+                self.panic_if(iszero(lt(top, constant(size))), 0x21);
+                return;
+            }
+            (Type::Bytes, Type::FixedBytes(_)) => {
+                let length = mload(top.clone());
+                let data = mload(add(top, constant(32u8)));
+                clean(
+                    to,
+                    and(
+                        data,
+                        not(shr(mul(length, constant(8u8)), not(constant(0u8)))),
+                    ),
+                )
+            }
+            (
+                Type::Integer { .. }
+                | Type::FixedBytes(_)
+                | Type::Address { .. }
+                | Type::Contract { .. },
+                Type::Integer { .. }
+                | Type::FixedBytes(_)
+                | Type::Address { .. }
+                | Type::Contract { .. },
+            ) => clean(to, top),
+            _ => return,
+        };
+        self.replace(1, word);
+    }
+
+    fn builtin_call(
+        &mut self,
+        expression: &'a Expression,
+        name: &str,
+        arguments: &'a CallArguments,
+    ) {
+        let arguments = self.ordered_arguments(arguments, &[]);
+        let height = self.ir.height;
+        match (name, arguments.as_slice()) {
+            ("require", [condition, message @ ..]) if message.len() <= 1 => {
+                self.expression_to(condition, &Type::Bool, Location::Memory);
+                let passed = self.ir.new_block();
+                self.ir.jump_if(passed);
+                match message {
+                    [_message] => self.revert_with_expression(_message),
+                    _ => self.exec(Opcode::Revert, [constant(0u8), constant(0u8)]),
+                }
+                self.ir.height = height;
+                self.ir.start(passed);
+            }
+            ("assert", [condition]) => {
+                self.expression_to(condition, &Type::Bool, Location::Memory);
+                let top = self.ir.height;
+                self.panic_if(iszero(slot(top)), 0x01);
+                self.pop(1);
+            }
+            ("revert", []) => {
+                self.exec(Opcode::Revert, [constant(0u8), constant(0u8)]);
+                self.ir.height = height;
+            }
+            ("revert", [message]) => {
+                self.revert_with_expression(message);
+                self.ir.height = height;
+            }
+            ("keccak256", [data]) => {
+                self.expression_to(data, &Type::Bytes, Location::Memory);
+                let top = self.ir.height;
+                self.replace(
+                    1,
+                    apply(
+                        Opcode::Keccak256,
+                        [add(slot(top), constant(32u8)), mload(slot(top))],
+                    ),
+                );
+            }
+            ("sha256" | "ripemd160", [data]) => {
+                self.expression_to(data, &Type::Bytes, Location::Memory);
+                let top = self.ir.height;
+                let precompile = if name == "sha256" { 2u8 } else { 3 };
+                self.revert_if(iszero(apply(
+                    Opcode::StaticCall,
+                    [
+                        env(Opcode::Gas),
+                        constant(precompile),
+                        add(slot(top), constant(32u8)),
+                        mload(slot(top)),
+                        constant(0u8),
+                        constant(32u8),
+                    ],
+                )));
+                let hash = match name {
+                    "sha256" => mload(constant(0u8)),
+                    _ => shl(constant(96u8), mload(constant(0u8))),
+                };
+                self.replace(1, hash);
+            }
+            ("ecrecover", [hash, v, r, s]) => {
+                let bytes32 = Type::FixedBytes(32);
+                self.expression_to(hash, &bytes32, Location::Memory);
+                self.expression_to(
+                    v,
+                    &Type::Integer {
+                        signed: false,
+                        bits: 8,
+                    },
+                    Location::Memory,
+                );
+                self.expression_to(r, &bytes32, Location::Memory);
+                self.expression_to(s, &bytes32, Location::Memory);
+                let buffer = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+                for index in 0..4 {
+                    self.exec(
+                        Opcode::MStore,
+                        [
+                            add(slot(buffer), constant(32 * index)),
+                            slot(height + 1 + index),
+                        ],
+                    );
+                }
+                self.exec(Opcode::MStore, [constant(0u8), constant(0u8)]);
+                self.revert_if(iszero(apply(
+                    Opcode::StaticCall,
+                    [
+                        env(Opcode::Gas),
+                        constant(1u8),
+                        slot(buffer),
+                        constant(128u8),
+                        constant(0u8),
+                        constant(32u8),
+                    ],
+                )));
+                let address = self.let_(mload(constant(0u8)));
+                self.keep(height, &[address]);
+            }
+            ("addmod" | "mulmod", [a, b, modulus]) => {
+                for argument in [a, b, modulus] {
+                    self.expression_to(argument, &Type::UINT256, Location::Memory);
+                }
+                self.panic_if(iszero(slot(height + 3)), 0x12);
+                let opcode = if name == "addmod" {
+                    Opcode::AddMod
+                } else {
+                    Opcode::MulMod
+                };
+                self.replace(
+                    3,
+                    apply(
+                        opcode,
+                        [slot(height + 1), slot(height + 2), slot(height + 3)],
+                    ),
+                );
+            }
+            ("gasleft", []) => self.op(Opcode::Gas),
+            ("blockhash", [number]) => {
+                self.expression_to(number, &Type::UINT256, Location::Memory);
+                self.op(Opcode::BlockHash);
+            }
+            ("blobhash", [index]) if self.context.settings.evm_version >= EvmVersion::Cancun => {
+                self.expression_to(index, &Type::UINT256, Location::Memory);
+                self.op(Opcode::BlobHash);
+            }
+            ("selfdestruct", [recipient]) => {
+                self.expression_to(
+                    recipient,
+                    &Type::Address { payable: true },
+                    Location::Memory,
+                );
+                self.op(Opcode::SelfDestruct);
+            }
+            ("abi.encode" | "abi.encodePacked", _) => {
+                let (types, positions) = self.abi_arguments(&arguments);
+                self.encode_bytes(
+                    expression,
+                    None,
+                    &types,
+                    &positions,
+                    name == "abi.encodePacked",
+                );
+                self.keep(height, &[self.ir.height]);
+            }
+            ("abi.encodeWithSelector", [function_selector, rest @ ..]) => {
+                self.expression_to(function_selector, &Type::FixedBytes(4), Location::Memory);
+                let selector_position = self.ir.height;
+                let (types, positions) = self.abi_arguments(rest);
+                self.encode_bytes(
+                    expression,
+                    Some(selector_position),
+                    &types,
+                    &positions,
+                    false,
+                );
+                self.keep(height, &[self.ir.height]);
+            }
+            ("abi.encodeWithSignature", [function_signature, rest @ ..]) => {
+                self.expression_to(function_signature, &Type::String, Location::Memory);
+                let text = self.ir.height;
+                let selector_position = self.let_(and(
+                    apply(
+                        Opcode::Keccak256,
+                        [add(slot(text), constant(32u8)), mload(slot(text))],
+                    ),
+                    constant(high_mask(4)),
+                ));
+                let (types, positions) = self.abi_arguments(rest);
+                self.encode_bytes(
+                    expression,
+                    Some(selector_position),
+                    &types,
+                    &positions,
+                    false,
+                );
+                self.keep(height, &[self.ir.height]);
+            }
+            ("abi.encodeCall", [function, values]) => {
+                let declaration = match &function.kind {
+                    ExpressionKind::MemberAccess {
+                        expression: base,
+                        member,
+                    } => match self.expression_type(base) {
+                        Type::Contract {
+                            file_id, contract, ..
+                        } => self
+                            .external_candidates((file_id, contract), &member.name)
+                            .first()
+                            .copied(),
+                        _ => self.declarations(&member.span).first().copied(),
+                    },
+                    ExpressionKind::Variable(identifier) => {
+                        self.declarations(&identifier.span).first().copied()
+                    }
+                    _ => None,
+                };
+                let Some(ExternalFunction {
+                    signature,
+                    parameters,
+                    ..
+                }) = declaration.and_then(|declaration| self.external_signature(declaration))
+                else {
+                    return self.unsupported(expression.span, "this `abi.encodeCall`");
+                };
+                let values: Vec<&'a Expression> = match &values.kind {
+                    ExpressionKind::Tuple(components) => components.iter().flatten().collect(),
+                    _ => vec![values],
+                };
+                let mut positions = Vec::new();
+                for (value, ty) in values.into_iter().zip(&parameters) {
+                    self.expression_to(value, ty, Location::Memory);
+                    positions.push(self.ir.height);
+                }
+                let selector_position = self.let_(constant(
+                    BigUint::from_bytes_be(&selector(&signature)) << 224,
+                ));
+                self.encode_bytes(
+                    expression,
+                    Some(selector_position),
+                    &parameters,
+                    &positions,
+                    false,
+                );
+                self.keep(height, &[self.ir.height]);
+            }
+            ("abi.decode", [data, types]) => {
+                let types = match self.expression_type(types) {
+                    Type::TypeType(ty) => vec![*ty],
+                    Type::Tuple(components) => components
+                        .into_iter()
+                        .map(|component| match component {
+                            Some(Type::TypeType(ty)) => *ty,
+                            _ => Type::Unknown,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                self.expression_to(data, &Type::Bytes, Location::Memory);
+                let pointer = self.ir.height;
+                let start = self.let_(add(slot(pointer), constant(32u8)));
+                let end = self.let_(add(slot(start), mload(slot(pointer))));
+                let size: usize = types.iter().map(|ty| self.head_size(ty)).sum();
+                self.revert_if(lt(mload(slot(pointer)), constant(size)));
+                let mut positions = Vec::new();
+                let mut offset = 0;
+                for ty in &types {
+                    positions.push(self.decode(
+                        ty,
+                        false,
+                        add(slot(start), constant(offset)),
+                        slot(start),
+                        slot(end),
+                    ));
+                    offset += self.head_size(ty);
+                }
+                self.keep(height, &positions);
+            }
+            _ => self.unsupported(expression.span, format!("`{name}` here")),
+        }
+    }
+
+    /* `require` AND `revert` WITH A MESSAGE */
+    fn revert_with_expression(&mut self, message: &'a Expression) {
+        match self.expression_type(message) {
+            Type::String | Type::StringLiteral(_) => {
+                self.expression_to(message, &Type::String, Location::Memory);
+                self.revert_with_reason(self.ir.height);
+            }
+            _ => self.unsupported(message.span, "this revert reason"),
+        }
+    }
+
+    /* ARGUMENTS OF `abi.encode` AND THE LIKE, AS THEIR MOBILE TYPES IN MEMORY */
+    fn abi_arguments(&mut self, arguments: &[&'a Expression]) -> (Vec<Type>, Vec<usize>) {
+        let mut types = Vec::new();
+        let mut positions = Vec::new();
+        for argument in arguments {
+            let ty = match self.expression_type(argument) {
+                Type::StringLiteral(_) => Type::Bytes,
+                ty => ty.mobile(),
+            };
+            self.expression_to(argument, &ty, Location::Memory);
+            positions.push(self.ir.height);
+            types.push(ty);
+        }
+        (types, positions)
+    }
+
+    /* PUSHES A NEW `bytes` HOLDING THE SELECTOR, IF ANY, AND THE ENCODED VALUES */
+    fn encode_bytes(
+        &mut self,
+        expression: &Expression,
+        function_selector: Option<usize>,
+        types: &[Type],
+        positions: &[usize],
+        packed: bool,
+    ) {
+        let height = self.ir.height;
+        let pointer = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+        let mut start = add(slot(pointer), constant(32u8));
+        if let Some(_selector) = function_selector {
+            self.exec(Opcode::MStore, [start.clone(), slot(_selector)]);
+            start = add(slot(pointer), constant(36u8));
+        }
+        let end = if packed {
+            self.encode_packed(expression, types, positions, start)
+        } else {
+            self.encode_tuple(types, positions, start)
+        };
+        self.exec(
+            Opcode::MStore,
+            [
+                slot(pointer),
+                sub(slot(end), add(slot(pointer), constant(32u8))),
+            ],
+        );
+        self.exec(
+            Opcode::MStore,
+            [constant(FREE_MEMORY_POINTER), ceil32(slot(end))],
+        );
+        self.keep(height, &[pointer]);
+    }
+
+    /* `push`, `pop`, `transfer`, `send`, LOW LEVEL CALLS AND `concat` */
+    fn member_call(
+        &mut self,
+        expression: &'a Expression,
+        callee: &'a Expression,
+        name: &str,
+        receiver: &Type,
+        options: &'a [NamedArgument],
+        arguments: &'a CallArguments,
+    ) {
+        let arguments = self.ordered_arguments(arguments, &[]);
+        let ExpressionKind::MemberAccess {
+            expression: base, ..
+        } = &callee.kind
+        else {
+            return self.unsupported(expression.span, "this call");
+        };
+        let height = self.ir.height;
+        match (name, receiver) {
+            ("push" | "pop", Type::Array { base: element, .. })
+                if self.location(base) == Location::Storage =>
+            {
+                self.expression(base);
+                let array = self.ir.height;
+                let value = arguments.first().map(|argument| {
+                    let location = if element.is_value_type() {
+                        Location::Memory
+                    } else {
+                        self.location(argument)
+                    };
+                    self.expression_to(argument, element, location);
+                    location
+                });
+                let length = self.let_(sload(slot(array)));
+                if name == "pop" {
+                    self.panic_if(iszero(slot(length)), 0x31);
+                    self.assign(length, sub(slot(length), constant(1u8)));
+                } else {
+                    self.exec(
+                        Opcode::SStore,
+                        [slot(array), add(slot(length), constant(1u8))],
+                    );
+                }
+                self.call_routine(
+                    Routine::StorageIndex(receiver.clone()),
+                    vec![slot(array), slot(length)],
+                    2,
+                );
+                let (element_slot, element_offset) = (self.ir.height - 1, self.ir.height);
+                match (name, value) {
+                    ("pop", _) => {
+                        if element.is_value_type() {
+                            self.storage_write(
+                                element,
+                                slot(element_slot),
+                                slot(element_offset),
+                                constant(0u8),
+                            );
+                        } else {
+                            self.call_routine(
+                                Routine::ClearStorage((**element).clone()),
+                                vec![slot(element_slot)],
+                                0,
+                            );
+                        }
+                        self.exec(Opcode::SStore, [slot(array), slot(length)]);
+                        self.pop_to(height);
+                    }
+                    (_, Some(_location)) => {
+                        let value = array + 1;
+                        if element.is_value_type() {
+                            self.storage_write(
+                                element,
+                                slot(element_slot),
+                                slot(element_offset),
+                                slot(value),
+                            );
+                        } else if !matches!(**element, Type::Mapping { .. }) {
+                            self.keep(height, &[value, element_slot]);
+                            return self
+                                .store(&Lvalue::StorageReference((**element).clone()), _location);
+                        }
+                        self.pop_to(height);
+                    }
+                    (_, None) => {
+                        if element.is_value_type() {
+                            self.keep(height, &[])
+                        } else {
+                            self.keep(height, &[element_slot])
+                        }
+                    }
+                }
+                if name == "push" && arguments.is_empty() && element.is_value_type() {
+                    self.push(0u8);
+                }
+            }
+            /* STORAGE `bytes` ARE REWRITTEN THROUGH MEMORY, THE SHORT AND LONG LAYOUTS ARE
+            HANDLED BY THE COPY ROUTINES */
+            ("push" | "pop", Type::Bytes)
+                if self.location(base) == Location::Storage
+                    && arguments.len() == usize::from(name == "push") =>
+            {
+                self.expression(base);
+                let key = self.ir.height;
+                if let Some(_argument) = arguments.first() {
+                    self.expression_to(_argument, &Type::FixedBytes(1), Location::Memory);
+                }
+                self.call_routine(Routine::StorageToMemory(Type::Bytes), vec![slot(key)], 1);
+                let pointer = self.ir.height;
+                let length = self.let_(mload(slot(pointer)));
+                if name == "pop" {
+                    self.panic_if(iszero(slot(length)), 0x31);
+                    self.exec(
+                        Opcode::MStore,
+                        [slot(pointer), sub(slot(length), constant(1u8))],
+                    );
+                } else {
+                    let pushed = self.allocate(add(slot(length), constant(33u8)));
+                    self.exec(
+                        Opcode::MStore,
+                        [slot(pushed), add(slot(length), constant(1u8))],
+                    );
+                    self.copy_memory(
+                        add(slot(pointer), constant(32u8)),
+                        add(slot(pushed), constant(32u8)),
+                        slot(length),
+                    );
+                    self.exec(
+                        Opcode::MStore8,
+                        [
+                            add(add(slot(pushed), constant(32u8)), slot(length)),
+                            shr(constant(248u8), slot(key + 1)),
+                        ],
+                    );
+                    self.assign(pointer, slot(pushed));
+                }
+                self.call_routine(
+                    Routine::MemoryToStorage(Type::Bytes),
+                    vec![slot(pointer), slot(key)],
+                    0,
+                );
+                self.pop_to(height);
+            }
+            ("transfer" | "send", _) => {
+                self.expression(base);
+                let [amount] = arguments[..] else {
+                    return;
+                };
+                self.expression_to(amount, &Type::UINT256, Location::Memory);
+                let (address, amount) = (height + 1, height + 2);
+                let success = self.let_(apply(
+                    Opcode::Call,
+                    [
+                        mul(iszero(slot(amount)), constant(2300u16)),
+                        slot(address),
+                        slot(amount),
+                        constant(0u8),
+                        constant(0u8),
+                        constant(0u8),
+                        constant(0u8),
+                    ],
+                ));
+                if name == "transfer" {
+                    self.if_(iszero(slot(success)), |codegen| codegen.bubble_revert());
+                    self.pop_to(height);
+                } else {
+                    self.keep(height, &[success]);
+                }
+            }
+            ("call" | "delegatecall" | "staticcall", _) => {
+                self.expression(base);
+                let address = self.ir.height;
+                let gas = self.call_option(options, "gas", Type::UINT256, env(Opcode::Gas));
+                let value = self.call_option(options, "value", Type::UINT256, constant(0u8));
+                let [data] = arguments[..] else {
+                    return;
+                };
+                self.expression_to(data, &Type::Bytes, Location::Memory);
+                let data = self.ir.height;
+                let input = [add(slot(data), constant(32u8)), mload(slot(data))];
+                let [input_start, input_size] = input;
+                let call = match name {
+                    "call" => apply(
+                        Opcode::Call,
+                        [
+                            gas,
+                            slot(address),
+                            value,
+                            input_start,
+                            input_size,
+                            constant(0u8),
+                            constant(0u8),
+                        ],
+                    ),
+                    _ => apply(
+                        if name == "delegatecall" {
+                            Opcode::DelegateCall
+                        } else {
+                            Opcode::StaticCall
+                        },
+                        [
+                            gas,
+                            slot(address),
+                            input_start,
+                            input_size,
+                            constant(0u8),
+                            constant(0u8),
+                        ],
+                    ),
+                };
+                let success = self.let_(call);
+                let returned = self.return_data();
+                self.keep(height, &[success, returned]);
+            }
+            ("concat", _) => {
+                let mut parts = Vec::new();
+                for argument in &arguments {
+                    let ty = match self.expression_type(argument) {
+                        Type::FixedBytes(size) => Type::FixedBytes(size),
+                        _ => Type::Bytes,
+                    };
+                    self.expression_to(argument, &ty, Location::Memory);
+                    parts.push((ty, self.ir.height));
+                }
+                let length = self.let_(constant(0u8));
+                for (ty, position) in &parts {
+                    let size = match ty {
+                        Type::FixedBytes(size) => constant(*size),
+                        _ => mload(slot(*position)),
+                    };
+                    self.assign(length, add(slot(length), size));
+                }
+                let pointer = self.allocate(add(slot(length), constant(32u8)));
+                self.exec(Opcode::MStore, [slot(pointer), slot(length)]);
+                let cursor = self.let_(add(slot(pointer), constant(32u8)));
+                for (ty, position) in &parts {
+                    match ty {
+                        Type::FixedBytes(size) => {
+                            self.exec(Opcode::MStore, [slot(cursor), slot(*position)]);
+                            self.assign(cursor, add(slot(cursor), constant(*size)));
+                        }
+                        _ => {
+                            self.copy_memory(
+                                add(slot(*position), constant(32u8)),
+                                slot(cursor),
+                                mload(slot(*position)),
+                            );
+                            self.assign(cursor, add(slot(cursor), mload(slot(*position))));
+                        }
+                    }
+                }
+                self.keep(height, &[pointer]);
+            }
+            _ => self.unsupported(expression.span, format!("`{name}` here")),
+        }
+    }
+
+    /* PUSHES `{name: value}` OF A CALL, OR `default` */
+    fn call_option(
+        &mut self,
+        options: &'a [NamedArgument],
+        name: &str,
+        ty: Type,
+        default: Word,
+    ) -> Word {
+        match options.iter().find(|option| option.name.name == name) {
+            Some(_option) => {
+                self.expression_to(&_option.value, &ty, Location::Memory);
+                slot(self.ir.height)
+            }
+            None => default,
+        }
+    }
+
+    /* COPIES THE RETURN DATA INTO A NEW `bytes` */
+    pub fn return_data(&mut self) -> usize {
+        let height = self.ir.height;
+        let size = self.let_(env(Opcode::ReturnDataSize));
+        let pointer = self.allocate(add(slot(size), constant(32u8)));
+        self.exec(Opcode::MStore, [slot(pointer), slot(size)]);
+        self.exec(
+            Opcode::ReturnDataCopy,
+            [
+                add(slot(pointer), constant(32u8)),
+                constant(0u8),
+                slot(size),
+            ],
+        );
+        self.keep(height, &[pointer]);
+        self.ir.height
+    }
+
+    /* REVERTS WITH WHAT THE LAST CALL REVERTED WITH */
+    pub fn bubble_revert(&mut self) {
+        let height = self.ir.height;
+        let buffer = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+        self.exec(
+            Opcode::ReturnDataCopy,
+            [slot(buffer), constant(0u8), env(Opcode::ReturnDataSize)],
+        );
+        self.exec(Opcode::Revert, [slot(buffer), env(Opcode::ReturnDataSize)]);
+        self.ir.height = height;
+    }
+
+    /* FUNCTIONS AND GETTERS OF `contract` THAT CAN BE CALLED FROM OUTSIDE UNDER `name`, IN
+    THE ORDER THE TYPE CHECKER LISTS THEM */
+    pub fn external_candidates(&self, contract: ContractId, name: &str) -> Vec<Declaration> {
+        let declarations = self
+            .context
+            .bindings
+            .members
+            .get(&contract)
+            .and_then(|members| members.get(name));
+        declarations
+            .into_iter()
+            .flatten()
+            .filter(|declaration| match self.part(**declaration) {
+                Some(ContractPart::Function(function)) => !matches!(
+                    function.visibility,
+                    Some(Visibility::Private | Visibility::Internal)
+                ),
+                Some(ContractPart::StateVariable(variable)) => {
+                    variable.visibility == Some(Visibility::Public)
+                }
+                _ => false,
+            })
+            .copied()
+            .collect()
+    }
+
+    /* A FUNCTION OR GETTER AS CALLED FROM OUTSIDE */
+    pub fn external_signature(&self, declaration: Declaration) -> Option<ExternalFunction> {
+        let context = self.context;
+        let library = matches!(declaration, Declaration::Member { file_id, contract, .. }
+            if self.definition((file_id, contract)).kind == ContractKind::Library);
+        match self.part(declaration)? {
+            ContractPart::Function(function) => Some(ExternalFunction {
+                signature: match library {
+                    true => library_signature(
+                        &function.name.as_ref()?.name,
+                        &function.parameters,
+                        context.graph,
+                        context.types,
+                    ),
+                    false => signature(
+                        &function.name.as_ref()?.name,
+                        &function.parameters,
+                        context.graph,
+                        context.types,
+                    ),
+                },
+                parameters: self.parameter_types(&function.parameters),
+                returns: self.parameter_types(&function.returns),
+                mutability: function.mutability,
+            }),
+            ContractPart::StateVariable(variable) => {
+                let (parameters, value) = crate::mods::functions::controllers::abi::getter(
+                    &self.type_name(&variable.ty.span),
+                );
+                let names: Vec<String> = parameters
+                    .iter()
+                    .map(|ty| canonical_type(ty, context.graph, context.types))
+                    .collect();
+                Some(ExternalFunction {
+                    signature: format!("{}({})", variable.name.name, names.join(",")),
+                    parameters,
+                    returns: vec![value],
+                    mutability: Some(StateMutability::View),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /* `c.f(..)`: ENCODES THE CALL, RUNS IT, BUBBLES A REVERT AND DECODES WHAT IT RETURNS */
+    fn external_call(
+        &mut self,
+        expression: &'a Expression,
+        callee: &'a Expression,
+        options: &'a [NamedArgument],
+        arguments: &'a CallArguments,
+    ) {
+        let ExpressionKind::MemberAccess {
+            expression: receiver,
+            member,
+        } = &callee.kind
+        else {
+            return self.unsupported(expression.span, "this call");
+        };
+        let Type::Contract {
+            file_id, contract, ..
+        } = self.expression_type(receiver)
+        else {
+            return self.unsupported(expression.span, "this call");
+        };
+        let index = self
+            .types()
+            .overloads
+            .get(&expression.span)
+            .copied()
+            .unwrap_or_default();
+        let candidates = self.external_candidates((file_id, contract), &member.name);
+        let Some(ExternalFunction {
+            signature,
+            parameters,
+            returns,
+            mutability,
+        }) = candidates
+            .get(index)
+            .and_then(|declaration| self.external_signature(*declaration))
+        else {
+            return self.unsupported(expression.span, "this call");
+        };
+        if matches!(
+            candidates.get(index).and_then(|d| self.part(*d)),
+            Some(ContractPart::StateVariable(_))
+        ) && matches!(returns.first(), Some(Type::Struct { .. }))
+        {
+            return self.unsupported(expression.span, "calling the getter of a struct");
+        }
+        let names = match candidates.get(index).and_then(|d| self.part(*d)) {
+            Some(ContractPart::Function(function)) => Self::parameter_names(&function.parameters),
+            _ => vec![None; parameters.len()],
+        };
+        let height = self.ir.height;
+        self.expression(receiver);
+        let address = self.ir.height;
+        let gas = self.call_option(options, "gas", Type::UINT256, env(Opcode::Gas));
+        let value = self.call_option(options, "value", Type::UINT256, constant(0u8));
+        let mut positions = Vec::new();
+        for (argument, ty) in self
+            .ordered_arguments(arguments, &names)
+            .into_iter()
+            .zip(&parameters)
+        {
+            self.expression_to(argument, ty, Location::Memory);
+            positions.push(self.ir.height);
+        }
+        self.revert_if(iszero(apply(Opcode::ExtCodeSize, [slot(address)])));
+        let start = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+        self.exec(
+            Opcode::MStore,
+            [
+                slot(start),
+                constant(BigUint::from_bytes_be(&selector(&signature)) << 224),
+            ],
+        );
+        let end = self.encode_tuple(&parameters, &positions, add(slot(start), constant(4u8)));
+        let size = sub(slot(end), slot(start));
+        let call = match mutability {
+            Some(StateMutability::View | StateMutability::Pure) => apply(
+                Opcode::StaticCall,
+                [
+                    gas,
+                    slot(address),
+                    slot(start),
+                    size,
+                    constant(0u8),
+                    constant(0u8),
+                ],
+            ),
+            _ => apply(
+                Opcode::Call,
+                [
+                    gas,
+                    slot(address),
+                    value,
+                    slot(start),
+                    size,
+                    constant(0u8),
+                    constant(0u8),
+                ],
+            ),
+        };
+        let success = self.let_(call);
+        self.if_(iszero(slot(success)), |codegen| codegen.bubble_revert());
+        let mut results = Vec::new();
+        if !returns.is_empty() {
+            let size = self.let_(env(Opcode::ReturnDataSize));
+            let buffer = self.allocate(slot(size));
+            self.exec(
+                Opcode::ReturnDataCopy,
+                [slot(buffer), constant(0u8), slot(size)],
+            );
+            let heads: usize = returns.iter().map(|ty| self.head_size(ty)).sum();
+            self.revert_if(lt(slot(size), constant(heads)));
+            let end = self.let_(add(slot(buffer), slot(size)));
+            let mut offset = 0;
+            for ty in &returns {
+                results.push(self.decode(
+                    ty,
+                    false,
+                    add(slot(buffer), constant(offset)),
+                    slot(buffer),
+                    slot(end),
+                ));
+                offset += self.head_size(ty);
+            }
+        }
+        self.keep(height, &results);
+    }
+
+    /* `new C(..)` DEPLOYS THE EMBEDDED CREATION CODE WITH THE ENCODED ARGUMENTS APPENDED,
+    `new T[](n)` ALLOCATES */
+    fn new_expression(
+        &mut self,
+        expression: &'a Expression,
+        type_name: &'a TypeName,
+        options: &'a [NamedArgument],
+        arguments: &'a CallArguments,
+    ) {
+        let ty = self.type_name(&type_name.span);
+        let height = self.ir.height;
+        match &ty {
+            Type::Contract {
+                file_id, contract, ..
+            } => {
+                let Some(section) = self.embedded_code((*file_id, *contract), true, expression)
+                else {
+                    return self.push(0u8);
+                };
+                let constructor = self
+                    .definition((*file_id, *contract))
+                    .parts
+                    .iter()
+                    .find_map(|part| match part {
+                        ContractPart::Function(_function)
+                            if _function.kind
+                                == crate::mods::types::ast::FunctionKind::Constructor =>
+                        {
+                            Some(_function)
+                        }
+                        _ => None,
+                    });
+                let parameters = constructor
+                    .map(|constructor| constructor.parameters.as_slice())
+                    .unwrap_or_default();
+                let types = self.parameter_types(parameters);
+                let value = self.call_option(options, "value", Type::UINT256, constant(0u8));
+                let salt = options
+                    .iter()
+                    .any(|option| option.name.name == "salt")
+                    .then(|| {
+                        self.call_option(options, "salt", Type::FixedBytes(32), constant(0u8))
+                    });
+                let mut positions = Vec::new();
+                for (argument, ty) in self
+                    .ordered_arguments(arguments, &Self::parameter_names(parameters))
+                    .into_iter()
+                    .zip(&types)
+                {
+                    self.expression_to(argument, ty, Location::Memory);
+                    positions.push(self.ir.height);
+                }
+                let start = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+                let size = Word::Link(Link::DataSize(section));
+                self.exec(
+                    Opcode::CodeCopy,
+                    [
+                        slot(start),
+                        Word::Link(Link::DataOffset(section)),
+                        size.clone(),
+                    ],
+                );
+                let end = self.encode_tuple(&types, &positions, add(slot(start), size));
+                let length = sub(slot(end), slot(start));
+                let create = match salt {
+                    Some(_salt) => apply(Opcode::Create2, [value, slot(start), length, _salt]),
+                    None => apply(Opcode::Create, [value, slot(start), length]),
+                };
+                let address = self.let_(create);
+                self.if_(iszero(slot(address)), |codegen| codegen.bubble_revert());
+                self.keep(height, &[address]);
+            }
+            Type::Array { base, length: None } => {
+                let [count] = self.ordered_arguments(arguments, &[])[..] else {
+                    return;
+                };
+                self.expression_to(count, &Type::UINT256, Location::Memory);
+                let count = self.ir.height;
+                self.panic_if(gt(slot(count), constant(u64::MAX)), 0x41);
+                let pointer = self.allocate(add(mul(slot(count), constant(32u8)), constant(32u8)));
+                self.exec(Opcode::MStore, [slot(pointer), slot(count)]);
+                if base.is_value_type() {
+                    self.zero_memory(
+                        add(slot(pointer), constant(32u8)),
+                        mul(slot(count), constant(32u8)),
+                    );
+                } else {
+                    self.for_range(slot(count), |codegen, index| {
+                        codegen.default_value(base, Location::Memory);
+                        let top = codegen.ir.height;
+                        codegen.exec(
+                            Opcode::MStore,
+                            [
+                                add(
+                                    slot(pointer),
+                                    add(constant(32u8), mul(slot(index), constant(32u8))),
+                                ),
+                                slot(top),
+                            ],
+                        );
+                        codegen.pop(1);
+                    });
+                }
+                self.keep(height, &[pointer]);
+            }
+            Type::Bytes | Type::String => {
+                let [count] = self.ordered_arguments(arguments, &[])[..] else {
+                    return;
+                };
+                self.expression_to(count, &Type::UINT256, Location::Memory);
+                let count = self.ir.height;
+                self.panic_if(gt(slot(count), constant(u64::MAX)), 0x41);
+                let pointer = self.allocate(add(slot(count), constant(32u8)));
+                self.exec(Opcode::MStore, [slot(pointer), slot(count)]);
+                self.zero_memory(add(slot(pointer), constant(32u8)), ceil32(slot(count)));
+                self.keep(height, &[pointer]);
+            }
+            _ => self.unsupported(expression.span, "creating this type"),
+        }
+    }
+
+    /* ------------------------------ STORAGE ROUTINES ------------------------------ */
+
+    /* (slot, index) -> (slot, offset) OF AN ELEMENT, PANICKING OUT OF BOUNDS */
+    pub fn storage_index_routine(&mut self, ty: &Type) {
+        let Type::Array { base, length } = ty else {
+            return self.leave(&[]);
+        };
+        let (array, index) = (1, 2);
+        let (data, size) = match length {
+            Some(_length) => (
+                slot(array),
+                constant(_length.to_biguint().unwrap_or_default()),
+            ),
+            None => {
+                let data = self.hash_word(slot(array));
+                (slot(data), sload(slot(array)))
+            }
+        };
+        self.panic_if(iszero(lt(slot(index), size)), 0x32);
+        let (element, offset) = self.element_position(base, data, slot(index));
+        let element = self.let_(element);
+        let offset = self.let_(offset);
+        self.leave(&[element, offset]);
+    }
+
+    /* (pointer, slot) -> slot OF THE VALUE UNDER A `string` OR `bytes` KEY */
+    pub fn mapping_slot_routine(&mut self) {
+        let (key, mapping) = (1, 2);
+        let buffer = self.let_(mload(constant(FREE_MEMORY_POINTER)));
+        let length = self.let_(mload(slot(key)));
+        self.copy_memory(add(slot(key), constant(32u8)), slot(buffer), slot(length));
+        self.exec(
+            Opcode::MStore,
+            [add(slot(buffer), slot(length)), slot(mapping)],
+        );
+        let hash = self.let_(apply(
+            Opcode::Keccak256,
+            [slot(buffer), add(slot(length), constant(32u8))],
+        ));
+        self.leave(&[hash]);
+    }
+
+    /* (slot) -> pointer */
+    pub fn storage_to_memory_routine(&mut self, ty: &Type) {
+        let key = 1;
+        match ty {
+            Type::String | Type::Bytes => {
+                let value = self.let_(sload(slot(key)));
+                let length = self.let_(Self::storage_bytes_length(slot(value)));
+                let pointer = self.allocate(add(ceil32(slot(length)), constant(32u8)));
+                self.exec(Opcode::MStore, [slot(pointer), slot(length)]);
+                self.if_(and(slot(value), constant(1u8)), |codegen| {
+                    let data = codegen.hash_word(slot(key));
+                    codegen.for_range(
+                        div(add(slot(length), constant(31u8)), constant(32u8)),
+                        |codegen, index| {
+                            codegen.exec(
+                                Opcode::MStore,
+                                [
+                                    add(
+                                        slot(pointer),
+                                        add(constant(32u8), mul(slot(index), constant(32u8))),
+                                    ),
+                                    sload(add(slot(data), slot(index))),
+                                ],
+                            );
+                        },
+                    );
+                    codegen.pop(1);
+                });
+                self.if_(iszero(and(slot(value), constant(1u8))), |codegen| {
+                    codegen.exec(
+                        Opcode::MStore,
+                        [
+                            add(slot(pointer), constant(32u8)),
+                            and(slot(value), not(constant(0xffu8))),
+                        ],
+                    );
+                });
+                self.leave(&[pointer]);
+            }
+            Type::Array { base, length } => {
+                let (data, count, first) = match length {
+                    Some(_length) => {
+                        let count = _length.to_usize().unwrap_or_default();
+                        let pointer = self.allocate(constant(32 * count));
+                        (slot(key), constant(count), (pointer, 0))
+                    }
+                    None => {
+                        let data = self.hash_word(slot(key));
+                        let count = self.let_(sload(slot(key)));
+                        let pointer =
+                            self.allocate(add(mul(slot(count), constant(32u8)), constant(32u8)));
+                        self.exec(Opcode::MStore, [slot(pointer), slot(count)]);
+                        (slot(data), slot(count), (pointer, 32usize))
+                    }
+                };
+                let (pointer, offset) = first;
+                self.for_range(count, |codegen, index| {
+                    let (element, element_offset) =
+                        codegen.element_position(base, data.clone(), slot(index));
+                    let value = codegen.storage_load(base, element, element_offset);
+                    codegen.exec(
+                        Opcode::MStore,
+                        [
+                            add(
+                                slot(pointer),
+                                add(constant(offset), mul(slot(index), constant(32u8))),
+                            ),
+                            slot(value),
+                        ],
+                    );
+                    codegen.pop(1);
+                });
+                self.leave(&[pointer]);
+            }
+            Type::Struct { .. } => {
+                let fields = self.struct_fields(ty);
+                let layout = self.storage_type(ty).members.unwrap_or_default();
+                let pointer = self.allocate(constant(32 * fields.len()));
+                for (index, (field, member)) in fields.iter().zip(&layout).enumerate() {
+                    let target = add(slot(pointer), constant(32 * index));
+                    if matches!(field, Type::Mapping { .. }) {
+                        self.exec(Opcode::MStore, [target, constant(0u8)]);
+                        continue;
+                    }
+                    let value = self.storage_load(
+                        field,
+                        add(slot(key), constant(member.slot.clone())),
+                        constant(member.offset),
+                    );
+                    self.exec(Opcode::MStore, [target, slot(value)]);
+                    self.pop(1);
+                }
+                self.leave(&[pointer]);
+            }
+            _ => {
+                let value = self.let_(constant(0u8));
+                self.leave(&[value]);
+            }
+        }
+    }
+
+    /* (pointer, slot) */
+    pub fn memory_to_storage_routine(&mut self, ty: &Type) {
+        let (pointer, key) = (1, 2);
+        match ty {
+            Type::String | Type::Bytes => {
+                self.call_routine(Routine::ClearStorage(ty.clone()), vec![slot(key)], 0);
+                let length = self.let_(mload(slot(pointer)));
+                let kept = |length: Word| not(shr(mul(length, constant(8u8)), not(constant(0u8))));
+                self.if_(lt(slot(length), constant(32u8)), |codegen| {
+                    codegen.exec(
+                        Opcode::SStore,
+                        [
+                            slot(key),
+                            or(
+                                and(
+                                    mload(add(slot(pointer), constant(32u8))),
+                                    kept(slot(length)),
+                                ),
+                                mul(slot(length), constant(2u8)),
+                            ),
+                        ],
+                    );
+                });
+                self.if_(iszero(lt(slot(length), constant(32u8))), |codegen| {
+                    codegen.exec(
+                        Opcode::SStore,
+                        [
+                            slot(key),
+                            add(mul(slot(length), constant(2u8)), constant(1u8)),
+                        ],
+                    );
+                    let data = codegen.hash_word(slot(key));
+                    codegen.for_range(
+                        div(add(slot(length), constant(31u8)), constant(32u8)),
+                        |codegen, index| {
+                            let offset = mul(slot(index), constant(32u8));
+                            codegen.exec(
+                                Opcode::SStore,
+                                [
+                                    add(slot(data), slot(index)),
+                                    and(
+                                        mload(add(
+                                            slot(pointer),
+                                            add(constant(32u8), offset.clone()),
+                                        )),
+                                        kept(sub(slot(length), offset)),
+                                    ),
+                                ],
+                            );
+                        },
+                    );
+                    codegen.pop(1);
+                });
+                self.leave(&[]);
+            }
+            Type::Array { base, length } => {
+                let (data, count, first) = match length {
+                    Some(_length) => (
+                        slot(key),
+                        constant(_length.to_usize().unwrap_or_default()),
+                        0usize,
+                    ),
+                    None => {
+                        self.call_routine(Routine::ClearStorage(ty.clone()), vec![slot(key)], 0);
+                        self.exec(Opcode::SStore, [slot(key), mload(slot(pointer))]);
+                        let data = self.hash_word(slot(key));
+                        (slot(data), mload(slot(pointer)), 32)
+                    }
+                };
+                self.for_range(count, |codegen, index| {
+                    let (element, offset) =
+                        codegen.element_position(base, data.clone(), slot(index));
+                    let value = mload(add(
+                        slot(pointer),
+                        add(constant(first), mul(slot(index), constant(32u8))),
+                    ));
+                    if base.is_value_type() {
+                        codegen.storage_write(base, element, offset, value);
+                    } else {
+                        codegen.call_routine(
+                            Routine::MemoryToStorage((**base).clone()),
+                            vec![value, element],
+                            0,
+                        );
+                    }
+                });
+                self.leave(&[]);
+            }
+            Type::Struct { .. } => {
+                let fields = self.struct_fields(ty);
+                let layout = self.storage_type(ty).members.unwrap_or_default();
+                for (index, (field, member)) in fields.iter().zip(&layout).enumerate() {
+                    let value = mload(add(slot(pointer), constant(32 * index)));
+                    let target = add(slot(key), constant(member.slot.clone()));
+                    match field {
+                        Type::Mapping { .. } => {}
+                        field if field.is_value_type() => {
+                            self.storage_write(field, target, constant(member.offset), value)
+                        }
+                        _ => self.call_routine(
+                            Routine::MemoryToStorage(field.clone()),
+                            vec![value, target],
+                            0,
+                        ),
+                    }
+                }
+                self.leave(&[]);
+            }
+            _ => self.leave(&[]),
+        }
+    }
+
+    /* (slot) */
+    pub fn clear_storage_routine(&mut self, ty: &Type) {
+        let key = 1;
+        match ty {
+            Type::String | Type::Bytes => {
+                let value = self.let_(sload(slot(key)));
+                self.if_(and(slot(value), constant(1u8)), |codegen| {
+                    let data = codegen.hash_word(slot(key));
+                    codegen.for_range(
+                        div(
+                            add(shr(constant(1u8), slot(value)), constant(31u8)),
+                            constant(32u8),
+                        ),
+                        |codegen, index| {
+                            codegen.exec(
+                                Opcode::SStore,
+                                [add(slot(data), slot(index)), constant(0u8)],
+                            );
+                        },
+                    );
+                    codegen.pop(1);
+                });
+                self.exec(Opcode::SStore, [slot(key), constant(0u8)]);
+            }
+            Type::Array { base, length } => {
+                let (data, count) = match length {
+                    Some(_length) => (slot(key), constant(_length.to_usize().unwrap_or_default())),
+                    None => {
+                        let data = self.hash_word(slot(key));
+                        (slot(data), sload(slot(key)))
+                    }
+                };
+                let size = self.storage_size(base);
+                if base.is_value_type() {
+                    let words = if size <= 16 {
+                        div(add(count, constant(32 / size - 1)), constant(32 / size))
+                    } else {
+                        mul(count, constant(self.storage_slots(base)))
+                    };
+                    self.for_range(words, |codegen, index| {
+                        codegen.exec(
+                            Opcode::SStore,
+                            [add(data.clone(), slot(index)), constant(0u8)],
+                        );
+                    });
+                } else if !matches!(**base, Type::Mapping { .. }) {
+                    let slots = self.storage_slots(base);
+                    self.for_range(count, |codegen, index| {
+                        codegen.call_routine(
+                            Routine::ClearStorage((**base).clone()),
+                            vec![add(data.clone(), mul(slot(index), constant(slots)))],
+                            0,
+                        );
+                    });
+                }
+                if length.is_none() {
+                    self.exec(Opcode::SStore, [slot(key), constant(0u8)]);
+                }
+            }
+            Type::Struct { .. } => {
+                let fields = self.struct_fields(ty);
+                let layout = self.storage_type(ty).members.unwrap_or_default();
+                for (field, member) in fields.iter().zip(&layout) {
+                    let target = add(slot(key), constant(member.slot.clone()));
+                    match field {
+                        Type::Mapping { .. } => {}
+                        field if field.is_value_type() => {
+                            self.exec(Opcode::SStore, [target, constant(0u8)])
+                        }
+                        _ => {
+                            self.call_routine(Routine::ClearStorage(field.clone()), vec![target], 0)
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.leave(&[]);
+    }
+}
@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use crate::mods::types::{line_descriptors::LineDescriptions, token::Token};
+
+const INDENT: &str = "    ";
+
+/* RE-INDENTS `source` BY BRACKET DEPTH, STRIPS TRAILING WHITESPACE AND SQUEEZES BLANK LINES.
+COMMENTS ARE KEPT, AND LINES INSIDE A MULTI-LINE STRING ARE LEFT UNTOUCHED */
+pub fn format_source(source: &str, tokens: &[LineDescriptions<Token>]) -> String {
+    let block_comments = block_comments(source, tokens);
+    let mut output = String::with_capacity(source.len());
+    let mut depth: usize = 0;
+    let mut next_token = 0;
+    let mut blank_lines = 0;
+    let mut offset = 0;
+
+    for line in source.split_inclusive('\n') {
+        let line_start = offset;
+        let line_end = offset + line.len();
+        offset = line_end;
+        let text = line.trim_end();
+
+        /* A LINE THAT CONTINUES A STRING LITERAL IS PART OF ITS VALUE */
+        let inside_token = next_token > 0 && tokens[next_token - 1].span.end > line_start;
+
+        let mut line_tokens = Vec::new();
+        while next_token < tokens.len() && tokens[next_token].span.start < line_end {
+            if tokens[next_token].span.start >= line_start {
+                line_tokens.push(&tokens[next_token].data);
+            }
+            next_token += 1;
+        }
+
+        if inside_token {
+            output.push_str(line.trim_end_matches(['\n', '\r']));
+            output.push('\n');
+        } else if text.trim_start().is_empty() {
+            blank_lines += 1;
+        } else {
+            if blank_lines > 0 && !output.is_empty() {
+                output.push('\n');
+            }
+            blank_lines = 0;
+            let leading_closers = line_tokens
+                .iter()
+                .take_while(|token| is_closer(token))
+                .count();
+            let indent = INDENT.repeat(depth.saturating_sub(leading_closers));
+            let content = text.trim_start();
+            output.push_str(&indent);
+            if content.starts_with('*')
+                && block_comments
+                    .iter()
+                    .any(|comment| comment.start < line_start && line_start < comment.end)
+            {
+                output.push(' ');
+            }
+            output.push_str(content);
+            output.push('\n');
+        }
+
+        for token in line_tokens {
+            if is_opener(token) {
+                depth += 1;
+            } else if is_closer(token) {
+                depth = depth.saturating_sub(1);
+            }
+        }
+    }
+    output
+}
+
+fn is_opener(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::OpenBraces | Token::OpenParenthesis | Token::OpenSquareBracket
+    )
+}
+
+fn is_closer(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::CloseBraces | Token::CloseParenthesis | Token::CloseSquareBracket
+    )
+}
+
+/* BYTE RANGES OF /* */ COMMENTS. THEY CAN ONLY APPEAR IN THE GAPS BETWEEN TOKENS */
+fn block_comments(source: &str, tokens: &[LineDescriptions<Token>]) -> Vec<Range<usize>> {
+    let mut comments = Vec::new();
+    let mut gap_start = 0;
+    let gaps = tokens
+        .iter()
+        .map(|token| token.span.start..token.span.end)
+        .chain(std::iter::once(source.len()..source.len()));
+    for token in gaps {
+        let gap = &source[gap_start..token.start];
+        let mut index = 0;
+        while index < gap.len() {
+            if gap[index..].starts_with("//") {
+                index += gap[index..].find('\n').unwrap_or(gap.len() - index);
+            } else if gap[index..].starts_with("/*") {
+                let length = gap[index + 2..]
+                    .find("*/")
+                    .map_or(gap.len() - index, |end| end + 4);
+                comments.push(gap_start + index..gap_start + index + length);
+                index += length;
+            } else {
+                index += gap[index..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        gap_start = token.end;
+    }
+    comments
+}
@@ -0,0 +1,131 @@
+use crate::mods::{
+    functions::{controllers::abi::signature, helpers::hash_helper::selector},
+    types::{
+        ast::{
+            ContractKind, ContractPart, FunctionDefinition, FunctionKind, StateMutability,
+            Visibility,
+        },
+        bindings::{Bindings, ContractId, Declaration},
+        compilation::GaslessFunction,
+        compiler_errors::{CompilerError, SemanticError},
+        diagnostics::Diagnostics,
+        semantic_types::TypeTable,
+        source_graph::SourceGraph,
+    },
+};
+
+/* A `gasless` FUNCTION IS PAID FOR BY A RELAYER, SO IT MUST BE CALLABLE FROM OUTSIDE, CHANGE
+STATE (A VIEW CALL COSTS NOTHING ANYWAY) AND NOT EXPECT ETHER FROM THE USER */
+pub fn check_gasless(graph: &SourceGraph, diagnostics: &mut Diagnostics) {
+    for unit in &graph.units {
+        for contract in &unit.ast.contracts {
+            for part in &contract.parts {
+                let ContractPart::Function(function) = part else {
+                    continue;
+                };
+                if !function.gasless {
+                    continue;
+                }
+                let problem = if function.kind != FunctionKind::Function {
+                    Some((
+                        format!("`gasless` is not allowed on a {}", kind_name(function.kind)),
+                        "only named functions can be sponsored",
+                    ))
+                } else if contract.kind == ContractKind::Library {
+                    Some((
+                        "`gasless` is not allowed in libraries".to_string(),
+                        "library functions cannot be called through a relayer",
+                    ))
+                } else if matches!(
+                    function.visibility,
+                    Some(Visibility::Internal | Visibility::Private)
+                ) {
+                    Some((
+                        "`gasless` functions must be `external` or `public`".to_string(),
+                        "a relayer cannot call this function",
+                    ))
+                } else {
+                    match function.mutability {
+                        Some(StateMutability::View | StateMutability::Pure) => Some((
+                            "`gasless` is not allowed on `view` or `pure` functions".to_string(),
+                            "calls that do not change state already cost nothing",
+                        )),
+                        Some(StateMutability::Payable) => Some((
+                            "`gasless` functions cannot be `payable`".to_string(),
+                            "a sponsored call cannot carry ether from its sender",
+                        )),
+                        None => None,
+                    }
+                };
+                if let Some((message, label)) = problem {
+                    let span = function
+                        .name
+                        .as_ref()
+                        .map_or(function.span, |name| name.span);
+                    diagnostics.push(
+                        CompilerError::SemanticError(SemanticError::InvalidGasless(message))
+                            .diagnostic()
+                            .with_label(span, label),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/* THE `gasless` FUNCTIONS OF A CONTRACT, INHERITED ONES INCLUDED, SORTED BY SIGNATURE */
+pub fn sponsored_functions(
+    contract: ContractId,
+    graph: &SourceGraph,
+    bindings: &Bindings,
+    types: &TypeTable,
+) -> Vec<GaslessFunction> {
+    let mut functions: Vec<GaslessFunction> = bindings
+        .members
+        .get(&contract)
+        .into_iter()
+        .flat_map(|members| members.values().flatten())
+        .filter_map(|declaration| gasless_function(declaration, graph))
+        .map(|function| {
+            let name = function.name.as_ref().unwrap().name.clone();
+            let signature = signature(&name, &function.parameters, graph, types);
+            GaslessFunction {
+                selector: selector(&signature),
+                name,
+                signature,
+            }
+        })
+        .collect();
+    functions.sort_by(|a, b| a.signature.cmp(&b.signature));
+    functions.dedup_by(|a, b| a.signature == b.signature);
+    functions
+}
+
+fn gasless_function<'a>(
+    declaration: &Declaration,
+    graph: &'a SourceGraph,
+) -> Option<&'a FunctionDefinition> {
+    let Declaration::Member {
+        file_id,
+        contract,
+        part,
+    } = declaration
+    else {
+        return None;
+    };
+    match &graph.units[*file_id].ast.contracts[*contract].parts[*part] {
+        ContractPart::Function(function) if function.gasless && function.name.is_some() => {
+            Some(function)
+        }
+        _ => None,
+    }
+}
+
+fn kind_name(kind: FunctionKind) -> &'static str {
+    match kind {
+        FunctionKind::Function => "function",
+        FunctionKind::Constructor => "constructor",
+        FunctionKind::Fallback => "fallback function",
+        FunctionKind::Receive => "receive function",
+    }
+}
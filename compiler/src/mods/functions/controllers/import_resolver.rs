@@ -0,0 +1,280 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::Path,
+};
+
+use crate::mods::{
+    functions::controllers::{process_file_contents::read_file_contents, sub_main::parse_tokens},
+    types::{
+        ast::{ContractKind, ContractPart, ImportSymbols},
+        compiler_errors::{CompilerError, IOError, SemanticError},
+        diagnostics::Diagnostics,
+        line_descriptors::Span,
+        settings::{Remapping, Settings},
+        source_graph::{ResolvedUnit, SourceGraph, Symbol},
+        source_map::SourceMap,
+        token::lex,
+    },
+};
+
+/* PARSES `entries` AND EVERY UNIT THEY IMPORT, DIRECTLY OR NOT, THEN WORKS OUT WHICH NAMES
+EACH UNIT CAN SEE. A UNIT IS LOADED ONCE NO MATTER HOW MANY TIMES IT IS IMPORTED */
+pub fn resolve_imports(
+    sources: &mut SourceMap,
+    entries: &[usize],
+    settings: &Settings,
+    diagnostics: &mut Diagnostics,
+) -> SourceGraph {
+    let mut units = Vec::new();
+    let mut pending: VecDeque<usize> = entries.iter().copied().collect();
+
+    while let Some(file_id) = pending.pop_front() {
+        let tokens = lex(
+            &sources.get(file_id).unwrap().contents,
+            file_id,
+            diagnostics,
+        );
+        let ast = parse_tokens(tokens.clone(), diagnostics);
+        let importer = sources.get(file_id).unwrap().name.clone();
+        let mut imports = Vec::new();
+        for import in &ast.imports {
+            let name = source_unit_name(&importer, &import.path, &settings.remappings);
+            let imported = match sources.find(&name) {
+                Some(_file_id) => Some(_file_id),
+                None => match load(&name, settings) {
+                    Ok(contents) => {
+                        let _file_id = sources.add(name, contents);
+                        pending.push_back(_file_id);
+                        Some(_file_id)
+                    }
+                    Err(error) => {
+                        diagnostics.push(error.at(import.span));
+                        None
+                    }
+                },
+            };
+            imports.push(imported);
+        }
+        units.push(ResolvedUnit {
+            file_id,
+            tokens,
+            ast,
+            imports,
+            scope: BTreeMap::new(),
+        });
+    }
+    units.sort_by_key(|unit| unit.file_id);
+
+    /* IMPORTS MAY FORM CYCLES, SO THE SCOPES GROW TOGETHER UNTIL NONE CHANGES. ONLY THE LAST
+    ROUND, AGAINST COMPLETE SCOPES, REPORTS CLASHES AND MISSING NAMES */
+    let mut scopes = vec![BTreeMap::new(); units.len()];
+    loop {
+        let mut ignored = Diagnostics::default();
+        let next: Vec<_> = (0..units.len())
+            .map(|file_id| build_scope(file_id, &units, &scopes, sources, &mut ignored))
+            .collect();
+        if next == scopes {
+            break;
+        }
+        scopes = next;
+    }
+    for file_id in 0..units.len() {
+        units[file_id].scope = build_scope(file_id, &units, &scopes, sources, diagnostics);
+    }
+
+    SourceGraph {
+        units,
+        entries: entries.to_vec(),
+    }
+}
+
+/* SOURCE UNIT NAME OF AN IMPORT. RELATIVE PATHS ARE JOINED TO THE IMPORTING UNIT'S DIRECTORY,
+THEN THE BEST MATCHING REMAPPING IS APPLIED */
+pub fn source_unit_name(importer: &str, path: &str, remappings: &[Remapping]) -> String {
+    let name = if path.starts_with("./") || path.starts_with("../") {
+        let directory = importer.rfind('/').map_or("", |index| &importer[..=index]);
+        normalize_path(&format!("{directory}{path}"))
+    } else {
+        path.to_string()
+    };
+
+    /* THE LONGEST CONTEXT WINS, THEN THE LONGEST PREFIX, THEN THE ONE GIVEN LAST */
+    let remapping = remappings
+        .iter()
+        .filter(|remapping| {
+            name.starts_with(&remapping.prefix)
+                && remapping
+                    .context
+                    .as_ref()
+                    .is_none_or(|context| importer.starts_with(context.as_str()))
+        })
+        .max_by_key(|remapping| {
+            (
+                remapping.context.as_ref().map_or(0, String::len),
+                remapping.prefix.len(),
+            )
+        });
+    match remapping {
+        Some(_remapping) => format!("{}{}", _remapping.target, &name[_remapping.prefix.len()..]),
+        None => name,
+    }
+}
+
+/* REMOVES `.` AND EMPTY SEGMENTS AND FOLDS `..` INTO THE SEGMENT BEFORE IT */
+pub fn normalize_path(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    let normalized = segments.join("/");
+    if path.starts_with('/') {
+        format!("/{normalized}")
+    } else {
+        normalized
+    }
+}
+
+/* LOOKS A SOURCE UNIT UP UNDER THE BASE PATH, THEN UNDER EACH INCLUDE PATH */
+fn load(name: &str, settings: &Settings) -> Result<String, CompilerError> {
+    let base_path = settings.base_path.as_deref().unwrap_or(Path::new(""));
+    let roots =
+        std::iter::once(base_path).chain(settings.include_paths.iter().map(|path| path.as_path()));
+    for root in roots {
+        match read_file_contents(&root.join(name)) {
+            Ok(contents) => return Ok(contents),
+            Err(CompilerError::IOError(IOError::FileNotFound(_))) => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    Err(CompilerError::IOError(IOError::FileNotFound(
+        name.to_string(),
+    )))
+}
+
+/* NAMES VISIBLE AT FILE LEVEL IN `file_id`, GIVEN THE SCOPES OF THE UNITS IT IMPORTS. A UNIT
+EXPORTS EVERYTHING IT CAN SEE, SO `import "x";` ALSO BRINGS IN WHAT "x" ITSELF IMPORTED */
+fn build_scope(
+    file_id: usize,
+    units: &[ResolvedUnit],
+    scopes: &[BTreeMap<String, Symbol>],
+    sources: &SourceMap,
+    diagnostics: &mut Diagnostics,
+) -> BTreeMap<String, Symbol> {
+    let unit = &units[file_id];
+    let mut scope = BTreeMap::new();
+    for (index, contract) in unit.ast.contracts.iter().enumerate() {
+        if contract.kind != ContractKind::File {
+            declare(
+                &mut scope,
+                &contract.name.name,
+                Symbol::Contract { file_id, index },
+                contract.name.span,
+                diagnostics,
+            );
+            continue;
+        }
+        /* CLASHES BETWEEN FILE LEVEL DEFINITIONS ARE REPORTED WITH THE MEMBERS OF THE FILE */
+        for (part, definition) in contract.parts.iter().enumerate() {
+            let name = match definition {
+                ContractPart::StateVariable(variable) => &variable.name,
+                ContractPart::Function(function) => match &function.name {
+                    Some(_name) => _name,
+                    None => continue,
+                },
+                ContractPart::Struct(definition) => &definition.name,
+                ContractPart::Enum(definition) => &definition.name,
+                _ => continue,
+            };
+            if matches!(
+                scope.get(&name.name),
+                Some(Symbol::Member { contract, .. }) if *contract == index
+            ) {
+                continue;
+            }
+            let symbol = Symbol::Member {
+                file_id,
+                contract: index,
+                part,
+            };
+            declare(&mut scope, &name.name, symbol, name.span, diagnostics);
+        }
+    }
+    for (index, error) in unit.ast.custom_errors.iter().enumerate() {
+        declare(
+            &mut scope,
+            &error.name.name,
+            Symbol::Error { file_id, index },
+            error.name.span,
+            diagnostics,
+        );
+    }
+
+    for (import, imported) in unit.ast.imports.iter().zip(&unit.imports) {
+        let Some(imported) = *imported else {
+            continue;
+        };
+        match &import.symbols {
+            ImportSymbols::All(None) => {
+                for (name, symbol) in &scopes[imported] {
+                    declare(&mut scope, name, *symbol, import.span, diagnostics);
+                }
+            }
+            ImportSymbols::All(Some(alias)) | ImportSymbols::Glob(alias) => {
+                declare(
+                    &mut scope,
+                    &alias.name,
+                    Symbol::Unit(imported),
+                    alias.span,
+                    diagnostics,
+                );
+            }
+            ImportSymbols::Named(aliases) => {
+                for alias in aliases {
+                    let local = alias.alias.as_ref().unwrap_or(&alias.name);
+                    match scopes[imported].get(&alias.name.name) {
+                        Some(symbol) => {
+                            declare(&mut scope, &local.name, *symbol, local.span, diagnostics)
+                        }
+                        None => diagnostics.push(
+                            CompilerError::SemanticError(SemanticError::DeclarationNotFound(
+                                alias.name.name.clone(),
+                            ))
+                            .at(alias.name.span)
+                            .with_note(format!(
+                                "\"{}\" does not declare or import it",
+                                sources.get(imported).unwrap().name
+                            )),
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    scope
+}
+
+/* THE SAME DECLARATION MAY BE IMPORTED MORE THAN ONCE, A DIFFERENT ONE UNDER A TAKEN NAME MAY NOT */
+fn declare(
+    scope: &mut BTreeMap<String, Symbol>,
+    name: &str,
+    symbol: Symbol,
+    span: Span,
+    diagnostics: &mut Diagnostics,
+) {
+    match scope.get(name) {
+        Some(existing) if *existing != symbol => diagnostics.push(
+            CompilerError::SemanticError(SemanticError::Redeclaration(name.to_string())).at(span),
+        ),
+        Some(_) => {}
+        None => {
+            scope.insert(name.to_string(), symbol);
+        }
+    }
+}
@@ -0,0 +1,785 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::mods::{
+    functions::controllers::abi::getter,
+    types::{
+        ast::{
+            ContractDefinition, ContractKind, ContractPart, FunctionDefinition, FunctionKind,
+            Identifier, StateMutability, Visibility,
+        },
+        bindings::{BaseArguments, Bindings, ContractId, Declaration},
+        compiler_errors::{CompilerError, SemanticError},
+        diagnostics::{Diagnostic, Diagnostics},
+        line_descriptors::Span,
+        semantic_types::{Type, TypeTable},
+        source_graph::SourceGraph,
+    },
+};
+
+/* ------------------------------ LINEARIZATION ------------------------------ */
+
+/* C3 LINEARIZATION OF EVERY CONTRACT, AS IN SOLIDITY: BASES ARE LISTED FROM THE MOST BASE-LIKE
+TO THE MOST DERIVED, SO `contract C is A, B` SEARCHES `C`, THEN `B`, THEN `A` */
+pub fn linearize_contracts(
+    graph: &SourceGraph,
+    bindings: &mut Bindings,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut linearizer = Linearizer {
+        graph,
+        bindings,
+        diagnostics,
+        results: BTreeMap::new(),
+        visiting: Vec::new(),
+    };
+    for unit in &graph.units {
+        for index in 0..unit.ast.contracts.len() {
+            linearizer.linearize((unit.file_id, index));
+        }
+    }
+    let results = linearizer.results;
+    bindings.linearization = results
+        .into_iter()
+        .filter_map(|(contract, linearization)| Some((contract, linearization?)))
+        .collect();
+}
+
+struct Linearizer<'a> {
+    graph: &'a SourceGraph,
+    bindings: &'a Bindings,
+    diagnostics: &'a mut Diagnostics,
+    /* None FOR A CONTRACT THAT CANNOT BE LINEARIZED, SO THAT IT IS REPORTED ONCE */
+    results: BTreeMap<ContractId, Option<Vec<ContractId>>>,
+    visiting: Vec<ContractId>,
+}
+
+impl Linearizer<'_> {
+    fn linearize(&mut self, contract: ContractId) -> Option<Vec<ContractId>> {
+        if let Some(_result) = self.results.get(&contract) {
+            return _result.clone();
+        }
+        self.visiting.push(contract);
+        let definition = definition(self.graph, contract);
+        let bases = self
+            .bindings
+            .bases
+            .get(&contract)
+            .cloned()
+            .unwrap_or_default();
+
+        let mut lists = Vec::new();
+        let mut failed = false;
+        for (index, &base) in bases.iter().enumerate().rev() {
+            if let Some(_position) = self.visiting.iter().position(|id| *id == base) {
+                let cycle: Vec<&str> = self.visiting[_position..]
+                    .iter()
+                    .chain([&base])
+                    .map(|id| definition_name(self.graph, *id))
+                    .collect();
+                self.diagnostics.push(
+                    semantic_error(format!(
+                        "cyclic inheritance: `{}` inherits from itself",
+                        definition_name(self.graph, base)
+                    ))
+                    .with_label(definition.bases[index].span, "closes the cycle")
+                    .with_note(format!("the cycle is {}", cycle.join(" -> "))),
+                );
+                failed = true;
+                continue;
+            }
+            match self.linearize(base) {
+                Some(_linearization) => lists.push(_linearization),
+                None => failed = true,
+            }
+        }
+        lists.push(bases.iter().rev().copied().collect());
+        self.visiting.pop();
+
+        let result = if failed {
+            None
+        } else {
+            let merged = merge(lists);
+            if merged.is_none() {
+                let span = Span {
+                    end: definition.bases.last().map_or(0, |base| base.span.end),
+                    ..definition.bases[0].span
+                };
+                self.diagnostics.push(
+                    semantic_error(format!(
+                        "linearization of the inheritance graph of `{}` is impossible",
+                        definition.name.name
+                    ))
+                    .with_label(span, "these bases conflict with the order of their own bases")
+                    .with_help(
+                        "list bases from the most base-like to the most derived, e.g. `is A, B` when `B is A`",
+                    ),
+                );
+            }
+            merged.map(|merged| [contract].into_iter().chain(merged).collect())
+        };
+        self.results.insert(contract, result.clone());
+        result
+    }
+}
+
+/* REPEATEDLY TAKES THE FIRST HEAD THAT IS IN NO LIST'S TAIL. None WHEN NO HEAD QUALIFIES */
+fn merge(mut lists: Vec<Vec<ContractId>>) -> Option<Vec<ContractId>> {
+    let mut merged = Vec::new();
+    loop {
+        lists.retain(|list| !list.is_empty());
+        if lists.is_empty() {
+            return Some(merged);
+        }
+        let head = lists
+            .iter()
+            .map(|list| list[0])
+            .find(|head| lists.iter().all(|list| !list[1..].contains(head)))?;
+        for list in &mut lists {
+            if list[0] == head {
+                list.remove(0);
+            }
+        }
+        merged.push(head);
+    }
+}
+
+/* ------------------------------ OVERRIDES ------------------------------ */
+
+/* CHECKS `virtual` AND `override`, THAT NON-ABSTRACT CONTRACTS IMPLEMENT EVERY FUNCTION, AND
+RESOLVES WHERE THE ARGUMENTS OF EACH BASE CONSTRUCTOR ARE GIVEN */
+pub fn check_inheritance(
+    graph: &SourceGraph,
+    bindings: &mut Bindings,
+    types: &TypeTable,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut checker = InheritanceChecker {
+        graph,
+        bindings,
+        types,
+        diagnostics,
+        reported: HashSet::new(),
+    };
+    let mut constructor_arguments = Vec::new();
+    for unit in &graph.units {
+        for index in 0..unit.ast.contracts.len() {
+            let contract = (unit.file_id, index);
+            if !checker.bindings.linearization.contains_key(&contract) {
+                continue;
+            }
+            checker.check_overrides(contract);
+            checker.check_implemented(contract);
+            constructor_arguments.push((contract, checker.constructor_arguments(contract)));
+        }
+    }
+    bindings.constructor_arguments = constructor_arguments.into_iter().collect();
+}
+
+/* A FUNCTION, MODIFIER OR PUBLIC STATE VARIABLE THAT CAN OVERRIDE OR BE OVERRIDDEN */
+#[derive(Clone)]
+struct Overridable<'a> {
+    contract: ContractId,
+    name: &'a Identifier,
+    /* PARAMETER TYPES, THE KEYS OF A GETTER. None FOR MODIFIERS, MATCHED BY NAME ONLY */
+    parameters: Option<Vec<Type>>,
+    function: Option<&'a FunctionDefinition>,
+    is_virtual: bool,
+    overrides: &'a Option<Vec<Vec<Identifier>>>,
+    implemented: bool,
+}
+
+impl Overridable<'_> {
+    fn matches(&self, other: &Overridable) -> bool {
+        self.name.name == other.name.name && self.parameters == other.parameters
+    }
+
+    fn describe(&self) -> &'static str {
+        match (self.parameters.is_some(), self.function.is_some()) {
+            (false, _) => "modifier",
+            (true, true) => "function",
+            (true, false) => "state variable",
+        }
+    }
+}
+
+struct InheritanceChecker<'a> {
+    graph: &'a SourceGraph,
+    bindings: &'a Bindings,
+    types: &'a TypeTable,
+    diagnostics: &'a mut Diagnostics,
+    reported: HashSet<(Span, Span)>,
+}
+
+impl<'a> InheritanceChecker<'a> {
+    fn overridables(&self, contract: ContractId) -> Vec<Overridable<'a>> {
+        let definition = definition(self.graph, contract);
+        let interface = definition.kind == ContractKind::Interface;
+        definition
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                ContractPart::Function(function) if function.kind == FunctionKind::Function => {
+                    Some(Overridable {
+                        contract,
+                        name: function.name.as_ref()?,
+                        parameters: Some(
+                            function
+                                .parameters
+                                .iter()
+                                .map(|parameter| self.type_name(&parameter.ty.span))
+                                .collect(),
+                        ),
+                        function: Some(function),
+                        is_virtual: function.is_virtual || interface,
+                        overrides: &function.overrides,
+                        implemented: function.body.is_some(),
+                    })
+                }
+                ContractPart::Modifier(modifier) => Some(Overridable {
+                    contract,
+                    name: &modifier.name,
+                    parameters: None,
+                    function: None,
+                    is_virtual: modifier.is_virtual,
+                    overrides: &modifier.overrides,
+                    implemented: modifier.body.is_some(),
+                }),
+                ContractPart::StateVariable(variable)
+                    if variable.visibility == Some(Visibility::Public) =>
+                {
+                    Some(Overridable {
+                        contract,
+                        name: &variable.name,
+                        parameters: Some(getter(&self.type_name(&variable.ty.span)).0),
+                        function: None,
+                        is_virtual: false,
+                        overrides: &variable.overrides,
+                        implemented: true,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /* THE DEFINITIONS OF `item` IN THE BASES OF `contract` THAT NO OTHER SUCH DEFINITION
+    OVERRIDES, THE ONES AN OVERRIDE HAS TO NAME */
+    fn overridden(&self, contract: ContractId, item: &Overridable) -> Vec<Overridable<'a>> {
+        let found: Vec<Overridable> = self.bindings.linearization[&contract][1..]
+            .iter()
+            .flat_map(|base| self.overridables(*base))
+            .filter(|candidate| candidate.matches(item))
+            .collect();
+        let contracts: Vec<ContractId> = found.iter().map(|found| found.contract).collect();
+        found
+            .into_iter()
+            .filter(|candidate| {
+                !contracts.iter().any(|other| {
+                    *other != candidate.contract
+                        && self.bindings.derives_from(*other, candidate.contract)
+                })
+            })
+            .collect()
+    }
+
+    fn check_overrides(&mut self, contract: ContractId) {
+        let own = self.overridables(contract);
+        for item in &own {
+            let overridden = self.overridden(contract, item);
+            self.check_override(item, &overridden);
+        }
+
+        /* TWO UNRELATED BASES DEFINING THE SAME FUNCTION, WHICH THE CONTRACT DOES NOT */
+        let definition = definition(self.graph, contract);
+        if definition.kind == ContractKind::Interface {
+            return;
+        }
+        let mut reported: Vec<(String, Option<Vec<Type>>)> = Vec::new();
+        for base in &self.bindings.linearization[&contract][1..] {
+            for item in self.overridables(*base) {
+                if own.iter().any(|own| own.matches(&item))
+                    || reported.contains(&(item.name.name.clone(), item.parameters.clone()))
+                {
+                    continue;
+                }
+                let overridden = self.overridden(contract, &item);
+                if overridden.len() < 2 {
+                    continue;
+                }
+                reported.push((item.name.name.clone(), item.parameters.clone()));
+                let mut diagnostic = semantic_error(format!(
+                    "`{}` must override {} `{}`, it is defined by {}",
+                    definition.name.name,
+                    item.describe(),
+                    item.name.name,
+                    self.contract_list(&overridden)
+                ))
+                .with_label(definition.name.span, "inherits more than one definition");
+                for other in &overridden {
+                    diagnostic = diagnostic.with_secondary_label(other.name.span, "defined here");
+                }
+                self.diagnostics.push(diagnostic.with_help(format!(
+                    "add a {} `{}` with `override({})`",
+                    item.describe(),
+                    item.name.name,
+                    self.contract_names(&overridden)
+                )));
+            }
+        }
+    }
+
+    fn check_override(&mut self, item: &Overridable, overridden: &[Overridable]) {
+        let Some(_overrides) = item.overrides else {
+            if let Some(_base) = overridden.first() {
+                self.diagnostics.push(
+                    semantic_error(format!(
+                        "{} `{}` overrides {} but is missing `override`",
+                        item.describe(),
+                        item.name.name,
+                        self.contract_list(overridden)
+                    ))
+                    .with_label(item.name.span, "missing `override`")
+                    .with_secondary_label(_base.name.span, "overridden definition")
+                    .with_help(match overridden.len() {
+                        1 => "add `override`".to_string(),
+                        _ => format!("add `override({})`", self.contract_names(overridden)),
+                    }),
+                );
+            }
+            return;
+        };
+        if overridden.is_empty() {
+            self.diagnostics.push(
+                semantic_error(format!(
+                    "{} `{}` is marked `override` but does not override anything",
+                    item.describe(),
+                    item.name.name
+                ))
+                .with_label(item.name.span, "nothing to override"),
+            );
+            return;
+        }
+
+        /* THE OVERRIDE LIST MUST NAME EXACTLY THE BASES THAT DEFINE IT WHEN THERE ARE SEVERAL */
+        let listed: Vec<(ContractId, Span)> = _overrides
+            .iter()
+            .filter_map(|path| {
+                let name = path.last()?;
+                match self.bindings.get(&name.span)?.first()? {
+                    Declaration::Contract { file_id, contract } => {
+                        Some(((*file_id, *contract), name.span))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+        if overridden.len() > 1 || !listed.is_empty() {
+            for (contract, span) in &listed {
+                if !overridden.iter().any(|base| base.contract == *contract) {
+                    self.diagnostics.push(
+                        semantic_error(format!(
+                            "invalid contract `{}` in the override list of `{}`",
+                            definition_name(self.graph, *contract),
+                            item.name.name
+                        ))
+                        .with_label(*span, "does not define an overridden definition"),
+                    );
+                }
+            }
+            let missing: Vec<Overridable> = overridden
+                .iter()
+                .filter(|base| {
+                    !listed
+                        .iter()
+                        .any(|(contract, _)| *contract == base.contract)
+                })
+                .cloned()
+                .collect();
+            if !missing.is_empty() {
+                self.diagnostics.push(
+                    semantic_error(format!(
+                        "{} `{}` needs to specify the overridden {}",
+                        item.describe(),
+                        item.name.name,
+                        self.contract_list(&missing)
+                    ))
+                    .with_label(item.name.span, "incomplete override list")
+                    .with_help(format!(
+                        "write `override({})`",
+                        self.contract_names(overridden)
+                    )),
+                );
+            }
+        }
+
+        for base in overridden {
+            self.check_compatible(item, base);
+        }
+    }
+
+    /* THE OVERRIDING DEFINITION KEEPS THE INTERFACE OF THE ONE IT REPLACES */
+    fn check_compatible(&mut self, item: &Overridable, base: &Overridable) {
+        let base_name = definition_name(self.graph, base.contract);
+        let Some(_base_function) = base.function.filter(|_| base.is_virtual) else {
+            let (message, help) = match base.parameters {
+                Some(_) if base.function.is_none() => (
+                    format!(
+                        "state variable `{}` of `{base_name}` cannot be overridden",
+                        base.name.name
+                    ),
+                    "rename one of them",
+                ),
+                _ if !base.is_virtual => (
+                    format!(
+                        "cannot override non-virtual {} `{}` of `{base_name}`",
+                        base.describe(),
+                        base.name.name
+                    ),
+                    "mark the base definition `virtual`",
+                ),
+                _ => return,
+            };
+            self.diagnostics.push(
+                semantic_error(message)
+                    .with_label(item.name.span, "overrides it")
+                    .with_secondary_label(base.name.span, "overridden definition")
+                    .with_help(help),
+            );
+            return;
+        };
+        let visibility = |function: Option<&FunctionDefinition>| {
+            function.map_or(Visibility::Public, |function| {
+                function.visibility.unwrap_or(Visibility::Public)
+            })
+        };
+        let (old, new) = (visibility(Some(_base_function)), visibility(item.function));
+        if old != new && !(old == Visibility::External && new == Visibility::Public) {
+            self.incompatible(
+                item,
+                base,
+                format!(
+                    "overriding {} changes visibility from `{}` to `{}`",
+                    item.describe(),
+                    visibility_name(old),
+                    visibility_name(new)
+                ),
+            );
+        }
+        if item.function.is_none() {
+            if old != Visibility::External {
+                self.incompatible(
+                    item,
+                    base,
+                    "public state variables can only override external functions".to_string(),
+                );
+            }
+            return;
+        }
+        let function = item.function.unwrap();
+
+        let (old, new) = (_base_function.mutability, function.mutability);
+        let allowed = old == new
+            || matches!(
+                (old, new),
+                (None, Some(StateMutability::View | StateMutability::Pure))
+                    | (Some(StateMutability::View), Some(StateMutability::Pure))
+            );
+        if !allowed {
+            self.incompatible(
+                item,
+                base,
+                format!(
+                    "overriding function changes state mutability from `{}` to `{}`",
+                    mutability_name(old),
+                    mutability_name(new)
+                ),
+            );
+        }
+
+        let returns = |function: &FunctionDefinition| -> Vec<Type> {
+            function
+                .returns
+                .iter()
+                .map(|parameter| self.type_name(&parameter.ty.span))
+                .collect()
+        };
+        if returns(_base_function) != returns(function) {
+            self.incompatible(
+                item,
+                base,
+                "overriding function has different return types".to_string(),
+            );
+        }
+
+        if _base_function.gasless != function.gasless {
+            self.incompatible(
+                item,
+                base,
+                if _base_function.gasless {
+                    "overriding function must also be `gasless`".to_string()
+                } else {
+                    "overriding function cannot be `gasless`, the function it overrides is not"
+                        .to_string()
+                },
+            );
+        }
+    }
+
+    fn incompatible(&mut self, item: &Overridable, base: &Overridable, message: String) {
+        self.diagnostics.push(
+            semantic_error(message)
+                .with_label(item.name.span, "overriding definition")
+                .with_secondary_label(base.name.span, "overridden definition"),
+        );
+    }
+
+    /* ------------------------------ ABSTRACT ------------------------------ */
+
+    /* A CONTRACT NOT MARKED `abstract` MUST BE DEPLOYABLE: EVERY FUNCTION IT HAS, ITS OWN OR
+    INHERITED, NEEDS A BODY */
+    fn check_implemented(&mut self, contract: ContractId) {
+        let definition = definition(self.graph, contract);
+        if definition.kind != ContractKind::Contract {
+            return;
+        }
+        let mut seen: Vec<Overridable> = Vec::new();
+        let mut missing: Vec<Overridable> = Vec::new();
+        for base in &self.bindings.linearization[&contract] {
+            for item in self.overridables(*base) {
+                if seen.iter().any(|seen| seen.matches(&item)) {
+                    continue;
+                }
+                if !item.implemented {
+                    missing.push(item.clone());
+                }
+                seen.push(item);
+            }
+        }
+        if missing.is_empty() {
+            return;
+        }
+        let mut diagnostic = semantic_error(format!(
+            "contract `{}` should be marked `abstract`",
+            definition.name.name
+        ))
+        .with_label(
+            definition.name.span,
+            "has functions without an implementation",
+        );
+        for item in &missing {
+            diagnostic = diagnostic.with_secondary_label(
+                item.name.span,
+                format!("`{}` is not implemented", item.name.name),
+            );
+        }
+        self.diagnostics.push(diagnostic.with_help(format!(
+            "implement them, or declare `abstract contract {}`",
+            definition.name.name
+        )));
+    }
+
+    /* ------------------------------ CONSTRUCTORS ------------------------------ */
+
+    /* EVERY BASE CONSTRUCTOR WITH PARAMETERS GETS ITS ARGUMENTS EXACTLY ONCE, FROM SOME CONTRACT
+    OF THE LINEARIZATION. ONLY A CONTRACT THAT IS NOT ABSTRACT MUST PROVIDE THEM ALL */
+    fn constructor_arguments(
+        &mut self,
+        contract: ContractId,
+    ) -> BTreeMap<ContractId, BaseArguments> {
+        let mut given: BTreeMap<ContractId, (BaseArguments, Span)> = BTreeMap::new();
+        for &owner in &self.bindings.linearization[&contract] {
+            for (base, arguments, span) in self.argument_sources(owner, owner == contract) {
+                if !has_constructor_parameters(self.graph, base) {
+                    continue;
+                }
+                if let Some((_, _first)) = given.get(&base) {
+                    /* A CONTRACT DERIVING FROM BOTH SEES THE SAME PAIR, IT IS REPORTED ONCE */
+                    if self.reported.insert((*_first, span)) {
+                        let mut diagnostic = semantic_error(format!(
+                            "arguments for the constructor of `{}` are given more than once",
+                            definition_name(self.graph, base)
+                        ))
+                        .with_label(span, "given again here")
+                        .with_secondary_label(*_first, "first given here");
+                        if owner != contract {
+                            diagnostic = diagnostic.with_secondary_label(
+                                definition(self.graph, contract).name.span,
+                                "inherits both",
+                            );
+                        }
+                        self.diagnostics.push(diagnostic);
+                    }
+                    continue;
+                }
+                given.insert(base, (arguments, span));
+            }
+        }
+
+        let definition = definition(self.graph, contract);
+        if definition.kind == ContractKind::Contract {
+            for &base in &self.bindings.linearization[&contract][1..] {
+                if has_constructor_parameters(self.graph, base) && !given.contains_key(&base) {
+                    let name = definition_name(self.graph, base);
+                    self.diagnostics.push(
+                        semantic_error(format!(
+                            "no arguments given for the constructor of `{name}`"
+                        ))
+                        .with_label(definition.name.span, format!("`{name}` is a base"))
+                        .with_help(format!(
+                            "pass them as `is {name}(..)` or `constructor() {name}(..)`, or declare `abstract contract {}`",
+                            definition.name.name
+                        )),
+                    );
+                }
+            }
+        }
+        given
+            .into_iter()
+            .map(|(base, (arguments, _))| (base, arguments))
+            .collect()
+    }
+
+    /* THE BASE CONSTRUCTOR ARGUMENTS WRITTEN IN `owner`. `report` CHECKS CONSTRUCTOR STYLE
+    ARGUMENTS, ONCE, FOR THE CONTRACT THAT WRITES THEM */
+    fn argument_sources(
+        &mut self,
+        owner: ContractId,
+        report: bool,
+    ) -> Vec<(ContractId, BaseArguments, Span)> {
+        let definition = definition(self.graph, owner);
+        let bases = self.bindings.bases.get(&owner).cloned().unwrap_or_default();
+        let mut sources = Vec::new();
+        for (index, specifier) in definition.bases.iter().enumerate() {
+            if let (Some(_), Some(_base)) = (&specifier.arguments, bases.get(index)) {
+                sources.push((
+                    *_base,
+                    BaseArguments::Specifier {
+                        contract: owner,
+                        index,
+                    },
+                    specifier.span,
+                ));
+            }
+        }
+        for (part, item) in definition.parts.iter().enumerate() {
+            let ContractPart::Function(function) = item else {
+                continue;
+            };
+            for (index, modifier) in function.modifiers.iter().enumerate() {
+                let Some(Declaration::Contract { file_id, contract }) = modifier
+                    .name
+                    .last()
+                    .and_then(|name| self.bindings.get(&name.span))
+                    .and_then(|declarations| declarations.first().copied())
+                else {
+                    continue;
+                };
+                let base = (file_id, contract);
+                if function.kind != FunctionKind::Constructor {
+                    if report {
+                        self.diagnostics.push(
+                            semantic_error(
+                                "base constructor arguments can only be given on a constructor"
+                                    .to_string(),
+                            )
+                            .with_label(modifier.span, "not a modifier"),
+                        );
+                    }
+                    continue;
+                }
+                if !bases.contains(&base) {
+                    if report {
+                        self.diagnostics.push(
+                            semantic_error(format!(
+                                "`{}` is not a direct base of `{}`",
+                                definition_name(self.graph, base),
+                                definition.name.name
+                            ))
+                            .with_label(modifier.span, "cannot pass constructor arguments to it"),
+                        );
+                    }
+                    continue;
+                }
+                sources.push((
+                    base,
+                    BaseArguments::Constructor {
+                        contract: owner,
+                        part,
+                        index,
+                    },
+                    modifier.span,
+                ));
+            }
+        }
+        sources
+    }
+
+    /* ------------------------------ HELPERS ------------------------------ */
+
+    fn type_name(&self, span: &Span) -> Type {
+        self.types
+            .type_names
+            .get(span)
+            .cloned()
+            .unwrap_or(Type::Unknown)
+    }
+
+    /* "`A`" OR "`A` AND `B`", WITH WHAT THEY ARE */
+    fn contract_list(&self, items: &[Overridable]) -> String {
+        let names: Vec<String> = items
+            .iter()
+            .map(|item| format!("`{}`", definition_name(self.graph, item.contract)))
+            .collect();
+        match names.split_last() {
+            Some((_last, rest)) if !rest.is_empty() => {
+                format!("{} and {_last}", rest.join(", "))
+            }
+            _ => names.concat(),
+        }
+    }
+
+    fn contract_names(&self, items: &[Overridable]) -> String {
+        let names: Vec<&str> = items
+            .iter()
+            .map(|item| definition_name(self.graph, item.contract))
+            .collect();
+        names.join(", ")
+    }
+}
+
+fn has_constructor_parameters(graph: &SourceGraph, contract: ContractId) -> bool {
+    definition(graph, contract).parts.iter().any(|part| {
+        matches!(part, ContractPart::Function(function)
+            if function.kind == FunctionKind::Constructor && !function.parameters.is_empty())
+    })
+}
+
+fn definition(graph: &SourceGraph, (file_id, contract): ContractId) -> &ContractDefinition {
+    &graph.units[file_id].ast.contracts[contract]
+}
+
+fn definition_name(graph: &SourceGraph, contract: ContractId) -> &str {
+    &definition(graph, contract).name.name
+}
+
+fn visibility_name(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "public",
+        Visibility::Private => "private",
+        Visibility::Internal => "internal",
+        Visibility::External => "external",
+    }
+}
+
+fn mutability_name(mutability: Option<StateMutability>) -> &'static str {
+    match mutability {
+        None => "non-payable",
+        Some(StateMutability::Pure) => "pure",
+        Some(StateMutability::View) => "view",
+        Some(StateMutability::Payable) => "payable",
+    }
+}
+
+fn semantic_error(message: String) -> Diagnostic {
+    CompilerError::SemanticError(SemanticError::InvalidInheritance(message)).diagnostic()
+}
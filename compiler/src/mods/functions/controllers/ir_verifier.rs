@@ -0,0 +1,620 @@
+use crate::mods::types::{
+    ir::{is_halt, Block, Function, Instruction, Link, Program, Terminator, Word},
+    opcodes::Opcode,
+};
+
+/* CHECKS WHAT THE BACKEND AND THE OPTIMIZER RELY ON: EVERY VARIABLE READ IS LIVE, EVERY
+BUILTIN HAS ITS NUMBER OF ARGUMENTS AND RESULTS, AND THE HEIGHT AT EVERY JUMP, CALL AND RETURN
+MATCHES WHERE IT GOES. A FAILURE IS A BUG IN WHAT PRODUCED THE PROGRAM */
+pub fn verify(program: &Program) -> Result<(), String> {
+    if program.functions.is_empty() {
+        return Err("the program has no entry function".to_string());
+    }
+    for (index, function) in program.functions.iter().enumerate() {
+        Verifier {
+            program,
+            function,
+            internal: index > 0,
+        }
+        .function()
+        .map_err(|message| format!("in @{index} {:?}: {message}", function.name))?;
+    }
+    Ok(())
+}
+
+struct Verifier<'p> {
+    program: &'p Program,
+    function: &'p Function,
+    internal: bool,
+}
+
+impl Verifier<'_> {
+    fn function(&self) -> Result<(), String> {
+        let function = self.function;
+        if !self.internal && (function.parameters > 0 || function.returns > 0) {
+            return Err("the entry function takes or returns values".to_string());
+        }
+        let Some(entry) = function.blocks.first() else {
+            return Err("the function has no blocks".to_string());
+        };
+        if entry.height != function.parameters {
+            return Err(format!(
+                "the entry block starts at height {}, not at the {} parameters",
+                entry.height, function.parameters
+            ));
+        }
+        for (index, block) in function.blocks.iter().enumerate() {
+            self.block(block)
+                .map_err(|message| format!("b{index}: {message}"))?;
+        }
+        Ok(())
+    }
+
+    /* THE LOWEST HEIGHT THE FUNCTION CAN DROP TO. AN INTERNAL FUNCTION KEEPS ITS PARAMETERS,
+    THE RETURN ADDRESS IS UNDER ITS LOCAL VARIABLES */
+    fn floor(&self) -> usize {
+        match self.internal {
+            true => self.function.parameters,
+            false => 0,
+        }
+    }
+
+    fn block(&self, block: &Block) -> Result<(), String> {
+        let mut height = block.height;
+        for instruction in &block.instructions {
+            match instruction {
+                Instruction::Let(word) => {
+                    self.word(word, height, 1)?;
+                    height += 1;
+                }
+                Instruction::Assign(position, word) => {
+                    self.slot(*position, height)?;
+                    self.word(word, height, 1)?;
+                }
+                Instruction::Exec(word) => {
+                    self.word(word, height, 0)?;
+                    if is_halt(word) {
+                        return Err(format!("`{word}` halts in the middle of a block"));
+                    }
+                }
+                Instruction::Keep { base, positions } => {
+                    if *base > height || *base < self.floor() {
+                        return Err(format!("`{instruction}` at height {height}"));
+                    }
+                    for position in positions {
+                        self.slot(*position, height)?;
+                    }
+                    height = base + positions.len();
+                }
+            }
+        }
+        match &block.terminator {
+            Terminator::Jump(target) => self.target(*target, height),
+            Terminator::Branch {
+                condition,
+                pops,
+                then,
+                otherwise,
+            } => {
+                self.word(condition, height, 1)?;
+                if height < self.floor() + pops {
+                    return Err(format!("a branch drops {pops} values at height {height}"));
+                }
+                self.target(*then, height - pops)?;
+                self.target(*otherwise, height - pops)
+            }
+            Terminator::Call { function, next } => {
+                let Some(callee) = self
+                    .program
+                    .functions
+                    .get(*function)
+                    .filter(|_| *function > 0)
+                else {
+                    return Err(format!("a call of @{function}, which is not a function"));
+                };
+                if height < self.floor() + callee.parameters {
+                    return Err(format!(
+                        "a call of @{function} with {} arguments at height {height}",
+                        callee.parameters
+                    ));
+                }
+                self.target(*next, height - callee.parameters + callee.returns)
+            }
+            Terminator::Leave(outputs) => {
+                if !self.internal {
+                    return Err("the entry function leaves".to_string());
+                }
+                if outputs.len() != self.function.returns {
+                    return Err(format!(
+                        "it leaves with {} values instead of {}",
+                        outputs.len(),
+                        self.function.returns
+                    ));
+                }
+                outputs
+                    .iter()
+                    .try_for_each(|position| self.slot(*position, height))
+            }
+            Terminator::Halt(word) => {
+                self.word(word, height, 0)?;
+                match is_halt(word) {
+                    true => Ok(()),
+                    false => Err(format!("`{word}` ends a block without halting")),
+                }
+            }
+            Terminator::Panic(_) => Ok(()),
+        }
+    }
+
+    fn target(&self, target: usize, height: usize) -> Result<(), String> {
+        match self.function.blocks.get(target) {
+            Some(_block) if _block.height == height => Ok(()),
+            Some(_block) => Err(format!(
+                "b{target} starts at height {} but is reached at height {height}",
+                _block.height
+            )),
+            None => Err(format!("a jump to b{target}, which does not exist")),
+        }
+    }
+
+    fn slot(&self, position: usize, height: usize) -> Result<(), String> {
+        match position >= 1 && position <= height {
+            true => Ok(()),
+            false => Err(format!("${position} is read at height {height}")),
+        }
+    }
+
+    /* A WORD THAT PUSHES `outputs` VALUES AT `height` */
+    fn word(&self, word: &Word, height: usize, outputs: usize) -> Result<(), String> {
+        let pushes = match word {
+            Word::Constant(_) => 1,
+            Word::Slot(position) => {
+                self.slot(*position, height)?;
+                1
+            }
+            Word::Link(Link::DataOffset(index) | Link::DataSize(index))
+                if *index >= self.program.data.len() =>
+            {
+                return Err(format!("`{word}` names a data section that does not exist"));
+            }
+            Word::Link(_) => 1,
+            Word::Checked(_, ty, arguments) => {
+                if arguments.len() != 2 || ty.bits == 0 || ty.bits > 256 || ty.bits % 8 != 0 {
+                    return Err(format!("`{word}` is not a checked operation"));
+                }
+                for argument in arguments {
+                    self.word(argument, height, 1)?;
+                }
+                1
+            }
+            Word::Apply(opcode, arguments) => {
+                if matches!(
+                    opcode,
+                    Opcode::Jump
+                        | Opcode::JumpI
+                        | Opcode::JumpDest
+                        | Opcode::Pc
+                        | Opcode::Pop
+                        | Opcode::Push(_)
+                        | Opcode::Dup(_)
+                        | Opcode::Swap(_)
+                ) {
+                    return Err(format!("`{opcode}` is not a builtin"));
+                }
+                let (pops, pushes) = opcode.stack_io();
+                if arguments.len() != pops {
+                    return Err(format!(
+                        "`{word}` has {} arguments instead of {pops}",
+                        arguments.len()
+                    ));
+                }
+                for argument in arguments {
+                    self.word(argument, height, 1)?;
+                }
+                pushes
+            }
+        };
+        match pushes == outputs {
+            true => Ok(()),
+            false => Err(format!(
+                "`{word}` pushes {pushes} values instead of {outputs}"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::verify;
+    use crate::mods::types::{
+        ir::{Block, Checked, Function, Instruction, Integer, Link, Program, Terminator, Word},
+        line_descriptors::Span,
+        opcodes::Opcode,
+    };
+
+    fn constant(value: u32) -> Word {
+        Word::Constant(BigUint::from(value))
+    }
+
+    fn apply(opcode: Opcode, arguments: impl Into<Vec<Word>>) -> Word {
+        Word::Apply(opcode, arguments.into())
+    }
+
+    fn stop() -> Terminator {
+        Terminator::Halt(apply(Opcode::Stop, []))
+    }
+
+    fn block(height: usize, instructions: Vec<Instruction>, terminator: Terminator) -> Block {
+        Block {
+            height,
+            span: Span::default(),
+            instructions,
+            terminator,
+        }
+    }
+
+    fn function(parameters: usize, returns: usize, blocks: Vec<Block>) -> Function {
+        Function {
+            name: "f".to_string(),
+            span: Span::default(),
+            parameters,
+            returns,
+            blocks,
+        }
+    }
+
+    fn program(functions: Vec<Function>) -> Program {
+        let mut program = Program::default();
+        program.functions = functions;
+        program
+    }
+
+    /* AN ENTRY FUNCTION MADE OF `blocks` */
+    fn entry(blocks: Vec<Block>) -> Program {
+        program(vec![function(0, 0, blocks)])
+    }
+
+    fn error(program: &Program) -> String {
+        verify(program).expect_err("the program should not verify")
+    }
+
+    #[test]
+    fn accepts_a_valid_program() {
+        let caller = function(
+            0,
+            0,
+            vec![
+                block(
+                    0,
+                    vec![
+                        Instruction::Let(constant(1)),
+                        Instruction::Let(apply(Opcode::CallValue, [])),
+                    ],
+                    Terminator::Branch {
+                        condition: Word::Slot(2),
+                        pops: 1,
+                        then: 1,
+                        otherwise: 2,
+                    },
+                ),
+                block(
+                    1,
+                    vec![],
+                    Terminator::Call {
+                        function: 1,
+                        next: 2,
+                    },
+                ),
+                block(
+                    1,
+                    vec![
+                        Instruction::Exec(apply(Opcode::MStore, [constant(0), Word::Slot(1)])),
+                        Instruction::Keep {
+                            base: 0,
+                            positions: vec![],
+                        },
+                    ],
+                    Terminator::Halt(apply(Opcode::Return, [constant(0), constant(32)])),
+                ),
+            ],
+        );
+        let callee = function(
+            1,
+            1,
+            vec![block(
+                1,
+                vec![Instruction::Let(Word::Checked(
+                    Checked::Add,
+                    Integer {
+                        bits: 256,
+                        signed: false,
+                    },
+                    vec![Word::Slot(1), constant(1)],
+                ))],
+                Terminator::Leave(vec![2]),
+            )],
+        );
+        assert_eq!(verify(&program(vec![caller, callee])), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_program_without_functions() {
+        assert_eq!(error(&program(vec![])), "the program has no entry function");
+    }
+
+    #[test]
+    fn rejects_an_entry_function_with_parameters() {
+        let program = program(vec![function(1, 0, vec![block(1, vec![], stop())])]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": the entry function takes or returns values"
+        );
+    }
+
+    #[test]
+    fn rejects_a_function_without_blocks() {
+        assert_eq!(
+            error(&entry(vec![])),
+            "in @0 \"f\": the function has no blocks"
+        );
+    }
+
+    #[test]
+    fn rejects_an_entry_block_above_the_parameters() {
+        assert_eq!(
+            error(&entry(vec![block(1, vec![], stop())])),
+            "in @0 \"f\": the entry block starts at height 1, not at the 0 parameters"
+        );
+    }
+
+    #[test]
+    fn rejects_reading_a_dead_variable() {
+        let program = entry(vec![block(
+            0,
+            vec![
+                Instruction::Let(constant(1)),
+                Instruction::Exec(apply(Opcode::MStore, [constant(0), Word::Slot(2)])),
+            ],
+            stop(),
+        )]);
+        assert_eq!(error(&program), "in @0 \"f\": b0: $2 is read at height 1");
+    }
+
+    #[test]
+    fn rejects_assigning_a_dead_variable() {
+        let program = entry(vec![block(
+            0,
+            vec![Instruction::Assign(1, constant(1))],
+            stop(),
+        )]);
+        assert_eq!(error(&program), "in @0 \"f\": b0: $1 is read at height 0");
+    }
+
+    #[test]
+    fn rejects_builtins_with_the_wrong_number_of_arguments() {
+        let program = entry(vec![block(
+            0,
+            vec![Instruction::Let(apply(Opcode::Add, [constant(1)]))],
+            stop(),
+        )]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: `add(1)` has 1 arguments instead of 2"
+        );
+    }
+
+    #[test]
+    fn rejects_builtins_with_the_wrong_number_of_results() {
+        let program = entry(vec![block(
+            0,
+            vec![Instruction::Let(apply(
+                Opcode::MStore,
+                [constant(0), constant(0)],
+            ))],
+            stop(),
+        )]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: `mstore(0, 0)` pushes 0 values instead of 1"
+        );
+        let program = entry(vec![block(
+            0,
+            vec![Instruction::Exec(apply(Opcode::CallValue, []))],
+            stop(),
+        )]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: `callvalue()` pushes 1 values instead of 0"
+        );
+    }
+
+    #[test]
+    fn rejects_stack_opcodes_as_builtins() {
+        let program = entry(vec![block(
+            0,
+            vec![Instruction::Let(apply(Opcode::Pc, []))],
+            stop(),
+        )]);
+        assert_eq!(error(&program), "in @0 \"f\": b0: `PC` is not a builtin");
+    }
+
+    #[test]
+    fn rejects_malformed_checked_operations() {
+        let program = entry(vec![block(
+            0,
+            vec![Instruction::Let(Word::Checked(
+                Checked::Mul,
+                Integer {
+                    bits: 12,
+                    signed: true,
+                },
+                vec![constant(1), constant(2)],
+            ))],
+            stop(),
+        )]);
+        assert!(error(&program).ends_with("is not a checked operation"));
+    }
+
+    #[test]
+    fn rejects_links_to_missing_data() {
+        let program = entry(vec![block(
+            0,
+            vec![Instruction::Let(Word::Link(Link::DataSize(0)))],
+            stop(),
+        )]);
+        assert!(error(&program).ends_with("names a data section that does not exist"));
+    }
+
+    #[test]
+    fn rejects_a_halt_in_the_middle_of_a_block() {
+        let program = entry(vec![block(
+            0,
+            vec![Instruction::Exec(apply(Opcode::Stop, []))],
+            stop(),
+        )]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: `stop()` halts in the middle of a block"
+        );
+    }
+
+    #[test]
+    fn rejects_a_block_that_does_not_halt() {
+        let program = entry(vec![block(
+            0,
+            vec![],
+            Terminator::Halt(apply(Opcode::MStore, [constant(0), constant(0)])),
+        )]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: `mstore(0, 0)` ends a block without halting"
+        );
+    }
+
+    #[test]
+    fn rejects_keeping_below_the_floor() {
+        let internal = function(
+            1,
+            0,
+            vec![block(
+                1,
+                vec![Instruction::Keep {
+                    base: 0,
+                    positions: vec![],
+                }],
+                Terminator::Leave(vec![]),
+            )],
+        );
+        let program = program(vec![
+            function(0, 0, vec![block(0, vec![], stop())]),
+            internal,
+        ]);
+        assert!(error(&program).starts_with("in @1 \"f\": b0: "));
+    }
+
+    #[test]
+    fn rejects_jumps_that_change_the_height() {
+        let program = entry(vec![
+            block(0, vec![Instruction::Let(constant(1))], Terminator::Jump(1)),
+            block(0, vec![], stop()),
+        ]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: b1 starts at height 0 but is reached at height 1"
+        );
+    }
+
+    #[test]
+    fn rejects_jumps_to_missing_blocks() {
+        let program = entry(vec![block(0, vec![], Terminator::Jump(3))]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: a jump to b3, which does not exist"
+        );
+    }
+
+    #[test]
+    fn rejects_branches_that_drop_too_much() {
+        let program = entry(vec![
+            block(
+                0,
+                vec![],
+                Terminator::Branch {
+                    condition: constant(1),
+                    pops: 1,
+                    then: 1,
+                    otherwise: 1,
+                },
+            ),
+            block(0, vec![], stop()),
+        ]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: a branch drops 1 values at height 0"
+        );
+    }
+
+    #[test]
+    fn rejects_calls_of_the_entry_function() {
+        let program = entry(vec![
+            block(
+                0,
+                vec![],
+                Terminator::Call {
+                    function: 0,
+                    next: 1,
+                },
+            ),
+            block(0, vec![], stop()),
+        ]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: a call of @0, which is not a function"
+        );
+    }
+
+    #[test]
+    fn rejects_calls_without_enough_arguments() {
+        let program = program(vec![
+            function(
+                0,
+                0,
+                vec![
+                    block(
+                        0,
+                        vec![],
+                        Terminator::Call {
+                            function: 1,
+                            next: 1,
+                        },
+                    ),
+                    block(0, vec![], stop()),
+                ],
+            ),
+            function(2, 0, vec![block(2, vec![], Terminator::Leave(vec![]))]),
+        ]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: a call of @1 with 2 arguments at height 0"
+        );
+    }
+
+    #[test]
+    fn rejects_leaving_with_the_wrong_number_of_values() {
+        let program = program(vec![
+            function(0, 0, vec![block(0, vec![], stop())]),
+            function(1, 2, vec![block(1, vec![], Terminator::Leave(vec![1]))]),
+        ]);
+        assert_eq!(
+            error(&program),
+            "in @1 \"f\": b0: it leaves with 1 values instead of 2"
+        );
+        let program = entry(vec![block(0, vec![], Terminator::Leave(vec![]))]);
+        assert_eq!(
+            error(&program),
+            "in @0 \"f\": b0: the entry function leaves"
+        );
+    }
+}
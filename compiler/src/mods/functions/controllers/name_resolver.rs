@@ -0,0 +1,980 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::mods::types::{
+    ast::{
+        Block, CallArguments, ContractDefinition, ContractKind, ContractPart, Expression,
+        ExpressionKind, FunctionKind, Identifier, Parameter, Statement, StatementKind, TypeName,
+        TypeNameKind, YulBlock, YulExpression, YulExpressionKind, YulStatement, YulStatementKind,
+    },
+    bindings::{Bindings, ContractId, Declaration},
+    compiler_errors::{CompilerError, SemanticError},
+    diagnostics::{Diagnostic, Diagnostics},
+    line_descriptors::Span,
+    opcodes::Opcode,
+    source_graph::{SourceGraph, Symbol},
+};
+
+/* NAMES THAT ARE ALWAYS IN SCOPE */
+const BUILTINS: [&str; 20] = [
+    "abi",
+    "addmod",
+    "assert",
+    "blobhash",
+    "block",
+    "blockhash",
+    "ecrecover",
+    "gasleft",
+    "keccak256",
+    "msg",
+    "mulmod",
+    "require",
+    "revert",
+    "ripemd160",
+    "selfdestruct",
+    "sha256",
+    "super",
+    "this",
+    "tx",
+    "type",
+];
+
+/* NAMES DECLARED IN A CONTRACT OR INHERITED BY IT */
+type Members = BTreeMap<String, Vec<Declaration>>;
+
+/* RESULT OF LOOKING A MEMBER UP. `Unchecked` WHEN THE MEMBER DEPENDS ON A TYPE, E.G `x.length`,
+WHICH IS LEFT TO THE TYPE CHECKER */
+enum Lookup {
+    Found(Vec<Declaration>),
+    NotFound,
+    Unchecked,
+}
+
+/* RESOLVES EVERY NAME IN `graph` TO ITS DECLARATION, REPORTING UNDEFINED AND DUPLICATE NAMES.
+SCOPES NEST FROM THE SOURCE UNIT, THROUGH THE CONTRACT AND ITS BASES, DOWN TO FUNCTIONS AND
+BLOCKS. A LOCAL VARIABLE IS VISIBLE FROM ITS DECLARATION TO THE END OF ITS BLOCK */
+pub fn resolve_names(graph: &SourceGraph, diagnostics: &mut Diagnostics) -> Bindings {
+    let mut resolver = NameResolver {
+        graph,
+        diagnostics,
+        bindings: Bindings::default(),
+        members: HashMap::new(),
+        file_id: 0,
+        contract: None,
+        scopes: Vec::new(),
+        assembly_functions: HashSet::new(),
+        undefined: HashSet::new(),
+    };
+    for unit in &graph.units {
+        for index in 0..unit.ast.contracts.len() {
+            resolver.contract_members(unit.file_id, index);
+        }
+    }
+    for unit in &graph.units {
+        resolver.file_id = unit.file_id;
+        for error in &unit.ast.custom_errors {
+            resolver.resolve_parameters(&error.parameters, false);
+        }
+        for (index, contract) in unit.ast.contracts.iter().enumerate() {
+            resolver.resolve_contract(index, contract);
+        }
+    }
+    let mut bindings = resolver.bindings;
+    bindings.members = resolver
+        .members
+        .into_iter()
+        .filter_map(|(contract, members)| Some((contract, members?)))
+        .collect();
+    bindings
+}
+
+struct NameResolver<'a> {
+    graph: &'a SourceGraph,
+    diagnostics: &'a mut Diagnostics,
+    bindings: Bindings,
+    /* None WHILE A CONTRACT'S MEMBERS ARE BEING COLLECTED, TO STOP ON CYCLIC INHERITANCE */
+    members: HashMap<ContractId, Option<Members>>,
+    file_id: usize,
+    contract: Option<usize>,
+    /* LOCAL SCOPES, INNERMOST LAST. EACH NAME MAPS TO THE SPAN IT IS DECLARED AT */
+    scopes: Vec<HashMap<String, Span>>,
+    /* FUNCTIONS DEFINED IN INLINE ASSEMBLY, BY THE SPAN OF THEIR NAME */
+    assembly_functions: HashSet<Span>,
+    /* OCCURRENCES ALREADY REPORTED AS UNDEFINED, SO A NAME RESOLVED TWICE IS REPORTED ONCE */
+    undefined: HashSet<(usize, Span)>,
+}
+
+impl<'a> NameResolver<'a> {
+    /* ------------------------------ MEMBERS ------------------------------ */
+
+    fn contract_members(&mut self, file_id: usize, contract: usize) -> Members {
+        match self.members.get(&(file_id, contract)) {
+            Some(Some(_members)) => return _members.clone(),
+            Some(None) => return Members::new(),
+            None => {}
+        }
+        self.members.insert((file_id, contract), None);
+
+        let graph = self.graph;
+        let definition = &graph.units[file_id].ast.contracts[contract];
+        let mut members = Members::new();
+        for (index, part) in definition.parts.iter().enumerate() {
+            let Some(name) = part_name(part) else {
+                continue;
+            };
+            let declaration = Declaration::Member {
+                file_id,
+                contract,
+                part: index,
+            };
+            match members.get_mut(&name.name) {
+                Some(existing) if self.overloads(existing, declaration) => {
+                    existing.push(declaration)
+                }
+                Some(existing) => {
+                    let previous = self.declaration_span(existing[0]);
+                    self.redeclared(name, previous);
+                }
+                None => {
+                    members.insert(name.name.clone(), vec![declaration]);
+                }
+            }
+        }
+
+        /* BASES ARE LOOKED UP IN THE FILE THE CONTRACT IS DECLARED IN. OWN MEMBERS HIDE
+        INHERITED ONES, EXCEPT THAT FUNCTIONS AND EVENTS ADD OVERLOADS */
+        let (file, scope_contract) = (self.file_id, self.contract);
+        self.file_id = file_id;
+        self.contract = None;
+        for base in &definition.bases {
+            let Some(declarations) = self.resolve_path(&base.name, SemanticError::UndefinedType)
+            else {
+                continue;
+            };
+            let Some(Declaration::Contract {
+                file_id: base_file,
+                contract: base_contract,
+            }) = self.expect_contract(&base.name, &declarations)
+            else {
+                continue;
+            };
+            self.bindings
+                .bases
+                .entry((file_id, contract))
+                .or_default()
+                .push((base_file, base_contract));
+            for (name, inherited) in self.contract_members(base_file, base_contract) {
+                match members.get_mut(&name) {
+                    Some(existing) => {
+                        for declaration in inherited {
+                            if !existing.contains(&declaration)
+                                && self.overloads(existing, declaration)
+                            {
+                                existing.push(declaration);
+                            }
+                        }
+                    }
+                    None => {
+                        members.insert(name, inherited);
+                    }
+                }
+            }
+        }
+        self.file_id = file;
+        self.contract = scope_contract;
+
+        self.members
+            .insert((file_id, contract), Some(members.clone()));
+        members
+    }
+
+    /* FUNCTIONS MAY SHARE A NAME WITH OTHER FUNCTIONS, EVENTS WITH OTHER EVENTS */
+    fn overloads(&self, existing: &[Declaration], declaration: Declaration) -> bool {
+        let kind = |declaration: Declaration| match self.part(declaration) {
+            Some(ContractPart::Function(_)) => Some(0),
+            Some(ContractPart::Event(_)) => Some(1),
+            _ => None,
+        };
+        kind(declaration).is_some()
+            && existing
+                .iter()
+                .all(|_declaration| kind(*_declaration) == kind(declaration))
+    }
+
+    fn part(&self, declaration: Declaration) -> Option<&'a ContractPart> {
+        match declaration {
+            Declaration::Member {
+                file_id,
+                contract,
+                part,
+            } => Some(&self.graph.units[file_id].ast.contracts[contract].parts[part]),
+            _ => None,
+        }
+    }
+
+    /* SPAN OF THE NAME OF A DECLARATION, FOR "PREVIOUS DECLARATION" LABELS */
+    fn declaration_span(&self, declaration: Declaration) -> Option<Span> {
+        let ast = |file_id: usize| &self.graph.units[file_id].ast;
+        match declaration {
+            Declaration::Contract { file_id, contract } => {
+                Some(ast(file_id).contracts[contract].name.span)
+            }
+            Declaration::Error { file_id, index } => {
+                Some(ast(file_id).custom_errors[index].name.span)
+            }
+            Declaration::Member { .. } => self
+                .part(declaration)
+                .and_then(part_name)
+                .map(|name| name.span),
+            Declaration::Local(span) => Some(span),
+            _ => None,
+        }
+    }
+
+    /* ------------------------------ CONTRACTS ------------------------------ */
+
+    fn resolve_contract(&mut self, index: usize, contract: &ContractDefinition) {
+        self.contract = Some(index);
+        for base in &contract.bases {
+            if let Some(arguments) = &base.arguments {
+                for argument in arguments {
+                    self.resolve_expression(argument);
+                }
+            }
+        }
+
+        for part in &contract.parts {
+            match part {
+                ContractPart::StateVariable(variable) => {
+                    self.resolve_type(&variable.ty);
+                    self.resolve_overrides(&variable.overrides);
+                    if let Some(_initializer) = &variable.initializer {
+                        self.resolve_expression(_initializer);
+                    }
+                }
+                ContractPart::Function(function) => {
+                    self.scopes.push(HashMap::new());
+                    self.resolve_parameters(&function.parameters, true);
+                    self.resolve_parameters(&function.returns, true);
+                    self.resolve_overrides(&function.overrides);
+                    for modifier in &function.modifiers {
+                        self.resolve_path(&modifier.name, SemanticError::UndefinedVariable);
+                        if let Some(arguments) = &modifier.arguments {
+                            for argument in arguments {
+                                self.resolve_expression(argument);
+                            }
+                        }
+                    }
+                    if let Some(body) = &function.body {
+                        self.resolve_block(body);
+                    }
+                    self.scopes.pop();
+                }
+                ContractPart::Modifier(modifier) => {
+                    self.scopes.push(HashMap::new());
+                    self.resolve_parameters(&modifier.parameters, true);
+                    self.resolve_overrides(&modifier.overrides);
+                    if let Some(body) = &modifier.body {
+                        self.resolve_block(body);
+                    }
+                    self.scopes.pop();
+                }
+                ContractPart::Event(event) => self.resolve_parameters(&event.parameters, false),
+                ContractPart::Error(error) => self.resolve_parameters(&error.parameters, false),
+                ContractPart::Struct(definition) => {
+                    let mut fields: HashMap<&str, Span> = HashMap::new();
+                    for field in &definition.fields {
+                        self.resolve_type(&field.ty);
+                        if let Some(previous) = fields.insert(&field.name.name, field.name.span) {
+                            self.redeclared(&field.name, Some(previous));
+                        }
+                    }
+                }
+                ContractPart::Enum(definition) => {
+                    let mut values: HashMap<&str, Span> = HashMap::new();
+                    for value in &definition.values {
+                        if let Some(previous) = values.insert(&value.name, value.span) {
+                            self.redeclared(value, Some(previous));
+                        }
+                    }
+                }
+                ContractPart::Using(using) => {
+                    if let Some(declarations) =
+                        self.resolve_path(&using.library, SemanticError::UndefinedType)
+                    {
+                        if let Some(Declaration::Contract { file_id, contract }) =
+                            self.expect_contract(&using.library, &declarations)
+                        {
+                            let library = &self.graph.units[file_id].ast.contracts[contract];
+                            if library.kind != ContractKind::Library {
+                                self.invalid(
+                                    &using.library,
+                                    format!("`{}` is not a library", library.name.name),
+                                );
+                            }
+                        }
+                    }
+                    if let Some(_target) = &using.target {
+                        self.resolve_type(_target);
+                    }
+                }
+                ContractPart::Cron(cron) => {
+                    self.scopes.push(HashMap::new());
+                    self.resolve_expression(&cron.schedule);
+                    if let Some(_max_runs) = &cron.max_runs {
+                        self.resolve_expression(_max_runs);
+                    }
+                    self.resolve_block(&cron.body);
+                    self.scopes.pop();
+                }
+            }
+        }
+        self.contract = None;
+    }
+
+    /* PARAMETERS ARE ONLY DECLARED AS LOCALS WHERE THEY HAVE A BODY TO BE USED IN */
+    fn resolve_parameters(&mut self, parameters: &[Parameter], declare: bool) {
+        for parameter in parameters {
+            self.resolve_type(&parameter.ty);
+            if let (true, Some(name)) = (declare, &parameter.name) {
+                self.declare_local(name);
+            }
+        }
+    }
+
+    fn resolve_overrides(&mut self, overrides: &Option<Vec<Vec<Identifier>>>) {
+        for path in overrides.iter().flatten() {
+            if let Some(declarations) = self.resolve_path(path, SemanticError::UndefinedType) {
+                self.expect_contract(path, &declarations);
+            }
+        }
+    }
+
+    fn resolve_type(&mut self, ty: &TypeName) {
+        match &ty.kind {
+            TypeNameKind::Elementary(_) => {}
+            TypeNameKind::UserDefined(path) => {
+                let Some(declarations) = self.resolve_path(path, SemanticError::UndefinedType)
+                else {
+                    return;
+                };
+                let is_type = declarations.iter().all(|declaration| match declaration {
+                    Declaration::Contract { .. } => true,
+                    Declaration::Member { .. } => matches!(
+                        self.part(*declaration),
+                        Some(ContractPart::Struct(_) | ContractPart::Enum(_))
+                    ),
+                    _ => false,
+                });
+                if !is_type {
+                    self.invalid(path, format!("`{}` is not a type", path_name(path)));
+                }
+            }
+            TypeNameKind::Array { base, length } => {
+                self.resolve_type(base);
+                if let Some(_length) = length {
+                    self.resolve_expression(_length);
+                }
+            }
+            TypeNameKind::Mapping { key, value, .. } => {
+                self.resolve_type(key);
+                self.resolve_type(value);
+            }
+            TypeNameKind::Function {
+                parameters,
+                returns,
+                ..
+            } => {
+                self.resolve_parameters(parameters, false);
+                self.resolve_parameters(returns, false);
+            }
+        }
+    }
+
+    /* ------------------------------ STATEMENTS ------------------------------ */
+
+    fn resolve_block(&mut self, block: &Block) {
+        self.scopes.push(HashMap::new());
+        for statement in &block.statements {
+            self.resolve_statement(statement);
+        }
+        self.scopes.pop();
+    }
+
+    /* A STATEMENT IN THE BRANCH OF AN `if` OR THE BODY OF A LOOP GETS A SCOPE OF ITS OWN */
+    fn resolve_scoped(&mut self, statement: &Statement) {
+        self.scopes.push(HashMap::new());
+        self.resolve_statement(statement);
+        self.scopes.pop();
+    }
+
+    fn resolve_statement(&mut self, statement: &Statement) {
+        match &statement.kind {
+            StatementKind::Block(block) => self.resolve_block(block),
+            StatementKind::VariableDeclaration {
+                declarations,
+                initializer,
+            } => {
+                /* THE NEW NAMES ARE NOT VISIBLE IN THEIR OWN INITIALIZER */
+                if let Some(_initializer) = initializer {
+                    self.resolve_expression(_initializer);
+                }
+                for declaration in declarations.iter().flatten() {
+                    self.resolve_type(&declaration.ty);
+                    self.declare_local(&declaration.name);
+                }
+            }
+            StatementKind::Expression(expression) => {
+                self.resolve_expression(expression);
+            }
+            StatementKind::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                self.resolve_expression(condition);
+                self.resolve_scoped(then);
+                if let Some(_otherwise) = otherwise {
+                    self.resolve_scoped(_otherwise);
+                }
+            }
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.scopes.push(HashMap::new());
+                if let Some(_init) = init {
+                    self.resolve_statement(_init);
+                }
+                if let Some(_condition) = condition {
+                    self.resolve_expression(_condition);
+                }
+                if let Some(_update) = update {
+                    self.resolve_expression(_update);
+                }
+                self.resolve_scoped(body);
+                self.scopes.pop();
+            }
+            StatementKind::While { condition, body }
+            | StatementKind::DoWhile { body, condition } => {
+                self.resolve_expression(condition);
+                self.resolve_scoped(body);
+            }
+            StatementKind::Return(expression) => {
+                if let Some(_expression) = expression {
+                    self.resolve_expression(_expression);
+                }
+            }
+            StatementKind::Emit(expression) => match &expression.kind {
+                ExpressionKind::FunctionCall { callee, arguments } => {
+                    self.resolve_callee(callee, SemanticError::UndefinedVariable);
+                    self.resolve_arguments(arguments);
+                }
+                _ => {
+                    self.resolve_expression(expression);
+                }
+            },
+            StatementKind::Revert { error, arguments } => {
+                if let Some(_error) = error {
+                    self.resolve_path(_error, SemanticError::UndefinedVariable);
+                }
+                self.resolve_arguments(arguments);
+            }
+            StatementKind::Assembly { body, .. } => self.resolve_yul_block(body),
+            StatementKind::Break | StatementKind::Continue | StatementKind::Placeholder => {}
+        }
+    }
+
+    /* ------------------------------ INLINE ASSEMBLY ------------------------------ */
+
+    fn resolve_yul_block(&mut self, block: &YulBlock) {
+        self.scopes.push(HashMap::new());
+        self.resolve_yul_statements(&block.statements);
+        self.scopes.pop();
+    }
+
+    /* A FUNCTION IS VISIBLE IN THE WHOLE BLOCK IT IS DEFINED IN, ALSO BEFORE ITS DEFINITION */
+    fn resolve_yul_statements(&mut self, statements: &[YulStatement]) {
+        for statement in statements {
+            if let YulStatementKind::Function(function) = &statement.kind {
+                self.declare_yul(&function.name);
+                self.assembly_functions.insert(function.name.span);
+            }
+        }
+        for statement in statements {
+            self.resolve_yul_statement(statement);
+        }
+    }
+
+    fn resolve_yul_statement(&mut self, statement: &YulStatement) {
+        match &statement.kind {
+            YulStatementKind::Block(block) => self.resolve_yul_block(block),
+            YulStatementKind::VariableDeclaration { names, value } => {
+                if let Some(_value) = value {
+                    self.resolve_yul_expression(_value);
+                }
+                for name in names {
+                    self.declare_yul(name);
+                }
+            }
+            YulStatementKind::Assignment { targets, value } => {
+                self.resolve_yul_expression(value);
+                for target in targets {
+                    self.resolve_yul_name(&target[0], SemanticError::UndefinedVariable);
+                }
+            }
+            YulStatementKind::Expression(expression) => self.resolve_yul_expression(expression),
+            YulStatementKind::If { condition, body } => {
+                self.resolve_yul_expression(condition);
+                self.resolve_yul_block(body);
+            }
+            YulStatementKind::Switch {
+                expression,
+                cases,
+                default,
+            } => {
+                self.resolve_yul_expression(expression);
+                for (_, body) in cases {
+                    self.resolve_yul_block(body);
+                }
+                if let Some(_default) = default {
+                    self.resolve_yul_block(_default);
+                }
+            }
+            /* WHAT `init` DECLARES IS VISIBLE IN THE REST OF THE LOOP */
+            YulStatementKind::For {
+                init,
+                condition,
+                post,
+                body,
+            } => {
+                self.scopes.push(HashMap::new());
+                self.resolve_yul_statements(&init.statements);
+                self.resolve_yul_expression(condition);
+                self.resolve_yul_block(post);
+                self.resolve_yul_block(body);
+                self.scopes.pop();
+            }
+            /* THE BODY OF A FUNCTION ONLY SEES ITS PARAMETERS, ITS RETURN VARIABLES, THE OTHER
+            FUNCTIONS AND THE CONTRACT */
+            YulStatementKind::Function(function) => {
+                let visible: HashMap<String, Span> = self
+                    .scopes
+                    .iter()
+                    .flatten()
+                    .filter(|(_, span)| self.assembly_functions.contains(span))
+                    .map(|(name, span)| (name.clone(), *span))
+                    .collect();
+                let scopes = std::mem::replace(&mut self.scopes, vec![visible, HashMap::new()]);
+                for name in function.parameters.iter().chain(&function.returns) {
+                    self.declare_yul(name);
+                }
+                self.resolve_yul_block(&function.body);
+                self.scopes = scopes;
+            }
+            YulStatementKind::Break | YulStatementKind::Continue | YulStatementKind::Leave => {}
+        }
+    }
+
+    fn resolve_yul_expression(&mut self, expression: &YulExpression) {
+        match &expression.kind {
+            YulExpressionKind::Literal(_) => {}
+            /* `.slot` AND `.offset` ARE LEFT TO THE TYPE CHECKER */
+            YulExpressionKind::Path(path) => {
+                self.resolve_yul_name(&path[0], SemanticError::UndefinedVariable);
+            }
+            YulExpressionKind::Call {
+                function,
+                arguments,
+            } => {
+                for argument in arguments {
+                    self.resolve_yul_expression(argument);
+                }
+                self.resolve_yul_name(function, SemanticError::UndefinedFunction);
+            }
+        }
+    }
+
+    /* BUILTINS SUCH AS `add` COME FIRST, NOTHING CAN SHADOW THEM */
+    fn resolve_yul_name(&mut self, identifier: &Identifier, missing: fn(String) -> SemanticError) {
+        match Opcode::builtin(&identifier.name) {
+            Some((_name, _)) => {
+                self.bindings
+                    .references
+                    .insert(identifier.span, vec![Declaration::Builtin(_name)]);
+            }
+            None => {
+                self.resolve_identifier(identifier, missing);
+            }
+        }
+    }
+
+    /* UNLIKE SOLIDITY, ASSEMBLY CANNOT SHADOW ANYTHING IT CAN SEE */
+    fn declare_yul(&mut self, name: &Identifier) {
+        let previous = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.name).copied());
+        match (Opcode::builtin(&name.name), previous) {
+            (Some(_), _) => self.redeclared(name, None),
+            (None, Some(_previous)) => self.redeclared(name, Some(_previous)),
+            (None, None) => self.declare_local(name),
+        }
+    }
+
+    /* ------------------------------ EXPRESSIONS ------------------------------ */
+
+    /* RETURNS WHAT THE EXPRESSION NAMES WHEN IT IS A NAME OR A PATH OF NAMES, E.G `Lib.f` */
+    fn resolve_expression(&mut self, expression: &Expression) -> Option<Vec<Declaration>> {
+        match &expression.kind {
+            ExpressionKind::Literal(_) | ExpressionKind::ElementaryType(_) => None,
+            ExpressionKind::Variable(identifier) => {
+                self.resolve_identifier(identifier, SemanticError::UndefinedVariable)
+            }
+            ExpressionKind::MemberAccess { expression, member } => {
+                let declarations = self.resolve_expression(expression)?;
+                self.resolve_member(&declarations, member)
+            }
+            ExpressionKind::Index { base, index } => {
+                self.resolve_expression(base);
+                if let Some(_index) = index {
+                    self.resolve_expression(_index);
+                }
+                None
+            }
+            ExpressionKind::FunctionCall { callee, arguments } => {
+                self.resolve_callee(callee, SemanticError::UndefinedFunction);
+                self.resolve_arguments(arguments);
+                None
+            }
+            ExpressionKind::CallOptions { callee, options } => {
+                self.resolve_expression(callee);
+                for option in options {
+                    self.resolve_expression(&option.value);
+                }
+                None
+            }
+            ExpressionKind::Unary { operand, .. } => {
+                self.resolve_expression(operand);
+                None
+            }
+            ExpressionKind::Binary { left, right, .. }
+            | ExpressionKind::Assignment { left, right, .. } => {
+                self.resolve_expression(left);
+                self.resolve_expression(right);
+                None
+            }
+            ExpressionKind::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => {
+                self.resolve_expression(condition);
+                self.resolve_expression(then);
+                self.resolve_expression(otherwise);
+                None
+            }
+            ExpressionKind::Tuple(components) => {
+                for component in components.iter().flatten() {
+                    self.resolve_expression(component);
+                }
+                None
+            }
+            ExpressionKind::ArrayLiteral(elements) => {
+                for element in elements {
+                    self.resolve_expression(element);
+                }
+                None
+            }
+            ExpressionKind::New(ty) => {
+                self.resolve_type(ty);
+                None
+            }
+        }
+    }
+
+    /* A CALLEE THAT IS A PLAIN NAME IS REPORTED WITH `missing` INSTEAD OF AS A VARIABLE */
+    fn resolve_callee(&mut self, callee: &Expression, missing: fn(String) -> SemanticError) {
+        match &callee.kind {
+            ExpressionKind::Variable(identifier) => {
+                self.resolve_identifier(identifier, missing);
+            }
+            _ => {
+                self.resolve_expression(callee);
+            }
+        }
+    }
+
+    fn resolve_arguments(&mut self, arguments: &CallArguments) {
+        match arguments {
+            CallArguments::Positional(expressions) => {
+                for expression in expressions {
+                    self.resolve_expression(expression);
+                }
+            }
+            CallArguments::Named(named) => {
+                for argument in named {
+                    self.resolve_expression(&argument.value);
+                }
+            }
+        }
+    }
+
+    /* ------------------------------ LOOKUP ------------------------------ */
+
+    fn resolve_identifier(
+        &mut self,
+        identifier: &Identifier,
+        missing: fn(String) -> SemanticError,
+    ) -> Option<Vec<Declaration>> {
+        match self.lookup(&identifier.name) {
+            Some(declarations) => {
+                self.bindings
+                    .references
+                    .insert(identifier.span, declarations.clone());
+                Some(declarations)
+            }
+            None => {
+                let key = (self.file_id, identifier.span);
+                if self.undefined.insert(key) {
+                    self.diagnostics.push(
+                        CompilerError::SemanticError(missing(identifier.name.clone()))
+                            .at(identifier.span),
+                    );
+                }
+                None
+            }
+        }
+    }
+
+    /* RESOLVES `A.B.C` SEGMENT BY SEGMENT, STOPPING QUIETLY AT A MEMBER THAT NEEDS TYPES */
+    fn resolve_path(
+        &mut self,
+        path: &[Identifier],
+        missing: fn(String) -> SemanticError,
+    ) -> Option<Vec<Declaration>> {
+        let (first, rest) = path.split_first()?;
+        let mut declarations = self.resolve_identifier(first, missing)?;
+        for member in rest {
+            declarations = self.resolve_member(&declarations, member)?;
+        }
+        Some(declarations)
+    }
+
+    fn resolve_member(
+        &mut self,
+        declarations: &[Declaration],
+        member: &Identifier,
+    ) -> Option<Vec<Declaration>> {
+        let [declaration] = declarations else {
+            return None;
+        };
+        match self.member_of(*declaration, &member.name) {
+            Lookup::Found(_declarations) => {
+                self.bindings
+                    .references
+                    .insert(member.span, _declarations.clone());
+                Some(_declarations)
+            }
+            Lookup::NotFound => {
+                let name = match self.declaration_name(*declaration) {
+                    owner if owner.is_empty() => member.name.clone(),
+                    owner => format!("{owner}.{}", member.name),
+                };
+                self.diagnostics.push(
+                    CompilerError::SemanticError(SemanticError::UndefinedVariable(name))
+                        .at(member.span),
+                );
+                None
+            }
+            Lookup::Unchecked => None,
+        }
+    }
+
+    /* INNERMOST LOCAL FIRST, THEN THE CONTRACT AND ITS BASES, THE SOURCE UNIT, THE GLOBALS */
+    fn lookup(&self, name: &str) -> Option<Vec<Declaration>> {
+        if let Some(_span) = self.scopes.iter().rev().find_map(|scope| scope.get(name)) {
+            return Some(vec![Declaration::Local(*_span)]);
+        }
+        if let Some(_contract) = self.contract {
+            if let Some(Some(_members)) = self.members.get(&(self.file_id, _contract)) {
+                if let Some(_declarations) = _members.get(name) {
+                    return Some(_declarations.clone());
+                }
+            }
+        }
+        if let Some(_symbol) = self.graph.units[self.file_id].scope.get(name) {
+            return Some(self.symbol_declarations(*_symbol));
+        }
+        BUILTINS
+            .iter()
+            .find(|builtin| **builtin == name)
+            .map(|builtin| vec![Declaration::Builtin(builtin)])
+    }
+
+    /* A FILE LEVEL FUNCTION STANDS FOR ALL OF ITS OVERLOADS */
+    fn symbol_declarations(&self, symbol: Symbol) -> Vec<Declaration> {
+        let declaration = Declaration::from(symbol);
+        let Symbol::Member {
+            file_id, contract, ..
+        } = symbol
+        else {
+            return vec![declaration];
+        };
+        self.part(declaration)
+            .and_then(part_name)
+            .and_then(|name| {
+                self.members
+                    .get(&(file_id, contract))?
+                    .as_ref()?
+                    .get(&name.name)
+                    .cloned()
+            })
+            .unwrap_or_else(|| vec![declaration])
+    }
+
+    fn member_of(&self, declaration: Declaration, name: &str) -> Lookup {
+        match declaration {
+            Declaration::Unit(file_id) => match self.graph.units[file_id].scope.get(name) {
+                Some(_symbol) => Lookup::Found(self.symbol_declarations(*_symbol)),
+                None => Lookup::NotFound,
+            },
+            Declaration::Contract { file_id, contract } => {
+                match self
+                    .members
+                    .get(&(file_id, contract))
+                    .and_then(|members| members.as_ref())
+                    .and_then(|members| members.get(name))
+                {
+                    Some(_declarations) => Lookup::Found(_declarations.clone()),
+                    None => Lookup::NotFound,
+                }
+            }
+            Declaration::Member {
+                file_id,
+                contract,
+                part,
+            } => match self.part(declaration) {
+                Some(ContractPart::Enum(definition)) => {
+                    match definition
+                        .values
+                        .iter()
+                        .position(|value| value.name == name)
+                    {
+                        Some(value) => Lookup::Found(vec![Declaration::EnumValue {
+                            file_id,
+                            contract,
+                            part,
+                            value,
+                        }]),
+                        None => Lookup::NotFound,
+                    }
+                }
+                _ => Lookup::Unchecked,
+            },
+            _ => Lookup::Unchecked,
+        }
+    }
+
+    fn declaration_name(&self, declaration: Declaration) -> String {
+        match declaration {
+            Declaration::Contract { file_id, contract } => self.graph.units[file_id].ast.contracts
+                [contract]
+                .name
+                .name
+                .clone(),
+            Declaration::Member { .. } => self
+                .part(declaration)
+                .and_then(part_name)
+                .map_or_else(String::new, |name| name.name.clone()),
+            _ => String::new(),
+        }
+    }
+
+    /* ------------------------------ REPORTING ------------------------------ */
+
+    /* A LOCAL MAY HIDE A NAME FROM AN ENCLOSING SCOPE, WHICH IS WORTH A WARNING, BUT NOT ONE
+    DECLARED IN THE SAME SCOPE */
+    fn declare_local(&mut self, name: &Identifier) {
+        if let Some(_previous) = self.scopes.last().unwrap().get(&name.name) {
+            return self.redeclared(name, Some(*_previous));
+        }
+        if let Some(_shadowed) = self.lookup(&name.name) {
+            let mut diagnostic =
+                Diagnostic::warning(format!("`{}` shadows an existing declaration", name.name))
+                    .with_label(name.span, "shadowing declaration");
+            let previous = _shadowed
+                .first()
+                .and_then(|declaration| self.declaration_span(*declaration));
+            if let Some(_previous) = previous {
+                diagnostic = diagnostic.with_secondary_label(_previous, "shadowed declaration");
+            }
+            self.diagnostics.push(diagnostic);
+        }
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.name.clone(), name.span);
+        self.bindings
+            .references
+            .insert(name.span, vec![Declaration::Local(name.span)]);
+    }
+
+    /* THE LAST SEGMENT OF A BASE, `override(..)` OR `using` PATH MUST NAME A CONTRACT */
+    fn expect_contract(
+        &mut self,
+        path: &[Identifier],
+        declarations: &[Declaration],
+    ) -> Option<Declaration> {
+        match declarations {
+            [_declaration @ Declaration::Contract { .. }] => Some(*_declaration),
+            _ => {
+                self.invalid(path, format!("`{}` is not a contract", path_name(path)));
+                None
+            }
+        }
+    }
+
+    fn redeclared(&mut self, name: &Identifier, previous: Option<Span>) {
+        let mut diagnostic =
+            CompilerError::SemanticError(SemanticError::Redeclaration(name.name.clone()))
+                .at(name.span);
+        if let Some(_previous) = previous {
+            diagnostic = diagnostic.with_secondary_label(_previous, "previous declaration here");
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn invalid(&mut self, path: &[Identifier], message: String) {
+        let span = path[0].span.to(&path[path.len() - 1].span);
+        self.diagnostics
+            .push(CompilerError::SemanticError(SemanticError::InvalidOperation(message)).at(span));
+    }
+}
+
+/* NAME A CONTRACT PART IS DECLARED UNDER. CONSTRUCTORS, `receive`, `fallback`, `using` AND
+`cron` DECLARE NOTHING */
+fn part_name(part: &ContractPart) -> Option<&Identifier> {
+    match part {
+        ContractPart::StateVariable(variable) => Some(&variable.name),
+        ContractPart::Function(function) if function.kind == FunctionKind::Function => {
+            function.name.as_ref()
+        }
+        ContractPart::Function(_) => None,
+        ContractPart::Modifier(modifier) => Some(&modifier.name),
+        ContractPart::Event(event) => Some(&event.name),
+        ContractPart::Error(error) => Some(&error.name),
+        ContractPart::Struct(definition) => Some(&definition.name),
+        ContractPart::Enum(definition) => Some(&definition.name),
+        ContractPart::Using(_) | ContractPart::Cron(_) => None,
+    }
+}
+
+fn path_name(path: &[Identifier]) -> String {
+    path.iter()
+        .map(|identifier| identifier.name.as_str())
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -0,0 +1,1581 @@
+use std::collections::HashSet;
+
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::mods::{
+    functions::controllers::{
+        evm_backend::lower, expression_codegen::twos_complement, ir_verifier::verify,
+    },
+    types::{
+        ir::{
+            is_pure_opcode, mask, Block, Checked, Function, Instruction, Integer, Program,
+            Terminator, Word,
+        },
+        opcodes::Opcode,
+        semantic_types::Type,
+        settings::{Pass, PASSES},
+    },
+};
+
+/* HOW MANY TIMES THE PIPELINE RUNS AT MOST, EACH PASS OPENING UP WORK FOR THE OTHERS */
+const ROUNDS: usize = 4;
+/* THE LARGEST INTERNAL FUNCTION THAT IS INLINED EVERYWHERE IT IS CALLED, IN INSTRUCTIONS AND
+PARTS OF WORDS */
+const INLINE_SIZE: usize = 12;
+/* HOW FAR UNDER THE TOP A VARIABLE CAN BE TO BE READ AGAIN INSTEAD OF RECOMPUTING OR LOADING
+ITS VALUE, SO THAT THE OPTIMIZER DOES NOT MAKE A STACK TOO DEEP */
+const REUSE_DEPTH: usize = 8;
+
+/* RUNS THE PASSES THAT ARE NOT DISABLED OVER THE PROGRAM UNTIL NONE OF THEM CHANGES IT. THE
+PROGRAM IS VERIFIED AFTER EVERY CHANGE, AND `log` GETS THE IR BEFORE AND AFTER EACH ONE WITH THE
+SIZE OF THE CODE BOTH LOWER TO */
+pub fn optimize(
+    program: &mut Program,
+    disabled: &[Pass],
+    push0: bool,
+    mut log: Option<&mut String>,
+) -> Result<(), String> {
+    for round in 1..=ROUNDS {
+        let mut changed = false;
+        for pass in PASSES.into_iter().filter(|pass| !disabled.contains(pass)) {
+            let before = program.clone();
+            match pass {
+                Pass::Inlining => inlining(program),
+                Pass::ConstantFolding => constant_folding(program),
+                Pass::JumpThreading => jump_threading(program),
+                Pass::LoadForwarding => storage(program, Pass::LoadForwarding),
+                Pass::RedundantStorage => storage(program, Pass::RedundantStorage),
+                Pass::Cse => cse(program),
+                Pass::DeadCode => dead_code(program),
+            }
+            if program.functions == before.functions {
+                continue;
+            }
+            changed = true;
+            verify(program).map_err(|message| format!("after the {pass} pass, {message}"))?;
+            if let Some(_log) = log.as_deref_mut() {
+                let size = |program: &Program| {
+                    lower(program, push0)
+                        .assembly
+                        .assemble()
+                        .map_or("?".to_string(), |bytecode| bytecode.code.len().to_string())
+                };
+                _log.push_str(&format!(
+                    "/* {pass}, round {round}: {} -> {} bytes */\n/* before */\n{before}\n/* after */\n{program}\n",
+                    size(&before),
+                    size(program)
+                ));
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/* ------------------------------ HELPERS ------------------------------ */
+
+/* THE HEIGHT BEFORE EVERY INSTRUCTION OF THE BLOCK, AND AT ITS TERMINATOR LAST */
+fn heights(block: &Block) -> Vec<usize> {
+    let mut height = block.height;
+    let mut heights = vec![height];
+    for instruction in &block.instructions {
+        match instruction {
+            Instruction::Let(_) => height += 1,
+            Instruction::Keep { base, positions } => height = base + positions.len(),
+            _ => {}
+        }
+        heights.push(height);
+    }
+    heights
+}
+
+/* REBUILDS A WORD FROM THE LEAVES UP, IN THE ORDER IT IS EVALUATED */
+fn rewrite(word: Word, f: &mut impl FnMut(Word) -> Word) -> Word {
+    let word = match word {
+        Word::Apply(opcode, arguments) => Word::Apply(opcode, rewrite_arguments(arguments, f)),
+        Word::Checked(operation, ty, arguments) => {
+            Word::Checked(operation, ty, rewrite_arguments(arguments, f))
+        }
+        word => word,
+    };
+    f(word)
+}
+
+fn rewrite_arguments(arguments: Vec<Word>, f: &mut impl FnMut(Word) -> Word) -> Vec<Word> {
+    let mut arguments: Vec<Word> = arguments
+        .into_iter()
+        .rev()
+        .map(|argument| rewrite(argument, f))
+        .collect();
+    arguments.reverse();
+    arguments
+}
+
+fn reads(word: &Word, position: usize) -> bool {
+    word.slots().contains(&position)
+}
+
+/* A WORD THAT CAN BE DROPPED WHEN ITS VALUE IS NOT USED: IT CHANGES NOTHING AND CANNOT FAIL */
+fn is_removable(word: &Word) -> bool {
+    let mut removable = true;
+    word.visit(&mut |part| {
+        removable &= match part {
+            Word::Checked(..) => false,
+            Word::Apply(opcode, _) => {
+                is_pure_opcode(*opcode)
+                    || matches!(
+                        opcode,
+                        Opcode::SLoad
+                            | Opcode::TLoad
+                            | Opcode::MLoad
+                            | Opcode::Keccak256
+                            | Opcode::Balance
+                            | Opcode::SelfBalance
+                            | Opcode::ExtCodeSize
+                            | Opcode::ExtCodeHash
+                            | Opcode::ReturnDataSize
+                            | Opcode::BlockHash
+                            | Opcode::BlobHash
+                    )
+            }
+            _ => true,
+        }
+    });
+    removable
+}
+
+/* A WORD WHOSE VALUE ONLY DEPENDS ON THE VARIABLES IT READS, SO THAT COMPUTING IT AGAIN GIVES
+THE SAME VALUE. A CHECKED OPERATION THAT DID NOT PANIC THE FIRST TIME DOES NOT PANIC AGAIN */
+fn is_stable(word: &Word) -> bool {
+    let mut stable = true;
+    word.visit(&mut |part| {
+        stable &= match part {
+            Word::Apply(opcode, _) => is_pure_opcode(*opcode),
+            _ => true,
+        }
+    });
+    stable
+}
+
+/* A VALUE THAT IS AS CHEAP TO PUSH AS A VARIABLE */
+fn is_simple(word: &Word) -> bool {
+    matches!(word, Word::Constant(_) | Word::Slot(_) | Word::Link(_))
+}
+
+/* MOVES EVERY VARIABLE ABOVE `above` BY `by`, AND THE HEIGHTS AT OR ABOVE IT WITH THEM */
+fn shift(instruction: &mut Instruction, above: usize, by: isize) {
+    let position = |position: usize| match position > above {
+        true => position.wrapping_add_signed(by),
+        false => position,
+    };
+    match instruction {
+        Instruction::Let(word) | Instruction::Exec(word) => *word = shift_word(word, above, by),
+        Instruction::Assign(target, word) => {
+            *target = position(*target);
+            *word = shift_word(word, above, by);
+        }
+        Instruction::Keep { base, positions } => {
+            if *base >= above {
+                *base = base.wrapping_add_signed(by);
+            }
+            for kept in positions {
+                *kept = position(*kept);
+            }
+        }
+    }
+}
+
+fn shift_word(word: &Word, above: usize, by: isize) -> Word {
+    word.substitute(&|position| {
+        (position > above).then(|| Word::Slot(position.wrapping_add_signed(by)))
+    })
+}
+
+fn shift_terminator(terminator: &mut Terminator, above: usize, by: isize) {
+    match terminator {
+        Terminator::Branch { condition, .. } => *condition = shift_word(condition, above, by),
+        Terminator::Halt(word) => *word = shift_word(word, above, by),
+        Terminator::Leave(outputs) => {
+            for output in outputs {
+                if *output > above {
+                    *output = output.wrapping_add_signed(by);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/* WHERE THE VALUES MOVE WHEN A `Keep` RUNS, `None` FOR THOSE IT DROPS */
+fn kept(base: usize, positions: &[usize], position: usize) -> Option<usize> {
+    match position <= base {
+        true => Some(position),
+        false => positions
+            .iter()
+            .position(|kept| *kept == position)
+            .map(|index| base + index + 1),
+    }
+}
+
+/* A WORD READING THE VARIABLES WHERE THEY ARE AFTER A `Keep`, UNLESS IT READS ONE IT DROPS */
+fn kept_word(base: usize, positions: &[usize], word: &Word) -> Option<Word> {
+    let slots = word.slots();
+    if slots
+        .iter()
+        .any(|position| kept(base, positions, *position).is_none())
+    {
+        return None;
+    }
+    Some(word.substitute(&|position| kept(base, positions, position).map(Word::Slot)))
+}
+
+/* ------------------------------ CONSTANT FOLDING ------------------------------ */
+
+/* FOLDS WHAT ONLY DEPENDS ON CONSTANTS AND TURNS BRANCHES ON A CONSTANT INTO JUMPS. A
+VARIABLE OF THE BLOCK KNOWN TO HOLD A SMALL CONSTANT IS REPLACED BY IT, AND ONE THAT IS A COPY
+OF ANOTHER BY THAT ONE WHILE IT IS NOT TOO DEEP, SO THAT THE COPY CAN BE DROPPED */
+fn constant_folding(program: &mut Program) {
+    for block in program
+        .functions
+        .iter_mut()
+        .flat_map(|function| function.blocks.iter_mut())
+    {
+        let mut values: Vec<Option<Word>> = vec![None; block.height];
+        let fold = |word: &Word, values: &[Option<Word>]| {
+            let height = values.len();
+            simplify(word.substitute(&|position| match &values[position - 1] {
+                Some(Word::Slot(_original)) if height - _original > REUSE_DEPTH => None,
+                value => value.clone(),
+            }))
+        };
+        let value = |word: &Word| match word {
+            Word::Slot(_) => Some(word.clone()),
+            _ => evaluate(word).filter(is_cheap).map(Word::Constant),
+        };
+        for instruction in &mut block.instructions {
+            match instruction {
+                Instruction::Let(word) => {
+                    *word = fold(word, &values);
+                    values.push(value(word));
+                }
+                Instruction::Assign(position, word) => {
+                    *word = fold(word, &values);
+                    let assigned = Word::Slot(*position);
+                    for known in &mut values {
+                        if known.as_ref() == Some(&assigned) {
+                            *known = None;
+                        }
+                    }
+                    values[*position - 1] = value(word).filter(|value| *value != assigned);
+                }
+                Instruction::Exec(word) => *word = fold(word, &values),
+                Instruction::Keep { base, positions } => {
+                    let moved = |value: &Option<Word>| match value {
+                        Some(Word::Slot(_original)) => {
+                            kept(*base, positions, *_original).map(Word::Slot)
+                        }
+                        value => value.clone(),
+                    };
+                    let kept: Vec<Option<Word>> = positions
+                        .iter()
+                        .map(|position| moved(&values[position - 1]))
+                        .collect();
+                    values.truncate(*base);
+                    values = values.iter().map(moved).collect();
+                    values.extend(kept);
+                }
+            }
+        }
+        let height = values.len();
+        match &mut block.terminator {
+            Terminator::Branch {
+                condition,
+                pops,
+                then,
+                otherwise,
+            } => {
+                /* A BRANCH ONLY TESTS WHETHER ITS CONDITION IS ZERO */
+                let mut simplified = fold(condition, &values);
+                while let Word::Apply(Opcode::IsZero, _arguments) = &simplified {
+                    match _arguments.as_slice() {
+                        [Word::Apply(Opcode::IsZero, _inner)] => simplified = _inner[0].clone(),
+                        _ => break,
+                    }
+                }
+                let Some(_value) = evaluate(&simplified) else {
+                    *condition = simplified;
+                    continue;
+                };
+                let target = if _value.is_zero() { *otherwise } else { *then };
+                if *pops > 0 {
+                    let base = height - *pops;
+                    block.instructions.push(Instruction::Keep {
+                        base,
+                        positions: Vec::new(),
+                    });
+                }
+                block.terminator = Terminator::Jump(target);
+            }
+            Terminator::Halt(word) => *word = fold(word, &values),
+            _ => {}
+        }
+    }
+}
+
+/* A CONSTANT THAT IS ABOUT AS CHEAP TO PUSH AS A VARIABLE IS TO COPY */
+fn is_cheap(value: &BigUint) -> bool {
+    value.bits() <= 64 || (mask(256) ^ value).bits() <= 56
+}
+
+fn simplify(word: Word) -> Word {
+    rewrite(word, &mut simplify_node)
+}
+
+/* ONE OPERATION WHOSE ARGUMENTS ARE ALREADY SIMPLIFIED */
+fn simplify_node(word: Word) -> Word {
+    if !matches!(word, Word::Slot(_) | Word::Link(_)) {
+        if let Some(_value) = evaluate(&word) {
+            return materialize(_value);
+        }
+    }
+    let Word::Apply(opcode, arguments) = &word else {
+        return word;
+    };
+    let value = |index: usize| arguments.get(index).and_then(evaluate);
+    let is = |index: usize, expected: u8| value(index) == Some(BigUint::from(expected));
+    let zero = Word::Constant(BigUint::zero());
+    let one = Word::Constant(BigUint::one());
+    /* AN OPERAND CAN ONLY BE DROPPED WHEN IT HAS NO EFFECT */
+    let droppable = |index: usize| arguments.get(index).is_some_and(Word::is_pure);
+    let same = arguments.len() == 2 && arguments[0] == arguments[1] && droppable(0);
+    match opcode {
+        Opcode::Add | Opcode::Or | Opcode::Xor if is(0, 0) => arguments[1].clone(),
+        Opcode::Add | Opcode::Or | Opcode::Xor | Opcode::Sub if is(1, 0) => arguments[0].clone(),
+        Opcode::Sub | Opcode::Xor if same => zero,
+        Opcode::Or if same => arguments[0].clone(),
+        Opcode::Mul if is(0, 1) => arguments[1].clone(),
+        Opcode::Mul | Opcode::Div if is(1, 1) => arguments[0].clone(),
+        Opcode::Mul | Opcode::And if (is(0, 0) && droppable(1)) || (is(1, 0) && droppable(0)) => {
+            zero
+        }
+        Opcode::Div if is(0, 0) && droppable(1) => zero,
+        Opcode::And if same => arguments[0].clone(),
+        /* A MASK THAT KEEPS EVERY BIT THE OTHER OPERAND CAN HAVE */
+        Opcode::And => {
+            for (mask_index, other) in [(0, 1), (1, 0)] {
+                if let Some(_mask) = value(mask_index) {
+                    let bits = mask(max_bits(&arguments[other]));
+                    if &_mask & &bits == bits {
+                        return arguments[other].clone();
+                    }
+                }
+            }
+            word
+        }
+        Opcode::Shl | Opcode::Shr | Opcode::Sar if is(0, 0) => arguments[1].clone(),
+        Opcode::Shl | Opcode::Shr
+            if value(0).is_some_and(|shift| shift >= BigUint::from(256u16)) && droppable(1) =>
+        {
+            zero
+        }
+        Opcode::Eq if same => one,
+        Opcode::Eq if is(1, 0) => Word::Apply(Opcode::IsZero, vec![arguments[0].clone()]),
+        Opcode::Eq if is(0, 0) => Word::Apply(Opcode::IsZero, vec![arguments[1].clone()]),
+        Opcode::Lt | Opcode::Gt | Opcode::SLt | Opcode::SGt if same => zero,
+        Opcode::Lt if is(1, 0) && droppable(0) => zero,
+        Opcode::Gt if is(0, 0) && droppable(1) => zero,
+        Opcode::Gt if is(1, 0) && max_bits(&arguments[0]) == 1 => arguments[0].clone(),
+        /* `iszero(iszero(iszero(x)))` IS `iszero(x)`, AND `iszero(iszero(x))` IS `x` WHEN `x`
+        IS ALREADY A BOOLEAN */
+        Opcode::IsZero => match &arguments[0] {
+            Word::Apply(Opcode::IsZero, _inner) => match &_inner[0] {
+                Word::Apply(Opcode::IsZero, _) => _inner[0].clone(),
+                _inner_word if max_bits(_inner_word) == 1 => _inner_word.clone(),
+                _ => word,
+            },
+            _ => word,
+        },
+        _ => word,
+    }
+}
+
+/* AN UPPER BOUND ON THE NUMBER OF BITS OF THE VALUE */
+fn max_bits(word: &Word) -> usize {
+    match word {
+        Word::Constant(value) => value.bits() as usize,
+        Word::Checked(_, ty, _) if !ty.signed => ty.bits as usize,
+        Word::Apply(opcode, arguments) => match (opcode, arguments.as_slice()) {
+            (
+                Opcode::Lt | Opcode::Gt | Opcode::SLt | Opcode::SGt | Opcode::Eq | Opcode::IsZero,
+                _,
+            ) => 1,
+            (Opcode::Address | Opcode::Caller | Opcode::Origin | Opcode::Coinbase, _) => 160,
+            (Opcode::Byte, _) => 8,
+            (Opcode::And, [a, b]) => max_bits(a).min(max_bits(b)),
+            (Opcode::Or | Opcode::Xor, [a, b]) => max_bits(a).max(max_bits(b)),
+            (Opcode::Shr, [shift, value]) => match evaluate(shift).and_then(|s| s.to_usize()) {
+                Some(_shift) => max_bits(value).saturating_sub(_shift),
+                None => 256,
+            },
+            _ => 256,
+        },
+        _ => 256,
+    }
+}
+
+/* THE SHORTEST WAY TO PUSH A CONSTANT: A WORD OF MOSTLY ONES IS `not` OF A SMALL ONE */
+fn materialize(value: BigUint) -> Word {
+    let complement = mask(256) ^ &value;
+    match complement.bits() + 8 < value.bits() {
+        true => Word::Apply(Opcode::Not, vec![Word::Constant(complement)]),
+        false => Word::Constant(value),
+    }
+}
+
+fn signed(value: &BigUint) -> BigInt {
+    match value.bit(255) {
+        true => BigInt::from(value.clone()) - (BigInt::one() << 256),
+        false => BigInt::from(value.clone()),
+    }
+}
+
+/* THE VALUE OF A WORD MADE ONLY OF CONSTANTS, AS THE EVM COMPUTES IT */
+fn evaluate(word: &Word) -> Option<BigUint> {
+    let Word::Apply(opcode, arguments) = word else {
+        return match word {
+            Word::Constant(value) => Some(value.clone()),
+            Word::Checked(operation, ty, arguments) => {
+                let values = arguments.iter().map(evaluate).collect::<Option<Vec<_>>>()?;
+                checked(*operation, *ty, &values)
+            }
+            _ => None,
+        };
+    };
+    let values = arguments.iter().map(evaluate).collect::<Option<Vec<_>>>()?;
+    let full = mask(256);
+    let modulus = BigUint::one() << 256;
+    let bit = |value: bool| BigUint::from(value as u8);
+    let shift = |value: &BigUint| value.to_usize().filter(|shift| *shift < 256);
+    let wrap = |value: BigInt| twos_complement(&value) & &full;
+    Some(match (opcode, values.as_slice()) {
+        (Opcode::Add, [a, b]) => (a + b) & &full,
+        (Opcode::Sub, [a, b]) => (a + &modulus - b) & &full,
+        (Opcode::Mul, [a, b]) => (a * b) & &full,
+        (Opcode::Div | Opcode::SDiv | Opcode::Mod | Opcode::SMod, [_, b]) if b.is_zero() => {
+            BigUint::zero()
+        }
+        (Opcode::Div, [a, b]) => a / b,
+        (Opcode::Mod, [a, b]) => a % b,
+        (Opcode::SDiv, [a, b]) => wrap(signed(a) / signed(b)),
+        (Opcode::SMod, [a, b]) => wrap(signed(a) % signed(b)),
+        (Opcode::AddMod | Opcode::MulMod, [_, _, n]) if n.is_zero() => BigUint::zero(),
+        (Opcode::AddMod, [a, b, n]) => (a + b) % n,
+        (Opcode::MulMod, [a, b, n]) => (a * b) % n,
+        (Opcode::Exp, [a, b]) => a.modpow(b, &modulus),
+        (Opcode::SignExtend, [bytes, value]) => {
+            match bytes.to_usize().filter(|bytes| *bytes < 31) {
+                Some(_bytes) => {
+                    let low = mask(8 * _bytes + 8);
+                    match value.bit(8 * _bytes as u64 + 7) {
+                        true => value | (&full ^ &low),
+                        false => value & low,
+                    }
+                }
+                None => value.clone(),
+            }
+        }
+        (Opcode::Lt, [a, b]) => bit(a < b),
+        (Opcode::Gt, [a, b]) => bit(a > b),
+        (Opcode::SLt, [a, b]) => bit(signed(a) < signed(b)),
+        (Opcode::SGt, [a, b]) => bit(signed(a) > signed(b)),
+        (Opcode::Eq, [a, b]) => bit(a == b),
+        (Opcode::IsZero, [a]) => bit(a.is_zero()),
+        (Opcode::And, [a, b]) => a & b,
+        (Opcode::Or, [a, b]) => a | b,
+        (Opcode::Xor, [a, b]) => a ^ b,
+        (Opcode::Not, [a]) => &full ^ a,
+        (Opcode::Byte, [index, value]) => match index.to_usize().filter(|index| *index < 32) {
+            Some(_index) => (value >> (8 * (31 - _index))) & BigUint::from(0xffu8),
+            None => BigUint::zero(),
+        },
+        (Opcode::Shl, [s, v]) => shift(s).map_or(BigUint::zero(), |s| (v << s) & &full),
+        (Opcode::Shr, [s, v]) => shift(s).map_or(BigUint::zero(), |s| v >> s),
+        (Opcode::Sar, [s, v]) => wrap(signed(v) >> shift(s).unwrap_or(256)),
+        _ => return None,
+    })
+}
+
+/* A CHECKED OPERATION ON CONSTANTS THAT FIT THE TYPE, UNLESS IT WOULD PANIC */
+fn checked(operation: Checked, ty: Integer, values: &[BigUint]) -> Option<BigUint> {
+    let [a, b] = values else {
+        return None;
+    };
+    let value = |word: &BigUint| match ty.signed {
+        true => signed(word),
+        false => BigInt::from(word.clone()),
+    };
+    let (a, b) = (value(a), value(b));
+    let (min, max) = Type::integer_range(ty.signed, ty.bits);
+    let fits = |value: &BigInt| min <= *value && *value <= max;
+    if !fits(&a) || !fits(&b) {
+        return None;
+    }
+    let result = match operation {
+        Checked::Add => a + b,
+        Checked::Sub => a - b,
+        Checked::Mul => a * b,
+        Checked::Div | Checked::Mod if b.is_zero() => return None,
+        Checked::Div => a / b,
+        Checked::Mod => a % b,
+    };
+    fits(&result).then(|| twos_complement(&result))
+}
+
+/* ------------------------------ JUMP THREADING ------------------------------ */
+
+fn jump_threading(program: &mut Program) {
+    for function in &mut program.functions {
+        /* A JUMP TO A BLOCK THAT ONLY JUMPS ON GOES STRAIGHT TO ITS TARGET */
+        for index in 0..function.blocks.len() {
+            let mut terminator = function.blocks[index].terminator.clone();
+            for target in terminator.successors_mut() {
+                *target = forward(function, *target);
+            }
+            function.blocks[index].terminator = terminator;
+        }
+        for index in 0..function.blocks.len() {
+            let height = *heights(&function.blocks[index]).last().unwrap();
+            let block = &function.blocks[index];
+            let replacement = match &block.terminator {
+                /* A BLOCK THAT ONLY PANICS OR HALTS WITH CONSTANTS IS COPIED INTO THE BLOCKS
+                THAT JUMP TO IT */
+                Terminator::Jump(_target) => {
+                    let target = &function.blocks[*_target];
+                    let small = match &target.terminator {
+                        Terminator::Panic(_) => true,
+                        Terminator::Halt(word) => word.slots().is_empty() && is_stable(word),
+                        _ => false,
+                    };
+                    (target.instructions.is_empty() && small && *_target != index)
+                        .then(|| (None, target.terminator.clone()))
+                }
+                /* A BRANCH THAT GOES THE SAME WAY EITHER WAY */
+                Terminator::Branch {
+                    condition,
+                    pops,
+                    then,
+                    otherwise,
+                } if then == otherwise && is_removable(condition) => Some((
+                    (*pops > 0).then(|| Instruction::Keep {
+                        base: height - pops,
+                        positions: Vec::new(),
+                    }),
+                    Terminator::Jump(*then),
+                )),
+                _ => None,
+            };
+            if let Some((_instruction, _terminator)) = replacement {
+                let block = &mut function.blocks[index];
+                block.instructions.extend(_instruction);
+                block.terminator = _terminator;
+            }
+        }
+        merge_blocks(function);
+        let reachable = function.reachable();
+        function.retain_blocks(&reachable);
+    }
+}
+
+fn forward(function: &Function, mut target: usize) -> usize {
+    let mut seen = HashSet::new();
+    while seen.insert(target) {
+        let block = &function.blocks[target];
+        match (&block.terminator, block.instructions.is_empty()) {
+            (Terminator::Jump(_next), true) => target = *_next,
+            _ => break,
+        }
+    }
+    target
+}
+
+/* A BLOCK THAT IS ONLY REACHED BY A JUMP FROM ANOTHER ONE BECOMES THE END OF IT */
+fn merge_blocks(function: &mut Function) {
+    let mut predecessors = vec![0; function.blocks.len()];
+    for block in &function.blocks {
+        for target in block.terminator.successors() {
+            predecessors[target] += 1;
+        }
+    }
+    let mut merged = vec![false; function.blocks.len()];
+    for index in 0..function.blocks.len() {
+        if merged[index] {
+            continue;
+        }
+        while let Terminator::Jump(_target) = function.blocks[index].terminator {
+            if _target == 0 || _target == index || predecessors[_target] != 1 || merged[_target] {
+                break;
+            }
+            merged[_target] = true;
+            let target = function.blocks[_target].clone();
+            let block = &mut function.blocks[index];
+            block.instructions.extend(target.instructions);
+            block.terminator = target.terminator;
+        }
+    }
+}
+
+/* ------------------------------ STORAGE ------------------------------ */
+
+/* WHAT A STORAGE SLOT IS KNOWN TO HOLD, FROM A LOAD OR A STORE EARLIER IN THE BLOCK */
+struct Known {
+    key: Word,
+    value: Word,
+    stored: bool,
+}
+
+/* BUILTINS THAT RUN OTHER CODE, WHICH CAN CHANGE THE STORAGE OF THIS CONTRACT */
+fn writes_storage(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::Call | Opcode::CallCode | Opcode::DelegateCall | Opcode::Create | Opcode::Create2
+    )
+}
+
+/* TWO KEYS THAT CANNOT NAME THE SAME SLOT: DIFFERENT CONSTANTS, OR DIFFERENT OFFSETS FROM THE
+SAME VALUE */
+fn distinct(a: &Word, b: &Word) -> bool {
+    let split = |key: &Word| match key {
+        Word::Apply(Opcode::Add, _arguments) => match evaluate(&_arguments[1]) {
+            Some(_offset) => (Some(_arguments[0].clone()), _offset),
+            None => (Some(key.clone()), BigUint::zero()),
+        },
+        _ => match evaluate(key) {
+            Some(_value) => (None, _value),
+            None => (Some(key.clone()), BigUint::zero()),
+        },
+    };
+    let (a, b) = (split(a), split(b));
+    a.0 == b.0 && a.1 != b.1
+}
+
+/* FORWARDS WHAT IS KNOWN ABOUT STORAGE INTO THE `sload`S THAT FOLLOW IN THE BLOCK. LOAD
+FORWARDING USES THE VALUES STORED, THE REDUNDANT STORAGE PASS THE VALUES ALREADY LOADED, AND
+ALSO DROPS STORES THAT DO NOT CHANGE THE SLOT OR THAT ARE OVERWRITTEN BEFORE ANYTHING READS
+THEM */
+fn storage(program: &mut Program, pass: Pass) {
+    let use_stored = pass == Pass::LoadForwarding;
+    for block in program
+        .functions
+        .iter_mut()
+        .flat_map(|function| function.blocks.iter_mut())
+    {
+        let mut known: Vec<Known> = Vec::new();
+        let mut height = block.height;
+        let instructions = std::mem::take(&mut block.instructions);
+        for (index, instruction) in instructions.iter().enumerate() {
+            let forward = |word: &Word, known: &mut Vec<Known>| {
+                forward_loads(word, known, height, use_stored)
+            };
+            match instruction {
+                Instruction::Let(word) => {
+                    let word = forward(word, &mut known);
+                    height += 1;
+                    if let Word::Apply(Opcode::SLoad, _arguments) = &word {
+                        if _arguments[0].is_pure() {
+                            known.push(Known {
+                                key: _arguments[0].clone(),
+                                value: Word::Slot(height),
+                                stored: false,
+                            });
+                        }
+                    }
+                    block.instructions.push(Instruction::Let(word));
+                }
+                Instruction::Assign(position, word) => {
+                    let word = forward(word, &mut known);
+                    known.retain(|known| {
+                        !reads(&known.key, *position) && !reads(&known.value, *position)
+                    });
+                    if let Word::Apply(Opcode::SLoad, _arguments) = &word {
+                        if _arguments[0].is_pure() && !reads(&_arguments[0], *position) {
+                            known.push(Known {
+                                key: _arguments[0].clone(),
+                                value: Word::Slot(*position),
+                                stored: false,
+                            });
+                        }
+                    }
+                    block
+                        .instructions
+                        .push(Instruction::Assign(*position, word));
+                }
+                Instruction::Exec(word) => {
+                    let word = forward(word, &mut known);
+                    if let Word::Apply(Opcode::SStore, _arguments) = &word {
+                        let (key, value) = (&_arguments[0], &_arguments[1]);
+                        if !use_stored
+                            && is_removable(key)
+                            && is_removable(value)
+                            && (known
+                                .iter()
+                                .any(|known| known.key == *key && known.value == *value)
+                                || overwritten(&instructions[index + 1..], key))
+                        {
+                            continue;
+                        }
+                        known.retain(|known| distinct(&known.key, key));
+                        if key.is_pure() && is_simple(value) {
+                            known.push(Known {
+                                key: key.clone(),
+                                value: value.clone(),
+                                stored: true,
+                            });
+                        }
+                    }
+                    block.instructions.push(Instruction::Exec(word));
+                }
+                Instruction::Keep { base, positions } => {
+                    known = known
+                        .into_iter()
+                        .filter_map(|known| {
+                            Some(Known {
+                                key: kept_word(*base, positions, &known.key)?,
+                                value: kept_word(*base, positions, &known.value)?,
+                                stored: known.stored,
+                            })
+                        })
+                        .collect();
+                    height = base + positions.len();
+                    block.instructions.push(instruction.clone());
+                }
+            }
+        }
+    }
+}
+
+/* THE WORD WITH EVERY `sload` OF A SLOT KNOWN TO HOLD A VALUE REPLACED BY IT. A CALL IN THE
+WORD FORGETS WHAT IS KNOWN FOR THE PARTS EVALUATED AFTER IT */
+fn forward_loads(word: &Word, known: &mut Vec<Known>, height: usize, stored: bool) -> Word {
+    rewrite(word.clone(), &mut |part| {
+        if let Word::Apply(Opcode::SLoad, _arguments) = &part {
+            if let Some(_known) = known.iter().find(|known| {
+                known.key == _arguments[0]
+                    && known.stored == stored
+                    && match known.value {
+                        Word::Slot(_position) => height - _position <= REUSE_DEPTH,
+                        _ => true,
+                    }
+            }) {
+                return _known.value.clone();
+            }
+        }
+        if let Word::Apply(_opcode, _) = &part {
+            if writes_storage(*_opcode) {
+                known.clear();
+            }
+        }
+        part
+    })
+}
+
+/* WHETHER THE INSTRUCTIONS STORE TO `key` AGAIN BEFORE ANYTHING CAN READ IT OR `key` CHANGES */
+fn overwritten(instructions: &[Instruction], key: &Word) -> bool {
+    let slots = key.slots();
+    for instruction in instructions {
+        let word = match instruction {
+            Instruction::Let(word) | Instruction::Assign(_, word) | Instruction::Exec(word) => word,
+            Instruction::Keep { base, .. } => match slots.iter().all(|slot| slot <= base) {
+                true => continue,
+                false => return false,
+            },
+        };
+        let mut observed = false;
+        word.visit(&mut |part| match part {
+            Word::Apply(Opcode::SLoad, _arguments) => observed |= !distinct(&_arguments[0], key),
+            Word::Apply(_opcode, _) => {
+                observed |= writes_storage(*_opcode) || *_opcode == Opcode::StaticCall
+            }
+            _ => {}
+        });
+        if observed {
+            return false;
+        }
+        match instruction {
+            Instruction::Exec(Word::Apply(Opcode::SStore, _arguments)) if _arguments[0] == *key => {
+                return true
+            }
+            Instruction::Assign(position, _) if slots.contains(position) => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+/* ------------------------------ COMMON SUBEXPRESSIONS ------------------------------ */
+
+/* A VALUE COMPUTED AGAIN IN THE SAME BLOCK, WHILE THE VARIABLE IT WAS FIRST PUT IN STILL HOLDS
+IT, IS READ FROM THAT VARIABLE */
+fn cse(program: &mut Program) {
+    for block in program
+        .functions
+        .iter_mut()
+        .flat_map(|function| function.blocks.iter_mut())
+    {
+        let mut available: Vec<(Word, usize)> = Vec::new();
+        let mut height = block.height;
+        let worth =
+            |word: &Word| matches!(word, Word::Apply(..) | Word::Checked(..)) && is_stable(word);
+        for instruction in &mut block.instructions {
+            match instruction {
+                Instruction::Let(word) => {
+                    *word = reuse(word, &available, height);
+                    height += 1;
+                    if worth(word) {
+                        available.push((word.clone(), height));
+                    }
+                }
+                Instruction::Assign(position, word) => {
+                    *word = reuse(word, &available, height);
+                    let position = *position;
+                    available.retain(|(value, at)| *at != position && !reads(value, position));
+                    if worth(word) && !reads(word, position) {
+                        available.push((word.clone(), position));
+                    }
+                }
+                Instruction::Exec(word) => *word = reuse(word, &available, height),
+                Instruction::Keep { base, positions } => {
+                    available = available
+                        .into_iter()
+                        .filter_map(|(value, at)| {
+                            Some((
+                                kept_word(*base, positions, &value)?,
+                                kept(*base, positions, at)?,
+                            ))
+                        })
+                        .collect();
+                    height = *base + positions.len();
+                }
+            }
+        }
+        match &mut block.terminator {
+            Terminator::Branch { condition, .. } => {
+                *condition = reuse(condition, &available, height)
+            }
+            Terminator::Halt(word) => *word = reuse(word, &available, height),
+            _ => {}
+        }
+    }
+}
+
+fn reuse(word: &Word, available: &[(Word, usize)], height: usize) -> Word {
+    if let Some((_, _position)) = available
+        .iter()
+        .find(|(value, position)| value == word && height - position <= REUSE_DEPTH)
+    {
+        return Word::Slot(*_position);
+    }
+    match word {
+        Word::Apply(opcode, arguments) => Word::Apply(
+            *opcode,
+            arguments
+                .iter()
+                .map(|argument| reuse(argument, available, height))
+                .collect(),
+        ),
+        Word::Checked(operation, ty, arguments) => Word::Checked(
+            *operation,
+            *ty,
+            arguments
+                .iter()
+                .map(|argument| reuse(argument, available, height))
+                .collect(),
+        ),
+        _ => word.clone(),
+    }
+}
+
+/* ------------------------------ DEAD CODE ------------------------------ */
+
+/* WHAT HAPPENS TO A VALUE AFTER IT IS WRITTEN */
+enum Fate {
+    Read,
+    Overwritten,
+    /* DROPPED BY THE INSTRUCTION AT THE INDEX, OR THE TERMINATOR WHEN IT IS PAST THE LAST */
+    Dropped(usize),
+}
+
+fn dead_code(program: &mut Program) {
+    for function in &mut program.functions {
+        let reachable = function.reachable();
+        function.retain_blocks(&reachable);
+    }
+    remove_functions(program);
+    for block in program
+        .functions
+        .iter_mut()
+        .flat_map(|function| function.blocks.iter_mut())
+    {
+        for index in (0..block.instructions.len()).rev() {
+            let heights = heights(block);
+            match &block.instructions[index] {
+                Instruction::Let(word) if is_removable(word) => {
+                    let position = heights[index] + 1;
+                    if let Fate::Dropped(_at) = fate(block, index, position) {
+                        remove_variable(block, index, position, _at);
+                    }
+                }
+                Instruction::Assign(position, word)
+                    if is_removable(word)
+                        && !matches!(fate(block, index, *position), Fate::Read) =>
+                {
+                    block.instructions.remove(index);
+                }
+                _ => {}
+            }
+        }
+        let heights = heights(block);
+        let mut index = 0;
+        block.instructions.retain(|instruction| {
+            index += 1;
+            !matches!(instruction, Instruction::Keep { base, positions }
+                if base + positions.len() == heights[index - 1]
+                    && positions.iter().enumerate().all(|(offset, position)| *position == base + offset + 1))
+        });
+    }
+}
+
+/* DROPS THE FUNCTIONS NO CALL REACHES FROM THE ENTRY, RENUMBERING THE OTHERS */
+fn remove_functions(program: &mut Program) {
+    let mut called = vec![false; program.functions.len()];
+    let mut work = vec![0];
+    while let Some(function) = work.pop() {
+        if called[function] {
+            continue;
+        }
+        called[function] = true;
+        for block in &program.functions[function].blocks {
+            if let Terminator::Call {
+                function: _callee, ..
+            } = block.terminator
+            {
+                work.push(_callee);
+            }
+        }
+    }
+    if called.iter().all(|called| *called) {
+        return;
+    }
+    let mut numbers = Vec::new();
+    let mut next = 0;
+    for kept in &called {
+        numbers.push(next);
+        next += *kept as usize;
+    }
+    let mut index = 0;
+    program.functions.retain(|_| {
+        index += 1;
+        called[index - 1]
+    });
+    for block in program
+        .functions
+        .iter_mut()
+        .flat_map(|function| function.blocks.iter_mut())
+    {
+        if let Terminator::Call { function, .. } = &mut block.terminator {
+            *function = numbers[*function];
+        }
+    }
+}
+
+/* FOLLOWS THE VALUE WRITTEN TO `position` BY THE INSTRUCTION AT `index` TO WHERE IT IS READ,
+OVERWRITTEN OR DROPPED. ONE THAT IS STILL THERE AT A JUMP OR CALL MAY BE READ AFTER IT */
+fn fate(block: &Block, index: usize, position: usize) -> Fate {
+    let heights = heights(block);
+    for (at, instruction) in block.instructions.iter().enumerate().skip(index + 1) {
+        match instruction {
+            Instruction::Let(word) | Instruction::Exec(word) if reads(word, position) => {
+                return Fate::Read
+            }
+            Instruction::Assign(target, word) => {
+                if reads(word, position) {
+                    return Fate::Read;
+                }
+                if *target == position {
+                    return Fate::Overwritten;
+                }
+            }
+            Instruction::Keep { base, positions } => {
+                if positions.contains(&position) {
+                    return Fate::Read;
+                }
+                if *base < position {
+                    return Fate::Dropped(at);
+                }
+            }
+            _ => {}
+        }
+    }
+    let dropped = Fate::Dropped(block.instructions.len());
+    match &block.terminator {
+        Terminator::Branch {
+            condition, pops, ..
+        } if !reads(condition, position) && position > heights[block.instructions.len()] - pops => {
+            dropped
+        }
+        Terminator::Leave(outputs) if !outputs.contains(&position) => dropped,
+        Terminator::Halt(word) if !reads(word, position) => dropped,
+        Terminator::Panic(_) => dropped,
+        _ => Fate::Read,
+    }
+}
+
+/* REMOVES THE `Let` AT `index` OF A VARIABLE NOTHING READS BEFORE IT IS DROPPED AT `dropped`,
+MOVING THE VARIABLES ABOVE IT DOWN UNTIL THEN */
+fn remove_variable(block: &mut Block, index: usize, position: usize, dropped: usize) {
+    block.instructions.remove(index);
+    let end = dropped.min(block.instructions.len());
+    for instruction in &mut block.instructions[index..end] {
+        shift(instruction, position, -1);
+    }
+    if dropped == block.instructions.len() + 1 {
+        if let Terminator::Branch { pops, .. } = &mut block.terminator {
+            *pops -= 1;
+        }
+        shift_terminator(&mut block.terminator, position, -1);
+    }
+}
+
+/* ------------------------------ INLINING ------------------------------ */
+
+/* INSTRUCTIONS, TERMINATORS AND THE PARTS OF THEIR WORDS */
+fn size(function: &Function) -> usize {
+    let mut size = 0;
+    let mut count = |word: &Word| word.visit(&mut |_| size += 1);
+    for block in &function.blocks {
+        for instruction in &block.instructions {
+            if let Instruction::Let(_word)
+            | Instruction::Assign(_, _word)
+            | Instruction::Exec(_word) = instruction
+            {
+                count(_word);
+            }
+        }
+        if let Terminator::Branch {
+            condition: _word, ..
+        }
+        | Terminator::Halt(_word) = &block.terminator
+        {
+            count(_word);
+        }
+    }
+    size + function
+        .blocks
+        .iter()
+        .map(|block| block.instructions.len() + 1)
+        .sum::<usize>()
+}
+
+/* A CALL OF A FUNCTION THAT CALLS NO OTHER IS REPLACED BY A COPY OF ITS BLOCKS, ITS VARIABLES
+MOVED ABOVE THE CALLER'S AND ITS RETURNS TURNED INTO JUMPS BACK, WHEN THE FUNCTION IS SMALL OR
+THAT IS ITS ONLY CALL, SO THAT THE CODE DOES NOT GROW */
+fn inlining(program: &mut Program) {
+    let mut calls = vec![0; program.functions.len()];
+    for block in program
+        .functions
+        .iter()
+        .flat_map(|function| function.blocks.iter())
+    {
+        if let Terminator::Call { function, .. } = block.terminator {
+            calls[function] += 1;
+        }
+    }
+    let small: Vec<bool> = program
+        .functions
+        .iter()
+        .enumerate()
+        .map(|(index, function)| {
+            index > 0
+                && (calls[index] == 1 || size(function) <= INLINE_SIZE)
+                && function
+                    .blocks
+                    .iter()
+                    .all(|block| !matches!(block.terminator, Terminator::Call { .. }))
+        })
+        .collect();
+    for caller in 0..program.functions.len() {
+        for index in 0..program.functions[caller].blocks.len() {
+            let Terminator::Call { function, next } =
+                program.functions[caller].blocks[index].terminator
+            else {
+                continue;
+            };
+            if !small[function] {
+                continue;
+            }
+            let callee = program.functions[function].clone();
+            let blocks = &mut program.functions[caller].blocks;
+            let offset = *heights(&blocks[index]).last().unwrap() - callee.parameters;
+            let start = blocks.len();
+            for mut block in callee.blocks {
+                block.height += offset;
+                for instruction in &mut block.instructions {
+                    shift(instruction, 0, offset as isize);
+                }
+                block.terminator = match block.terminator {
+                    Terminator::Leave(outputs) => {
+                        block.instructions.push(Instruction::Keep {
+                            base: offset,
+                            positions: outputs.iter().map(|output| output + offset).collect(),
+                        });
+                        Terminator::Jump(next)
+                    }
+                    mut terminator => {
+                        shift_terminator(&mut terminator, 0, offset as isize);
+                        for target in terminator.successors_mut() {
+                            *target += start;
+                        }
+                        terminator
+                    }
+                };
+                blocks.push(block);
+            }
+            blocks[index].terminator = Terminator::Jump(start);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::{
+        constant_folding, cse, dead_code, inlining, jump_threading, optimize, storage, verify,
+    };
+    use crate::mods::types::{
+        ir::{Block, Function, Instruction, Program, Terminator, Word},
+        line_descriptors::Span,
+        opcodes::Opcode,
+        settings::Pass,
+    };
+
+    fn constant(value: u32) -> Word {
+        Word::Constant(BigUint::from(value))
+    }
+
+    fn apply(opcode: Opcode, arguments: impl Into<Vec<Word>>) -> Word {
+        Word::Apply(opcode, arguments.into())
+    }
+
+    fn stop() -> Terminator {
+        Terminator::Halt(apply(Opcode::Stop, []))
+    }
+
+    fn block(height: usize, instructions: Vec<Instruction>, terminator: Terminator) -> Block {
+        Block {
+            height,
+            span: Span::default(),
+            instructions,
+            terminator,
+        }
+    }
+
+    fn function(parameters: usize, returns: usize, blocks: Vec<Block>) -> Function {
+        Function {
+            name: "f".to_string(),
+            span: Span::default(),
+            parameters,
+            returns,
+            blocks,
+        }
+    }
+
+    fn program(functions: Vec<Function>) -> Program {
+        let mut program = Program::default();
+        program.functions = functions;
+        program
+    }
+
+    /* AN ENTRY FUNCTION MADE OF ONE BLOCK THAT STOPS */
+    fn straight(instructions: Vec<Instruction>) -> Program {
+        program(vec![function(0, 0, vec![block(0, instructions, stop())])])
+    }
+
+    /* RUNS THE PASS, CHECKS THE PROGRAM IS STILL VALID AND RETURNS ITS TEXT */
+    fn run(mut program: Program, pass: fn(&mut Program)) -> String {
+        verify(&program).expect("the test program should be valid");
+        pass(&mut program);
+        verify(&program).expect("the pass should keep the program valid");
+        program.to_string()
+    }
+
+    fn text(lines: &[&str]) -> String {
+        lines.iter().map(|line| format!("{line}\n")).collect()
+    }
+
+    #[test]
+    fn constant_folding_evaluates_constants_and_branches() {
+        let program = program(vec![function(
+            0,
+            0,
+            vec![
+                block(
+                    0,
+                    vec![
+                        Instruction::Let(apply(Opcode::Add, [constant(2), constant(3)])),
+                        Instruction::Let(apply(Opcode::CallValue, [])),
+                        Instruction::Exec(apply(
+                            Opcode::MStore,
+                            [
+                                constant(0),
+                                apply(Opcode::Mul, [Word::Slot(2), Word::Slot(1)]),
+                            ],
+                        )),
+                        Instruction::Exec(apply(
+                            Opcode::MStore,
+                            [
+                                constant(32),
+                                apply(Opcode::Add, [Word::Slot(2), constant(0)]),
+                            ],
+                        )),
+                    ],
+                    Terminator::Branch {
+                        condition: apply(Opcode::Lt, [Word::Slot(1), constant(4)]),
+                        pops: 1,
+                        then: 1,
+                        otherwise: 2,
+                    },
+                ),
+                block(1, vec![], Terminator::Panic(0x01)),
+                block(1, vec![], stop()),
+            ],
+        )]);
+        assert_eq!(
+            run(program, constant_folding),
+            text(&[
+                "function @0 \"f\" 0 -> 0",
+                "b0 [0]:",
+                "    let $1 := 5",
+                "    let $2 := callvalue()",
+                "    mstore(0, mul($2, 5))",
+                "    mstore(32, $2)",
+                "    keep 1 []",
+                "    jump b2",
+                "b1 [1]:",
+                "    panic 0x01",
+                "b2 [1]:",
+                "    stop()",
+            ])
+        );
+    }
+
+    #[test]
+    fn jump_threading_skips_empty_blocks_and_merges_single_predecessors() {
+        let program = program(vec![function(
+            0,
+            0,
+            vec![
+                block(
+                    0,
+                    vec![Instruction::Let(apply(Opcode::CallValue, []))],
+                    Terminator::Branch {
+                        condition: Word::Slot(1),
+                        pops: 1,
+                        then: 1,
+                        otherwise: 2,
+                    },
+                ),
+                block(0, vec![], Terminator::Jump(3)),
+                block(0, vec![], Terminator::Jump(4)),
+                block(0, vec![], Terminator::Panic(0x11)),
+                block(
+                    0,
+                    vec![Instruction::Exec(apply(
+                        Opcode::SStore,
+                        [constant(0), constant(1)],
+                    ))],
+                    stop(),
+                ),
+            ],
+        )]);
+        assert_eq!(
+            run(program, jump_threading),
+            text(&[
+                "function @0 \"f\" 0 -> 0",
+                "b0 [0]:",
+                "    let $1 := callvalue()",
+                "    branch $1 b1 b2 pop 1",
+                "b1 [0]:",
+                "    panic 0x11",
+                "b2 [0]:",
+                "    sstore(0, 1)",
+                "    stop()",
+            ])
+        );
+    }
+
+    #[test]
+    fn jump_threading_drops_branches_that_go_the_same_way() {
+        let program = program(vec![function(
+            0,
+            0,
+            vec![
+                block(
+                    0,
+                    vec![Instruction::Let(apply(Opcode::CallValue, []))],
+                    Terminator::Branch {
+                        condition: Word::Slot(1),
+                        pops: 1,
+                        then: 1,
+                        otherwise: 1,
+                    },
+                ),
+                block(0, vec![], stop()),
+            ],
+        )]);
+        assert_eq!(
+            run(program, jump_threading),
+            text(&[
+                "function @0 \"f\" 0 -> 0",
+                "b0 [0]:",
+                "    let $1 := callvalue()",
+                "    keep 0 []",
+                "    stop()",
+            ])
+        );
+    }
+
+    #[test]
+    fn load_forwarding_reads_what_was_just_stored() {
+        let program = straight(vec![
+            Instruction::Exec(apply(Opcode::SStore, [constant(1), constant(5)])),
+            Instruction::Let(apply(Opcode::SLoad, [constant(1)])),
+            Instruction::Let(apply(Opcode::SLoad, [constant(2)])),
+            Instruction::Let(apply(
+                Opcode::Call,
+                [
+                    apply(Opcode::Gas, []),
+                    constant(0),
+                    constant(0),
+                    constant(0),
+                    constant(0),
+                    constant(0),
+                    constant(0),
+                ],
+            )),
+            Instruction::Let(apply(Opcode::SLoad, [constant(1)])),
+        ]);
+        assert_eq!(
+            run(program, |program| storage(program, Pass::LoadForwarding)),
+            text(&[
+                "function @0 \"f\" 0 -> 0",
+                "b0 [0]:",
+                "    sstore(1, 5)",
+                "    let $1 := 5",
+                "    let $2 := sload(2)",
+                "    let $3 := call(gas(), 0, 0, 0, 0, 0, 0)",
+                "    let $4 := sload(1)",
+                "    stop()",
+            ])
+        );
+    }
+
+    #[test]
+    fn redundant_storage_drops_loads_and_stores_that_change_nothing() {
+        let program = straight(vec![
+            Instruction::Let(apply(Opcode::SLoad, [constant(1)])),
+            Instruction::Let(apply(Opcode::SLoad, [constant(1)])),
+            Instruction::Exec(apply(Opcode::SStore, [constant(1), Word::Slot(1)])),
+            Instruction::Exec(apply(Opcode::SStore, [constant(2), constant(7)])),
+            Instruction::Exec(apply(Opcode::SStore, [constant(2), constant(8)])),
+        ]);
+        assert_eq!(
+            run(program, |program| storage(program, Pass::RedundantStorage)),
+            text(&[
+                "function @0 \"f\" 0 -> 0",
+                "b0 [0]:",
+                "    let $1 := sload(1)",
+                "    let $2 := $1",
+                "    sstore(2, 8)",
+                "    stop()",
+            ])
+        );
+    }
+
+    #[test]
+    fn cse_reuses_values_computed_in_the_block() {
+        let sum = || apply(Opcode::Add, [apply(Opcode::CallValue, []), constant(1)]);
+        let program = straight(vec![
+            Instruction::Let(sum()),
+            Instruction::Let(apply(Opcode::Mul, [sum(), constant(2)])),
+            Instruction::Assign(1, constant(0)),
+            Instruction::Let(sum()),
+        ]);
+        assert_eq!(
+            run(program, cse),
+            text(&[
+                "function @0 \"f\" 0 -> 0",
+                "b0 [0]:",
+                "    let $1 := add(callvalue(), 1)",
+                "    let $2 := mul($1, 2)",
+                "    $1 := 0",
+                "    let $3 := add(callvalue(), 1)",
+                "    stop()",
+            ])
+        );
+    }
+
+    #[test]
+    fn dead_code_removes_unused_values_blocks_and_functions() {
+        let program = program(vec![
+            function(
+                0,
+                0,
+                vec![
+                    block(
+                        0,
+                        vec![
+                            Instruction::Let(apply(Opcode::CallValue, [])),
+                            Instruction::Let(apply(Opcode::CallDataSize, [])),
+                            Instruction::Exec(apply(Opcode::MStore, [constant(0), Word::Slot(2)])),
+                            Instruction::Assign(1, constant(3)),
+                        ],
+                        stop(),
+                    ),
+                    block(
+                        0,
+                        vec![],
+                        Terminator::Call {
+                            function: 1,
+                            next: 0,
+                        },
+                    ),
+                ],
+            ),
+            function(0, 0, vec![block(0, vec![], Terminator::Leave(vec![]))]),
+        ]);
+        assert_eq!(
+            run(program, dead_code),
+            text(&[
+                "function @0 \"f\" 0 -> 0",
+                "b0 [0]:",
+                "    let $1 := calldatasize()",
+                "    mstore(0, $1)",
+                "    stop()",
+            ])
+        );
+    }
+
+    #[test]
+    fn inlining_copies_small_functions_into_their_callers() {
+        let program = program(vec![
+            function(
+                0,
+                0,
+                vec![
+                    block(
+                        0,
+                        vec![Instruction::Let(apply(Opcode::CallValue, []))],
+                        Terminator::Call {
+                            function: 1,
+                            next: 1,
+                        },
+                    ),
+                    block(
+                        1,
+                        vec![Instruction::Exec(apply(
+                            Opcode::MStore,
+                            [constant(0), Word::Slot(1)],
+                        ))],
+                        stop(),
+                    ),
+                ],
+            ),
+            function(
+                1,
+                1,
+                vec![block(
+                    1,
+                    vec![Instruction::Let(apply(
+                        Opcode::Add,
+                        [Word::Slot(1), constant(1)],
+                    ))],
+                    Terminator::Leave(vec![2]),
+                )],
+            ),
+        ]);
+        assert_eq!(
+            run(program, inlining),
+            text(&[
+                "function @0 \"f\" 0 -> 0",
+                "b0 [0]:",
+                "    let $1 := callvalue()",
+                "    jump b2",
+                "b1 [1]:",
+                "    mstore(0, $1)",
+                "    stop()",
+                "b2 [1]:",
+                "    let $2 := add($1, 1)",
+                "    keep 0 [$2]",
+                "    jump b1",
+                "",
+                "function @1 \"f\" 1 -> 1",
+                "b0 [1]:",
+                "    let $2 := add($1, 1)",
+                "    leave [$2]",
+            ])
+        );
+    }
+
+    /* `x + 0` KEPT IN A VARIABLE THAT IS NEVER READ */
+    fn foldable() -> Program {
+        straight(vec![Instruction::Let(apply(
+            Opcode::Add,
+            [apply(Opcode::CallValue, []), constant(0)],
+        ))])
+    }
+
+    #[test]
+    fn optimize_runs_every_pass_until_nothing_changes() {
+        let mut program = foldable();
+        let mut log = String::new();
+        optimize(&mut program, &[], true, Some(&mut log)).unwrap();
+        assert_eq!(
+            program.to_string(),
+            text(&["function @0 \"f\" 0 -> 0", "b0 [0]:", "    stop()"])
+        );
+        /* `x + 0` BECOMES `x`, THEN THE UNUSED VARIABLE GOES, AND THE SECOND ROUND CHANGES
+        NOTHING */
+        assert!(log.starts_with("/* constant-folding, round 1: "));
+        assert!(log.contains("/* dead-code, round 1: "));
+        assert_eq!(log.matches("/* after */").count(), 2);
+    }
+
+    #[test]
+    fn optimize_leaves_out_disabled_passes() {
+        let mut program = foldable();
+        optimize(&mut program, &[Pass::DeadCode], true, None).unwrap();
+        assert_eq!(
+            program.to_string(),
+            text(&[
+                "function @0 \"f\" 0 -> 0",
+                "b0 [0]:",
+                "    let $1 := callvalue()",
+                "    stop()",
+            ])
+        );
+        let mut program = foldable();
+        let mut log = String::new();
+        optimize(
+            &mut program,
+            &[Pass::ConstantFolding, Pass::DeadCode],
+            true,
+            Some(&mut log),
+        )
+        .unwrap();
+        assert_eq!(program.to_string(), foldable().to_string());
+        assert!(log.is_empty());
+    }
+}
@@ -1 +1,1979 @@
+use std::panic::{self, AssertUnwindSafe};
 
+use num_bigint::BigInt;
+
+use crate::mods::types::{
+    ast::{
+        BinaryOperator, Block, CallArguments, ContractDefinition, ContractKind, ContractPart,
+        CronDefinition, ElementaryType, EnumDefinition, ErrorDefinition, EventDefinition,
+        Expression, ExpressionKind, FunctionDefinition, FunctionKind, Identifier, ImportAlias,
+        ImportDirective, ImportSymbols, InheritanceSpecifier, Literal, ModifierDefinition,
+        ModifierInvocation, NamedArgument, Parameter, SourceUnit, StateMutability, Statement,
+        StatementKind, StorageLocation, StructDefinition, StructField, TypeName, TypeNameKind,
+        UnaryOperator, UsingDirective, VariableDeclaration, VariableDefinition, VariableMutability,
+        Visibility, YulBlock, YulExpression, YulExpressionKind, YulFunction, YulStatement,
+        YulStatementKind,
+    },
+    compiler_errors::{CompilerError, SyntaxError},
+    diagnostics::{Diagnostic, Diagnostics},
+    line_descriptors::{LineDescriptions, Span},
+    token::{Token, TokenTrait},
+};
+
+/* BUILDS THE SOURCE UNIT FROM THE GROUPS PRODUCED BY `seperate_variants`. A GROUP THAT FAILS TO
+PARSE IS LEFT OUT AND ITS ERRORS ARE RECORDED IN `diagnostics` */
+pub fn parse_source_unit(
+    imports: &[Vec<LineDescriptions<Token>>],
+    interfaces: &[Vec<LineDescriptions<Token>>],
+    contracts: &[Vec<LineDescriptions<Token>>],
+    libraries: &[Vec<LineDescriptions<Token>>],
+    custom_errors: &[Vec<LineDescriptions<Token>>],
+    definitions: &[Vec<LineDescriptions<Token>>],
+    diagnostics: &mut Diagnostics,
+) -> SourceUnit {
+    let mut source_unit = SourceUnit::default();
+
+    for import in imports {
+        let mut parser = Parser::new(import, diagnostics);
+        if let Some(_import) = parser.recover(|parser| {
+            let import = parser.parse_import();
+            parser.expect_end();
+            import
+        }) {
+            source_unit.imports.push(_import);
+        }
+    }
+
+    for custom_error in custom_errors {
+        let mut parser = Parser::new(custom_error, diagnostics);
+        if let Some(_error) = parser.recover(|parser| {
+            let error = parser.parse_error_definition();
+            parser.expect_end();
+            error
+        }) {
+            source_unit.custom_errors.push(_error);
+        }
+    }
+
+    for group in interfaces.iter().chain(contracts).chain(libraries) {
+        let mut parser = Parser::new(group, diagnostics);
+        if let Some(_contract) = parser.recover(|parser| {
+            let contract = parser.parse_contract();
+            parser.expect_end();
+            contract
+        }) {
+            source_unit.contracts.push(_contract);
+        }
+    }
+    source_unit
+        .contracts
+        .sort_by_key(|contract| contract.span.start);
+
+    let mut parts = Vec::new();
+    for definition in definitions {
+        let mut parser = Parser::new(definition, diagnostics);
+        if let Some(_part) = parser.recover(|parser| {
+            let part = parser.parse_definition();
+            parser.expect_end();
+            part
+        }) {
+            parts.push(_part);
+        }
+    }
+    let first = definitions.first().and_then(|group| group.first());
+    let last = definitions.last().and_then(|group| group.last());
+    if let (Some(_first), Some(_last), false) = (first, last, parts.is_empty()) {
+        source_unit.contracts.push(ContractDefinition {
+            span: _first.span.to(&_last.span),
+            kind: ContractKind::File,
+            upgradable: false,
+            name: Identifier {
+                span: _first.span,
+                name: String::new(),
+            },
+            bases: Vec::new(),
+            parts,
+        });
+    }
+
+    source_unit
+}
+
+/* WORDS OF INLINE ASSEMBLY THAT CANNOT NAME A VARIABLE OR A FUNCTION */
+const YUL_KEYWORDS: [&str; 12] = [
+    "let", "if", "switch", "case", "default", "for", "break", "continue", "leave", "function",
+    "true", "false",
+];
+
+/* UNWIND PAYLOAD USED TO ABANDON A CONSTRUCT ONCE ITS ERROR HAS BEEN RECORDED */
+struct ParseAbort;
+
+struct Parser<'a> {
+    tokens: Vec<LineDescriptions<Token>>,
+    position: usize,
+    diagnostics: &'a mut Diagnostics,
+}
+
+impl<'a> Parser<'a> {
+    fn new(group: &[LineDescriptions<Token>], diagnostics: &'a mut Diagnostics) -> Self {
+        let tokens = group
+            .iter()
+            .filter(|token| token.data != Token::Space)
+            .cloned()
+            .collect();
+
+        Self {
+            tokens,
+            position: 0,
+            diagnostics,
+        }
+    }
+
+    /* RUNS `parse`, RETURNING None IF IT REPORTED A SYNTAX ERROR */
+    fn recover<T>(&mut self, parse: impl FnOnce(&mut Self) -> T) -> Option<T> {
+        /* `resume_unwind` SKIPS THE PANIC HOOK SO NOTHING IS PRINTED FOR A ParseAbort */
+        match panic::catch_unwind(AssertUnwindSafe(|| parse(self))) {
+            Ok(value) => Some(value),
+            Err(payload) if payload.is::<ParseAbort>() => None,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    /* SKIPS THE REST OF A BROKEN CONTRACT PART: UP TO A `;` OR A BALANCED `{ }` BLOCK */
+    fn synchronize(&mut self, start: usize) {
+        self.position = start;
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::OpenBraces => depth += 1,
+                Token::CloseBraces if depth == 0 => break,
+                Token::CloseBraces => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.position += 1;
+                        break;
+                    }
+                }
+                Token::SemiColon if depth == 0 => {
+                    self.position += 1;
+                    break;
+                }
+                _ => {}
+            }
+            self.position += 1;
+        }
+        if self.position == start {
+            self.position += 1;
+        }
+    }
+
+    /* ------------------------------ CURSOR ------------------------------ */
+
+    fn peek(&self) -> Option<&Token> {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.position + n).map(|token| &token.data)
+    }
+
+    fn check(&self, token: &Token) -> bool {
+        self.peek() == Some(token)
+    }
+
+    /* SPAN OF THE CURRENT TOKEN, OR AN EMPTY SPAN RIGHT AFTER THE LAST ONE */
+    fn start(&self) -> Span {
+        match self.tokens.get(self.position) {
+            Some(token) => token.span,
+            None => self
+                .tokens
+                .last()
+                .map(|token| Span {
+                    start: token.span.end,
+                    ..token.span
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /* SPAN FROM `start` UP TO THE END OF THE LAST CONSUMED TOKEN */
+    fn span_from(&self, start: Span) -> Span {
+        match self
+            .position
+            .checked_sub(1)
+            .and_then(|index| self.tokens.get(index))
+        {
+            Some(previous) => start.to(&previous.span),
+            None => start,
+        }
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.peek().cloned();
+        match token {
+            Some(_token) => {
+                self.position += 1;
+                _token
+            }
+            None => self.unexpected(),
+        }
+    }
+
+    fn advance_by(&mut self, count: usize) {
+        self.position += count;
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.check(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token) {
+        if !self.eat(token) {
+            if self.peek().is_none() {
+                let error =
+                    CompilerError::SyntaxError(SyntaxError::MissingToken(token.to_string()));
+                self.abort(error.at(self.start()));
+            }
+            self.unexpected();
+        }
+    }
+
+    fn expect_end(&mut self) {
+        if self.peek().is_some() {
+            self.unexpected();
+        }
+    }
+
+    /* RECORDS `diagnostic` AND ABANDONS THE CONSTRUCT BEING PARSED */
+    fn abort(&mut self, diagnostic: Diagnostic) -> ! {
+        self.diagnostics.push(diagnostic);
+        panic::resume_unwind(Box::new(ParseAbort))
+    }
+
+    fn unexpected(&mut self) -> ! {
+        let diagnostic = match self.tokens.get(self.position) {
+            Some(token) => {
+                CompilerError::SyntaxError(SyntaxError::UnexpectedToken(token.data.to_string()))
+                    .at(token.span)
+            }
+            None => CompilerError::SyntaxError(SyntaxError::SyntaxError(
+                "unexpected end of input".to_string(),
+            ))
+            .at(self.start()),
+        };
+        self.abort(diagnostic)
+    }
+
+    fn error(&mut self, message: &str) -> ! {
+        self.fail(SyntaxError::SyntaxError(message.to_string()))
+    }
+
+    fn fail(&mut self, error: SyntaxError) -> ! {
+        let diagnostic = CompilerError::SyntaxError(error).at(self.start());
+        self.abort(diagnostic)
+    }
+
+    /* ---------------------------- IDENTIFIERS ---------------------------- */
+
+    fn identifier_name(token: &Token) -> Option<String> {
+        match token {
+            Token::Identifier(name) if is_identifier(name) => Some(name.to_string()),
+            Token::From
+            | Token::Solidity
+            | Token::Call
+            | Token::Delegatecall
+            | Token::Push
+            | Token::Pop => Some(token.to_string()),
+            _ => None,
+        }
+    }
+
+    fn check_identifier(&self, n: usize) -> bool {
+        self.peek_nth(n).and_then(Self::identifier_name).is_some()
+    }
+
+    fn expect_identifier(&mut self) -> Identifier {
+        let start = self.start();
+        match self.peek().and_then(Self::identifier_name) {
+            Some(name) => {
+                self.advance();
+                Identifier { span: start, name }
+            }
+            None => self.unexpected(),
+        }
+    }
+
+    /* MEMBER NAMES MAY BE KEYWORDS, E.G `arr.push` OR `addr.call` */
+    fn expect_member_name(&mut self) -> Identifier {
+        let start = self.start();
+        if let Some(name) = self.peek().and_then(Self::identifier_name) {
+            self.advance();
+            return Identifier { span: start, name };
+        }
+        let name = match self.peek() {
+            Some(Token::Identifier(_)) | None => self.unexpected(),
+            Some(token) => token.to_string(),
+        };
+        if !is_identifier(&name) {
+            self.unexpected();
+        }
+        self.advance();
+        Identifier { span: start, name }
+    }
+
+    fn parse_path(&mut self) -> Vec<Identifier> {
+        let mut path = vec![self.expect_identifier()];
+        while self.check(&Token::Dot) && self.check_identifier(1) {
+            self.advance();
+            path.push(self.expect_identifier());
+        }
+        path
+    }
+
+    fn expect_string_literal(&mut self) -> String {
+        match self.peek() {
+            Some(Token::StringLiteral(value)) => {
+                let value = value.to_string();
+                self.advance();
+                value
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    /* ---------------------------- SOURCE UNIT ---------------------------- */
+
+    fn parse_import(&mut self) -> ImportDirective {
+        let start = self.start();
+        self.expect(&Token::Import);
+        let symbols;
+        let path;
+        match self.peek() {
+            Some(Token::OpenBraces) => {
+                self.advance();
+                let mut aliases = Vec::new();
+                loop {
+                    let name = self.expect_identifier();
+                    let alias = if self.eat_as() {
+                        Some(self.expect_identifier())
+                    } else {
+                        None
+                    };
+                    aliases.push(ImportAlias { name, alias });
+                    if !self.eat(&Token::Coma) {
+                        break;
+                    }
+                }
+                self.expect(&Token::CloseBraces);
+                self.expect(&Token::From);
+                path = self.expect_string_literal();
+                symbols = ImportSymbols::Named(aliases);
+            }
+            Some(Token::Multiply) => {
+                self.advance();
+                if !self.eat_as() {
+                    self.unexpected();
+                }
+                let alias = self.expect_identifier();
+                self.expect(&Token::From);
+                path = self.expect_string_literal();
+                symbols = ImportSymbols::Glob(alias);
+            }
+            _ => {
+                path = self.expect_string_literal();
+                let alias = if self.eat_as() {
+                    Some(self.expect_identifier())
+                } else {
+                    None
+                };
+                symbols = ImportSymbols::All(alias);
+            }
+        }
+        self.expect(&Token::SemiColon);
+
+        ImportDirective {
+            span: self.span_from(start),
+            path,
+            symbols,
+        }
+    }
+
+    fn eat_as(&mut self) -> bool {
+        self.eat(&Token::Identifier("as".to_string()))
+    }
+
+    fn parse_contract(&mut self) -> ContractDefinition {
+        let start = self.start();
+        let upgradable = self.eat(&Token::Upgradable);
+        let kind = match self.advance() {
+            Token::Abstract if !upgradable && self.check(&Token::Upgradable) => {
+                self.position -= 1;
+                let diagnostic = CompilerError::SyntaxError(SyntaxError::InvalidUpgradable(
+                    "`upgradable` must come before `abstract`".to_string(),
+                ))
+                .at(self.start())
+                .with_help("write `upgradable abstract contract`");
+                self.abort(diagnostic)
+            }
+            Token::Abstract => {
+                self.expect(&Token::Contract);
+                ContractKind::AbstractContract
+            }
+            Token::Contract => ContractKind::Contract,
+            Token::Interface => ContractKind::Interface,
+            Token::Library => ContractKind::Library,
+            _ => {
+                self.position -= 1;
+                self.unexpected()
+            }
+        };
+        if upgradable && matches!(kind, ContractKind::Interface | ContractKind::Library) {
+            self.position -= 1;
+            self.fail(SyntaxError::InvalidUpgradable(
+                "only contracts can be `upgradable`".to_string(),
+            ));
+        }
+        let name = self.expect_identifier();
+
+        let mut bases = Vec::new();
+        if self.eat(&Token::Is) {
+            loop {
+                let start = self.start();
+                let name = self.parse_path();
+                let arguments = if self.eat(&Token::OpenParenthesis) {
+                    Some(self.parse_expression_list(&Token::CloseParenthesis))
+                } else {
+                    None
+                };
+                bases.push(InheritanceSpecifier {
+                    span: self.span_from(start),
+                    name,
+                    arguments,
+                });
+                if !self.eat(&Token::Coma) {
+                    break;
+                }
+            }
+        }
+
+        self.expect(&Token::OpenBraces);
+        let mut parts = Vec::new();
+        while !self.eat(&Token::CloseBraces) {
+            if self.peek().is_none() {
+                self.expect(&Token::CloseBraces);
+            }
+            let part_start = self.position;
+            match self.recover(|parser| parser.parse_contract_part()) {
+                Some(part) => parts.push(part),
+                None => self.synchronize(part_start),
+            }
+        }
+
+        ContractDefinition {
+            span: self.span_from(start),
+            kind,
+            upgradable,
+            name,
+            bases,
+            parts,
+        }
+    }
+
+    fn parse_contract_part(&mut self) -> ContractPart {
+        match self.peek() {
+            Some(Token::Function) => {
+                ContractPart::Function(self.parse_function(FunctionKind::Function))
+            }
+            Some(Token::Constructor) => {
+                ContractPart::Function(self.parse_function(FunctionKind::Constructor))
+            }
+            Some(Token::Receive) if self.peek_nth(1) == Some(&Token::OpenParenthesis) => {
+                ContractPart::Function(self.parse_function(FunctionKind::Receive))
+            }
+            Some(Token::Fallback) if self.peek_nth(1) == Some(&Token::OpenParenthesis) => {
+                ContractPart::Function(self.parse_function(FunctionKind::Fallback))
+            }
+            Some(Token::Modifier) => ContractPart::Modifier(self.parse_modifier()),
+            Some(Token::Event) => ContractPart::Event(self.parse_event()),
+            Some(Token::Error) => ContractPart::Error(self.parse_error_definition()),
+            Some(Token::Struct) => ContractPart::Struct(self.parse_struct()),
+            Some(Token::Enum) => ContractPart::Enum(self.parse_enum()),
+            Some(Token::Using) => ContractPart::Using(self.parse_using()),
+            Some(Token::Cron) if self.peek_nth(1) == Some(&Token::OpenParenthesis) => {
+                ContractPart::Cron(self.parse_cron())
+            }
+            _ => ContractPart::StateVariable(self.parse_state_variable()),
+        }
+    }
+
+    /* A STRUCT, ENUM, CONSTANT OR FUNCTION OUTSIDE ANY CONTRACT */
+    fn parse_definition(&mut self) -> ContractPart {
+        let start = self.start();
+        let part = match self.peek() {
+            Some(Token::Function) => {
+                ContractPart::Function(self.parse_function(FunctionKind::Function))
+            }
+            Some(Token::Struct) => ContractPart::Struct(self.parse_struct()),
+            Some(Token::Enum) => ContractPart::Enum(self.parse_enum()),
+            _ => ContractPart::StateVariable(self.parse_state_variable()),
+        };
+        let problem = match &part {
+            ContractPart::Function(function) if function.body.is_none() => {
+                Some("free functions must have a body")
+            }
+            ContractPart::Function(function) if function.visibility.is_some() => {
+                Some("free functions cannot have a visibility")
+            }
+            ContractPart::Function(function)
+                if function.is_virtual || function.overrides.is_some() =>
+            {
+                Some("free functions cannot be `virtual` or `override`")
+            }
+            ContractPart::Function(function) if !function.modifiers.is_empty() => {
+                Some("free functions cannot have modifiers")
+            }
+            ContractPart::Function(function)
+                if function.gasless || function.mutability == Some(StateMutability::Payable) =>
+            {
+                Some("free functions cannot be `payable` or `gasless`")
+            }
+            ContractPart::StateVariable(variable)
+                if variable.mutability != Some(VariableMutability::Constant)
+                    || variable.visibility.is_some()
+                    || variable.overrides.is_some() =>
+            {
+                Some("only constants without a visibility can be declared at file level")
+            }
+            _ => None,
+        };
+        if let Some(_problem) = problem {
+            let diagnostic =
+                CompilerError::SyntaxError(SyntaxError::SyntaxError(_problem.to_string()))
+                    .at(start);
+            self.abort(diagnostic);
+        }
+        part
+    }
+
+    fn parse_state_variable(&mut self) -> VariableDefinition {
+        let start = self.start();
+        let ty = self.parse_type_name();
+        let mut visibility = None;
+        let mut mutability = None;
+        let mut overrides = None;
+        loop {
+            match self.peek() {
+                Some(Token::Public | Token::Private | Token::Internal | Token::External) => {
+                    if visibility.is_some() {
+                        self.error("Visibility already specified");
+                    }
+                    visibility = self.parse_visibility();
+                }
+                Some(Token::Constant | Token::Immutable | Token::Mutable) => {
+                    if mutability.is_some() {
+                        self.error("Mutability already specified");
+                    }
+                    mutability = Some(match self.advance() {
+                        Token::Constant => VariableMutability::Constant,
+                        Token::Immutable => VariableMutability::Immutable,
+                        _ => VariableMutability::Mutable,
+                    });
+                }
+                Some(Token::Override) => overrides = Some(self.parse_override_specifier()),
+                _ => break,
+            }
+        }
+        let name = self.expect_identifier();
+        let initializer = if self.eat(&Token::Equals) {
+            Some(self.parse_expression())
+        } else {
+            None
+        };
+        self.expect(&Token::SemiColon);
+
+        VariableDefinition {
+            span: self.span_from(start),
+            ty,
+            visibility,
+            mutability,
+            overrides,
+            name,
+            initializer,
+        }
+    }
+
+    fn parse_visibility(&mut self) -> Option<Visibility> {
+        match self.advance() {
+            Token::Public => Some(Visibility::Public),
+            Token::Private => Some(Visibility::Private),
+            Token::Internal => Some(Visibility::Internal),
+            Token::External => Some(Visibility::External),
+            _ => None,
+        }
+    }
+
+    fn parse_override_specifier(&mut self) -> Vec<Vec<Identifier>> {
+        self.expect(&Token::Override);
+        let mut overrides = Vec::new();
+        if self.eat(&Token::OpenParenthesis) {
+            loop {
+                overrides.push(self.parse_path());
+                if !self.eat(&Token::Coma) {
+                    break;
+                }
+            }
+            self.expect(&Token::CloseParenthesis);
+        }
+        overrides
+    }
+
+    fn parse_function(&mut self, kind: FunctionKind) -> FunctionDefinition {
+        let start = self.start();
+        self.advance();
+        let name = match kind {
+            FunctionKind::Function => Some(self.expect_identifier()),
+            _ => None,
+        };
+        let parameters = self.parse_parameter_list();
+
+        let mut function = FunctionDefinition {
+            span: self.span_from(start),
+            kind,
+            name,
+            parameters,
+            visibility: None,
+            mutability: None,
+            is_virtual: false,
+            overrides: None,
+            modifiers: Vec::new(),
+            gasless: false,
+            returns: Vec::new(),
+            body: None,
+        };
+
+        let mut has_returns = false;
+        loop {
+            match self.peek() {
+                Some(Token::Public | Token::Private | Token::Internal | Token::External) => {
+                    if function.visibility.is_some() {
+                        self.error("Visibility already specified");
+                    }
+                    function.visibility = self.parse_visibility();
+                }
+                Some(Token::Pure | Token::View | Token::Payable) => {
+                    if function.mutability.is_some() {
+                        self.error("State mutability already specified");
+                    }
+                    function.mutability = Some(match self.advance() {
+                        Token::Pure => StateMutability::Pure,
+                        Token::View => StateMutability::View,
+                        _ => StateMutability::Payable,
+                    });
+                }
+                Some(Token::Virtual) => {
+                    self.advance();
+                    function.is_virtual = true;
+                }
+                Some(Token::Override) => function.overrides = Some(self.parse_override_specifier()),
+                Some(Token::Gasless) => {
+                    if has_returns {
+                        let diagnostic = CompilerError::SyntaxError(SyntaxError::InvalidGasless(
+                            "`gasless` must come before `returns`".to_string(),
+                        ))
+                        .at(self.start())
+                        .with_help("move `gasless` in front of the `returns` list");
+                        self.abort(diagnostic);
+                    }
+                    if function.gasless {
+                        self.fail(SyntaxError::InvalidGasless(
+                            "`gasless` already specified".to_string(),
+                        ));
+                    }
+                    self.advance();
+                    function.gasless = true;
+                }
+                Some(Token::Returns) => {
+                    self.advance();
+                    has_returns = true;
+                    function.returns = self.parse_parameter_list();
+                }
+                Some(Token::SemiColon) => {
+                    self.advance();
+                    break;
+                }
+                Some(Token::OpenBraces) => {
+                    function.body = Some(self.parse_block());
+                    break;
+                }
+                _ if self.check_identifier(0) => {
+                    function.modifiers.push(self.parse_modifier_invocation())
+                }
+                _ => self.unexpected(),
+            }
+        }
+
+        function
+    }
+
+    fn parse_modifier_invocation(&mut self) -> ModifierInvocation {
+        let start = self.start();
+        let name = self.parse_path();
+        let arguments = if self.eat(&Token::OpenParenthesis) {
+            Some(self.parse_expression_list(&Token::CloseParenthesis))
+        } else {
+            None
+        };
+        ModifierInvocation {
+            span: self.span_from(start),
+            name,
+            arguments,
+        }
+    }
+
+    fn parse_modifier(&mut self) -> ModifierDefinition {
+        let start = self.start();
+        self.expect(&Token::Modifier);
+        let name = self.expect_identifier();
+        let parameters = if self.check(&Token::OpenParenthesis) {
+            self.parse_parameter_list()
+        } else {
+            Vec::new()
+        };
+        let mut is_virtual = false;
+        let mut overrides = None;
+        let body;
+        loop {
+            match self.peek() {
+                Some(Token::Virtual) => {
+                    self.advance();
+                    is_virtual = true;
+                }
+                Some(Token::Override) => overrides = Some(self.parse_override_specifier()),
+                Some(Token::SemiColon) => {
+                    self.advance();
+                    body = None;
+                    break;
+                }
+                Some(Token::OpenBraces) => {
+                    body = Some(self.parse_block());
+                    break;
+                }
+                _ => self.unexpected(),
+            }
+        }
+
+        ModifierDefinition {
+            span: self.span_from(start),
+            name,
+            parameters,
+            is_virtual,
+            overrides,
+            body,
+        }
+    }
+
+    fn parse_event(&mut self) -> EventDefinition {
+        let start = self.start();
+        self.expect(&Token::Event);
+        let name = self.expect_identifier();
+        let parameters = self.parse_parameter_list();
+        let anonymous = self.eat(&Token::Identifier("anonymous".to_string()));
+        self.expect(&Token::SemiColon);
+        EventDefinition {
+            span: self.span_from(start),
+            name,
+            parameters,
+            anonymous,
+        }
+    }
+
+    fn parse_error_definition(&mut self) -> ErrorDefinition {
+        let start = self.start();
+        self.expect(&Token::Error);
+        let name = self.expect_identifier();
+        let parameters = self.parse_parameter_list();
+        self.expect(&Token::SemiColon);
+        ErrorDefinition {
+            span: self.span_from(start),
+            name,
+            parameters,
+        }
+    }
+
+    fn parse_struct(&mut self) -> StructDefinition {
+        let start = self.start();
+        self.expect(&Token::Struct);
+        let name = self.expect_identifier();
+        self.expect(&Token::OpenBraces);
+        let mut fields = Vec::new();
+        while !self.eat(&Token::CloseBraces) {
+            let start = self.start();
+            let ty = self.parse_type_name();
+            let name = self.expect_identifier();
+            self.expect(&Token::SemiColon);
+            fields.push(StructField {
+                span: self.span_from(start),
+                ty,
+                name,
+            });
+        }
+        StructDefinition {
+            span: self.span_from(start),
+            name,
+            fields,
+        }
+    }
+
+    fn parse_enum(&mut self) -> EnumDefinition {
+        let start = self.start();
+        self.expect(&Token::Enum);
+        let name = self.expect_identifier();
+        self.expect(&Token::OpenBraces);
+        let mut values = Vec::new();
+        if !self.check(&Token::CloseBraces) {
+            loop {
+                values.push(self.expect_identifier());
+                if !self.eat(&Token::Coma) {
+                    break;
+                }
+            }
+        }
+        self.expect(&Token::CloseBraces);
+        EnumDefinition {
+            span: self.span_from(start),
+            name,
+            values,
+        }
+    }
+
+    fn parse_using(&mut self) -> UsingDirective {
+        let start = self.start();
+        self.expect(&Token::Using);
+        let library = self.parse_path();
+        self.expect(&Token::For);
+        let target = if self.eat(&Token::Multiply) {
+            None
+        } else {
+            Some(self.parse_type_name())
+        };
+        self.eat(&Token::Identifier("global".to_string()));
+        self.expect(&Token::SemiColon);
+        UsingDirective {
+            span: self.span_from(start),
+            library,
+            target,
+        }
+    }
+
+    fn parse_cron(&mut self) -> CronDefinition {
+        let start = self.start();
+        self.expect(&Token::Cron);
+        self.expect(&Token::OpenParenthesis);
+        if self.check(&Token::CloseParenthesis) {
+            self.fail(SyntaxError::InvalidCron(
+                "expected a cron schedule".to_string(),
+            ));
+        }
+        let schedule = self.parse_expression();
+        let max_runs = if self.eat(&Token::Coma) {
+            Some(self.parse_expression())
+        } else {
+            None
+        };
+        self.expect(&Token::CloseParenthesis);
+        let body = self.parse_block();
+        CronDefinition {
+            span: self.span_from(start),
+            schedule,
+            max_runs,
+            body,
+        }
+    }
+
+    fn parse_parameter_list(&mut self) -> Vec<Parameter> {
+        self.expect(&Token::OpenParenthesis);
+        let mut parameters = Vec::new();
+        if self.eat(&Token::CloseParenthesis) {
+            return parameters;
+        }
+        loop {
+            let start = self.start();
+            let ty = self.parse_type_name();
+            let mut storage = None;
+            let mut indexed = false;
+            loop {
+                match self.peek() {
+                    Some(Token::Memory | Token::Storage | Token::Calldata) => {
+                        if storage.is_some() {
+                            self.error("Data location already specified");
+                        }
+                        storage = self.parse_storage_location();
+                    }
+                    Some(Token::Indexed) => {
+                        self.advance();
+                        indexed = true;
+                    }
+                    _ => break,
+                }
+            }
+            let name = if self.check_identifier(0) {
+                Some(self.expect_identifier())
+            } else {
+                None
+            };
+            parameters.push(Parameter {
+                span: self.span_from(start),
+                ty,
+                storage,
+                indexed,
+                name,
+            });
+            if !self.eat(&Token::Coma) {
+                break;
+            }
+        }
+        self.expect(&Token::CloseParenthesis);
+        parameters
+    }
+
+    fn parse_storage_location(&mut self) -> Option<StorageLocation> {
+        match self.peek() {
+            Some(Token::Memory) => {
+                self.advance();
+                Some(StorageLocation::Memory)
+            }
+            Some(Token::Storage) => {
+                self.advance();
+                Some(StorageLocation::Storage)
+            }
+            Some(Token::Calldata) => {
+                self.advance();
+                Some(StorageLocation::Calldata)
+            }
+            _ => None,
+        }
+    }
+
+    /* ------------------------------- TYPES ------------------------------- */
+
+    fn parse_type_name(&mut self) -> TypeName {
+        let start = self.start();
+        let kind = match self.peek() {
+            Some(Token::Mapping) => self.parse_mapping(),
+            Some(Token::Function) => self.parse_function_type(),
+            Some(_token) if elementary_type(_token).is_some() => {
+                TypeNameKind::Elementary(self.parse_elementary_type())
+            }
+            _ => TypeNameKind::UserDefined(self.parse_path()),
+        };
+        let mut ty = TypeName {
+            span: self.span_from(start),
+            kind,
+        };
+
+        while self.eat(&Token::OpenSquareBracket) {
+            let length = if self.check(&Token::CloseSquareBracket) {
+                None
+            } else {
+                Some(Box::new(self.parse_expression()))
+            };
+            self.expect(&Token::CloseSquareBracket);
+            ty = TypeName {
+                span: self.span_from(start),
+                kind: TypeNameKind::Array {
+                    base: Box::new(ty),
+                    length,
+                },
+            };
+        }
+
+        ty
+    }
+
+    fn parse_elementary_type(&mut self) -> ElementaryType {
+        let token = self.advance();
+        if let Token::Bytes(Some(size)) = token {
+            if size == 0 || size > 32 {
+                self.position -= 1;
+                self.error(&format!("Invalid fixed bytes size {}", size));
+            }
+        }
+        match elementary_type(&token) {
+            Some(ElementaryType::Address) if self.eat(&Token::Payable) => {
+                ElementaryType::AddressPayable
+            }
+            Some(ty) => ty,
+            None => {
+                self.position -= 1;
+                self.unexpected()
+            }
+        }
+    }
+
+    fn parse_mapping(&mut self) -> TypeNameKind {
+        self.expect(&Token::Mapping);
+        self.expect(&Token::OpenParenthesis);
+        let key = self.parse_type_name();
+        let key_name = if self.check_identifier(0) {
+            Some(self.expect_identifier())
+        } else {
+            None
+        };
+        self.expect(&Token::Arrow);
+        let value = self.parse_type_name();
+        let value_name = if self.check_identifier(0) {
+            Some(self.expect_identifier())
+        } else {
+            None
+        };
+        self.expect(&Token::CloseParenthesis);
+        TypeNameKind::Mapping {
+            key: Box::new(key),
+            key_name,
+            value: Box::new(value),
+            value_name,
+        }
+    }
+
+    fn parse_function_type(&mut self) -> TypeNameKind {
+        self.expect(&Token::Function);
+        let parameters = self.parse_parameter_list();
+        let mut visibility = None;
+        let mut mutability = None;
+        let mut returns = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Internal | Token::External) => visibility = self.parse_visibility(),
+                Some(Token::Pure) => {
+                    self.advance();
+                    mutability = Some(StateMutability::Pure);
+                }
+                Some(Token::View) => {
+                    self.advance();
+                    mutability = Some(StateMutability::View);
+                }
+                Some(Token::Payable) => {
+                    self.advance();
+                    mutability = Some(StateMutability::Payable);
+                }
+                Some(Token::Returns) => {
+                    self.advance();
+                    returns = self.parse_parameter_list();
+                }
+                _ => break,
+            }
+        }
+        TypeNameKind::Function {
+            parameters,
+            visibility,
+            mutability,
+            returns,
+        }
+    }
+
+    /* ----------------------------- STATEMENTS ----------------------------- */
+
+    fn parse_block(&mut self) -> Block {
+        let start = self.start();
+        self.expect(&Token::OpenBraces);
+        let mut statements = Vec::new();
+        while !self.eat(&Token::CloseBraces) {
+            statements.push(self.parse_statement());
+        }
+        Block {
+            span: self.span_from(start),
+            unchecked: false,
+            statements,
+        }
+    }
+
+    fn parse_statement(&mut self) -> Statement {
+        let start = self.start();
+        let kind = match self.peek() {
+            Some(Token::OpenBraces) => StatementKind::Block(self.parse_block()),
+            Some(Token::If) => {
+                self.advance();
+                self.expect(&Token::OpenParenthesis);
+                let condition = self.parse_expression();
+                self.expect(&Token::CloseParenthesis);
+                let then = Box::new(self.parse_statement());
+                let otherwise = if self.eat(&Token::Else) {
+                    Some(Box::new(self.parse_statement()))
+                } else {
+                    None
+                };
+                StatementKind::If {
+                    condition,
+                    then,
+                    otherwise,
+                }
+            }
+            Some(Token::For) => {
+                self.advance();
+                self.expect(&Token::OpenParenthesis);
+                let init = if self.eat(&Token::SemiColon) {
+                    None
+                } else {
+                    Some(Box::new(self.parse_simple_statement()))
+                };
+                let condition = if self.check(&Token::SemiColon) {
+                    None
+                } else {
+                    Some(self.parse_expression())
+                };
+                self.expect(&Token::SemiColon);
+                let update = if self.check(&Token::CloseParenthesis) {
+                    None
+                } else {
+                    Some(self.parse_expression())
+                };
+                self.expect(&Token::CloseParenthesis);
+                let body = Box::new(self.parse_statement());
+                StatementKind::For {
+                    init,
+                    condition,
+                    update,
+                    body,
+                }
+            }
+            Some(Token::While) => {
+                self.advance();
+                self.expect(&Token::OpenParenthesis);
+                let condition = self.parse_expression();
+                self.expect(&Token::CloseParenthesis);
+                let body = Box::new(self.parse_statement());
+                StatementKind::While { condition, body }
+            }
+            Some(Token::Return) => {
+                self.advance();
+                let value = if self.check(&Token::SemiColon) {
+                    None
+                } else {
+                    Some(self.parse_expression())
+                };
+                self.expect(&Token::SemiColon);
+                StatementKind::Return(value)
+            }
+            Some(Token::Emit) => {
+                self.advance();
+                let event = self.parse_expression();
+                self.expect(&Token::SemiColon);
+                StatementKind::Emit(event)
+            }
+            Some(Token::Revert) => {
+                self.advance();
+                let error = if self.check(&Token::OpenParenthesis) {
+                    None
+                } else {
+                    Some(self.parse_path())
+                };
+                self.expect(&Token::OpenParenthesis);
+                let arguments = self.parse_call_arguments();
+                self.expect(&Token::SemiColon);
+                StatementKind::Revert { error, arguments }
+            }
+            Some(Token::Assembly) => self.parse_assembly(),
+            Some(Token::Identifier(word)) => match word.as_str() {
+                "do" => {
+                    self.advance();
+                    let body = Box::new(self.parse_statement());
+                    self.expect(&Token::While);
+                    self.expect(&Token::OpenParenthesis);
+                    let condition = self.parse_expression();
+                    self.expect(&Token::CloseParenthesis);
+                    self.expect(&Token::SemiColon);
+                    StatementKind::DoWhile { body, condition }
+                }
+                "break" | "continue" | "_" if self.peek_nth(1) == Some(&Token::SemiColon) => {
+                    let kind = match word.as_str() {
+                        "break" => StatementKind::Break,
+                        "continue" => StatementKind::Continue,
+                        _ => StatementKind::Placeholder,
+                    };
+                    self.advance_by(2);
+                    kind
+                }
+                "unchecked" if self.peek_nth(1) == Some(&Token::OpenBraces) => {
+                    self.advance();
+                    let mut block = self.parse_block();
+                    block.unchecked = true;
+                    StatementKind::Block(block)
+                }
+                _ => return self.parse_simple_statement(),
+            },
+            _ => return self.parse_simple_statement(),
+        };
+        Statement {
+            span: self.span_from(start),
+            kind,
+        }
+    }
+
+    /* A VARIABLE DECLARATION OR AN EXPRESSION, TERMINATED BY `;` */
+    fn parse_simple_statement(&mut self) -> Statement {
+        let start = self.start();
+        let kind = if self.is_variable_declaration() {
+            let declarations = if self.check(&Token::OpenParenthesis) {
+                self.advance();
+                let mut declarations = Vec::new();
+                loop {
+                    if self.check(&Token::Coma) || self.check(&Token::CloseParenthesis) {
+                        declarations.push(None);
+                    } else {
+                        declarations.push(Some(self.parse_variable_declaration()));
+                    }
+                    if !self.eat(&Token::Coma) {
+                        break;
+                    }
+                }
+                self.expect(&Token::CloseParenthesis);
+                declarations
+            } else {
+                vec![Some(self.parse_variable_declaration())]
+            };
+            let initializer = if self.eat(&Token::Equals) {
+                Some(self.parse_expression())
+            } else {
+                None
+            };
+            StatementKind::VariableDeclaration {
+                declarations,
+                initializer,
+            }
+        } else {
+            StatementKind::Expression(self.parse_expression())
+        };
+        self.expect(&Token::SemiColon);
+        Statement {
+            span: self.span_from(start),
+            kind,
+        }
+    }
+
+    fn parse_variable_declaration(&mut self) -> VariableDeclaration {
+        let start = self.start();
+        let ty = self.parse_type_name();
+        let storage = self.parse_storage_location();
+        let name = self.expect_identifier();
+        VariableDeclaration {
+            span: self.span_from(start),
+            ty,
+            storage,
+            name,
+        }
+    }
+
+    fn is_variable_declaration(&self) -> bool {
+        if self.check(&Token::OpenParenthesis) {
+            let mut offset = 1;
+            while self.peek_nth(offset) == Some(&Token::Coma) {
+                offset += 1;
+            }
+            return self.is_declaration_at(offset);
+        }
+        self.is_declaration_at(0)
+    }
+
+    /* LOOKS AHEAD FOR `Type [location] name` WITHOUT CONSUMING ANY TOKEN */
+    fn is_declaration_at(&self, offset: usize) -> bool {
+        let mut offset = offset;
+        match self.peek_nth(offset) {
+            Some(Token::Mapping | Token::Function) => return true,
+            Some(_token) if elementary_type(_token).is_some() => {
+                return !matches!(
+                    self.peek_nth(offset + 1),
+                    Some(Token::OpenParenthesis | Token::Dot)
+                );
+            }
+            _ if self.check_identifier(offset) => offset += 1,
+            _ => return false,
+        }
+
+        while self.peek_nth(offset) == Some(&Token::Dot) && self.check_identifier(offset + 1) {
+            offset += 2;
+        }
+        while self.peek_nth(offset) == Some(&Token::OpenSquareBracket) {
+            let mut depth = 0;
+            loop {
+                match self.peek_nth(offset) {
+                    Some(Token::OpenSquareBracket) => depth += 1,
+                    Some(Token::CloseSquareBracket) => depth -= 1,
+                    None => return false,
+                    _ => {}
+                }
+                offset += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+
+        matches!(
+            self.peek_nth(offset),
+            Some(Token::Memory | Token::Storage | Token::Calldata)
+        ) || self.check_identifier(offset)
+    }
+
+    /* --------------------------- INLINE ASSEMBLY --------------------------- */
+
+    /* `assembly "evmasm" ("memory-safe") { ... }`, THE DIALECT AND THE FLAGS ARE OPTIONAL */
+    fn parse_assembly(&mut self) -> StatementKind {
+        self.expect(&Token::Assembly);
+        if let Some(Token::StringLiteral(dialect)) = self.peek() {
+            if dialect != "evmasm" {
+                self.fail(SyntaxError::InvalidAssembly(
+                    "the only inline assembly dialect is \"evmasm\"".to_string(),
+                ));
+            }
+            self.advance();
+        }
+        let mut flags = Vec::new();
+        if self.eat(&Token::OpenParenthesis) {
+            loop {
+                flags.push(self.expect_string_literal());
+                if !self.eat(&Token::Coma) {
+                    break;
+                }
+            }
+            self.expect(&Token::CloseParenthesis);
+        }
+        StatementKind::Assembly {
+            flags,
+            body: self.parse_yul_block(),
+        }
+    }
+
+    /* A YUL NAME MAY BE A SOLIDITY KEYWORD, SUCH AS THE BUILTINS `return` AND `address` */
+    fn yul_name(token: &Token) -> Option<String> {
+        let name = match token {
+            Token::Identifier(name) => name.to_string(),
+            token => token.to_string(),
+        };
+        (is_identifier(&name) && !YUL_KEYWORDS.contains(&name.as_str())).then_some(name)
+    }
+
+    fn expect_yul_name(&mut self) -> Identifier {
+        let start = self.start();
+        match self.peek().and_then(Self::yul_name) {
+            Some(name) => {
+                self.advance();
+                Identifier { span: start, name }
+            }
+            None => self.unexpected(),
+        }
+    }
+
+    fn check_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Identifier(_word)) if _word == word)
+    }
+
+    /* `:=` IS LEXED AS `:` AND `=` */
+    fn expect_yul_assign(&mut self) {
+        self.expect(&Token::Colon);
+        self.expect(&Token::Equals);
+    }
+
+    fn parse_yul_block(&mut self) -> YulBlock {
+        let start = self.start();
+        self.expect(&Token::OpenBraces);
+        let mut statements = Vec::new();
+        while !self.eat(&Token::CloseBraces) {
+            statements.push(self.parse_yul_statement());
+        }
+        YulBlock {
+            span: self.span_from(start),
+            statements,
+        }
+    }
+
+    fn parse_yul_statement(&mut self) -> YulStatement {
+        let start = self.start();
+        let kind = match self.peek() {
+            Some(Token::OpenBraces) => YulStatementKind::Block(self.parse_yul_block()),
+            Some(Token::If) => {
+                self.advance();
+                let condition = self.parse_yul_expression();
+                YulStatementKind::If {
+                    condition,
+                    body: self.parse_yul_block(),
+                }
+            }
+            Some(Token::For) => {
+                self.advance();
+                let init = self.parse_yul_block();
+                let condition = self.parse_yul_expression();
+                let post = self.parse_yul_block();
+                YulStatementKind::For {
+                    init,
+                    condition,
+                    post,
+                    body: self.parse_yul_block(),
+                }
+            }
+            Some(Token::Function) => YulStatementKind::Function(self.parse_yul_function()),
+            _ if self.check_word("let") => {
+                self.advance();
+                let mut names = vec![self.expect_yul_name()];
+                while self.eat(&Token::Coma) {
+                    names.push(self.expect_yul_name());
+                }
+                let value = match self.check(&Token::Colon) {
+                    true => {
+                        self.expect_yul_assign();
+                        Some(self.parse_yul_expression())
+                    }
+                    false => None,
+                };
+                YulStatementKind::VariableDeclaration { names, value }
+            }
+            _ if self.check_word("switch") => {
+                self.advance();
+                let expression = self.parse_yul_expression();
+                let mut cases = Vec::new();
+                while self.check_word("case") {
+                    self.advance();
+                    let value = self.parse_yul_literal();
+                    cases.push((value, self.parse_yul_block()));
+                }
+                let default = match self.check_word("default") {
+                    true => {
+                        self.advance();
+                        Some(self.parse_yul_block())
+                    }
+                    false => None,
+                };
+                if cases.is_empty() && default.is_none() {
+                    self.fail(SyntaxError::InvalidAssembly(
+                        "a `switch` needs a `case` or a `default`".to_string(),
+                    ));
+                }
+                YulStatementKind::Switch {
+                    expression,
+                    cases,
+                    default,
+                }
+            }
+            _ if self.check_word("break") => {
+                self.advance();
+                YulStatementKind::Break
+            }
+            _ if self.check_word("continue") => {
+                self.advance();
+                YulStatementKind::Continue
+            }
+            _ if self.check_word("leave") => {
+                self.advance();
+                YulStatementKind::Leave
+            }
+            _ if self.peek_nth(1) == Some(&Token::OpenParenthesis) => {
+                YulStatementKind::Expression(self.parse_yul_expression())
+            }
+            _ => {
+                let mut targets = vec![self.parse_yul_path()];
+                while self.eat(&Token::Coma) {
+                    targets.push(self.parse_yul_path());
+                }
+                self.expect_yul_assign();
+                YulStatementKind::Assignment {
+                    targets,
+                    value: self.parse_yul_expression(),
+                }
+            }
+        };
+        YulStatement {
+            span: self.span_from(start),
+            kind,
+        }
+    }
+
+    /* `function f(a, b) -> c { ... }`, `->` IS LEXED AS `-` AND `>` */
+    fn parse_yul_function(&mut self) -> YulFunction {
+        let start = self.start();
+        self.expect(&Token::Function);
+        let name = self.expect_yul_name();
+        self.expect(&Token::OpenParenthesis);
+        let mut parameters = Vec::new();
+        if !self.eat(&Token::CloseParenthesis) {
+            loop {
+                parameters.push(self.expect_yul_name());
+                if !self.eat(&Token::Coma) {
+                    break;
+                }
+            }
+            self.expect(&Token::CloseParenthesis);
+        }
+        let mut returns = Vec::new();
+        if self.eat(&Token::Minus) {
+            self.expect(&Token::Gt);
+            loop {
+                returns.push(self.expect_yul_name());
+                if !self.eat(&Token::Coma) {
+                    break;
+                }
+            }
+        }
+        let body = self.parse_yul_block();
+        YulFunction {
+            span: self.span_from(start),
+            name,
+            parameters,
+            returns,
+            body,
+        }
+    }
+
+    /* `x`, OR `x.slot` AND `x.offset` OF A SOLIDITY VARIABLE */
+    fn parse_yul_path(&mut self) -> Vec<Identifier> {
+        let mut path = vec![self.expect_yul_name()];
+        while self.eat(&Token::Dot) {
+            path.push(self.expect_yul_name());
+        }
+        path
+    }
+
+    fn parse_yul_expression(&mut self) -> YulExpression {
+        let start = self.start();
+        let kind = match self.peek() {
+            Some(
+                Token::NumberLiteral(..)
+                | Token::AddressLiteral(_)
+                | Token::StringLiteral(_)
+                | Token::HexStringLiteral(_)
+                | Token::True
+                | Token::False,
+            ) => return self.parse_yul_literal(),
+            _ if self.peek_nth(1) == Some(&Token::OpenParenthesis) => {
+                let function = self.expect_yul_name();
+                self.advance();
+                let mut arguments = Vec::new();
+                if !self.eat(&Token::CloseParenthesis) {
+                    loop {
+                        arguments.push(self.parse_yul_expression());
+                        if !self.eat(&Token::Coma) {
+                            break;
+                        }
+                    }
+                    self.expect(&Token::CloseParenthesis);
+                }
+                YulExpressionKind::Call {
+                    function,
+                    arguments,
+                }
+            }
+            _ => YulExpressionKind::Path(self.parse_yul_path()),
+        };
+        YulExpression {
+            span: self.span_from(start),
+            kind,
+        }
+    }
+
+    /* A 40 DIGIT HEX NUMBER IS LEXED AS AN ADDRESS, IN ASSEMBLY IT IS JUST A NUMBER */
+    fn parse_yul_literal(&mut self) -> YulExpression {
+        let start = self.start();
+        let literal = match self.peek().cloned() {
+            Some(Token::NumberLiteral(value, _)) => Literal::Number(value, None),
+            Some(Token::AddressLiteral(value)) => Literal::Number(
+                BigInt::parse_bytes(&value.as_bytes()[2..], 16).unwrap_or_default(),
+                None,
+            ),
+            Some(Token::StringLiteral(value)) => Literal::String(value),
+            Some(Token::HexStringLiteral(value)) => Literal::HexString(value),
+            Some(Token::True) => Literal::Bool(true),
+            Some(Token::False) => Literal::Bool(false),
+            _ => self.unexpected(),
+        };
+        self.advance();
+        YulExpression {
+            span: self.span_from(start),
+            kind: YulExpressionKind::Literal(literal),
+        }
+    }
+
+    /* ---------------------------- EXPRESSIONS ---------------------------- */
+
+    fn parse_expression(&mut self) -> Expression {
+        let left = self.parse_conditional();
+        match self.assignment_operator() {
+            Some(operator) => {
+                self.advance();
+                let right = self.parse_expression();
+                Expression {
+                    span: self.span_from(left.span),
+                    kind: ExpressionKind::Assignment {
+                        operator,
+                        left: Box::new(left),
+                        right: Box::new(right),
+                    },
+                }
+            }
+            None => left,
+        }
+    }
+
+    fn parse_conditional(&mut self) -> Expression {
+        let condition = self.parse_binary(0);
+        if self.eat(&Token::QuestionMark) {
+            let then = self.parse_expression();
+            self.expect(&Token::Colon);
+            let otherwise = self.parse_expression();
+            return Expression {
+                span: self.span_from(condition.span),
+                kind: ExpressionKind::Conditional {
+                    condition: Box::new(condition),
+                    then: Box::new(then),
+                    otherwise: Box::new(otherwise),
+                },
+            };
+        }
+        condition
+    }
+
+    fn parse_binary(&mut self, min_precedence: u8) -> Expression {
+        let mut left = self.parse_unary();
+        while let Some(operator) = self.binary_operator() {
+            let precedence = precedence(operator);
+            if precedence < min_precedence {
+                break;
+            }
+            self.advance();
+            /* EXPONENTIATION IS RIGHT ASSOCIATIVE */
+            let right = if operator == BinaryOperator::Exp {
+                self.parse_binary(precedence)
+            } else {
+                self.parse_binary(precedence + 1)
+            };
+            left = Expression {
+                span: self.span_from(left.span),
+                kind: ExpressionKind::Binary {
+                    operator,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+            };
+        }
+        left
+    }
+
+    fn parse_unary(&mut self) -> Expression {
+        let start = self.start();
+        let operator = match self.peek() {
+            Some(Token::Bang) => Some(UnaryOperator::Not),
+            Some(Token::Not) => Some(UnaryOperator::BitNot),
+            Some(Token::Delete) => Some(UnaryOperator::Delete),
+            Some(Token::Decrement) => Some(UnaryOperator::PreDecrement),
+            Some(Token::Minus) => Some(UnaryOperator::Negate),
+            Some(Token::Increment) => Some(UnaryOperator::PreIncrement),
+            _ => None,
+        };
+        match operator {
+            Some(operator) => {
+                self.advance();
+                let operand = self.parse_unary();
+                Expression {
+                    span: self.span_from(start),
+                    kind: ExpressionKind::Unary {
+                        operator,
+                        operand: Box::new(operand),
+                    },
+                }
+            }
+            None => self.parse_postfix(),
+        }
+    }
+
+    fn parse_postfix(&mut self) -> Expression {
+        let mut expression = self.parse_primary();
+        loop {
+            let start = expression.span;
+            let kind = match self.peek() {
+                Some(Token::Dot) => {
+                    self.advance();
+                    let member = self.expect_member_name();
+                    ExpressionKind::MemberAccess {
+                        expression: Box::new(expression),
+                        member,
+                    }
+                }
+                Some(Token::OpenSquareBracket) => {
+                    self.advance();
+                    let index = if self.check(&Token::CloseSquareBracket) {
+                        None
+                    } else {
+                        Some(Box::new(self.parse_expression()))
+                    };
+                    self.expect(&Token::CloseSquareBracket);
+                    ExpressionKind::Index {
+                        base: Box::new(expression),
+                        index,
+                    }
+                }
+                Some(Token::OpenParenthesis) => {
+                    self.advance();
+                    let arguments = self.parse_call_arguments();
+                    ExpressionKind::FunctionCall {
+                        callee: Box::new(expression),
+                        arguments,
+                    }
+                }
+                Some(Token::OpenBraces)
+                    if self.check_identifier(1) && self.peek_nth(2) == Some(&Token::Colon) =>
+                {
+                    self.advance();
+                    let options = self.parse_named_arguments();
+                    ExpressionKind::CallOptions {
+                        callee: Box::new(expression),
+                        options,
+                    }
+                }
+                Some(Token::Increment) => {
+                    self.advance();
+                    ExpressionKind::Unary {
+                        operator: UnaryOperator::PostIncrement,
+                        operand: Box::new(expression),
+                    }
+                }
+                Some(Token::Decrement) => {
+                    self.advance();
+                    ExpressionKind::Unary {
+                        operator: UnaryOperator::PostDecrement,
+                        operand: Box::new(expression),
+                    }
+                }
+                _ => break,
+            };
+            expression = Expression {
+                span: self.span_from(start),
+                kind,
+            };
+        }
+        expression
+    }
+
+    fn parse_primary(&mut self) -> Expression {
+        let start = self.start();
+        let kind = match self.peek().cloned() {
+            Some(Token::True) => {
+                self.advance();
+                ExpressionKind::Literal(Literal::Bool(true))
+            }
+            Some(Token::False) => {
+                self.advance();
+                ExpressionKind::Literal(Literal::Bool(false))
+            }
+            /* ADJACENT STRING LITERALS ARE CONCATENATED */
+            Some(Token::StringLiteral(_)) => {
+                let mut value = String::new();
+                while let Some(Token::StringLiteral(_value)) = self.peek() {
+                    value.push_str(_value);
+                    self.advance();
+                }
+                ExpressionKind::Literal(Literal::String(value))
+            }
+            Some(Token::HexStringLiteral(_)) => {
+                let mut value = Vec::new();
+                while let Some(Token::HexStringLiteral(_value)) = self.peek() {
+                    value.extend_from_slice(_value);
+                    self.advance();
+                }
+                ExpressionKind::Literal(Literal::HexString(value))
+            }
+            Some(Token::NumberLiteral(value, digits)) => {
+                self.advance();
+                ExpressionKind::Literal(Literal::Number(value, digits))
+            }
+            Some(Token::RationalLiteral(value)) => {
+                self.advance();
+                ExpressionKind::Literal(Literal::Rational(value))
+            }
+            Some(Token::AddressLiteral(value)) => {
+                self.advance();
+                ExpressionKind::Literal(Literal::Address(value))
+            }
+            Some(Token::Msg | Token::Require | Token::Assert) => {
+                let name = self.advance().to_string();
+                ExpressionKind::Variable(Identifier { span: start, name })
+            }
+            Some(Token::Payable) => {
+                self.advance();
+                ExpressionKind::ElementaryType(ElementaryType::AddressPayable)
+            }
+            Some(_token) if elementary_type(&_token).is_some() => {
+                ExpressionKind::ElementaryType(self.parse_elementary_type())
+            }
+            Some(Token::OpenParenthesis) => {
+                self.advance();
+                let mut components = Vec::new();
+                loop {
+                    if self.check(&Token::Coma) || self.check(&Token::CloseParenthesis) {
+                        components.push(None);
+                    } else {
+                        components.push(Some(self.parse_expression()));
+                    }
+                    if !self.eat(&Token::Coma) {
+                        break;
+                    }
+                }
+                self.expect(&Token::CloseParenthesis);
+                if components.len() == 1 && components[0].is_some() {
+                    return components.remove(0).unwrap();
+                }
+                ExpressionKind::Tuple(components)
+            }
+            Some(Token::OpenSquareBracket) => {
+                self.advance();
+                ExpressionKind::ArrayLiteral(self.parse_expression_list(&Token::CloseSquareBracket))
+            }
+            Some(Token::New) => {
+                self.advance();
+                ExpressionKind::New(self.parse_type_name())
+            }
+            _ if self.check_identifier(0) => ExpressionKind::Variable(self.expect_identifier()),
+            _ => self.unexpected(),
+        };
+        Expression {
+            span: self.span_from(start),
+            kind,
+        }
+    }
+
+    /* PARSES THE ARGUMENTS AFTER AN ALREADY CONSUMED `(` INCLUDING THE CLOSING `)` */
+    fn parse_call_arguments(&mut self) -> CallArguments {
+        if self.eat(&Token::OpenBraces) {
+            let arguments = self.parse_named_arguments();
+            self.expect(&Token::CloseParenthesis);
+            CallArguments::Named(arguments)
+        } else {
+            CallArguments::Positional(self.parse_expression_list(&Token::CloseParenthesis))
+        }
+    }
+
+    /* PARSES `name: value, ...` AFTER AN ALREADY CONSUMED `{` INCLUDING THE CLOSING `}` */
+    fn parse_named_arguments(&mut self) -> Vec<NamedArgument> {
+        let mut arguments = Vec::new();
+        if self.eat(&Token::CloseBraces) {
+            return arguments;
+        }
+        loop {
+            let start = self.start();
+            let name = self.expect_identifier();
+            self.expect(&Token::Colon);
+            let value = self.parse_expression();
+            arguments.push(NamedArgument {
+                span: self.span_from(start),
+                name,
+                value,
+            });
+            if !self.eat(&Token::Coma) {
+                break;
+            }
+        }
+        self.expect(&Token::CloseBraces);
+        arguments
+    }
+
+    /* PARSES A COMMA SEPARATED LIST UP TO AND INCLUDING THE `close` TOKEN */
+    fn parse_expression_list(&mut self, close: &Token) -> Vec<Expression> {
+        let mut expressions = Vec::new();
+        if self.eat(close) {
+            return expressions;
+        }
+        loop {
+            expressions.push(self.parse_expression());
+            if !self.eat(&Token::Coma) {
+                break;
+            }
+        }
+        self.expect(close);
+        expressions
+    }
+
+    fn binary_operator(&self) -> Option<BinaryOperator> {
+        let operator = match self.peek()? {
+            Token::Plus => BinaryOperator::Add,
+            Token::Minus => BinaryOperator::Sub,
+            Token::Multiply => BinaryOperator::Mul,
+            Token::Divide => BinaryOperator::Div,
+            Token::Modulu => BinaryOperator::Mod,
+            Token::Power => BinaryOperator::Exp,
+            Token::ShiftLeft => BinaryOperator::Shl,
+            Token::ShiftRight => BinaryOperator::Shr,
+            Token::And => BinaryOperator::BitAnd,
+            Token::Or => BinaryOperator::BitOr,
+            Token::Xor => BinaryOperator::BitXor,
+            Token::DoubleAnd => BinaryOperator::And,
+            Token::DoubleOr => BinaryOperator::Or,
+            Token::DoubleEquals => BinaryOperator::Eq,
+            Token::NotEquals => BinaryOperator::Ne,
+            Token::Lt => BinaryOperator::Lt,
+            Token::LtEquals => BinaryOperator::Le,
+            Token::Gt => BinaryOperator::Gt,
+            Token::GtEquals => BinaryOperator::Ge,
+            _ => return None,
+        };
+        Some(operator)
+    }
+
+    /* Some(None) FOR A PLAIN `=`, Some(Some(op)) FOR COMPOUND ASSIGNMENTS */
+    fn assignment_operator(&self) -> Option<Option<BinaryOperator>> {
+        let operator = match self.peek()? {
+            Token::Equals => None,
+            Token::PlusEquals => Some(BinaryOperator::Add),
+            Token::MinusEquals => Some(BinaryOperator::Sub),
+            Token::MultiplyEquals => Some(BinaryOperator::Mul),
+            Token::DivideEquals => Some(BinaryOperator::Div),
+            Token::ModuluEquals => Some(BinaryOperator::Mod),
+            Token::AndEquals => Some(BinaryOperator::BitAnd),
+            Token::OrEquals => Some(BinaryOperator::BitOr),
+            Token::XorEquals => Some(BinaryOperator::BitXor),
+            Token::ShiftLeftEquals => Some(BinaryOperator::Shl),
+            Token::ShiftRightEquals => Some(BinaryOperator::Shr),
+            _ => return None,
+        };
+        Some(operator)
+    }
+}
+
+fn precedence(operator: BinaryOperator) -> u8 {
+    match operator {
+        BinaryOperator::Or => 1,
+        BinaryOperator::And => 2,
+        BinaryOperator::Eq | BinaryOperator::Ne => 3,
+        BinaryOperator::Lt | BinaryOperator::Le | BinaryOperator::Gt | BinaryOperator::Ge => 4,
+        BinaryOperator::BitOr => 5,
+        BinaryOperator::BitXor => 6,
+        BinaryOperator::BitAnd => 7,
+        BinaryOperator::Shl | BinaryOperator::Shr => 8,
+        BinaryOperator::Add | BinaryOperator::Sub => 9,
+        BinaryOperator::Mul | BinaryOperator::Div | BinaryOperator::Mod => 10,
+        BinaryOperator::Exp => 11,
+    }
+}
+
+fn elementary_type(token: &Token) -> Option<ElementaryType> {
+    match token {
+        Token::Address => Some(ElementaryType::Address),
+        Token::Bool => Some(ElementaryType::Bool),
+        Token::String => Some(ElementaryType::String),
+        Token::Bytes(None) => Some(ElementaryType::Bytes),
+        Token::Bytes(Some(size)) => Some(ElementaryType::FixedBytes(*size as u8)),
+        Token::Uint(size) => Some(ElementaryType::Uint(size.unwrap_or(256))),
+        Token::Int(size) => Some(ElementaryType::Int(size.unwrap_or(256))),
+        _ => None,
+    }
+}
+
+fn is_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' || first == '$' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::mods::types::{
+    ast::{ContractPart, VariableMutability},
+    bindings::{Bindings, ContractId},
+    compiler_errors::{CompilerError, SemanticError},
+    diagnostics::Diagnostics,
+    line_descriptors::Span,
+    semantic_types::{FunctionKind, Type, TypeTable},
+    source_graph::SourceGraph,
+    source_map::SourceMap,
+    storage_layout::{StorageLayout, StorageType, StorageVariable},
+};
+
+/* WHERE THE STATE VARIABLES OF `contract` LIVE, THOSE OF ITS BASES FIRST. CONSTANTS AND
+IMMUTABLES ARE PART OF THE CODE AND TAKE NO STORAGE */
+pub fn storage_layout(
+    contract: ContractId,
+    graph: &SourceGraph,
+    bindings: &Bindings,
+    types: &TypeTable,
+    sources: &SourceMap,
+    diagnostics: &mut Diagnostics,
+) -> StorageLayout {
+    let mut allocator = Allocator {
+        graph,
+        types,
+        sources,
+        layout: StorageLayout::default(),
+    };
+    let mut next = Position::default();
+    let mut variables = Vec::new();
+    for id in bindings.inheritance_order(contract) {
+        let owner = allocator.owner(id);
+        let definition = &graph.units[id.0].ast.contracts[id.1];
+        for part in &definition.parts {
+            let ContractPart::StateVariable(variable) = part else {
+                continue;
+            };
+            if matches!(
+                variable.mutability,
+                Some(VariableMutability::Constant | VariableMutability::Immutable)
+            ) {
+                continue;
+            }
+            let ty = allocator.type_name(&variable.ty.span);
+            let (slot, offset, ty) = allocator.place(&mut next, &ty);
+            variables.push(StorageVariable {
+                contract: owner.clone(),
+                label: variable.name.name.clone(),
+                slot,
+                offset,
+                ty,
+                span: Some(variable.name.span),
+            });
+        }
+    }
+    allocator.layout.variables = variables;
+    check_size(contract, graph, &allocator.layout, diagnostics);
+    allocator.layout
+}
+
+/* SLOTS AND SIZES ARE WORDS. A VARIABLE PAST THE LAST SLOT WOULD WRAP AROUND ONTO SLOT 0, AND
+A TYPE OF 2**256 BYTES OR MORE CANNOT BE ADDRESSED AT ALL */
+fn check_size(
+    contract: ContractId,
+    graph: &SourceGraph,
+    layout: &StorageLayout,
+    diagnostics: &mut Diagnostics,
+) {
+    let limit = BigUint::one() << 256u16;
+    for variable in &layout.variables {
+        let label = match oversized(layout, &variable.ty, &limit, &mut Vec::new()) {
+            Some(_ty) if _ty.label == layout.label(&variable.ty) => {
+                format!("`{}` takes 2**256 bytes or more", variable.label)
+            }
+            Some(_ty) => format!("its `{}` takes 2**256 bytes or more", _ty.label),
+            None if &variable.slot + slots(&layout.types[&variable.ty].number_of_bytes) > limit => {
+                "stored past the last slot".to_string()
+            }
+            None => continue,
+        };
+        let definition = &graph.units[contract.0].ast.contracts[contract.1];
+        let span = variable.span.unwrap_or(definition.name.span);
+        let mut diagnostic = CompilerError::SemanticError(SemanticError::StorageTooLarge(
+            definition.name.name.clone(),
+        ))
+        .diagnostic()
+        .with_label(span, label);
+        if span != definition.name.span {
+            diagnostic = diagnostic.with_secondary_label(definition.name.span, "in this contract");
+        }
+        diagnostics.push(diagnostic);
+        return;
+    }
+}
+
+/* `id` OR A TYPE IT IS MADE OF, IF ONE OF THEM TAKES 2**256 BYTES OR MORE. A STRUCT THAT
+REACHES ITSELF IS LOOKED AT ONCE */
+fn oversized<'a>(
+    layout: &'a StorageLayout,
+    id: &'a str,
+    limit: &BigUint,
+    seen: &mut Vec<&'a str>,
+) -> Option<&'a StorageType> {
+    if seen.contains(&id) {
+        return None;
+    }
+    seen.push(id);
+    let ty = layout.types.get(id)?;
+    if &ty.number_of_bytes >= limit {
+        return Some(ty);
+    }
+    let members = ty.members.iter().flatten().map(|member| &member.ty);
+    [&ty.base, &ty.key, &ty.value]
+        .into_iter()
+        .flatten()
+        .chain(members)
+        .find_map(|inner| oversized(layout, inner, limit, seen))
+}
+
+/* HOW A VALUE OF `ty` IS STORED, WITH THE ENTRIES OF THE TYPES IT IS MADE OF, KEYED BY
+`type_id`. THE CODE GENERATOR ASKS FOR TYPES THAT NO STATE VARIABLE OF THE CONTRACT USES */
+pub fn storage_types(
+    ty: &Type,
+    graph: &SourceGraph,
+    types: &TypeTable,
+    sources: &SourceMap,
+) -> BTreeMap<String, StorageType> {
+    let mut allocator = Allocator {
+        graph,
+        types,
+        sources,
+        layout: StorageLayout::default(),
+    };
+    allocator.register(ty);
+    allocator.layout.types
+}
+
+/* THE FIRST FREE BYTE: A SLOT AND HOW MANY OF ITS LOW BYTES ARE TAKEN */
+#[derive(Default)]
+struct Position {
+    slot: BigUint,
+    offset: u8,
+}
+
+struct Allocator<'a> {
+    graph: &'a SourceGraph,
+    types: &'a TypeTable,
+    sources: &'a SourceMap,
+    layout: StorageLayout,
+}
+
+impl Allocator<'_> {
+    fn owner(&self, (file_id, contract): ContractId) -> String {
+        format!(
+            "{}:{}",
+            self.sources
+                .get(file_id)
+                .map_or("", |file| file.name.as_str()),
+            self.graph.units[file_id].ast.contracts[contract].name.name
+        )
+    }
+
+    fn type_name(&self, span: &Span) -> Type {
+        self.types
+            .type_names
+            .get(span)
+            .cloned()
+            .unwrap_or(Type::Unknown)
+    }
+
+    /* THE TYPE AS solc SPELLS IT, WITH STRUCTS AND ENUMS QUALIFIED BY THEIR CONTRACT */
+    fn label(&self, ty: &Type) -> String {
+        match ty {
+            Type::Array { base, length } => format!(
+                "{}[{}]",
+                self.label(base),
+                length.as_ref().map_or(String::new(), ToString::to_string)
+            ),
+            Type::Mapping { key, value } => {
+                format!("mapping({} => {})", self.label(key), self.label(value))
+            }
+            Type::Struct {
+                name,
+                file_id,
+                contract,
+                ..
+            } => format!("struct {}.{name}", self.contract_name(*file_id, *contract)),
+            Type::Enum {
+                name,
+                file_id,
+                contract,
+                ..
+            } => format!("enum {}.{name}", self.contract_name(*file_id, *contract)),
+            ty => ty.to_string(),
+        }
+    }
+
+    fn contract_name(&self, file_id: usize, contract: usize) -> &str {
+        &self.graph.units[file_id].ast.contracts[contract].name.name
+    }
+
+    /* SOLIDITY'S PACKING RULES: A VALUE TYPE SHARES THE CURRENT SLOT IF IT FITS IN WHAT IS LEFT
+    OF IT. STRUCTS AND ARRAYS START A NEW SLOT AND WHATEVER FOLLOWS THEM DOES TOO. MAPPINGS,
+    DYNAMIC ARRAYS AND STRINGS TAKE ONE WHOLE SLOT, THEIR DATA LIVES AT A HASH OF IT */
+    fn place(&mut self, next: &mut Position, ty: &Type) -> (BigUint, u8, String) {
+        let id = self.register(ty);
+        let entry = &self.layout.types[&id];
+        let packed = entry.encoding == "inplace"
+            && entry.members.is_none()
+            && entry.base.is_none()
+            && entry.number_of_bytes < BigUint::from(32u8);
+        let size = entry.number_of_bytes.clone();
+        if packed {
+            let size = size.to_u8().unwrap_or(32);
+            if next.offset + size > 32 {
+                next.slot += 1u8;
+                next.offset = 0;
+            }
+            let placed = (next.slot.clone(), next.offset, id);
+            next.offset += size;
+            placed
+        } else {
+            if next.offset > 0 {
+                next.slot += 1u8;
+                next.offset = 0;
+            }
+            let placed = (next.slot.clone(), 0, id);
+            next.slot += slots(&size);
+            placed
+        }
+    }
+
+    /* ADDS THE ENTRY OF `ty` AND OF THE TYPES IT IS MADE OF TO `types`, RETURNING ITS KEY */
+    fn register(&mut self, ty: &Type) -> String {
+        let id = type_id(ty);
+        if self.layout.types.contains_key(&id) {
+            return id;
+        }
+        let entry = match ty {
+            Type::Mapping { key, value } => StorageType {
+                key: Some(self.register(key)),
+                value: Some(self.register(value)),
+                ..storage_type("mapping", self.label(ty), 32u8)
+            },
+            Type::Array { base, length: None } => StorageType {
+                base: Some(self.register(base)),
+                ..storage_type("dynamic_array", self.label(ty), 32u8)
+            },
+            Type::Array {
+                base,
+                length: Some(length),
+            } => {
+                let base_id = self.register(base);
+                let element = &self.layout.types[&base_id];
+                let length = length.to_biguint().unwrap_or_default();
+                let element_size = element.number_of_bytes.clone();
+                /* SMALL VALUE TYPES ARE PACKED SEVERAL TO A SLOT, ANYTHING ELSE TAKES WHOLE SLOTS */
+                let used_slots = if element.members.is_none()
+                    && element.base.is_none()
+                    && element.encoding == "inplace"
+                    && element_size < BigUint::from(32u8)
+                    && !element_size.is_zero()
+                {
+                    let per_slot = BigUint::from(32u8) / &element_size;
+                    (length + &per_slot - 1u8) / per_slot
+                } else {
+                    length * slots(&element_size)
+                };
+                StorageType {
+                    base: Some(base_id),
+                    ..storage_type("inplace", self.label(ty), used_slots * 32u8)
+                }
+            }
+            Type::Struct {
+                file_id,
+                contract,
+                part,
+                ..
+            } => {
+                let owner = self.owner((*file_id, *contract));
+                let label = self.label(ty);
+                /* A PLACEHOLDER FIRST, SO THAT A STRUCT REACHING ITSELF THROUGH A MAPPING OR A
+                DYNAMIC ARRAY FINDS ITS KEY */
+                let placeholder = StorageType {
+                    members: Some(Vec::new()),
+                    ..storage_type("inplace", label.clone(), 0u8)
+                };
+                self.layout.types.insert(id.clone(), placeholder);
+                let ContractPart::Struct(definition) =
+                    &self.graph.units[*file_id].ast.contracts[*contract].parts[*part]
+                else {
+                    return id;
+                };
+                let mut next = Position::default();
+                let mut members = Vec::new();
+                for field in &definition.fields {
+                    let ty = self.type_name(&field.ty.span);
+                    let (slot, offset, ty) = self.place(&mut next, &ty);
+                    members.push(StorageVariable {
+                        contract: owner.clone(),
+                        label: field.name.name.clone(),
+                        slot,
+                        offset,
+                        ty,
+                        span: Some(field.name.span),
+                    });
+                }
+                let used_slots = next.slot + u8::from(next.offset > 0);
+                StorageType {
+                    members: Some(members),
+                    ..storage_type("inplace", label, used_slots * 32u8)
+                }
+            }
+            Type::String | Type::Bytes => storage_type("bytes", ty.to_string(), 32u8),
+            Type::Enum { .. } => storage_type("inplace", self.label(ty), 1u8),
+            ty => storage_type("inplace", ty.to_string(), value_size(ty)),
+        };
+        self.layout.types.insert(id.clone(), entry);
+        id
+    }
+}
+
+/* KEY OF A TYPE IN `types`, SPELLED AS solc DOES */
+pub fn type_id(ty: &Type) -> String {
+    match ty {
+        Type::Address { payable: false } => "t_address".to_string(),
+        Type::Address { payable: true } => "t_address_payable".to_string(),
+        Type::Bool => "t_bool".to_string(),
+        Type::String => "t_string_storage".to_string(),
+        Type::Bytes => "t_bytes_storage".to_string(),
+        Type::FixedBytes(size) => format!("t_bytes{size}"),
+        Type::Integer { signed, bits } => {
+            format!("t_{}int{bits}", if *signed { "" } else { "u" })
+        }
+        Type::Array { base, length } => format!(
+            "t_array({}){}_storage",
+            type_id(base),
+            length
+                .as_ref()
+                .map_or("dyn".to_string(), ToString::to_string)
+        ),
+        Type::Mapping { key, value } => format!("t_mapping({},{})", type_id(key), type_id(value)),
+        Type::Struct { name, .. } => format!("t_struct({name})_storage"),
+        Type::Enum { name, .. } => format!("t_enum({name})"),
+        Type::Contract { name, .. } => format!("t_contract({name})"),
+        Type::Function(function) if function.kind == FunctionKind::External => {
+            "t_function_external".to_string()
+        }
+        Type::Function(_) => "t_function_internal".to_string(),
+        _ => "t_unknown".to_string(),
+    }
+}
+
+/* BYTES A VALUE TYPE TAKES IN STORAGE */
+pub fn value_size(ty: &Type) -> u8 {
+    match ty {
+        Type::Bool => 1,
+        Type::Address { .. } | Type::Contract { .. } => 20,
+        Type::FixedBytes(size) => *size,
+        Type::Integer { bits, .. } => (bits / 8) as u8,
+        /* AN ADDRESS AND A SELECTOR, OR A CODE OFFSET */
+        Type::Function(function) if function.kind == FunctionKind::External => 24,
+        Type::Function(_) => 8,
+        _ => 32,
+    }
+}
+
+fn slots(bytes: &BigUint) -> BigUint {
+    (bytes + 31u8) / 32u8
+}
+
+fn storage_type(encoding: &str, label: String, bytes: impl Into<BigUint>) -> StorageType {
+    StorageType {
+        encoding: encoding.to_string(),
+        label,
+        number_of_bytes: bytes.into(),
+        base: None,
+        key: None,
+        value: None,
+        members: None,
+    }
+}
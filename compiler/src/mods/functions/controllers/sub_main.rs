@@ -1,238 +1,553 @@
+use std::{mem, process};
+
+use tokio::fs;
+
 use crate::mods::{
-    functions::controllers::process_file_contents::process_file_contents,
+    functions::controllers::{
+        compile::compile, formatter::format_source, import_resolver::normalize_path,
+        parser::parse_source_unit, process_file_contents::process_file_contents,
+    },
     types::{
-        compiler_errors::{CompilerError, SyntaxError},
-        line_descriptors::{LineDescriptions, StringDescriptor},
-        token::{Context, Token, TokenTrait, VecExtension},
+        ast::SourceUnit,
+        cli::{Cli, Command},
+        compilation::{artifact_path, Artifact},
+        compiler_errors::{CompilerError, IOError, SyntaxError},
+        diagnostics::{Diagnostic, Diagnostics, ErrorFormat},
+        line_descriptors::{LineDescriptions, Span},
+        settings::Settings,
+        source_map::SourceMap,
+        token::{lex, Context, Token, TokenTrait},
     },
 };
 
-pub async fn compile_source_code(args: Vec<String>) {
-    let parsable_structure = process_file_contents(args).await;
-    let mut imports: Vec<Vec<LineDescriptions<Vec<Token>>>> = Vec::new();
-    let mut libraries: Vec<Vec<LineDescriptions<Vec<Token>>>> = Vec::new();
-    let mut interfaces: Vec<Vec<LineDescriptions<Vec<Token>>>> = Vec::new();
-    let mut contracts: Vec<Vec<LineDescriptions<Vec<Token>>>> = Vec::new();
-    let mut custom_errors: Vec<Vec<LineDescriptions<Vec<Token>>>> = Vec::new();
+pub async fn compile_source_code(cli: &Cli) {
+    let error_format = cli.error_format;
+    let args = cli.command.args();
+    let settings = args.settings().unwrap_or_else(|message| {
+        exit_with(
+            CompilerError::IOError(IOError::IOError(message)),
+            error_format,
+        )
+    });
+    let files = args.files();
+    if files.is_empty() {
+        exit_with(
+            CompilerError::IOError(IOError::IOError("No input files".to_string())),
+            error_format,
+        );
+    }
+
+    /* READ EVERY INPUT FIRST SO THAT ALL MISSING FILES ARE REPORTED TOGETHER */
+    let mut sources = SourceMap::default();
+    let mut diagnostics = Diagnostics::default();
+    let mut file_ids = Vec::new();
+    for path in files {
+        /* INPUTS ARE NAMED BY THEIR PATH, IMPORTS RELATIVE TO THEM ARE RESOLVED FROM IT */
+        let name = normalize_path(path);
+        if sources.find(&name).is_some() {
+            continue;
+        }
+        match process_file_contents(path).await {
+            Ok(contents) => file_ids.push(sources.add(name, contents)),
+            Err(error) => diagnostics.push(error.diagnostic()),
+        }
+    }
+    finish_stage(&diagnostics, &sources, error_format);
+
+    match &cli.command {
+        Command::Tokens(_) => {
+            for &file_id in &file_ids {
+                let file = sources.get(file_id).unwrap();
+                let lexems = lex(&file.contents, file_id, &mut diagnostics);
+                if file_ids.len() > 1 {
+                    println!("======= {} =======", file.name);
+                }
+                for lexem in lexems {
+                    println!(
+                        "{}:{}\t{:?}",
+                        lexem.span.line, lexem.span.column, lexem.data
+                    );
+                }
+            }
+            finish_stage(&diagnostics, &sources, error_format);
+        }
+        Command::Fmt { check, write, .. } => {
+            let mut unformatted = Vec::new();
+            for &file_id in &file_ids {
+                let file = sources.get(file_id).unwrap();
+                let mut file_diagnostics = Diagnostics::default();
+                let lexems = lex(&file.contents, file_id, &mut file_diagnostics);
+                /* A FILE THAT DOES NOT LEX IS LEFT AS IT IS */
+                if file_diagnostics.has_errors() {
+                    diagnostics.extend(file_diagnostics);
+                    continue;
+                }
+                let formatted = format_source(&file.contents, &lexems);
+                if *check {
+                    if formatted != file.contents {
+                        unformatted.push(file.name.clone());
+                    }
+                } else if *write {
+                    if formatted != file.contents {
+                        if let Err(error) = fs::write(&file.name, formatted).await {
+                            diagnostics.push(
+                                CompilerError::IOError(IOError::IOError(format!(
+                                    "{}: {error}",
+                                    file.name
+                                )))
+                                .diagnostic(),
+                            );
+                        }
+                    }
+                } else {
+                    print!("{formatted}");
+                }
+            }
+            finish_stage(&diagnostics, &sources, error_format);
+            if !unformatted.is_empty() {
+                for name in unformatted {
+                    eprintln!("Would reformat {name}");
+                }
+                process::exit(1);
+            }
+        }
+        Command::Build(_) | Command::Check(_) | Command::Ast(_) | Command::Abi(_) => {
+            let output = compile(mem::take(&mut sources), &settings);
+            sources = output.sources;
+            diagnostics.extend(output.diagnostics);
+            match &cli.command {
+                Command::Ast(_) => {
+                    finish_stage(&diagnostics, &sources, error_format);
+                    for unit in &output.graph.units {
+                        if output.graph.units.len() > 1 {
+                            println!(
+                                "======= {} =======",
+                                sources.get(unit.file_id).unwrap().name
+                            );
+                        }
+                        println!("{:#?}", unit.ast);
+                    }
+                }
+                Command::Build(_) => {
+                    finish_stage(&diagnostics, &sources, error_format);
+                    write_artifacts(&output.artifacts, &settings, false, &mut diagnostics).await;
+                }
+                Command::Abi(_) => {
+                    finish_stage(&diagnostics, &sources, error_format);
+                    write_artifacts(&output.artifacts, &settings, true, &mut diagnostics).await;
+                }
+                _ => {}
+            }
+            finish_stage(&diagnostics, &sources, error_format);
+        }
+    }
+
+    /* ONLY WARNINGS ARE LEFT AT THIS POINT */
+    diagnostics.emit(&sources, error_format);
+}
+
+/* WRITES THE ARTIFACTS TO THE OUTPUT DIRECTORY, OR PRINTS THEM WHEN THERE IS NONE.
+`abi_only` LEAVES OUT EVERYTHING BUT THE ABI */
+async fn write_artifacts(
+    artifacts: &[Artifact],
+    settings: &Settings,
+    abi_only: bool,
+    diagnostics: &mut Diagnostics,
+) {
+    for artifact in artifacts {
+        let json = |metadata: serde_json::Value| serde_json::to_string_pretty(&metadata).unwrap();
+        let bytecode = artifact.bytecode.as_ref();
+        let outputs = [
+            (
+                "abi.json",
+                "Contract JSON ABI",
+                true,
+                json(artifact.abi_json()),
+            ),
+            (
+                "bin",
+                "Binary",
+                bytecode.is_some(),
+                bytecode
+                    .map(|code| hex::encode(&code.creation))
+                    .unwrap_or_default(),
+            ),
+            (
+                "bin-runtime",
+                "Binary of the runtime part",
+                bytecode.is_some(),
+                bytecode
+                    .map(|code| hex::encode(&code.runtime))
+                    .unwrap_or_default(),
+            ),
+            (
+                "cron.json",
+                "Cron schedules",
+                !artifact.cron.is_empty(),
+                json(artifact.cron_metadata()),
+            ),
+            (
+                "relayer.json",
+                "Gasless functions",
+                !artifact.gasless.is_empty(),
+                json(artifact.relayer_metadata()),
+            ),
+            (
+                "storage.json",
+                "Storage layout",
+                artifact.storage_layout.is_some(),
+                json(artifact.storage_metadata()),
+            ),
+            (
+                "layout.json",
+                "Storage layout snapshot",
+                /* ONLY USEFUL AS A FILE THE NEXT BUILD READS BACK */
+                artifact.upgradable && settings.output_dir.is_some(),
+                json(artifact.layout_snapshot()),
+            ),
+            (
+                "ir",
+                "IR",
+                bytecode.is_some_and(|code| code.ir.is_some()),
+                bytecode
+                    .and_then(|code| code.ir.clone())
+                    .unwrap_or_default(),
+            ),
+            (
+                "passes.ir",
+                "IR before and after each optimizer pass",
+                bytecode.is_some_and(|code| code.passes.is_some()),
+                bytecode
+                    .and_then(|code| code.passes.clone())
+                    .unwrap_or_default(),
+            ),
+        ];
+        for (extension, title, _, metadata) in outputs
+            .into_iter()
+            .filter(|output| output.2 && (!abi_only || output.0 == "abi.json"))
+        {
+            let Some(output_dir) = &settings.output_dir else {
+                println!(
+                    "======= {}:{} =======\n{title}:\n{metadata}",
+                    artifact.source_unit, artifact.contract
+                );
+                continue;
+            };
+            let path = output_dir.join(artifact_path(
+                &artifact.source_unit,
+                &artifact.contract,
+                extension,
+            ));
+            let written = match fs::create_dir_all(path.parent().unwrap_or(output_dir)).await {
+                Ok(()) => fs::write(&path, metadata).await,
+                Err(error) => Err(error),
+            };
+            if let Err(error) = written {
+                diagnostics.push(
+                    CompilerError::IOError(IOError::IOError(format!(
+                        "{}: {error}",
+                        path.display()
+                    )))
+                    .diagnostic(),
+                );
+            }
+        }
+    }
+}
+
+/* PARSES THE TOKENS OF ONE FILE */
+pub fn parse_tokens(
+    lexems: Vec<LineDescriptions<Token>>,
+    diagnostics: &mut Diagnostics,
+) -> SourceUnit {
+    let mut imports: Vec<Vec<LineDescriptions<Token>>> = Vec::new();
+    let mut libraries: Vec<Vec<LineDescriptions<Token>>> = Vec::new();
+    let mut interfaces: Vec<Vec<LineDescriptions<Token>>> = Vec::new();
+    let mut contracts: Vec<Vec<LineDescriptions<Token>>> = Vec::new();
+    let mut custom_errors: Vec<Vec<LineDescriptions<Token>>> = Vec::new();
+    let mut definitions: Vec<Vec<LineDescriptions<Token>>> = Vec::new();
     seperate_variants(
-        parsable_structure,
+        lexems,
         &mut imports,
         &mut interfaces,
         &mut contracts,
         &mut libraries,
         &mut custom_errors,
+        &mut definitions,
+        diagnostics,
     );
-    println!("{:#?}", imports);
+    parse_source_unit(
+        &imports,
+        &interfaces,
+        &contracts,
+        &libraries,
+        &custom_errors,
+        &definitions,
+        diagnostics,
+    )
+}
+
+/* REPORTS WHAT HAS BEEN COLLECTED SO FAR AND STOPS IF ANY OF IT IS AN ERROR */
+fn finish_stage(diagnostics: &Diagnostics, sources: &SourceMap, error_format: ErrorFormat) {
+    if diagnostics.has_errors() {
+        diagnostics.emit(sources, error_format);
+        process::exit(1);
+    }
+}
+
+fn exit_with(error: CompilerError, error_format: ErrorFormat) -> ! {
+    let mut diagnostics = Diagnostics::default();
+    diagnostics.push(error.diagnostic());
+    diagnostics.emit(&SourceMap::default(), error_format);
+    process::exit(1)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn seperate_variants(
-    parsable_structure: Vec<LineDescriptions<String>>,
-    imports: &mut Vec<Vec<LineDescriptions<Vec<Token>>>>,
-    interfaces: &mut Vec<Vec<LineDescriptions<Vec<Token>>>>,
-    contracts: &mut Vec<Vec<LineDescriptions<Vec<Token>>>>,
-    libraries: &mut Vec<Vec<LineDescriptions<Vec<Token>>>>,
-    custom_errors: &mut Vec<Vec<LineDescriptions<Vec<Token>>>>,
+    lexems: Vec<LineDescriptions<Token>>,
+    imports: &mut Vec<Vec<LineDescriptions<Token>>>,
+    interfaces: &mut Vec<Vec<LineDescriptions<Token>>>,
+    contracts: &mut Vec<Vec<LineDescriptions<Token>>>,
+    libraries: &mut Vec<Vec<LineDescriptions<Token>>>,
+    custom_errors: &mut Vec<Vec<LineDescriptions<Token>>>,
+    definitions: &mut Vec<Vec<LineDescriptions<Token>>>,
+    diagnostics: &mut Diagnostics,
 ) {
     let mut is_import_brace = false;
     let mut opened_braces_count = 0;
-    let mut tokens: Vec<Token> = Vec::new();
+    let mut tokens: Vec<LineDescriptions<Token>> = Vec::new();
 
-    let mut combined: Vec<LineDescriptions<Vec<Token>>> = Vec::new();
     let mut context = Context::None;
-    for (parent_index, line_desc) in parsable_structure.iter().enumerate() {
-        let lexems = line_desc.lex();
-        // println!("{:?}", lexems);
-        for (index, token) in lexems.data.iter().enumerate() {
-            tokens.push(token.clone());
-            match token {
-                Token::Pragma => {
-                    if parent_index > 0 {
-                        validate_clash(context, &tokens, &parsable_structure.get(parent_index - 1));
-                    }
-                    context = Context::Header;
+    let mut previous: Option<Span> = None;
+    for lexem in lexems {
+        let token = lexem.data.clone();
+        let span = lexem.span;
+        tokens.push(lexem);
+        match token {
+            Token::Pragma if opened_braces_count == 0 => {
+                if let Some(_previous) = previous {
+                    validate_clash(context, &mut tokens, &_previous, diagnostics);
                 }
-                Token::Error => {
-                    if parent_index > 0 {
-                        validate_clash(context, &tokens, &parsable_structure.get(parent_index - 1));
-                    }
-
-                    context = Context::Error;
+                context = Context::Header;
+            }
+            Token::Error if opened_braces_count == 0 => {
+                if let Some(_previous) = previous {
+                    validate_clash(context, &mut tokens, &_previous, diagnostics);
                 }
-                Token::Abstract => {
-                    if parent_index > 0 {
-                        validate_clash(context, &tokens, &parsable_structure.get(parent_index - 1));
-                    }
 
-                    context = Context::Contract;
+                context = Context::Error;
+            }
+            Token::Abstract | Token::Upgradable if opened_braces_count == 0 => {
+                if !contract_prefix(&tokens) {
+                    if let Some(_previous) = previous {
+                        validate_clash(context, &mut tokens, &_previous, diagnostics);
+                    }
                 }
-                Token::Library => {
-                    if parent_index > 0 {
-                        validate_clash(context, &tokens, &parsable_structure.get(parent_index - 1));
+
+                context = Context::Contract;
+            }
+            Token::Library if opened_braces_count == 0 => {
+                if !contract_prefix(&tokens) {
+                    if let Some(_previous) = previous {
+                        validate_clash(context, &mut tokens, &_previous, diagnostics);
                     }
+                }
 
-                    context = Context::Library;
+                context = Context::Library;
+            }
+            Token::Import if opened_braces_count == 0 => {
+                if let Some(_previous) = previous {
+                    validate_clash(context, &mut tokens, &_previous, diagnostics);
                 }
-                Token::Import => {
-                    if parent_index > 0 {
-                        validate_clash(context, &tokens, &parsable_structure.get(parent_index - 1));
+
+                context = Context::Import;
+            }
+
+            Token::Interface if opened_braces_count == 0 => {
+                if !contract_prefix(&tokens) {
+                    if let Some(_previous) = previous {
+                        validate_clash(context, &mut tokens, &_previous, diagnostics);
                     }
+                }
 
-                    context = Context::Import;
+                context = Context::Interface;
+            }
+            Token::Contract if opened_braces_count == 0 => {
+                if !contract_prefix(&tokens) {
+                    if let Some(_previous) = previous {
+                        validate_clash(context, &mut tokens, &_previous, diagnostics);
+                    }
                 }
+                context = Context::Contract;
+            }
 
-                Token::Interface => {
-                    if parent_index > 0 {
-                        validate_clash(context, &tokens, &parsable_structure.get(parent_index - 1));
+            Token::SemiColon if opened_braces_count == 0 => {
+                match context {
+                    Context::Import => {
+                        imports.push(mem::take(&mut tokens));
+                    }
+                    Context::Header => {
+                        tokens.clear();
                     }
 
-                    context = Context::Interface;
-                }
-                Token::Contract => {
-                    if let Context::None = context {
-                    } else {
-                        if !tokens.is_empty() {
-                            if tokens.strip_spaces()[0] != Token::Abstract {
-                                println!("{:?}", tokens);
-                                panic!("contr")
-                            }
-                        }
+                    Context::Error => {
+                        custom_errors.push(mem::take(&mut tokens));
+                    }
+                    Context::Definition => {
+                        definitions.push(mem::take(&mut tokens));
+                    }
+                    Context::Skip => {
+                        tokens.clear();
+                    }
+
+                    _ => {
+                        diagnostics.push(
+                            CompilerError::SyntaxError(SyntaxError::UnexpectedToken(
+                                token.to_string(),
+                            ))
+                            .at(span),
+                        );
+                        tokens.clear();
                     }
-                    context = Context::Contract;
                 }
+                context = Context::None;
+            }
 
-                Token::SemiColon => {
+            Token::OpenBraces => {
+                if context == Context::Import {
+                    is_import_brace = true;
+                } else {
+                    opened_braces_count += 1;
+                }
+            }
+            Token::CloseBraces if is_import_brace || opened_braces_count > 0 => {
+                if !is_import_brace {
+                    opened_braces_count -= 1;
                     if opened_braces_count == 0 {
                         match context {
-                            Context::Import => {
-                                combined.push(LineDescriptions {
-                                    data: tokens.clone(),
-                                    line: lexems.line,
-                                });
-                                tokens.clear();
-                                imports.push(combined.clone());
-                                combined.clear();
+                            Context::Library => {
+                                libraries.push(mem::take(&mut tokens));
                             }
-                            Context::Header => {
-                                tokens.clear();
+                            Context::Interface => {
+                                interfaces.push(mem::take(&mut tokens));
                             }
 
-                            Context::Error => {
-                                combined.push(LineDescriptions {
-                                    data: tokens.clone(),
-                                    line: lexems.line,
-                                });
-                                tokens.clear();
-                                custom_errors.push(combined.clone());
-                                combined.clear();
+                            Context::Contract => {
+                                contracts.push(mem::take(&mut tokens));
                             }
-
-                            _ => {
-                                CompilerError::SyntaxError(SyntaxError::UnexpectedToken(
-                                    &token.to_string(),
-                                ))
-                                .throw_with_file_info("Contract.sol", lexems.line);
+                            Context::Definition => {
+                                definitions.push(mem::take(&mut tokens));
                             }
-                        }
-                        context = Context::None;
-                    }
-                }
-
-                Token::OpenBraces => {
-                    if index > 0 {
-                        let stripped = lexems.data.strip_spaces();
-                        let prev = stripped.get(index - 2);
-                        if prev.is_some() && *prev.unwrap() == Token::Import {
-                            is_import_brace = true;
-                        } else {
-                            opened_braces_count += 1;
-                        }
-                    } else {
-                        CompilerError::SyntaxError(SyntaxError::UnexpectedToken("{"))
-                            .throw_with_file_info("Contract.sol", lexems.line);
-                    }
-                }
-                Token::CloseBraces => {
-                    if !is_import_brace {
-                        opened_braces_count -= 1;
-                        if opened_braces_count == 0 {
-                            match context {
-                                Context::Library => {
-                                    combined.push(LineDescriptions {
-                                        data: tokens.clone(),
-                                        line: lexems.line,
-                                    });
-                                    tokens.clear();
-
-                                    libraries.push(combined.clone());
-                                    combined.clear();
-                                }
-                                Context::Interface => {
-                                    combined.push(LineDescriptions {
-                                        data: tokens.clone(),
-                                        line: lexems.line,
-                                    });
-                                    tokens.clear();
-
-                                    interfaces.push(combined.clone());
-                                    combined.clear();
-                                }
-
-                                Context::Contract => {
-                                    combined.push(LineDescriptions {
-                                        data: tokens.clone(),
-                                        line: lexems.line,
-                                    });
-                                    tokens.clear();
-
-                                    contracts.push(combined.clone());
-                                    combined.clear();
-                                }
-                                _ => {}
+                            Context::Skip => {
+                                tokens.clear();
                             }
-                            context = Context::None;
+                            _ => {}
                         }
-                    } else {
-                        is_import_brace = false;
+                        context = Context::None;
                     }
+                } else {
+                    is_import_brace = false;
                 }
-                _ => {}
             }
+            _ => {}
+        }
 
-            if let Context::None = context {
-                if !tokens.strip_spaces().is_empty() {
-                    CompilerError::SyntaxError(SyntaxError::UnexpectedToken(
-                        &tokens.strip_spaces()[0].to_string(),
-                    ))
-                    .throw_with_file_info("Contract.sol", lexems.line);
+        /* ANYTHING ELSE AT FILE LEVEL EITHER STARTS A DEFINITION OR IS REPORTED ONCE, SKIPPING
+        WHAT FOLLOWS IT UP TO THE NEXT `;` OR BALANCED `}` */
+        if let Context::None = context {
+            if let Some(_token) = tokens.first() {
+                if starts_definition(&_token.data) {
+                    context = Context::Definition;
+                } else {
+                    diagnostics.push(
+                        CompilerError::SyntaxError(SyntaxError::UnexpectedToken(
+                            _token.data.to_string(),
+                        ))
+                        .at(_token.span),
+                    );
+                    match tokens.len() == 1 && _token.data != Token::CloseBraces {
+                        true => context = Context::Skip,
+                        false => tokens.clear(),
+                    }
                 }
             }
         }
+        previous = Some(span);
+    }
 
-        if !tokens.is_empty() {
-            combined.push(LineDescriptions {
-                line: lexems.line,
-                data: tokens.clone(),
-            });
-            tokens.clear();
+    /* A CONSTRUCT LEFT OPEN AT THE END OF THE FILE */
+    if let (Some(_first), Some(_previous)) = (tokens.first(), previous) {
+        if !matches!(context, Context::None | Context::Skip) {
+            diagnostics.push(missing_token(
+                context,
+                opened_braces_count > 0,
+                _first.span,
+                &_previous,
+            ));
         }
     }
 }
 
-/* VALIDATES CLASH DUE TO MISSING TOKEN E.G ";" OR "}" */
+/* TOKENS THAT MAY BEGIN A STRUCT, ENUM, CONSTANT OR FUNCTION AT FILE LEVEL */
+fn starts_definition(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Struct
+            | Token::Enum
+            | Token::Function
+            | Token::Identifier(_)
+            | Token::Uint(_)
+            | Token::Int(_)
+            | Token::Bytes(_)
+            | Token::Address
+            | Token::Bool
+            | Token::String
+            | Token::Mapping
+    )
+}
+
+/* VALIDATES CLASH DUE TO MISSING TOKEN E.G ";" OR "}". THE UNFINISHED CONSTRUCT IS DROPPED AND
+ONLY THE LAST TOKEN, WHICH STARTS THE NEXT ONE, IS KEPT */
 fn validate_clash(
     context: Context,
-    tokens: &Vec<Token>,
-    lexems: &Option<&LineDescriptions<String>>,
+    tokens: &mut Vec<LineDescriptions<Token>>,
+    previous: &Span,
+    diagnostics: &mut Diagnostics,
 ) {
-    if let Some(_lexems) = lexems {
-        if context != Context::None && !tokens.is_empty() {
-            CompilerError::SyntaxError(SyntaxError::MissingToken(match context {
-                Context::Contract | Context::Interface | Context::Library => "}",
-                _ => ";",
-            }))
-            .throw_with_file_info("Contract.sol", _lexems.lex().line);
-        }
-    } else {
-        CompilerError::InternalError("Unprocessible entity").throw();
+    if context == Context::Skip {
+        tokens.drain(..tokens.len() - 1);
+    } else if context != Context::None && tokens.len() > 1 {
+        diagnostics.push(missing_token(context, false, tokens[0].span, previous));
+        tokens.drain(..tokens.len() - 1);
     }
 }
+
+/* POINTS JUST PAST `previous`, WHERE THE CLOSING TOKEN OF THE CONSTRUCT WAS EXPECTED. `open`
+WHEN THE CONSTRUCT STILL HAS A `{` WITHOUT ITS `}` */
+fn missing_token(context: Context, open: bool, construct: Span, previous: &Span) -> Diagnostic {
+    let missing = match context {
+        Context::Contract | Context::Interface | Context::Library => "}",
+        Context::Definition if open => "}",
+        _ => ";",
+    };
+    CompilerError::SyntaxError(SyntaxError::MissingToken(missing.to_string()))
+        .at(Span {
+            start: previous.end,
+            end: previous.end,
+            column: previous.column + (previous.end - previous.start) as i32,
+            ..*previous
+        })
+        .with_secondary_label(construct, "unclosed construct starts here")
+}
+
+/* WHETHER EVERYTHING BEFORE THE LAST TOKEN IS `abstract` OR `upgradable`, SO THAT THE LAST
+TOKEN CONTINUES THE SAME CONTRACT HEADER */
+fn contract_prefix(tokens: &[LineDescriptions<Token>]) -> bool {
+    let mut prefix = tokens[..tokens.len() - 1]
+        .iter()
+        .map(|token| &token.data)
+        .filter(|token| **token != Token::Space)
+        .peekable();
+    prefix.peek().is_some()
+        && prefix.all(|token| matches!(token, Token::Abstract | Token::Upgradable))
+}
@@ -0,0 +1,2466 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Signed, Zero};
+use std::collections::{HashMap, HashSet};
+
+use super::const_evaluator::{
+    binary_symbol, fold, unary_symbol, yul_literal, ConstError, ConstEvaluator,
+};
+use crate::mods::types::{
+    ast::{
+        self, BinaryOperator, Block, CallArguments, ContractDefinition, ContractPart, Expression,
+        ExpressionKind, Identifier, Literal, Parameter, Statement, StatementKind, StorageLocation,
+        TypeName, TypeNameKind, UnaryOperator, VariableMutability, Visibility, YulBlock,
+        YulExpression, YulExpressionKind, YulStatement, YulStatementKind,
+    },
+    bindings::{Bindings, ContractId, Declaration},
+    compiler_errors::{CompilerError, SemanticError},
+    diagnostics::{Diagnostic, Diagnostics},
+    line_descriptors::Span,
+    opcodes::Opcode,
+    semantic_types::{ConstValue, FunctionKind, FunctionType, Type, TypeTable},
+    source_graph::SourceGraph,
+};
+
+/* AN ARGUMENT OF A CALL, CHECKED ONCE BEFORE IT IS MATCHED AGAINST ONE OR MORE SIGNATURES */
+struct Argument {
+    span: Span,
+    name: Option<String>,
+    ty: Type,
+}
+
+/* WHY A CALL DOES NOT MATCH A SIGNATURE */
+enum ArgumentError {
+    Count {
+        expected: usize,
+        found: usize,
+    },
+    Name(Span, String),
+    Mismatch {
+        span: Span,
+        expected: Box<Type>,
+        found: Box<Type>,
+    },
+}
+
+/* CHECKS THE TYPES OF EVERY EXPRESSION AND DECLARATION IN `graph`. NAMES MUST HAVE BEEN
+RESOLVED INTO `bindings` ALREADY */
+pub fn check_types(
+    graph: &SourceGraph,
+    bindings: &Bindings,
+    diagnostics: &mut Diagnostics,
+) -> TypeTable {
+    let mut checker = TypeChecker {
+        graph,
+        bindings,
+        diagnostics,
+        table: TypeTable::default(),
+        file_id: 0,
+        contract: None,
+        returns: None,
+        storage_locals: HashSet::new(),
+        assembly_functions: HashMap::new(),
+        assembly_loop: false,
+        assembly_function: false,
+    };
+    for unit in &graph.units {
+        checker.file_id = unit.file_id;
+        for error in &unit.ast.custom_errors {
+            checker.check_parameters(&error.parameters, false);
+        }
+        for (index, contract) in unit.ast.contracts.iter().enumerate() {
+            checker.check_contract(index, contract);
+        }
+    }
+    checker.table
+}
+
+struct TypeChecker<'a> {
+    graph: &'a SourceGraph,
+    bindings: &'a Bindings,
+    diagnostics: &'a mut Diagnostics,
+    table: TypeTable,
+    file_id: usize,
+    contract: Option<usize>,
+    /* RETURN TYPES OF THE FUNCTION BEING CHECKED, None IN MODIFIERS */
+    returns: Option<Vec<Type>>,
+    /* LOCAL VARIABLES DECLARED `storage`, WHICH INLINE ASSEMBLY ONLY SEES THROUGH `.slot` */
+    storage_locals: HashSet<Span>,
+    /* NUMBER OF PARAMETERS AND RETURN VARIABLES OF THE FUNCTIONS DEFINED IN INLINE ASSEMBLY */
+    assembly_functions: HashMap<Span, (usize, usize)>,
+    /* WHETHER `break` AND `continue`, OR `leave`, ARE ALLOWED WHERE THE CHECKER IS */
+    assembly_loop: bool,
+    assembly_function: bool,
+}
+
+impl<'a> TypeChecker<'a> {
+    /* ------------------------------ CONTRACTS ------------------------------ */
+
+    fn check_contract(&mut self, index: usize, contract: &ContractDefinition) {
+        self.contract = Some(index);
+        for base in &contract.bases {
+            if let (Some(arguments), Some(Declaration::Contract { file_id, contract })) = (
+                &base.arguments,
+                self.declaration_of(base.name.last().unwrap()),
+            ) {
+                let constructor = self.constructor_type((file_id, contract));
+                let arguments = self.positional_arguments(arguments);
+                self.apply_call(base.span, &Type::Function(constructor), arguments);
+            }
+        }
+        self.check_redeclarations(contract);
+
+        for (part_index, part) in contract.parts.iter().enumerate() {
+            match part {
+                ContractPart::StateVariable(variable) => {
+                    self.check_type_name(&variable.ty);
+                    let ty = self.type_of_name(&variable.ty);
+                    self.table
+                        .declarations
+                        .insert(variable.name.span, ty.clone());
+                    if let Some(_initializer) = &variable.initializer {
+                        let errors = self.diagnostics.error_count();
+                        self.expect_declared(_initializer, &ty, variable.ty.span);
+                        /* CONSTANTS THAT ONLY HAVE A VALUE AT RUNTIME, SUCH AS A `keccak256`
+                        OF A LITERAL, ARE FINE. OVERFLOWS AND CYCLES ARE NOT */
+                        if variable.mutability == Some(VariableMutability::Constant)
+                            && self.diagnostics.error_count() == errors
+                        {
+                            match ConstEvaluator::new(self.graph, self.bindings).constant(variable)
+                            {
+                                Ok(value) => {
+                                    self.table.constants.insert(variable.name.span, value);
+                                }
+                                Err(ConstError::Invalid(diagnostic)) => {
+                                    self.diagnostics.push(*diagnostic)
+                                }
+                                Err(ConstError::NotConstant(_)) => {}
+                            }
+                        }
+                    }
+                }
+                ContractPart::Function(function) => {
+                    self.check_parameters(&function.parameters, true);
+                    self.check_parameters(&function.returns, true);
+                    self.check_special_function(function);
+                    self.returns = Some(
+                        function
+                            .returns
+                            .iter()
+                            .map(|parameter| self.type_of_name(&parameter.ty))
+                            .collect(),
+                    );
+                    for modifier in &function.modifiers {
+                        let callee = match self.declaration_of(modifier.name.last().unwrap()) {
+                            Some(Declaration::Contract { file_id, contract }) => {
+                                Type::Function(self.constructor_type((file_id, contract)))
+                            }
+                            Some(_declaration @ Declaration::Member { .. }) => {
+                                match self.part(_declaration) {
+                                    Some(ContractPart::Modifier(definition)) => {
+                                        Type::Function(self.signature(
+                                            FunctionKind::Internal,
+                                            &definition.parameters,
+                                            &[],
+                                            None,
+                                        ))
+                                    }
+                                    _ => Type::Unknown,
+                                }
+                            }
+                            _ => Type::Unknown,
+                        };
+                        let arguments =
+                            self.positional_arguments(modifier.arguments.as_deref().unwrap_or(&[]));
+                        self.apply_call(modifier.span, &callee, arguments);
+                    }
+                    if let Some(body) = &function.body {
+                        self.check_block(body);
+                    }
+                    self.returns = None;
+                }
+                ContractPart::Modifier(modifier) => {
+                    self.check_parameters(&modifier.parameters, true);
+                    if let Some(body) = &modifier.body {
+                        self.check_block(body);
+                    }
+                }
+                ContractPart::Event(event) => self.check_parameters(&event.parameters, false),
+                ContractPart::Error(error) => self.check_parameters(&error.parameters, false),
+                ContractPart::Struct(definition) => {
+                    for field in &definition.fields {
+                        self.check_type_name(&field.ty);
+                    }
+                    let id = (self.file_id, index, part_index);
+                    if let Some(_field) = definition.fields.iter().find(|field| {
+                        self.contains_struct(&self.type_of_name(&field.ty), id, &mut Vec::new())
+                    }) {
+                        let name = &definition.name.name;
+                        self.diagnostics.push(
+                            CompilerError::SemanticError(SemanticError::InvalidOperation(format!(
+                                "recursive struct `{name}`"
+                            )))
+                            .diagnostic()
+                            .with_label(
+                                _field.ty.span,
+                                format!("`{}` contains `{name}` itself", _field.name.name),
+                            )
+                            .with_secondary_label(definition.name.span, "declared here"),
+                        );
+                    }
+                }
+                ContractPart::Using(using) => {
+                    if let Some(_target) = &using.target {
+                        self.check_type_name(_target);
+                    }
+                }
+                ContractPart::Cron(cron) => {
+                    self.expect(&cron.schedule, &Type::String);
+                    if let Some(_max_runs) = &cron.max_runs {
+                        self.expect(_max_runs, &Type::UINT256);
+                    }
+                    self.returns = Some(Vec::new());
+                    self.check_block(&cron.body);
+                    self.returns = None;
+                }
+                ContractPart::Enum(_) => {}
+            }
+        }
+        self.contract = None;
+    }
+
+    /* FUNCTIONS AND EVENTS MAY SHARE A NAME ONLY IF THEIR PARAMETER TYPES DIFFER */
+    fn check_redeclarations(&mut self, contract: &ContractDefinition) {
+        let signatures: Vec<(bool, &Identifier, Vec<Type>)> = contract
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                ContractPart::Function(function)
+                    if function.kind == ast::FunctionKind::Function =>
+                {
+                    Some((false, function.name.as_ref()?, &function.parameters))
+                }
+                ContractPart::Event(event) => Some((true, &event.name, &event.parameters)),
+                _ => None,
+            })
+            .map(|(event, name, parameters)| {
+                let types = parameters
+                    .iter()
+                    .map(|parameter| self.type_of_name(&parameter.ty))
+                    .collect();
+                (event, name, types)
+            })
+            .collect();
+        for (index, (event, name, types)) in signatures.iter().enumerate() {
+            if let Some((_, _previous, _)) = signatures[..index]
+                .iter()
+                .find(|other| other.0 == *event && other.1.name == name.name && other.2 == *types)
+            {
+                self.diagnostics.push(
+                    CompilerError::SemanticError(SemanticError::Redeclaration(name.name.clone()))
+                        .at(name.span)
+                        .with_secondary_label(_previous.span, "previous declaration here"),
+                );
+            }
+        }
+    }
+
+    /* `receive` TAKES AND RETURNS NOTHING, `fallback` EITHER THAT OR `bytes` IN AND OUT. BOTH ARE
+    `external`, `receive` IS ALWAYS `payable` AND `fallback` CANNOT BE `view` OR `pure` */
+    fn check_special_function(&mut self, function: &ast::FunctionDefinition) {
+        let name = match function.kind {
+            ast::FunctionKind::Receive => "receive",
+            ast::FunctionKind::Fallback => "fallback",
+            _ => return,
+        };
+        let types = |parameters: &[Parameter]| {
+            parameters
+                .iter()
+                .map(|parameter| self.type_of_name(&parameter.ty))
+                .collect::<Vec<_>>()
+        };
+        let signature = (types(&function.parameters), types(&function.returns));
+        let parameters = match function.kind {
+            ast::FunctionKind::Receive
+                if !function.parameters.is_empty() || !function.returns.is_empty() =>
+            {
+                Some("`receive` cannot take parameters or return values")
+            }
+            ast::FunctionKind::Fallback
+                if !matches!(
+                    (&signature.0[..], &signature.1[..]),
+                    ([], []) | ([Type::Bytes], [Type::Bytes])
+                ) =>
+            {
+                Some("`fallback` must take no parameters, or take and return `bytes`")
+            }
+            _ => None,
+        };
+        if let Some(_problem) = parameters {
+            self.error(
+                function.span,
+                _problem.to_string(),
+                "invalid parameters for this function",
+            );
+        }
+        if function.visibility != Some(ast::Visibility::External) {
+            self.error(
+                function.span,
+                format!("`{name}` must be `external`"),
+                "declared without `external`",
+            );
+        }
+        match (function.kind, function.mutability) {
+            (ast::FunctionKind::Receive, mutability)
+                if mutability != Some(ast::StateMutability::Payable) =>
+            {
+                self.error(
+                    function.span,
+                    "`receive` must be `payable`".to_string(),
+                    "declared without `payable`",
+                )
+            }
+            (
+                ast::FunctionKind::Fallback,
+                Some(ast::StateMutability::View | ast::StateMutability::Pure),
+            ) => self.error(
+                function.span,
+                "`fallback` cannot be `view` or `pure`".to_string(),
+                "it must be able to change state",
+            ),
+            _ => {}
+        }
+    }
+
+    fn check_parameters(&mut self, parameters: &[Parameter], declare: bool) {
+        for parameter in parameters {
+            self.check_type_name(&parameter.ty);
+            if let (true, Some(name)) = (declare, &parameter.name) {
+                let ty = self.type_of_name(&parameter.ty);
+                self.table.declarations.insert(name.span, ty);
+                if parameter.storage == Some(StorageLocation::Storage) {
+                    self.storage_locals.insert(name.span);
+                }
+            }
+        }
+    }
+
+    /* ------------------------------ TYPE NAMES ------------------------------ */
+
+    fn check_type_name(&mut self, ty: &TypeName) {
+        let resolved = self.type_of_name(ty);
+        self.table.type_names.insert(ty.span, resolved);
+        match &ty.kind {
+            TypeNameKind::Elementary(_) | TypeNameKind::UserDefined(_) => {}
+            TypeNameKind::Array { base, length } => {
+                self.check_type_name(base);
+                if let Some(_length) = length {
+                    if let Err(error) =
+                        ConstEvaluator::new(self.graph, self.bindings).array_length(_length)
+                    {
+                        self.diagnostics.push(error.diagnostic());
+                    }
+                }
+            }
+            TypeNameKind::Mapping { key, value, .. } => {
+                self.check_type_name(key);
+                self.check_type_name(value);
+                let key_type = self.type_of_name(key);
+                if !(key_type.is_value_type()
+                    || matches!(key_type, Type::String | Type::Bytes | Type::Unknown))
+                {
+                    self.error(
+                        key.span,
+                        format!("`{key_type}` cannot be a mapping key"),
+                        "only value types, `string` and `bytes` can be mapping keys",
+                    );
+                }
+            }
+            TypeNameKind::Function {
+                parameters,
+                returns,
+                ..
+            } => {
+                self.check_parameters(parameters, false);
+                self.check_parameters(returns, false);
+            }
+        }
+    }
+
+    fn type_of_name(&self, ty: &TypeName) -> Type {
+        match &ty.kind {
+            TypeNameKind::Elementary(elementary) => Type::elementary(*elementary),
+            TypeNameKind::UserDefined(path) => match self.declaration_of(path.last().unwrap()) {
+                Some(declaration) => match self.declaration_type(declaration) {
+                    Type::TypeType(ty) => *ty,
+                    _ => Type::Unknown,
+                },
+                None => Type::Unknown,
+            },
+            TypeNameKind::Array { base, length } => Type::Array {
+                base: Box::new(self.type_of_name(base)),
+                length: length.as_ref().and_then(|length| {
+                    ConstEvaluator::new(self.graph, self.bindings)
+                        .array_length(length)
+                        .ok()
+                }),
+            },
+            TypeNameKind::Mapping { key, value, .. } => Type::Mapping {
+                key: Box::new(self.type_of_name(key)),
+                value: Box::new(self.type_of_name(value)),
+            },
+            TypeNameKind::Function {
+                parameters,
+                visibility,
+                mutability,
+                returns,
+            } => {
+                let kind = match visibility {
+                    Some(Visibility::External) => FunctionKind::External,
+                    _ => FunctionKind::Internal,
+                };
+                Type::Function(self.signature(kind, parameters, returns, *mutability))
+            }
+        }
+    }
+
+    /* ------------------------------ DECLARATIONS ------------------------------ */
+
+    /* THE DECLARATION A NAME RESOLVED TO, IF IT RESOLVED TO EXACTLY ONE */
+    fn declaration_of(&self, identifier: &Identifier) -> Option<Declaration> {
+        match self.bindings.get(&identifier.span) {
+            Some([declaration]) => Some(*declaration),
+            _ => None,
+        }
+    }
+
+    fn part(&self, declaration: Declaration) -> Option<&'a ContractPart> {
+        match declaration {
+            Declaration::Member {
+                file_id,
+                contract,
+                part,
+            } => Some(&self.graph.units[file_id].ast.contracts[contract].parts[part]),
+            _ => None,
+        }
+    }
+
+    fn contract_definition(&self, (file_id, contract): ContractId) -> &'a ContractDefinition {
+        &self.graph.units[file_id].ast.contracts[contract]
+    }
+
+    fn contract_type(&self, (file_id, contract): ContractId) -> Type {
+        Type::Contract {
+            name: self
+                .contract_definition((file_id, contract))
+                .name
+                .name
+                .clone(),
+            file_id,
+            contract,
+        }
+    }
+
+    fn declaration_type(&self, declaration: Declaration) -> Type {
+        match declaration {
+            Declaration::Contract { file_id, contract } => {
+                Type::TypeType(Box::new(self.contract_type((file_id, contract))))
+            }
+            Declaration::Error { file_id, index } => Type::Function(self.signature(
+                FunctionKind::Error,
+                &self.graph.units[file_id].ast.custom_errors[index].parameters,
+                &[],
+                None,
+            )),
+            Declaration::Unit(file_id) => Type::Module(file_id),
+            Declaration::Member {
+                file_id,
+                contract,
+                part,
+            } => match self.part(declaration).unwrap() {
+                ContractPart::StateVariable(variable) => self.type_of_name(&variable.ty),
+                ContractPart::Function(function) => {
+                    let kind = match function.visibility {
+                        Some(Visibility::External) => FunctionKind::External,
+                        _ => FunctionKind::Internal,
+                    };
+                    Type::Function(self.signature(
+                        kind,
+                        &function.parameters,
+                        &function.returns,
+                        function.mutability,
+                    ))
+                }
+                ContractPart::Modifier(_) => Type::Modifier,
+                ContractPart::Event(event) => Type::Function(self.signature(
+                    FunctionKind::Event,
+                    &event.parameters,
+                    &[],
+                    None,
+                )),
+                ContractPart::Error(error) => Type::Function(self.signature(
+                    FunctionKind::Error,
+                    &error.parameters,
+                    &[],
+                    None,
+                )),
+                ContractPart::Struct(definition) => Type::TypeType(Box::new(Type::Struct {
+                    name: definition.name.name.clone(),
+                    file_id,
+                    contract,
+                    part,
+                })),
+                ContractPart::Enum(definition) => Type::TypeType(Box::new(Type::Enum {
+                    name: definition.name.name.clone(),
+                    file_id,
+                    contract,
+                    part,
+                })),
+                ContractPart::Using(_) | ContractPart::Cron(_) => Type::Unknown,
+            },
+            Declaration::EnumValue {
+                file_id,
+                contract,
+                part,
+                ..
+            } => match self.declaration_type(Declaration::Member {
+                file_id,
+                contract,
+                part,
+            }) {
+                Type::TypeType(ty) => *ty,
+                ty => ty,
+            },
+            Declaration::Local(span) => self
+                .table
+                .declarations
+                .get(&span)
+                .cloned()
+                .unwrap_or(Type::Unknown),
+            Declaration::Builtin("this") => match self.contract {
+                Some(_contract) => self.contract_type((self.file_id, _contract)),
+                None => Type::Unknown,
+            },
+            Declaration::Builtin("super") => Type::Unknown,
+            Declaration::Builtin(name) => Type::Magic(name),
+        }
+    }
+
+    /* TYPE OF A NAME, AN OVERLOAD SET WHEN IT MEANS SEVERAL FUNCTIONS OR EVENTS */
+    fn declarations_type(&self, declarations: &[Declaration]) -> Type {
+        match declarations {
+            [declaration] => self.declaration_type(*declaration),
+            [] => Type::Unknown,
+            _ => Type::Overloaded(
+                declarations
+                    .iter()
+                    .filter_map(|declaration| match self.declaration_type(*declaration) {
+                        Type::Function(function) => Some(function),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn signature(
+        &self,
+        kind: FunctionKind,
+        parameters: &[Parameter],
+        returns: &[Parameter],
+        mutability: Option<ast::StateMutability>,
+    ) -> FunctionType {
+        FunctionType {
+            kind,
+            parameters: parameters
+                .iter()
+                .map(|parameter| self.type_of_name(&parameter.ty))
+                .collect(),
+            parameter_names: parameters
+                .iter()
+                .map(|parameter| parameter.name.as_ref().map(|name| name.name.clone()))
+                .collect(),
+            returns: returns
+                .iter()
+                .map(|parameter| self.type_of_name(&parameter.ty))
+                .collect(),
+            mutability,
+        }
+    }
+
+    /* `new C(..)` AND BASE CONSTRUCTOR ARGUMENTS. A CONTRACT WITHOUT A CONSTRUCTOR TAKES NONE */
+    fn constructor_type(&self, contract: ContractId) -> FunctionType {
+        let definition = self.contract_definition(contract);
+        let constructor = definition.parts.iter().find_map(|part| match part {
+            ContractPart::Function(function) if function.kind == ast::FunctionKind::Constructor => {
+                Some(function)
+            }
+            _ => None,
+        });
+        let mut signature = match constructor {
+            Some(_constructor) => self.signature(
+                FunctionKind::External,
+                &_constructor.parameters,
+                &[],
+                _constructor.mutability,
+            ),
+            None => self.signature(FunctionKind::External, &[], &[], None),
+        };
+        signature.returns = vec![self.contract_type(contract)];
+        signature
+    }
+
+    /* ------------------------------ STATEMENTS ------------------------------ */
+
+    fn check_block(&mut self, block: &Block) {
+        for statement in &block.statements {
+            self.check_statement(statement);
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement) {
+        match &statement.kind {
+            StatementKind::Block(block) => self.check_block(block),
+            StatementKind::VariableDeclaration {
+                declarations,
+                initializer,
+            } => {
+                for declaration in declarations.iter().flatten() {
+                    self.check_type_name(&declaration.ty);
+                    if declaration.storage == Some(StorageLocation::Storage) {
+                        self.storage_locals.insert(declaration.name.span);
+                    }
+                }
+                let declared: Vec<Option<Type>> = declarations
+                    .iter()
+                    .map(|declaration| {
+                        declaration
+                            .as_ref()
+                            .map(|declaration| self.type_of_name(&declaration.ty))
+                    })
+                    .collect();
+                match (declarations.as_slice(), initializer) {
+                    ([Some(declaration)], Some(_initializer)) => {
+                        self.expect_declared(
+                            _initializer,
+                            declared[0].as_ref().unwrap(),
+                            declaration.ty.span,
+                        );
+                    }
+                    (_, Some(_initializer)) => {
+                        let found = self.check_expression(_initializer);
+                        self.expect_tuple(_initializer, &declared, &found);
+                    }
+                    (_, None) => {}
+                }
+                for (declaration, ty) in declarations.iter().zip(declared) {
+                    if let (Some(_declaration), Some(_ty)) = (declaration, ty) {
+                        self.table.declarations.insert(_declaration.name.span, _ty);
+                    }
+                }
+            }
+            StatementKind::Expression(expression) => {
+                self.check_expression(expression);
+            }
+            StatementKind::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                self.expect(condition, &Type::Bool);
+                self.check_statement(then);
+                if let Some(_otherwise) = otherwise {
+                    self.check_statement(_otherwise);
+                }
+            }
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                if let Some(_init) = init {
+                    self.check_statement(_init);
+                }
+                if let Some(_condition) = condition {
+                    self.expect(_condition, &Type::Bool);
+                }
+                if let Some(_update) = update {
+                    self.check_expression(_update);
+                }
+                self.check_statement(body);
+            }
+            StatementKind::While { condition, body }
+            | StatementKind::DoWhile { body, condition } => {
+                self.expect(condition, &Type::Bool);
+                self.check_statement(body);
+            }
+            StatementKind::Return(expression) => {
+                let returns = self.returns.clone();
+                match (returns, expression) {
+                    (Some(_returns), Some(_expression)) if _returns.len() == 1 => {
+                        self.expect(_expression, &_returns[0]);
+                    }
+                    (Some(_returns), Some(_expression)) if _returns.is_empty() => {
+                        self.check_expression(_expression);
+                        self.error(
+                            _expression.span,
+                            "unexpected return value".to_string(),
+                            "the function does not return anything",
+                        );
+                    }
+                    (Some(_returns), Some(_expression)) => {
+                        let found = self.check_expression(_expression);
+                        let expected: Vec<Option<Type>> = _returns.into_iter().map(Some).collect();
+                        self.expect_tuple(_expression, &expected, &found);
+                    }
+                    (None, Some(_expression)) => {
+                        self.check_expression(_expression);
+                        self.error(
+                            _expression.span,
+                            "unexpected return value".to_string(),
+                            "modifiers cannot return values",
+                        );
+                    }
+                    (_, None) => {}
+                }
+            }
+            StatementKind::Emit(expression) => match &expression.kind {
+                ExpressionKind::FunctionCall { callee, arguments } => {
+                    let callee_type = self.check_expression(callee);
+                    let is_event = match &callee_type {
+                        Type::Function(function) => function.kind == FunctionKind::Event,
+                        Type::Overloaded(functions) => functions
+                            .iter()
+                            .all(|function| function.kind == FunctionKind::Event),
+                        _ => callee_type.is_unknown(),
+                    };
+                    if !is_event {
+                        self.error(
+                            callee.span,
+                            "expected an event".to_string(),
+                            format!("found `{callee_type}`"),
+                        );
+                    }
+                    let arguments = self.arguments(arguments);
+                    let ty = self.apply_call(expression.span, &callee_type, arguments);
+                    self.table.expressions.insert(expression.span, ty);
+                }
+                _ => {
+                    self.check_expression(expression);
+                    self.error(
+                        expression.span,
+                        "expected an event call".to_string(),
+                        "`emit` must be followed by an event call",
+                    );
+                }
+            },
+            StatementKind::Revert { error, arguments } => {
+                let callee = match error {
+                    Some(_error) => match self.declaration_of(_error.last().unwrap()) {
+                        Some(declaration) => self.declaration_type(declaration),
+                        None => Type::Unknown,
+                    },
+                    None => Type::Magic("revert"),
+                };
+                let arguments = self.arguments(arguments);
+                self.apply_call(statement.span, &callee, arguments);
+            }
+            StatementKind::Assembly { flags, body } => {
+                for flag in flags.iter().filter(|flag| *flag != "memory-safe") {
+                    self.assembly_error(
+                        statement.span,
+                        format!("unknown inline assembly flag \"{flag}\""),
+                        "only \"memory-safe\" is supported",
+                    );
+                }
+                self.check_yul_block(body);
+            }
+            StatementKind::Break | StatementKind::Continue | StatementKind::Placeholder => {}
+        }
+    }
+
+    /* ------------------------------ INLINE ASSEMBLY ------------------------------ */
+
+    fn check_yul_block(&mut self, block: &YulBlock) {
+        for statement in &block.statements {
+            if let YulStatementKind::Function(function) = &statement.kind {
+                self.assembly_functions.insert(
+                    function.name.span,
+                    (function.parameters.len(), function.returns.len()),
+                );
+            }
+        }
+        for statement in &block.statements {
+            self.check_yul_statement(statement);
+        }
+    }
+
+    fn check_yul_statement(&mut self, statement: &YulStatement) {
+        match &statement.kind {
+            YulStatementKind::Block(block) => self.check_yul_block(block),
+            YulStatementKind::VariableDeclaration { names, value } => {
+                if let Some(_value) = value {
+                    let found = self.check_yul_expression(_value);
+                    self.expect_values(_value.span, names.len(), found);
+                }
+            }
+            YulStatementKind::Assignment { targets, value } => {
+                for target in targets {
+                    self.check_yul_path(target, true);
+                }
+                let found = self.check_yul_expression(value);
+                self.expect_values(value.span, targets.len(), found);
+            }
+            YulStatementKind::Expression(expression) => {
+                let found = self.check_yul_expression(expression);
+                self.expect_values(expression.span, 0, found);
+            }
+            YulStatementKind::If { condition, body } => {
+                let found = self.check_yul_expression(condition);
+                self.expect_values(condition.span, 1, found);
+                self.check_yul_block(body);
+            }
+            YulStatementKind::Switch {
+                expression,
+                cases,
+                default,
+            } => {
+                let found = self.check_yul_expression(expression);
+                self.expect_values(expression.span, 1, found);
+                let mut seen = Vec::new();
+                for (value, body) in cases {
+                    if let YulExpressionKind::Literal(_literal) = &value.kind {
+                        match yul_literal(value.span, _literal) {
+                            Ok(_value) if seen.contains(&_value) => self.assembly_error(
+                                value.span,
+                                "duplicate case in a `switch`".to_string(),
+                                "this value is already handled",
+                            ),
+                            Ok(_value) => seen.push(_value),
+                            Err(ConstError::Invalid(diagnostic)) => {
+                                self.diagnostics.push(*diagnostic)
+                            }
+                            Err(ConstError::NotConstant(_)) => {}
+                        }
+                    }
+                    self.check_yul_block(body);
+                }
+                if let Some(_default) = default {
+                    self.check_yul_block(_default);
+                }
+            }
+            /* `break` AND `continue` ONLY BELONG TO THE BODY, NOT TO `init` OR `post` */
+            YulStatementKind::For {
+                init,
+                condition,
+                post,
+                body,
+            } => {
+                let in_loop = std::mem::replace(&mut self.assembly_loop, false);
+                self.check_yul_block(init);
+                let found = self.check_yul_expression(condition);
+                self.expect_values(condition.span, 1, found);
+                self.check_yul_block(post);
+                self.assembly_loop = true;
+                self.check_yul_block(body);
+                self.assembly_loop = in_loop;
+            }
+            YulStatementKind::Function(function) => {
+                let in_loop = std::mem::replace(&mut self.assembly_loop, false);
+                let in_function = std::mem::replace(&mut self.assembly_function, true);
+                self.check_yul_block(&function.body);
+                self.assembly_loop = in_loop;
+                self.assembly_function = in_function;
+            }
+            YulStatementKind::Break | YulStatementKind::Continue if !self.assembly_loop => {
+                self.assembly_error(
+                    statement.span,
+                    "`break` and `continue` can only be used in the body of a `for` loop"
+                        .to_string(),
+                    "outside of a loop body",
+                );
+            }
+            YulStatementKind::Leave if !self.assembly_function => {
+                self.assembly_error(
+                    statement.span,
+                    "`leave` can only be used in a function".to_string(),
+                    "outside of a function",
+                );
+            }
+            YulStatementKind::Break | YulStatementKind::Continue | YulStatementKind::Leave => {}
+        }
+    }
+
+    /* THE NUMBER OF VALUES `expression` YIELDS, None WHEN IT IS NOT KNOWN BECAUSE OF AN ERROR
+    REPORTED ALREADY */
+    fn check_yul_expression(&mut self, expression: &YulExpression) -> Option<usize> {
+        match &expression.kind {
+            YulExpressionKind::Literal(literal) => {
+                if let Err(ConstError::Invalid(diagnostic)) = yul_literal(expression.span, literal)
+                {
+                    self.diagnostics.push(*diagnostic);
+                }
+                Some(1)
+            }
+            YulExpressionKind::Path(path) => {
+                self.check_yul_path(path, false);
+                Some(1)
+            }
+            YulExpressionKind::Call {
+                function,
+                arguments,
+            } => {
+                for argument in arguments {
+                    let found = self.check_yul_expression(argument);
+                    self.expect_values(argument.span, 1, found);
+                }
+                let (parameters, returns) = match self.declaration_of(function) {
+                    Some(Declaration::Builtin(name)) if Opcode::builtin(name).is_some() => {
+                        Opcode::builtin(name).unwrap().1.stack_io()
+                    }
+                    Some(Declaration::Local(span))
+                        if self.assembly_functions.contains_key(&span) =>
+                    {
+                        self.assembly_functions[&span]
+                    }
+                    Some(_) => {
+                        self.assembly_error(
+                            function.span,
+                            format!("`{}` is not a function", function.name),
+                            "cannot be called",
+                        );
+                        return None;
+                    }
+                    None => return None,
+                };
+                if arguments.len() != parameters {
+                    self.assembly_error(
+                        expression.span,
+                        format!(
+                            "`{}` expects {parameters} argument{} but {} {} given",
+                            function.name,
+                            if parameters == 1 { "" } else { "s" },
+                            arguments.len(),
+                            if arguments.len() == 1 { "was" } else { "were" }
+                        ),
+                        "wrong number of arguments",
+                    );
+                }
+                Some(returns)
+            }
+        }
+    }
+
+    /* A VARIABLE READ OR ASSIGNED BY INLINE ASSEMBLY. LOCALS ARE WORDS, EXCEPT THOSE IN STORAGE
+    WHICH ONLY HAVE A `.slot` AND AN `.offset`, LIKE STATE VARIABLES. CONSTANTS CAN BE READ */
+    fn check_yul_path(&mut self, path: &[Identifier], assigned: bool) {
+        let name = &path[0];
+        let suffix = path.get(1).map(|suffix| suffix.name.as_str());
+        if let Some(_extra) = path.get(2) {
+            return self.assembly_error(
+                _extra.span,
+                format!("unexpected `.{}` in inline assembly", _extra.name),
+                "only `.slot` and `.offset` are allowed",
+            );
+        }
+        if let Some(_suffix) = path
+            .get(1)
+            .filter(|_| !matches!(suffix, Some("slot" | "offset")))
+        {
+            return self.assembly_error(
+                _suffix.span,
+                format!("unexpected `.{}` in inline assembly", _suffix.name),
+                "only `.slot` and `.offset` are allowed",
+            );
+        }
+        let Some(declaration) = self.declaration_of(name) else {
+            return;
+        };
+        let problem = match declaration {
+            Declaration::Local(span) if self.assembly_functions.contains_key(&span) => {
+                Some(format!("`{}` is a function, not a variable", name.name))
+            }
+            Declaration::Local(span) if self.storage_locals.contains(&span) => match suffix {
+                None => Some(format!(
+                    "`{}` is in storage, use `{0}.slot` and `{0}.offset` instead",
+                    name.name
+                )),
+                Some("offset") if assigned => {
+                    Some("the `.offset` of a storage pointer cannot be assigned".to_string())
+                }
+                Some(_) => None,
+            },
+            Declaration::Local(_) => suffix.map(|_suffix| {
+                format!("`{}` is not in storage and has no `.{_suffix}`", name.name)
+            }),
+            Declaration::Builtin(_name) if Opcode::builtin(_name).is_some() => {
+                Some(format!("`{}` is a builtin, not a variable", name.name))
+            }
+            _ => match self.part(declaration) {
+                Some(ContractPart::StateVariable(_variable)) => {
+                    match (_variable.mutability, suffix) {
+                        (_, _) if assigned => Some(format!(
+                            "`{}` is a state variable and cannot be assigned from inline assembly",
+                            name.name
+                        )),
+                        (Some(VariableMutability::Constant), None)
+                            if self.type_of_name(&_variable.ty).is_value_type() =>
+                        {
+                            None
+                        }
+                        (Some(VariableMutability::Constant), _) => Some(format!(
+                            "only constants of value types can be read from inline assembly, \
+                             `{}` is not one",
+                            name.name
+                        )),
+                        (Some(VariableMutability::Immutable), _) => Some(format!(
+                            "immutable `{}` cannot be accessed from inline assembly",
+                            name.name
+                        )),
+                        (_, None) => Some(format!(
+                            "`{}` is in storage, use `{0}.slot` and `{0}.offset` instead",
+                            name.name
+                        )),
+                        (_, Some(_)) => None,
+                    }
+                }
+                _ => Some(format!(
+                    "`{}` cannot be accessed from inline assembly, only local and state \
+                     variables can",
+                    name.name
+                )),
+            },
+        };
+        if let Some(_problem) = problem {
+            self.assembly_error(name.span, _problem, "invalid in inline assembly");
+        }
+    }
+
+    fn expect_values(&mut self, span: Span, expected: usize, found: Option<usize>) {
+        if let Some(_found) = found.filter(|found| *found != expected) {
+            self.assembly_error(
+                span,
+                format!("expected {expected} values but the expression yields {_found}"),
+                format!("yields {_found} values"),
+            );
+        }
+    }
+
+    /* ------------------------------ EXPRESSIONS ------------------------------ */
+
+    fn check_expression(&mut self, expression: &Expression) -> Type {
+        let ty = match &expression.kind {
+            ExpressionKind::Literal(literal) => match literal {
+                Literal::Bool(_) => Type::Bool,
+                Literal::Number(value, digits) => {
+                    Type::RationalConstant(BigRational::from(value.clone()), *digits)
+                }
+                Literal::Rational(value) => Type::RationalConstant(value.clone(), None),
+                Literal::String(value) => Type::StringLiteral(value.as_bytes().to_vec()),
+                Literal::HexString(value) => Type::StringLiteral(value.clone()),
+                Literal::Address(_) => Type::Address { payable: false },
+            },
+            ExpressionKind::Variable(identifier) => match self.bindings.get(&identifier.span) {
+                Some(declarations) => self.declarations_type(declarations),
+                None => Type::Unknown,
+            },
+            ExpressionKind::ElementaryType(elementary) => {
+                Type::TypeType(Box::new(Type::elementary(*elementary)))
+            }
+            ExpressionKind::MemberAccess { expression, member } => {
+                let base = self.check_expression(expression);
+                match &expression.kind {
+                    ExpressionKind::Variable(_base)
+                        if self.declaration_of(_base) == Some(Declaration::Builtin("super")) =>
+                    {
+                        self.super_functions(member)
+                    }
+                    _ => self.member_type(&base, member),
+                }
+            }
+            ExpressionKind::Index { base, index } => self.index_type(base, index.as_deref()),
+            ExpressionKind::FunctionCall { callee, arguments } => {
+                let callee_type = self.check_expression(callee);
+                if let Type::Function(function) = &callee_type {
+                    if function.kind == FunctionKind::Event {
+                        self.error(
+                            callee.span,
+                            "events cannot be called".to_string(),
+                            "use `emit` to emit an event",
+                        );
+                    }
+                }
+                let arguments = self.arguments(arguments);
+                self.apply_call(expression.span, &callee_type, arguments)
+            }
+            ExpressionKind::CallOptions { callee, options } => {
+                let callee_type = self.check_expression(callee);
+                for option in options {
+                    match option.name.name.as_str() {
+                        "value" | "gas" => self.expect(&option.value, &Type::UINT256),
+                        "salt" => self.expect(&option.value, &Type::FixedBytes(32)),
+                        _ => {
+                            self.check_expression(&option.value);
+                            self.error(
+                                option.name.span,
+                                format!("unknown call option `{}`", option.name.name),
+                                "expected `value`, `gas` or `salt`",
+                            );
+                        }
+                    }
+                }
+                callee_type
+            }
+            ExpressionKind::Unary { operator, operand } => {
+                let operand_type = self.check_expression(operand);
+                self.unary_type(expression.span, *operator, operand, operand_type)
+            }
+            ExpressionKind::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let left_type = self.check_expression(left);
+                let right_type = self.check_expression(right);
+                self.binary_type(expression.span, *operator, left_type, right_type)
+            }
+            ExpressionKind::Assignment {
+                operator,
+                left,
+                right,
+            } => {
+                let left_type = self.check_expression(left);
+                self.check_assignable(left);
+                if let Type::Mapping { .. } = left_type {
+                    self.error(
+                        left.span,
+                        "mappings cannot be assigned to".to_string(),
+                        "a mapping",
+                    );
+                }
+                match operator {
+                    None => match &left_type {
+                        Type::Tuple(components) => {
+                            let found = self.check_expression(right);
+                            self.expect_tuple(right, components, &found);
+                        }
+                        _ => self.expect(right, &left_type),
+                    },
+                    Some(_operator) => {
+                        let right_type = self.check_expression(right);
+                        let result = self.binary_type(
+                            expression.span,
+                            *_operator,
+                            left_type.clone(),
+                            right_type.clone(),
+                        );
+                        /* THE RIGHT OPERAND IS WHAT DOES NOT FIT, NOT THE TYPE IT WIDENED TO */
+                        if !self.implicit(&result, &left_type) {
+                            self.mismatch(right.span, &left_type, &right_type);
+                        }
+                    }
+                }
+                left_type
+            }
+            ExpressionKind::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => {
+                self.expect(condition, &Type::Bool);
+                let then_type = self.check_expression(then);
+                let otherwise_type = self.check_expression(otherwise);
+                match self.common_type(&then_type, &otherwise_type) {
+                    Some(ty) => ty.mobile(),
+                    None => {
+                        self.mismatch(otherwise.span, &then_type, &otherwise_type);
+                        Type::Unknown
+                    }
+                }
+            }
+            ExpressionKind::Tuple(components) => Type::Tuple(
+                components
+                    .iter()
+                    .map(|component| {
+                        component
+                            .as_ref()
+                            .map(|component| self.check_expression(component))
+                    })
+                    .collect(),
+            ),
+            ExpressionKind::ArrayLiteral(elements) => {
+                let mut base: Option<Type> = None;
+                for element in elements {
+                    let element_type = self.check_expression(element).mobile();
+                    base = match base {
+                        None => Some(element_type),
+                        Some(_base) => match self.common_type(&_base, &element_type) {
+                            Some(common) => Some(common),
+                            None => {
+                                self.mismatch(element.span, &_base, &element_type);
+                                Some(_base)
+                            }
+                        },
+                    };
+                }
+                Type::Array {
+                    base: Box::new(base.unwrap_or(Type::Unknown)),
+                    length: Some(BigInt::from(elements.len())),
+                }
+            }
+            ExpressionKind::New(ty) => {
+                self.check_type_name(ty);
+                match self.type_of_name(ty) {
+                    Type::Contract {
+                        file_id, contract, ..
+                    } => Type::Function(self.constructor_type((file_id, contract))),
+                    array @ Type::Array { length: None, .. } => Type::Function(FunctionType {
+                        kind: FunctionKind::Internal,
+                        parameters: vec![Type::UINT256],
+                        parameter_names: vec![None],
+                        returns: vec![array],
+                        mutability: Some(ast::StateMutability::Pure),
+                    }),
+                    ty @ (Type::Bytes | Type::String) => Type::Function(FunctionType {
+                        kind: FunctionKind::Internal,
+                        parameters: vec![Type::UINT256],
+                        parameter_names: vec![None],
+                        returns: vec![ty],
+                        mutability: Some(ast::StateMutability::Pure),
+                    }),
+                    Type::Unknown => Type::Unknown,
+                    ty => {
+                        self.error(
+                            expression.span,
+                            format!("cannot create `{ty}` with `new`"),
+                            "expected a contract or a dynamic array",
+                        );
+                        Type::Unknown
+                    }
+                }
+            }
+        };
+        self.table.expressions.insert(expression.span, ty.clone());
+        ty
+    }
+
+    fn member_type(&mut self, base: &Type, member: &Identifier) -> Type {
+        /* `Lib.f`, `Enum.Value` AND `M.Contract` WERE RESOLVED ALONG WITH THE OTHER NAMES */
+        if let Some(declarations) = self.bindings.get(&member.span) {
+            return self.declarations_type(declarations);
+        }
+        let name = member.name.as_str();
+        let found = match base {
+            Type::Unknown | Type::Module(_) => return Type::Unknown,
+            Type::Struct {
+                file_id,
+                contract,
+                part,
+                ..
+            } => match &self.graph.units[*file_id].ast.contracts[*contract].parts[*part] {
+                ContractPart::Struct(definition) => definition
+                    .fields
+                    .iter()
+                    .find(|field| field.name.name == name)
+                    .map(|field| self.type_of_name(&field.ty)),
+                _ => None,
+            },
+            Type::Array { length, .. } => match name {
+                "length" => Some(Type::UINT256),
+                "push" if length.is_none() => Some(builtin_member("push", base)),
+                "pop" if length.is_none() => Some(builtin_member("pop", base)),
+                _ => None,
+            },
+            Type::Bytes => match name {
+                "length" => Some(Type::UINT256),
+                "push" => Some(builtin_member("push", base)),
+                "pop" => Some(builtin_member("pop", base)),
+                _ => None,
+            },
+            Type::FixedBytes(_) => match name {
+                "length" => Some(Type::Integer {
+                    signed: false,
+                    bits: 8,
+                }),
+                _ => None,
+            },
+            Type::Address { payable } => match name {
+                "balance" => Some(Type::UINT256),
+                "code" => Some(Type::Bytes),
+                "codehash" => Some(Type::FixedBytes(32)),
+                "transfer" if *payable => Some(builtin_member("transfer", base)),
+                "send" if *payable => Some(builtin_member("send", base)),
+                "call" => Some(builtin_member("call", base)),
+                "delegatecall" => Some(builtin_member("delegatecall", base)),
+                "staticcall" => Some(builtin_member("staticcall", base)),
+                _ => None,
+            },
+            Type::Contract {
+                file_id, contract, ..
+            } => self.external_member((*file_id, *contract), name),
+            Type::TypeType(ty)
+                if matches!(**ty, Type::String | Type::Bytes) && name == "concat" =>
+            {
+                Some(builtin_member("concat", ty))
+            }
+            Type::Function(_) | Type::Overloaded(_) => match name {
+                "selector" => Some(Type::FixedBytes(4)),
+                "address" => Some(Type::Address { payable: false }),
+                _ => None,
+            },
+            Type::Magic(magic) => magic_member(magic, name),
+            Type::BuiltinMember {
+                name: "type",
+                receiver,
+            } => match (&**receiver, name) {
+                (Type::Integer { .. } | Type::Enum { .. }, "min" | "max") => {
+                    Some((**receiver).clone())
+                }
+                (Type::Contract { .. }, "name") => Some(Type::String),
+                (Type::Contract { .. }, "creationCode" | "runtimeCode") => Some(Type::Bytes),
+                (Type::Contract { .. }, "interfaceId") => Some(Type::FixedBytes(4)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let found = match found {
+            Some(_found) => Some(_found),
+            None => self.attached_functions(base, member),
+        };
+        match found {
+            Some(ty) => ty,
+            None => {
+                self.error(
+                    member.span,
+                    format!("no member named `{name}` in `{base}`"),
+                    "unknown member",
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    /* FUNCTIONS AND PUBLIC STATE VARIABLES REACHABLE THROUGH A VALUE OF CONTRACT TYPE */
+    fn external_member(&self, contract: ContractId, name: &str) -> Option<Type> {
+        let declarations = self.bindings.members.get(&contract)?.get(name)?;
+        let mut functions = Vec::new();
+        for declaration in declarations {
+            match self.part(*declaration)? {
+                ContractPart::Function(function)
+                    if !matches!(
+                        function.visibility,
+                        Some(Visibility::Private | Visibility::Internal)
+                    ) =>
+                {
+                    let mut signature = self.signature(
+                        FunctionKind::External,
+                        &function.parameters,
+                        &function.returns,
+                        function.mutability,
+                    );
+                    signature.kind = FunctionKind::External;
+                    functions.push(signature);
+                }
+                ContractPart::StateVariable(variable)
+                    if variable.visibility == Some(Visibility::Public) =>
+                {
+                    functions.push(self.getter(&self.type_of_name(&variable.ty)));
+                }
+                _ => {}
+            }
+        }
+        match functions.len() {
+            0 => None,
+            1 => Some(Type::Function(functions.remove(0))),
+            _ => Some(Type::Overloaded(functions)),
+        }
+    }
+
+    /* GETTER OF A PUBLIC STATE VARIABLE. MAPPING KEYS AND ARRAY INDICES BECOME PARAMETERS */
+    fn getter(&self, ty: &Type) -> FunctionType {
+        let mut parameters = Vec::new();
+        let mut value = ty.clone();
+        loop {
+            match value {
+                Type::Mapping { key, value: inner } => {
+                    parameters.push(*key);
+                    value = *inner;
+                }
+                Type::Array { base, .. } => {
+                    parameters.push(Type::UINT256);
+                    value = *base;
+                }
+                _ => break,
+            }
+        }
+        FunctionType {
+            kind: FunctionKind::External,
+            parameter_names: vec![None; parameters.len()],
+            parameters,
+            returns: vec![value],
+            mutability: Some(ast::StateMutability::View),
+        }
+    }
+
+    /* FUNCTIONS ATTACHED TO `receiver` WITH `using L for T` IN THE CURRENT CONTRACT. THE
+    RECEIVER BECOMES THE FIRST ARGUMENT, SO IT IS DROPPED FROM THE SIGNATURE */
+    fn attached_functions(&mut self, receiver: &Type, member: &Identifier) -> Option<Type> {
+        let name = member.name.as_str();
+        let contract = self.contract_definition((self.file_id, self.contract?));
+        let mut functions = Vec::new();
+        let mut attached = Vec::new();
+        for part in &contract.parts {
+            let ContractPart::Using(using) = part else {
+                continue;
+            };
+            if let Some(_target) = &using.target {
+                if self.type_of_name(_target) != receiver.mobile() {
+                    continue;
+                }
+            }
+            let Some(Declaration::Contract { file_id, contract }) =
+                self.declaration_of(using.library.last().unwrap())
+            else {
+                continue;
+            };
+            let declarations = self
+                .bindings
+                .members
+                .get(&(file_id, contract))
+                .and_then(|members| members.get(name));
+            for declaration in declarations.into_iter().flatten() {
+                if let Type::Function(mut function) = self.declaration_type(*declaration) {
+                    if function
+                        .parameters
+                        .first()
+                        .is_some_and(|first| self.implicit(receiver, first))
+                    {
+                        function.parameters.remove(0);
+                        function.parameter_names.remove(0);
+                        functions.push(function);
+                        attached.push(*declaration);
+                    }
+                }
+            }
+        }
+        if !attached.is_empty() {
+            self.table.attached.insert(member.span, attached);
+        }
+        match functions.len() {
+            0 => None,
+            1 => Some(Type::Function(functions.remove(0))),
+            _ => Some(Type::Overloaded(functions)),
+        }
+    }
+
+    /* `super.name`: THE FIRST IMPLEMENTATION OF EACH SIGNATURE AFTER THE CURRENT CONTRACT IN ITS
+    LINEARIZATION. THE CALL RUNS THE ONE AFTER IT IN THE CONTRACT BEING DEPLOYED, WHICH MAY SIT
+    IN BETWEEN */
+    fn super_functions(&mut self, member: &Identifier) -> Type {
+        let Some(contract) = self.contract else {
+            return Type::Unknown;
+        };
+        let linearization = self
+            .bindings
+            .linearization
+            .get(&(self.file_id, contract))
+            .cloned()
+            .unwrap_or_default();
+        let mut functions: Vec<FunctionType> = Vec::new();
+        let mut declarations = Vec::new();
+        for &(file_id, index) in linearization.iter().skip(1) {
+            let parts = &self.contract_definition((file_id, index)).parts;
+            for (part, candidate) in parts.iter().enumerate() {
+                let ContractPart::Function(_function) = candidate else {
+                    continue;
+                };
+                if _function.kind != ast::FunctionKind::Function
+                    || _function.body.is_none()
+                    || _function.visibility == Some(Visibility::Private)
+                    || _function.name.as_ref().map(|name| name.name.as_str())
+                        != Some(member.name.as_str())
+                {
+                    continue;
+                }
+                let declaration = Declaration::Member {
+                    file_id,
+                    contract: index,
+                    part,
+                };
+                let Type::Function(function) = self.declaration_type(declaration) else {
+                    continue;
+                };
+                if !functions
+                    .iter()
+                    .any(|seen| seen.parameters == function.parameters)
+                {
+                    functions.push(function);
+                    declarations.push(declaration);
+                }
+            }
+        }
+        match functions.len() {
+            0 => {
+                self.error(
+                    member.span,
+                    format!("no member named `{}` in `super`", member.name),
+                    "no base contract implements it",
+                );
+                Type::Unknown
+            }
+            1 => {
+                self.table.supers.insert(member.span, declarations);
+                Type::Function(functions.remove(0))
+            }
+            _ => {
+                self.table.supers.insert(member.span, declarations);
+                Type::Overloaded(functions)
+            }
+        }
+    }
+
+    /* WHETHER A VALUE OF `ty` HOLDS A STRUCT `id` IN PLACE, WHICH WOULD MAKE IT INFINITELY LARGE.
+    DYNAMIC ARRAYS AND MAPPINGS ONLY POINT TO THEIR ELEMENTS */
+    fn contains_struct(&self, ty: &Type, id: (usize, usize, usize), seen: &mut Vec<Type>) -> bool {
+        match ty {
+            Type::Struct {
+                file_id,
+                contract,
+                part,
+                ..
+            } => {
+                if (*file_id, *contract, *part) == id {
+                    return true;
+                }
+                if seen.contains(ty) {
+                    return false;
+                }
+                seen.push(ty.clone());
+                let ContractPart::Struct(definition) =
+                    &self.graph.units[*file_id].ast.contracts[*contract].parts[*part]
+                else {
+                    return false;
+                };
+                definition
+                    .fields
+                    .iter()
+                    .any(|field| self.contains_struct(&self.type_of_name(&field.ty), id, seen))
+            }
+            Type::Array {
+                base,
+                length: Some(_),
+            } => self.contains_struct(base, id, seen),
+            _ => false,
+        }
+    }
+
+    fn index_type(&mut self, base: &Expression, index: Option<&Expression>) -> Type {
+        let base_type = self.check_expression(base);
+        match (&base_type, index) {
+            (Type::TypeType(ty), None) => Type::TypeType(Box::new(Type::Array {
+                base: ty.clone(),
+                length: None,
+            })),
+            (Type::TypeType(ty), Some(_index)) => {
+                let length =
+                    match ConstEvaluator::new(self.graph, self.bindings).array_length(_index) {
+                        Ok(length) => Some(length),
+                        Err(error) => {
+                            self.diagnostics.push(error.diagnostic());
+                            None
+                        }
+                    };
+                Type::TypeType(Box::new(Type::Array {
+                    base: ty.clone(),
+                    length,
+                }))
+            }
+            (Type::Mapping { key, value }, Some(_index)) => {
+                self.expect(_index, key);
+                (**value).clone()
+            }
+            (Type::Array { base, .. }, Some(_index)) => {
+                self.expect_index(_index);
+                (**base).clone()
+            }
+            (Type::Bytes | Type::FixedBytes(_), Some(_index)) => {
+                self.expect_index(_index);
+                Type::FixedBytes(1)
+            }
+            (Type::Unknown, _) => {
+                if let Some(_index) = index {
+                    self.check_expression(_index);
+                }
+                Type::Unknown
+            }
+            (_, _index) => {
+                if let Some(_index) = _index {
+                    self.check_expression(_index);
+                }
+                self.error(
+                    base.span,
+                    format!("`{base_type}` cannot be indexed"),
+                    "expected an array, a mapping or `bytes`",
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn expect_index(&mut self, index: &Expression) {
+        let found = self.check_expression(index);
+        let valid = match &found {
+            Type::Integer { .. } | Type::Unknown => true,
+            Type::RationalConstant(value, _) => value.is_integer() && !value.is_negative(),
+            _ => false,
+        };
+        if !valid {
+            self.mismatch(index.span, &Type::UINT256, &found);
+        }
+    }
+
+    /* ------------------------------ CALLS ------------------------------ */
+
+    fn arguments(&mut self, arguments: &CallArguments) -> Vec<Argument> {
+        match arguments {
+            CallArguments::Positional(expressions) => self.positional_arguments(expressions),
+            CallArguments::Named(named) => named
+                .iter()
+                .map(|argument| Argument {
+                    span: argument.value.span,
+                    name: Some(argument.name.name.clone()),
+                    ty: self.check_expression(&argument.value),
+                })
+                .collect(),
+        }
+    }
+
+    fn positional_arguments(&mut self, expressions: &[Expression]) -> Vec<Argument> {
+        expressions
+            .iter()
+            .map(|expression| Argument {
+                span: expression.span,
+                name: None,
+                ty: self.check_expression(expression),
+            })
+            .collect()
+    }
+
+    /* TYPE OF CALLING SOMETHING OF TYPE `callee` WITH `arguments` */
+    fn apply_call(&mut self, span: Span, callee: &Type, arguments: Vec<Argument>) -> Type {
+        match callee {
+            Type::Function(function) => {
+                if let Err(error) = self.match_arguments(function, &arguments) {
+                    self.argument_error(span, error);
+                }
+                call_result(&function.returns)
+            }
+            Type::Overloaded(functions) => {
+                /* AN OVERRIDE AND WHAT IT OVERRIDES ARE ONE CANDIDATE, THE MOST DERIVED, WHICH
+                COMES FIRST */
+                let distinct: Vec<usize> = (0..functions.len())
+                    .filter(|index| {
+                        functions[..*index]
+                            .iter()
+                            .all(|earlier| earlier.parameters != functions[*index].parameters)
+                    })
+                    .collect();
+                let candidates: Vec<usize> = distinct
+                    .iter()
+                    .copied()
+                    .filter(|index| self.match_arguments(&functions[*index], &arguments).is_ok())
+                    .collect();
+                match candidates.as_slice() {
+                    [index] => {
+                        self.table.overloads.insert(span, *index);
+                        call_result(&functions[*index].returns)
+                    }
+                    [_, ..] => {
+                        let mut diagnostic =
+                            CompilerError::SemanticError(SemanticError::TypeMismatch(
+                                "call is ambiguous between several overloads".to_string(),
+                            ))
+                            .diagnostic()
+                            .with_label(span, "more than one overload matches");
+                        for index in &candidates {
+                            diagnostic =
+                                diagnostic.with_note(format!("candidate: {}", functions[*index]));
+                        }
+                        self.diagnostics.push(diagnostic);
+                        Type::Unknown
+                    }
+                    [] => {
+                        let mut diagnostic =
+                            CompilerError::SemanticError(SemanticError::TypeMismatch(
+                                "no overload matches these arguments".to_string(),
+                            ))
+                            .diagnostic()
+                            .with_label(span, "no matching overload");
+                        for index in distinct {
+                            diagnostic =
+                                diagnostic.with_note(format!("candidate: {}", functions[index]));
+                        }
+                        self.diagnostics.push(diagnostic);
+                        Type::Unknown
+                    }
+                }
+            }
+            Type::TypeType(ty) => match &**ty {
+                Type::Struct {
+                    file_id,
+                    contract,
+                    part,
+                    ..
+                } => {
+                    let ContractPart::Struct(definition) =
+                        &self.graph.units[*file_id].ast.contracts[*contract].parts[*part]
+                    else {
+                        return Type::Unknown;
+                    };
+                    let constructor = FunctionType {
+                        kind: FunctionKind::Internal,
+                        parameters: definition
+                            .fields
+                            .iter()
+                            .map(|field| self.type_of_name(&field.ty))
+                            .collect(),
+                        parameter_names: definition
+                            .fields
+                            .iter()
+                            .map(|field| Some(field.name.name.clone()))
+                            .collect(),
+                        returns: vec![(**ty).clone()],
+                        mutability: Some(ast::StateMutability::Pure),
+                    };
+                    if let Err(error) = self.match_arguments(&constructor, &arguments) {
+                        self.argument_error(span, error);
+                    }
+                    (**ty).clone()
+                }
+                target => {
+                    match arguments.as_slice() {
+                        [argument] if argument.name.is_none() => {
+                            if !self.explicit(&argument.ty, target) {
+                                self.error(
+                                    argument.span,
+                                    "invalid conversion".to_string(),
+                                    format!("cannot convert `{}` to `{target}`", argument.ty),
+                                );
+                            }
+                        }
+                        _ => self.argument_error(
+                            span,
+                            ArgumentError::Count {
+                                expected: 1,
+                                found: arguments.len(),
+                            },
+                        ),
+                    }
+                    target.clone()
+                }
+            },
+            Type::Magic(name) => self.builtin_call(span, name, None, arguments),
+            Type::BuiltinMember { name, receiver } => {
+                self.builtin_call(span, name, Some(receiver), arguments)
+            }
+            Type::Unknown => Type::Unknown,
+            _ => {
+                self.error(
+                    span,
+                    format!("`{callee}` is not callable"),
+                    "expected a function",
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn match_arguments(
+        &self,
+        function: &FunctionType,
+        arguments: &[Argument],
+    ) -> Result<(), ArgumentError> {
+        if arguments.len() != function.parameters.len() {
+            return Err(ArgumentError::Count {
+                expected: function.parameters.len(),
+                found: arguments.len(),
+            });
+        }
+        for (position, argument) in arguments.iter().enumerate() {
+            let index = match &argument.name {
+                Some(name) => function
+                    .parameter_names
+                    .iter()
+                    .position(|parameter| parameter.as_deref() == Some(name.as_str()))
+                    .ok_or_else(|| ArgumentError::Name(argument.span, name.clone()))?,
+                None => position,
+            };
+            let expected = &function.parameters[index];
+            if !self.implicit(&argument.ty, expected) {
+                return Err(ArgumentError::Mismatch {
+                    span: argument.span,
+                    expected: Box::new(expected.clone()),
+                    found: Box::new(argument.ty.clone()),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn argument_error(&mut self, span: Span, error: ArgumentError) {
+        match error {
+            ArgumentError::Count { expected, found } => self.error(
+                span,
+                format!("wrong number of arguments: expected {expected}, found {found}"),
+                format!(
+                    "expected {expected} argument{}",
+                    if expected == 1 { "" } else { "s" }
+                ),
+            ),
+            ArgumentError::Name(span, name) => self.error(
+                span,
+                format!("no parameter named `{name}`"),
+                "unknown parameter name",
+            ),
+            ArgumentError::Mismatch {
+                span,
+                expected,
+                found,
+            } => self.mismatch(span, &expected, &found),
+        }
+    }
+
+    /* SIGNATURES OF THE GLOBAL FUNCTIONS AND OF BUILTIN MEMBERS. VARIADIC ONES ARE CHECKED BY HAND */
+    fn builtin_call(
+        &mut self,
+        span: Span,
+        name: &str,
+        receiver: Option<&Type>,
+        arguments: Vec<Argument>,
+    ) -> Type {
+        let bytes32 = Type::FixedBytes(32);
+        let (parameters, returns): (Vec<Type>, Vec<Type>) = match (name, receiver) {
+            ("require", _) if arguments.len() == 2 => (vec![Type::Bool, Type::String], vec![]),
+            ("require" | "assert", _) => (vec![Type::Bool], vec![]),
+            ("revert", None) if arguments.len() == 1 => (vec![Type::String], vec![]),
+            ("revert", None) => (vec![], vec![]),
+            ("keccak256" | "sha256", _) => (vec![Type::Bytes], vec![bytes32]),
+            ("ripemd160", _) => (vec![Type::Bytes], vec![Type::FixedBytes(20)]),
+            ("ecrecover", _) => (
+                vec![
+                    bytes32.clone(),
+                    Type::Integer {
+                        signed: false,
+                        bits: 8,
+                    },
+                    bytes32.clone(),
+                    bytes32,
+                ],
+                vec![Type::Address { payable: false }],
+            ),
+            ("addmod" | "mulmod", _) => (vec![Type::UINT256; 3], vec![Type::UINT256]),
+            ("gasleft", _) => (vec![], vec![Type::UINT256]),
+            ("blockhash" | "blobhash", _) => (vec![Type::UINT256], vec![bytes32]),
+            ("selfdestruct", _) => (vec![Type::Address { payable: true }], vec![]),
+            ("transfer", Some(_)) => (vec![Type::UINT256], vec![]),
+            ("send", Some(_)) => (vec![Type::UINT256], vec![Type::Bool]),
+            ("call" | "delegatecall" | "staticcall", Some(_)) => {
+                (vec![Type::Bytes], vec![Type::Bool, Type::Bytes])
+            }
+            ("pop", Some(_)) => (vec![], vec![]),
+            ("push", Some(Type::Bytes)) if !arguments.is_empty() => {
+                (vec![Type::FixedBytes(1)], vec![])
+            }
+            ("push", Some(Type::Array { base, .. })) if !arguments.is_empty() => {
+                (vec![(**base).clone()], vec![])
+            }
+            ("push", Some(Type::Bytes)) => (vec![], vec![Type::FixedBytes(1)]),
+            ("push", Some(Type::Array { base, .. })) => (vec![], vec![(**base).clone()]),
+            ("type", None) => {
+                return match arguments.as_slice() {
+                    [Argument {
+                        ty: Type::TypeType(ty),
+                        ..
+                    }] if matches!(
+                        **ty,
+                        Type::Integer { .. } | Type::Enum { .. } | Type::Contract { .. }
+                    ) =>
+                    {
+                        builtin_member("type", ty)
+                    }
+                    [argument] => {
+                        self.error(
+                            argument.span,
+                            "invalid argument for `type`".to_string(),
+                            "expected an integer, enum or contract type",
+                        );
+                        Type::Unknown
+                    }
+                    _ => {
+                        self.argument_error(
+                            span,
+                            ArgumentError::Count {
+                                expected: 1,
+                                found: arguments.len(),
+                            },
+                        );
+                        Type::Unknown
+                    }
+                };
+            }
+            ("concat", Some(receiver)) => {
+                for argument in &arguments {
+                    let valid = match receiver {
+                        Type::String => self.implicit(&argument.ty, &Type::String),
+                        _ => {
+                            self.implicit(&argument.ty, &Type::Bytes)
+                                || matches!(argument.ty, Type::FixedBytes(_))
+                        }
+                    };
+                    if !valid {
+                        self.mismatch(argument.span, receiver, &argument.ty);
+                    }
+                }
+                return receiver.clone();
+            }
+            ("abi.encode" | "abi.encodePacked", _) => return Type::Bytes,
+            ("abi.encodeWithSelector" | "abi.encodeWithSignature" | "abi.encodeCall", _) => {
+                let first = match name {
+                    "abi.encodeWithSelector" => Type::FixedBytes(4),
+                    "abi.encodeWithSignature" => Type::String,
+                    _ => Type::Unknown,
+                };
+                match arguments.first() {
+                    Some(argument) => {
+                        if !self.implicit(&argument.ty, &first) {
+                            self.mismatch(argument.span, &first, &argument.ty);
+                        }
+                    }
+                    None => self.argument_error(
+                        span,
+                        ArgumentError::Count {
+                            expected: 1,
+                            found: 0,
+                        },
+                    ),
+                }
+                return Type::Bytes;
+            }
+            ("abi.decode", _) => {
+                if arguments.len() != 2 {
+                    self.argument_error(
+                        span,
+                        ArgumentError::Count {
+                            expected: 2,
+                            found: arguments.len(),
+                        },
+                    );
+                    return Type::Unknown;
+                }
+                if !self.implicit(&arguments[0].ty, &Type::Bytes) {
+                    self.mismatch(arguments[0].span, &Type::Bytes, &arguments[0].ty);
+                }
+                let decoded = match &arguments[1].ty {
+                    Type::TypeType(ty) => vec![(**ty).clone()],
+                    Type::Tuple(components) => components
+                        .iter()
+                        .map(|component| match component {
+                            Some(Type::TypeType(ty)) => (**ty).clone(),
+                            _ => Type::Unknown,
+                        })
+                        .collect(),
+                    _ => vec![Type::Unknown],
+                };
+                return call_result(&decoded);
+            }
+            _ => {
+                self.error(
+                    span,
+                    format!("`{name}` is not callable"),
+                    "expected a function",
+                );
+                return Type::Unknown;
+            }
+        };
+        let signature = FunctionType {
+            kind: FunctionKind::Internal,
+            parameter_names: vec![None; parameters.len()],
+            parameters,
+            returns,
+            mutability: None,
+        };
+        if let Err(error) = self.match_arguments(&signature, &arguments) {
+            self.argument_error(span, error);
+        }
+        call_result(&signature.returns)
+    }
+
+    /* ------------------------------ OPERATORS ------------------------------ */
+
+    fn unary_type(
+        &mut self,
+        span: Span,
+        operator: UnaryOperator,
+        operand: &Expression,
+        ty: Type,
+    ) -> Type {
+        if ty.is_unknown() {
+            return Type::Unknown;
+        }
+        let result = match operator {
+            UnaryOperator::Not => (ty == Type::Bool).then_some(Type::Bool),
+            UnaryOperator::Negate => match &ty {
+                Type::RationalConstant(value, _) => Some(Type::RationalConstant(-value, None)),
+                Type::Integer { signed: true, .. } => Some(ty.clone()),
+                _ => None,
+            },
+            UnaryOperator::BitNot => match &ty {
+                Type::RationalConstant(value, _) if value.is_integer() => Some(
+                    Type::RationalConstant(BigRational::from(!value.to_integer()), None),
+                ),
+                Type::Integer { .. } | Type::FixedBytes(_) => Some(ty.clone()),
+                _ => None,
+            },
+            UnaryOperator::PreIncrement
+            | UnaryOperator::PreDecrement
+            | UnaryOperator::PostIncrement
+            | UnaryOperator::PostDecrement => {
+                self.check_assignable(operand);
+                ty.is_integer().then(|| ty.clone())
+            }
+            UnaryOperator::Delete => {
+                self.check_assignable(operand);
+                (!matches!(ty, Type::Mapping { .. })).then(|| Type::Tuple(Vec::new()))
+            }
+        };
+        result.unwrap_or_else(|| {
+            self.error(
+                span,
+                format!(
+                    "operator `{}` cannot be applied to `{ty}`",
+                    unary_symbol(operator)
+                ),
+                "invalid operand",
+            );
+            Type::Unknown
+        })
+    }
+
+    fn binary_type(
+        &mut self,
+        span: Span,
+        operator: BinaryOperator,
+        left: Type,
+        right: Type,
+    ) -> Type {
+        let comparison = matches!(
+            operator,
+            BinaryOperator::Eq
+                | BinaryOperator::Ne
+                | BinaryOperator::Lt
+                | BinaryOperator::Le
+                | BinaryOperator::Gt
+                | BinaryOperator::Ge
+        );
+        if left.is_unknown() || right.is_unknown() {
+            return if comparison {
+                Type::Bool
+            } else {
+                Type::Unknown
+            };
+        }
+        if let (Type::RationalConstant(a, _), Type::RationalConstant(b, _)) = (&left, &right) {
+            match fold(operator, a, b) {
+                Ok(ConstValue::Rational(value)) => return Type::RationalConstant(value, None),
+                Ok(_) => return Type::Bool,
+                Err(message) => {
+                    self.error(span, message, "in this constant expression");
+                    return Type::Unknown;
+                }
+            }
+        }
+
+        let result = match operator {
+            BinaryOperator::And | BinaryOperator::Or => {
+                (left == Type::Bool && right == Type::Bool).then_some(Type::Bool)
+            }
+            BinaryOperator::Eq | BinaryOperator::Ne => self
+                .common_type(&left, &right)
+                .filter(|common| common.is_value_type() || matches!(common, Type::Function(_)))
+                .map(|_| Type::Bool),
+            BinaryOperator::Lt | BinaryOperator::Le | BinaryOperator::Gt | BinaryOperator::Ge => {
+                self.common_type(&left, &right)
+                    .filter(|common| {
+                        matches!(
+                            common,
+                            Type::Integer { .. }
+                                | Type::FixedBytes(_)
+                                | Type::Address { .. }
+                                | Type::Enum { .. }
+                        )
+                    })
+                    .map(|_| Type::Bool)
+            }
+            BinaryOperator::Shl | BinaryOperator::Shr | BinaryOperator::Exp => {
+                /* A LITERAL BASE WITH A VARIABLE EXPONENT OR SHIFT IS A FULL WORD, AS IN solc */
+                let base = match &left {
+                    Type::RationalConstant(value, _) if value.is_integer() => match left.mobile() {
+                        Type::Integer { signed, .. } => Type::Integer { signed, bits: 256 },
+                        ty => ty,
+                    },
+                    _ => left.mobile(),
+                };
+                let valid_base = match operator {
+                    BinaryOperator::Exp => base.is_integer(),
+                    _ => matches!(base, Type::Integer { .. } | Type::FixedBytes(_)),
+                };
+                let valid_exponent = match &right {
+                    Type::Integer { signed, .. } => !signed,
+                    Type::RationalConstant(value, _) => value.is_integer() && !value.is_negative(),
+                    _ => false,
+                };
+                (valid_base && valid_exponent).then_some(base)
+            }
+            BinaryOperator::Add
+            | BinaryOperator::Sub
+            | BinaryOperator::Mul
+            | BinaryOperator::Div
+            | BinaryOperator::Mod => self
+                .common_type(&left, &right)
+                .map(|common| common.mobile())
+                .filter(Type::is_integer),
+            BinaryOperator::BitAnd | BinaryOperator::BitOr | BinaryOperator::BitXor => self
+                .common_type(&left, &right)
+                .map(|common| common.mobile())
+                .filter(|common| matches!(common, Type::Integer { .. } | Type::FixedBytes(_))),
+        };
+        result.unwrap_or_else(|| {
+            self.error(
+                span,
+                format!(
+                    "operator `{}` cannot be applied to `{left}` and `{right}`",
+                    binary_symbol(operator)
+                ),
+                "invalid operands",
+            );
+            Type::Unknown
+        })
+    }
+
+    /* ------------------------------ CONVERSIONS ------------------------------ */
+
+    /* THE TYPE BOTH OPERANDS CONVERT TO, IF ONE OF THEM CONVERTS TO THE OTHER */
+    fn common_type(&self, left: &Type, right: &Type) -> Option<Type> {
+        if left.is_unknown() || right.is_unknown() {
+            Some(Type::Unknown)
+        } else if self.implicit(right, left) {
+            Some(left.clone())
+        } else if self.implicit(left, right) {
+            Some(right.clone())
+        } else {
+            let (left, right) = (left.mobile(), right.mobile());
+            if self.implicit(&right, &left) {
+                Some(left)
+            } else if self.implicit(&left, &right) {
+                Some(right)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn implicit(&self, from: &Type, to: &Type) -> bool {
+        if from == to || from.is_unknown() || to.is_unknown() {
+            return true;
+        }
+        match (from, to) {
+            (
+                Type::Integer { signed, bits },
+                Type::Integer {
+                    signed: to_signed,
+                    bits: to_bits,
+                },
+            ) => signed == to_signed && bits <= to_bits,
+            (Type::RationalConstant(value, _), Type::Integer { signed, bits }) => {
+                fits(value, *signed, *bits)
+            }
+            (Type::RationalConstant(value, digits), Type::FixedBytes(size)) => {
+                value.is_zero() || *digits == Some(2 * *size as usize)
+            }
+            (Type::StringLiteral(_), Type::String | Type::Bytes) => true,
+            (Type::StringLiteral(value), Type::FixedBytes(size)) => value.len() <= *size as usize,
+            (Type::Address { payable: true }, Type::Address { payable: false }) => true,
+            (
+                Type::Contract {
+                    file_id, contract, ..
+                },
+                Type::Contract {
+                    file_id: base_file,
+                    contract: base_contract,
+                    ..
+                },
+            ) => self
+                .bindings
+                .derives_from((*file_id, *contract), (*base_file, *base_contract)),
+            (Type::FixedBytes(size), Type::FixedBytes(to_size)) => size <= to_size,
+            (
+                Type::Array { base, length },
+                Type::Array {
+                    base: to_base,
+                    length: to_length,
+                },
+            ) => length == to_length && self.implicit(base, to_base),
+            (Type::Tuple(components), Type::Tuple(to_components)) => {
+                components.len() == to_components.len()
+                    && components.iter().zip(to_components).all(|pair| match pair {
+                        (Some(component), Some(to_component)) => {
+                            self.implicit(component, to_component)
+                        }
+                        _ => true,
+                    })
+            }
+            (Type::Function(function), Type::Function(to_function)) => {
+                function.parameters == to_function.parameters
+                    && function.returns == to_function.returns
+            }
+            _ => false,
+        }
+    }
+
+    /* CONVERSIONS ALLOWED WITH `T(x)` ON TOP OF THE IMPLICIT ONES */
+    fn explicit(&self, from: &Type, to: &Type) -> bool {
+        if self.implicit(from, to) {
+            return true;
+        }
+        match (from, to) {
+            (Type::RationalConstant(value, _), Type::Integer { signed, bits }) => {
+                fits(value, *signed, *bits)
+            }
+            (Type::RationalConstant(value, _), Type::Address { .. }) => fits(value, false, 160),
+            (Type::RationalConstant(value, _), Type::FixedBytes(size)) => {
+                fits(value, false, *size as u16 * 8)
+            }
+            (Type::RationalConstant(value, _), Type::Enum { .. }) => fits(value, false, 8),
+            /* THE SIGN AND THE SIZE CANNOT BOTH CHANGE IN ONE CONVERSION */
+            (
+                Type::Integer { signed, bits },
+                Type::Integer {
+                    signed: to_signed,
+                    bits: to_bits,
+                },
+            ) => signed == to_signed || bits == to_bits,
+            (Type::Integer { bits, .. }, Type::FixedBytes(size))
+            | (Type::FixedBytes(size), Type::Integer { bits, .. }) => *bits == *size as u16 * 8,
+            (
+                Type::Integer {
+                    signed: false,
+                    bits: 160,
+                },
+                Type::Address { .. },
+            )
+            | (
+                Type::Address { .. },
+                Type::Integer {
+                    signed: false,
+                    bits: 160,
+                },
+            ) => true,
+            (Type::FixedBytes(20), Type::Address { .. })
+            | (Type::Address { .. }, Type::FixedBytes(20)) => true,
+            (Type::Address { .. }, Type::Address { .. }) => true,
+            (Type::Contract { .. }, Type::Address { .. })
+            | (Type::Address { .. }, Type::Contract { .. }) => true,
+            (Type::FixedBytes(_), Type::FixedBytes(_)) => true,
+            (Type::Bytes, Type::FixedBytes(_)) => true,
+            (Type::String, Type::Bytes) | (Type::Bytes, Type::String) => true,
+            (Type::Integer { .. }, Type::Enum { .. })
+            | (Type::Enum { .. }, Type::Integer { .. }) => true,
+            _ => false,
+        }
+    }
+
+    /* ------------------------------ CHECKS ------------------------------ */
+
+    fn expect(&mut self, expression: &Expression, expected: &Type) {
+        let found = self.check_expression(expression);
+        if !self.implicit(&found, expected) {
+            self.mismatch(expression.span, expected, &found);
+        }
+    }
+
+    /* LIKE `expect`, POINTING ALSO AT THE TYPE THE EXPECTATION COMES FROM */
+    fn expect_declared(&mut self, expression: &Expression, expected: &Type, declared_at: Span) {
+        let found = self.check_expression(expression);
+        if !self.implicit(&found, expected) {
+            let diagnostic = mismatch_diagnostic(expression.span, expected, &found)
+                .with_secondary_label(declared_at, "expected due to this type");
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /* `(a, , b) = ..` AND `return (a, b)` AGAINST A TUPLE OF EXPECTED TYPES */
+    fn expect_tuple(&mut self, expression: &Expression, expected: &[Option<Type>], found: &Type) {
+        let span = expression.span;
+        match found {
+            Type::Unknown => {}
+            Type::Tuple(components) if components.len() == expected.len() => {
+                for (index, (expected, component)) in expected.iter().zip(components).enumerate() {
+                    if let (Some(_expected), Some(_component)) = (expected, component) {
+                        if !self.implicit(_component, _expected) {
+                            /* POINT AT THE COMPONENT ITSELF WHEN THE TUPLE IS WRITTEN OUT */
+                            let span = match &expression.kind {
+                                ExpressionKind::Tuple(items) => {
+                                    items[index].as_ref().map_or(span, |item| item.span)
+                                }
+                                _ => span,
+                            };
+                            self.mismatch(span, _expected, _component);
+                        }
+                    }
+                }
+            }
+            _ => {
+                let components = match found {
+                    Type::Tuple(components) => components.len(),
+                    _ => 1,
+                };
+                self.error(
+                    span,
+                    "mismatched number of components".to_string(),
+                    format!("expected {} components, found {components}", expected.len()),
+                );
+            }
+        }
+    }
+
+    /* THE LEFT HAND SIDE OF AN ASSIGNMENT, `delete` AND `++` */
+    fn check_assignable(&mut self, expression: &Expression) {
+        let assignable = match &expression.kind {
+            ExpressionKind::Variable(identifier) => match self.declaration_of(identifier) {
+                Some(Declaration::Local(_)) | None => true,
+                Some(_declaration) => match self.part(_declaration) {
+                    Some(ContractPart::StateVariable(variable)) => {
+                        if variable.mutability == Some(VariableMutability::Constant) {
+                            self.error(
+                                expression.span,
+                                format!("cannot assign to constant `{}`", identifier.name),
+                                "constants cannot be modified",
+                            );
+                            return;
+                        }
+                        true
+                    }
+                    _ => false,
+                },
+            },
+            ExpressionKind::Index { .. } => {
+                !matches!(
+                    self.table.expressions.get(&expression.span),
+                    Some(Type::FixedBytes(1))
+                ) || matches!(&expression.kind, ExpressionKind::Index { base, .. }
+                    if matches!(self.table.expressions.get(&base.span), Some(Type::Bytes)))
+            }
+            ExpressionKind::MemberAccess {
+                expression: base, ..
+            } => !matches!(
+                self.table.expressions.get(&base.span),
+                Some(Type::Magic(_) | Type::Address { .. } | Type::Array { .. } | Type::Bytes)
+            ),
+            ExpressionKind::Tuple(components) => {
+                for component in components.iter().flatten() {
+                    self.check_assignable(component);
+                }
+                true
+            }
+            _ => false,
+        };
+        if !assignable {
+            self.error(
+                expression.span,
+                "invalid left-hand side of assignment".to_string(),
+                "cannot assign to this expression",
+            );
+        }
+    }
+
+    fn mismatch(&mut self, span: Span, expected: &Type, found: &Type) {
+        self.diagnostics
+            .push(mismatch_diagnostic(span, expected, found));
+    }
+
+    fn error(&mut self, span: Span, message: String, label: impl Into<String>) {
+        self.diagnostics.push(
+            CompilerError::SemanticError(SemanticError::InvalidOperation(message))
+                .diagnostic()
+                .with_label(span, label),
+        );
+    }
+
+    fn assembly_error(&mut self, span: Span, message: String, label: impl Into<String>) {
+        self.diagnostics.push(
+            CompilerError::SemanticError(SemanticError::InvalidAssembly(message))
+                .diagnostic()
+                .with_label(span, label),
+        );
+    }
+}
+
+fn mismatch_diagnostic(span: Span, expected: &Type, found: &Type) -> Diagnostic {
+    let mut diagnostic =
+        CompilerError::SemanticError(SemanticError::TypeMismatch("mismatched types".to_string()))
+            .diagnostic()
+            .with_label(span, format!("expected `{expected}`, found `{found}`"));
+    if let (Type::RationalConstant(..), Type::Integer { .. }) = (found, expected) {
+        diagnostic = diagnostic.with_note(format!("the constant does not fit in `{expected}`"));
+    }
+    diagnostic
+}
+
+fn builtin_member(name: &'static str, receiver: &Type) -> Type {
+    Type::BuiltinMember {
+        name,
+        receiver: Box::new(receiver.clone()),
+    }
+}
+
+fn magic_member(magic: &str, name: &str) -> Option<Type> {
+    let address = Type::Address { payable: false };
+    match (magic, name) {
+        ("msg", "sender") => Some(address),
+        ("msg", "value" | "gas") => Some(Type::UINT256),
+        ("msg", "data") => Some(Type::Bytes),
+        ("msg", "sig") => Some(Type::FixedBytes(4)),
+        ("block", "coinbase") => Some(Type::Address { payable: true }),
+        (
+            "block",
+            "timestamp" | "number" | "chainid" | "basefee" | "gaslimit" | "difficulty"
+            | "prevrandao" | "blobbasefee",
+        ) => Some(Type::UINT256),
+        ("tx", "origin") => Some(address),
+        ("tx", "gasprice") => Some(Type::UINT256),
+        ("abi", "encode") => Some(Type::Magic("abi.encode")),
+        ("abi", "encodePacked") => Some(Type::Magic("abi.encodePacked")),
+        ("abi", "encodeWithSelector") => Some(Type::Magic("abi.encodeWithSelector")),
+        ("abi", "encodeWithSignature") => Some(Type::Magic("abi.encodeWithSignature")),
+        ("abi", "encodeCall") => Some(Type::Magic("abi.encodeCall")),
+        ("abi", "decode") => Some(Type::Magic("abi.decode")),
+        _ => None,
+    }
+}
+
+fn call_result(returns: &[Type]) -> Type {
+    match returns {
+        [ty] => ty.clone(),
+        _ => Type::Tuple(returns.iter().cloned().map(Some).collect()),
+    }
+}
+
+fn fits(value: &BigRational, signed: bool, bits: u16) -> bool {
+    if !value.is_integer() {
+        return false;
+    }
+    let value = value.to_integer();
+    let (min, max) = Type::integer_range(signed, bits);
+    min <= value && value <= max
+}
@@ -0,0 +1,353 @@
+use std::collections::{HashMap, HashSet};
+
+use num_bigint::BigUint;
+use serde_json::Value;
+
+use crate::mods::{
+    functions::controllers::process_file_contents::read_file_contents,
+    types::{
+        ast::{ContractDefinition, ContractPart, FunctionKind, VariableMutability},
+        bindings::{Bindings, ContractId, Declaration},
+        compilation::snapshot_path,
+        compiler_errors::{CompilerError, IOError, SemanticError},
+        diagnostics::{Diagnostic, Diagnostics},
+        line_descriptors::Span,
+        settings::Settings,
+        source_graph::SourceGraph,
+        source_map::SourceMap,
+        storage_layout::StorageLayout,
+    },
+};
+
+/* CHECKS THAT EVERY `upgradable` CONTRACT CAN RUN BEHIND A PROXY AND THAT ITS STORAGE STILL
+LINES UP WITH THE SNAPSHOT OF THE DEPLOYED VERSION. NO PROXY IS GENERATED: THE CONTRACT IS THE
+IMPLEMENTATION OF AN ERC-1967 PROXY DEPLOYED SEPARATELY, WHOSE SLOT THE SNAPSHOT RECORDS */
+pub fn check_upgradable(
+    graph: &SourceGraph,
+    bindings: &Bindings,
+    sources: &SourceMap,
+    layouts: &HashMap<ContractId, StorageLayout>,
+    settings: &Settings,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut reported = HashSet::new();
+    for unit in &graph.units {
+        for (index, contract) in unit.ast.contracts.iter().enumerate() {
+            if !contract.upgradable {
+                continue;
+            }
+            let id = (unit.file_id, index);
+            for base in bindings.inheritance_order(id) {
+                let definition = &graph.units[base.0].ast.contracts[base.1];
+                check_proxy_safety(contract, definition, bindings, &mut reported, diagnostics);
+            }
+
+            let source_unit = &sources.get(unit.file_id).unwrap().name;
+            if let (Some(_layout), Some(_previous)) = (
+                layouts.get(&id),
+                previous_layout(source_unit, &contract.name.name, settings, diagnostics),
+            ) {
+                compare_layouts(contract, &_previous, _layout, diagnostics);
+            }
+        }
+    }
+}
+
+/* A PROXY ONLY BORROWS THE CODE OF THE CONTRACT: THE CONSTRUCTOR AND THE INITIALIZERS OF STATE
+VARIABLES NEVER RUN IN ITS STORAGE, IMMUTABLES ARE BAKED INTO THE IMPLEMENTATION INSTEAD OF
+LIVING IN THE PROXY, AND A `selfdestruct` CAN DESTROY THE PROXY OR THE IMPLEMENTATION */
+fn check_proxy_safety(
+    contract: &ContractDefinition,
+    definition: &ContractDefinition,
+    bindings: &Bindings,
+    reported: &mut HashSet<Span>,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut unsafe_parts = Vec::new();
+    for part in &definition.parts {
+        unsafe_parts.push(match part {
+            ContractPart::Function(function) if function.kind == FunctionKind::Constructor => (
+                "a constructor",
+                function.span,
+                "a proxy never runs this constructor",
+                "move the setup into an initializer function",
+            ),
+            ContractPart::StateVariable(variable)
+                if variable.mutability == Some(VariableMutability::Immutable) =>
+            {
+                (
+                    "immutable variables",
+                    variable.name.span,
+                    "stored in the implementation's code, not in the proxy's storage",
+                    "make it a regular state variable set by an initializer function",
+                )
+            }
+            ContractPart::StateVariable(variable)
+                if variable.mutability != Some(VariableMutability::Constant) =>
+            {
+                let Some(_initializer) = &variable.initializer else {
+                    continue;
+                };
+                (
+                    "state variable initializers",
+                    _initializer.span,
+                    "a proxy never runs this initializer",
+                    "set the variable in an initializer function",
+                )
+            }
+            _ => continue,
+        });
+    }
+    /* `selfdestruct`, CALLED IN SOLIDITY OR IN INLINE ASSEMBLY, ANYWHERE IN THE DEFINITION */
+    let mut destructs: Vec<Span> = bindings
+        .references
+        .iter()
+        .filter(|(span, declarations)| {
+            span.file_id == definition.span.file_id
+                && definition.span.start <= span.start
+                && span.end <= definition.span.end
+                && declarations.as_slice() == [Declaration::Builtin("selfdestruct")]
+        })
+        .map(|(span, _)| *span)
+        .collect();
+    destructs.sort_by_key(|span| span.start);
+    unsafe_parts.extend(destructs.into_iter().map(|span| {
+        (
+            "`selfdestruct`",
+            span,
+            "can destroy the proxy or the implementation it delegates to",
+            "remove the call",
+        )
+    }));
+
+    for (what, span, label, help) in unsafe_parts {
+        if !reported.insert(span) {
+            continue;
+        }
+        let mut diagnostic =
+            CompilerError::SemanticError(SemanticError::InvalidUpgradable(format!(
+                "upgradable contract `{}` cannot have {what}",
+                contract.name.name
+            )))
+            .diagnostic()
+            .with_label(span, label)
+            .with_help(help);
+        if definition.name.span != contract.name.span {
+            diagnostic = diagnostic
+                .with_secondary_label(contract.name.span, "inherited by this upgradable contract");
+        }
+        diagnostics.push(diagnostic);
+    }
+}
+
+/* THE SNAPSHOT THE LAST BUILD WROTE FOR THIS CONTRACT OF THIS SOURCE UNIT, IF THERE IS ONE */
+fn previous_layout(
+    source_unit: &str,
+    contract: &str,
+    settings: &Settings,
+    diagnostics: &mut Diagnostics,
+) -> Option<StorageLayout> {
+    let path = settings
+        .storage_snapshots
+        .as_ref()?
+        .join(snapshot_path(source_unit, contract));
+    if !path.exists() {
+        return None;
+    }
+    let parsed = read_file_contents(&path).and_then(|contents| {
+        serde_json::from_str::<Value>(&contents)
+            .map_err(|error| error.to_string())
+            .and_then(|value| StorageLayout::from_json(&value))
+            .map_err(|error| {
+                CompilerError::IOError(IOError::IOError(format!(
+                    "invalid storage layout snapshot {}: {error}",
+                    path.display()
+                )))
+            })
+    });
+    match parsed {
+        Ok(layout) => Some(layout),
+        Err(error) => {
+            diagnostics.push(error.diagnostic());
+            None
+        }
+    }
+}
+
+/* NEW VARIABLES MAY ONLY BE APPENDED. EVERY VARIABLE OF THE SNAPSHOT MUST KEEP ITS SLOT, ITS
+OFFSET AND A TYPE STORED THE SAME WAY, OTHERWISE THE NEW CODE READS THE PROXY'S STORAGE WRONG */
+fn compare_layouts(
+    contract: &ContractDefinition,
+    previous: &StorageLayout,
+    current: &StorageLayout,
+    diagnostics: &mut Diagnostics,
+) {
+    let name = &contract.name;
+    for old in &previous.variables {
+        let Some(position) = current.position(old) else {
+            /* A NEW NAME IN THE SAME PLACE, STORED THE SAME WAY, STILL READS THE SAME VALUE */
+            let renamed = current.variables.iter().find(|new| {
+                (&new.slot, new.offset) == (&old.slot, old.offset)
+                    && new.contract_name() == old.contract_name()
+                    && previous.position(new).is_none()
+                    && compatible(previous, &old.ty, current, &new.ty, &mut Vec::new()).is_ok()
+            });
+            if let Some(_new) = renamed {
+                diagnostics.push(
+                    Diagnostic::warning(format!(
+                        "state variable `{}` was renamed to `{}`",
+                        old.label, _new.label
+                    ))
+                    .with_label(
+                        _new.span.unwrap_or(name.span),
+                        format!("stores what `{}` stored", old.label),
+                    ),
+                );
+                continue;
+            }
+            diagnostics.push(
+                layout_change(
+                    format!("state variable `{}` was removed", old.label),
+                    name.span,
+                    format!(
+                        "`{}` no longer stores `{} {}`",
+                        name.name,
+                        previous.label(&old.ty),
+                        old.label
+                    ),
+                )
+                .with_note(format!(
+                    "the deployed layout stores it at {}, declared in `{}`",
+                    location(&old.slot, old.offset),
+                    old.contract
+                ))
+                .with_help("keep the variable, unused, so that later variables keep their slots"),
+            );
+            continue;
+        };
+        let new = &current.variables[position];
+        let span = new.span.unwrap_or(name.span);
+        let (old_label, new_label) = (previous.label(&old.ty), current.label(&new.ty));
+        if let Err(reason) = compatible(previous, &old.ty, current, &new.ty, &mut Vec::new()) {
+            let message = if old_label != new_label {
+                format!(
+                    "type of state variable `{}` changed from `{old_label}` to `{new_label}`",
+                    old.label
+                )
+            } else {
+                format!(
+                    "storage of state variable `{}` changed: {reason}",
+                    old.label
+                )
+            };
+            diagnostics.push(layout_change(
+                message,
+                span,
+                format!("was `{old_label}` in the deployed layout"),
+            ));
+        } else if (&new.slot, new.offset) != (&old.slot, old.offset) {
+            diagnostics.push(
+                layout_change(
+                    format!(
+                        "state variable `{}` moved from {} to {}",
+                        old.label,
+                        location(&old.slot, old.offset),
+                        location(&new.slot, new.offset)
+                    ),
+                    span,
+                    "the deployed contract stores it elsewhere",
+                )
+                .with_help("declare new state variables after the existing ones"),
+            );
+        }
+    }
+}
+
+/* WHETHER VALUES OF `old` IN THE DEPLOYED LAYOUT READ THE SAME AS `new`. STRUCTS MAY GROW AT
+THE END, EVERYTHING ELSE MUST STAY AS IT WAS */
+fn compatible(
+    previous: &StorageLayout,
+    old: &str,
+    current: &StorageLayout,
+    new: &str,
+    seen: &mut Vec<(String, String)>,
+) -> Result<(), String> {
+    let (Some(old_type), Some(new_type)) = (previous.types.get(old), current.types.get(new)) else {
+        return if old == new {
+            Ok(())
+        } else {
+            Err(format!("`{old}` became `{new}`"))
+        };
+    };
+    /* A STRUCT THAT REACHES ITSELF IS COMPARED ONCE */
+    let pair = (old.to_string(), new.to_string());
+    if seen.contains(&pair) {
+        return Ok(());
+    }
+    seen.push(pair);
+
+    if let (Some(_old_members), Some(_new_members)) = (&old_type.members, &new_type.members) {
+        for (index, member) in _old_members.iter().enumerate() {
+            let Some(_new_member) = _new_members.get(index) else {
+                return Err(format!(
+                    "member `{}` of `{}` was removed",
+                    member.label, old_type.label
+                ));
+            };
+            if _new_member.label != member.label {
+                return Err(format!(
+                    "member `{}` of `{}` was replaced by `{}`",
+                    member.label, old_type.label, _new_member.label
+                ));
+            }
+            if let Err(reason) = compatible(previous, &member.ty, current, &_new_member.ty, seen) {
+                return Err(format!(
+                    "member `{}` of `{}`: {reason}",
+                    member.label, old_type.label
+                ));
+            }
+            if (&_new_member.slot, _new_member.offset) != (&member.slot, member.offset) {
+                return Err(format!(
+                    "member `{}` of `{}` moved from {} to {}",
+                    member.label,
+                    old_type.label,
+                    location(&member.slot, member.offset),
+                    location(&_new_member.slot, _new_member.offset)
+                ));
+            }
+        }
+        return Ok(());
+    }
+
+    if old_type.encoding != new_type.encoding
+        || old_type.number_of_bytes != new_type.number_of_bytes
+        || old_type.members.is_some() != new_type.members.is_some()
+        || (old_type.base.is_none() && old_type.key.is_none() && old_type.label != new_type.label)
+    {
+        return Err(format!("`{}` became `{}`", old_type.label, new_type.label));
+    }
+    for (old_inner, new_inner) in [
+        (&old_type.base, &new_type.base),
+        (&old_type.key, &new_type.key),
+        (&old_type.value, &new_type.value),
+    ] {
+        if let (Some(_old), Some(_new)) = (old_inner, new_inner) {
+            compatible(previous, _old, current, _new, seen)?;
+        }
+    }
+    Ok(())
+}
+
+fn location(slot: &BigUint, offset: u8) -> String {
+    if offset == 0 {
+        format!("slot {slot}")
+    } else {
+        format!("slot {slot}, offset {offset}")
+    }
+}
+
+fn layout_change(message: String, span: Span, label: impl Into<String>) -> Diagnostic {
+    CompilerError::SemanticError(SemanticError::StorageLayoutChange(message))
+        .diagnostic()
+        .with_label(span, label)
+}
@@ -0,0 +1,30 @@
+use tiny_keccak::{Hasher, Keccak};
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+/* FIRST FOUR BYTES OF THE HASH OF A CANONICAL SIGNATURE SUCH AS `transfer(address,uint256)` */
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/* EIP-55 MIXED CASE ENCODING OF A 40 DIGIT HEX ADDRESS, RETURNED WITH ITS 0x PREFIX */
+pub fn checksum_address(digits: &str) -> String {
+    let lowercase = digits.to_ascii_lowercase();
+    let hash = hex::encode(keccak256(lowercase.as_bytes()));
+    let mut checksummed = String::from("0x");
+    for (character, nibble) in lowercase.chars().zip(hash.chars()) {
+        if nibble >= '8' {
+            checksummed.push(character.to_ascii_uppercase());
+        } else {
+            checksummed.push(character);
+        }
+    }
+    checksummed
+}
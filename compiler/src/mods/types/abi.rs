@@ -0,0 +1,117 @@
+use serde_json::{json, Value};
+
+use crate::mods::functions::helpers::hash_helper::{keccak256, selector};
+
+/* WHAT AN ENTRY OF THE ABI DESCRIBES, IN THE ORDER solc SORTS THEM */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AbiEntryKind {
+    Constructor,
+    Error,
+    Event,
+    Fallback,
+    Function,
+    Receive,
+}
+
+/* A PARAMETER, RETURN VALUE, EVENT FIELD OR STRUCT MEMBER. `ty` IS THE CANONICAL TYPE WITH
+STRUCTS WRITTEN `tuple`, THEIR FIELDS ARE THE `components` */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiParameter {
+    pub name: String,
+    pub ty: String,
+    /* THE TYPE AS WRITTEN IN THE SOURCE, E.G `struct Vault.Position` OR `contract IERC20` */
+    pub internal_type: String,
+    pub components: Vec<AbiParameter>,
+    /* ONLY FOR EVENT FIELDS */
+    pub indexed: Option<bool>,
+}
+
+/* ONE ENTRY OF THE ETHEREUM JSON ABI */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiEntry {
+    pub kind: AbiEntryKind,
+    /* EMPTY FOR THE CONSTRUCTOR, `fallback` AND `receive` */
+    pub name: String,
+    pub inputs: Vec<AbiParameter>,
+    pub outputs: Vec<AbiParameter>,
+    /* `pure`, `view`, `nonpayable` OR `payable`, NONE FOR EVENTS AND ERRORS */
+    pub state_mutability: Option<&'static str>,
+    pub anonymous: bool,
+    pub gasless: bool,
+    /* `name(type1,type2)`, HASHED INTO THE SELECTOR OR THE EVENT TOPIC */
+    pub signature: String,
+}
+
+impl AbiEntryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AbiEntryKind::Constructor => "constructor",
+            AbiEntryKind::Error => "error",
+            AbiEntryKind::Event => "event",
+            AbiEntryKind::Fallback => "fallback",
+            AbiEntryKind::Function => "function",
+            AbiEntryKind::Receive => "receive",
+        }
+    }
+}
+
+impl AbiParameter {
+    fn to_json(&self) -> Value {
+        let mut parameter = json!({
+            "internalType": self.internal_type,
+            "name": self.name,
+            "type": self.ty,
+        });
+        if !self.components.is_empty() {
+            parameter["components"] = self.components.iter().map(Self::to_json).collect();
+        }
+        if let Some(_indexed) = self.indexed {
+            parameter["indexed"] = json!(_indexed);
+        }
+        parameter
+    }
+}
+
+impl AbiEntry {
+    /* THE STANDARD FIELDS, PLUS `selector` ON FUNCTIONS AND ERRORS, `topic` ON EVENTS THAT ARE
+    NOT ANONYMOUS AND `gasless` ON FUNCTIONS A RELAYER MAY CALL */
+    pub fn to_json(&self) -> Value {
+        let mut entry = json!({ "type": self.kind.as_str() });
+        let parameters = |parameters: &[AbiParameter]| -> Value {
+            parameters.iter().map(AbiParameter::to_json).collect()
+        };
+        match self.kind {
+            AbiEntryKind::Constructor => entry["inputs"] = parameters(&self.inputs),
+            AbiEntryKind::Fallback | AbiEntryKind::Receive => {}
+            AbiEntryKind::Function => {
+                entry["name"] = json!(self.name);
+                entry["inputs"] = parameters(&self.inputs);
+                entry["outputs"] = parameters(&self.outputs);
+                entry["selector"] = json!(format!("0x{}", hex::encode(selector(&self.signature))));
+                if self.gasless {
+                    entry["gasless"] = json!(true);
+                }
+            }
+            AbiEntryKind::Event => {
+                entry["name"] = json!(self.name);
+                entry["inputs"] = parameters(&self.inputs);
+                entry["anonymous"] = json!(self.anonymous);
+                if !self.anonymous {
+                    entry["topic"] = json!(format!(
+                        "0x{}",
+                        hex::encode(keccak256(self.signature.as_bytes()))
+                    ));
+                }
+            }
+            AbiEntryKind::Error => {
+                entry["name"] = json!(self.name);
+                entry["inputs"] = parameters(&self.inputs);
+                entry["selector"] = json!(format!("0x{}", hex::encode(selector(&self.signature))));
+            }
+        }
+        if let Some(_mutability) = self.state_mutability {
+            entry["stateMutability"] = json!(_mutability);
+        }
+        entry
+    }
+}
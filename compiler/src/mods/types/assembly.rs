@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use super::opcodes::Opcode;
+
+/* A JUMP TARGET, NUMBERED IN THE ORDER THE CODE GENERATOR ASKS FOR THEM */
+pub type Label = usize;
+
+/* ONE ITEM OF EVM ASSEMBLY. EVERYTHING THAT NAMES A POSITION IN THE CODE IS RESOLVED BY
+`Assembly::assemble` ONCE THE SIZE OF EVERY ITEM IS KNOWN */
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssemblyItem {
+    Op(Opcode),
+    /* THE SHORTEST PUSH OF THE VALUE, PUSH0 FOR ZERO WHEN THE TARGET HAS IT */
+    Push(BigUint),
+    /* PUSH2 OF THE OFFSET OF A LABEL */
+    PushLabel(Label),
+    /* A JUMPDEST */
+    Label(Label),
+    /* PUSH2 OF THE OFFSET AND THE SIZE OF A DATA SECTION, WHICH ALL FOLLOW THE CODE */
+    PushDataOffset(usize),
+    PushDataSize(usize),
+    /* PUSH2 OF THE SIZE OF THE CODE AND ALL DATA, WHERE CONSTRUCTOR ARGUMENTS START */
+    PushProgramSize,
+    /* PUSH32 OF ZEROS THE CONSTRUCTOR OVERWRITES WITH THE VALUE OF AN IMMUTABLE */
+    PushImmutable(usize),
+}
+
+/* A PIECE OF CODE BEING GENERATED. `height` FOLLOWS THE NUMBER OF VALUES ON THE STACK AS
+ITEMS ARE ADDED, THE GENERATOR SETS IT BY HAND AFTER A JUMP */
+#[derive(Debug, Clone, Default)]
+pub struct Assembly {
+    pub items: Vec<AssemblyItem>,
+    /* SECTIONS APPENDED AFTER THE CODE, SUCH AS THE RUNTIME CODE IN THE CREATION CODE */
+    pub data: Vec<Vec<u8>>,
+    pub height: usize,
+    labels: usize,
+    push0: bool,
+}
+
+/* ASSEMBLED CODE AND, FOR EVERY IMMUTABLE, WHERE ITS PUSH32 PLACEHOLDERS ARE */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bytecode {
+    pub code: Vec<u8>,
+    pub immutables: BTreeMap<usize, Vec<usize>>,
+}
+
+impl Assembly {
+    pub fn new(push0: bool) -> Self {
+        Assembly {
+            push0,
+            ..Assembly::default()
+        }
+    }
+
+    pub fn new_label(&mut self) -> Label {
+        self.labels += 1;
+        self.labels - 1
+    }
+
+    pub fn op(&mut self, opcode: Opcode) {
+        let (pops, pushes) = opcode.stack_io();
+        debug_assert!(self.height >= pops, "stack underflow at {opcode}");
+        self.height = self.height.saturating_sub(pops) + pushes;
+        self.items.push(AssemblyItem::Op(opcode));
+    }
+
+    pub fn push(&mut self, value: impl Into<BigUint>) {
+        self.append(AssemblyItem::Push(value.into()));
+    }
+
+    pub fn push_label(&mut self, label: Label) {
+        self.append(AssemblyItem::PushLabel(label));
+    }
+
+    pub fn label(&mut self, label: Label) {
+        self.items.push(AssemblyItem::Label(label));
+    }
+
+    pub fn jump(&mut self, label: Label) {
+        self.push_label(label);
+        self.op(Opcode::Jump);
+    }
+
+    pub fn jump_if(&mut self, label: Label) {
+        self.push_label(label);
+        self.op(Opcode::JumpI);
+    }
+
+    /* ADDS A DATA SECTION, RETURNING ITS INDEX */
+    pub fn add_data(&mut self, data: Vec<u8>) -> usize {
+        self.data.push(data);
+        self.data.len() - 1
+    }
+
+    /* ITEMS THAT PUSH ONE VALUE */
+    pub fn append(&mut self, item: AssemblyItem) {
+        match item {
+            AssemblyItem::Op(opcode) => return self.op(opcode),
+            AssemblyItem::Label(_) => {}
+            _ => self.height += 1,
+        }
+        self.items.push(item);
+    }
+
+    fn push_size(&self, value: &BigUint) -> usize {
+        if value.is_zero() {
+            if self.push0 {
+                1
+            } else {
+                2
+            }
+        } else {
+            1 + value.to_bytes_be().len()
+        }
+    }
+
+    fn size(&self, item: &AssemblyItem) -> usize {
+        match item {
+            AssemblyItem::Op(_) | AssemblyItem::Label(_) => 1,
+            AssemblyItem::Push(value) => self.push_size(value),
+            AssemblyItem::PushLabel(_)
+            | AssemblyItem::PushDataOffset(_)
+            | AssemblyItem::PushDataSize(_)
+            | AssemblyItem::PushProgramSize => 3,
+            AssemblyItem::PushImmutable(_) => 33,
+        }
+    }
+
+    /* LAYS OUT THE ITEMS AND RESOLVES THE LABELS. OFFSETS ARE PUSHED AS TWO BYTES, SO THE
+    PROGRAM CANNOT BE LARGER THAN 64 KIB */
+    pub fn assemble(&self) -> Result<Bytecode, String> {
+        let mut labels = vec![0usize; self.labels];
+        let mut offset = 0;
+        for item in &self.items {
+            if let AssemblyItem::Label(_label) = item {
+                labels[*_label] = offset;
+            }
+            offset += self.size(item);
+        }
+        let code_size = offset;
+        let mut data_offsets = Vec::new();
+        for data in &self.data {
+            data_offsets.push(offset);
+            offset += data.len();
+        }
+        let program_size = offset;
+        if program_size > 0xffff {
+            return Err(format!(
+                "the program is {program_size} bytes long, offsets beyond 65535 bytes cannot be addressed"
+            ));
+        }
+
+        let mut bytecode = Bytecode {
+            code: Vec::with_capacity(program_size),
+            immutables: BTreeMap::new(),
+        };
+        let push2 = |code: &mut Vec<u8>, value: usize| {
+            code.push(Opcode::Push(2).byte());
+            code.extend_from_slice(&(value as u16).to_be_bytes());
+        };
+        for item in &self.items {
+            match item {
+                AssemblyItem::Op(opcode) => bytecode.code.push(opcode.byte()),
+                AssemblyItem::Push(value) => {
+                    if value.is_zero() {
+                        if self.push0 {
+                            bytecode.code.push(Opcode::Push(0).byte());
+                        } else {
+                            bytecode.code.extend([Opcode::Push(1).byte(), 0]);
+                        }
+                    } else {
+                        let bytes = value.to_bytes_be();
+                        bytecode.code.push(Opcode::Push(bytes.len() as u8).byte());
+                        bytecode.code.extend(bytes);
+                    }
+                }
+                AssemblyItem::PushLabel(label) => push2(&mut bytecode.code, labels[*label]),
+                AssemblyItem::Label(_) => bytecode.code.push(Opcode::JumpDest.byte()),
+                AssemblyItem::PushDataOffset(index) => {
+                    push2(&mut bytecode.code, data_offsets[*index])
+                }
+                AssemblyItem::PushDataSize(index) => {
+                    push2(&mut bytecode.code, self.data[*index].len())
+                }
+                AssemblyItem::PushProgramSize => push2(&mut bytecode.code, program_size),
+                AssemblyItem::PushImmutable(id) => {
+                    bytecode
+                        .immutables
+                        .entry(*id)
+                        .or_default()
+                        .push(bytecode.code.len());
+                    bytecode.code.push(Opcode::Push(32).byte());
+                    bytecode.code.extend([0; 32]);
+                }
+            }
+        }
+        debug_assert_eq!(bytecode.code.len(), code_size);
+        for data in &self.data {
+            bytecode.code.extend(data);
+        }
+        Ok(bytecode)
+    }
+}
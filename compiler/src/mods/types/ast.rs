@@ -0,0 +1,524 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+use super::line_descriptors::Span;
+
+/* TYPED SYNTAX TREE PRODUCED BY THE PARSER. EVERY NODE CARRIES THE SPAN IT COVERS */
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identifier {
+    pub span: Span,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SourceUnit {
+    pub imports: Vec<ImportDirective>,
+    pub custom_errors: Vec<ErrorDefinition>,
+    /* STRUCTS, ENUMS, CONSTANTS AND FUNCTIONS DECLARED OUTSIDE ANY CONTRACT ARE GATHERED INTO
+    ONE UNNAMED `ContractKind::File` DEFINITION, LAST IN THE LIST */
+    pub contracts: Vec<ContractDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportDirective {
+    pub span: Span,
+    pub path: String,
+    pub symbols: ImportSymbols,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportSymbols {
+    /* import "./IT.sol"; OR import "./IT.sol" as It; */
+    All(Option<Identifier>),
+    /* import * as It from "./IT.sol"; */
+    Glob(Identifier),
+    /* import {IERC20, IT as It} from "./IT.sol"; */
+    Named(Vec<ImportAlias>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportAlias {
+    pub name: Identifier,
+    pub alias: Option<Identifier>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContractKind {
+    Contract,
+    AbstractContract,
+    Interface,
+    Library,
+    /* THE FILE LEVEL DEFINITIONS OF A SOURCE UNIT */
+    File,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractDefinition {
+    pub span: Span,
+    pub kind: ContractKind,
+    /* `upgradable contract`, DEPLOYED BEHIND A PROXY */
+    pub upgradable: bool,
+    pub name: Identifier,
+    pub bases: Vec<InheritanceSpecifier>,
+    pub parts: Vec<ContractPart>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InheritanceSpecifier {
+    pub span: Span,
+    pub name: Vec<Identifier>,
+    pub arguments: Option<Vec<Expression>>,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractPart {
+    StateVariable(VariableDefinition),
+    Function(FunctionDefinition),
+    Modifier(ModifierDefinition),
+    Event(EventDefinition),
+    Error(ErrorDefinition),
+    Struct(StructDefinition),
+    Enum(EnumDefinition),
+    Using(UsingDirective),
+    Cron(CronDefinition),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Visibility {
+    Public,
+    Private,
+    Internal,
+    External,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StateMutability {
+    Pure,
+    View,
+    Payable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VariableMutability {
+    Constant,
+    Immutable,
+    Mutable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageLocation {
+    Memory,
+    Storage,
+    Calldata,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableDefinition {
+    pub span: Span,
+    pub ty: TypeName,
+    pub visibility: Option<Visibility>,
+    pub mutability: Option<VariableMutability>,
+    pub overrides: Option<Vec<Vec<Identifier>>>,
+    pub name: Identifier,
+    pub initializer: Option<Expression>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FunctionKind {
+    Function,
+    Constructor,
+    Fallback,
+    Receive,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionDefinition {
+    pub span: Span,
+    pub kind: FunctionKind,
+    pub name: Option<Identifier>,
+    pub parameters: Vec<Parameter>,
+    pub visibility: Option<Visibility>,
+    pub mutability: Option<StateMutability>,
+    pub is_virtual: bool,
+    pub overrides: Option<Vec<Vec<Identifier>>>,
+    pub modifiers: Vec<ModifierInvocation>,
+    pub gasless: bool,
+    pub returns: Vec<Parameter>,
+    pub body: Option<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub span: Span,
+    pub ty: TypeName,
+    pub storage: Option<StorageLocation>,
+    pub indexed: bool,
+    pub name: Option<Identifier>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModifierInvocation {
+    pub span: Span,
+    pub name: Vec<Identifier>,
+    pub arguments: Option<Vec<Expression>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModifierDefinition {
+    pub span: Span,
+    pub name: Identifier,
+    pub parameters: Vec<Parameter>,
+    pub is_virtual: bool,
+    pub overrides: Option<Vec<Vec<Identifier>>>,
+    pub body: Option<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventDefinition {
+    pub span: Span,
+    pub name: Identifier,
+    pub parameters: Vec<Parameter>,
+    pub anonymous: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorDefinition {
+    pub span: Span,
+    pub name: Identifier,
+    pub parameters: Vec<Parameter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructDefinition {
+    pub span: Span,
+    pub name: Identifier,
+    pub fields: Vec<StructField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructField {
+    pub span: Span,
+    pub ty: TypeName,
+    pub name: Identifier,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumDefinition {
+    pub span: Span,
+    pub name: Identifier,
+    pub values: Vec<Identifier>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsingDirective {
+    pub span: Span,
+    pub library: Vec<Identifier>,
+    /* None MEANS `using L for *` */
+    pub target: Option<TypeName>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronDefinition {
+    pub span: Span,
+    /* THE FIVE FIELD SCHEDULE STRING, `"0 8 * * 1"` */
+    pub schedule: Expression,
+    /* `cron("..", n)` RUNS THE BLOCK AT MOST n TIMES, 0 OR NONE MEANS NO LIMIT */
+    pub max_runs: Option<Expression>,
+    pub body: Block,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeName {
+    pub span: Span,
+    pub kind: TypeNameKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeNameKind {
+    Elementary(ElementaryType),
+    UserDefined(Vec<Identifier>),
+    Array {
+        base: Box<TypeName>,
+        length: Option<Box<Expression>>,
+    },
+    Mapping {
+        key: Box<TypeName>,
+        key_name: Option<Identifier>,
+        value: Box<TypeName>,
+        value_name: Option<Identifier>,
+    },
+    Function {
+        parameters: Vec<Parameter>,
+        visibility: Option<Visibility>,
+        mutability: Option<StateMutability>,
+        returns: Vec<Parameter>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElementaryType {
+    Address,
+    AddressPayable,
+    Bool,
+    String,
+    Bytes,
+    FixedBytes(u8),
+    Uint(u16),
+    Int(u16),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub span: Span,
+    pub unchecked: bool,
+    pub statements: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub span: Span,
+    pub kind: StatementKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementKind {
+    Block(Block),
+    /* A SINGLE DECLARATION OR A TUPLE `(bool s, , uint j) = ...` WITH EMPTY SLOTS AS None */
+    VariableDeclaration {
+        declarations: Vec<Option<VariableDeclaration>>,
+        initializer: Option<Expression>,
+    },
+    Expression(Expression),
+    If {
+        condition: Expression,
+        then: Box<Statement>,
+        otherwise: Option<Box<Statement>>,
+    },
+    For {
+        init: Option<Box<Statement>>,
+        condition: Option<Expression>,
+        update: Option<Expression>,
+        body: Box<Statement>,
+    },
+    While {
+        condition: Expression,
+        body: Box<Statement>,
+    },
+    DoWhile {
+        body: Box<Statement>,
+        condition: Expression,
+    },
+    Return(Option<Expression>),
+    Emit(Expression),
+    /* `revert("reason")` HAS NO ERROR PATH, `revert Err(..)` DOES */
+    Revert {
+        error: Option<Vec<Identifier>>,
+        arguments: CallArguments,
+    },
+    /* `assembly ("memory-safe") { ... }`, THE FLAGS ARE THE STRINGS IN PARENTHESES */
+    Assembly {
+        flags: Vec<String>,
+        body: YulBlock,
+    },
+    Break,
+    Continue,
+    Placeholder,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableDeclaration {
+    pub span: Span,
+    pub ty: TypeName,
+    pub storage: Option<StorageLocation>,
+    pub name: Identifier,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub span: Span,
+    pub kind: ExpressionKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionKind {
+    Literal(Literal),
+    Variable(Identifier),
+    ElementaryType(ElementaryType),
+    MemberAccess {
+        expression: Box<Expression>,
+        member: Identifier,
+    },
+    Index {
+        base: Box<Expression>,
+        index: Option<Box<Expression>>,
+    },
+    FunctionCall {
+        callee: Box<Expression>,
+        arguments: CallArguments,
+    },
+    CallOptions {
+        callee: Box<Expression>,
+        options: Vec<NamedArgument>,
+    },
+    Unary {
+        operator: UnaryOperator,
+        operand: Box<Expression>,
+    },
+    Binary {
+        operator: BinaryOperator,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    /* operator IS Some FOR COMPOUND ASSIGNMENTS SUCH AS `+=` */
+    Assignment {
+        operator: Option<BinaryOperator>,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    Conditional {
+        condition: Box<Expression>,
+        then: Box<Expression>,
+        otherwise: Box<Expression>,
+    },
+    Tuple(Vec<Option<Expression>>),
+    ArrayLiteral(Vec<Expression>),
+    New(TypeName),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallArguments {
+    Positional(Vec<Expression>),
+    Named(Vec<NamedArgument>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedArgument {
+    pub span: Span,
+    pub name: Identifier,
+    pub value: Expression,
+}
+
+/* UNIT SUFFIXES SUCH AS `1 ether` ARE ALREADY APPLIED BY THE LEXER */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Bool(bool),
+    /* THE VALUE AND, FOR A HEX LITERAL, ITS NUMBER OF DIGITS */
+    Number(BigInt, Option<usize>),
+    Rational(BigRational),
+    String(String),
+    HexString(Vec<u8>),
+    Address(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOperator {
+    Not,
+    BitNot,
+    Negate,
+    PreIncrement,
+    PreDecrement,
+    PostIncrement,
+    PostDecrement,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Exp,
+    Shl,
+    Shr,
+    BitAnd,
+    BitOr,
+    BitXor,
+    And,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/* INLINE ASSEMBLY. NAMES ARE RESOLVED LIKE SOLIDITY ONES, BUILTINS SUCH AS `add` OR `sstore`
+INCLUDED */
+#[derive(Debug, Clone, PartialEq)]
+pub struct YulBlock {
+    pub span: Span,
+    pub statements: Vec<YulStatement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct YulStatement {
+    pub span: Span,
+    pub kind: YulStatementKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum YulStatementKind {
+    Block(YulBlock),
+    /* `let a, b := f()`, VARIABLES WITHOUT A VALUE START AT ZERO */
+    VariableDeclaration {
+        names: Vec<Identifier>,
+        value: Option<YulExpression>,
+    },
+    /* `a, b := f()`, A TARGET IS A NAME OR `x.slot` */
+    Assignment {
+        targets: Vec<Vec<Identifier>>,
+        value: YulExpression,
+    },
+    Expression(YulExpression),
+    If {
+        condition: YulExpression,
+        body: YulBlock,
+    },
+    /* THE VALUE OF EACH CASE IS A LITERAL */
+    Switch {
+        expression: YulExpression,
+        cases: Vec<(YulExpression, YulBlock)>,
+        default: Option<YulBlock>,
+    },
+    For {
+        init: YulBlock,
+        condition: YulExpression,
+        post: YulBlock,
+        body: YulBlock,
+    },
+    Function(YulFunction),
+    Break,
+    Continue,
+    Leave,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct YulFunction {
+    pub span: Span,
+    pub name: Identifier,
+    pub parameters: Vec<Identifier>,
+    pub returns: Vec<Identifier>,
+    pub body: YulBlock,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct YulExpression {
+    pub span: Span,
+    pub kind: YulExpressionKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum YulExpressionKind {
+    Literal(Literal),
+    /* A VARIABLE, OR `x.slot` AND `x.offset` OF A SOLIDITY ONE IN STORAGE */
+    Path(Vec<Identifier>),
+    Call {
+        function: Identifier,
+        arguments: Vec<YulExpression>,
+    },
+}
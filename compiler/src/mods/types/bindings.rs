@@ -0,0 +1,123 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::{line_descriptors::Span, source_graph::Symbol};
+
+/* WHAT A NAME IN THE SOURCE REFERS TO. CONTRACT MEMBERS ARE ADDRESSED BY THE FILE, THE INDEX
+INTO `ast.contracts` AND THE INDEX INTO `parts` */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Declaration {
+    Contract {
+        file_id: usize,
+        contract: usize,
+    },
+    /* AN ERROR DECLARED AT FILE LEVEL */
+    Error {
+        file_id: usize,
+        index: usize,
+    },
+    Unit(usize),
+    /* STATE VARIABLE, FUNCTION, MODIFIER, EVENT, ERROR, STRUCT OR ENUM OF A CONTRACT */
+    Member {
+        file_id: usize,
+        contract: usize,
+        part: usize,
+    },
+    EnumValue {
+        file_id: usize,
+        contract: usize,
+        part: usize,
+        value: usize,
+    },
+    /* A PARAMETER OR LOCAL VARIABLE, IDENTIFIED BY THE SPAN OF ITS NAME */
+    Local(Span),
+    /* GLOBALS SUCH AS `msg`, `require` OR `keccak256` */
+    Builtin(&'static str),
+}
+
+impl From<Symbol> for Declaration {
+    fn from(symbol: Symbol) -> Self {
+        match symbol {
+            Symbol::Contract { file_id, index } => Declaration::Contract {
+                file_id,
+                contract: index,
+            },
+            Symbol::Error { file_id, index } => Declaration::Error { file_id, index },
+            Symbol::Member {
+                file_id,
+                contract,
+                part,
+            } => Declaration::Member {
+                file_id,
+                contract,
+                part,
+            },
+            Symbol::Unit(file_id) => Declaration::Unit(file_id),
+        }
+    }
+}
+
+/* A CONTRACT, AS THE FILE ID AND THE INDEX INTO `ast.contracts` */
+pub type ContractId = (usize, usize);
+
+/* OUTPUT OF NAME RESOLUTION. EVERY RESOLVED IDENTIFIER, KEYED BY ITS SPAN, MAPS TO THE
+DECLARATIONS IT CAN MEAN, MORE THAN ONE ONLY FOR OVERLOADED FUNCTIONS AND EVENTS */
+#[derive(Debug, Clone, Default)]
+pub struct Bindings {
+    pub references: HashMap<Span, Vec<Declaration>>,
+    /* NAMES DECLARED IN EACH CONTRACT OR INHERITED BY IT */
+    pub members: HashMap<ContractId, BTreeMap<String, Vec<Declaration>>>,
+    /* DIRECT BASES OF EACH CONTRACT, IN THE ORDER THEY ARE LISTED */
+    pub bases: HashMap<ContractId, Vec<ContractId>>,
+    /* C3 LINEARIZATION OF EACH CONTRACT, THE CONTRACT ITSELF FIRST AND ITS MOST BASE ONE LAST */
+    pub linearization: HashMap<ContractId, Vec<ContractId>>,
+    /* FOR EACH CONTRACT, WHERE THE ARGUMENTS OF EVERY BASE CONSTRUCTOR THAT TAKES ANY ARE WRITTEN */
+    pub constructor_arguments: HashMap<ContractId, BTreeMap<ContractId, BaseArguments>>,
+}
+
+/* ARGUMENTS OF A BASE CONSTRUCTOR, GIVEN IN AN INHERITANCE LIST, `is B(1)`, OR ON A
+CONSTRUCTOR, `constructor() B(1)` */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseArguments {
+    /* INDEX INTO `bases` OF `contract` */
+    Specifier {
+        contract: ContractId,
+        index: usize,
+    },
+    /* INDEX INTO THE MODIFIERS OF THE CONSTRUCTOR AT `part` OF `contract` */
+    Constructor {
+        contract: ContractId,
+        part: usize,
+        index: usize,
+    },
+}
+
+impl Bindings {
+    pub fn get(&self, span: &Span) -> Option<&[Declaration]> {
+        self.references.get(span).map(Vec::as_slice)
+    }
+
+    /* WHETHER `contract` IS `base` OR INHERITS FROM IT, DIRECTLY OR NOT */
+    pub fn derives_from(&self, contract: ContractId, base: ContractId) -> bool {
+        let mut pending = vec![contract];
+        let mut seen = Vec::new();
+        while let Some(_contract) = pending.pop() {
+            if _contract == base {
+                return true;
+            }
+            if !seen.contains(&_contract) {
+                seen.push(_contract);
+                pending.extend(self.bases.get(&_contract).into_iter().flatten());
+            }
+        }
+        false
+    }
+
+    /* `contract` AND EVERY CONTRACT IT INHERITS FROM, MOST BASE FIRST, THE ORDER STATE VARIABLES
+    ARE STORED IN */
+    pub fn inheritance_order(&self, contract: ContractId) -> Vec<ContractId> {
+        match self.linearization.get(&contract) {
+            Some(_linearization) => _linearization.iter().rev().copied().collect(),
+            None => vec![contract],
+        }
+    }
+}
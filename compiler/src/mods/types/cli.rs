@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use super::{
+    diagnostics::ErrorFormat,
+    settings::{Emit, EvmVersion, Pass, Remapping, Settings},
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "solc", version, about = "Solidity compiler")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Format used to report errors and warnings: human or json
+    #[arg(long, global = true, default_value = "human", value_parser = parse_error_format)]
+    pub error_format: ErrorFormat,
+
+    /// Do not print the timing line once the program completes
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Compile the inputs and write artifacts to the output directory
+    Build(CompileArgs),
+    /// Report errors without producing any output
+    Check(CompileArgs),
+    /// Print the token stream of each input
+    Tokens(CompileArgs),
+    /// Print the syntax tree of each input
+    Ast(CompileArgs),
+    /// Re-indent the inputs and print them, or rewrite them with --write
+    Fmt {
+        #[command(flatten)]
+        args: CompileArgs,
+        /// Exit with an error if any input is not formatted
+        #[arg(long, conflicts_with = "write")]
+        check: bool,
+        /// Overwrite the inputs with their formatted contents
+        #[arg(long)]
+        write: bool,
+    },
+    /// Print the ABI of every contract
+    Abi(CompileArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CompileArgs {
+    /// Source files, optionally mixed with import remappings written as [context:]prefix=target
+    #[arg(required = true, value_name = "FILES")]
+    pub inputs: Vec<String>,
+
+    /// Directory the artifacts are written to, each under the path of its source file
+    #[arg(short, long)]
+    pub output_dir: Option<PathBuf>,
+
+    /// Target EVM version
+    #[arg(long, default_value = "cancun", value_parser = str::parse::<EvmVersion>)]
+    pub evm_version: EvmVersion,
+
+    /// Enable the optimizer
+    #[arg(long)]
+    pub optimize: bool,
+
+    /// Root directory source unit names are resolved against
+    #[arg(long)]
+    pub base_path: Option<PathBuf>,
+
+    /// Extra directories searched for imports, may be repeated
+    #[arg(long = "include-path")]
+    pub include_paths: Vec<PathBuf>,
+
+    /// Directory with the storage layout snapshots upgradable contracts are checked against,
+    /// defaults to the output directory
+    #[arg(long)]
+    pub storage_snapshots: Option<PathBuf>,
+
+    /// Intermediate outputs to write next to the artifacts, may be repeated
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub emit: Vec<Emit>,
+
+    /// Optimizer passes to leave out, may be repeated
+    #[arg(long = "disable-pass", value_enum, value_delimiter = ',')]
+    pub disabled_passes: Vec<Pass>,
+}
+
+impl Command {
+    pub fn args(&self) -> &CompileArgs {
+        match self {
+            Command::Build(args)
+            | Command::Check(args)
+            | Command::Tokens(args)
+            | Command::Ast(args)
+            | Command::Abi(args)
+            | Command::Fmt { args, .. } => args,
+        }
+    }
+}
+
+impl CompileArgs {
+    /* INPUTS THAT ARE NOT REMAPPINGS */
+    pub fn files(&self) -> Vec<&str> {
+        self.inputs
+            .iter()
+            .filter(|input| !input.contains('='))
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn settings(&self) -> Result<Settings, String> {
+        let remappings = self
+            .inputs
+            .iter()
+            .filter(|input| input.contains('='))
+            .map(|input| input.parse::<Remapping>())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Settings {
+            evm_version: self.evm_version,
+            optimize: self.optimize,
+            remappings,
+            base_path: self.base_path.clone(),
+            include_paths: self.include_paths.clone(),
+            output_dir: self.output_dir.clone(),
+            storage_snapshots: self
+                .storage_snapshots
+                .clone()
+                .or_else(|| self.output_dir.clone()),
+            emit: self.emit.clone(),
+            disabled_passes: self.disabled_passes.clone(),
+        })
+    }
+}
+
+fn parse_error_format(input: &str) -> Result<ErrorFormat, String> {
+    match input {
+        "human" => Ok(ErrorFormat::Human),
+        "json" => Ok(ErrorFormat::Json),
+        _ => Err(format!(
+            "unknown error format `{input}`, expected `human` or `json`"
+        )),
+    }
+}
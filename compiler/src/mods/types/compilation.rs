@@ -0,0 +1,156 @@
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use super::{
+    abi::AbiEntry, bindings::Bindings, cron::CronJob, diagnostics::Diagnostics,
+    line_descriptors::LineDescriptions, semantic_types::TypeTable, source_graph::SourceGraph,
+    source_map::SourceMap, storage_layout::StorageLayout, token::Token,
+};
+use crate::mods::{constants::constants::IMPLEMENTATION_SLOT, types::ast::SourceUnit};
+
+/* EVERYTHING A COMPILATION PRODUCED. STAGES THAT FAILED LEAVE THEIR OUTPUT EMPTY AND SAY WHY
+IN `diagnostics` */
+#[derive(Debug, Clone, Default)]
+pub struct CompilationOutput {
+    /* THE INPUT SOURCES FOLLOWED BY EVERY FILE THEY IMPORTED */
+    pub sources: SourceMap,
+    pub graph: SourceGraph,
+    pub bindings: Bindings,
+    pub types: TypeTable,
+    pub diagnostics: Diagnostics,
+    pub artifacts: Vec<Artifact>,
+}
+
+/* OUTPUT FOR ONE CONTRACT OF AN INPUT SOURCE UNIT */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Artifact {
+    pub source_unit: String,
+    pub contract: String,
+    /* THE ETHEREUM JSON ABI, SORTED AS solc SORTS IT */
+    pub abi: Vec<AbiEntry>,
+    /* THE CONTRACT'S `cron` BLOCKS, FOR THE OFF-CHAIN SCHEDULER */
+    pub cron: Vec<CronJob>,
+    /* FUNCTIONS A RELAYER MAY CALL ON BEHALF OF A USER, SORTED BY SIGNATURE */
+    pub gasless: Vec<GaslessFunction>,
+    /* DEPLOYED BEHIND A PROXY, SO ITS STORAGE LAYOUT IS SNAPSHOT FOR THE NEXT UPGRADE */
+    pub upgradable: bool,
+    /* WHERE THE STATE VARIABLES LIVE, NONE FOR INTERFACES AND LIBRARIES */
+    pub storage_layout: Option<StorageLayout>,
+    /* NONE FOR WHAT CANNOT BE DEPLOYED: INTERFACES, ABSTRACT CONTRACTS AND LIBRARIES, WHOSE
+    FUNCTIONS ARE COMPILED INTO THE CONTRACTS THAT CALL THEM */
+    pub bytecode: Option<ContractBytecode>,
+}
+
+/* THE CODE THAT DEPLOYS A CONTRACT, FOLLOWED BY ITS CONSTRUCTOR ARGUMENTS WHEN SENT, AND THE
+CODE IT LEAVES ON CHAIN */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractBytecode {
+    pub creation: Vec<u8>,
+    pub runtime: Vec<u8>,
+    /* THE IR BOTH WERE LOWERED FROM, WHEN ASKED FOR WITH `--emit=ir` */
+    pub ir: Option<String>,
+    /* THE IR BEFORE AND AFTER EACH OPTIMIZER PASS, WITH `--emit=passes` */
+    pub passes: Option<String>,
+}
+
+/* A `gasless` FUNCTION AS THE RELAYER SDKS SEE IT */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GaslessFunction {
+    pub name: String,
+    pub signature: String,
+    pub selector: [u8; 4],
+}
+
+impl Artifact {
+    /* CONTENTS OF `<Contract>.abi.json` */
+    pub fn abi_json(&self) -> Value {
+        self.abi.iter().map(AbiEntry::to_json).collect()
+    }
+
+    /* CONTENTS OF `<Contract>.cron.json` */
+    pub fn cron_metadata(&self) -> Value {
+        json!({
+            "sourceUnit": self.source_unit,
+            "contract": self.contract,
+            "jobs": self.cron.iter().map(CronJob::to_json).collect::<Vec<_>>(),
+        })
+    }
+
+    /* CONTENTS OF `<Contract>.storage.json`, solc's `storageLayout` */
+    pub fn storage_metadata(&self) -> Value {
+        json!({
+            "sourceUnit": self.source_unit,
+            "contract": self.contract,
+            "storageLayout": self.storage_layout.as_ref().map(StorageLayout::to_json),
+        })
+    }
+
+    /* CONTENTS OF `<source unit>/<Contract>.layout.json`, READ BACK BY THE NEXT BUILD TO CHECK
+    THE UPGRADE. ONLY THE ERC-1967 SLOT IS RECORDED, THE PROXY ITSELF IS DEPLOYED SEPARATELY */
+    pub fn layout_snapshot(&self) -> Value {
+        let mut snapshot = self
+            .storage_layout
+            .as_ref()
+            .map_or(json!({}), StorageLayout::to_json);
+        snapshot["sourceUnit"] = json!(self.source_unit);
+        snapshot["contract"] = json!(self.contract);
+        snapshot["proxy"] = json!({
+            "standard": "ERC-1967",
+            "implementationSlot": IMPLEMENTATION_SLOT,
+        });
+        snapshot
+    }
+
+    /* CONTENTS OF `<Contract>.relayer.json`. IT ONLY LISTS WHICH CALLS A RELAYER MAY SPONSOR: THE
+    CONTRACT HAS NO META-TRANSACTION ENTRY POINT, SO A RELAYER CALLS THE FUNCTIONS DIRECTLY AND
+    `msg.sender` IS THE RELAYER */
+    pub fn relayer_metadata(&self) -> Value {
+        json!({
+            "sourceUnit": self.source_unit,
+            "contract": self.contract,
+            "description": "Functions a relayer may sponsor. They are called directly, so `msg.sender` is the relayer; the contract does not check a user signature or nonce.",
+            "functions": self.gasless.iter().map(|function| json!({
+                "name": function.name,
+                "signature": function.signature,
+                "selector": format!("0x{}", hex::encode(function.selector)),
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl CompilationOutput {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.has_errors()
+    }
+
+    pub fn tokens(&self, file_id: usize) -> Option<&[LineDescriptions<Token>]> {
+        self.graph
+            .units
+            .get(file_id)
+            .map(|unit| unit.tokens.as_slice())
+    }
+
+    pub fn ast(&self, file_id: usize) -> Option<&SourceUnit> {
+        self.graph.units.get(file_id).map(|unit| &unit.ast)
+    }
+}
+
+/* WHERE THE ARTIFACT `extension` OF A CONTRACT IS WRITTEN, RELATIVE TO THE OUTPUT DIRECTORY. THE
+SOURCE UNIT NAME BECOMES DIRECTORIES SO THAT CONTRACTS OF THE SAME NAME IN DIFFERENT FILES DO
+NOT OVERWRITE EACH OTHER */
+pub fn artifact_path(source_unit: &str, contract: &str, extension: &str) -> PathBuf {
+    Path::new(source_unit)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        })
+        .collect::<PathBuf>()
+        .join(format!("{contract}.{extension}"))
+}
+
+/* WHERE THE LAYOUT SNAPSHOT OF A CONTRACT IS KEPT, RELATIVE TO THE SNAPSHOT DIRECTORY */
+pub fn snapshot_path(source_unit: &str, contract: &str) -> PathBuf {
+    artifact_path(source_unit, contract, "layout.json")
+}
@@ -1,94 +1,225 @@
-#[derive(Debug)]
-pub enum CompilerError<'a> {
+use super::{diagnostics::Diagnostic, line_descriptors::Span};
+
+#[allow(dead_code, clippy::enum_variant_names)]
+#[derive(Debug, Clone)]
+pub enum CompilerError {
     LexicalError(LexicalError),
-    SyntaxError(SyntaxError<'a>),
-    SemanticError(SemanticError<'a>),
-    IOError(IOError<'a>),
-    InternalError(&'a str),
+    SyntaxError(SyntaxError),
+    SemanticError(SemanticError),
+    IOError(IOError),
+    CodegenError(CodegenError),
+    InternalError(String),
 }
 
-#[derive(Debug)]
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
 pub enum LexicalError {
     InvalidCharacter(char),
     UnterminatedString,
     UnexpectedEndOfFile,
+    InvalidNumberLiteral,
+    InvalidEscapeSequence,
+    InvalidHexLiteral,
+    /* CARRIES THE CORRECTLY CHECKSUMMED ADDRESS */
+    InvalidAddressChecksum(String),
 }
 
-#[derive(Debug)]
-pub enum SyntaxError<'a> {
-    UnexpectedToken(&'a str),
-    MissingToken(&'a str),
-    SyntaxError(&'a str),
+#[allow(dead_code, clippy::enum_variant_names)]
+#[derive(Debug, Clone)]
+pub enum SyntaxError {
+    UnexpectedToken(String),
+    MissingToken(String),
+    SyntaxError(String),
+    InvalidCron(String),
+    InvalidGasless(String),
+    InvalidUpgradable(String),
+    InvalidAssembly(String),
 }
 
-#[derive(Debug)]
-pub enum SemanticError<'a> {
-    UndefinedVariable(&'a str),
-    Redeclaration(&'a str),
-    TypeMismatch(&'a str),
-    UndefinedFunction(&'a str),
-    InvalidOperation(&'a str),
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum SemanticError {
+    UndefinedVariable(String),
+    Redeclaration(String),
+    TypeMismatch(String),
+    UndefinedFunction(String),
+    InvalidOperation(String),
+    DeclarationNotFound(String),
+    UndefinedType(String),
+    NotConstant(String),
+    StorageLayoutChange(String),
+    InvalidCron(String),
+    InvalidGasless(String),
+    /* CYCLES, IMPOSSIBLE LINEARIZATIONS, OVERRIDES AND BASE CONSTRUCTOR ARGUMENTS */
+    InvalidInheritance(String),
+    /* WHAT AN UPGRADABLE CONTRACT CANNOT CONTAIN, E.G A CONSTRUCTOR OR `selfdestruct` */
+    InvalidUpgradable(String),
+    InvalidAssembly(String),
+    /* THE NAME OF A CONTRACT WHOSE STATE DOES NOT FIT IN 2**256 SLOTS */
+    StorageTooLarge(String),
 }
 
-#[derive(Debug)]
-pub enum IOError<'a> {
-    FileNotFound(&'a str),
-    IOError(&'a str),
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum IOError {
+    FileNotFound(String),
+    IOError(String),
 }
 
-impl<'a> CompilerError<'a> {
-    pub fn throw(&self) {
-        match &self {
-            CompilerError::LexicalError(lex_error) => {
-                panic!("\x1b[31mLexical error: {:?}\x1b[0m", lex_error);
-            }
-            CompilerError::SyntaxError(syntax_error) => {
-                panic!("\x1b[31mSyntax error: {:?}\x1b[0m", syntax_error);
-            }
-            CompilerError::SemanticError(semantic_error) => {
-                panic!("\x1b[31mSemantic error: {:?}\x1b[0m", semantic_error);
-            }
-            CompilerError::IOError(io_error) => {
-                panic!("\x1b[31mIO error: {:?}\x1b[0m", io_error);
-            }
-            CompilerError::InternalError(message) => {
-                panic!("\x1b[31mInternal error: {}\x1b[0m", message);
-            }
+/* A VALID PROGRAM THE BACKEND CANNOT TURN INTO BYTECODE */
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum CodegenError {
+    Unsupported(String),
+    StackTooDeep(String),
+    CodeTooLarge(String),
+}
+
+impl CompilerError {
+    /* STABLE CODE FOR EACH KIND OF ERROR. 1xxx LEXICAL, 2xxx SYNTAX, 3xxx SEMANTIC, 4xxx IO,
+    5xxx CODE GENERATION */
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompilerError::LexicalError(lex_error) => match lex_error {
+                LexicalError::InvalidCharacter(_) => "E1001",
+                LexicalError::UnterminatedString => "E1002",
+                LexicalError::UnexpectedEndOfFile => "E1003",
+                LexicalError::InvalidNumberLiteral => "E1004",
+                LexicalError::InvalidEscapeSequence => "E1005",
+                LexicalError::InvalidHexLiteral => "E1006",
+                LexicalError::InvalidAddressChecksum(_) => "E1007",
+            },
+            CompilerError::SyntaxError(syntax_error) => match syntax_error {
+                SyntaxError::UnexpectedToken(_) => "E2001",
+                SyntaxError::MissingToken(_) => "E2002",
+                SyntaxError::SyntaxError(_) => "E2003",
+                SyntaxError::InvalidCron(_) => "E2004",
+                SyntaxError::InvalidGasless(_) => "E2005",
+                SyntaxError::InvalidUpgradable(_) => "E2006",
+                SyntaxError::InvalidAssembly(_) => "E2007",
+            },
+            CompilerError::SemanticError(semantic_error) => match semantic_error {
+                SemanticError::UndefinedVariable(_) => "E3001",
+                SemanticError::Redeclaration(_) => "E3002",
+                SemanticError::TypeMismatch(_) => "E3003",
+                SemanticError::UndefinedFunction(_) => "E3004",
+                SemanticError::InvalidOperation(_) => "E3005",
+                SemanticError::DeclarationNotFound(_) => "E3007",
+                SemanticError::UndefinedType(_) => "E3008",
+                SemanticError::NotConstant(_) => "E3009",
+                SemanticError::StorageLayoutChange(_) => "E3010",
+                SemanticError::InvalidCron(_) => "E3011",
+                SemanticError::InvalidGasless(_) => "E3012",
+                SemanticError::InvalidInheritance(_) => "E3013",
+                SemanticError::InvalidUpgradable(_) => "E3014",
+                SemanticError::InvalidAssembly(_) => "E3015",
+                SemanticError::StorageTooLarge(_) => "E3016",
+            },
+            CompilerError::IOError(io_error) => match io_error {
+                IOError::FileNotFound(_) => "E4001",
+                IOError::IOError(_) => "E4002",
+            },
+            CompilerError::CodegenError(codegen_error) => match codegen_error {
+                CodegenError::Unsupported(_) => "E5001",
+                CodegenError::StackTooDeep(_) => "E5002",
+                CodegenError::CodeTooLarge(_) => "E5003",
+            },
+            CompilerError::InternalError(_) => "E9999",
         }
     }
 
-    pub fn throw_with_file_info(&self, file: &str, line: i32) {
-        match &self {
-            CompilerError::LexicalError(lex_error) => {
-                panic!(
-                    "\x1b[31mLexical error: {:?}\x1b[0m\n\x1b[4m{file} {line}\x1b[24m",
-                    lex_error
-                );
-            }
-            CompilerError::SyntaxError(syntax_error) => {
-                panic!(
-                    "\x1b[31mSyntax error: {:?}\x1b[0m\n\x1b[4m{file} {line}\x1b[24m",
-                    syntax_error
-                );
-            }
-            CompilerError::SemanticError(semantic_error) => {
-                panic!(
-                    "\x1b[31mSemantic error: {:?}\x1b[0m\n\x1b[4m{file} {line}\x1b[24m",
-                    semantic_error
-                );
+    pub fn message(&self) -> String {
+        match self {
+            CompilerError::LexicalError(lex_error) => match lex_error {
+                LexicalError::InvalidCharacter(character) => {
+                    format!("invalid character `{}`", character.escape_default())
+                }
+                LexicalError::UnterminatedString => "unterminated string literal".to_string(),
+                LexicalError::UnexpectedEndOfFile => "unexpected end of file".to_string(),
+                LexicalError::InvalidNumberLiteral => "invalid number literal".to_string(),
+                LexicalError::InvalidEscapeSequence => "invalid escape sequence".to_string(),
+                LexicalError::InvalidHexLiteral => "invalid hex string literal".to_string(),
+                LexicalError::InvalidAddressChecksum(_) => {
+                    "address literal does not have a valid checksum".to_string()
+                }
+            },
+            CompilerError::SyntaxError(syntax_error) => match syntax_error {
+                SyntaxError::UnexpectedToken(token) => format!("unexpected token `{token}`"),
+                SyntaxError::MissingToken(token) => format!("expected `{token}`"),
+                SyntaxError::SyntaxError(message)
+                | SyntaxError::InvalidCron(message)
+                | SyntaxError::InvalidGasless(message)
+                | SyntaxError::InvalidUpgradable(message)
+                | SyntaxError::InvalidAssembly(message) => message.to_string(),
+            },
+            CompilerError::SemanticError(semantic_error) => match semantic_error {
+                SemanticError::UndefinedVariable(name) => {
+                    format!("undeclared identifier `{name}`")
+                }
+                SemanticError::Redeclaration(name) => format!("`{name}` is already declared"),
+                SemanticError::TypeMismatch(message) => message.to_string(),
+                SemanticError::UndefinedFunction(name) => format!("undefined function `{name}`"),
+                SemanticError::InvalidOperation(message) => message.to_string(),
+                SemanticError::DeclarationNotFound(name) => {
+                    format!("declaration `{name}` not found")
+                }
+                SemanticError::UndefinedType(name) => format!("undefined type `{name}`"),
+                SemanticError::NotConstant(message) => message.to_string(),
+                SemanticError::StorageLayoutChange(message)
+                | SemanticError::InvalidCron(message)
+                | SemanticError::InvalidGasless(message)
+                | SemanticError::InvalidInheritance(message)
+                | SemanticError::InvalidUpgradable(message)
+                | SemanticError::InvalidAssembly(message) => message.to_string(),
+                SemanticError::StorageTooLarge(name) => {
+                    format!("contract `{name}` requires too much storage")
+                }
+            },
+            CompilerError::IOError(io_error) => match io_error {
+                IOError::FileNotFound(path) => format!("file not found: {path}"),
+                IOError::IOError(message) => message.to_string(),
+            },
+            CompilerError::CodegenError(codegen_error) => match codegen_error {
+                CodegenError::Unsupported(message) => {
+                    format!("code generation does not support {message} yet")
+                }
+                CodegenError::StackTooDeep(message) => message.to_string(),
+                CodegenError::CodeTooLarge(message) => message.to_string(),
+            },
+            CompilerError::InternalError(message) => format!("internal error: {message}"),
+        }
+    }
+
+    /* DIAGNOSTIC WITHOUT A SOURCE LOCATION, E.G FOR IO ERRORS */
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.message()).with_code(self.code());
+        match self {
+            CompilerError::LexicalError(LexicalError::InvalidCharacter(character))
+                if !character.is_ascii() =>
+            {
+                diagnostic.with_help("non-ASCII text must be written in a unicode\"...\" literal")
             }
-            CompilerError::IOError(io_error) => {
-                panic!(
-                    "\x1b[31mIO error: {:?}\x1b[0m\n\x1b[4m{file} {line}\x1b[24m",
-                    io_error
-                );
+            _ => diagnostic,
+        }
+    }
+
+    /* DIAGNOSTIC POINTING AT `span` */
+    pub fn at(&self, span: Span) -> Diagnostic {
+        let label = match self {
+            CompilerError::SyntaxError(SyntaxError::UnexpectedToken(_)) => "unexpected token",
+            CompilerError::SyntaxError(SyntaxError::MissingToken(_)) => "expected here",
+            CompilerError::IOError(IOError::FileNotFound(_)) => "imported here",
+            _ => "",
+        };
+        let diagnostic = self.diagnostic().with_label(span, label);
+        match self {
+            CompilerError::LexicalError(LexicalError::InvalidAddressChecksum(expected)) => {
+                diagnostic.with_suggestion(span, expected, "use the checksummed address")
             }
-            CompilerError::InternalError(message) => {
-                panic!(
-                    "\x1b[31mInternal error: {}\x1b[0m\n\x1b[4m{file} {line}\x1b[24m",
-                    message
-                );
+            CompilerError::SyntaxError(SyntaxError::MissingToken(token)) => {
+                diagnostic.with_suggestion(span, token, format!("insert `{token}`"))
             }
+            _ => diagnostic,
         }
     }
 }
@@ -0,0 +1,255 @@
+use std::{fmt, ops::Range, str::FromStr};
+
+use serde_json::{json, Value};
+
+use super::{
+    abi::{AbiEntry, AbiEntryKind},
+    bindings::ContractId,
+    line_descriptors::Span,
+};
+use crate::mods::functions::helpers::hash_helper::selector;
+
+/* NAME, SMALLEST AND LARGEST VALUE OF EACH FIELD, IN THE ORDER THEY ARE WRITTEN */
+const FIELDS: [(&str, u8, u8); 5] = [
+    ("minute", 0, 59),
+    ("hour", 0, 23),
+    ("day of month", 1, 31),
+    ("month", 1, 12),
+    ("day of week", 0, 6),
+];
+
+/* MOST DAYS EACH MONTH CAN HAVE */
+const DAYS_IN_MONTH: [u8; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/* ONE FIELD OF A SCHEDULE, AS WRITTEN AND AS THE SORTED VALUES IT MATCHES */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronField {
+    pub text: String,
+    pub values: Vec<u8>,
+}
+
+/* A FIVE FIELD CRON EXPRESSION, `minute hour day-of-month month day-of-week`. EACH FIELD IS
+`*`, A NUMBER, A RANGE `a-b` OR A COMMA SEPARATED LIST OF THEM, AND `*` AND RANGES TAKE A STEP
+`/n`. AS IN VIXIE CRON A DAY MATCHES WHEN EITHER DAY FIELD DOES IF BOTH ARE RESTRICTED */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    pub minute: CronField,
+    pub hour: CronField,
+    pub day_of_month: CronField,
+    pub month: CronField,
+    pub day_of_week: CronField,
+}
+
+/* WHY A SCHEDULE IS INVALID, WITH THE BYTES OF THE SCHEDULE STRING AT FAULT */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronError {
+    pub message: String,
+    pub range: Range<usize>,
+}
+
+/* A `cron` BLOCK OF A CONTRACT AS THE OFF-CHAIN SCHEDULER SEES IT. `index` ORDERS THE BLOCKS OF
+THE CONTRACT AND THOSE IT INHERITS, MOST BASE FIRST, AND NAMES THE ENTRY POINT THE SCHEDULER
+CALLS */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronJob {
+    pub index: usize,
+    /* THE CONTRACT THAT DECLARES THE BLOCK */
+    pub contract: ContractId,
+    pub span: Span,
+    pub schedule: CronSchedule,
+    /* 0 MEANS NO LIMIT */
+    pub max_runs: u64,
+}
+
+impl CronField {
+    pub fn is_wildcard(&self) -> bool {
+        self.text == "*"
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        for text in input.split(' ') {
+            let range = offset..offset + text.len();
+            offset = range.end + 1;
+            if text.is_empty() {
+                continue;
+            }
+            let Some(&(name, min, max)) = FIELDS.get(fields.len()) else {
+                return Err(CronError {
+                    message: format!("unexpected sixth field `{text}` in cron schedule"),
+                    range,
+                });
+            };
+            let values = parse_field(text, name, min, max).map_err(|message| CronError {
+                message,
+                range: range.clone(),
+            })?;
+            fields.push((
+                CronField {
+                    text: text.to_string(),
+                    values,
+                },
+                range,
+            ));
+        }
+        if fields.len() < FIELDS.len() {
+            let missing: Vec<&str> = FIELDS[fields.len()..]
+                .iter()
+                .map(|(name, _, _)| *name)
+                .collect();
+            return Err(CronError {
+                message: format!(
+                    "cron schedule needs 5 fields, found {}: missing {}",
+                    fields.len(),
+                    missing.join(", ")
+                ),
+                range: 0..input.len(),
+            });
+        }
+
+        let mut fields = fields.into_iter();
+        let mut next = || fields.next().unwrap();
+        let (minute, _) = next();
+        let (hour, _) = next();
+        let (day_of_month, day_range) = next();
+        let (month, _) = next();
+        let (day_of_week, _) = next();
+
+        /* `0 0 30 2 *` IS VALID FIELD BY FIELD BUT NEVER RUNS */
+        let longest_month = month
+            .values
+            .iter()
+            .map(|month| DAYS_IN_MONTH[*month as usize - 1])
+            .max()
+            .unwrap_or(31);
+        if day_of_week.is_wildcard() && day_of_month.values[0] > longest_month {
+            return Err(CronError {
+                message: format!(
+                    "cron schedule never runs: no selected month has {} days",
+                    day_of_month.values[0]
+                ),
+                range: day_range,
+            });
+        }
+
+        Ok(CronSchedule {
+            minute,
+            hour,
+            day_of_month,
+            month,
+            day_of_week,
+        })
+    }
+}
+
+fn parse_field(text: &str, name: &str, min: u8, max: u8) -> Result<Vec<u8>, String> {
+    let mut values = Vec::new();
+    for item in text.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, Some(step)),
+            None => (item, None),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let (start, end) = (
+                parse_value(start, name, min, max)?,
+                parse_value(end, name, min, max)?,
+            );
+            if start > end {
+                return Err(format!(
+                    "invalid {name} range `{range}`, {start} is after {end}"
+                ));
+            }
+            (start, end)
+        } else {
+            let value = parse_value(range, name, min, max)?;
+            if step.is_some() {
+                return Err(format!(
+                    "a step needs `*` or a range in the {name} field, found `{item}`"
+                ));
+            }
+            (value, value)
+        };
+        let step = match step {
+            Some(step) => match step.parse::<u8>() {
+                Ok(step) if step > 0 => step,
+                _ => return Err(format!("invalid step `{step}` in the {name} field")),
+            },
+            None => 1,
+        };
+        values.extend((start..=end).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+fn parse_value(text: &str, name: &str, min: u8, max: u8) -> Result<u8, String> {
+    if text.is_empty() || !text.chars().all(|character| character.is_ascii_digit()) {
+        return Err(format!(
+            "expected a number in the {name} field, found `{text}`"
+        ));
+    }
+    match text.parse::<u8>() {
+        Ok(value) if (min..=max).contains(&value) => Ok(value),
+        _ => Err(format!(
+            "{name} `{text}` is out of range, expected {min} to {max}"
+        )),
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.minute.text,
+            self.hour.text,
+            self.day_of_month.text,
+            self.month.text,
+            self.day_of_week.text
+        )
+    }
+}
+
+impl CronJob {
+    /* THE FUNCTION THE SCHEDULER CALLS TO RUN THE BLOCK */
+    pub fn entry_point(&self) -> String {
+        format!("__cron_{}()", self.index)
+    }
+
+    /* THE ENTRY POINT AS A FUNCTION OF THE CONTRACT ABI */
+    pub fn abi_entry(&self) -> AbiEntry {
+        AbiEntry {
+            kind: AbiEntryKind::Function,
+            name: format!("__cron_{}", self.index),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            state_mutability: Some("nonpayable"),
+            anonymous: false,
+            gasless: false,
+            signature: self.entry_point(),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "index": self.index,
+            "entryPoint": self.entry_point(),
+            "selector": format!("0x{}", hex::encode(selector(&self.entry_point()))),
+            "schedule": self.schedule.to_string(),
+            "minute": self.schedule.minute.values,
+            "hour": self.schedule.hour.values,
+            "dayOfMonth": self.schedule.day_of_month.values,
+            "month": self.schedule.month.values,
+            "dayOfWeek": self.schedule.day_of_week.values,
+            "maxRuns": self.max_runs,
+        })
+    }
+}
@@ -0,0 +1,403 @@
+use std::{
+    fmt::Write,
+    io::{self, IsTerminal},
+};
+
+use serde_json::{json, Value};
+
+use super::{line_descriptors::Span, source_map::SourceMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    #[default]
+    Human,
+    /* ONE JSON OBJECT PER LINE, FOR EDITORS AND CI */
+    Json,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    /* PRIMARY LABELS ARE UNDERLINED WITH `^`, SECONDARY ONES WITH `-` */
+    pub primary: bool,
+}
+
+/* A MACHINE APPLICABLE FIX: REPLACE THE TEXT UNDER `span` WITH `replacement` */
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub span: Span,
+    pub replacement: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: true,
+        });
+        self
+    }
+
+    pub fn with_secondary_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: false,
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    /* THE SUGGESTION IS ALSO SHOWN AS HELP TEXT IN HUMAN READABLE OUTPUT */
+    pub fn with_suggestion(
+        mut self,
+        span: Span,
+        replacement: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            span,
+            replacement: replacement.into(),
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn primary_span(&self) -> Option<Span> {
+        self.labels
+            .iter()
+            .find(|label| label.primary)
+            .or(self.labels.first())
+            .map(|label| label.span)
+    }
+
+    /* RUSTC STYLE RENDERING WITH THE OFFENDING SOURCE LINES UNDERLINED */
+    pub fn render(&self, sources: &SourceMap, colored: bool) -> String {
+        let paint = |text: &str, colour: &str| {
+            if colored {
+                format!("\x1b[{colour}m{text}\x1b[0m")
+            } else {
+                text.to_string()
+            }
+        };
+        let (severity, colour) = match self.severity {
+            Severity::Error => ("error", "1;31"),
+            Severity::Warning => ("warning", "1;33"),
+            Severity::Note => ("note", "1;36"),
+        };
+        let gutter_colour = "1;34";
+
+        let mut output = String::new();
+        let heading = match self.code {
+            Some(code) => format!("{severity}[{code}]"),
+            None => severity.to_string(),
+        };
+        let _ = writeln!(
+            output,
+            "{}{}",
+            paint(&heading, colour),
+            paint(&format!(": {}", self.message), "1")
+        );
+
+        let mut labels: Vec<&Label> = self.labels.iter().collect();
+        labels.sort_by_key(|label| (label.span.file_id, label.span.line, label.span.column));
+        let width = labels
+            .iter()
+            .map(|label| label.span.line.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let gutter = paint(&format!("{} |", " ".repeat(width)), gutter_colour);
+
+        if let Some(span) = self.primary_span() {
+            let name = sources
+                .get(span.file_id)
+                .map_or("<unknown>", |file| file.name.as_str());
+            let _ = writeln!(
+                output,
+                "{}{name}:{}:{}",
+                paint(&format!("{}--> ", " ".repeat(width)), gutter_colour),
+                span.line,
+                span.column
+            );
+            let _ = writeln!(output, "{gutter}");
+        }
+
+        let mut previous: Option<(usize, i32)> = None;
+        for label in &labels {
+            let Some(file) = sources.get(label.span.file_id) else {
+                continue;
+            };
+            let Some(text) = file.line(label.span.line) else {
+                continue;
+            };
+            if previous.is_some_and(|(file_id, _)| file_id != label.span.file_id) {
+                let _ = writeln!(
+                    output,
+                    "{}{}:{}:{}",
+                    paint(&format!("{}::: ", " ".repeat(width)), gutter_colour),
+                    file.name,
+                    label.span.line,
+                    label.span.column
+                );
+            }
+            if previous != Some((label.span.file_id, label.span.line)) {
+                if previous.is_some_and(|(file_id, line)| {
+                    file_id == label.span.file_id && label.span.line > line + 1
+                }) {
+                    let _ = writeln!(output, "{}", paint("...", gutter_colour));
+                }
+                let _ = writeln!(
+                    output,
+                    "{} {}",
+                    paint(&format!("{:>width$} |", label.span.line), gutter_colour),
+                    text.replace('\t', " ")
+                );
+            }
+            previous = Some((label.span.file_id, label.span.line));
+
+            /* UNDERLINE UP TO THE END OF THE SPAN OR OF THE LINE, WHICHEVER COMES FIRST */
+            let start = label.span.start.min(file.contents.len());
+            let line_end = file.contents[start..]
+                .find('\n')
+                .map_or(file.contents.len(), |offset| start + offset);
+            let end = label.span.end.clamp(start, line_end);
+            let length = file.contents[start..end].chars().count().max(1);
+            let (marker, marker_colour) = if label.primary {
+                ("^", colour)
+            } else {
+                ("-", gutter_colour)
+            };
+            let underline = format!("{} {}", marker.repeat(length), label.message);
+            let _ = writeln!(
+                output,
+                "{} {}{}",
+                gutter,
+                " ".repeat((label.span.column - 1).max(0) as usize),
+                paint(underline.trim_end(), marker_colour)
+            );
+        }
+
+        let help: Vec<String> = self
+            .help
+            .iter()
+            .cloned()
+            .chain(self.suggestions.iter().map(|suggestion| {
+                if suggestion.message.contains(&suggestion.replacement) {
+                    suggestion.message.clone()
+                } else {
+                    format!("{}: `{}`", suggestion.message, suggestion.replacement)
+                }
+            }))
+            .collect();
+        if !self.notes.is_empty() || !help.is_empty() {
+            if !labels.is_empty() {
+                let _ = writeln!(output, "{gutter}");
+            }
+            for note in &self.notes {
+                let _ = writeln!(
+                    output,
+                    "{} {note}",
+                    paint(&format!("{} = note:", " ".repeat(width)), "1")
+                );
+            }
+            for help in help {
+                let _ = writeln!(
+                    output,
+                    "{} {help}",
+                    paint(&format!("{} = help:", " ".repeat(width)), "1")
+                );
+            }
+        }
+        output
+    }
+
+    pub fn to_json(&self, sources: &SourceMap) -> Value {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        };
+        let file = self
+            .primary_span()
+            .and_then(|span| sources.get(span.file_id))
+            .map(|file| file.name.as_str());
+        let spans: Vec<Value> = self
+            .labels
+            .iter()
+            .map(|label| {
+                let mut span = span_json(&label.span, sources);
+                span["primary"] = json!(label.primary);
+                span["label"] = json!(label.message);
+                span
+            })
+            .collect();
+        let suggestions: Vec<Value> = self
+            .suggestions
+            .iter()
+            .map(|suggestion| {
+                json!({
+                    "message": suggestion.message,
+                    "replacement": suggestion.replacement,
+                    "span": span_json(&suggestion.span, sources),
+                })
+            })
+            .collect();
+        json!({
+            "code": self.code,
+            "severity": severity,
+            "message": self.message,
+            "file": file,
+            "spans": spans,
+            "notes": self.notes,
+            "help": self.help,
+            "suggestions": suggestions,
+        })
+    }
+}
+
+/* BYTE RANGE PLUS 1-BASED LINE/COLUMN OF BOTH ENDS */
+fn span_json(span: &Span, sources: &SourceMap) -> Value {
+    let file = sources.get(span.file_id);
+    let (end_line, end_column) =
+        file.map_or((span.line, span.column), |file| file.position(span.end));
+    json!({
+        "file": file.map(|file| file.name.as_str()),
+        "start": span.start,
+        "end": span.end,
+        "line": span.line,
+        "column": span.column,
+        "end_line": end_line,
+        "end_column": end_column,
+    })
+}
+
+/* DIAGNOSTICS COLLECTED OVER A WHOLE RUN SO THAT ONE ERROR DOES NOT HIDE THE NEXT */
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn extend(&mut self, other: Diagnostics) {
+        self.diagnostics.extend(other.diagnostics);
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    /* DIAGNOSTICS IN SOURCE ORDER, THOSE WITHOUT A LOCATION FIRST */
+    fn sorted(&self) -> Vec<&Diagnostic> {
+        let mut diagnostics: Vec<&Diagnostic> = self.diagnostics.iter().collect();
+        diagnostics.sort_by_key(|diagnostic| {
+            diagnostic
+                .primary_span()
+                .map(|span| (span.file_id, span.start))
+        });
+        diagnostics
+    }
+
+    /* WRITES EVERY DIAGNOSTIC TO STDERR IN THE REQUESTED FORMAT */
+    pub fn emit(&self, sources: &SourceMap, format: ErrorFormat) {
+        match format {
+            ErrorFormat::Human => {
+                eprint!("{}", self.render(sources, io::stderr().is_terminal()));
+            }
+            ErrorFormat::Json => {
+                for diagnostic in self.sorted() {
+                    eprintln!("{}", diagnostic.to_json(sources));
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, sources: &SourceMap, colored: bool) -> String {
+        let mut output = String::new();
+        for diagnostic in self.sorted() {
+            output.push_str(&diagnostic.render(sources, colored));
+            output.push('\n');
+        }
+        let errors = self.error_count();
+        if errors > 0 {
+            let summary = format!(
+                "aborting due to {errors} previous error{}",
+                if errors == 1 { "" } else { "s" }
+            );
+            let _ = writeln!(
+                output,
+                "{}",
+                Diagnostic::error(summary)
+                    .render(sources, colored)
+                    .trim_end()
+            );
+        }
+        output
+    }
+}
@@ -0,0 +1,839 @@
+use std::{collections::HashMap, fmt};
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+use super::{line_descriptors::Span, opcodes::Opcode};
+
+/* THE INTERMEDIATE REPRESENTATION BETWEEN THE CODE GENERATOR AND THE EVM BACKEND. IT IS
+YUL-LIKE: A FUNCTION IS A LIST OF BASIC BLOCKS, EACH A LIST OF INSTRUCTIONS ENDED BY ONE
+TERMINATOR, AND VALUES ARE TREES OF EVM BUILTINS OVER CONSTANTS AND VARIABLES. VARIABLES ARE
+THE SLOTS OF THE FUNCTION'S FRAME, NUMBERED FROM 1 AT THE BOTTOM WHERE THE PARAMETERS ARE, SO
+EVERY POINT OF A FUNCTION HAS A KNOWN NUMBER OF LIVE VARIABLES, ITS HEIGHT */
+
+/* A BLOCK OR FUNCTION, BY ITS INDEX IN THE FUNCTION OR PROGRAM */
+pub type BlockId = usize;
+pub type FunctionId = usize;
+
+/* VALUES THAT ARE ONLY KNOWN ONCE THE CODE IS LAID OUT */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Link {
+    /* WHERE A DATA SECTION STARTS IN THE CODE AND HOW LONG IT IS */
+    DataOffset(usize),
+    DataSize(usize),
+    /* THE SIZE OF THE CODE AND ALL DATA, WHERE CONSTRUCTOR ARGUMENTS START */
+    ProgramSize,
+    /* AN IMMUTABLE, FILLED IN BY THE CONSTRUCTOR */
+    Immutable(usize),
+}
+
+/* INTEGER ARITHMETIC THAT PANICS WITH 0x11 WHEN THE RESULT DOES NOT FIT ITS TYPE AND WITH
+0x12 WHEN DIVIDING BY ZERO */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Checked {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Integer {
+    pub bits: u16,
+    pub signed: bool,
+}
+
+/* A VALUE: A CONSTANT, A VARIABLE, A BUILTIN OR CHECKED OPERATION APPLIED TO OTHER VALUES, OR
+A LINK. ARGUMENTS ARE EVALUATED LAST FIRST, AS THE EVM PUSHES THEM, SO `Apply(Sub, [a, b])` IS
+`a - b` AND `b` IS EVALUATED BEFORE `a`. A BUILTIN WITHOUT RESULT, SUCH AS `mstore`, ONLY
+APPEARS AT THE ROOT OF AN `Exec` OR `Halt` */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Word {
+    Constant(BigUint),
+    Slot(usize),
+    Apply(Opcode, Vec<Word>),
+    Checked(Checked, Integer, Vec<Word>),
+    Link(Link),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    /* A NEW VARIABLE ON TOP OF THE OTHERS */
+    Let(Word),
+    Assign(usize, Word),
+    /* A BUILTIN RUN FOR ITS EFFECT */
+    Exec(Word),
+    /* DROPS EVERY VARIABLE ABOVE `base`, EXCEPT THAT THE VALUES OF THE VARIABLES AT
+    `positions` END UP RIGHT ABOVE IT, IN THAT ORDER */
+    Keep { base: usize, positions: Vec<usize> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Terminator {
+    Jump(BlockId),
+    /* TO `then` WHEN `condition` IS NOT ZERO AND TO `otherwise` WHEN IT IS, DROPPING THE TOP
+    `pops` VARIABLES, WHICH THE CONDITION MAY READ, ON BOTH PATHS */
+    Branch {
+        condition: Word,
+        pops: usize,
+        then: BlockId,
+        otherwise: BlockId,
+    },
+    /* CALLS AN INTERNAL FUNCTION WITH THE TOP VARIABLES AS ITS ARGUMENTS, WHICH ITS RESULTS
+    REPLACE, AND GOES ON AT `next` */
+    Call {
+        function: FunctionId,
+        next: BlockId,
+    },
+    /* RETURNS FROM AN INTERNAL FUNCTION WITH THE VALUES OF THE VARIABLES AT THE POSITIONS */
+    Leave(Vec<usize>),
+    /* `stop`, `return`, `revert`, `invalid` OR `selfdestruct` */
+    Halt(Word),
+    /* REVERTS WITH `Panic(uint256)` AND THE CODE */
+    Panic(u8),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    /* THE NUMBER OF VARIABLES WHEN THE BLOCK IS ENTERED */
+    pub height: usize,
+    /* WHAT THE CODE WAS GENERATED FOR, WHERE A STACK TOO DEEP ERROR IN IT IS REPORTED */
+    pub span: Span,
+    pub instructions: Vec<Instruction>,
+    pub terminator: Terminator,
+}
+
+/* THE FIRST FUNCTION OF A PROGRAM IS WHERE EXECUTION STARTS, THE OTHERS ARE INTERNAL
+FUNCTIONS ONLY REACHED THROUGH `Call`. THE FIRST BLOCK IS THE ENTRY */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    /* WHERE ERRORS ABOUT THE FUNCTION ARE REPORTED */
+    pub span: Span,
+    pub parameters: usize,
+    pub returns: usize,
+    pub blocks: Vec<Block>,
+}
+
+/* THE CODE OF ONE CONTRACT, CREATION OR RUNTIME, AND THE DATA SECTIONS THAT FOLLOW IT. THE
+CODE GENERATOR BUILDS ONE FUNCTION AT A TIME THROUGH THE METHODS BELOW, WHICH FOLLOW `height`
+AS IT ADDS INSTRUCTIONS */
+#[derive(Debug, Clone, Default)]
+pub struct Program {
+    pub functions: Vec<Function>,
+    pub data: Vec<Vec<u8>>,
+    pub height: usize,
+    /* THE SPAN THE BLOCKS ENDED FROM NOW ON ARE GIVEN */
+    pub span: Span,
+    building: Option<Building>,
+}
+
+/* THE FUNCTION BEING BUILT. BLOCKS ARE NAMED BY LABELS UNTIL IT IS DONE, THEN NUMBERED IN THE
+ORDER THEY WERE STARTED, THE BLOCKS STARTED OUT OF LINE LAST */
+#[derive(Debug, Clone, Default)]
+struct Building {
+    function: FunctionId,
+    labels: usize,
+    current: Option<(BlockId, usize, Vec<Instruction>)>,
+    blocks: Vec<(BlockId, Block)>,
+    out_of_line: Vec<(BlockId, Block)>,
+}
+
+impl Word {
+    /* HOW MANY VALUES EVALUATING IT PUSHES */
+    pub fn outputs(&self) -> usize {
+        match self {
+            Word::Apply(opcode, _) => opcode.stack_io().1,
+            _ => 1,
+        }
+    }
+
+    /* THE WORD WITH EVERY VARIABLE `map` HAS A WORD FOR REPLACED */
+    pub fn substitute(&self, map: &impl Fn(usize) -> Option<Word>) -> Word {
+        match self {
+            Word::Slot(position) => map(*position).unwrap_or_else(|| self.clone()),
+            Word::Apply(opcode, arguments) => Word::Apply(
+                *opcode,
+                arguments
+                    .iter()
+                    .map(|argument| argument.substitute(map))
+                    .collect(),
+            ),
+            Word::Checked(operation, ty, arguments) => Word::Checked(
+                *operation,
+                *ty,
+                arguments
+                    .iter()
+                    .map(|argument| argument.substitute(map))
+                    .collect(),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /* THE VARIABLES READ, IN THE ORDER THEY ARE EVALUATED */
+    pub fn slots(&self) -> Vec<usize> {
+        let mut slots = Vec::new();
+        self.visit(&mut |word| {
+            if let Word::Slot(_position) = word {
+                slots.push(*_position);
+            }
+        });
+        slots
+    }
+
+    /* CALLS `f` ON EVERY PART OF THE WORD IN THE ORDER IT IS EVALUATED, ARGUMENTS BEFORE THE
+    OPERATION */
+    pub fn visit(&self, f: &mut impl FnMut(&Word)) {
+        if let Word::Apply(_, arguments) | Word::Checked(_, _, arguments) = self {
+            for argument in arguments.iter().rev() {
+                argument.visit(f);
+            }
+        }
+        f(self);
+    }
+
+    /* NEITHER READS NOR CHANGES ANYTHING BUT THE STACK AND CANNOT FAIL, SO IT CAN BE MOVED,
+    DUPLICATED OR DROPPED */
+    pub fn is_pure(&self) -> bool {
+        match self {
+            Word::Constant(_) | Word::Slot(_) | Word::Link(_) => true,
+            Word::Checked(..) => false,
+            Word::Apply(opcode, arguments) => {
+                is_pure_opcode(*opcode) && arguments.iter().all(Word::is_pure)
+            }
+        }
+    }
+}
+
+/* BUILTINS WHOSE RESULT ONLY DEPENDS ON THEIR ARGUMENTS AND THE CALL, WHICH NOTHING CHANGES */
+pub fn is_pure_opcode(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::Add
+            | Opcode::Mul
+            | Opcode::Sub
+            | Opcode::Div
+            | Opcode::SDiv
+            | Opcode::Mod
+            | Opcode::SMod
+            | Opcode::AddMod
+            | Opcode::MulMod
+            | Opcode::Exp
+            | Opcode::SignExtend
+            | Opcode::Lt
+            | Opcode::Gt
+            | Opcode::SLt
+            | Opcode::SGt
+            | Opcode::Eq
+            | Opcode::IsZero
+            | Opcode::And
+            | Opcode::Or
+            | Opcode::Xor
+            | Opcode::Not
+            | Opcode::Byte
+            | Opcode::Shl
+            | Opcode::Shr
+            | Opcode::Sar
+            | Opcode::Address
+            | Opcode::Origin
+            | Opcode::Caller
+            | Opcode::CallValue
+            | Opcode::CallDataLoad
+            | Opcode::CallDataSize
+            | Opcode::CodeSize
+            | Opcode::GasPrice
+            | Opcode::Coinbase
+            | Opcode::Timestamp
+            | Opcode::Number
+            | Opcode::PrevRandao
+            | Opcode::GasLimit
+            | Opcode::ChainId
+            | Opcode::BaseFee
+            | Opcode::BlobBaseFee
+    )
+}
+
+/* 2^bits - 1 */
+pub fn mask(bits: usize) -> BigUint {
+    (BigUint::one() << bits) - 1u8
+}
+
+/* THE VALUE OF A WORD MADE ONLY OF CONSTANTS AND PURE BUILTINS THAT ARE WORTH FOLDING */
+pub fn fold(word: &Word) -> Option<BigUint> {
+    let Word::Apply(opcode, arguments) = word else {
+        return match word {
+            Word::Constant(value) => Some(value.clone()),
+            _ => None,
+        };
+    };
+    let values = arguments.iter().map(fold).collect::<Option<Vec<_>>>()?;
+    let full = mask(256);
+    let modulus = BigUint::one() << 256;
+    let bit = |value: bool| BigUint::from(value as u8);
+    let shift = |value: &BigUint| value.to_usize().filter(|shift| *shift < 256);
+    Some(match (opcode, values.as_slice()) {
+        (Opcode::Add, [a, b]) => (a + b) & full,
+        (Opcode::Sub, [a, b]) => (a + &modulus - b) & full,
+        (Opcode::Mul, [a, b]) => (a * b) & full,
+        (Opcode::Div, [a, b]) if !b.is_zero() => a / b,
+        (Opcode::Div, [_, _]) => BigUint::zero(),
+        (Opcode::Mod, [a, b]) if !b.is_zero() => a % b,
+        (Opcode::Mod, [_, _]) => BigUint::zero(),
+        (Opcode::And, [a, b]) => a & b,
+        (Opcode::Or, [a, b]) => a | b,
+        (Opcode::Xor, [a, b]) => a ^ b,
+        /* `not` OF A SMALL CONSTANT IS CHEAPER THAN ITS 32 BYTE PUSH */
+        (Opcode::Not, [a]) if a.bits() > 128 => full ^ a,
+        (Opcode::Shl, [s, v]) => shift(s).map_or(BigUint::zero(), |s| (v << s) & full),
+        (Opcode::Shr, [s, v]) => shift(s).map_or(BigUint::zero(), |s| v >> s),
+        (Opcode::IsZero, [a]) => bit(a.is_zero()),
+        (Opcode::Eq, [a, b]) => bit(a == b),
+        (Opcode::Lt, [a, b]) => bit(a < b),
+        (Opcode::Gt, [a, b]) => bit(a > b),
+        _ => return None,
+    })
+}
+
+impl Terminator {
+    /* THE BLOCKS IT MAY GO TO */
+    pub fn successors(&self) -> Vec<BlockId> {
+        match self {
+            Terminator::Jump(target) => vec![*target],
+            Terminator::Branch {
+                then, otherwise, ..
+            } => vec![*then, *otherwise],
+            Terminator::Call { next, .. } => vec![*next],
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn successors_mut(&mut self) -> Vec<&mut BlockId> {
+        match self {
+            Terminator::Jump(target) => vec![target],
+            Terminator::Branch {
+                then, otherwise, ..
+            } => vec![then, otherwise],
+            Terminator::Call { next, .. } => vec![next],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Function {
+    /* THE BLOCKS THAT CAN BE REACHED FROM THE ENTRY */
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut reached = vec![false; self.blocks.len()];
+        let mut work = vec![0];
+        while let Some(block) = work.pop() {
+            if block >= self.blocks.len() || reached[block] {
+                continue;
+            }
+            reached[block] = true;
+            work.extend(self.blocks[block].terminator.successors());
+        }
+        reached
+    }
+
+    /* DROPS THE BLOCKS `keep` IS FALSE FOR AND RENUMBERS THE OTHERS */
+    pub fn retain_blocks(&mut self, keep: &[bool]) {
+        let mut numbers = Vec::new();
+        let mut next = 0;
+        for kept in keep {
+            numbers.push(next);
+            next += *kept as usize;
+        }
+        let blocks = std::mem::take(&mut self.blocks);
+        for (index, mut block) in blocks.into_iter().enumerate() {
+            if !keep[index] {
+                continue;
+            }
+            for target in block.terminator.successors_mut() {
+                *target = numbers.get(*target).copied().unwrap_or(*target);
+            }
+            self.blocks.push(block);
+        }
+    }
+}
+
+/* `stop`, `return`, `revert`, `invalid` AND `selfdestruct`, WHICH END A BLOCK */
+pub fn is_halt(word: &Word) -> bool {
+    matches!(word, Word::Apply(opcode, _) if opcode.is_terminator() && *opcode != Opcode::Jump)
+}
+
+impl Program {
+    /* ------------------------------ BUILDING ------------------------------ */
+
+    /* DECLARES A FUNCTION, WHOSE BLOCKS ARE BUILT LATER */
+    pub fn add_function(
+        &mut self,
+        name: impl Into<String>,
+        span: Span,
+        parameters: usize,
+        returns: usize,
+    ) -> FunctionId {
+        self.functions.push(Function {
+            name: name.into(),
+            span,
+            parameters,
+            returns,
+            blocks: Vec::new(),
+        });
+        self.functions.len() - 1
+    }
+
+    /* ADDS A DATA SECTION, RETURNING ITS INDEX */
+    pub fn add_data(&mut self, data: Vec<u8>) -> usize {
+        self.data.push(data);
+        self.data.len() - 1
+    }
+
+    /* STARTS BUILDING THE BLOCKS OF `function`, WITH ITS PARAMETERS ON THE STACK */
+    pub fn begin(&mut self, function: FunctionId) {
+        self.height = self.functions[function].parameters;
+        self.building = Some(Building {
+            function,
+            labels: 1,
+            current: Some((0, self.height, Vec::new())),
+            ..Building::default()
+        });
+    }
+
+    /* FINISHES THE FUNCTION BEING BUILT. A BLOCK LEFT OPEN ENDS WITH `invalid`, AND BLOCKS
+    NOTHING REACHES ARE DROPPED */
+    pub fn end(&mut self) {
+        if self.building().current.is_some() {
+            self.terminate(Terminator::Halt(Word::Apply(Opcode::Invalid, Vec::new())));
+        }
+        let Some(building) = self.building.take() else {
+            return;
+        };
+        let mut labelled = building.blocks;
+        labelled.extend(building.out_of_line);
+        let numbers: HashMap<BlockId, BlockId> = labelled
+            .iter()
+            .enumerate()
+            .map(|(index, (label, _))| (*label, index))
+            .collect();
+        let function = &mut self.functions[building.function];
+        function.blocks = labelled
+            .into_iter()
+            .map(|(_, mut block)| {
+                for target in block.terminator.successors_mut() {
+                    *target = numbers.get(target).copied().unwrap_or(usize::MAX);
+                }
+                block
+            })
+            .collect();
+        let reachable = function.reachable();
+        function.retain_blocks(&reachable);
+    }
+
+    fn building(&mut self) -> &mut Building {
+        self.building.as_mut().expect("no function is being built")
+    }
+
+    pub fn new_block(&mut self) -> BlockId {
+        let building = self.building();
+        building.labels += 1;
+        building.labels - 1
+    }
+
+    /* CONTINUES IN `block`, FALLING THROUGH FROM THE CURRENT ONE */
+    pub fn start(&mut self, block: BlockId) {
+        if self.building().current.is_some() {
+            self.terminate(Terminator::Jump(block));
+        }
+        let height = self.height;
+        self.building().current = Some((block, height, Vec::new()));
+    }
+
+    /* ENDS THE CURRENT BLOCK. WHAT IS ADDED AFTER IT UNTIL THE NEXT `start` CANNOT BE
+    REACHED */
+    pub fn terminate(&mut self, terminator: Terminator) {
+        let span = self.span;
+        let building = self.building();
+        if let Some((label, height, instructions)) = building.current.take() {
+            building.blocks.push((
+                label,
+                Block {
+                    height,
+                    span,
+                    instructions,
+                    terminator,
+                },
+            ));
+        }
+    }
+
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        let height = self.height;
+        let building = self.building();
+        if building.current.is_none() {
+            let label = building.labels;
+            building.labels += 1;
+            building.current = Some((label, height, Vec::new()));
+        }
+        &mut building.current.as_mut().unwrap().2
+    }
+
+    /* A BLOCK OF ITS OWN, PLACED AFTER THE OTHERS, THAT ONLY HOLDS `terminator`. THE SAME
+    BLOCK SERVES EVERY BRANCH TO IT AT THE SAME HEIGHT */
+    pub fn out_of_line(&mut self, terminator: Terminator) -> BlockId {
+        let (height, span) = (self.height, self.span);
+        if let Some((_label, _)) = self
+            .building()
+            .out_of_line
+            .iter()
+            .find(|(_, block)| block.height == height && block.terminator == terminator)
+        {
+            return *_label;
+        }
+        let label = self.new_block();
+        self.building().out_of_line.push((
+            label,
+            Block {
+                height,
+                span,
+                instructions: Vec::new(),
+                terminator,
+            },
+        ));
+        label
+    }
+
+    pub fn let_(&mut self, word: Word) -> usize {
+        self.instructions().push(Instruction::Let(word));
+        self.height += 1;
+        self.height
+    }
+
+    pub fn assign(&mut self, position: usize, word: Word) {
+        self.instructions()
+            .push(Instruction::Assign(position, word));
+    }
+
+    /* MOVES THE TOP VARIABLE INTO `position`. ONE JUST DEFINED IS ASSIGNED DIRECTLY */
+    pub fn store_top(&mut self, position: usize) {
+        let top = self.height;
+        if position == top {
+            return self.pop(1);
+        }
+        let instructions = self.instructions();
+        if let Some(Instruction::Let(_word)) = instructions.last() {
+            let word = _word.clone();
+            instructions.pop();
+            self.height -= 1;
+            return self.assign(position, word);
+        }
+        self.assign(position, Word::Slot(top));
+        self.pop(1);
+    }
+
+    /* RUNS A BUILTIN WITHOUT RESULT. ONE THAT HALTS ENDS THE BLOCK */
+    pub fn exec(&mut self, word: Word) {
+        if is_halt(&word) {
+            self.instructions();
+            return self.terminate(Terminator::Halt(word));
+        }
+        self.instructions().push(Instruction::Exec(word));
+    }
+
+    pub fn keep(&mut self, base: usize, positions: &[usize]) {
+        if base + positions.len() == self.height
+            && positions
+                .iter()
+                .enumerate()
+                .all(|(index, position)| *position == base + index + 1)
+        {
+            return;
+        }
+        self.instructions().push(Instruction::Keep {
+            base,
+            positions: positions.to_vec(),
+        });
+        self.height = base + positions.len();
+    }
+
+    pub fn pop(&mut self, count: usize) {
+        if count > 0 {
+            self.keep(self.height - count, &[]);
+        }
+    }
+
+    /* EVALUATES `word`, WHICH MAY READ THE TOP `count` VARIABLES, AND DROPS THEM. WHEN THEY
+    WERE JUST DEFINED AND `word` READS EACH ONCE, BEFORE ANYTHING WITH AN EFFECT AND IN THE
+    ORDER THEY WERE DEFINED, THEIR DEFINITIONS ARE MOVED INTO IT INSTEAD */
+    pub fn replace(&mut self, count: usize, word: Word) {
+        let top = self.height;
+        let base = top - count;
+        if let Some(_word) = self.inline_top(count, &word) {
+            self.height = base;
+            return self.emit(_word);
+        }
+        match word.outputs() {
+            0 => {
+                self.exec(word);
+                self.pop(count);
+            }
+            _ => {
+                self.let_(word);
+                self.keep(base, &[top + 1]);
+            }
+        }
+    }
+
+    fn emit(&mut self, word: Word) {
+        match word.outputs() {
+            0 => self.exec(word),
+            _ => {
+                self.let_(word);
+            }
+        }
+    }
+
+    fn inline_top(&mut self, count: usize, word: &Word) -> Option<Word> {
+        let top = self.height;
+        let base = top - count;
+        let instructions = &self.building.as_ref()?.current.as_ref()?.2;
+        if count == 0 || instructions.len() < count {
+            return None;
+        }
+        let definitions: Vec<Word> = instructions[instructions.len() - count..]
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::Let(_word) => Some(_word.clone()),
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
+        let mut reads = Vec::new();
+        let mut effect_before = false;
+        word.visit(&mut |part| match part {
+            Word::Slot(_position) if *_position > base => reads.push((*_position, effect_before)),
+            Word::Checked(..) => effect_before = true,
+            Word::Apply(opcode, _) if !is_pure_opcode(*opcode) => effect_before = true,
+            _ => {}
+        });
+        let in_order = reads.len() == count
+            && reads
+                .iter()
+                .enumerate()
+                .all(|(index, (position, effect))| *position == base + index + 1 && !effect);
+        if definitions
+            .iter()
+            .any(|definition| definition.slots().iter().any(|position| *position > base))
+        {
+            return None;
+        }
+        let pure = definitions.iter().all(Word::is_pure);
+        if !(in_order || pure && reads.len() == count) {
+            return None;
+        }
+        let mut sorted: Vec<usize> = reads.iter().map(|(position, _)| *position).collect();
+        sorted.sort();
+        if sorted != (base + 1..=top).collect::<Vec<_>>() {
+            return None;
+        }
+        let instructions = &mut self.building().current.as_mut().unwrap().2;
+        instructions.truncate(instructions.len() - count);
+        Some(word.substitute(&|position| {
+            (position > base).then(|| definitions[position - base - 1].clone())
+        }))
+    }
+
+    pub fn jump(&mut self, block: BlockId) {
+        self.instructions();
+        self.terminate(Terminator::Jump(block));
+    }
+
+    /* GOES TO `block` WHEN `condition` IS NOT ZERO */
+    pub fn branch(&mut self, condition: Word, block: BlockId) {
+        self.branch_and_pop(condition, 0, block);
+    }
+
+    /* GOES TO `block` WHEN THE TOP VARIABLE IS NOT ZERO, DROPPING IT EITHER WAY */
+    pub fn jump_if(&mut self, block: BlockId) {
+        let top = self.height;
+        match self.inline_top(1, &Word::Slot(top)) {
+            Some(_condition) => {
+                self.height -= 1;
+                self.branch(_condition, block);
+            }
+            None => self.branch_and_pop(Word::Slot(top), 1, block),
+        }
+    }
+
+    fn branch_and_pop(&mut self, condition: Word, pops: usize, block: BlockId) {
+        self.instructions();
+        let otherwise = self.new_block();
+        self.terminate(Terminator::Branch {
+            condition,
+            pops,
+            then: block,
+            otherwise,
+        });
+        self.height -= pops;
+        self.start(otherwise);
+    }
+
+    /* CALLS `function` WITH THE TOP VARIABLES AS ITS ARGUMENTS */
+    pub fn call(&mut self, function: FunctionId) {
+        self.instructions();
+        let next = self.new_block();
+        self.terminate(Terminator::Call { function, next });
+        let callee = &self.functions[function];
+        self.height = self.height - callee.parameters + callee.returns;
+        self.start(next);
+    }
+
+    pub fn leave(&mut self, outputs: &[usize]) {
+        self.instructions();
+        self.terminate(Terminator::Leave(outputs.to_vec()));
+    }
+}
+
+/* ------------------------------ TEXT ------------------------------ */
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Link::DataOffset(index) => write!(f, "dataoffset({index})"),
+            Link::DataSize(index) => write!(f, "datasize({index})"),
+            Link::ProgramSize => write!(f, "programsize()"),
+            Link::Immutable(id) => write!(f, "loadimmutable({id})"),
+        }
+    }
+}
+
+impl fmt::Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.signed { "int" } else { "uint" };
+        write!(f, "{sign}{}", self.bits)
+    }
+}
+
+fn write_arguments(f: &mut fmt::Formatter<'_>, arguments: &[Word]) -> fmt::Result {
+    for (index, argument) in arguments.iter().enumerate() {
+        if index > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{argument}")?;
+    }
+    f.write_str(")")
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Word::Constant(value) if value.bits() <= 32 => write!(f, "{value}"),
+            Word::Constant(value) => write!(f, "{value:#x}"),
+            Word::Slot(position) => write!(f, "${position}"),
+            Word::Apply(opcode, arguments) => {
+                write!(f, "{}(", opcode.to_string().to_lowercase())?;
+                write_arguments(f, arguments)
+            }
+            Word::Checked(operation, ty, arguments) => {
+                let name = format!("{operation:?}").to_lowercase();
+                write!(f, "checked_{name}<{ty}>(")?;
+                write_arguments(f, arguments)
+            }
+            Word::Link(link) => write!(f, "{link}"),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Let(word) => write!(f, "let {word}"),
+            Instruction::Assign(position, word) => write!(f, "${position} := {word}"),
+            Instruction::Exec(word) => write!(f, "{word}"),
+            Instruction::Keep { base, positions } => {
+                let kept: Vec<String> = positions
+                    .iter()
+                    .map(|position| format!("${position}"))
+                    .collect();
+                write!(f, "keep {base} [{}]", kept.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Terminator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Terminator::Jump(target) => write!(f, "jump b{target}"),
+            Terminator::Branch {
+                condition,
+                pops,
+                then,
+                otherwise,
+            } => {
+                write!(f, "branch {condition} b{then} b{otherwise}")?;
+                if *pops > 0 {
+                    write!(f, " pop {pops}")?;
+                }
+                Ok(())
+            }
+            Terminator::Call { function, next } => write!(f, "call @{function} b{next}"),
+            Terminator::Leave(outputs) => {
+                let outputs: Vec<String> = outputs
+                    .iter()
+                    .map(|position| format!("${position}"))
+                    .collect();
+                write!(f, "leave [{}]", outputs.join(", "))
+            }
+            Terminator::Halt(word) => write!(f, "{word}"),
+            Terminator::Panic(code) => write!(f, "panic {code:#04x}"),
+        }
+    }
+}
+
+/* ONE VARIABLE PER LINE `let`, EACH BLOCK HEADED BY ITS NUMBER AND ENTRY HEIGHT:
+
+    function @1 "Counter.inc(uint256)" 1 -> 1
+    b0 [1]:
+        let $2 := 0
+        $2 := checked_add<uint256>(sload(0), $1)
+        ...
+*/
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:?} {} -> {}", self.name, self.parameters, self.returns)?;
+        for (index, block) in self.blocks.iter().enumerate() {
+            writeln!(f, "b{index} [{}]:", block.height)?;
+            let mut height = block.height;
+            for instruction in &block.instructions {
+                match instruction {
+                    Instruction::Let(word) => {
+                        height += 1;
+                        writeln!(f, "    let ${height} := {word}")?;
+                    }
+                    Instruction::Keep { base, positions } => {
+                        height = base + positions.len();
+                        writeln!(f, "    {instruction}")?;
+                    }
+                    _ => writeln!(f, "    {instruction}")?,
+                }
+            }
+            writeln!(f, "    {}", block.terminator)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, function) in self.functions.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "function @{index} {function}")?;
+        }
+        for (index, data) in self.data.iter().enumerate() {
+            writeln!(f, "\ndata {index}: {} bytes", data.len())?;
+        }
+        Ok(())
+    }
+}
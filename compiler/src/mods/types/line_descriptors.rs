@@ -1,32 +1,38 @@
-use super::token::{StringExtension, Token, VecExtension};
+use std::fmt;
 
-#[derive(Debug, Clone)]
-pub struct LineDescriptions<T> {
+/* BYTE RANGE OF A SOURCE FRAGMENT ALONG WITH ITS 1-BASED LINE AND COLUMN */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub file_id: usize,
+    pub start: usize,
+    pub end: usize,
     pub line: i32,
-    pub data: T,
-}
-pub trait StringDescriptor {
-    fn lex(&self) -> LineDescriptions<Vec<Token>>;
-}
-
-pub trait TokenDescriptor {
-    fn detokenize(&self) -> LineDescriptions<String>;
+    pub column: i32,
 }
 
-impl StringDescriptor for LineDescriptions<String> {
-    fn lex(&self) -> LineDescriptions<Vec<Token>> {
-        LineDescriptions {
-            line: self.line,
-            data: self.data.lex(),
+impl Span {
+    /* SPAN STARTING AT `self` AND ENDING WHERE `other` ENDS */
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            end: other.end.max(self.end),
+            ..*self
         }
     }
 }
 
-impl TokenDescriptor for LineDescriptions<Vec<Token>> {
-    fn detokenize(&self) -> LineDescriptions<String> {
-        LineDescriptions {
-            line: self.line,
-            data: self.data.to_string(),
-        }
+/* KEPT ON ONE LINE, EVEN IN `{:#?}`, SO THAT SYNTAX TREE DUMPS STAY READABLE */
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Span({}:{}..{} @ {}:{})",
+            self.file_id, self.start, self.end, self.line, self.column
+        )
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct LineDescriptions<T> {
+    pub span: Span,
+    pub data: T,
+}
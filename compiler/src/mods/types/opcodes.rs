@@ -0,0 +1,400 @@
+use std::fmt;
+
+/* EVERY EVM INSTRUCTION UP TO CANCUN. THE NUMBERED FAMILIES CARRY THEIR NUMBER: `Push(n)` IS
+PUSHn AND READS n IMMEDIATE BYTES, `Dup(n)`, `Swap(n)` AND `Log(n)` ARE DUPn, SWAPn AND LOGn */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Stop,
+    Add,
+    Mul,
+    Sub,
+    Div,
+    SDiv,
+    Mod,
+    SMod,
+    AddMod,
+    MulMod,
+    Exp,
+    SignExtend,
+    Lt,
+    Gt,
+    SLt,
+    SGt,
+    Eq,
+    IsZero,
+    And,
+    Or,
+    Xor,
+    Not,
+    Byte,
+    Shl,
+    Shr,
+    Sar,
+    Keccak256,
+    Address,
+    Balance,
+    Origin,
+    Caller,
+    CallValue,
+    CallDataLoad,
+    CallDataSize,
+    CallDataCopy,
+    CodeSize,
+    CodeCopy,
+    GasPrice,
+    ExtCodeSize,
+    ExtCodeCopy,
+    ReturnDataSize,
+    ReturnDataCopy,
+    ExtCodeHash,
+    BlockHash,
+    Coinbase,
+    Timestamp,
+    Number,
+    PrevRandao,
+    GasLimit,
+    ChainId,
+    SelfBalance,
+    BaseFee,
+    BlobHash,
+    BlobBaseFee,
+    Pop,
+    MLoad,
+    MStore,
+    MStore8,
+    SLoad,
+    SStore,
+    Jump,
+    JumpI,
+    Pc,
+    MSize,
+    Gas,
+    JumpDest,
+    TLoad,
+    TStore,
+    MCopy,
+    Push(u8),
+    Dup(u8),
+    Swap(u8),
+    Log(u8),
+    Create,
+    Call,
+    CallCode,
+    Return,
+    DelegateCall,
+    Create2,
+    StaticCall,
+    Revert,
+    Invalid,
+    SelfDestruct,
+}
+
+/* THE INSTRUCTIONS WITHOUT A NUMBER, BY BYTE AND MNEMONIC */
+const SIMPLE: [(u8, Opcode, &str); 78] = [
+    (0x00, Opcode::Stop, "STOP"),
+    (0x01, Opcode::Add, "ADD"),
+    (0x02, Opcode::Mul, "MUL"),
+    (0x03, Opcode::Sub, "SUB"),
+    (0x04, Opcode::Div, "DIV"),
+    (0x05, Opcode::SDiv, "SDIV"),
+    (0x06, Opcode::Mod, "MOD"),
+    (0x07, Opcode::SMod, "SMOD"),
+    (0x08, Opcode::AddMod, "ADDMOD"),
+    (0x09, Opcode::MulMod, "MULMOD"),
+    (0x0a, Opcode::Exp, "EXP"),
+    (0x0b, Opcode::SignExtend, "SIGNEXTEND"),
+    (0x10, Opcode::Lt, "LT"),
+    (0x11, Opcode::Gt, "GT"),
+    (0x12, Opcode::SLt, "SLT"),
+    (0x13, Opcode::SGt, "SGT"),
+    (0x14, Opcode::Eq, "EQ"),
+    (0x15, Opcode::IsZero, "ISZERO"),
+    (0x16, Opcode::And, "AND"),
+    (0x17, Opcode::Or, "OR"),
+    (0x18, Opcode::Xor, "XOR"),
+    (0x19, Opcode::Not, "NOT"),
+    (0x1a, Opcode::Byte, "BYTE"),
+    (0x1b, Opcode::Shl, "SHL"),
+    (0x1c, Opcode::Shr, "SHR"),
+    (0x1d, Opcode::Sar, "SAR"),
+    (0x20, Opcode::Keccak256, "KECCAK256"),
+    (0x30, Opcode::Address, "ADDRESS"),
+    (0x31, Opcode::Balance, "BALANCE"),
+    (0x32, Opcode::Origin, "ORIGIN"),
+    (0x33, Opcode::Caller, "CALLER"),
+    (0x34, Opcode::CallValue, "CALLVALUE"),
+    (0x35, Opcode::CallDataLoad, "CALLDATALOAD"),
+    (0x36, Opcode::CallDataSize, "CALLDATASIZE"),
+    (0x37, Opcode::CallDataCopy, "CALLDATACOPY"),
+    (0x38, Opcode::CodeSize, "CODESIZE"),
+    (0x39, Opcode::CodeCopy, "CODECOPY"),
+    (0x3a, Opcode::GasPrice, "GASPRICE"),
+    (0x3b, Opcode::ExtCodeSize, "EXTCODESIZE"),
+    (0x3c, Opcode::ExtCodeCopy, "EXTCODECOPY"),
+    (0x3d, Opcode::ReturnDataSize, "RETURNDATASIZE"),
+    (0x3e, Opcode::ReturnDataCopy, "RETURNDATACOPY"),
+    (0x3f, Opcode::ExtCodeHash, "EXTCODEHASH"),
+    (0x40, Opcode::BlockHash, "BLOCKHASH"),
+    (0x41, Opcode::Coinbase, "COINBASE"),
+    (0x42, Opcode::Timestamp, "TIMESTAMP"),
+    (0x43, Opcode::Number, "NUMBER"),
+    (0x44, Opcode::PrevRandao, "PREVRANDAO"),
+    (0x45, Opcode::GasLimit, "GASLIMIT"),
+    (0x46, Opcode::ChainId, "CHAINID"),
+    (0x47, Opcode::SelfBalance, "SELFBALANCE"),
+    (0x48, Opcode::BaseFee, "BASEFEE"),
+    (0x49, Opcode::BlobHash, "BLOBHASH"),
+    (0x4a, Opcode::BlobBaseFee, "BLOBBASEFEE"),
+    (0x50, Opcode::Pop, "POP"),
+    (0x51, Opcode::MLoad, "MLOAD"),
+    (0x52, Opcode::MStore, "MSTORE"),
+    (0x53, Opcode::MStore8, "MSTORE8"),
+    (0x54, Opcode::SLoad, "SLOAD"),
+    (0x55, Opcode::SStore, "SSTORE"),
+    (0x56, Opcode::Jump, "JUMP"),
+    (0x57, Opcode::JumpI, "JUMPI"),
+    (0x58, Opcode::Pc, "PC"),
+    (0x59, Opcode::MSize, "MSIZE"),
+    (0x5a, Opcode::Gas, "GAS"),
+    (0x5b, Opcode::JumpDest, "JUMPDEST"),
+    (0x5c, Opcode::TLoad, "TLOAD"),
+    (0x5d, Opcode::TStore, "TSTORE"),
+    (0x5e, Opcode::MCopy, "MCOPY"),
+    (0xf0, Opcode::Create, "CREATE"),
+    (0xf1, Opcode::Call, "CALL"),
+    (0xf2, Opcode::CallCode, "CALLCODE"),
+    (0xf3, Opcode::Return, "RETURN"),
+    (0xf4, Opcode::DelegateCall, "DELEGATECALL"),
+    (0xf5, Opcode::Create2, "CREATE2"),
+    (0xfa, Opcode::StaticCall, "STATICCALL"),
+    (0xfd, Opcode::Revert, "REVERT"),
+    (0xfe, Opcode::Invalid, "INVALID"),
+];
+
+/* THE INSTRUCTIONS INLINE ASSEMBLY CALLS BY NAME. JUMPS, `pc` AND THE STACK ONES ARE LEFT OUT,
+THE COMPILER MANAGES THE STACK AND THE CONTROL FLOW */
+pub const BUILTINS: [(&str, Opcode); 80] = [
+    ("stop", Opcode::Stop),
+    ("add", Opcode::Add),
+    ("mul", Opcode::Mul),
+    ("sub", Opcode::Sub),
+    ("div", Opcode::Div),
+    ("sdiv", Opcode::SDiv),
+    ("mod", Opcode::Mod),
+    ("smod", Opcode::SMod),
+    ("addmod", Opcode::AddMod),
+    ("mulmod", Opcode::MulMod),
+    ("exp", Opcode::Exp),
+    ("signextend", Opcode::SignExtend),
+    ("lt", Opcode::Lt),
+    ("gt", Opcode::Gt),
+    ("slt", Opcode::SLt),
+    ("sgt", Opcode::SGt),
+    ("eq", Opcode::Eq),
+    ("iszero", Opcode::IsZero),
+    ("and", Opcode::And),
+    ("or", Opcode::Or),
+    ("xor", Opcode::Xor),
+    ("not", Opcode::Not),
+    ("byte", Opcode::Byte),
+    ("shl", Opcode::Shl),
+    ("shr", Opcode::Shr),
+    ("sar", Opcode::Sar),
+    ("keccak256", Opcode::Keccak256),
+    ("address", Opcode::Address),
+    ("balance", Opcode::Balance),
+    ("origin", Opcode::Origin),
+    ("caller", Opcode::Caller),
+    ("callvalue", Opcode::CallValue),
+    ("calldataload", Opcode::CallDataLoad),
+    ("calldatasize", Opcode::CallDataSize),
+    ("calldatacopy", Opcode::CallDataCopy),
+    ("codesize", Opcode::CodeSize),
+    ("codecopy", Opcode::CodeCopy),
+    ("gasprice", Opcode::GasPrice),
+    ("extcodesize", Opcode::ExtCodeSize),
+    ("extcodecopy", Opcode::ExtCodeCopy),
+    ("returndatasize", Opcode::ReturnDataSize),
+    ("returndatacopy", Opcode::ReturnDataCopy),
+    ("extcodehash", Opcode::ExtCodeHash),
+    ("blockhash", Opcode::BlockHash),
+    ("coinbase", Opcode::Coinbase),
+    ("timestamp", Opcode::Timestamp),
+    ("number", Opcode::Number),
+    ("prevrandao", Opcode::PrevRandao),
+    ("gaslimit", Opcode::GasLimit),
+    ("chainid", Opcode::ChainId),
+    ("selfbalance", Opcode::SelfBalance),
+    ("basefee", Opcode::BaseFee),
+    ("blobhash", Opcode::BlobHash),
+    ("blobbasefee", Opcode::BlobBaseFee),
+    ("pop", Opcode::Pop),
+    ("mload", Opcode::MLoad),
+    ("mstore", Opcode::MStore),
+    ("mstore8", Opcode::MStore8),
+    ("sload", Opcode::SLoad),
+    ("sstore", Opcode::SStore),
+    ("msize", Opcode::MSize),
+    ("gas", Opcode::Gas),
+    ("tload", Opcode::TLoad),
+    ("tstore", Opcode::TStore),
+    ("mcopy", Opcode::MCopy),
+    ("log0", Opcode::Log(0)),
+    ("log1", Opcode::Log(1)),
+    ("log2", Opcode::Log(2)),
+    ("log3", Opcode::Log(3)),
+    ("log4", Opcode::Log(4)),
+    ("create", Opcode::Create),
+    ("call", Opcode::Call),
+    ("callcode", Opcode::CallCode),
+    ("return", Opcode::Return),
+    ("delegatecall", Opcode::DelegateCall),
+    ("create2", Opcode::Create2),
+    ("staticcall", Opcode::StaticCall),
+    ("revert", Opcode::Revert),
+    ("invalid", Opcode::Invalid),
+    ("selfdestruct", Opcode::SelfDestruct),
+];
+
+impl Opcode {
+    pub fn byte(self) -> u8 {
+        match self {
+            Opcode::Push(size) => 0x5f + size,
+            Opcode::Dup(depth) => 0x7f + depth,
+            Opcode::Swap(depth) => 0x8f + depth,
+            Opcode::Log(topics) => 0xa0 + topics,
+            Opcode::SelfDestruct => 0xff,
+            opcode => {
+                SIMPLE
+                    .iter()
+                    .find(|(_, candidate, _)| *candidate == opcode)
+                    .unwrap()
+                    .0
+            }
+        }
+    }
+
+    /* None FOR BYTES THAT ARE NO INSTRUCTION, WHICH EXECUTE AS `INVALID` */
+    #[allow(dead_code)]
+    pub fn from_byte(byte: u8) -> Option<Opcode> {
+        match byte {
+            0x5f..=0x7f => Some(Opcode::Push(byte - 0x5f)),
+            0x80..=0x8f => Some(Opcode::Dup(byte - 0x7f)),
+            0x90..=0x9f => Some(Opcode::Swap(byte - 0x8f)),
+            0xa0..=0xa4 => Some(Opcode::Log(byte - 0xa0)),
+            0xff => Some(Opcode::SelfDestruct),
+            _ => SIMPLE
+                .iter()
+                .find(|(candidate, _, _)| *candidate == byte)
+                .map(|(_, opcode, _)| *opcode),
+        }
+    }
+
+    /* NUMBER OF IMMEDIATE BYTES THAT FOLLOW THE INSTRUCTION IN THE CODE */
+    #[allow(dead_code)]
+    pub fn immediate_size(self) -> usize {
+        match self {
+            Opcode::Push(size) => size as usize,
+            _ => 0,
+        }
+    }
+
+    /* THE BUILTIN OF INLINE ASSEMBLY CALLED `name` */
+    pub fn builtin(name: &str) -> Option<(&'static str, Opcode)> {
+        BUILTINS
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .copied()
+    }
+
+    /* HOW MANY VALUES THE INSTRUCTION TAKES OFF THE STACK AND HOW MANY IT PUTS BACK */
+    pub fn stack_io(self) -> (usize, usize) {
+        match self {
+            Opcode::Push(_) => (0, 1),
+            Opcode::Dup(depth) => (depth as usize, depth as usize + 1),
+            Opcode::Swap(depth) => (depth as usize + 1, depth as usize + 1),
+            Opcode::Log(topics) => (topics as usize + 2, 0),
+            Opcode::Stop | Opcode::JumpDest | Opcode::Invalid => (0, 0),
+            Opcode::Address
+            | Opcode::Origin
+            | Opcode::Caller
+            | Opcode::CallValue
+            | Opcode::CallDataSize
+            | Opcode::CodeSize
+            | Opcode::GasPrice
+            | Opcode::ReturnDataSize
+            | Opcode::Coinbase
+            | Opcode::Timestamp
+            | Opcode::Number
+            | Opcode::PrevRandao
+            | Opcode::GasLimit
+            | Opcode::ChainId
+            | Opcode::SelfBalance
+            | Opcode::BaseFee
+            | Opcode::BlobBaseFee
+            | Opcode::Pc
+            | Opcode::MSize
+            | Opcode::Gas => (0, 1),
+            Opcode::IsZero
+            | Opcode::Not
+            | Opcode::Balance
+            | Opcode::CallDataLoad
+            | Opcode::ExtCodeSize
+            | Opcode::ExtCodeHash
+            | Opcode::BlockHash
+            | Opcode::BlobHash
+            | Opcode::MLoad
+            | Opcode::SLoad
+            | Opcode::TLoad => (1, 1),
+            Opcode::Pop | Opcode::Jump | Opcode::SelfDestruct => (1, 0),
+            Opcode::MStore
+            | Opcode::MStore8
+            | Opcode::SStore
+            | Opcode::TStore
+            | Opcode::JumpI
+            | Opcode::Return
+            | Opcode::Revert => (2, 0),
+            Opcode::CallDataCopy | Opcode::CodeCopy | Opcode::ReturnDataCopy | Opcode::MCopy => {
+                (3, 0)
+            }
+            Opcode::ExtCodeCopy => (4, 0),
+            Opcode::AddMod | Opcode::MulMod | Opcode::Create => (3, 1),
+            Opcode::Create2 => (4, 1),
+            Opcode::Call | Opcode::CallCode => (7, 1),
+            Opcode::DelegateCall | Opcode::StaticCall => (6, 1),
+            _ => (2, 1),
+        }
+    }
+
+    /* INSTRUCTIONS AFTER WHICH EXECUTION NEVER FALLS THROUGH TO THE NEXT ONE */
+    pub fn is_terminator(self) -> bool {
+        matches!(
+            self,
+            Opcode::Stop
+                | Opcode::Jump
+                | Opcode::Return
+                | Opcode::Revert
+                | Opcode::Invalid
+                | Opcode::SelfDestruct
+        )
+    }
+}
+
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Opcode::Push(size) => write!(f, "PUSH{size}"),
+            Opcode::Dup(depth) => write!(f, "DUP{depth}"),
+            Opcode::Swap(depth) => write!(f, "SWAP{depth}"),
+            Opcode::Log(topics) => write!(f, "LOG{topics}"),
+            Opcode::SelfDestruct => write!(f, "SELFDESTRUCT"),
+            opcode => {
+                let (_, _, name) = SIMPLE
+                    .iter()
+                    .find(|(_, candidate, _)| candidate == opcode)
+                    .unwrap();
+                f.write_str(name)
+            }
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use std::{collections::HashMap, fmt};
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+
+use super::{
+    ast::{ElementaryType, StateMutability},
+    bindings::Declaration,
+    line_descriptors::Span,
+};
+
+/* TYPE OF AN EXPRESSION OR A DECLARATION AS SEEN BY THE TYPE CHECKER. DATA LOCATIONS ARE NOT
+PART OF THE TYPE. USER DEFINED TYPES CARRY THEIR NAME FOR MESSAGES AND THE INDICES THAT
+IDENTIFY THEIR DEFINITION, AS IN `Declaration` */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Address {
+        payable: bool,
+    },
+    Bool,
+    String,
+    Bytes,
+    FixedBytes(u8),
+    Integer {
+        signed: bool,
+        bits: u16,
+    },
+    /* A NUMBER LITERAL OR AN EXPRESSION OF THEM, KEPT EXACT UNTIL IT IS CONVERTED. A HEX
+    LITERAL KEEPS ITS NUMBER OF DIGITS, IT CAN ALSO BECOME THE `bytesN` OF HALF THAT SIZE */
+    RationalConstant(BigRational, Option<usize>),
+    /* A STRING OR HEX LITERAL, WHICH CAN BECOME `string`, `bytes` OR A LARGE ENOUGH `bytesN` */
+    StringLiteral(Vec<u8>),
+    Array {
+        base: Box<Type>,
+        length: Option<BigInt>,
+    },
+    Mapping {
+        key: Box<Type>,
+        value: Box<Type>,
+    },
+    Struct {
+        name: String,
+        file_id: usize,
+        contract: usize,
+        part: usize,
+    },
+    Enum {
+        name: String,
+        file_id: usize,
+        contract: usize,
+        part: usize,
+    },
+    Contract {
+        name: String,
+        file_id: usize,
+        contract: usize,
+    },
+    Function(FunctionType),
+    /* A NAME THAT MEANS MORE THAN ONE FUNCTION OR EVENT, PICKED FROM BY THE CALL'S ARGUMENTS */
+    Overloaded(Vec<FunctionType>),
+    /* COMPONENTS OF `(a, , b)` OR THE RESULTS OF A CALL. None FOR AN EMPTY SLOT */
+    Tuple(Vec<Option<Type>>),
+    /* AN EXPRESSION THAT NAMES A TYPE, E.G `uint8` IN `uint8(x)` OR A STRUCT NAME */
+    TypeType(Box<Type>),
+    /* AN IMPORTED UNIT, `M` IN `import "x" as M` */
+    Module(usize),
+    /* GLOBALS SUCH AS `msg` AND BUILTIN FUNCTIONS SUCH AS `require` OR `abi.encode` */
+    Magic(&'static str),
+    /* BUILTIN MEMBER FUNCTIONS SUCH AS `push` ON AN ARRAY OR `call` ON AN ADDRESS */
+    BuiltinMember {
+        name: &'static str,
+        receiver: Box<Type>,
+    },
+    Modifier,
+    /* THE TYPE OF AN EXPRESSION THAT ALREADY FAILED TO CHECK. IT CONVERTS TO AND FROM
+    ANYTHING SO THAT ONE MISTAKE IS REPORTED ONCE */
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionKind {
+    Internal,
+    External,
+    Event,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionType {
+    pub kind: FunctionKind,
+    pub parameters: Vec<Type>,
+    pub parameter_names: Vec<Option<String>>,
+    pub returns: Vec<Type>,
+    pub mutability: Option<StateMutability>,
+}
+
+/* VALUE OF AN EXPRESSION KNOWN AT COMPILE TIME */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    /* A LITERAL OR AN EXPRESSION OF LITERALS, EXACT UNTIL IT MEETS A TYPED VALUE */
+    Rational(BigRational),
+    /* A VALUE OF A SIZED INTEGER TYPE, SUCH AS A `uint8 constant` OR `type(uint8).max` */
+    Integer {
+        value: BigInt,
+        signed: bool,
+        bits: u16,
+    },
+    Bool(bool),
+    Bytes(Vec<u8>),
+}
+
+/* TYPES FOUND BY THE TYPE CHECKER. EXPRESSIONS ARE KEYED BY THEIR SPAN, PARAMETERS AND
+VARIABLES BY THE SPAN OF THEIR NAME, THE SAME SPAN AS `Declaration::Local` */
+#[derive(Debug, Clone, Default)]
+pub struct TypeTable {
+    pub expressions: HashMap<Span, Type>,
+    pub declarations: HashMap<Span, Type>,
+    /* EVERY TYPE WRITTEN IN THE SOURCE, BY THE SPAN OF ITS `TypeName` */
+    pub type_names: HashMap<Span, Type>,
+    /* VALUES OF THE `constant` STATE VARIABLES THAT FOLD AT COMPILE TIME, BY NAME SPAN */
+    pub constants: HashMap<Span, ConstValue>,
+    /* FOR A CALL OF AN OVERLOADED NAME, BY THE SPAN OF THE CALL, THE INDEX OF THE CANDIDATE IN
+    `Type::Overloaded` THE ARGUMENTS PICKED */
+    pub overloads: HashMap<Span, usize>,
+    /* LIBRARY FUNCTIONS A MEMBER NAME MEANS THROUGH `using L for T`, BY THE SPAN OF THE NAME, IN
+    THE ORDER OF THE CANDIDATES OF ITS TYPE */
+    pub attached: HashMap<Span, Vec<Declaration>>,
+    /* FUNCTIONS `super.name` MEANS IN THE CONTRACT IT IS WRITTEN IN, BY THE SPAN OF THE NAME, IN
+    THE ORDER OF THE CANDIDATES OF ITS TYPE */
+    pub supers: HashMap<Span, Vec<Declaration>>,
+}
+
+impl Type {
+    pub const UINT256: Type = Type::Integer {
+        signed: false,
+        bits: 256,
+    };
+
+    pub fn elementary(ty: ElementaryType) -> Type {
+        match ty {
+            ElementaryType::Address => Type::Address { payable: false },
+            ElementaryType::AddressPayable => Type::Address { payable: true },
+            ElementaryType::Bool => Type::Bool,
+            ElementaryType::String => Type::String,
+            ElementaryType::Bytes => Type::Bytes,
+            ElementaryType::FixedBytes(size) => Type::FixedBytes(size),
+            ElementaryType::Uint(bits) => Type::Integer {
+                signed: false,
+                bits,
+            },
+            ElementaryType::Int(bits) => Type::Integer { signed: true, bits },
+        }
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Type::Unknown)
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Type::Integer { .. })
+    }
+
+    /* TYPES THAT ARE COPIED RATHER THAN REFERENCED AND MAY BE MAPPING KEYS */
+    pub fn is_value_type(&self) -> bool {
+        matches!(
+            self,
+            Type::Address { .. }
+                | Type::Bool
+                | Type::FixedBytes(_)
+                | Type::Integer { .. }
+                | Type::Enum { .. }
+                | Type::Contract { .. }
+        )
+    }
+
+    /* SMALLEST TYPE THAT CAN HOLD A CONSTANT, E.G `uint8` FOR `255` AND `int16` FOR `-129` */
+    pub fn mobile(&self) -> Type {
+        match self {
+            Type::RationalConstant(value, _) if value.is_integer() => {
+                let value = value.to_integer();
+                let signed = value.is_negative();
+                let magnitude = if signed { -value - 1 } else { value };
+                let bits = (magnitude.bits() as u16 + signed as u16).div_ceil(8) * 8;
+                if bits > 256 {
+                    Type::Unknown
+                } else {
+                    Type::Integer {
+                        signed,
+                        bits: bits.max(8),
+                    }
+                }
+            }
+            Type::RationalConstant(..) => Type::Unknown,
+            Type::StringLiteral(_) => Type::String,
+            Type::Tuple(components) => Type::Tuple(
+                components
+                    .iter()
+                    .map(|component| component.as_ref().map(Type::mobile))
+                    .collect(),
+            ),
+            ty => ty.clone(),
+        }
+    }
+
+    /* RANGE OF AN INTEGER TYPE */
+    pub fn integer_range(signed: bool, bits: u16) -> (BigInt, BigInt) {
+        if signed {
+            let half = BigInt::one() << (bits - 1);
+            (-half.clone(), half - 1)
+        } else {
+            (BigInt::zero(), (BigInt::one() << bits) - 1)
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Address { payable: false } => write!(f, "address"),
+            Type::Address { payable: true } => write!(f, "address payable"),
+            Type::Bool => write!(f, "bool"),
+            Type::String => write!(f, "string"),
+            Type::Bytes => write!(f, "bytes"),
+            Type::FixedBytes(size) => write!(f, "bytes{size}"),
+            Type::Integer { signed, bits } => {
+                write!(f, "{}int{bits}", if *signed { "" } else { "u" })
+            }
+            Type::RationalConstant(value, _) if value.is_integer() => {
+                write!(f, "int_const {}", value.to_integer())
+            }
+            Type::RationalConstant(value, _) => write!(f, "rational_const {value}"),
+            Type::StringLiteral(value) => match std::str::from_utf8(value) {
+                Ok(text) => write!(f, "literal_string \"{}\"", text.escape_debug()),
+                Err(_) => write!(f, "literal_string hex\"{}\"", hex::encode(value)),
+            },
+            Type::Array { base, length } => match length {
+                Some(length) => write!(f, "{base}[{length}]"),
+                None => write!(f, "{base}[]"),
+            },
+            Type::Mapping { key, value } => write!(f, "mapping({key} => {value})"),
+            Type::Struct { name, .. } => write!(f, "struct {name}"),
+            Type::Enum { name, .. } => write!(f, "enum {name}"),
+            Type::Contract { name, .. } => write!(f, "contract {name}"),
+            Type::Function(function) => write!(f, "{function}"),
+            Type::Overloaded(_) => write!(f, "overloaded function"),
+            Type::Tuple(components) => {
+                let components: Vec<String> = components
+                    .iter()
+                    .map(|component| component.as_ref().map_or(String::new(), Type::to_string))
+                    .collect();
+                write!(f, "tuple({})", components.join(","))
+            }
+            Type::TypeType(ty) => write!(f, "type({ty})"),
+            Type::Module(_) => write!(f, "module"),
+            Type::Magic(name) => write!(f, "{name}"),
+            Type::BuiltinMember { name, receiver } => write!(f, "{receiver}.{name}"),
+            Type::Modifier => write!(f, "modifier"),
+            Type::Unknown => write!(f, "<unknown>"),
+        }
+    }
+}
+
+impl ConstValue {
+    /* THE VALUE AS A WHOLE NUMBER, WHATEVER ITS TYPE */
+    pub fn to_integer(&self) -> Option<BigInt> {
+        match self {
+            ConstValue::Rational(value) if value.is_integer() => Some(value.to_integer()),
+            ConstValue::Integer { value, .. } => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstValue::Rational(value) if value.is_integer() => {
+                write!(f, "{}", value.to_integer())
+            }
+            ConstValue::Rational(value) => write!(f, "{value}"),
+            ConstValue::Integer { value, .. } => write!(f, "{value}"),
+            ConstValue::Bool(value) => write!(f, "{value}"),
+            ConstValue::Bytes(value) => write!(f, "hex\"{}\"", hex::encode(value)),
+        }
+    }
+}
+
+impl fmt::Display for FunctionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |types: &[Type]| {
+            types
+                .iter()
+                .map(Type::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let keyword = match self.kind {
+            FunctionKind::Internal | FunctionKind::External => "function",
+            FunctionKind::Event => "event",
+            FunctionKind::Error => "error",
+        };
+        write!(f, "{keyword} ({})", list(&self.parameters))?;
+        if !self.returns.is_empty() {
+            write!(f, " returns ({})", list(&self.returns))?;
+        }
+        Ok(())
+    }
+}
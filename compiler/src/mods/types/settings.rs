@@ -0,0 +1,155 @@
+use std::{fmt, path::PathBuf, str::FromStr};
+
+/* OPTIONS THAT AFFECT HOW SOURCES ARE RESOLVED AND COMPILED */
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub evm_version: EvmVersion,
+    pub optimize: bool,
+    pub remappings: Vec<Remapping>,
+    pub base_path: Option<PathBuf>,
+    pub include_paths: Vec<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    /* WHERE THE STORAGE LAYOUT SNAPSHOTS OF THE DEPLOYED VERSION ARE READ FROM */
+    pub storage_snapshots: Option<PathBuf>,
+    /* INTERMEDIATE OUTPUTS WRITTEN NEXT TO THE ARTIFACTS */
+    pub emit: Vec<Emit>,
+    /* OPTIMIZER PASSES LEFT OUT OF `--optimize` */
+    pub disabled_passes: Vec<Pass>,
+}
+
+/* WHAT `--emit` CAN ASK FOR */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Emit {
+    /// The IR the bytecode is lowered from
+    Ir,
+    /// The IR before and after every optimizer pass that changes it
+    Passes,
+}
+
+/* THE OPTIMIZER PASSES, IN THE ORDER THEY RUN */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Pass {
+    /// Inline small internal functions
+    Inlining,
+    /// Propagate constants and copies, evaluate constant expressions and simplify identities
+    ConstantFolding,
+    /// Skip empty blocks and merge blocks with a single predecessor
+    JumpThreading,
+    /// Replace `sload` of a slot just written with the value stored
+    LoadForwarding,
+    /// Drop repeated `sload`s and `sstore`s that do not change storage
+    RedundantStorage,
+    /// Reuse values already computed in the same block
+    Cse,
+    /// Remove unreachable blocks and functions and unused values
+    DeadCode,
+}
+
+pub const PASSES: [Pass; 7] = [
+    Pass::Inlining,
+    Pass::ConstantFolding,
+    Pass::JumpThreading,
+    Pass::LoadForwarding,
+    Pass::RedundantStorage,
+    Pass::Cse,
+    Pass::DeadCode,
+];
+
+impl fmt::Display for Pass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = clap::ValueEnum::to_possible_value(self).expect("every pass has a name");
+        f.write_str(name.get_name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum EvmVersion {
+    Homestead,
+    TangerineWhistle,
+    SpuriousDragon,
+    Byzantium,
+    Constantinople,
+    Petersburg,
+    Istanbul,
+    Berlin,
+    London,
+    Paris,
+    Shanghai,
+    #[default]
+    Cancun,
+}
+
+const EVM_VERSIONS: [(&str, EvmVersion); 12] = [
+    ("homestead", EvmVersion::Homestead),
+    ("tangerineWhistle", EvmVersion::TangerineWhistle),
+    ("spuriousDragon", EvmVersion::SpuriousDragon),
+    ("byzantium", EvmVersion::Byzantium),
+    ("constantinople", EvmVersion::Constantinople),
+    ("petersburg", EvmVersion::Petersburg),
+    ("istanbul", EvmVersion::Istanbul),
+    ("berlin", EvmVersion::Berlin),
+    ("london", EvmVersion::London),
+    ("paris", EvmVersion::Paris),
+    ("shanghai", EvmVersion::Shanghai),
+    ("cancun", EvmVersion::Cancun),
+];
+
+impl FromStr for EvmVersion {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        EVM_VERSIONS
+            .iter()
+            .find(|(name, _)| *name == input)
+            .map(|(_, version)| *version)
+            .ok_or_else(|| {
+                let names: Vec<&str> = EVM_VERSIONS.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown EVM version `{input}`, expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for EvmVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = EVM_VERSIONS
+            .iter()
+            .find(|(_, version)| version == self)
+            .map_or("cancun", |(name, _)| *name);
+        f.write_str(name)
+    }
+}
+
+/* IMPORT REMAPPING WRITTEN AS `[context:]prefix=target`, AS IN solc */
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remapping {
+    pub context: Option<String>,
+    pub prefix: String,
+    pub target: String,
+}
+
+impl FromStr for Remapping {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (left, target) = input
+            .split_once('=')
+            .ok_or_else(|| format!("invalid remapping `{input}`, expected `prefix=target`"))?;
+        let (context, prefix) = match left.split_once(':') {
+            Some((context, prefix)) => (Some(context.to_string()), prefix),
+            None => (None, left),
+        };
+        if prefix.is_empty() {
+            return Err(format!("invalid remapping `{input}`, the prefix is empty"));
+        }
+        Ok(Remapping {
+            context: context.filter(|context| !context.is_empty()),
+            prefix: prefix.to_string(),
+            target: target.to_string(),
+        })
+    }
+}
@@ -0,0 +1,48 @@
+use std::collections::BTreeMap;
+
+use super::{ast::SourceUnit, line_descriptors::LineDescriptions, token::Token};
+
+/* EVERY SOURCE UNIT OF A COMPILATION, THE INPUTS AND EVERYTHING THEY IMPORT.
+`units` IS INDEXED BY THE SAME FILE ID AS THE `SourceMap` */
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct SourceGraph {
+    pub units: Vec<ResolvedUnit>,
+    /* FILE IDS OF THE UNITS NAMED ON THE COMMAND LINE */
+    pub entries: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedUnit {
+    pub file_id: usize,
+    pub tokens: Vec<LineDescriptions<Token>>,
+    pub ast: SourceUnit,
+    /* FILE ID LOADED FOR EACH OF `ast.imports`, NONE IF IT COULD NOT BE FOUND */
+    pub imports: Vec<Option<usize>>,
+    /* EVERY NAME VISIBLE AT FILE LEVEL, WHETHER DECLARED HERE OR IMPORTED */
+    pub scope: BTreeMap<String, Symbol>,
+}
+
+/* A FILE LEVEL DECLARATION, POSSIBLY OWNED BY ANOTHER UNIT */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbol {
+    /* INDEX INTO `ast.contracts` OF THE OWNING UNIT */
+    Contract {
+        file_id: usize,
+        index: usize,
+    },
+    /* INDEX INTO `ast.custom_errors` OF THE OWNING UNIT */
+    Error {
+        file_id: usize,
+        index: usize,
+    },
+    /* A STRUCT, ENUM, CONSTANT OR FUNCTION DECLARED AT FILE LEVEL, A PART OF THE UNIT'S
+    `ContractKind::File` DEFINITION. OVERLOADS OF A FUNCTION SHARE THE SYMBOL OF THE FIRST ONE */
+    Member {
+        file_id: usize,
+        contract: usize,
+        part: usize,
+    },
+    /* A WHOLE UNIT BROUGHT IN WITH `import "x" as M` OR `import * as M from "x"` */
+    Unit(usize),
+}
@@ -0,0 +1,61 @@
+/* SOURCE FILES OF A COMPILATION. `Span::file_id` INDEXES INTO `files` */
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub name: String,
+    pub contents: String,
+}
+
+impl SourceMap {
+    pub fn add(&mut self, name: impl Into<String>, contents: impl Into<String>) -> usize {
+        self.files.push(SourceFile {
+            name: name.into(),
+            contents: contents.into(),
+        });
+        self.files.len() - 1
+    }
+
+    pub fn get(&self, file_id: usize) -> Option<&SourceFile> {
+        self.files.get(file_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /* FILE ID OF THE SOURCE UNIT CALLED `name`, IF IT HAS BEEN LOADED */
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.files.iter().position(|file| file.name == name)
+    }
+}
+
+impl SourceFile {
+    /* TEXT OF THE 1-BASED `line`, WITHOUT ITS LINE BREAK */
+    pub fn line(&self, line: i32) -> Option<&str> {
+        if line < 1 {
+            return None;
+        }
+        self.contents
+            .lines()
+            .nth(line as usize - 1)
+            .map(|text| text.trim_end_matches('\r'))
+    }
+
+    /* 1-BASED LINE AND CHARACTER COLUMN OF A BYTE OFFSET */
+    pub fn position(&self, offset: usize) -> (i32, i32) {
+        let before = &self.contents[..offset.min(self.contents.len())];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        (
+            before.matches('\n').count() as i32 + 1,
+            before[line_start..].chars().count() as i32 + 1,
+        )
+    }
+}
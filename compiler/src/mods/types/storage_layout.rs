@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigUint;
+use serde_json::{json, Map, Value};
+
+use super::line_descriptors::Span;
+
+/* A STATE VARIABLE OR STRUCT MEMBER AND WHERE IT IS STORED. `contract` IS `source:Name` OF THE
+CONTRACT THAT DECLARES IT AND `ty` A KEY INTO `StorageLayout::types` */
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageVariable {
+    pub contract: String,
+    pub label: String,
+    pub slot: BigUint,
+    /* BYTES FROM THE LOW END OF THE SLOT */
+    pub offset: u8,
+    pub ty: String,
+    /* WHERE IT IS DECLARED, NONE WHEN READ BACK FROM A SNAPSHOT */
+    pub span: Option<Span>,
+}
+
+/* HOW A VALUE OF ONE TYPE IS STORED, AS IN THE `types` OF solc's `storageLayout` */
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageType {
+    /* `inplace`, `mapping`, `dynamic_array` OR `bytes` */
+    pub encoding: String,
+    pub label: String,
+    pub number_of_bytes: BigUint,
+    /* ELEMENT TYPE OF AN ARRAY */
+    pub base: Option<String>,
+    pub key: Option<String>,
+    pub value: Option<String>,
+    /* MEMBERS OF A STRUCT, SLOTS COUNTED FROM THE START OF THE STRUCT */
+    pub members: Option<Vec<StorageVariable>>,
+}
+
+/* THE STATE VARIABLES OF A CONTRACT, INHERITED ONES FIRST, IN STORAGE ORDER, AND THE TYPES
+THEY USE */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StorageLayout {
+    pub variables: Vec<StorageVariable>,
+    pub types: BTreeMap<String, StorageType>,
+}
+
+impl StorageVariable {
+    pub fn contract_name(&self) -> &str {
+        self.contract.rsplit(':').next().unwrap_or_default()
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "contract": self.contract,
+            "label": self.label,
+            "offset": self.offset,
+            "slot": self.slot.to_string(),
+            "type": self.ty,
+        })
+    }
+
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let label = string_field(value, "label")?;
+        let number = |name: &str| {
+            string_field(value, name)
+                .ok()
+                .or_else(|| value.get(name).map(Value::to_string))
+                .ok_or(format!("`{label}` has no `{name}`"))
+        };
+        Ok(StorageVariable {
+            contract: string_field(value, "contract")?,
+            slot: number("slot")?
+                .parse()
+                .map_err(|_| format!("`{label}` has an invalid slot"))?,
+            offset: number("offset")?
+                .parse()
+                .map_err(|_| format!("`{label}` has an invalid offset"))?,
+            ty: string_field(value, "type")?,
+            label,
+            span: None,
+        })
+    }
+}
+
+impl StorageLayout {
+    /* WHERE `variable` IS STORED IN THIS LAYOUT. ONLY THE CONTRACT NAME HAS TO MATCH, SO THAT
+    MOVING A CONTRACT TO ANOTHER FILE DOES NOT COUNT AS A CHANGE */
+    pub fn position(&self, variable: &StorageVariable) -> Option<usize> {
+        self.variables.iter().position(|candidate| {
+            candidate.label == variable.label
+                && candidate.contract_name() == variable.contract_name()
+        })
+    }
+
+    /* THE TYPE AS WRITTEN IN THE SOURCE, FOR MESSAGES */
+    pub fn label<'a>(&'a self, ty: &'a str) -> &'a str {
+        self.types.get(ty).map_or(ty, |ty| ty.label.as_str())
+    }
+
+    /* solc's `storageLayout` */
+    pub fn to_json(&self) -> Value {
+        let types: Map<String, Value> = self
+            .types
+            .iter()
+            .map(|(id, ty)| {
+                let mut entry = json!({
+                    "encoding": ty.encoding,
+                    "label": ty.label,
+                    "numberOfBytes": ty.number_of_bytes.to_string(),
+                });
+                for (name, value) in [("base", &ty.base), ("key", &ty.key), ("value", &ty.value)] {
+                    if let Some(_value) = value {
+                        entry[name] = json!(_value);
+                    }
+                }
+                if let Some(_members) = &ty.members {
+                    entry["members"] = _members.iter().map(StorageVariable::to_json).collect();
+                }
+                (id.clone(), entry)
+            })
+            .collect();
+        json!({
+            "storage": self.variables.iter().map(StorageVariable::to_json).collect::<Vec<_>>(),
+            "types": types,
+        })
+    }
+
+    /* READS BACK A LAYOUT WRITTEN BY `to_json` */
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let variables = value
+            .get("storage")
+            .and_then(Value::as_array)
+            .ok_or("missing `storage` list")?
+            .iter()
+            .map(StorageVariable::from_json)
+            .collect::<Result<Vec<_>, String>>()?;
+        let mut types = BTreeMap::new();
+        for (id, entry) in value
+            .get("types")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            let optional = |name: &str| string_field(entry, name).ok();
+            let members = match entry.get("members").and_then(Value::as_array) {
+                Some(_members) => Some(
+                    _members
+                        .iter()
+                        .map(StorageVariable::from_json)
+                        .collect::<Result<Vec<_>, String>>()?,
+                ),
+                None => None,
+            };
+            types.insert(
+                id.clone(),
+                StorageType {
+                    encoding: string_field(entry, "encoding")?,
+                    label: string_field(entry, "label")?,
+                    number_of_bytes: string_field(entry, "numberOfBytes")?
+                        .parse()
+                        .map_err(|_| format!("type `{id}` has an invalid size"))?,
+                    base: optional("base"),
+                    key: optional("key"),
+                    value: optional("value"),
+                    members,
+                },
+            );
+        }
+        Ok(StorageLayout { variables, types })
+    }
+}
+
+fn string_field(value: &Value, name: &str) -> Result<String, String> {
+    value
+        .get(name)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or(format!("missing `{name}`"))
+}
@@ -1,13 +1,32 @@
-use crate::mods::constants::constants::{DATA_TYPES, INTEGER_SIZES, KEYWORDS, SYMBOLS};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::Pow;
+
+use crate::mods::{
+    constants::constants::{INTEGER_SIZES, OPERATORS, SYMBOLS},
+    functions::helpers::hash_helper::checksum_address,
+    types::{
+        compiler_errors::{CompilerError, LexicalError},
+        diagnostics::Diagnostics,
+        line_descriptors::{LineDescriptions, Span},
+    },
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     Identifier(String),
+    /* THE VALUE AND, FOR A HEX LITERAL, ITS NUMBER OF DIGITS, WHICH DECIDES THE `bytesN` IT FITS */
+    NumberLiteral(BigInt, Option<usize>),
+    RationalLiteral(BigRational),
+    StringLiteral(String),
+    HexStringLiteral(Vec<u8>),
+    AddressLiteral(String),
     Contract,
     Solidity,
     Library,
     Using,
     Abstract,
+    Upgradable,
     Emit,
     Call,
     Import,
@@ -65,6 +84,7 @@ pub enum Token {
     Fallback,
     Cron,
     Gasless,
+    Assembly,
     Int(Option<u16>),
     String,
     Bool,
@@ -88,135 +108,85 @@ pub enum Token {
     Bang,
     Modulu,
     SemiColon,
-    Quotation,
     Coma,
+    Colon,
+    QuestionMark,
     Or,
     And,
     Xor,
     Not,
     True,
     False,
+    ShiftLeftEquals,
+    ShiftRightEquals,
+    DoubleEquals,
+    NotEquals,
+    LtEquals,
+    GtEquals,
+    DoubleAnd,
+    DoubleOr,
+    Increment,
+    Decrement,
+    PlusEquals,
+    MinusEquals,
+    MultiplyEquals,
+    DivideEquals,
+    ModuluEquals,
+    OrEquals,
+    AndEquals,
+    XorEquals,
+    ShiftLeft,
+    ShiftRight,
+    Power,
+    Arrow,
 }
 
 pub trait TokenTrait {
     fn to_string(&self) -> String;
+    #[allow(dead_code)]
     fn tokenize(input: &str) -> Token;
-    fn lex(input: &str) -> Vec<Token>;
-}
-
-pub trait VecExtension {
-    fn to_string(&self) -> String;
-    fn strip_spaces(&self) -> Self;
 }
 
 pub trait StringExtension {
     fn tokenize(&self) -> Token;
-    fn lex(&self) -> Vec<Token>;
-}
-
-impl VecExtension for Vec<Token> {
-    fn to_string(&self) -> String {
-        let mut stringified = String::new();
-        for _token in self.iter() {
-            stringified.push_str(&_token.to_string())
-        }
-
-        stringified
-    }
-
-    fn strip_spaces(&self) -> Self {
-        let mut result = Vec::new();
-        for token in self.iter() {
-            match token {
-                Token::Space => {}
-                _ => {
-                    result.push(token.to_owned());
-                }
-            }
-        }
-
-        result
-    }
-}
-
-impl VecExtension for Vec<&Token> {
-    fn to_string(&self) -> String {
-        let mut stringified = String::new();
-        for _token in self.iter() {
-            stringified.push_str(&_token.to_string())
-        }
-
-        stringified
-    }
-    fn strip_spaces(&self) -> Self {
-        let mut result = Vec::new();
-        for token in self.iter() {
-            match token {
-                Token::Space => {}
-                _ => {
-                    result.push(token.to_owned());
-                }
-            }
-        }
-
-        result
-    }
 }
 
 impl StringExtension for String {
     fn tokenize(&self) -> Token {
-        tokenize(&self)
-    }
-
-    fn lex(&self) -> Vec<Token> {
-        lex(&self)
+        tokenize(self)
     }
 }
 
 impl StringExtension for &str {
     fn tokenize(&self) -> Token {
-        tokenize(&self)
-    }
-    fn lex(&self) -> Vec<Token> {
-        lex(&self)
+        tokenize(self)
     }
 }
 
 impl StringExtension for char {
     fn tokenize(&self) -> Token {
-        tokenize(&self.to_string().as_str())
-    }
-    fn lex(&self) -> Vec<Token> {
-        lex(&self.to_string().as_str())
+        tokenize(self.to_string().as_str())
     }
 }
 
 impl TokenTrait for Token {
     fn to_string(&self) -> String {
-        detokenize(&self)
+        detokenize(self)
     }
 
     fn tokenize(input: &str) -> Token {
         tokenize(input)
     }
-
-    fn lex(input: &str) -> Vec<Token> {
-        lex(input)
-    }
 }
 
 impl TokenTrait for &Token {
     fn to_string(&self) -> String {
-        detokenize(&self)
+        detokenize(self)
     }
 
     fn tokenize(input: &str) -> Token {
         tokenize(input)
     }
-
-    fn lex(input: &str) -> Vec<Token> {
-        lex(input)
-    }
 }
 
 fn detokenize(input: &Token) -> String {
@@ -236,6 +206,7 @@ fn detokenize(input: &Token) -> String {
         Token::Library => "library".to_string(),
         Token::Solidity => "solidity".to_string(),
         Token::Abstract => "abstract".to_string(),
+        Token::Upgradable => "upgradable".to_string(),
         Token::Indexed => "indexed".to_string(),
         Token::Modifier => "modifier".to_string(),
         Token::Space => " ".to_string(),
@@ -253,6 +224,7 @@ fn detokenize(input: &Token) -> String {
             }
         }
         Token::Revert => "revert".to_string(),
+        Token::Assembly => "assembly".to_string(),
         Token::Storage => "storage".to_string(),
         Token::While => "while".to_string(),
         Token::True => "true".to_string(),
@@ -327,13 +299,41 @@ fn detokenize(input: &Token) -> String {
         Token::Bang => "!".to_string(),
         Token::Modulu => "%".to_string(),
         Token::SemiColon => ";".to_string(),
-        Token::Quotation => "\"".to_string(),
-        Token::Coma => ".to_string(),".to_string(),
+        Token::Coma => ",".to_string(),
+        Token::Colon => ":".to_string(),
+        Token::QuestionMark => "?".to_string(),
         Token::Or => "|".to_string(),
         Token::And => "&".to_string(),
         Token::Not => "~".to_string(),
         Token::Xor => "^".to_string(),
+        Token::ShiftLeftEquals => "<<=".to_string(),
+        Token::ShiftRightEquals => ">>=".to_string(),
+        Token::DoubleEquals => "==".to_string(),
+        Token::NotEquals => "!=".to_string(),
+        Token::LtEquals => "<=".to_string(),
+        Token::GtEquals => ">=".to_string(),
+        Token::DoubleAnd => "&&".to_string(),
+        Token::DoubleOr => "||".to_string(),
+        Token::Increment => "++".to_string(),
+        Token::Decrement => "--".to_string(),
+        Token::PlusEquals => "+=".to_string(),
+        Token::MinusEquals => "-=".to_string(),
+        Token::MultiplyEquals => "*=".to_string(),
+        Token::DivideEquals => "/=".to_string(),
+        Token::ModuluEquals => "%=".to_string(),
+        Token::OrEquals => "|=".to_string(),
+        Token::AndEquals => "&=".to_string(),
+        Token::XorEquals => "^=".to_string(),
+        Token::ShiftLeft => "<<".to_string(),
+        Token::ShiftRight => ">>".to_string(),
+        Token::Power => "**".to_string(),
+        Token::Arrow => "=>".to_string(),
         Token::Identifier(val) => val.to_string(),
+        Token::NumberLiteral(value, _) => value.to_string(),
+        Token::RationalLiteral(value) => value.to_string(),
+        Token::StringLiteral(value) => format!("{:?}", value),
+        Token::HexStringLiteral(value) => format!("hex\"{}\"", hex::encode(value)),
+        Token::AddressLiteral(value) => value.to_string(),
     }
 }
 
@@ -347,6 +347,7 @@ fn tokenize(input: &str) -> Token {
         "from" => Token::From,
         "using" => Token::Using,
         "abstract" => Token::Abstract,
+        "upgradable" => Token::Upgradable,
         "library" => Token::Library,
         "call" => Token::Call,
         "delegatecall" => Token::Delegatecall,
@@ -381,6 +382,7 @@ fn tokenize(input: &str) -> Token {
         "cron" => Token::Cron,
         "enum" => Token::Enum,
         "gasless" => Token::Gasless,
+        "assembly" => Token::Assembly,
         "true" => Token::True,
         "false" => Token::False,
         "address" => Token::Address,
@@ -423,200 +425,482 @@ fn tokenize(input: &str) -> Token {
         "!" => Token::Bang,
         "%" => Token::Modulu,
         ";" => Token::SemiColon,
-        "'" => Token::Quotation,
-        "\"" => Token::Quotation,
         "," => Token::Coma,
+        ":" => Token::Colon,
+        "?" => Token::QuestionMark,
         "|" => Token::Or,
         "&" => Token::And,
         "^" => Token::Xor,
         "~" => Token::Not,
+        "<<=" => Token::ShiftLeftEquals,
+        ">>=" => Token::ShiftRightEquals,
+        "==" => Token::DoubleEquals,
+        "!=" => Token::NotEquals,
+        "<=" => Token::LtEquals,
+        ">=" => Token::GtEquals,
+        "&&" => Token::DoubleAnd,
+        "||" => Token::DoubleOr,
+        "++" => Token::Increment,
+        "--" => Token::Decrement,
+        "+=" => Token::PlusEquals,
+        "-=" => Token::MinusEquals,
+        "*=" => Token::MultiplyEquals,
+        "/=" => Token::DivideEquals,
+        "%=" => Token::ModuluEquals,
+        "|=" => Token::OrEquals,
+        "&=" => Token::AndEquals,
+        "^=" => Token::XorEquals,
+        "<<" => Token::ShiftLeft,
+        ">>" => Token::ShiftRight,
+        "**" => Token::Power,
+        "=>" => Token::Arrow,
 
         _other => process_dyn(_other),
     }
 }
 
 fn process_dyn(input_val: &str) -> Token {
-    if input_val.starts_with("bytes") {
-        let chars = input_val.chars().collect::<Vec<_>>();
-        let next = chars.get("bytes".len());
-        if let Some(_next) = next {
-            if _next.is_ascii_digit() {
-                Token::Bytes(extract_size(input_val))
-            } else {
-                Token::Identifier(input_val.to_string())
-            }
-        } else {
-            Token::Bytes(extract_size(input_val))
+    if let Some(suffix) = input_val.strip_prefix("bytes") {
+        match sized(suffix) {
+            Some(size) => Token::Bytes(size),
+            None => Token::Identifier(input_val.to_string()),
         }
-    } else if input_val.starts_with("uint") {
-        let chars = input_val.chars().collect::<Vec<_>>();
-        let next = chars.get("uint".len());
-        if let Some(_next) = next {
-            if _next.is_ascii_digit() {
-                let size = extract_size(input_val);
-                if let Some(_size) = size {
-                    if INTEGER_SIZES.contains(&_size) {
-                        return Token::Uint(size);
-                    } else {
-                        return Token::Identifier(input_val.to_string());
-                    }
-                }
+    } else if let Some(suffix) = input_val.strip_prefix("uint") {
+        match sized(suffix) {
+            Some(size) if size.is_none_or(|_size| INTEGER_SIZES.contains(&_size)) => {
                 Token::Uint(size)
-            } else {
-                Token::Identifier(input_val.to_string())
             }
-        } else {
-            Token::Uint(extract_size(input_val))
+            _ => Token::Identifier(input_val.to_string()),
         }
-    } else if input_val.starts_with("int") {
-        let chars = input_val.chars().collect::<Vec<_>>();
-        let next = chars.get("int".len());
-        if let Some(_next) = next {
-            if _next.is_ascii_digit() {
-                let size = extract_size(input_val);
-                if let Some(_size) = size {
-                    if INTEGER_SIZES.contains(&_size) {
-                        return Token::Int(size);
-                    } else {
-                        return Token::Identifier(input_val.to_string());
-                    }
-                }
+    } else if let Some(suffix) = input_val.strip_prefix("int") {
+        match sized(suffix) {
+            Some(size) if size.is_none_or(|_size| INTEGER_SIZES.contains(&_size)) => {
                 Token::Int(size)
-            } else {
-                Token::Identifier(input_val.to_string())
             }
-        } else {
-            Token::Int(extract_size(input_val))
+            _ => Token::Identifier(input_val.to_string()),
         }
     } else {
         Token::Identifier(input_val.to_string())
     }
 }
 
-fn extract_size(s: &str) -> Option<u16> {
-    let number_string: String = s.chars().filter(|c| c.is_digit(10)).collect();
-
-    if number_string.is_empty() {
-        None
+/* Some(None) FOR A BARE TYPE NAME, Some(Some(n)) FOR A NUMERIC SUFFIX, None OTHERWISE */
+fn sized(suffix: &str) -> Option<Option<u16>> {
+    if suffix.is_empty() {
+        Some(None)
+    } else if suffix.chars().all(|c| c.is_ascii_digit()) && !suffix.starts_with('0') {
+        suffix.parse::<u16>().ok().map(Some)
     } else {
-        number_string.parse::<u16>().ok()
+        None
     }
 }
 
-fn lex(input: &str) -> Vec<Token> {
-    let mut combined_strings: Vec<String> = Vec::new();
-    let mut combined_char = String::new();
-    let mut lexems: Vec<Token> = Vec::new();
-    let mut quote = String::new();
+/* SINGLE PASS LEXER OVER A WHOLE SOURCE BUFFER. COMMENTS AND WHITESPACE ARE SKIPPED SO THE
+TOKEN STREAM DOES NOT DEPEND ON HOW THE SOURCE IS WRAPPED. ERRORS ARE RECORDED AND LEXING
+CARRIES ON SO THAT LATER MISTAKES ARE REPORTED TOO */
+pub fn lex(
+    source: &str,
+    file_id: usize,
+    diagnostics: &mut Diagnostics,
+) -> Vec<LineDescriptions<Token>> {
+    let mut lexer = Lexer {
+        source,
+        file_id,
+        diagnostics,
+        position: 0,
+        line: 1,
+        column: 1,
+    };
+    let mut lexems = Vec::new();
+    while let Some(lexem) = lexer.next_token() {
+        lexems.push(lexem);
+    }
+    lexems
+}
 
-    let mut opened_quote = false;
-    let mut data_type_pause = false;
+struct Lexer<'a> {
+    source: &'a str,
+    file_id: usize,
+    diagnostics: &'a mut Diagnostics,
+    position: usize,
+    line: i32,
+    column: i32,
+}
 
-    for (index, character) in input.trim().char_indices() {
-        if character.is_whitespace() {
-            if !combined_char.trim().is_empty() {
-                if opened_quote {
-                    combined_char.push(character)
-                } else {
-                    combined_strings.push(combined_char.trim().to_string());
-                    combined_char.clear();
+impl<'a> Lexer<'a> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.source[self.position..].chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.position += character.len_utf8();
+        if character == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(character)
+    }
+
+    fn eat_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while let Some(character) = self.peek() {
+            if !predicate(character) {
+                break;
+            }
+            self.bump();
+        }
+    }
+
+    fn span_from(&self, start: usize, line: i32, column: i32) -> Span {
+        Span {
+            file_id: self.file_id,
+            start,
+            end: self.position,
+            line,
+            column,
+        }
+    }
+
+    fn report(&mut self, lex_error: LexicalError, span: Span) {
+        self.diagnostics
+            .push(CompilerError::LexicalError(lex_error).at(span));
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            match (self.peek(), self.peek_nth(1)) {
+                (Some(character), _) if character.is_whitespace() => {
+                    self.bump();
                 }
-            } else {
-                let chars = input.trim().chars().collect::<Vec<_>>();
-                let next = chars.get(index + 1);
-                if let Some(_next) = next {
-                    if !_next.is_whitespace() {
-                        if opened_quote {
-                            combined_char.push(character)
-                        } else {
-                            assert!(combined_char.trim().is_empty(), "Missing quotation");
-                            combined_strings.push(character.to_string());
+                (Some('/'), Some('/')) => self.eat_while(|character| character != '\n'),
+                (Some('/'), Some('*')) => {
+                    let (start, line, column) = (self.position, self.line, self.column);
+                    self.bump();
+                    self.bump();
+                    loop {
+                        match (self.peek(), self.peek_nth(1)) {
+                            (Some('*'), Some('/')) => {
+                                self.bump();
+                                self.bump();
+                                break;
+                            }
+                            (Some(_), _) => {
+                                self.bump();
+                            }
+                            (None, _) => {
+                                let span = self.span_from(start, line, column);
+                                self.report(LexicalError::UnexpectedEndOfFile, span);
+                                break;
+                            }
                         }
-                        continue;
                     }
                 }
-                if opened_quote {
-                    combined_char.push(character);
+                _ => break,
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Option<LineDescriptions<Token>> {
+        self.skip_trivia();
+        let (start, line, column) = (self.position, self.line, self.column);
+        let character = self.peek()?;
+
+        let token = if is_identifier_start(character) {
+            self.eat_while(is_identifier_part);
+            let word = &self.source[start..self.position];
+            if (word == "hex" || word == "unicode") && matches!(self.peek(), Some('"' | '\'')) {
+                let body = self.eat_string(start, line, column);
+                if word == "hex" {
+                    hex_literal(body).map(Token::HexStringLiteral)
                 } else {
-                    continue;
+                    string_literal(body, true).map(Token::StringLiteral)
                 }
+            } else {
+                Ok(word.tokenize())
             }
+        } else if character.is_ascii_digit()
+            || (character == '.' && self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()))
+        {
+            self.eat_number();
+            let raw = &self.source[start..self.position];
+            number_literal(raw).and_then(|_token| match self.unit_suffix() {
+                None => Ok(_token),
+                Some(_) if raw.starts_with("0x") => Err(LexicalError::InvalidNumberLiteral),
+                Some(multiplier) => match _token {
+                    Token::NumberLiteral(value, _) => {
+                        Ok(Token::NumberLiteral(value * multiplier, None))
+                    }
+                    Token::RationalLiteral(value) => Ok(rational_token(
+                        value * BigRational::from(BigInt::from(multiplier)),
+                    )),
+                    _ => Err(LexicalError::InvalidNumberLiteral),
+                },
+            })
         } else if character == '"' || character == '\'' {
-            combined_char.push(character);
-            if opened_quote && quote == character.to_string() {
-                opened_quote = false;
-                quote.clear();
-                combined_strings.push(combined_char.trim().to_string());
-                combined_char.clear();
-            } else {
-                opened_quote = true;
-                quote = character.to_string();
+            let body = self.eat_string(start, line, column);
+            string_literal(body, false).map(Token::StringLiteral)
+        } else if let Some(operator) = OPERATORS
+            .iter()
+            .find(|operator| self.source[self.position..].starts_with(**operator))
+        {
+            for _ in 0..operator.len() {
+                self.bump();
             }
+            Ok(operator.tokenize())
         } else if SYMBOLS.contains(&character) {
-            if data_type_pause {
-                combined_strings.push(combined_char.trim().to_string());
-                combined_char.clear();
-                combined_strings.push(character.to_string());
+            self.bump();
+            Ok(character.tokenize())
+        } else {
+            self.bump();
+            Err(LexicalError::InvalidCharacter(character))
+        };
+
+        /* MALFORMED LITERALS KEEP THEIR PLACE IN THE STREAM WITH A PLACEHOLDER VALUE */
+        let token = match token {
+            Ok(token) => token,
+            Err(lex_error) => {
+                self.report(lex_error, self.span_from(start, line, column));
+                match character {
+                    '0'..='9' | '.' => Token::NumberLiteral(BigInt::from(0u8), None),
+                    '"' | '\'' | 'u' => Token::StringLiteral(String::new()),
+                    'h' => Token::HexStringLiteral(Vec::new()),
+                    _ => return self.next_token(),
+                }
+            }
+        };
 
-                data_type_pause = false;
-            } else {
-                if opened_quote {
-                    combined_char.push(character);
-                } else {
-                    if combined_char.trim().is_empty() {
-                        combined_strings.push(character.to_string())
-                    } else {
-                        combined_strings.push(combined_char.trim().to_string());
-                        combined_char.clear();
-                        combined_strings.push(character.to_string().trim().to_string())
-                    }
+        Some(LineDescriptions {
+            span: self.span_from(start, line, column),
+            data: token,
+        })
+    }
+
+    fn eat_number(&mut self) {
+        if self.peek() == Some('0') && matches!(self.peek_nth(1), Some('x' | 'X')) {
+            self.bump();
+            self.bump();
+            self.eat_while(|character| character.is_ascii_hexdigit() || character == '_');
+        } else {
+            self.eat_while(|character| character.is_ascii_digit() || character == '_');
+            if self.peek() == Some('.') && self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) {
+                self.bump();
+                self.eat_while(|character| character.is_ascii_digit() || character == '_');
+            }
+            let exponent_digit = match self.peek_nth(1) {
+                Some('-') => self.peek_nth(2),
+                next => next,
+            };
+            if matches!(self.peek(), Some('e' | 'E'))
+                && exponent_digit.is_some_and(|c| c.is_ascii_digit())
+            {
+                self.bump();
+                if self.peek() == Some('-') {
+                    self.bump();
                 }
+                self.eat_while(|character| character.is_ascii_digit() || character == '_');
             }
-        } else if DATA_TYPES.contains(&format!("{}{}", combined_char.trim(), character).as_str()) {
-            let chars = input.trim().chars().collect::<Vec<_>>();
-            let next = chars.get(index + 1);
-            if let Some(_next) = next {
-                if SYMBOLS.contains(_next) || _next.is_whitespace() {
-                    combined_strings.push(format!("{}{}", combined_char.trim(), character));
-                    combined_char.clear();
-                } else {
-                    data_type_pause = true;
-                    combined_char.push(character)
+        }
+        /* KEEP TRAILING IDENTIFIER CHARACTERS IN THE SAME TOKEN SO `1abc` IS REPORTED AS A WHOLE */
+        self.eat_while(is_identifier_part);
+    }
+
+    /* CONSUMES A UNIT DENOMINATION FOLLOWING A NUMBER AND RETURNS ITS MULTIPLIER */
+    fn unit_suffix(&mut self) -> Option<u64> {
+        let (position, line, column) = (self.position, self.line, self.column);
+        self.eat_while(char::is_whitespace);
+        let start = self.position;
+        self.eat_while(is_identifier_part);
+        let multiplier = match &self.source[start..self.position] {
+            "wei" => Some(1),
+            "gwei" => Some(1_000_000_000),
+            "ether" => Some(1_000_000_000_000_000_000),
+            "seconds" => Some(1),
+            "minutes" => Some(60),
+            "hours" => Some(3_600),
+            "days" => Some(86_400),
+            "weeks" => Some(604_800),
+            "years" => Some(31_536_000),
+            _ => None,
+        };
+        if multiplier.is_none() {
+            (self.position, self.line, self.column) = (position, line, column);
+        }
+        multiplier
+    }
+
+    /* CONSUMES A QUOTED STRING, SKIPPING OVER ESCAPED CHARACTERS, AND RETURNS ITS RAW BODY */
+    fn eat_string(&mut self, start: usize, line: i32, column: i32) -> &'a str {
+        let quote = self.bump();
+        let body_start = self.position;
+        loop {
+            match self.peek() {
+                Some('\\') => {
+                    self.bump();
+                    self.bump();
+                }
+                Some(character) if Some(character) == quote => {
+                    self.bump();
+                    return &self.source[body_start..self.position - 1];
                 }
+                Some('\n') | None => {
+                    let span = self.span_from(start, line, column);
+                    self.report(LexicalError::UnterminatedString, span);
+                    return &self.source[body_start..self.position];
+                }
+                Some(_) => {
+                    self.bump();
+                }
+            }
+        }
+    }
+}
+
+/* LARGEST DECIMAL EXPONENT ACCEPTED IN A LITERAL SUCH AS 1e18 */
+const MAX_EXPONENT: u32 = 4096;
+
+fn number_literal(raw: &str) -> Result<Token, LexicalError> {
+    if let Some(digits) = raw.strip_prefix("0x") {
+        if digits.is_empty()
+            || !digits.chars().all(|c| c.is_ascii_hexdigit() || c == '_')
+            || !valid_underscores(digits)
+        {
+            return Err(LexicalError::InvalidNumberLiteral);
+        }
+        if digits.len() == 40 {
+            let expected = checksum_address(digits);
+            return if expected[2..] == *digits {
+                Ok(Token::AddressLiteral(expected))
             } else {
-                combined_strings.push(format!("{}{}", combined_char.trim(), character));
-                combined_char.clear();
+                Err(LexicalError::InvalidAddressChecksum(expected))
+            };
+        }
+        let digits = digits.replace('_', "");
+        return BigInt::parse_bytes(digits.as_bytes(), 16)
+            .map(|value| Token::NumberLiteral(value, Some(digits.len())))
+            .ok_or(LexicalError::InvalidNumberLiteral);
+    }
+
+    let (mantissa, exponent) = match raw.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (raw, None),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let exponent_digits =
+        exponent.map(|_exponent| _exponent.strip_prefix('-').unwrap_or(_exponent));
+    for part in [integer, fraction].into_iter().chain(exponent_digits) {
+        if !part.chars().all(|c| c.is_ascii_digit() || c == '_') || !valid_underscores(part) {
+            return Err(LexicalError::InvalidNumberLiteral);
+        }
+    }
+    /* OCTAL LOOKING LITERALS SUCH AS 0123 ARE REJECTED */
+    if integer.len() > 1 && integer.starts_with('0') {
+        return Err(LexicalError::InvalidNumberLiteral);
+    }
+
+    let fraction = fraction.replace('_', "");
+    let numerator = BigInt::parse_bytes(
+        format!("{integer}{fraction}").replace('_', "").as_bytes(),
+        10,
+    )
+    .ok_or(LexicalError::InvalidNumberLiteral)?;
+    let mut value = BigRational::new(numerator, BigInt::from(10u8).pow(fraction.len() as u32));
+
+    if let Some(_exponent) = exponent {
+        let magnitude = exponent_digits
+            .unwrap_or_default()
+            .replace('_', "")
+            .parse::<u32>()
+            .ok()
+            .filter(|magnitude| *magnitude <= MAX_EXPONENT)
+            .ok_or(LexicalError::InvalidNumberLiteral)?;
+        let scale = BigRational::from(BigInt::from(10u8).pow(magnitude));
+        value = if _exponent.starts_with('-') {
+            value / scale
+        } else {
+            value * scale
+        };
+    }
+    Ok(rational_token(value))
+}
+
+fn rational_token(value: BigRational) -> Token {
+    if value.is_integer() {
+        Token::NumberLiteral(value.to_integer(), None)
+    } else {
+        Token::RationalLiteral(value)
+    }
+}
+
+/* UNDERSCORES MAY ONLY SEPARATE DIGITS */
+fn valid_underscores(digits: &str) -> bool {
+    !digits.starts_with('_') && !digits.ends_with('_') && !digits.contains("__")
+}
+
+/* RESOLVES ESCAPE SEQUENCES. PLAIN STRINGS ARE ASCII ONLY, unicode"..." ACCEPTS ANY CHARACTER */
+fn string_literal(body: &str, unicode: bool) -> Result<String, LexicalError> {
+    let mut value = String::with_capacity(body.len());
+    let mut characters = body.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            if !unicode && !character.is_ascii() {
+                return Err(LexicalError::InvalidCharacter(character));
             }
-        } else if KEYWORDS.contains(&format!("{}{}", combined_char.trim(), character).as_str()) {
-            let chars = input.trim().chars().collect::<Vec<_>>();
-            let next = chars.get(index + 1);
-            if let Some(_next) = next {
-                if !_next.is_whitespace() && _next.is_alphabetic() {
-                    combined_char.push(character);
-                } else {
-                    combined_strings.push(format!("{}{}", combined_char.trim(), character));
-                    combined_char.clear();
+            value.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some(quoted @ ('\\' | '\'' | '"')) => value.push(quoted),
+            /* A BACKSLASH BEFORE A LINE BREAK CONTINUES THE STRING ON THE NEXT LINE */
+            Some('\n') => {}
+            Some('x') => {
+                let code = hex_escape(&mut characters, 2)?;
+                /* BYTES ABOVE 0x7f WOULD NOT BE VALID UTF-8 */
+                if code > 0x7f {
+                    return Err(LexicalError::InvalidEscapeSequence);
                 }
-            } else {
-                combined_strings.push(format!("{}{}", combined_char.trim(), character));
-                combined_char.clear();
+                value.push(code as u8 as char);
             }
-        } else {
-            combined_char.push(character);
-            if index == input.trim().len() - 1 {
-                combined_strings.push(combined_char.trim().to_string());
-                combined_char.clear();
+            Some('u') => {
+                let code = hex_escape(&mut characters, 4)?;
+                value.push(char::from_u32(code).ok_or(LexicalError::InvalidEscapeSequence)?);
             }
+            _ => return Err(LexicalError::InvalidEscapeSequence),
         }
     }
+    Ok(value)
+}
 
-    assert!(combined_char.is_empty(), "Internal Error");
+fn hex_escape(characters: &mut std::str::Chars, length: usize) -> Result<u32, LexicalError> {
+    let digits: String = characters.take(length).collect();
+    if digits.len() != length || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(LexicalError::InvalidEscapeSequence);
+    }
+    u32::from_str_radix(&digits, 16).map_err(|_| LexicalError::InvalidEscapeSequence)
+}
 
-    for combined_string in combined_strings {
-        lexems.push(combined_string.tokenize())
+/* hex"00ff" MAY SEPARATE BYTES WITH SINGLE UNDERSCORES SUCH AS hex"00_ff" */
+fn hex_literal(body: &str) -> Result<Vec<u8>, LexicalError> {
+    if !valid_underscores(body) || body.split('_').any(|_bytes| _bytes.len() % 2 != 0) {
+        return Err(LexicalError::InvalidHexLiteral);
     }
+    hex::decode(body.replace('_', "")).map_err(|_| LexicalError::InvalidHexLiteral)
+}
 
-    lexems
+fn is_identifier_start(character: char) -> bool {
+    character.is_ascii_alphabetic() || character == '_' || character == '$'
+}
+
+fn is_identifier_part(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_' || character == '$'
 }
 
 #[derive(PartialEq)]
@@ -627,5 +911,9 @@ pub enum Context {
     Header,
     Contract,
     Error,
+    /* A STRUCT, ENUM, CONSTANT OR FUNCTION DECLARED OUTSIDE ANY CONTRACT */
+    Definition,
+    /* TOKENS DROPPED AFTER AN UNEXPECTED ONE, UP TO THE NEXT `;` OR BALANCED `}` */
+    Skip,
     None,
 }
@@ -0,0 +1,30 @@
+// emit: abi.json
+pragma solidity ^0.8.20;
+
+library T {
+    struct P {
+        uint256 x;
+    }
+
+    function li(P storage p) public view returns (uint256) {
+        return p.x;
+    }
+
+    function many(mapping(address => uint256) storage balances, uint256[] storage list, P memory point)
+        public
+        view
+        returns (uint256)
+    {
+        return balances[address(0)] + list.length + point.x;
+    }
+}
+
+contract Callbacks {
+    function apply(function (uint256) external view returns (bool) check, uint256 value)
+        external
+        view
+        returns (bool)
+    {
+        return value > 0;
+    }
+}
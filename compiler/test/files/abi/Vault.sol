@@ -0,0 +1,59 @@
+// emit: abi.json
+pragma solidity ^0.8.20;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+}
+
+error Unauthorized(address caller);
+
+contract Base {
+    event Deposited(address indexed owner, uint256 amount);
+
+    function deposit() public payable virtual {
+        emit Deposited(msg.sender, msg.value);
+    }
+}
+
+contract Vault is Base {
+    enum Kind {
+        Locked,
+        Open
+    }
+
+    struct Position {
+        uint128 amount;
+        Kind kind;
+        uint256[] history;
+    }
+
+    event Moved(address indexed from, address indexed to, Position position) anonymous;
+
+    error TooLarge(uint256 amount, uint256 limit);
+
+    mapping(address => Position) public positions;
+    IERC20 public immutable token;
+    address payable[2] public admins;
+
+    constructor(IERC20 _token) {
+        token = _token;
+    }
+
+    function deposit() public payable override {
+        if (msg.value > 100) {
+            revert TooLarge(msg.value, 100);
+        }
+        emit Deposited(msg.sender, msg.value);
+    }
+
+    function withdraw(uint128 amount) external {
+        if (positions[msg.sender].amount < amount) {
+            revert Unauthorized(msg.sender);
+        }
+        positions[msg.sender].amount -= amount;
+    }
+
+    receive() external payable {}
+
+    fallback() external {}
+}
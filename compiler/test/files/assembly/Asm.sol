@@ -0,0 +1,17 @@
+pragma solidity ^0.8.20;
+
+contract Asm {
+    uint256 stored;
+
+    function f(uint256 n) public returns (uint256 r) {
+        assembly ("memory-safe") {
+            function double(x) -> y { y := add(x, x) }
+            for { let i := 0 } lt(i, n) { i := add(i, 1) } {
+                r := double(r)
+            }
+            switch r
+            case 0 { sstore(stored.slot, 1) }
+            default { r := 0x20 }
+        }
+    }
+}
@@ -0,0 +1,17 @@
+pragma solidity ^0.8.20;
+
+contract Asm2 {
+    uint256 stored;
+
+    function f(uint256 a) public returns (uint256 r) {
+        assembly {
+            let x := add(a)
+            mstore(0, 1)
+            add(1, 2)
+            r := stored
+            stored.slot := 1
+            break
+            switch a case 1 {} case 1 {}
+        }
+    }
+}
@@ -0,0 +1,18 @@
+pragma solidity ^0.8.20;
+
+contract AsmShadow {
+    function f(uint256 a) public pure returns (uint256 r) {
+        assembly {
+            let x := 1
+            {
+                let x := 2
+            }
+            let a := 3
+            function g(y) -> z {
+                let x := y
+                z := x
+            }
+            r := g(x)
+        }
+    }
+}
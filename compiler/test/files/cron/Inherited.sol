@@ -0,0 +1,39 @@
+pragma solidity ^0.8.20;
+
+abstract contract Base {
+    uint256 public base;
+
+    cron("* * * * *", 1) {
+        base += 1;
+    }
+}
+
+contract Middle is Base {
+    uint256 public middle;
+
+    cron("0 * * * *", 1) {
+        middle += 1;
+    }
+}
+
+contract Derived is Middle {
+    uint256 public derived;
+
+    cron("* * * * *") {
+        derived += 1;
+    }
+}
+// ----
+// deploy Derived:
+// time 1700000040
+// call __cron_0:
+// call __cron_1:
+// call __cron_2:
+// time 1700002800
+// call __cron_0:
+// call __cron_1:
+// call __cron_1:
+// call __cron_2:
+// static base:
+// static middle:
+// static derived:
@@ -0,0 +1,57 @@
+pragma solidity ^0.8.20;
+
+contract Clock {
+    uint256 public ticks;
+    uint256 public resets;
+    uint256 public leaps;
+    uint256 public mornings;
+
+    cron("*/15 9-17 * * 1-5") {
+        ticks += 1;
+    }
+
+    cron("0 0 1,15 */3 *", 2) {
+        resets += 1;
+    }
+
+    cron("30 12 29 2 *") {
+        leaps += 1;
+    }
+
+    cron("0 8 13 * 5") {
+        mornings += 1;
+    }
+}
+// ----
+// deploy Clock:
+// call __cron_0:
+// time 1699952400
+// call __cron_0:
+// call __cron_0:
+// call __cron_0{value: 1}:
+// time 1699952700
+// call __cron_0:
+// time 1699953300
+// call __cron_0:
+// time 1700298000
+// call __cron_0:
+// static ticks:
+// time 1696118400
+// call __cron_1:
+// time 1698796800
+// call __cron_1:
+// time 1697328000
+// call __cron_1:
+// time 1704067200
+// call __cron_1:
+// static resets:
+// time 1709209800
+// call __cron_2:
+// static leaps:
+// time 1699948800
+// call __cron_3:
+// time 1699862400
+// call __cron_3:
+// time 1700208000
+// call __cron_3:
+// static mornings:
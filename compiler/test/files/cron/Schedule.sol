@@ -0,0 +1,19 @@
+// emit: cron.json
+pragma solidity ^0.8.20;
+
+contract Schedule {
+    uint256 constant RUNS = 3;
+    uint256 public ticks;
+
+    cron("*/15 9-17 * * 1-5") {
+        ticks += 1;
+    }
+
+    cron("0 0 1,15 */3 *", RUNS * 4) {
+        ticks = 0;
+    }
+}
+
+contract Idle {
+    function f() public pure {}
+}
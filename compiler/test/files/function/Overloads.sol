@@ -0,0 +1,15 @@
+pragma solidity ^0.8.20;
+
+contract Overloads {
+    function f(uint8 x) internal pure returns (uint256) {
+        return x;
+    }
+
+    function f(uint256 x) internal pure returns (uint256) {
+        return x;
+    }
+
+    function g() public pure returns (uint256) {
+        return f(1);
+    }
+}
@@ -0,0 +1,18 @@
+pragma solidity ^0.8.20;
+
+contract Base {
+    function f() public virtual {}
+}
+
+contract Redeclared is Base {
+    event E(uint256);
+    event E(uint256 indexed value);
+    event E(uint8);
+
+    function f() public override {}
+    function f() public {}
+
+    function g(uint x) public {}
+    function g(uint256 y) public {}
+    function g(uint8 x) public {}
+}
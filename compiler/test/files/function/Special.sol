@@ -0,0 +1,17 @@
+pragma solidity ^0.8.20;
+
+contract Special {
+    receive() external {}
+
+    fallback(bytes calldata input) public view returns (bytes memory) {
+        return input;
+    }
+}
+
+contract Valid {
+    receive() external payable {}
+
+    fallback(bytes calldata input) external returns (bytes memory) {
+        return input;
+    }
+}
@@ -0,0 +1,28 @@
+// emit: relayer.json
+pragma solidity ^0.8.20;
+
+contract Base {
+    mapping(address => uint256) public votes;
+
+    function vote(uint256 proposal) external gasless {
+        votes[msg.sender] = proposal;
+    }
+}
+
+contract Relayer is Base {
+    struct Point {
+        uint256 x;
+        uint256 y;
+    }
+
+    Point[] public points;
+
+    function mark(Point calldata point, bytes32 tag) public gasless {
+        points.push(point);
+        votes[msg.sender] = uint256(tag);
+    }
+
+    function total() public view returns (uint256) {
+        return points.length;
+    }
+}
@@ -0,0 +1,46 @@
+==== Source: Main.sol ====
+pragma solidity ^0.8.20;
+
+import {Counter as Base, LIMIT as MAX} from "./Counter.sol";
+import * as Shapes from "./Shapes.sol";
+import "./Shapes.sol" as Geometry;
+
+contract Main is Base {
+    function area(uint256 width, uint256 height) public pure returns (uint256) {
+        Shapes.Rect memory rect = Shapes.Rect(width, height);
+        return Geometry.area(rect);
+    }
+
+    function limit() public pure returns (uint256) {
+        return MAX;
+    }
+}
+==== Source: Counter.sol ====
+pragma solidity ^0.8.20;
+
+uint256 constant LIMIT = 100;
+
+contract Counter {
+    uint256 public count;
+
+    function increment() public {
+        count += 1;
+    }
+}
+==== Source: Shapes.sol ====
+pragma solidity ^0.8.20;
+
+struct Rect {
+    uint256 width;
+    uint256 height;
+}
+
+function area(Rect memory rect) pure returns (uint256) {
+    return rect.width * rect.height;
+}
+// ----
+// deploy Main:
+// call increment:
+// static count:
+// static area: 3, 4
+// static limit:
@@ -0,0 +1,32 @@
+==== Source: Token.sol ====
+pragma solidity ^0.8.20;
+
+import "./Ledger.sol";
+
+uint256 constant SUPPLY = 1000;
+
+contract Token is Ledger {
+    constructor() {
+        credit(msg.sender, SUPPLY);
+    }
+}
+==== Source: Ledger.sol ====
+pragma solidity ^0.8.20;
+
+import {SUPPLY} from "./Token.sol";
+
+contract Ledger {
+    mapping(address => uint256) public balances;
+
+    function credit(address account, uint256 amount) internal {
+        require(amount <= SUPPLY, "over supply");
+        balances[account] += amount;
+    }
+
+    function supply() public pure returns (uint256) {
+        return SUPPLY;
+    }
+}
+// ----
+// deploy Token:
+// static supply:
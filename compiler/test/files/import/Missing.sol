@@ -0,0 +1,16 @@
+==== Source: Main.sol ====
+pragma solidity ^0.8.20;
+
+import {Counter, Missing} from "./Counter.sol";
+import {Other as Counter} from "./Other.sol";
+import "./Absent.sol";
+
+contract Main is Counter {}
+==== Source: Counter.sol ====
+pragma solidity ^0.8.20;
+
+contract Counter {}
+==== Source: Other.sol ====
+pragma solidity ^0.8.20;
+
+contract Other {}
@@ -0,0 +1,34 @@
+==== Source: app/Main.sol ====
+pragma solidity ^0.8.20;
+
+import "./math/Math.sol";
+import {Scale} from "../shared/Scale.sol";
+
+contract Main is Scale {
+    function run(uint256 x) public pure returns (uint256) {
+        return scale(Math.square(x));
+    }
+}
+==== Source: app/math/Math.sol ====
+pragma solidity ^0.8.20;
+
+library Math {
+    function square(uint256 x) internal pure returns (uint256) {
+        return x * x;
+    }
+}
+==== Source: shared/Scale.sol ====
+pragma solidity ^0.8.20;
+
+import "../app/math/Math.sol";
+
+contract Scale {
+    uint256 constant FACTOR = 10;
+
+    function scale(uint256 x) internal pure returns (uint256) {
+        return Math.square(1) * x * FACTOR;
+    }
+}
+// ----
+// deploy Main:
+// static run: 3
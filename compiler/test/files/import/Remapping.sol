@@ -0,0 +1,36 @@
+// flags: @vendor/=lib/vendor/src/
+==== Source: Main.sol ====
+pragma solidity ^0.8.20;
+
+import {Ownable} from "@vendor/access/Ownable.sol";
+
+contract Main is Ownable {
+    uint256 public value;
+
+    function set(uint256 _value) public onlyOwner {
+        value = _value;
+    }
+}
+==== Source: lib/vendor/src/access/Ownable.sol ====
+pragma solidity ^0.8.20;
+
+import "../utils/Errors.sol";
+
+contract Ownable {
+    address public owner = msg.sender;
+
+    modifier onlyOwner() {
+        if (msg.sender != owner) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+}
+==== Source: lib/vendor/src/utils/Errors.sol ====
+pragma solidity ^0.8.20;
+
+error Unauthorized(address caller);
+// ----
+// deploy Main:
+// call set: 7
+// static value:
@@ -0,0 +1,7 @@
+pragma solidity ^0.8.20;
+
+contract X {}
+
+contract A is X {}
+
+contract C is A, X {}
@@ -0,0 +1,22 @@
+// emit: storage.json
+pragma solidity ^0.8.20;
+
+contract A {
+    uint256 a;
+}
+
+contract B is A {
+    uint256 b;
+}
+
+contract C is A {
+    uint256 c;
+}
+
+contract E {
+    uint256 e;
+}
+
+contract D is E, B, C {
+    uint256 d;
+}
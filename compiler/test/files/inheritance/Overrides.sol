@@ -0,0 +1,37 @@
+pragma solidity ^0.8.20;
+
+contract Base {
+    function fixed() public pure returns (uint256) {
+        return 1;
+    }
+
+    function open() public virtual returns (uint256) {
+        return 2;
+    }
+}
+
+contract Other {
+    function open() public virtual returns (uint256) {
+        return 3;
+    }
+}
+
+contract Child is Base, Other {
+    function fixed() public pure returns (uint256) {
+        return 4;
+    }
+
+    function open() public override(Base) returns (uint256) {
+        return 5;
+    }
+}
+
+contract Missing is Base {
+    function open() public returns (uint256) {
+        return 6;
+    }
+}
+
+contract Unimplemented {
+    function todo() public virtual;
+}
@@ -0,0 +1,21 @@
+pragma solidity ^0.8.20;
+
+contract Base {
+    function open() public virtual {}
+
+    function hidden() private {}
+}
+
+contract Derived is Base {
+    function open() public override {
+        super.open();
+        super.hidden();
+        super.missing();
+    }
+}
+
+contract Alone {
+    function f() public {
+        super.f();
+    }
+}
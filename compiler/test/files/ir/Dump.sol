@@ -0,0 +1,11 @@
+// emit: ir
+pragma solidity ^0.8.20;
+
+contract Dump {
+    uint8 public count;
+
+    function bump(uint8 by) public returns (uint8) {
+        count += by;
+        return count;
+    }
+}
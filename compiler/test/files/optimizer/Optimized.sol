@@ -0,0 +1,21 @@
+// flags: --optimize
+// emit: ir
+pragma solidity ^0.8.20;
+
+contract Scaled {
+    uint256 public total;
+
+    function add(uint256 x) public returns (uint256) {
+        total += scale(x);
+        return total;
+    }
+
+    function scale(uint256 x) internal pure returns (uint256) {
+        return x * (2 * 3);
+    }
+}
+// ----
+// deploy Scaled:
+// call add: 1
+// call add: 2
+// static total:
@@ -0,0 +1,46 @@
+// flags: --optimize
+pragma solidity ^0.8.20;
+
+contract Runtime {
+    uint256[] public values;
+    mapping(address => uint256) public balances;
+    uint8 public small;
+
+    function push(uint256 count) public {
+        for (uint256 i = 0; i < count; i++) {
+            values.push(square(i) + 1);
+        }
+    }
+
+    function sum() public view returns (uint256 total) {
+        for (uint256 i = 0; i < values.length; i++) {
+            total += values[i];
+        }
+    }
+
+    function deposit() public payable {
+        balances[msg.sender] += msg.value;
+        balances[msg.sender] += 0;
+    }
+
+    function bump(uint8 by) public returns (uint8) {
+        small += by;
+        small = small;
+        return small;
+    }
+
+    function square(uint256 x) internal pure returns (uint256) {
+        return x * x;
+    }
+}
+// ----
+// deploy Runtime:
+// call push: 4
+// static sum:
+// static values: 3
+// call deposit{value: 5}:
+// call deposit{value: 7}:
+// static balances: @sender
+// call bump: 200
+// call bump: 100
+// static small:
@@ -0,0 +1,143 @@
+pragma solidity ^0.8.20;
+
+contract Asm {
+    uint256 public stored;
+    uint128 public low;
+    uint128 public high;
+    uint256 constant K = 7;
+    mapping(uint256 => uint256) public table;
+
+    function add(uint256 a, uint256 b) public pure returns (uint256 r) {
+        assembly {
+            r := add(a, b)
+        }
+    }
+
+    function sum(uint256 n) public pure returns (uint256 total) {
+        assembly {
+            for { let i := 0 } lt(i, n) { i := add(i, 1) } {
+                if eq(i, 5) { continue }
+                if gt(i, 8) { break }
+                total := add(total, i)
+            }
+        }
+    }
+
+    function fib(uint256 n) public pure returns (uint256 r) {
+        assembly {
+            function f(x) -> y {
+                if lt(x, 2) {
+                    y := x
+                    leave
+                }
+                y := add(f(sub(x, 1)), f(sub(x, 2)))
+            }
+            r := f(n)
+        }
+    }
+
+    function classify(uint256 x) public pure returns (uint256 r) {
+        assembly {
+            switch x
+            case 0 { r := 100 }
+            case 1 { r := 200 }
+            case "ab" { r := 300 }
+            default { r := mul(x, K) }
+        }
+    }
+
+    function slots() public view returns (uint256 a, uint256 b, uint256 c, uint256 d) {
+        assembly {
+            a := stored.slot
+            b := high.slot
+            c := high.offset
+            d := table.slot
+        }
+    }
+
+    function write(uint256 v) public {
+        assembly {
+            sstore(stored.slot, v)
+            let s := sload(low.slot)
+            s := or(shl(128, 3), 9)
+            sstore(low.slot, s)
+        }
+    }
+
+    function pair() public pure returns (uint256 a, uint256 b) {
+        assembly {
+            function two() -> x, y { x := 11 y := 22 }
+            a, b := two()
+            let p, q := two()
+            a := add(a, q)
+        }
+    }
+
+    function ret() public pure returns (uint256) {
+        assembly {
+            mstore(0, 0x1234)
+            return(0, 32)
+        }
+    }
+
+    function boom() public pure {
+        assembly {
+            mstore(0, 0xdead)
+            revert(0, 32)
+        }
+    }
+
+    function emitLog() public {
+        assembly {
+            log2(0, 0, 0x11, caller())
+        }
+    }
+
+    function order() public returns (uint256 r) {
+        assembly {
+            function bump() -> v { sstore(5, add(sload(5), 1)) v := sload(5) }
+            r := add(mul(bump(), 10), sload(5))
+            pop(bump())
+        }
+    }
+
+    function storagePtr() public returns (uint256 s, uint256 o) {
+        uint256[] storage arr = nums;
+        assembly {
+            s := arr.slot
+            o := arr.offset
+            sstore(arr.slot, 3)
+        }
+        s = s + nums.length;
+    }
+    uint256[] nums;
+
+    function mem() public pure returns (bytes32 r) {
+        bytes memory b = "hello";
+        assembly {
+            r := mload(add(b, 32))
+        }
+    }
+}
+// ----
+// deploy Asm
+// static add: 3, 4
+// static sum: 20
+// static fib: 10
+// static classify: 0
+// static classify: 1
+// static classify: 0x6162000000000000000000000000000000000000000000000000000000000000
+// static classify: 6
+// static slots:
+// call write: 77
+// static stored:
+// static low:
+// static high:
+// static pair:
+// static ret:
+// static boom:
+// call emitLog:
+// call order:
+// call order:
+// call storagePtr:
+// static mem:
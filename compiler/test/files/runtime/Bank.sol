@@ -0,0 +1,62 @@
+pragma solidity ^0.8.20;
+
+contract Vault {
+    mapping(address => uint256) public deposits;
+
+    event Deposited(address indexed from, uint256 amount, string note);
+
+    error Insufficient(uint256 available, uint256 requested);
+
+    function deposit(string memory note) public payable {
+        deposits[msg.sender] += msg.value;
+        emit Deposited(msg.sender, msg.value, note);
+    }
+
+    function withdraw(uint256 amount) public {
+        uint256 available = deposits[msg.sender];
+        if (amount > available) {
+            revert Insufficient(available, amount);
+        }
+        deposits[msg.sender] = available - amount;
+        payable(msg.sender).transfer(amount);
+    }
+}
+
+contract Factory {
+    Vault public vault;
+
+    function create() public returns (address) {
+        vault = new Vault();
+        return address(vault);
+    }
+
+    function fund() public payable {
+        vault.deposit{value: msg.value}("from factory");
+    }
+
+    function held() public view returns (uint256) {
+        return vault.deposits(address(this));
+    }
+
+    function names() public pure returns (string memory, uint256[] memory) {
+        uint256[] memory numbers = new uint256[](3);
+        numbers[0] = 1;
+        numbers[2] = 3;
+        return ("factory", numbers);
+    }
+}
+// ----
+// deploy Vault
+// call deposit{value: 1000}: "first"
+// static deposits: @sender
+// balance @Vault
+// call withdraw: 400
+// call withdraw: 601
+// static deposits: @sender
+// balance @Vault
+// deploy Factory
+// call create:
+// call fund{value: 50}:
+// static held:
+// static names:
+// call Vault.deposit: "nothing"
@@ -0,0 +1,51 @@
+pragma solidity ^0.8.20;
+
+contract Counter {
+    uint256 public count;
+    address public owner;
+
+    event Incremented(address indexed by, uint256 count);
+
+    error NotOwner(address caller);
+
+    constructor(uint256 start) {
+        count = start;
+        owner = msg.sender;
+    }
+
+    function increment(uint256 by) public returns (uint256) {
+        count += by;
+        emit Incremented(msg.sender, count);
+        return count;
+    }
+
+    function reset() public {
+        if (msg.sender != owner) {
+            revert NotOwner(msg.sender);
+        }
+        count = 0;
+    }
+
+    function decrement() public {
+        require(count > 0, "already zero");
+        count -= 1;
+    }
+
+    function overflow() public view returns (uint256) {
+        return count + type(uint256).max;
+    }
+}
+// ----
+// deploy Counter: 5
+// static count:
+// call increment: 3
+// call increment: 2
+// static owner:
+// call decrement:
+// static count:
+// call overflow:
+// call reset:
+// call decrement:
+// static increment: 1
+// call Counter.increment{gas: 21500}: 1
+// static count:
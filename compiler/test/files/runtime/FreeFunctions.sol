@@ -0,0 +1,36 @@
+pragma solidity ^0.8.0;
+
+struct Pair {
+    uint256 a;
+    uint256 b;
+}
+
+enum Side { Left, Right }
+
+uint256 constant ONE = 1;
+
+error Bad(uint256 value);
+
+function sum(Pair memory pair) pure returns (uint256) {
+    return pair.a + pair.b + ONE;
+}
+
+function sum(uint256 a, uint256 b) pure returns (uint256) {
+    return a + b;
+}
+
+contract Free {
+    Side public side = Side.Right;
+
+    function total(uint256 a, uint256 b) external pure returns (uint256) {
+        if (a == 0) {
+            revert Bad(a);
+        }
+        return sum(Pair(a, b)) + sum(a, ONE);
+    }
+}
+// ----
+// deploy Free:
+// static total: 2, 3
+// static side:
+// static total: 0, 3
@@ -0,0 +1,30 @@
+pragma solidity ^0.8.20;
+
+contract Hex {
+    bytes4 constant ID = 0x01ffc9a7;
+    bytes4 constant PADDED = 0x0000_00ff;
+
+    function supports(bytes4 id) public pure returns (bool) {
+        return id == 0x01ffc9a7 || id == PADDED;
+    }
+
+    function recover() public pure returns (address) {
+        return ecrecover(
+            0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3,
+            28,
+            0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608,
+            0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada
+        );
+    }
+
+    function id() public pure returns (bytes4) {
+        return ID;
+    }
+}
+// ----
+// deploy Hex:
+// static supports: 0x01ffc9a7
+// static supports: 0x000000ff
+// static supports: 0x01ffc9a8
+// static id:
+// static recover:
@@ -0,0 +1,23 @@
+pragma solidity ^0.8.20;
+
+contract LiteralBase {
+    function power(uint256 x) public pure returns (uint256) {
+        return 2 ** x;
+    }
+
+    function shifted(uint256 x) public pure returns (uint256, uint256) {
+        return (1 << x, 4096 >> x);
+    }
+
+    function negative(uint256 x) public pure returns (int256) {
+        return -4096 >> x;
+    }
+}
+// ----
+// deploy LiteralBase:
+// static power: 10
+// static power: 255
+// static power: 256
+// static shifted: 10
+// static negative: 3
+// static negative: 20
@@ -0,0 +1,13 @@
+pragma solidity ^0.8.20;
+
+contract Literals {
+    uint256 constant HALF = .5 ether;
+    uint256 constant FIVE = .5e1;
+
+    function values() public pure returns (uint256, uint256, uint256) {
+        return (HALF, FIVE, 2.5e1 + .25 * 4);
+    }
+}
+// ----
+// deploy Literals:
+// static values:
@@ -0,0 +1,22 @@
+pragma solidity ^0.8.20;
+
+contract Overloads {
+    function f(bool flag) internal pure returns (uint256) {
+        return flag ? 1 : 0;
+    }
+
+    function f(uint256 x) internal pure returns (uint256) {
+        return x + 100;
+    }
+
+    function f(address) internal pure returns (uint256) {
+        return 7;
+    }
+
+    function pick(uint8 small) public pure returns (uint256, uint256, uint256) {
+        return (f(small), f(true), f(address(0)));
+    }
+}
+// ----
+// deploy Overloads:
+// static pick: 1
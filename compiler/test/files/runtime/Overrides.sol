@@ -0,0 +1,45 @@
+pragma solidity ^0.8.20;
+
+interface ICounter {
+    function inc(uint256 by) external returns (uint256);
+}
+
+contract Counter is ICounter {
+    uint256 public count;
+
+    function inc(uint256 by) public override returns (uint256) {
+        count += by;
+        return count;
+    }
+}
+
+contract A {
+    function f(uint256 x) public virtual returns (uint256) {
+        return x;
+    }
+}
+
+contract B is A {
+    function f(uint256 x) public override returns (uint256) {
+        return x * 10;
+    }
+
+    function g() public returns (uint256) {
+        return f(1);
+    }
+}
+
+contract Caller {
+    function run() public returns (uint256, uint256) {
+        B b = new B();
+        Counter k = new Counter();
+        k.inc(5);
+        return (b.f(2), k.inc(5));
+    }
+}
+// ----
+// deploy B:
+// call g:
+// call f: 3
+// deploy Caller:
+// call run:
@@ -0,0 +1,66 @@
+pragma solidity ^0.8.20;
+
+contract Base {
+    uint256[] public calls;
+
+    function record(uint256 tag) public virtual returns (uint256) {
+        calls.push(tag);
+        return tag;
+    }
+}
+
+contract Left is Base {
+    function record(uint256 tag) public virtual override returns (uint256) {
+        calls.push(10 + tag);
+        return super.record(tag) + 10;
+    }
+}
+
+contract Right is Base {
+    function record(uint256 tag) public virtual override returns (uint256) {
+        calls.push(20 + tag);
+        return super.record(tag) + 20;
+    }
+}
+
+contract Diamond is Left, Right {
+    function record(uint256 tag) public override(Left, Right) returns (uint256) {
+        calls.push(30 + tag);
+        return super.record(tag) + 30;
+    }
+
+    function count() public view returns (uint256) {
+        return calls.length;
+    }
+}
+
+contract Named {
+    function label(uint256 value) public pure virtual returns (uint256) {
+        return value;
+    }
+
+    function label(bool flag) public pure virtual returns (uint256) {
+        return flag ? 1 : 0;
+    }
+}
+
+contract Renamed is Named {
+    function label(uint256 value) public pure override returns (uint256) {
+        return super.label(value) * 100 + super.label(true);
+    }
+}
+// ----
+// deploy Diamond:
+// call record: 1
+// static count:
+// static calls: 0
+// static calls: 1
+// static calls: 2
+// static calls: 3
+// deploy Left:
+// call record: 5
+// static calls: 0
+// static calls: 1
+// deploy Renamed:
+// static label(uint256): 7
+// static label(bool): false
@@ -0,0 +1,16 @@
+pragma solidity ^0.8.0;
+
+garbage tokens here (1 + 2);
+= { nested { stuff } }
+uint constant X = ;
+uint counter;
+function f() external pure {}
+function g() pure returns (uint256);
+
+struct Kept {
+    uint256 a;
+}
+
+contract C {
+    Kept kept;
+}
@@ -0,0 +1,26 @@
+pragma solidity ^0.8.20;
+
+contract Overflow {
+    uint256[2**255] a;
+    uint256[2**255] c;
+    uint256 b;
+}
+
+contract Strings {
+    uint256 first;
+    string[2**256 - 1] names;
+}
+
+contract Nested {
+    struct Huge {
+        uint256 x;
+        uint256[2**252] values;
+    }
+
+    mapping(address => Huge) huge;
+}
+
+contract Fits {
+    uint256[2**250] big;
+    uint256 last;
+}
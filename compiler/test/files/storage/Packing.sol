@@ -0,0 +1,30 @@
+// emit: storage.json
+pragma solidity ^0.8.20;
+
+contract Base {
+    address owner;
+    bool paused;
+}
+
+contract Packing is Base {
+    enum State {
+        Idle,
+        Active
+    }
+
+    struct Position {
+        uint128 amount;
+        uint64 opened;
+        State state;
+        uint256 total;
+    }
+
+    uint8 small;
+    uint16[3] history;
+    uint256 constant LIMIT = 10;
+    Position position;
+    mapping(address => Position[]) positions;
+    bytes32 tag;
+    string name;
+    uint32 count;
+}
@@ -0,0 +1,22 @@
+pragma solidity ^0.8.20;
+
+struct Node {
+    uint256 value;
+    Node[] children;
+    mapping(uint256 => Node) byId;
+}
+
+contract Tree {
+    struct S {
+        uint256 a;
+        S s;
+    }
+
+    struct Pair {
+        Inner[2] inner;
+    }
+
+    struct Inner {
+        Pair pair;
+    }
+}
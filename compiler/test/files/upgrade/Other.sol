@@ -0,0 +1,7 @@
+// snapshots: snapshots
+// emit: layout.json
+pragma solidity ^0.8.20;
+
+upgradable contract Token {
+    bool paused;
+}
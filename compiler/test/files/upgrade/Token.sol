@@ -0,0 +1,15 @@
+// snapshots: snapshots
+pragma solidity ^0.8.20;
+
+upgradable contract Token {
+    struct Account {
+        uint128 balance;
+        uint64 nonce;
+    }
+
+    uint256 supply;
+    address owner;
+    uint256 fee;
+    mapping(address => Account) holders;
+    uint256 cap;
+}
@@ -0,0 +1,21 @@
+pragma solidity ^0.8.20;
+
+contract Base {
+    uint256 public count = 1;
+
+    function close() public {
+        selfdestruct(payable(msg.sender));
+    }
+}
+
+upgradable contract Vault is Base {
+    uint256 public constant LIMIT = 10;
+    address public owner = msg.sender;
+    uint256 public total;
+
+    function drain() public {
+        assembly {
+            selfdestruct(caller())
+        }
+    }
+}
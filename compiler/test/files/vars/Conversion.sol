@@ -0,0 +1,16 @@
+pragma solidity ^0.8.20;
+
+contract Conversion {
+    function widen(int8 x) public pure returns (uint256) {
+        return uint(int8(x));
+    }
+
+    function steps(int8 x) public pure returns (uint256) {
+        return uint256(int256(x));
+    }
+
+    function add(uint8 x) public pure returns (uint8) {
+        x += 300;
+        return x;
+    }
+}
@@ -0,0 +1,12 @@
+pragma solidity ^0.8.20;
+
+contract HexBytes {
+    bytes4 constant SHORT = 0x01ffc9;
+    bytes4 constant LONG = 0x01ffc9a700;
+    bytes2 constant DECIMAL = 258;
+    bytes4 constant ODD = 0x1ffc9a7;
+
+    function check(bytes4 id) public pure returns (bool) {
+        return id == 0x01ff;
+    }
+}
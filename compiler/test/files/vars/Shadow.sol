@@ -0,0 +1,14 @@
+pragma solidity ^0.8.20;
+
+contract Shadow {
+    uint256 count;
+
+    function set(uint256 count) public {
+        uint256 total = count;
+        {
+            uint256 total = 1;
+            total;
+        }
+        bool flag = total;
+    }
+}
@@ -0,0 +1,21 @@
+pragma solidity ^0.8.20;
+
+contract SignChange {
+    mapping(uint256 => uint256) m;
+    mapping(uint256 => uint256) other;
+
+    function widen(uint8 small, int8 signedSmall) public pure returns (int256) {
+        int16 b = small;
+        uint16 c = signedSmall;
+        int16 d = signedSmall;
+        uint16 e = small;
+        return b + d + int16(uint16(c + e));
+    }
+
+    function maps() public {
+        m = m;
+        m = other;
+        delete m;
+        delete m[1];
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::Parser;
+use evm::{run_script, Abi, Contract};
+use serde_json::Value;
+use solc::{cli::Cli, compile, settings::Emit, CompilationOutput, Settings, SourceMap};
+
+/* GOLDEN FILE TESTS. EVERY `.sol` UNDER `FIXTURES` IS COMPILED AND WHAT COMES OUT, THE SYNTAX
+TREE OR THE DIAGNOSTICS IF THERE ARE ERRORS, IS COMPARED WITH THE `.expected` FILE NEXT TO IT.
+A FIXTURE ENDING IN A `// ----` LINE IS ALSO RUN: THE COMMENTED LINES AFTER IT ARE A SCRIPT FOR
+THE LOCAL EVM AND ITS TRANSCRIPT IS COMPARED INSTEAD OF THE SYNTAX TREE. A `// emit: cron.json,
+abi.json, ir` LINE ASKS FOR THE ARTIFACTS `solc build` WOULD WRITE WITH THOSE EXTENSIONS, WHICH
+ARE COMPARED BEFORE THE TRANSCRIPT, `// snapshots: dir` CHECKS UPGRADABLE CONTRACTS AGAINST THE
+LAYOUT SNAPSHOTS IN `dir`, NEXT TO THE FIXTURE, AND `// flags: --optimize` COMPILES WITH THOSE
+OPTIONS OF `solc build`. A FIXTURE WITH `==== Source: a.sol ====` LINES IS SEVERAL SOURCE UNITS,
+EACH NAMED BY ITS LINE AND IMPORTABLE UNDER THAT NAME. RUN `cargo test --test fixtures -- --bless` (OR SET `BLESS=1`) TO
+REWRITE THE EXPECTATIONS, AND PASS ANY OTHER ARGUMENT TO ONLY RUN THE FIXTURES WHOSE PATH
+CONTAINS IT */
+const FIXTURES: &str = "test/files";
+
+fn main() {
+    let mut bless = env::var_os("BLESS").is_some();
+    let mut filters = Vec::new();
+    for arg in env::args().skip(1) {
+        if arg == "--bless" {
+            bless = true;
+        } else if !arg.starts_with('-') {
+            filters.push(arg);
+        }
+    }
+
+    let mut fixtures = Vec::new();
+    collect_fixtures(Path::new(FIXTURES), &mut fixtures);
+    fixtures.sort();
+    fixtures.retain(|fixture| {
+        filters.is_empty()
+            || filters
+                .iter()
+                .any(|filter| fixture.to_string_lossy().contains(filter.as_str()))
+    });
+
+    let mut failures = Vec::new();
+    for fixture in &fixtures {
+        let actual = compile_fixture(fixture);
+        let expectation = fixture.with_extension("expected");
+        if bless {
+            fs::write(&expectation, &actual).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&expectation) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{}: output differs from {}\n{}",
+                fixture.display(),
+                expectation.display(),
+                first_difference(&expected, &actual)
+            )),
+            Err(_) => failures.push(format!(
+                "{}: missing {}",
+                fixture.display(),
+                expectation.display()
+            )),
+        }
+    }
+
+    if bless {
+        println!("blessed {} fixtures", fixtures.len());
+        return;
+    }
+    for failure in &failures {
+        println!("FAILED {failure}\n");
+    }
+    println!(
+        "fixtures: {} passed, {} failed",
+        fixtures.len() - failures.len(),
+        failures.len()
+    );
+    if !failures.is_empty() {
+        println!("run `cargo test --test fixtures -- --bless` if the new output is correct");
+        process::exit(1);
+    }
+}
+
+fn collect_fixtures(directory: &Path, fixtures: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_fixtures(&path, fixtures);
+        } else if path.extension().is_some_and(|extension| extension == "sol") {
+            fixtures.push(path);
+        }
+    }
+}
+
+fn compile_fixture(fixture: &Path) -> String {
+    let name = fixture.to_string_lossy().to_string();
+    let source = fs::read_to_string(fixture).unwrap();
+    let script = script(&source);
+    let emit: Vec<String> = directive(&source, "emit")
+        .map(|emit| {
+            emit.split(',')
+                .map(|name| name.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut settings = match directive(&source, "flags") {
+        Some(_flags) => {
+            let arguments = ["solc", "build", &name].into_iter();
+            let cli = Cli::try_parse_from(arguments.chain(_flags.split_whitespace()))
+                .unwrap_or_else(|error| panic!("invalid `// flags:` in {name}: {error}"));
+            cli.command.args().settings().unwrap()
+        }
+        None => Settings::default(),
+    };
+    if emit.iter().any(|name| name == "ir") {
+        settings.emit.push(Emit::Ir);
+    }
+    settings.storage_snapshots =
+        directive(&source, "snapshots").map(|directory| fixture.parent().unwrap().join(directory));
+    let mut sources = SourceMap::default();
+    for (unit, contents) in source_units(&name, &source) {
+        sources.add(unit, contents);
+    }
+    let output = compile(sources, &settings);
+    if output.diagnostics.has_errors() {
+        return output.diagnostics.render(&output.sources, false);
+    }
+    let mut actual = emitted(&output, &emit);
+    match script {
+        Some(_script) => actual.push_str(&run_script(&contracts(&output), &_script)),
+        None if emit.is_empty() => actual = format!("{:#?}\n", output.ast(0).unwrap()),
+        None => {}
+    }
+    actual
+}
+
+/* THE SOURCE UNITS OF A FIXTURE, BY NAME. WITHOUT `==== Source: name ====` LINES IT IS ONE UNIT
+NAMED AFTER ITS PATH, OTHERWISE EACH LINE STARTS A UNIT AND WHAT COMES BEFORE THE FIRST IS LEFT
+OUT */
+fn source_units<'a>(name: &'a str, source: &'a str) -> Vec<(&'a str, String)> {
+    let mut units: Vec<(&str, String)> = Vec::new();
+    for line in source.split_inclusive('\n') {
+        let marker = line
+            .trim_end()
+            .strip_prefix("==== Source: ")
+            .and_then(|line| line.strip_suffix(" ===="));
+        match (marker, units.last_mut()) {
+            (Some(_unit), _) => units.push((_unit, String::new())),
+            (None, Some((_, _contents))) => _contents.push_str(line),
+            (None, None) => {}
+        }
+    }
+    if units.is_empty() {
+        units.push((name, source.to_string()));
+    }
+    units
+}
+
+/* THE VALUE OF A `// key: value` LINE */
+fn directive<'a>(source: &'a str, key: &str) -> Option<&'a str> {
+    source.lines().find_map(|line| {
+        line.strip_prefix("// ")?
+            .strip_prefix(key)?
+            .strip_prefix(':')
+            .map(str::trim)
+    })
+}
+
+/* THE ARTIFACTS ASKED FOR, IN THE ORDER THEY WERE ASKED FOR, OF EVERY CONTRACT THAT HAS THEM */
+fn emitted(output: &CompilationOutput, emit: &[String]) -> String {
+    let json = |value: Value| serde_json::to_string_pretty(&value).unwrap();
+    let mut emitted = String::new();
+    for artifact in &output.artifacts {
+        for extension in emit {
+            let contents = match extension.as_str() {
+                "abi.json" => Some(json(artifact.abi_json())),
+                "cron.json" if !artifact.cron.is_empty() => Some(json(artifact.cron_metadata())),
+                "relayer.json" if !artifact.gasless.is_empty() => {
+                    Some(json(artifact.relayer_metadata()))
+                }
+                "storage.json" if artifact.storage_layout.is_some() => {
+                    Some(json(artifact.storage_metadata()))
+                }
+                "layout.json" if artifact.upgradable => Some(json(artifact.layout_snapshot())),
+                "ir" => artifact
+                    .bytecode
+                    .as_ref()
+                    .and_then(|bytecode| bytecode.ir.clone()),
+                "cron.json" | "relayer.json" | "storage.json" | "layout.json" => None,
+                _ => panic!("`{extension}` is not an artifact `solc build` writes"),
+            };
+            if let Some(_contents) = contents {
+                emitted.push_str(&format!(
+                    "======= {}.{extension} =======\n{}\n",
+                    artifact.contract,
+                    _contents.trim_end()
+                ));
+            }
+        }
+    }
+    emitted
+}
+
+/* THE LINES AFTER `// ----`, WITHOUT THEIR `//` */
+fn script(source: &str) -> Option<String> {
+    let (_, script) = source.split_once("\n// ----\n")?;
+    Some(
+        script
+            .lines()
+            .map(|line| line.trim_start().trim_start_matches("//").trim())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+fn contracts(output: &CompilationOutput) -> Vec<Contract> {
+    output
+        .artifacts
+        .iter()
+        .filter_map(|artifact| {
+            Some(Contract {
+                name: artifact.contract.clone(),
+                creation: artifact.bytecode.as_ref()?.creation.clone(),
+                abi: Abi::from_json(&artifact.abi_json()).unwrap(),
+            })
+        })
+        .collect()
+}
+
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(_expected), Some(_actual)) if _expected == _actual => line += 1,
+            (None, None) => return "outputs differ in line endings".to_string(),
+            (_expected, _actual) => {
+                return format!(
+                    "first difference at line {line}:\n  expected: {}\n  actual:   {}",
+                    _expected.unwrap_or("<end of file>"),
+                    _actual.unwrap_or("<end of file>")
+                )
+            }
+        }
+    }
+}
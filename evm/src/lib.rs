@@ -0,0 +1,21 @@
+/* A LOCAL EVM FOR TESTING WITHOUT A NETWORK. IT RUNS THE BYTECODE THE COMPILER EMITS AGAINST AN
+IN-MEMORY WORLD STATE WITH CANCUN GAS RULES, AND `run_script` DRIVES IT FROM THE FIXTURE SUITE:
+DEPLOYS, CALLS AND STATIC CALLS WHOSE RESULTS, REVERT REASONS AND EVENTS ARE DECODED WITH THE ABI */
+mod mods;
+
+pub use mods::{
+    constants::constants,
+    functions::controllers::{
+        abi_codec::{self, decode, encode, encode_call, selector},
+        evm::Evm,
+        interpreter::Interpreter,
+        precompiles,
+        script::{run_script, Contract},
+    },
+    types::{
+        abi::{self, Abi, AbiItem, AbiParameter, AbiType, AbiValue, DecodedEvent, RevertReason},
+        environment::Environment,
+        execution::{Halt, Message, Outcome, Receipt, Status, Transaction},
+        world::{Account, Address, Log, Substate, World},
+    },
+};
@@ -0,0 +1,26 @@
+pub mod types {
+    pub mod abi;
+    pub mod environment;
+    pub mod execution;
+    pub mod world;
+}
+
+pub mod constants {
+    #[allow(clippy::module_inception)]
+    pub mod constants;
+}
+
+pub mod functions {
+    pub mod helpers {
+        pub mod rlp_helper;
+        pub mod word_helper;
+    }
+
+    pub mod controllers {
+        pub mod abi_codec;
+        pub mod evm;
+        pub mod interpreter;
+        pub mod precompiles;
+        pub mod script;
+    }
+}
@@ -0,0 +1,74 @@
+/* ------------------------------ LIMITS ------------------------------ */
+
+pub const STACK_LIMIT: usize = 1024;
+pub const CALL_DEPTH_LIMIT: usize = 1024;
+/* EIP-170 AND EIP-3860 */
+pub const MAX_CODE_SIZE: usize = 24_576;
+pub const MAX_INITCODE_SIZE: usize = 2 * MAX_CODE_SIZE;
+/* MEMORY IS CAPPED FAR BELOW WHAT GAS WOULD ALLOW, SO A RUNAWAY OFFSET FAILS FAST */
+pub const MAX_MEMORY: usize = 1 << 32;
+/* EIP-3529: AT MOST A FIFTH OF THE GAS USED IS REFUNDED */
+pub const MAX_REFUND_QUOTIENT: u64 = 5;
+
+/* ------------------------------ GAS: TRANSACTIONS ------------------------------ */
+
+pub const TX_GAS: u64 = 21_000;
+pub const TX_CREATE_GAS: u64 = 32_000;
+pub const TX_DATA_ZERO_GAS: u64 = 4;
+pub const TX_DATA_NONZERO_GAS: u64 = 16;
+
+/* ------------------------------ GAS: INSTRUCTIONS ------------------------------ */
+
+pub const GAS_ZERO: u64 = 0;
+pub const GAS_JUMPDEST: u64 = 1;
+pub const GAS_BASE: u64 = 2;
+pub const GAS_VERY_LOW: u64 = 3;
+pub const GAS_LOW: u64 = 5;
+pub const GAS_MID: u64 = 8;
+pub const GAS_HIGH: u64 = 10;
+pub const GAS_BLOCKHASH: u64 = 20;
+
+pub const GAS_EXP: u64 = 10;
+pub const GAS_EXP_BYTE: u64 = 50;
+pub const GAS_KECCAK256: u64 = 30;
+pub const GAS_KECCAK256_WORD: u64 = 6;
+pub const GAS_COPY_WORD: u64 = 3;
+pub const GAS_MEMORY_WORD: u64 = 3;
+pub const GAS_MEMORY_QUAD_DIVISOR: u64 = 512;
+
+pub const GAS_LOG: u64 = 375;
+pub const GAS_LOG_TOPIC: u64 = 375;
+pub const GAS_LOG_BYTE: u64 = 8;
+
+/* EIP-2929: THE FIRST ACCESS OF AN ACCOUNT OR A SLOT IN A TRANSACTION IS COLD */
+pub const GAS_WARM_ACCESS: u64 = 100;
+pub const GAS_COLD_ACCOUNT_ACCESS: u64 = 2_600;
+pub const GAS_COLD_SLOAD: u64 = 2_100;
+
+/* EIP-2200 AND EIP-3529 */
+pub const GAS_SSTORE_SET: u64 = 20_000;
+pub const GAS_SSTORE_RESET: u64 = 5_000 - GAS_COLD_SLOAD;
+pub const GAS_SSTORE_STIPEND: u64 = 2_300;
+pub const REFUND_SSTORE_CLEARS: u64 = 4_800;
+
+pub const GAS_CALL_VALUE: u64 = 9_000;
+pub const GAS_CALL_STIPEND: u64 = 2_300;
+pub const GAS_NEW_ACCOUNT: u64 = 25_000;
+pub const GAS_CREATE: u64 = 32_000;
+pub const GAS_INITCODE_WORD: u64 = 2;
+pub const GAS_CODE_DEPOSIT: u64 = 200;
+pub const GAS_SELFDESTRUCT: u64 = 5_000;
+
+/* ------------------------------ ADDRESSES ------------------------------ */
+
+/* ECRECOVER, SHA256, RIPEMD160 AND IDENTITY. THE OTHERS ARE NOT IMPLEMENTED AND FAIL */
+pub const PRECOMPILES: u8 = 10;
+
+/* WHERE SCRIPTS SEND TRANSACTIONS FROM, FUNDED WITH `SENDER_BALANCE` WEI */
+pub const DEFAULT_SENDER: [u8; 20] = [0xaa; 20];
+pub const SENDER_BALANCE: u128 = 1_000_000_000_000_000_000_000_000;
+pub const DEFAULT_GAS_LIMIT: u64 = 30_000_000;
+
+/* SELECTORS OF THE REVERT DATA SOLIDITY PRODUCES ITSELF */
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
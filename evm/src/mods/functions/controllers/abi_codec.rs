@@ -0,0 +1,398 @@
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, ToPrimitive, Zero};
+use serde_json::Value;
+
+use crate::mods::{
+    constants::constants::{ERROR_SELECTOR, PANIC_SELECTOR},
+    functions::helpers::word_helper::{from_signed, keccak256, to_bytes32, to_signed},
+    types::{
+        abi::{Abi, AbiItem, AbiParameter, AbiType, AbiValue, DecodedEvent, RevertReason},
+        world::Log,
+    },
+};
+
+/* ------------------------------ JSON ------------------------------ */
+
+/* A TYPE AS THE JSON ABI WRITES IT, STRUCTS AS `tuple` WITH THEIR FIELDS IN `components` */
+pub fn parse_type(ty: &str, components: Option<&Value>) -> Result<AbiType, String> {
+    if let Some(_base) = ty.strip_suffix(']') {
+        let open = _base
+            .rfind('[')
+            .ok_or_else(|| format!("invalid type `{ty}`"))?;
+        let length = match &_base[open + 1..] {
+            "" => None,
+            length => Some(
+                length
+                    .parse()
+                    .map_err(|_| format!("invalid array length in `{ty}`"))?,
+            ),
+        };
+        let base = parse_type(&_base[..open], components)?;
+        return Ok(AbiType::Array(Box::new(base), length));
+    }
+    let bits = |prefix: &str| -> Result<u16, String> {
+        match &ty[prefix.len()..] {
+            "" => Ok(256),
+            bits => match bits.parse() {
+                Ok(_bits) if _bits > 0 && _bits <= 256 && _bits % 8 == 0 => Ok(_bits),
+                _ => Err(format!("invalid type `{ty}`")),
+            },
+        }
+    };
+    match ty {
+        "address" => Ok(AbiType::Address),
+        "bool" => Ok(AbiType::Bool),
+        "string" => Ok(AbiType::String),
+        "bytes" => Ok(AbiType::Bytes),
+        /* AN ADDRESS FOLLOWED BY A SELECTOR */
+        "function" => Ok(AbiType::FixedBytes(24)),
+        "tuple" => {
+            let fields = components
+                .and_then(Value::as_array)
+                .ok_or_else(|| "a tuple without components".to_string())?;
+            Ok(AbiType::Tuple(
+                fields
+                    .iter()
+                    .map(|field| parse_parameter(field).map(|parameter| parameter.ty))
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        _ if ty.starts_with("uint") => bits("uint").map(AbiType::Uint),
+        _ if ty.starts_with("int") => bits("int").map(AbiType::Int),
+        _ if ty.starts_with("bytes") => match ty["bytes".len()..].parse() {
+            Ok(_size) if (1..=32).contains(&_size) => Ok(AbiType::FixedBytes(_size)),
+            _ => Err(format!("invalid type `{ty}`")),
+        },
+        _ => Err(format!("invalid type `{ty}`")),
+    }
+}
+
+fn parse_parameter(json: &Value) -> Result<AbiParameter, String> {
+    let ty = json["type"]
+        .as_str()
+        .ok_or_else(|| "a parameter without a type".to_string())?;
+    Ok(AbiParameter {
+        name: json["name"].as_str().unwrap_or_default().to_string(),
+        ty: parse_type(ty, json.get("components"))?,
+        indexed: json["indexed"].as_bool().unwrap_or(false),
+    })
+}
+
+fn parse_parameters(json: &Value) -> Result<Vec<AbiParameter>, String> {
+    json.as_array()
+        .map(|parameters| parameters.iter().map(parse_parameter).collect())
+        .unwrap_or(Ok(Vec::new()))
+}
+
+/* ------------------------------ ENCODING ------------------------------ */
+
+/* THE FIRST FOUR BYTES OF THE HASH OF A SIGNATURE */
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/* `values` ENCODED AS A TUPLE OF `types` */
+pub fn encode(types: &[AbiType], values: &[AbiValue]) -> Result<Vec<u8>, String> {
+    if types.len() != values.len() {
+        return Err(format!(
+            "{} values given for {} types",
+            values.len(),
+            types.len()
+        ));
+    }
+    let head_size: usize = types.iter().map(AbiType::head_size).sum();
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    for (ty, value) in types.iter().zip(values) {
+        let encoded = encode_value(ty, value)?;
+        match ty.is_dynamic() {
+            true => {
+                head.extend(to_bytes32(&BigUint::from(head_size + tail.len())));
+                tail.extend(encoded);
+            }
+            false => head.extend(encoded),
+        }
+    }
+    head.extend(tail);
+    Ok(head)
+}
+
+fn encode_value(ty: &AbiType, value: &AbiValue) -> Result<Vec<u8>, String> {
+    let padded = |bytes: &[u8]| -> Vec<u8> {
+        let mut encoded = bytes.to_vec();
+        encoded.resize(bytes.len().div_ceil(32) * 32, 0);
+        encoded
+    };
+    match (ty, value) {
+        (AbiType::Uint(bits), AbiValue::Uint(_value)) if _value.bits() <= *bits as u64 => {
+            Ok(to_bytes32(_value).to_vec())
+        }
+        (AbiType::Int(bits), AbiValue::Int(_value)) if fits_signed(_value, *bits) => {
+            Ok(to_bytes32(&from_signed(_value)).to_vec())
+        }
+        (AbiType::Address, AbiValue::Address(_address)) => {
+            Ok(to_bytes32(&BigUint::from_bytes_be(_address)).to_vec())
+        }
+        (AbiType::Bool, AbiValue::Bool(_value)) => {
+            Ok(to_bytes32(&BigUint::from(*_value as u8)).to_vec())
+        }
+        (AbiType::FixedBytes(size), AbiValue::FixedBytes(_bytes))
+            if _bytes.len() == *size as usize =>
+        {
+            Ok(padded(_bytes))
+        }
+        (AbiType::Bytes, AbiValue::Bytes(_bytes)) => {
+            let mut encoded = to_bytes32(&BigUint::from(_bytes.len())).to_vec();
+            encoded.extend(padded(_bytes));
+            Ok(encoded)
+        }
+        (AbiType::String, AbiValue::String(_value)) => encode_value(
+            &AbiType::Bytes,
+            &AbiValue::Bytes(_value.as_bytes().to_vec()),
+        ),
+        (AbiType::Array(base, None), AbiValue::Array(_values)) => {
+            let mut encoded = to_bytes32(&BigUint::from(_values.len())).to_vec();
+            encoded.extend(encode(&vec![(**base).clone(); _values.len()], _values)?);
+            Ok(encoded)
+        }
+        (AbiType::Array(base, Some(length)), AbiValue::Array(_values))
+            if _values.len() == *length =>
+        {
+            encode(&vec![(**base).clone(); *length], _values)
+        }
+        (AbiType::Tuple(fields), AbiValue::Tuple(_values)) => encode(fields, _values),
+        _ => Err(format!("`{value}` is not a valid `{ty}`")),
+    }
+}
+
+fn fits_signed(value: &BigInt, bits: u16) -> bool {
+    let limit = BigInt::one() << (bits - 1);
+    -&limit <= *value && *value < limit
+}
+
+/* THE CALLDATA OF A CALL OF `function` */
+pub fn encode_call(function: &AbiItem, values: &[AbiValue]) -> Result<Vec<u8>, String> {
+    let types: Vec<AbiType> = function
+        .inputs
+        .iter()
+        .map(|parameter| parameter.ty.clone())
+        .collect();
+    let mut data = selector(&function.signature()).to_vec();
+    data.extend(encode(&types, values)?);
+    Ok(data)
+}
+
+/* ------------------------------ DECODING ------------------------------ */
+
+/* A TUPLE OF `types` AT THE START OF `data`. OFFSETS ARE RELATIVE TO THE START OF THE TUPLE AND
+EVERY VALUE MUST BE CLEAN, SO A MALFORMED ENCODING IS AN ERROR RATHER THAN A GUESS */
+pub fn decode(types: &[AbiType], data: &[u8]) -> Result<Vec<AbiValue>, String> {
+    let mut values = Vec::new();
+    let mut position = 0;
+    for ty in types {
+        let value = match ty.is_dynamic() {
+            true => {
+                let offset = word(data, position)?
+                    .to_usize()
+                    .filter(|offset| *offset <= data.len())
+                    .ok_or_else(|| format!("offset of a `{ty}` out of bounds"))?;
+                decode_value(ty, &data[offset..])?
+            }
+            false => decode_value(ty, &data[position.min(data.len())..])?,
+        };
+        values.push(value);
+        position += ty.head_size();
+    }
+    Ok(values)
+}
+
+fn word(data: &[u8], position: usize) -> Result<BigUint, String> {
+    data.get(position..position + 32)
+        .map(BigUint::from_bytes_be)
+        .ok_or_else(|| "data too short".to_string())
+}
+
+fn decode_value(ty: &AbiType, data: &[u8]) -> Result<AbiValue, String> {
+    let invalid = || format!("invalid `{ty}`");
+    match ty {
+        AbiType::Uint(bits) => match word(data, 0)? {
+            value if value.bits() <= *bits as u64 => Ok(AbiValue::Uint(value)),
+            _ => Err(invalid()),
+        },
+        AbiType::Int(bits) => match to_signed(&word(data, 0)?) {
+            value if fits_signed(&value, *bits) => Ok(AbiValue::Int(value)),
+            _ => Err(invalid()),
+        },
+        AbiType::Address => match word(data, 0)? {
+            value if value.bits() <= 160 => Ok(AbiValue::Address(
+                to_bytes32(&value)[12..].try_into().unwrap(),
+            )),
+            _ => Err(invalid()),
+        },
+        AbiType::Bool => match word(data, 0)? {
+            value if value.is_zero() => Ok(AbiValue::Bool(false)),
+            value if value.is_one() => Ok(AbiValue::Bool(true)),
+            _ => Err(invalid()),
+        },
+        AbiType::FixedBytes(size) => {
+            let bytes = to_bytes32(&word(data, 0)?);
+            match bytes[*size as usize..].iter().all(|byte| *byte == 0) {
+                true => Ok(AbiValue::FixedBytes(bytes[..*size as usize].to_vec())),
+                false => Err(invalid()),
+            }
+        }
+        AbiType::Bytes | AbiType::String => {
+            let length = word(data, 0)?
+                .to_usize()
+                .filter(|length| 32 + length <= data.len())
+                .ok_or_else(|| format!("length of a `{ty}` out of bounds"))?;
+            let bytes = data[32..32 + length].to_vec();
+            match ty {
+                AbiType::String => String::from_utf8(bytes)
+                    .map(AbiValue::String)
+                    .map_err(|_| "a `string` that is not UTF-8".to_string()),
+                _ => Ok(AbiValue::Bytes(bytes)),
+            }
+        }
+        AbiType::Array(base, None) => {
+            let length = word(data, 0)?
+                .to_usize()
+                .filter(|length| length * base.head_size() <= data.len() - 32)
+                .ok_or_else(|| format!("length of a `{ty}` out of bounds"))?;
+            decode(&vec![(**base).clone(); length], &data[32..]).map(AbiValue::Array)
+        }
+        AbiType::Array(base, Some(length)) => {
+            decode(&vec![(**base).clone(); *length], data).map(AbiValue::Array)
+        }
+        AbiType::Tuple(fields) => decode(fields, data).map(AbiValue::Tuple),
+    }
+}
+
+/* ------------------------------ CONTRACT ABI ------------------------------ */
+
+impl Abi {
+    /* READS THE JSON ABI THE COMPILER WRITES */
+    pub fn from_json(json: &Value) -> Result<Abi, String> {
+        let mut abi = Abi::default();
+        let entries = json
+            .as_array()
+            .ok_or_else(|| "the ABI is not an array".to_string())?;
+        for entry in entries {
+            let item = AbiItem {
+                name: entry["name"].as_str().unwrap_or_default().to_string(),
+                inputs: parse_parameters(&entry["inputs"])?,
+                outputs: parse_parameters(&entry["outputs"])?,
+                anonymous: entry["anonymous"].as_bool().unwrap_or(false),
+            };
+            match entry["type"].as_str().unwrap_or("function") {
+                "function" => abi.functions.push(item),
+                "constructor" => abi.constructor = item.inputs,
+                "event" => abi.events.push(item),
+                "error" => abi.errors.push(item),
+                _ => {}
+            }
+        }
+        Ok(abi)
+    }
+
+    /* A FUNCTION BY ITS NAME, OR BY ITS SIGNATURE WHEN IT IS OVERLOADED */
+    pub fn function(&self, name: &str) -> Result<&AbiItem, String> {
+        let matches: Vec<&AbiItem> = self
+            .functions
+            .iter()
+            .filter(|function| match name.contains('(') {
+                true => function.signature() == name,
+                false => function.name == name,
+            })
+            .collect();
+        match matches.as_slice() {
+            [function] => Ok(function),
+            [] => Err(format!("no function `{name}` in the ABI")),
+            _ => Err(format!("`{name}` is overloaded, call it by its signature")),
+        }
+    }
+
+    pub fn decode_revert(&self, data: &[u8]) -> RevertReason {
+        if data.is_empty() {
+            return RevertReason::Empty;
+        }
+        let Some((prefix, arguments)) = data.split_first_chunk::<4>() else {
+            return RevertReason::Unknown(data.to_vec());
+        };
+        let decoded = match *prefix {
+            ERROR_SELECTOR => {
+                decode(&[AbiType::String], arguments).map(|mut values| match values.remove(0) {
+                    AbiValue::String(message) => RevertReason::Error(message),
+                    _ => RevertReason::Unknown(data.to_vec()),
+                })
+            }
+            PANIC_SELECTOR => {
+                decode(&[AbiType::Uint(256)], arguments).map(|mut values| match values.remove(0) {
+                    AbiValue::Uint(code) => RevertReason::Panic(code),
+                    _ => RevertReason::Unknown(data.to_vec()),
+                })
+            }
+            _ => match self
+                .errors
+                .iter()
+                .find(|error| selector(&error.signature()) == *prefix)
+            {
+                Some(_error) => {
+                    decode(&types(&_error.inputs), arguments).map(|values| RevertReason::Custom {
+                        name: _error.name.clone(),
+                        values,
+                    })
+                }
+                None => Err(String::new()),
+            },
+        };
+        decoded.unwrap_or_else(|_| RevertReason::Unknown(data.to_vec()))
+    }
+
+    /* THE EVENT A LOG WAS EMITTED AS, MATCHED BY ITS FIRST TOPIC. ANONYMOUS EVENTS HAVE NONE
+    AND ARE NOT RECOGNIZED */
+    pub fn decode_log(&self, log: &Log) -> Option<DecodedEvent> {
+        let topic = log.topics.first()?;
+        let event = self
+            .events
+            .iter()
+            .find(|event| !event.anonymous && keccak256(event.signature().as_bytes()) == *topic)?;
+        let (indexed, data): (Vec<&AbiParameter>, Vec<&AbiParameter>) =
+            event.inputs.iter().partition(|parameter| parameter.indexed);
+        if indexed.len() + 1 != log.topics.len() {
+            return None;
+        }
+        let data_types: Vec<AbiType> = data.iter().map(|parameter| parameter.ty.clone()).collect();
+        let mut data_values = decode(&data_types, &log.data).ok()?.into_iter();
+        let mut topics = log.topics[1..].iter();
+        let mut fields = Vec::new();
+        for parameter in &event.inputs {
+            let value = match parameter.indexed {
+                true => {
+                    let topic = topics.next()?;
+                    match parameter.ty {
+                        AbiType::Bytes
+                        | AbiType::String
+                        | AbiType::Array(..)
+                        | AbiType::Tuple(_) => AbiValue::FixedBytes(topic.to_vec()),
+                        _ => decode_value(&parameter.ty, topic).ok()?,
+                    }
+                }
+                false => data_values.next()?,
+            };
+            fields.push((parameter.name.clone(), value));
+        }
+        Some(DecodedEvent {
+            name: event.name.clone(),
+            fields,
+        })
+    }
+}
+
+pub fn types(parameters: &[AbiParameter]) -> Vec<AbiType> {
+    parameters
+        .iter()
+        .map(|parameter| parameter.ty.clone())
+        .collect()
+}
@@ -0,0 +1,336 @@
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::mods::{
+    constants::constants::{
+        CALL_DEPTH_LIMIT, DEFAULT_GAS_LIMIT, GAS_CODE_DEPOSIT, GAS_INITCODE_WORD, MAX_CODE_SIZE,
+        MAX_INITCODE_SIZE, MAX_REFUND_QUOTIENT, PRECOMPILES, TX_CREATE_GAS, TX_DATA_NONZERO_GAS,
+        TX_DATA_ZERO_GAS, TX_GAS,
+    },
+    functions::{
+        controllers::{interpreter::Interpreter, precompiles},
+        helpers::{
+            rlp_helper::{create2_address, create_address},
+            word_helper::words,
+        },
+    },
+    types::{
+        environment::Environment,
+        execution::{Halt, Message, Outcome, Receipt, Status, Transaction},
+        world::{Address, Substate, World},
+    },
+};
+
+/* THE CHAIN: THE WORLD STATE, THE BLOCK EVERY TRANSACTION RUNS IN AND WHAT THE TRANSACTION
+BEING EXECUTED HAS TOUCHED SO FAR */
+#[derive(Debug, Clone)]
+pub struct Evm {
+    pub world: World,
+    pub environment: Environment,
+    /* THE GAS LIMIT OF `deploy`, `call` AND `static_call` */
+    pub gas_limit: u64,
+    pub substate: Substate,
+    /* THE WORLD AS THE TRANSACTION FOUND IT, WHAT `SSTORE` PRICES AGAINST */
+    pub original: World,
+    pub origin: Address,
+}
+
+impl Default for Evm {
+    fn default() -> Self {
+        Evm {
+            world: World::default(),
+            environment: Environment::default(),
+            gas_limit: DEFAULT_GAS_LIMIT,
+            substate: Substate::default(),
+            original: World::default(),
+            origin: [0; 20],
+        }
+    }
+}
+
+impl Evm {
+    pub fn new() -> Self {
+        Evm::default()
+    }
+
+    pub fn fund(&mut self, address: Address, amount: BigUint) {
+        self.world.account_mut(address).balance += amount;
+    }
+
+    /* ------------------------------ TRANSACTIONS ------------------------------ */
+
+    pub fn deploy(&mut self, from: Address, init_code: Vec<u8>, value: BigUint) -> Receipt {
+        self.transact(&Transaction {
+            from,
+            to: None,
+            value,
+            data: init_code,
+            gas_limit: self.gas_limit,
+        })
+    }
+
+    pub fn call(&mut self, from: Address, to: Address, data: Vec<u8>, value: BigUint) -> Receipt {
+        self.transact(&Transaction {
+            from,
+            to: Some(to),
+            value,
+            data,
+            gas_limit: self.gas_limit,
+        })
+    }
+
+    pub fn static_call(&mut self, from: Address, to: Address, data: Vec<u8>) -> Receipt {
+        self.transact_static(&Transaction {
+            from,
+            to: Some(to),
+            value: BigUint::zero(),
+            data,
+            gas_limit: self.gas_limit,
+        })
+    }
+
+    pub fn transact(&mut self, transaction: &Transaction) -> Receipt {
+        self.execute(transaction, false)
+    }
+
+    /* RUNS A TRANSACTION AS `eth_call` DOES: IN A STATIC FRAME, LEAVING THE WORLD AS IT WAS */
+    pub fn transact_static(&mut self, transaction: &Transaction) -> Receipt {
+        let world = self.world.clone();
+        let receipt = self.execute(transaction, true);
+        self.world = world;
+        receipt
+    }
+
+    fn execute(&mut self, transaction: &Transaction, is_static: bool) -> Receipt {
+        let intrinsic = intrinsic_gas(transaction);
+        let fee = BigUint::from(transaction.gas_limit) * &self.environment.gas_price;
+        if intrinsic > transaction.gas_limit
+            || self.world.balance(&transaction.from) < &fee + &transaction.value
+            || (transaction.to.is_none() && transaction.data.len() > MAX_INITCODE_SIZE)
+        {
+            return Receipt {
+                status: Status::Halt(Halt::InvalidTransaction),
+                output: Vec::new(),
+                gas_used: 0,
+                logs: Vec::new(),
+                created: None,
+            };
+        }
+
+        self.substate = Substate::default();
+        self.original = self.world.clone();
+        self.origin = transaction.from;
+        self.world.account_mut(transaction.from).balance -= fee;
+        let warm = &mut self.substate.warm_accounts;
+        warm.insert(transaction.from);
+        warm.insert(self.environment.coinbase);
+        warm.extend(transaction.to);
+        warm.extend((1..=PRECOMPILES).map(precompile_address));
+
+        let gas = transaction.gas_limit - intrinsic;
+        let outcome = match transaction.to {
+            Some(_to) => {
+                self.world.account_mut(transaction.from).nonce += 1;
+                self.call_frame(Message {
+                    caller: transaction.from,
+                    address: _to,
+                    code_address: _to,
+                    value: transaction.value.clone(),
+                    data: transaction.data.clone(),
+                    gas,
+                    is_static,
+                    depth: 0,
+                    transfer: true,
+                })
+            }
+            None => self.create_frame(
+                transaction.from,
+                transaction.value.clone(),
+                transaction.data.clone(),
+                None,
+                gas,
+                0,
+            ),
+        };
+
+        let mut gas_used = transaction.gas_limit - outcome.gas_left;
+        let success = outcome.status == Status::Success;
+        if success {
+            let refund = self.substate.refund.max(0) as u64;
+            gas_used -= refund.min(gas_used / MAX_REFUND_QUOTIENT);
+        }
+        let price = &self.environment.gas_price;
+        let tip = match *price > self.environment.base_fee {
+            true => price - &self.environment.base_fee,
+            false => BigUint::zero(),
+        };
+        self.world.account_mut(transaction.from).balance +=
+            BigUint::from(transaction.gas_limit - gas_used) * price;
+        if !tip.is_zero() {
+            self.world.account_mut(self.environment.coinbase).balance +=
+                BigUint::from(gas_used) * tip;
+        }
+
+        let substate = std::mem::take(&mut self.substate);
+        for address in &substate.destroyed {
+            self.world.accounts.remove(address);
+        }
+        Receipt {
+            status: outcome.status,
+            output: outcome.output,
+            gas_used,
+            logs: match success {
+                true => substate.logs,
+                false => Vec::new(),
+            },
+            created: outcome.created,
+        }
+    }
+
+    /* ------------------------------ FRAMES ------------------------------ */
+
+    /* RUNS A MESSAGE CALL. NOTHING IT DID SURVIVES UNLESS IT SUCCEEDS */
+    pub fn call_frame(&mut self, message: Message) -> Outcome {
+        let refused = |halt: Halt, gas: u64| Outcome {
+            gas_left: gas,
+            ..Outcome::halt(halt)
+        };
+        if message.depth > CALL_DEPTH_LIMIT {
+            return refused(Halt::CallDepth, message.gas);
+        }
+        if message.transfer && self.world.balance(&message.caller) < message.value {
+            return refused(Halt::InsufficientBalance, message.gas);
+        }
+
+        let snapshot = (self.world.clone(), self.substate.clone());
+        if message.transfer {
+            self.world.account_mut(message.caller).balance -= &message.value;
+            self.world.account_mut(message.address).balance += &message.value;
+        }
+        let code = self.world.code(&message.code_address).to_vec();
+        let outcome = match precompile_id(&message.code_address) {
+            Some(_id) => precompiles::run(_id, &message.data, message.gas),
+            None if code.is_empty() => Outcome {
+                status: Status::Success,
+                output: Vec::new(),
+                gas_left: message.gas,
+                created: None,
+            },
+            None => Interpreter::new(message, code).run(self),
+        };
+        if outcome.status != Status::Success {
+            (self.world, self.substate) = snapshot;
+        }
+        outcome
+    }
+
+    /* RUNS INIT CODE AT A NEW ADDRESS, DERIVED FROM THE CREATOR'S NONCE OR FROM `salt`, AND
+    LEAVES WHAT IT RETURNS THERE AS THE CONTRACT'S CODE */
+    pub fn create_frame(
+        &mut self,
+        caller: Address,
+        value: BigUint,
+        init_code: Vec<u8>,
+        salt: Option<[u8; 32]>,
+        gas: u64,
+        depth: usize,
+    ) -> Outcome {
+        let refused = |halt: Halt, gas: u64| Outcome {
+            gas_left: gas,
+            ..Outcome::halt(halt)
+        };
+        if depth > CALL_DEPTH_LIMIT {
+            return refused(Halt::CallDepth, gas);
+        }
+        if self.world.balance(&caller) < value {
+            return refused(Halt::InsufficientBalance, gas);
+        }
+        let creator = self.world.account_mut(caller);
+        let Some(_nonce) = creator.nonce.checked_add(1) else {
+            return refused(Halt::CreateCollision, gas);
+        };
+        let address = match salt {
+            Some(_salt) => create2_address(&caller, &_salt, &init_code),
+            None => create_address(&caller, creator.nonce),
+        };
+        creator.nonce = _nonce;
+        self.substate.warm_accounts.insert(address);
+        if self.world.account(&address).is_some_and(|account| {
+            account.nonce != 0 || !account.code.is_empty() || !account.storage.is_empty()
+        }) {
+            return Outcome::halt(Halt::CreateCollision);
+        }
+
+        let snapshot = (self.world.clone(), self.substate.clone());
+        self.world.account_mut(caller).balance -= &value;
+        let account = self.world.account_mut(address);
+        account.balance += &value;
+        account.nonce = 1;
+        self.substate.created.insert(address);
+        let message = Message {
+            caller,
+            address,
+            code_address: address,
+            value,
+            data: Vec::new(),
+            gas,
+            is_static: false,
+            depth,
+            transfer: false,
+        };
+        let mut outcome = Interpreter::new(message, init_code).run(self);
+        if outcome.status == Status::Success {
+            let code = std::mem::take(&mut outcome.output);
+            let deposit = GAS_CODE_DEPOSIT * code.len() as u64;
+            outcome = match code.first() {
+                _ if code.len() > MAX_CODE_SIZE => Outcome::halt(Halt::CodeTooLarge),
+                Some(0xef) => Outcome::halt(Halt::InvalidCodePrefix),
+                _ if deposit > outcome.gas_left => Outcome::halt(Halt::OutOfGas),
+                _ => {
+                    self.world.account_mut(address).code = code;
+                    Outcome {
+                        gas_left: outcome.gas_left - deposit,
+                        created: Some(address),
+                        ..outcome
+                    }
+                }
+            };
+        }
+        if outcome.status != Status::Success {
+            (self.world, self.substate) = snapshot;
+        }
+        outcome
+    }
+}
+
+/* WHAT A TRANSACTION PAYS BEFORE ANY CODE RUNS */
+fn intrinsic_gas(transaction: &Transaction) -> u64 {
+    let data: u64 = transaction
+        .data
+        .iter()
+        .map(|byte| match byte {
+            0 => TX_DATA_ZERO_GAS,
+            _ => TX_DATA_NONZERO_GAS,
+        })
+        .sum();
+    let create = match transaction.to {
+        Some(_) => 0,
+        None => TX_CREATE_GAS + GAS_INITCODE_WORD * words(transaction.data.len()),
+    };
+    TX_GAS + data + create
+}
+
+pub fn precompile_address(id: u8) -> Address {
+    let mut address = [0; 20];
+    address[19] = id;
+    address
+}
+
+pub fn precompile_id(address: &Address) -> Option<u8> {
+    match address.split_last() {
+        Some((&_id, rest)) if (1..=PRECOMPILES).contains(&_id) && rest.iter().all(|b| *b == 0) => {
+            Some(_id)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,714 @@
+use std::ops::Range;
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::mods::{
+    constants::constants::{
+        GAS_BASE, GAS_BLOCKHASH, GAS_CALL_STIPEND, GAS_CALL_VALUE, GAS_COLD_ACCOUNT_ACCESS,
+        GAS_COLD_SLOAD, GAS_COPY_WORD, GAS_CREATE, GAS_EXP, GAS_EXP_BYTE, GAS_HIGH,
+        GAS_INITCODE_WORD, GAS_JUMPDEST, GAS_KECCAK256, GAS_KECCAK256_WORD, GAS_LOG, GAS_LOG_BYTE,
+        GAS_LOG_TOPIC, GAS_LOW, GAS_MEMORY_QUAD_DIVISOR, GAS_MEMORY_WORD, GAS_MID, GAS_NEW_ACCOUNT,
+        GAS_SELFDESTRUCT, GAS_SSTORE_RESET, GAS_SSTORE_SET, GAS_SSTORE_STIPEND, GAS_VERY_LOW,
+        GAS_WARM_ACCESS, GAS_ZERO, MAX_INITCODE_SIZE, MAX_MEMORY, REFUND_SSTORE_CLEARS,
+        STACK_LIMIT,
+    },
+    functions::{
+        controllers::evm::Evm,
+        helpers::word_helper::{
+            address_word, from_signed, keccak256, max_word, modulus, to_address, to_bytes32,
+            to_signed, to_usize, words,
+        },
+    },
+    types::{
+        execution::{Halt, Message, Outcome, Status},
+        world::{Address, Log},
+    },
+};
+
+/* WHAT AN INSTRUCTION DID: NOTHING TO REPORT, OR THE FRAME ENDED WITH RETURN DATA */
+type Step = Result<Option<(Status, Vec<u8>)>, Halt>;
+
+/* ONE CALL FRAME BEING EXECUTED */
+pub struct Interpreter {
+    message: Message,
+    code: Vec<u8>,
+    /* WHERE A `JUMPDEST` IS AN INSTRUCTION AND NOT PUSH DATA */
+    jumpdests: Vec<bool>,
+    pc: usize,
+    stack: Vec<BigUint>,
+    memory: Vec<u8>,
+    /* THE OUTPUT OF THE LAST CALL OR CREATE THIS FRAME MADE */
+    return_data: Vec<u8>,
+    gas: u64,
+}
+
+impl Interpreter {
+    pub fn new(message: Message, code: Vec<u8>) -> Self {
+        let mut jumpdests = vec![false; code.len()];
+        let mut pc = 0;
+        while pc < code.len() {
+            match code[pc] {
+                0x5b => jumpdests[pc] = true,
+                opcode @ 0x60..=0x7f => pc += (opcode - 0x5f) as usize,
+                _ => {}
+            }
+            pc += 1;
+        }
+        Interpreter {
+            gas: message.gas,
+            message,
+            code,
+            jumpdests,
+            pc: 0,
+            stack: Vec::new(),
+            memory: Vec::new(),
+            return_data: Vec::new(),
+        }
+    }
+
+    pub fn run(mut self, evm: &mut Evm) -> Outcome {
+        loop {
+            match self.step(evm) {
+                Ok(None) => {}
+                Ok(Some((status, output))) => {
+                    return Outcome {
+                        status,
+                        output,
+                        gas_left: self.gas,
+                        created: None,
+                    }
+                }
+                Err(halt) => return Outcome::halt(halt),
+            }
+        }
+    }
+
+    /* ------------------------------ INSTRUCTIONS ------------------------------ */
+
+    fn step(&mut self, evm: &mut Evm) -> Step {
+        /* RUNNING OFF THE END OF THE CODE IS A `STOP` */
+        let opcode = self.code.get(self.pc).copied().unwrap_or(0x00);
+        self.pc += 1;
+        let address = self.message.address;
+        match opcode {
+            0x00 => return Ok(Some((Status::Success, Vec::new()))),
+
+            /* ARITHMETIC */
+            0x01 => self.binary(GAS_VERY_LOW, |a, b| wrap(a + b))?,
+            0x02 => self.binary(GAS_LOW, |a, b| wrap(a * b))?,
+            0x03 => self.binary(GAS_VERY_LOW, |a, b| wrap(a + modulus() - b))?,
+            0x04 => self.binary(GAS_LOW, |a, b| match b.is_zero() {
+                true => b,
+                false => a / b,
+            })?,
+            0x05 => self.binary(GAS_LOW, |a, b| match b.is_zero() {
+                true => b,
+                false => from_signed(&(to_signed(&a) / to_signed(&b))),
+            })?,
+            0x06 => self.binary(GAS_LOW, |a, b| match b.is_zero() {
+                true => b,
+                false => a % b,
+            })?,
+            0x07 => self.binary(GAS_LOW, |a, b| match b.is_zero() {
+                true => b,
+                false => from_signed(&(to_signed(&a) % to_signed(&b))),
+            })?,
+            0x08 | 0x09 => {
+                self.charge(GAS_MID)?;
+                let (a, b, n) = (self.pop()?, self.pop()?, self.pop()?);
+                self.push(match (n.is_zero(), opcode) {
+                    (true, _) => n,
+                    (false, 0x08) => (a + b) % n,
+                    (false, _) => (a * b) % n,
+                })?
+            }
+            0x0a => {
+                let (base, exponent) = (self.pop()?, self.pop()?);
+                self.charge(GAS_EXP + GAS_EXP_BYTE * exponent.bits().div_ceil(8))?;
+                self.push(base.modpow(&exponent, &modulus()))?
+            }
+            0x0b => self.binary(GAS_LOW, |size, value| match size.to_u64() {
+                Some(_size) if _size < 31 => {
+                    let bit = _size * 8 + 7;
+                    let mask = (BigUint::one() << bit) - 1u8;
+                    match value.bit(bit) {
+                        true => value | (max_word() ^ mask),
+                        false => value & mask,
+                    }
+                }
+                _ => value,
+            })?,
+
+            /* COMPARISON AND BITWISE LOGIC */
+            0x10 => self.binary(GAS_VERY_LOW, |a, b| flag(a < b))?,
+            0x11 => self.binary(GAS_VERY_LOW, |a, b| flag(a > b))?,
+            0x12 => self.binary(GAS_VERY_LOW, |a, b| flag(to_signed(&a) < to_signed(&b)))?,
+            0x13 => self.binary(GAS_VERY_LOW, |a, b| flag(to_signed(&a) > to_signed(&b)))?,
+            0x14 => self.binary(GAS_VERY_LOW, |a, b| flag(a == b))?,
+            0x15 => {
+                self.charge(GAS_VERY_LOW)?;
+                let value = self.pop()?;
+                self.push(flag(value.is_zero()))?
+            }
+            0x16 => self.binary(GAS_VERY_LOW, |a, b| a & b)?,
+            0x17 => self.binary(GAS_VERY_LOW, |a, b| a | b)?,
+            0x18 => self.binary(GAS_VERY_LOW, |a, b| a ^ b)?,
+            0x19 => {
+                self.charge(GAS_VERY_LOW)?;
+                let value = self.pop()?;
+                self.push(max_word() ^ value)?
+            }
+            0x1a => self.binary(GAS_VERY_LOW, |index, value| match index.to_usize() {
+                Some(_index) if _index < 32 => BigUint::from(to_bytes32(&value)[_index]),
+                _ => BigUint::zero(),
+            })?,
+            0x1b => self.binary(GAS_VERY_LOW, |shift, value| match shift.to_usize() {
+                Some(_shift) if _shift < 256 => wrap(value << _shift),
+                _ => BigUint::zero(),
+            })?,
+            0x1c => self.binary(GAS_VERY_LOW, |shift, value| match shift.to_usize() {
+                Some(_shift) if _shift < 256 => value >> _shift,
+                _ => BigUint::zero(),
+            })?,
+            0x1d => self.binary(GAS_VERY_LOW, |shift, value| {
+                let shift = shift.to_usize().unwrap_or(256).min(256);
+                from_signed(&(to_signed(&value) >> shift))
+            })?,
+
+            0x20 => {
+                let (offset, size) = (self.pop()?, self.pop()?);
+                let range = self.expand(&offset, &size)?;
+                self.charge(GAS_KECCAK256 + GAS_KECCAK256_WORD * words(range.len()))?;
+                let hash = keccak256(&self.memory[range]);
+                self.push(BigUint::from_bytes_be(&hash))?
+            }
+
+            /* THE CALL AND THE ACCOUNTS */
+            0x30 => self.constant(address_word(&address))?,
+            0x31 => {
+                let account = to_address(&self.pop()?);
+                self.charge(access(evm, account))?;
+                self.push(evm.world.balance(&account))?
+            }
+            0x32 => self.constant(address_word(&evm.origin))?,
+            0x33 => self.constant(address_word(&self.message.caller))?,
+            0x34 => self.constant(self.message.value.clone())?,
+            0x35 => {
+                self.charge(GAS_VERY_LOW)?;
+                let offset = self.pop()?;
+                let word = padded(&self.message.data, &offset, 32);
+                self.push(BigUint::from_bytes_be(&word))?
+            }
+            0x36 => self.constant(BigUint::from(self.message.data.len()))?,
+            0x37 => {
+                let data = std::mem::take(&mut self.message.data);
+                let copied = self.copy(&data);
+                self.message.data = data;
+                copied?
+            }
+            0x38 => self.constant(BigUint::from(self.code.len()))?,
+            0x39 => {
+                let code = std::mem::take(&mut self.code);
+                let copied = self.copy(&code);
+                self.code = code;
+                copied?
+            }
+            0x3a => self.constant(evm.environment.gas_price.clone())?,
+            0x3b => {
+                let account = to_address(&self.pop()?);
+                self.charge(access(evm, account))?;
+                self.push(BigUint::from(evm.world.code(&account).len()))?
+            }
+            0x3c => {
+                let account = to_address(&self.pop()?);
+                self.charge(access(evm, account) - GAS_VERY_LOW)?;
+                self.copy(evm.world.code(&account))?
+            }
+            0x3d => self.constant(BigUint::from(self.return_data.len()))?,
+            0x3e => {
+                let (destination, offset, size) = (self.pop()?, self.pop()?, self.pop()?);
+                if &offset + &size > BigUint::from(self.return_data.len()) {
+                    return Err(Halt::ReturnDataOutOfBounds);
+                }
+                self.stack.extend([size, offset, destination]);
+                let return_data = std::mem::take(&mut self.return_data);
+                let copied = self.copy(&return_data);
+                self.return_data = return_data;
+                copied?
+            }
+            0x3f => {
+                let account = to_address(&self.pop()?);
+                self.charge(access(evm, account))?;
+                self.push(match evm.world.is_empty(&account) {
+                    true => BigUint::zero(),
+                    false => BigUint::from_bytes_be(&keccak256(evm.world.code(&account))),
+                })?
+            }
+
+            /* THE BLOCK */
+            0x40 => {
+                self.charge(GAS_BLOCKHASH)?;
+                let number = self.pop()?;
+                let current = BigUint::from(evm.environment.number);
+                /* THERE ARE NO EARLIER BLOCKS, SO THE LAST 256 HAVE MADE UP HASHES */
+                self.push(
+                    match number < current && &current - &number <= BigUint::from(256u16) {
+                        true => BigUint::from_bytes_be(&keccak256(&to_bytes32(&number))),
+                        false => BigUint::zero(),
+                    },
+                )?
+            }
+            0x41 => self.constant(address_word(&evm.environment.coinbase))?,
+            0x42 => self.constant(BigUint::from(evm.environment.timestamp))?,
+            0x43 => self.constant(BigUint::from(evm.environment.number))?,
+            0x44 => self.constant(evm.environment.prev_randao.clone())?,
+            0x45 => self.constant(BigUint::from(evm.environment.gas_limit))?,
+            0x46 => self.constant(BigUint::from(evm.environment.chain_id))?,
+            0x47 => {
+                self.charge(GAS_LOW)?;
+                self.push(evm.world.balance(&address))?
+            }
+            0x48 => self.constant(evm.environment.base_fee.clone())?,
+            /* TRANSACTIONS CARRY NO BLOBS */
+            0x49 => {
+                self.charge(GAS_VERY_LOW)?;
+                self.pop()?;
+                self.push(BigUint::zero())?
+            }
+            0x4a => self.constant(evm.environment.blob_base_fee.clone())?,
+
+            /* STACK, MEMORY, STORAGE AND FLOW */
+            0x50 => {
+                self.charge(GAS_BASE)?;
+                self.pop()?;
+            }
+            0x51 => {
+                self.charge(GAS_VERY_LOW)?;
+                let offset = self.pop()?;
+                let range = self.expand(&offset, &BigUint::from(32u8))?;
+                let word = BigUint::from_bytes_be(&self.memory[range]);
+                self.push(word)?
+            }
+            0x52 | 0x53 => {
+                self.charge(GAS_VERY_LOW)?;
+                let (offset, value) = (self.pop()?, self.pop()?);
+                let word = to_bytes32(&value);
+                let bytes = match opcode {
+                    0x52 => &word[..],
+                    _ => &word[31..],
+                };
+                let range = self.expand(&offset, &BigUint::from(bytes.len()))?;
+                self.memory[range].copy_from_slice(bytes);
+            }
+            0x54 => {
+                let key = self.pop()?;
+                let cost = match evm.substate.warm_slots.insert((address, key.clone())) {
+                    true => GAS_COLD_SLOAD,
+                    false => GAS_WARM_ACCESS,
+                };
+                self.charge(cost)?;
+                self.push(evm.world.storage(&address, &key))?
+            }
+            0x55 => self.sstore(evm)?,
+            0x56 => {
+                self.charge(GAS_MID)?;
+                let destination = self.pop()?;
+                self.jump(&destination)?
+            }
+            0x57 => {
+                self.charge(GAS_HIGH)?;
+                let (destination, condition) = (self.pop()?, self.pop()?);
+                if !condition.is_zero() {
+                    self.jump(&destination)?
+                }
+            }
+            0x58 => self.constant(BigUint::from(self.pc - 1))?,
+            0x59 => self.constant(BigUint::from(self.memory.len()))?,
+            0x5a => {
+                self.charge(GAS_BASE)?;
+                self.push(BigUint::from(self.gas))?
+            }
+            0x5b => self.charge(GAS_JUMPDEST)?,
+            0x5c => {
+                self.charge(GAS_WARM_ACCESS)?;
+                let key = self.pop()?;
+                let value = evm.substate.transient.get(&(address, key));
+                self.push(value.cloned().unwrap_or_default())?
+            }
+            0x5d => {
+                if self.message.is_static {
+                    return Err(Halt::StaticViolation);
+                }
+                self.charge(GAS_WARM_ACCESS)?;
+                let (key, value) = (self.pop()?, self.pop()?);
+                evm.substate.transient.insert((address, key), value);
+            }
+            0x5e => {
+                let (destination, source, size) = (self.pop()?, self.pop()?, self.pop()?);
+                let target = self.expand(&destination, &size)?;
+                let range = self.expand(&source, &size)?;
+                self.charge(GAS_VERY_LOW + GAS_COPY_WORD * words(range.len()))?;
+                self.memory.copy_within(range, target.start);
+            }
+            0x5f => self.constant(BigUint::zero())?,
+            0x60..=0x7f => {
+                self.charge(GAS_VERY_LOW)?;
+                let size = (opcode - 0x5f) as usize;
+                let value = padded(&self.code, &BigUint::from(self.pc), size);
+                self.pc += size;
+                self.push(BigUint::from_bytes_be(&value))?
+            }
+            0x80..=0x8f => {
+                self.charge(GAS_VERY_LOW)?;
+                let depth = (opcode - 0x7f) as usize;
+                let Some(_index) = self.stack.len().checked_sub(depth) else {
+                    return Err(Halt::StackUnderflow);
+                };
+                self.push(self.stack[_index].clone())?
+            }
+            0x90..=0x9f => {
+                self.charge(GAS_VERY_LOW)?;
+                let depth = (opcode - 0x8f) as usize;
+                let Some(_index) = self.stack.len().checked_sub(depth + 1) else {
+                    return Err(Halt::StackUnderflow);
+                };
+                let top = self.stack.len() - 1;
+                self.stack.swap(_index, top);
+            }
+
+            /* LOGS */
+            0xa0..=0xa4 => {
+                if self.message.is_static {
+                    return Err(Halt::StaticViolation);
+                }
+                let (offset, size) = (self.pop()?, self.pop()?);
+                let mut topics = Vec::new();
+                for _ in 0xa0..opcode {
+                    topics.push(to_bytes32(&self.pop()?));
+                }
+                let range = self.expand(&offset, &size)?;
+                self.charge(
+                    GAS_LOG
+                        + GAS_LOG_TOPIC * topics.len() as u64
+                        + GAS_LOG_BYTE * range.len() as u64,
+                )?;
+                evm.substate.logs.push(Log {
+                    address,
+                    topics,
+                    data: self.memory[range].to_vec(),
+                });
+            }
+
+            /* CALLS AND CONTRACTS */
+            0xf0 | 0xf5 => self.create(opcode, evm)?,
+            0xf1 | 0xf2 | 0xf4 | 0xfa => self.call(opcode, evm)?,
+            0xf3 | 0xfd => {
+                self.charge(GAS_ZERO)?;
+                let (offset, size) = (self.pop()?, self.pop()?);
+                let range = self.expand(&offset, &size)?;
+                let status = match opcode {
+                    0xf3 => Status::Success,
+                    _ => Status::Revert,
+                };
+                return Ok(Some((status, self.memory[range].to_vec())));
+            }
+            0xff => return self.selfdestruct(evm),
+            _ => return Err(Halt::InvalidOpcode(opcode)),
+        }
+        Ok(None)
+    }
+
+    /* EIP-2200 PRICING AND EIP-3529 REFUNDS, AGAINST THE VALUE THE SLOT HAD WHEN THE
+    TRANSACTION STARTED */
+    fn sstore(&mut self, evm: &mut Evm) -> Result<(), Halt> {
+        if self.message.is_static {
+            return Err(Halt::StaticViolation);
+        }
+        if self.gas <= GAS_SSTORE_STIPEND {
+            return Err(Halt::OutOfGas);
+        }
+        let (key, value) = (self.pop()?, self.pop()?);
+        let address = self.message.address;
+        let mut cost = match evm.substate.warm_slots.insert((address, key.clone())) {
+            true => GAS_COLD_SLOAD,
+            false => 0,
+        };
+        let current = evm.world.storage(&address, &key);
+        let original = evm.original.storage(&address, &key);
+        let mut refund = 0;
+        if value == current {
+            cost += GAS_WARM_ACCESS;
+        } else if original == current {
+            match original.is_zero() {
+                true => cost += GAS_SSTORE_SET,
+                false => {
+                    cost += GAS_SSTORE_RESET;
+                    if value.is_zero() {
+                        refund += REFUND_SSTORE_CLEARS as i64;
+                    }
+                }
+            }
+        } else {
+            cost += GAS_WARM_ACCESS;
+            if !original.is_zero() {
+                if current.is_zero() {
+                    refund -= REFUND_SSTORE_CLEARS as i64;
+                } else if value.is_zero() {
+                    refund += REFUND_SSTORE_CLEARS as i64;
+                }
+            }
+            if original == value {
+                refund += match original.is_zero() {
+                    true => GAS_SSTORE_SET - GAS_WARM_ACCESS,
+                    false => GAS_SSTORE_RESET - GAS_WARM_ACCESS,
+                } as i64;
+            }
+        }
+        self.charge(cost)?;
+        evm.substate.refund += refund;
+        evm.world.set_storage(address, key, value);
+        Ok(())
+    }
+
+    /* `CALL`, `CALLCODE`, `DELEGATECALL` AND `STATICCALL`. THE CALLEE GETS AT MOST ALL BUT A
+    64TH OF THE GAS LEFT, AND WHAT IT DOES NOT USE COMES BACK */
+    fn call(&mut self, opcode: u8, evm: &mut Evm) -> Result<(), Halt> {
+        let gas = self.pop()?;
+        let to = to_address(&self.pop()?);
+        let value = match opcode {
+            0xf1 | 0xf2 => self.pop()?,
+            _ => BigUint::zero(),
+        };
+        let (input_offset, input_size) = (self.pop()?, self.pop()?);
+        let (output_offset, output_size) = (self.pop()?, self.pop()?);
+        if opcode == 0xf1 && self.message.is_static && !value.is_zero() {
+            return Err(Halt::StaticViolation);
+        }
+        let input = self.expand(&input_offset, &input_size)?;
+        let output = self.expand(&output_offset, &output_size)?;
+        let mut cost = access(evm, to);
+        if !value.is_zero() {
+            cost += GAS_CALL_VALUE;
+            if opcode == 0xf1 && evm.world.is_empty(&to) {
+                cost += GAS_NEW_ACCOUNT;
+            }
+        }
+        self.charge(cost)?;
+        let available = self.gas - self.gas / 64;
+        let mut forwarded = gas.to_u64().map_or(available, |gas| gas.min(available));
+        self.charge(forwarded)?;
+        if !value.is_zero() {
+            forwarded += GAS_CALL_STIPEND;
+        }
+
+        let address = self.message.address;
+        let message = Message {
+            caller: address,
+            address: to,
+            code_address: to,
+            value,
+            data: self.memory[input].to_vec(),
+            gas: forwarded,
+            is_static: self.message.is_static,
+            depth: self.message.depth + 1,
+            transfer: true,
+        };
+        let message = match opcode {
+            0xf2 => Message { address, ..message },
+            0xf4 => Message {
+                caller: self.message.caller,
+                address,
+                value: self.message.value.clone(),
+                transfer: false,
+                ..message
+            },
+            0xfa => Message {
+                is_static: true,
+                ..message
+            },
+            _ => message,
+        };
+        let outcome = evm.call_frame(message);
+        self.gas += outcome.gas_left;
+        let length = output.len().min(outcome.output.len());
+        self.memory[output.start..output.start + length].copy_from_slice(&outcome.output[..length]);
+        self.return_data = outcome.output;
+        self.push(flag(outcome.status == Status::Success))
+    }
+
+    /* `CREATE` AND `CREATE2`. THE NEW CONTRACT'S ADDRESS, OR ZERO IF IT WAS NOT DEPLOYED */
+    fn create(&mut self, opcode: u8, evm: &mut Evm) -> Result<(), Halt> {
+        if self.message.is_static {
+            return Err(Halt::StaticViolation);
+        }
+        let (value, offset, size) = (self.pop()?, self.pop()?, self.pop()?);
+        let salt = match opcode {
+            0xf5 => Some(to_bytes32(&self.pop()?)),
+            _ => None,
+        };
+        if size > BigUint::from(MAX_INITCODE_SIZE) {
+            return Err(Halt::InitCodeTooLarge);
+        }
+        let range = self.expand(&offset, &size)?;
+        let size = words(range.len());
+        let hashing = match salt {
+            Some(_) => GAS_KECCAK256_WORD * size,
+            None => 0,
+        };
+        self.charge(GAS_CREATE + GAS_INITCODE_WORD * size + hashing)?;
+        let init_code = self.memory[range].to_vec();
+        let gas = self.gas - self.gas / 64;
+        self.charge(gas)?;
+        let outcome = evm.create_frame(
+            self.message.address,
+            value,
+            init_code,
+            salt,
+            gas,
+            self.message.depth + 1,
+        );
+        self.gas += outcome.gas_left;
+        self.return_data = match outcome.status {
+            Status::Revert => outcome.output,
+            _ => Vec::new(),
+        };
+        self.push(
+            outcome
+                .created
+                .map(|address| address_word(&address))
+                .unwrap_or_default(),
+        )
+    }
+
+    /* EIP-6780: THE BALANCE ALWAYS MOVES, THE ACCOUNT IS ONLY DELETED IF IT WAS CREATED IN
+    THIS TRANSACTION */
+    fn selfdestruct(&mut self, evm: &mut Evm) -> Step {
+        if self.message.is_static {
+            return Err(Halt::StaticViolation);
+        }
+        let beneficiary = to_address(&self.pop()?);
+        let address = self.message.address;
+        let balance = evm.world.balance(&address);
+        let mut cost = GAS_SELFDESTRUCT;
+        if evm.substate.warm_accounts.insert(beneficiary) {
+            cost += GAS_COLD_ACCOUNT_ACCESS;
+        }
+        if !balance.is_zero() && evm.world.is_empty(&beneficiary) {
+            cost += GAS_NEW_ACCOUNT;
+        }
+        self.charge(cost)?;
+        evm.world.account_mut(address).balance = BigUint::zero();
+        evm.world.account_mut(beneficiary).balance += balance;
+        if evm.substate.created.contains(&address) {
+            evm.substate.destroyed.insert(address);
+        }
+        Ok(Some((Status::Success, Vec::new())))
+    }
+
+    /* ------------------------------ HELPERS ------------------------------ */
+
+    fn charge(&mut self, gas: u64) -> Result<(), Halt> {
+        self.gas = self.gas.checked_sub(gas).ok_or(Halt::OutOfGas)?;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<BigUint, Halt> {
+        self.stack.pop().ok_or(Halt::StackUnderflow)
+    }
+
+    fn push(&mut self, value: BigUint) -> Result<(), Halt> {
+        if self.stack.len() >= STACK_LIMIT {
+            return Err(Halt::StackOverflow);
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /* AN INSTRUCTION THAT ONLY READS SOMETHING OF THE ENVIRONMENT */
+    fn constant(&mut self, value: BigUint) -> Result<(), Halt> {
+        self.charge(GAS_BASE)?;
+        self.push(value)
+    }
+
+    fn binary(&mut self, gas: u64, operation: fn(BigUint, BigUint) -> BigUint) -> Result<(), Halt> {
+        self.charge(gas)?;
+        let (a, b) = (self.pop()?, self.pop()?);
+        self.push(operation(a, b))
+    }
+
+    fn jump(&mut self, destination: &BigUint) -> Result<(), Halt> {
+        match to_usize(destination) {
+            Some(_destination) if self.jumpdests.get(_destination) == Some(&true) => {
+                self.pc = _destination;
+                Ok(())
+            }
+            _ => Err(Halt::InvalidJump),
+        }
+    }
+
+    /* GROWS MEMORY TO COVER `size` BYTES AT `offset` AND CHARGES FOR IT. AN EMPTY RANGE NEVER
+    GROWS IT, WHATEVER ITS OFFSET */
+    fn expand(&mut self, offset: &BigUint, size: &BigUint) -> Result<Range<usize>, Halt> {
+        if size.is_zero() {
+            return Ok(0..0);
+        }
+        let (Some(_offset), Some(_size)) = (to_usize(offset), to_usize(size)) else {
+            return Err(Halt::OutOfGas);
+        };
+        let end = _offset
+            .checked_add(_size)
+            .filter(|end| *end <= MAX_MEMORY)
+            .ok_or(Halt::OutOfGas)?;
+        let (current, needed) = (words(self.memory.len()), words(end));
+        if needed > current {
+            self.charge(memory_cost(needed) - memory_cost(current))?;
+            self.memory.resize(needed as usize * 32, 0);
+        }
+        Ok(_offset..end)
+    }
+
+    /* `CALLDATACOPY`, `CODECOPY`, `EXTCODECOPY` AND `RETURNDATACOPY`: BYTES OF `source` PAST
+    ITS END ARE ZEROS */
+    fn copy(&mut self, source: &[u8]) -> Result<(), Halt> {
+        let (destination, offset, size) = (self.pop()?, self.pop()?, self.pop()?);
+        let range = self.expand(&destination, &size)?;
+        self.charge(GAS_VERY_LOW + GAS_COPY_WORD * words(range.len()))?;
+        let bytes = padded(source, &offset, range.len());
+        self.memory[range].copy_from_slice(&bytes);
+        Ok(())
+    }
+}
+
+fn memory_cost(words: u64) -> u64 {
+    GAS_MEMORY_WORD * words + words * words / GAS_MEMORY_QUAD_DIVISOR
+}
+
+/* EIP-2929: WARMS THE ACCOUNT AND SAYS WHAT ACCESSING IT COST */
+fn access(evm: &mut Evm, address: Address) -> u64 {
+    match evm.substate.warm_accounts.insert(address) {
+        true => GAS_COLD_ACCOUNT_ACCESS,
+        false => GAS_WARM_ACCESS,
+    }
+}
+
+/* `size` BYTES OF `source` FROM `offset`, ZERO PADDED */
+fn padded(source: &[u8], offset: &BigUint, size: usize) -> Vec<u8> {
+    let mut bytes = vec![0; size];
+    if let Some(_offset) = to_usize(offset).filter(|offset| *offset < source.len()) {
+        let length = size.min(source.len() - _offset);
+        bytes[..length].copy_from_slice(&source[_offset.._offset + length]);
+    }
+    bytes
+}
+
+fn wrap(value: BigUint) -> BigUint {
+    match value.bits() > 256 {
+        true => value % modulus(),
+        false => value,
+    }
+}
+
+fn flag(value: bool) -> BigUint {
+    BigUint::from(value as u8)
+}
@@ -0,0 +1,61 @@
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::mods::{
+    functions::helpers::word_helper::{keccak256, words},
+    types::execution::{Halt, Outcome, Status},
+};
+
+/* RUNS THE PRECOMPILE AT ADDRESS `id`. A PRECOMPILE ONLY FAILS WHEN IT RUNS OUT OF GAS, OR FOR
+THE ONES THAT ARE NOT IMPLEMENTED */
+pub fn run(id: u8, input: &[u8], gas: u64) -> Outcome {
+    let (cost, output) = match id {
+        1 => (3_000, ecrecover(input)),
+        2 => (60 + 12 * words(input.len()), Sha256::digest(input).to_vec()),
+        3 => {
+            let mut output = vec![0; 12];
+            output.extend(Ripemd160::digest(input));
+            (600 + 120 * words(input.len()), output)
+        }
+        4 => (15 + 3 * words(input.len()), input.to_vec()),
+        _ => return Outcome::halt(Halt::Precompile),
+    };
+    match cost > gas {
+        true => Outcome::halt(Halt::OutOfGas),
+        false => Outcome {
+            status: Status::Success,
+            output,
+            gas_left: gas - cost,
+            created: None,
+        },
+    }
+}
+
+/* THE ADDRESS THAT SIGNED A HASH, LEFT PADDED TO A WORD, OR NOTHING FOR AN INVALID SIGNATURE */
+fn ecrecover(input: &[u8]) -> Vec<u8> {
+    let mut padded = input.to_vec();
+    padded.resize(128, 0);
+    let (hash, v, signature) = (&padded[..32], &padded[32..64], &padded[64..128]);
+    let recover = || -> Option<Vec<u8>> {
+        let recovery = match v[..31].iter().all(|byte| *byte == 0) {
+            true => RecoveryId::from_byte(v[31].checked_sub(27)?)?,
+            false => return None,
+        };
+        let signature = Signature::from_slice(signature).ok()?;
+        /* THE EVM ACCEPTS A HIGH `s`, k256 ONLY THE EQUIVALENT LOW ONE FOR THE OTHER PARITY */
+        let (signature, recovery) = match signature.normalize_s() {
+            Some(_normalized) => (
+                _normalized,
+                RecoveryId::new(!recovery.is_y_odd(), recovery.is_x_reduced()),
+            ),
+            None => (signature, recovery),
+        };
+        let key = VerifyingKey::recover_from_prehash(hash, &signature, recovery).ok()?;
+        let point = key.to_encoded_point(false);
+        let mut output = vec![0; 12];
+        output.extend_from_slice(&keccak256(&point.as_bytes()[1..])[12..]);
+        Some(output)
+    };
+    recover().unwrap_or_default()
+}
@@ -0,0 +1,401 @@
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{Num, Zero};
+
+use crate::mods::{
+    constants::constants::{DEFAULT_SENDER, SENDER_BALANCE},
+    functions::{
+        controllers::{
+            abi_codec::{decode, encode, encode_call, types},
+            evm::Evm,
+        },
+        helpers::word_helper::to_hex,
+    },
+    types::{
+        abi::{Abi, AbiType, AbiValue, RevertReason},
+        execution::{Receipt, Status, Transaction},
+        world::Address,
+    },
+};
+
+/* A COMPILED CONTRACT A SCRIPT CAN DEPLOY */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contract {
+    pub name: String,
+    pub creation: Vec<u8>,
+    pub abi: Abi,
+}
+
+/* RUNS A SCRIPT AGAINST A FRESH CHAIN AND RETURNS WHAT HAPPENED, EACH COMMAND FOLLOWED BY ITS
+RESULT AND THE EVENTS IT EMITTED. ONE COMMAND PER LINE:
+
+    deploy Name{value: 1, gas: 100000}: arg1, arg2
+    call [Name.]function{value: 1, gas: 100000}: arg1, arg2
+    static [Name.]function: arg1, arg2
+    balance @Name
+    time 1700000000
+
+`time` SETS THE TIMESTAMP OF THE BLOCKS THAT FOLLOW. THE OPTIONS ARE OPTIONAL, A CALL WITHOUT `Name.` GOES TO THE LAST CONTRACT DEPLOYED AND AN
+OVERLOADED FUNCTION IS CALLED BY ITS SIGNATURE. ARGUMENTS ARE NUMBERS, `true`, `false`,
+"STRINGS", 0x HEX, `[ARRAYS]`, `(TUPLES)` AND `@Name` OR `@sender` FOR ADDRESSES.
+TRANSACTIONS ARE SENT FROM `DEFAULT_SENDER` */
+pub fn run_script(contracts: &[Contract], script: &str) -> String {
+    let mut session = Session {
+        evm: Evm::new(),
+        contracts,
+        deployed: Vec::new(),
+    };
+    session
+        .evm
+        .fund(DEFAULT_SENDER, BigUint::from(SENDER_BALANCE));
+    let mut transcript = String::new();
+    for line in script
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        transcript.push_str(line);
+        transcript.push('\n');
+        let result = session
+            .command(line)
+            .unwrap_or_else(|error| format!("  -> error: {error}\n"));
+        transcript.push_str(&result);
+    }
+    transcript
+}
+
+struct Session<'a> {
+    evm: Evm,
+    contracts: &'a [Contract],
+    /* EVERY CONTRACT DEPLOYED SO FAR, OLDEST FIRST */
+    deployed: Vec<(&'a Contract, Address)>,
+}
+
+/* ------------------------------ COMMANDS ------------------------------ */
+
+impl<'a> Session<'a> {
+    fn command(&mut self, line: &str) -> Result<String, String> {
+        let (verb, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let end = rest.find(['{', ':']).unwrap_or(rest.len());
+        let target = rest[..end].trim();
+        let mut rest = &rest[end..];
+        let mut value = BigUint::zero();
+        let mut gas = self.evm.gas_limit;
+        if let Some(_options) = rest.strip_prefix('{') {
+            let close = _options
+                .find('}')
+                .ok_or_else(|| "unclosed options".to_string())?;
+            for option in split_list(&_options[..close])? {
+                let (key, number) = option
+                    .split_once(':')
+                    .ok_or_else(|| format!("invalid option `{option}`"))?;
+                let number = parse_number(number.trim())?
+                    .to_biguint()
+                    .ok_or_else(|| format!("negative `{}`", key.trim()))?;
+                match key.trim() {
+                    "value" => value = number,
+                    "gas" => gas = u64::try_from(number).map_err(|_| "gas too high")?,
+                    _ => return Err(format!("unknown option `{}`", key.trim())),
+                }
+            }
+            rest = &_options[close + 1..];
+        }
+        let arguments = match rest.trim_start().strip_prefix(':') {
+            Some(_arguments) => split_list(_arguments.trim())?,
+            None if rest.trim().is_empty() => Vec::new(),
+            None => return Err(format!("expected `:` before `{}`", rest.trim())),
+        };
+
+        match verb {
+            "deploy" => self.deploy(target, &arguments, value, gas),
+            "call" => self.call(target, &arguments, value, gas, false),
+            "static" => self.call(target, &arguments, value, gas, true),
+            "balance" => {
+                let address = self.parse_address(target)?;
+                Ok(format!("  -> {}\n", self.evm.world.balance(&address)))
+            }
+            "time" => {
+                self.evm.environment.timestamp = parse_number(target)?
+                    .try_into()
+                    .map_err(|_| format!("invalid timestamp `{target}`"))?;
+                Ok(String::new())
+            }
+            _ => Err(format!("unknown command `{verb}`")),
+        }
+    }
+
+    fn deploy(
+        &mut self,
+        name: &str,
+        arguments: &[&str],
+        value: BigUint,
+        gas: u64,
+    ) -> Result<String, String> {
+        let contract = self
+            .contracts
+            .iter()
+            .find(|contract| contract.name == name)
+            .ok_or_else(|| format!("no deployable contract `{name}`"))?;
+        let values = self.parse_arguments(&types(&contract.abi.constructor), arguments)?;
+        let mut data = contract.creation.clone();
+        data.extend(encode(&types(&contract.abi.constructor), &values)?);
+        let receipt = self.evm.transact(&Transaction {
+            from: DEFAULT_SENDER,
+            to: None,
+            value,
+            data,
+            gas_limit: gas,
+        });
+        let result = match receipt.created {
+            Some(_address) => {
+                self.deployed.push((contract, _address));
+                to_hex(&_address)
+            }
+            None => self.failure(&receipt, &contract.abi),
+        };
+        Ok(format!("  -> {result}\n{}", self.events(&receipt)))
+    }
+
+    fn call(
+        &mut self,
+        target: &str,
+        arguments: &[&str],
+        value: BigUint,
+        gas: u64,
+        is_static: bool,
+    ) -> Result<String, String> {
+        let (contract, address) = match target.split_once('.') {
+            Some((_name, _)) if !_name.contains('(') => self
+                .deployed
+                .iter()
+                .rev()
+                .find(|(contract, _)| contract.name == _name)
+                .ok_or_else(|| format!("no `{_name}` deployed"))?,
+            _ => self
+                .deployed
+                .last()
+                .ok_or_else(|| "nothing deployed".to_string())?,
+        };
+        let (contract, address) = (*contract, *address);
+        let name = match target.split_once('.') {
+            Some((_name, _function)) if !_name.contains('(') => _function,
+            _ => target,
+        };
+        let function = contract.abi.function(name)?;
+        let values = self.parse_arguments(&types(&function.inputs), arguments)?;
+        let transaction = Transaction {
+            from: DEFAULT_SENDER,
+            to: Some(address),
+            value,
+            data: encode_call(function, &values)?,
+            gas_limit: gas,
+        };
+        let receipt = match is_static {
+            true => self.evm.transact_static(&transaction),
+            false => self.evm.transact(&transaction),
+        };
+        let result = match receipt.status {
+            Status::Success => match decode(&types(&function.outputs), &receipt.output) {
+                Ok(_values) => AbiValue::Tuple(_values).to_string(),
+                Err(_error) => format!("{} ({_error})", to_hex(&receipt.output)),
+            },
+            _ => self.failure(&receipt, &contract.abi),
+        };
+        Ok(format!("  -> {result}\n{}", self.events(&receipt)))
+    }
+
+    /* A REVERT IS DECODED WITH THE ERRORS OF THE CONTRACT CALLED, THEN WITH THOSE OF ANY OTHER,
+    SINCE IT MAY HAVE BUBBLED UP FROM A CALL IT MADE */
+    fn failure(&self, receipt: &Receipt, abi: &Abi) -> String {
+        match receipt.status {
+            Status::Halt(_halt) => format!("halt {_halt}"),
+            _ => {
+                let reason = std::iter::once(abi)
+                    .chain(self.contracts.iter().map(|contract| &contract.abi))
+                    .map(|abi| abi.decode_revert(&receipt.output))
+                    .find(|reason| !matches!(reason, RevertReason::Unknown(_)))
+                    .unwrap_or_else(|| RevertReason::Unknown(receipt.output.clone()));
+                format!("revert {reason}")
+            }
+        }
+    }
+
+    /* EACH LOG DECODED WITH THE ABI OF THE CONTRACT THAT EMITTED IT */
+    fn events(&self, receipt: &Receipt) -> String {
+        let mut events = String::new();
+        for log in &receipt.logs {
+            let abi = self
+                .deployed
+                .iter()
+                .find(|(_, address)| *address == log.address)
+                .map(|(contract, _)| &contract.abi);
+            let event = abi
+                .into_iter()
+                .chain(self.contracts.iter().map(|contract| &contract.abi))
+                .find_map(|abi| abi.decode_log(log));
+            match event {
+                Some(_event) => events.push_str(&format!("  emit {_event}\n")),
+                None => events.push_str(&format!("  {log}\n")),
+            }
+        }
+        events
+    }
+
+    /* ------------------------------ ARGUMENTS ------------------------------ */
+
+    fn parse_arguments(
+        &self,
+        types: &[AbiType],
+        arguments: &[&str],
+    ) -> Result<Vec<AbiValue>, String> {
+        if types.len() != arguments.len() {
+            return Err(format!(
+                "expected {} arguments, got {}",
+                types.len(),
+                arguments.len()
+            ));
+        }
+        types
+            .iter()
+            .zip(arguments)
+            .map(|(ty, argument)| self.parse_value(ty, argument))
+            .collect()
+    }
+
+    fn parse_value(&self, ty: &AbiType, text: &str) -> Result<AbiValue, String> {
+        let invalid = || format!("`{text}` is not a valid `{ty}`");
+        match ty {
+            AbiType::Uint(_) => parse_number(text)?
+                .to_biguint()
+                .map(AbiValue::Uint)
+                .ok_or_else(invalid),
+            AbiType::Int(_) => parse_number(text).map(AbiValue::Int),
+            AbiType::Address => self.parse_address(text).map(AbiValue::Address),
+            AbiType::Bool => match text {
+                "true" => Ok(AbiValue::Bool(true)),
+                "false" => Ok(AbiValue::Bool(false)),
+                _ => Err(invalid()),
+            },
+            AbiType::FixedBytes(size) => {
+                let mut bytes = parse_bytes(text)?;
+                if bytes.len() > *size as usize {
+                    return Err(invalid());
+                }
+                bytes.resize(*size as usize, 0);
+                Ok(AbiValue::FixedBytes(bytes))
+            }
+            AbiType::Bytes => parse_bytes(text).map(AbiValue::Bytes),
+            AbiType::String => parse_string(text).map(AbiValue::String),
+            AbiType::Array(base, length) => {
+                let inner = text
+                    .strip_prefix('[')
+                    .and_then(|text| text.strip_suffix(']'))
+                    .ok_or_else(invalid)?;
+                let elements = split_list(inner)?;
+                if length.is_some_and(|length| length != elements.len()) {
+                    return Err(invalid());
+                }
+                elements
+                    .iter()
+                    .map(|element| self.parse_value(base, element))
+                    .collect::<Result<_, _>>()
+                    .map(AbiValue::Array)
+            }
+            AbiType::Tuple(fields) => {
+                let inner = text
+                    .strip_prefix('(')
+                    .and_then(|text| text.strip_suffix(')'))
+                    .ok_or_else(invalid)?;
+                self.parse_arguments(fields, &split_list(inner)?)
+                    .map(AbiValue::Tuple)
+            }
+        }
+    }
+
+    fn parse_address(&self, text: &str) -> Result<Address, String> {
+        match text.strip_prefix('@') {
+            Some("sender") => Ok(DEFAULT_SENDER),
+            Some(_name) => self
+                .deployed
+                .iter()
+                .rev()
+                .find(|(contract, _)| contract.name == _name)
+                .map(|(_, address)| *address)
+                .ok_or_else(|| format!("no `{_name}` deployed")),
+            None => parse_bytes(text)?
+                .try_into()
+                .map_err(|_| format!("`{text}` is not an address")),
+        }
+    }
+}
+
+/* DECIMAL OR 0x HEX, WITH AN OPTIONAL MINUS */
+fn parse_number(text: &str) -> Result<BigInt, String> {
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(_digits) => (Sign::Minus, _digits),
+        None => (Sign::Plus, text),
+    };
+    let magnitude = match digits.strip_prefix("0x") {
+        Some(_hex) => BigUint::from_str_radix(_hex, 16),
+        None => BigUint::from_str_radix(digits, 10),
+    };
+    magnitude
+        .map(|magnitude| BigInt::from_biguint(sign, magnitude))
+        .map_err(|_| format!("`{text}` is not a number"))
+}
+
+/* 0x HEX, OR A STRING FOR ITS UTF-8 BYTES */
+fn parse_bytes(text: &str) -> Result<Vec<u8>, String> {
+    match text.strip_prefix("0x") {
+        Some(_hex) => hex::decode(_hex).map_err(|_| format!("`{text}` is not hex")),
+        None => parse_string(text).map(String::into_bytes),
+    }
+}
+
+fn parse_string(text: &str) -> Result<String, String> {
+    let inner = text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .ok_or_else(|| format!("`{text}` is not a string"))?;
+    let mut value = String::new();
+    let mut chars = inner.chars();
+    while let Some(_char) = chars.next() {
+        match _char {
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(_escaped) => value.push(_escaped),
+                None => return Err(format!("`{text}` ends in an escape")),
+            },
+            _ => value.push(_char),
+        }
+    }
+    Ok(value)
+}
+
+/* SPLITS AT THE COMMAS THAT ARE NOT INSIDE BRACKETS, PARENTHESES OR A STRING */
+fn split_list(text: &str) -> Result<Vec<&str>, String> {
+    let mut items = Vec::new();
+    let (mut depth, mut start, mut quoted, mut escaped) = (0i32, 0, false, false);
+    for (index, _char) in text.char_indices() {
+        match _char {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(text[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 || quoted {
+        return Err(format!("unbalanced `{text}`"));
+    }
+    if !text.trim().is_empty() {
+        items.push(text[start..].trim());
+    }
+    Ok(items)
+}
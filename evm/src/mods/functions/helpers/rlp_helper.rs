@@ -0,0 +1,56 @@
+use crate::mods::{functions::helpers::word_helper::keccak256, types::world::Address};
+
+/* THE RLP ENCODING OF A BYTE STRING */
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    match bytes {
+        [byte] if *byte < 0x80 => vec![*byte],
+        _ => {
+            let mut encoded = rlp_length(bytes.len(), 0x80);
+            encoded.extend_from_slice(bytes);
+            encoded
+        }
+    }
+}
+
+fn rlp_length(length: usize, offset: u8) -> Vec<u8> {
+    if length < 56 {
+        return vec![offset + length as u8];
+    }
+    let bytes: Vec<u8> = length
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|byte| *byte == 0)
+        .collect();
+    let mut encoded = vec![offset + 55 + bytes.len() as u8];
+    encoded.extend(bytes);
+    encoded
+}
+
+/* WHERE `CREATE` PUTS A CONTRACT: THE LAST 20 BYTES OF `keccak256(rlp([sender, nonce]))` */
+pub fn create_address(sender: &Address, nonce: u64) -> Address {
+    let nonce: Vec<u8> = nonce
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|byte| *byte == 0)
+        .collect();
+    let mut payload = rlp_bytes(sender);
+    payload.extend(rlp_bytes(&nonce));
+    let mut encoded = rlp_length(payload.len(), 0xc0);
+    encoded.extend(payload);
+    last_20(&keccak256(&encoded))
+}
+
+/* EIP-1014: `keccak256(0xff ++ sender ++ salt ++ keccak256(init_code))` */
+pub fn create2_address(sender: &Address, salt: &[u8; 32], init_code: &[u8]) -> Address {
+    let mut preimage = vec![0xff];
+    preimage.extend_from_slice(sender);
+    preimage.extend_from_slice(salt);
+    preimage.extend_from_slice(&keccak256(init_code));
+    last_20(&keccak256(&preimage))
+}
+
+fn last_20(hash: &[u8; 32]) -> Address {
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
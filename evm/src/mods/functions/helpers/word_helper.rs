@@ -0,0 +1,73 @@
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, ToPrimitive};
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::mods::types::world::Address;
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/* 2^256 */
+pub fn modulus() -> BigUint {
+    BigUint::one() << 256
+}
+
+/* 2^256 - 1 */
+pub fn max_word() -> BigUint {
+    modulus() - 1u8
+}
+
+/* A WORD AS 32 BIG ENDIAN BYTES, TRUNCATED TO ITS LOW 256 BITS */
+pub fn to_bytes32(value: &BigUint) -> [u8; 32] {
+    let bytes = value.to_bytes_be();
+    let bytes = &bytes[bytes.len().saturating_sub(32)..];
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(bytes);
+    word
+}
+
+/* THE LOW 160 BITS OF A WORD */
+pub fn to_address(value: &BigUint) -> Address {
+    let word = to_bytes32(value);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&word[12..]);
+    address
+}
+
+pub fn address_word(address: &Address) -> BigUint {
+    BigUint::from_bytes_be(address)
+}
+
+/* TWO'S COMPLEMENT */
+pub fn to_signed(value: &BigUint) -> BigInt {
+    match value.bit(255) {
+        true => BigInt::from_biguint(Sign::Plus, value.clone()) - BigInt::from(modulus()),
+        false => BigInt::from_biguint(Sign::Plus, value.clone()),
+    }
+}
+
+pub fn from_signed(value: &BigInt) -> BigUint {
+    let modulus = BigInt::from(modulus());
+    (((value % &modulus) + &modulus) % &modulus)
+        .to_biguint()
+        .unwrap()
+}
+
+/* A WORD AS AN OFFSET OR A SIZE, None WHEN IT DOES NOT FIT IN MEMORY */
+pub fn to_usize(value: &BigUint) -> Option<usize> {
+    value.to_usize()
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/* WORDS NEEDED FOR `size` BYTES */
+pub fn words(size: usize) -> u64 {
+    size.div_ceil(32) as u64
+}
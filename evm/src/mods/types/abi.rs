@@ -0,0 +1,206 @@
+use num_bigint::{BigInt, BigUint};
+
+use crate::mods::{functions::helpers::word_helper::to_hex, types::world::Address};
+
+/* A TYPE OF THE ETHEREUM ABI */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+    Uint(u16),
+    Int(u16),
+    Address,
+    Bool,
+    FixedBytes(u8),
+    Bytes,
+    String,
+    /* `T[]` WITHOUT A LENGTH */
+    Array(Box<AbiType>, Option<usize>),
+    Tuple(Vec<AbiType>),
+}
+
+impl AbiType {
+    /* ENCODED IN THE TAIL, WITH AN OFFSET IN THE HEAD */
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            AbiType::Bytes | AbiType::String | AbiType::Array(_, None) => true,
+            AbiType::Array(base, Some(_)) => base.is_dynamic(),
+            AbiType::Tuple(fields) => fields.iter().any(AbiType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /* BYTES IT TAKES IN THE HEAD OF A TUPLE */
+    pub fn head_size(&self) -> usize {
+        match self {
+            _ if self.is_dynamic() => 32,
+            AbiType::Array(base, Some(length)) => base.head_size() * length,
+            AbiType::Tuple(fields) => fields.iter().map(AbiType::head_size).sum(),
+            _ => 32,
+        }
+    }
+}
+
+impl std::fmt::Display for AbiType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AbiType::Uint(bits) => write!(f, "uint{bits}"),
+            AbiType::Int(bits) => write!(f, "int{bits}"),
+            AbiType::Address => write!(f, "address"),
+            AbiType::Bool => write!(f, "bool"),
+            AbiType::FixedBytes(size) => write!(f, "bytes{size}"),
+            AbiType::Bytes => write!(f, "bytes"),
+            AbiType::String => write!(f, "string"),
+            AbiType::Array(base, Some(length)) => write!(f, "{base}[{length}]"),
+            AbiType::Array(base, None) => write!(f, "{base}[]"),
+            AbiType::Tuple(fields) => {
+                let fields: Vec<String> = fields.iter().map(ToString::to_string).collect();
+                write!(f, "({})", fields.join(","))
+            }
+        }
+    }
+}
+
+/* A DECODED VALUE, OR ONE TO ENCODE */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiValue {
+    Uint(BigUint),
+    Int(BigInt),
+    Address(Address),
+    Bool(bool),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    Array(Vec<AbiValue>),
+    Tuple(Vec<AbiValue>),
+}
+
+impl std::fmt::Display for AbiValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |values: &[AbiValue]| -> String {
+            values
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            AbiValue::Uint(value) => write!(f, "{value}"),
+            AbiValue::Int(value) => write!(f, "{value}"),
+            AbiValue::Address(address) => write!(f, "{}", to_hex(address)),
+            AbiValue::Bool(value) => write!(f, "{value}"),
+            AbiValue::FixedBytes(bytes) | AbiValue::Bytes(bytes) => write!(f, "{}", to_hex(bytes)),
+            AbiValue::String(value) => write!(f, "{value:?}"),
+            AbiValue::Array(values) => write!(f, "[{}]", list(values)),
+            AbiValue::Tuple(values) => write!(f, "({})", list(values)),
+        }
+    }
+}
+
+/* A PARAMETER, RETURN VALUE OR EVENT FIELD. `indexed` IS ONLY EVER SET ON EVENT FIELDS */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiParameter {
+    pub name: String,
+    pub ty: AbiType,
+    pub indexed: bool,
+}
+
+/* A FUNCTION, EVENT OR ERROR: WHAT IS NEEDED TO ENCODE A CALL OF IT OR DECODE WHAT IT
+PRODUCES */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiItem {
+    pub name: String,
+    pub inputs: Vec<AbiParameter>,
+    /* ONLY FUNCTIONS HAVE OUTPUTS */
+    pub outputs: Vec<AbiParameter>,
+    pub anonymous: bool,
+}
+
+impl AbiItem {
+    /* `name(type1,type2)`, HASHED INTO THE SELECTOR OR THE EVENT TOPIC */
+    pub fn signature(&self) -> String {
+        let types: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|parameter| parameter.ty.to_string())
+            .collect();
+        format!("{}({})", self.name, types.join(","))
+    }
+}
+
+/* THE PARTS OF A CONTRACT'S JSON ABI THE INTERPRETER DECODES WITH */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Abi {
+    pub constructor: Vec<AbiParameter>,
+    pub functions: Vec<AbiItem>,
+    pub events: Vec<AbiItem>,
+    pub errors: Vec<AbiItem>,
+}
+
+/* WHY A CALL REVERTED, READ FROM ITS REVERT DATA */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertReason {
+    /* `revert()` OR A FAILED `require` WITHOUT A MESSAGE */
+    Empty,
+    /* `Error(string)` */
+    Error(String),
+    /* `Panic(uint256)` */
+    Panic(BigUint),
+    /* AN ERROR OF THE ABI */
+    Custom { name: String, values: Vec<AbiValue> },
+    /* DATA NOTHING IN THE ABI MATCHES */
+    Unknown(Vec<u8>),
+}
+
+/* WHAT THE CODES OF `Panic(uint256)` MEAN */
+pub fn panic_reason(code: &BigUint) -> &'static str {
+    match u8::try_from(code) {
+        Ok(0x00) => "generic panic",
+        Ok(0x01) => "assertion failed",
+        Ok(0x11) => "arithmetic overflow or underflow",
+        Ok(0x12) => "division or modulo by zero",
+        Ok(0x21) => "invalid enum value",
+        Ok(0x22) => "invalid storage byte array",
+        Ok(0x31) => "pop on an empty array",
+        Ok(0x32) => "array index out of bounds",
+        Ok(0x41) => "out of memory",
+        Ok(0x51) => "call of an uninitialized internal function",
+        _ => "unknown panic",
+    }
+}
+
+impl std::fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevertReason::Empty => write!(f, "no reason"),
+            RevertReason::Error(message) => write!(f, "Error({message:?})"),
+            RevertReason::Panic(code) => {
+                write!(f, "Panic(0x{code:02x}): {}", panic_reason(code))
+            }
+            RevertReason::Custom { name, values } => {
+                write!(f, "{name}{}", AbiValue::Tuple(values.clone()))
+            }
+            RevertReason::Unknown(data) => write!(f, "{}", to_hex(data)),
+        }
+    }
+}
+
+/* A LOG MATCHED WITH AN EVENT OF THE ABI */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedEvent {
+    pub name: String,
+    /* NAME AND VALUE OF EACH FIELD. AN INDEXED FIELD OF A DYNAMIC TYPE ONLY HAS ITS HASH */
+    pub fields: Vec<(String, AbiValue)>,
+}
+
+impl std::fmt::Display for DecodedEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(name, value)| match name.is_empty() {
+                true => value.to_string(),
+                false => format!("{name}: {value}"),
+            })
+            .collect();
+        write!(f, "{}({})", self.name, fields.join(", "))
+    }
+}
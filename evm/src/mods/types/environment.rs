@@ -0,0 +1,35 @@
+use num_bigint::BigUint;
+
+use crate::mods::types::world::Address;
+
+/* THE BLOCK EVERY TRANSACTION RUNS IN, WHAT `block.*` AND `tx.gasprice` READ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Environment {
+    pub chain_id: u64,
+    pub number: u64,
+    pub timestamp: u64,
+    pub coinbase: Address,
+    pub gas_limit: u64,
+    pub base_fee: BigUint,
+    pub prev_randao: BigUint,
+    pub blob_base_fee: BigUint,
+    /* WEI PER UNIT OF GAS, CHARGED TO THE SENDER. ZERO BY DEFAULT SO BALANCES ONLY MOVE WITH
+    VALUE TRANSFERS */
+    pub gas_price: BigUint,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment {
+            chain_id: 1,
+            number: 1,
+            timestamp: 1_700_000_000,
+            coinbase: [0; 20],
+            gas_limit: 30_000_000,
+            base_fee: BigUint::default(),
+            prev_randao: BigUint::default(),
+            blob_base_fee: BigUint::from(1u8),
+            gas_price: BigUint::default(),
+        }
+    }
+}
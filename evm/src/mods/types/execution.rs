@@ -0,0 +1,124 @@
+use num_bigint::BigUint;
+
+use crate::mods::types::world::{Address, Log};
+
+/* WHY EXECUTION STOPPED WITHOUT `RETURN` OR `REVERT`. ALL THE GAS OF THE CALL IS CONSUMED */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Halt {
+    OutOfGas,
+    StackUnderflow,
+    StackOverflow,
+    InvalidJump,
+    InvalidOpcode(u8),
+    /* A STATE CHANGE IN A STATIC CALL */
+    StaticViolation,
+    ReturnDataOutOfBounds,
+    CallDepth,
+    InsufficientBalance,
+    CodeTooLarge,
+    InitCodeTooLarge,
+    /* EIP-3541: DEPLOYED CODE CANNOT START WITH 0xEF */
+    InvalidCodePrefix,
+    /* SOMETHING ALREADY LIVES WHERE A CONTRACT IS CREATED */
+    CreateCollision,
+    /* A PRECOMPILE THAT IS NOT IMPLEMENTED, OR ONE GIVEN INPUT IT REJECTS */
+    Precompile,
+    /* THE SENDER CANNOT PAY FOR THE GAS AND THE VALUE, OR THE GAS LIMIT IS BELOW THE
+    INTRINSIC COST */
+    InvalidTransaction,
+}
+
+impl std::fmt::Display for Halt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Halt::OutOfGas => write!(f, "out of gas"),
+            Halt::StackUnderflow => write!(f, "stack underflow"),
+            Halt::StackOverflow => write!(f, "stack overflow"),
+            Halt::InvalidJump => write!(f, "invalid jump destination"),
+            Halt::InvalidOpcode(opcode) => write!(f, "invalid opcode 0x{opcode:02x}"),
+            Halt::StaticViolation => write!(f, "state change in a static call"),
+            Halt::ReturnDataOutOfBounds => write!(f, "return data out of bounds"),
+            Halt::CallDepth => write!(f, "call depth exceeded"),
+            Halt::InsufficientBalance => write!(f, "insufficient balance"),
+            Halt::CodeTooLarge => write!(f, "code too large"),
+            Halt::InitCodeTooLarge => write!(f, "init code too large"),
+            Halt::InvalidCodePrefix => write!(f, "code starts with 0xef"),
+            Halt::CreateCollision => write!(f, "create collision"),
+            Halt::Precompile => write!(f, "precompile failed"),
+            Halt::InvalidTransaction => write!(f, "invalid transaction"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Revert,
+    Halt(Halt),
+}
+
+/* HOW A CALL FRAME ENDED */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub status: Status,
+    /* THE RETURN DATA, THE REVERT DATA, OR NOTHING AFTER A HALT. EMPTY FOR A SUCCESSFUL CREATE,
+    WHOSE OUTPUT IS THE DEPLOYED CODE */
+    pub output: Vec<u8>,
+    pub gas_left: u64,
+    pub created: Option<Address>,
+}
+
+impl Outcome {
+    pub fn halt(halt: Halt) -> Self {
+        Outcome {
+            status: Status::Halt(halt),
+            output: Vec::new(),
+            gas_left: 0,
+            created: None,
+        }
+    }
+}
+
+/* WHAT A TRANSACTION LEFT BEHIND */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    pub status: Status,
+    pub output: Vec<u8>,
+    /* AFTER THE REFUND */
+    pub gas_used: u64,
+    /* EMPTY UNLESS THE TRANSACTION SUCCEEDED */
+    pub logs: Vec<Log>,
+    pub created: Option<Address>,
+}
+
+impl Receipt {
+    pub fn is_success(&self) -> bool {
+        self.status == Status::Success
+    }
+}
+
+/* A TRANSACTION. WITHOUT `to` IT DEPLOYS `data` AS INIT CODE */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub from: Address,
+    pub to: Option<Address>,
+    pub value: BigUint,
+    pub data: Vec<u8>,
+    pub gas_limit: u64,
+}
+
+/* ONE CALL FRAME: WHO CALLS, WHOSE STORAGE AND BALANCE IT RUNS AGAINST AND WHOSE CODE IT RUNS.
+THE TWO ADDRESSES ONLY DIFFER FOR `DELEGATECALL` AND `CALLCODE` */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub caller: Address,
+    pub address: Address,
+    pub code_address: Address,
+    pub value: BigUint,
+    pub data: Vec<u8>,
+    pub gas: u64,
+    pub is_static: bool,
+    pub depth: usize,
+    /* FALSE FOR `DELEGATECALL`, WHOSE VALUE IS ONLY WHAT `CALLVALUE` READS */
+    pub transfer: bool,
+}
@@ -0,0 +1,106 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::mods::functions::helpers::word_helper::to_hex;
+
+pub type Address = [u8; 20];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Account {
+    pub balance: BigUint,
+    pub nonce: u64,
+    pub code: Vec<u8>,
+    /* SLOTS THAT HOLD ZERO ARE REMOVED */
+    pub storage: BTreeMap<BigUint, BigUint>,
+}
+
+impl Account {
+    /* EIP-161: NO CODE, NO NONCE AND NO BALANCE */
+    pub fn is_empty(&self) -> bool {
+        self.code.is_empty() && self.nonce == 0 && self.balance.is_zero()
+    }
+}
+
+/* AN EVENT: THE CONTRACT THAT EMITTED IT, UP TO FOUR TOPICS AND THE DATA */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Log {
+    pub address: Address,
+    pub topics: Vec<[u8; 32]>,
+    pub data: Vec<u8>,
+}
+
+impl std::fmt::Display for Log {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let topics: Vec<String> = self.topics.iter().map(|topic| to_hex(topic)).collect();
+        write!(
+            f,
+            "log {} topics=[{}] data={}",
+            to_hex(&self.address),
+            topics.join(", "),
+            to_hex(&self.data)
+        )
+    }
+}
+
+/* EVERY ACCOUNT THAT EXISTS, BY ADDRESS */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct World {
+    pub accounts: HashMap<Address, Account>,
+}
+
+impl World {
+    pub fn account(&self, address: &Address) -> Option<&Account> {
+        self.accounts.get(address)
+    }
+
+    pub fn account_mut(&mut self, address: Address) -> &mut Account {
+        self.accounts.entry(address).or_default()
+    }
+
+    pub fn balance(&self, address: &Address) -> BigUint {
+        self.account(address)
+            .map(|account| account.balance.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn code(&self, address: &Address) -> &[u8] {
+        self.account(address)
+            .map(|account| account.code.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn storage(&self, address: &Address, key: &BigUint) -> BigUint {
+        self.account(address)
+            .and_then(|account| account.storage.get(key).cloned())
+            .unwrap_or_default()
+    }
+
+    pub fn set_storage(&mut self, address: Address, key: BigUint, value: BigUint) {
+        let storage = &mut self.account_mut(address).storage;
+        match value.is_zero() {
+            true => storage.remove(&key),
+            false => storage.insert(key, value),
+        };
+    }
+
+    pub fn is_empty(&self, address: &Address) -> bool {
+        self.account(address).is_none_or(Account::is_empty)
+    }
+}
+
+/* WHAT A TRANSACTION CHANGES BESIDES THE ACCOUNTS, UNDONE WITH THEM WHEN A CALL FAILS */
+#[derive(Debug, Clone, Default)]
+pub struct Substate {
+    pub logs: Vec<Log>,
+    pub refund: i64,
+    /* EIP-2929 ACCESS LISTS */
+    pub warm_accounts: HashSet<Address>,
+    pub warm_slots: HashSet<(Address, BigUint)>,
+    /* EIP-1153 */
+    pub transient: HashMap<(Address, BigUint), BigUint>,
+    /* EIP-6780: ONLY A CONTRACT CREATED IN THE SAME TRANSACTION IS DELETED BY SELFDESTRUCT */
+    pub created: HashSet<Address>,
+    pub destroyed: HashSet<Address>,
+}